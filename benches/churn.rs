@@ -0,0 +1,82 @@
+//! Measures the cost of creating and dropping many short-lived contexts, the workload of batch
+//! jobs, with the default allocation path and, with the "allocator-api" feature, with contexts
+//! allocated in a bump arena reset between contexts.
+//!
+//! Run with `cargo bench --bench churn --features allocator-api`.
+use cdumay_context::{Context, Contextualize};
+use serde_value::Value;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const CONTEXTS: usize = 200_000;
+
+/// Counts heap allocations made through the global allocator.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn build(i: usize) -> Context {
+    let mut ctx = Context::new();
+    ctx.insert("job".to_string(), Value::String("nightly-export".to_string()));
+    ctx.insert("item".to_string(), Value::U64(i as u64));
+    ctx.insert("region".to_string(), Value::String("eu-west-1".to_string()));
+    ctx
+}
+
+fn measure(name: &str, mut f: impl FnMut(usize)) -> Duration {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..CONTEXTS {
+        f(i);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<24} {:>10.2?} {:>8.1} allocations/context",
+        name,
+        elapsed / CONTEXTS as u32,
+        allocations as f64 / CONTEXTS as f64
+    );
+    elapsed
+}
+
+fn main() {
+    measure("build_and_drop", |i| drop(build(i)));
+
+    let template = build(0);
+    measure("clone_and_drop", |i| {
+        let mut ctx = template.clone();
+        ctx.insert("item".to_string(), Value::U64(i as u64));
+        drop(ctx)
+    });
+
+    measure("build_and_dump", |i| drop(build(i).inner()));
+
+    #[cfg(feature = "allocator-api")]
+    {
+        let mut bump = bumpalo::Bump::new();
+        measure("arena_build_and_reset", |i| {
+            let mut ctx = cdumay_context::ArenaContext::new_in(&bump);
+            ctx.insert_str("job", "nightly-export");
+            ctx.insert("item", &Value::U64(i as u64));
+            ctx.insert_str("region", "eu-west-1");
+            drop(ctx);
+            bump.reset();
+        });
+    }
+}
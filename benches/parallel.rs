@@ -0,0 +1,54 @@
+//! Compares sequential and rayon-parallel bulk operations on a large context.
+//!
+//! Run with `cargo bench --features rayon,json --bench parallel`.
+use cdumay_context::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+const KEYS: usize = 20_000;
+const ROUNDS: u32 = 5;
+
+fn payload(i: usize) -> Value {
+    let mut map = BTreeMap::new();
+    for j in 0..20 {
+        map.insert(Value::String(format!("field_{}", j)), Value::String(format!("value {} {}", i, j)));
+    }
+    Value::Map(map)
+}
+
+fn data() -> Vec<(String, Value)> {
+    (0..KEYS).map(|i| (format!("key_{:06}", i), payload(i))).collect()
+}
+
+fn measure(name: &str, mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let elapsed = start.elapsed() / ROUNDS;
+    println!("{:<24} {:>10.2?}", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let sequential = measure("extend", || {
+        let mut ctx = Context::new();
+        ctx.extend(data().into_iter().collect());
+    });
+    let parallel = measure("par_extend", || {
+        let mut ctx = Context::new();
+        ctx.par_extend(data());
+    });
+    println!("speedup: {:.2}x\n", sequential.as_secs_f64() / parallel.as_secs_f64());
+
+    let mut ctx = Context::new();
+    ctx.extend(data().into_iter().collect());
+    let sequential = measure("to_json", || {
+        ctx.to_json(false).unwrap();
+    });
+    let parallel = measure("to_json_par", || {
+        ctx.to_json_par().unwrap();
+    });
+    println!("speedup: {:.2}x", sequential.as_secs_f64() / parallel.as_secs_f64());
+}
@@ -0,0 +1,100 @@
+//! Derive macro for the `ContextDump` trait of [cdumay_context](https://docs.rs/cdumay_context).
+//!
+//! This crate is re-exported by `cdumay_context` behind its "derive" feature and should not be
+//! used directly. See `cdumay_context::ContextDump` for the supported attributes.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derives `ContextDump` for a struct with named fields whose fields implement `Serialize`.
+///
+/// Field attributes:
+///
+/// * `#[context(rename = "name")]`: dumps the field under `name`;
+/// * `#[context(skip)]`: leaves the field out of the dump;
+/// * `#[context(flatten)]`: dumps the entries of the field, which must serialize as a map,
+///   instead of the field itself.
+#[proc_macro_derive(ContextDump, attributes(context))]
+pub fn derive_context_dump(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Options of a field, read from its `#[context(...)]` attributes.
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<String>,
+    skip: bool,
+    flatten: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "ContextDump can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "ContextDump can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut statements = Vec::new();
+    for field in fields {
+        let options = field_options(field)?;
+        if options.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        if options.flatten {
+            if options.rename.is_some() {
+                return Err(Error::new(field.span(), "`rename` and `flatten` cannot be combined"));
+            }
+            statements.push(quote! { ::cdumay_context::__private::dump_flatten(&mut dump, &self.#ident); });
+        } else {
+            let name = options.rename.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+            statements.push(quote! { ::cdumay_context::__private::dump_field(&mut dump, #name, &self.#ident); });
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::cdumay_context::ContextDump for #ident #ty_generics #where_clause {
+            fn dump(&self) -> ::std::collections::BTreeMap<::std::string::String, ::cdumay_context::__private::Value> {
+                let mut dump = ::std::collections::BTreeMap::new();
+                #(#statements)*
+                dump
+            }
+        }
+    })
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("context")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+            } else {
+                return Err(meta.error("expected `rename = \"...\"`, `skip` or `flatten`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
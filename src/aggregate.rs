@@ -0,0 +1,486 @@
+//! Aggregation across a set of contexts.
+//!
+//! A [`ContextAggregator`] summarizes a batch of contexts, such as the dumps of an incident: how
+//! many contexts hold each key, the distinct values of each key, and the minimum, maximum and
+//! average of numeric values. [`ContextAggregator::to_context`] outputs the summary as a context,
+//! so it can be rendered and exported like any other:
+//!
+//! ```json
+//! {
+//!   "contexts": 3,
+//!   "keys": {
+//!     "latency_ms": { "count": 3, "distinct": 3, "values": [120, 250, 4000], "min": 120.0, "max": 4000.0, "avg": 1456.66 },
+//!     "region": { "count": 2, "distinct": 1, "values": ["eu"] }
+//!   }
+//! }
+//! ```
+//!
+//! At most [`AGGREGATE_VALUES_LIMIT`] distinct values are tracked per key; beyond, `distinct`
+//! stops counting and `truncated` is set.
+//!
+//! Summaries shared outside the team can be made differentially private with
+//! [`ContextAggregator::with_noise`]: Laplace noise calibrated by a [`LaplaceNoise`] is then added
+//! to every count and numeric statistic, and the distinct values are left out of the summary.
+//! Counts have a sensitivity of 1, the sensitivity of `min`, `max` and `avg` is the range of the
+//! values a single context may contribute, set with [`LaplaceNoise::with_sensitivity`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextAggregator, Contextualize, LaplaceNoise};
+//! use serde_value::Value;
+//!
+//! let mut aggregator = ContextAggregator::new();
+//! for latency in [120u64, 250, 4000] {
+//!     let mut ctx = Context::new();
+//!     ctx.insert("latency_ms".to_string(), Value::U64(latency));
+//!     aggregator.add(&ctx);
+//! }
+//!
+//! let summary = aggregator.to_context();
+//! assert_eq!(summary.at("contexts").as_u64(), Some(3));
+//! assert_eq!(summary.at("keys").at("latency_ms").at("max").as_f64(), Some(4000.0));
+//!
+//! let noisy = aggregator.with_noise(LaplaceNoise::new(0.5).with_sensitivity(5000.0)).to_context();
+//! assert!(noisy.at("keys").at("latency_ms").at("max").as_f64().is_some());
+//! assert!(noisy.at("keys").at("latency_ms").at("values").is_missing());
+//! ```
+//!
+//! A [`ContextProfile`] is built the same way from historical dumps, counting the occurrences of
+//! each value, and [`Context::anomalies`] compares a context with it to point responders at what
+//! is unusual in a failure context:
+//!
+//! * [`AnomalyKind::UnseenKey`]: a key held by none of the profiled contexts;
+//! * [`AnomalyKind::MissingKey`]: a key held by nearly all the profiled contexts, but not this one;
+//! * [`AnomalyKind::UnseenValue`]: a value never seen for a key with few distinct values;
+//! * [`AnomalyKind::RareValue`]: a value seen in few of the profiled contexts holding the key;
+//! * [`AnomalyKind::OutOfRange`]: a number outside the range of the profiled numbers.
+//!
+//! "Few" and "nearly all" are set by [`ContextProfile::with_rare_ratio`], 5% by default. Keys with
+//! mostly distinct values, such as request ids, or more than [`AGGREGATE_VALUES_LIMIT`] distinct
+//! values, are only checked for their presence and range.
+//!
+//! ```rust
+//! use cdumay_context::{AnomalyKind, Context, ContextProfile, Contextualize};
+//! use serde_value::Value;
+//!
+//! let region = |region: &str| {
+//!     let mut ctx = Context::new();
+//!     ctx.insert("region".to_string(), Value::String(region.to_string()));
+//!     ctx
+//! };
+//! let history: Vec<Context> = (0..50).map(|i| region(if i % 2 == 0 { "eu" } else { "us" })).collect();
+//! let profile: ContextProfile = history.iter().collect();
+//!
+//! let anomalies = region("ap").anomalies(&profile);
+//! assert_eq!(anomalies.len(), 1);
+//! assert_eq!(anomalies[0].kind, AnomalyKind::UnseenValue);
+//! assert_eq!(anomalies[0].key, "region");
+//! assert!(region("eu").anomalies(&profile).is_empty());
+//! ```
+use crate::{Context, Contextualize, ValueExt};
+use serde_value::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Maximum number of distinct values tracked per key.
+pub const AGGREGATE_VALUES_LIMIT: usize = 64;
+
+/// Summarizes a batch of contexts, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct ContextAggregator {
+    contexts: u64,
+    keys: BTreeMap<String, KeyStats>,
+    noise: Option<LaplaceNoise>,
+}
+
+/// Laplace noise added to the summaries of a [`ContextAggregator`], see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaplaceNoise {
+    epsilon: f64,
+    sensitivity: f64,
+}
+
+impl LaplaceNoise {
+    /// Creates a noise with the privacy budget `epsilon` and a sensitivity of 1: the lower
+    /// `epsilon`, the noisier the summaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` is not a positive finite number.
+    pub fn new(epsilon: f64) -> Self {
+        assert!(epsilon.is_finite() && epsilon > 0.0, "epsilon must be a positive finite number");
+        Self { epsilon, sensitivity: 1.0 }
+    }
+
+    /// Sets the sensitivity of the numeric statistics, the range of the values a single context
+    /// may contribute.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sensitivity` is not a positive finite number.
+    pub fn with_sensitivity(mut self, sensitivity: f64) -> Self {
+        assert!(
+            sensitivity.is_finite() && sensitivity > 0.0,
+            "sensitivity must be a positive finite number"
+        );
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    /// Returns the privacy budget.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Returns the sensitivity of the numeric statistics.
+    pub fn sensitivity(&self) -> f64 {
+        self.sensitivity
+    }
+
+    /// Returns a sample of the Laplace distribution centered on 0 with the scale
+    /// `sensitivity / epsilon`.
+    pub fn sample(&self, sensitivity: f64) -> f64 {
+        // Uniform in (-0.5, 0.5), from the 53 high random bits.
+        let uniform = ((crate::id::random_u128() >> 75) as f64 + 0.5) / (1u64 << 53) as f64 - 0.5;
+        -(sensitivity / self.epsilon) * uniform.signum() * (1.0 - 2.0 * uniform.abs()).ln()
+    }
+
+    fn count(&self, count: u64) -> Value {
+        Value::U64((count as f64 + self.sample(1.0)).round().max(0.0) as u64)
+    }
+
+    fn statistic(&self, value: f64) -> Value {
+        Value::F64(value + self.sample(self.sensitivity))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct KeyStats {
+    count: u64,
+    values: BTreeSet<Value>,
+    truncated: bool,
+    numeric: Option<NumericStats>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NumericStats {
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u64,
+}
+
+impl ContextAggregator {
+    /// Creates an empty aggregator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds Laplace noise to the summaries returned by [`ContextAggregator::to_context`].
+    pub fn with_noise(mut self, noise: LaplaceNoise) -> Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    /// Returns the noise added to the summaries, if any.
+    pub fn noise(&self) -> Option<LaplaceNoise> {
+        self.noise
+    }
+
+    /// Adds the dump of `ctx` to the summary.
+    pub fn add(&mut self, ctx: &Context) {
+        self.contexts += 1;
+        for (k, v) in ctx.inner() {
+            let stats = self.keys.entry(k).or_default();
+            stats.count += 1;
+            if let Some(number) = v.as_f64().filter(|number| number.is_finite()) {
+                stats.numeric = Some(match stats.numeric {
+                    Some(numeric) => NumericStats {
+                        min: numeric.min.min(number),
+                        max: numeric.max.max(number),
+                        sum: numeric.sum + number,
+                        count: numeric.count + 1,
+                    },
+                    None => NumericStats {
+                        min: number,
+                        max: number,
+                        sum: number,
+                        count: 1,
+                    },
+                });
+            }
+            if stats.values.len() < AGGREGATE_VALUES_LIMIT || stats.values.contains(&v) {
+                stats.values.insert(v);
+            } else {
+                stats.truncated = true;
+            }
+        }
+    }
+
+    /// Returns the number of contexts added.
+    pub fn len(&self) -> u64 {
+        self.contexts
+    }
+
+    /// Returns `true` if no context was added.
+    pub fn is_empty(&self) -> bool {
+        self.contexts == 0
+    }
+
+    /// Returns the number of contexts holding each key.
+    pub fn frequencies(&self) -> BTreeMap<&str, u64> {
+        self.keys.iter().map(|(k, stats)| (k.as_str(), stats.count)).collect()
+    }
+
+    /// Returns the summary as a context.
+    ///
+    /// With a [noise](ContextAggregator::with_noise), counts and numeric statistics are noisy and
+    /// the distinct values are left out.
+    pub fn to_context(&self) -> Context {
+        if let Some(noise) = self.noise {
+            return self.to_noisy_context(&noise);
+        }
+        let keys = self
+            .keys
+            .iter()
+            .map(|(k, stats)| {
+                let mut summary = BTreeMap::from([
+                    ("count", Value::U64(stats.count)),
+                    ("distinct", Value::U64(stats.values.len() as u64)),
+                    ("values", Value::Seq(stats.values.iter().cloned().collect())),
+                ]);
+                if stats.truncated {
+                    summary.insert("truncated", Value::Bool(true));
+                }
+                if let Some(numeric) = stats.numeric {
+                    summary.insert("min", Value::F64(numeric.min));
+                    summary.insert("max", Value::F64(numeric.max));
+                    summary.insert("avg", Value::F64(numeric.sum / numeric.count as f64));
+                }
+                let summary = summary.into_iter().map(|(k, v)| (Value::String(k.to_string()), v)).collect();
+                (Value::String(k.clone()), Value::Map(summary))
+            })
+            .collect();
+        let mut ctx = Context::new();
+        ctx.insert("contexts".to_string(), Value::U64(self.contexts));
+        ctx.insert("keys".to_string(), Value::Map(keys));
+        ctx
+    }
+
+    fn to_noisy_context(&self, noise: &LaplaceNoise) -> Context {
+        let keys = self
+            .keys
+            .iter()
+            .map(|(k, stats)| {
+                let mut summary = BTreeMap::from([("count", noise.count(stats.count)), ("distinct", noise.count(stats.values.len() as u64))]);
+                if let Some(numeric) = stats.numeric {
+                    summary.insert("min", noise.statistic(numeric.min));
+                    summary.insert("max", noise.statistic(numeric.max));
+                    summary.insert("avg", noise.statistic(numeric.sum / numeric.count as f64));
+                }
+                let summary = summary.into_iter().map(|(k, v)| (Value::String(k.to_string()), v)).collect();
+                (Value::String(k.clone()), Value::Map(summary))
+            })
+            .collect();
+        let mut ctx = Context::new();
+        ctx.insert("contexts".to_string(), noise.count(self.contexts));
+        ctx.insert("keys".to_string(), Value::Map(keys));
+        ctx
+    }
+}
+
+impl<'a> Extend<&'a Context> for ContextAggregator {
+    fn extend<I: IntoIterator<Item = &'a Context>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|ctx| self.add(ctx));
+    }
+}
+
+impl<'a> FromIterator<&'a Context> for ContextAggregator {
+    fn from_iter<I: IntoIterator<Item = &'a Context>>(iter: I) -> Self {
+        let mut aggregator = Self::new();
+        aggregator.extend(iter);
+        aggregator
+    }
+}
+
+/// Profile of historical contexts, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ContextProfile {
+    contexts: u64,
+    keys: BTreeMap<String, KeyProfile>,
+    rare_ratio: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct KeyProfile {
+    count: u64,
+    values: BTreeMap<Value, u64>,
+    truncated: bool,
+    numeric: Option<NumericStats>,
+}
+
+impl Default for ContextProfile {
+    fn default() -> Self {
+        Self {
+            contexts: 0,
+            keys: BTreeMap::new(),
+            rare_ratio: 0.05,
+        }
+    }
+}
+
+impl ContextProfile {
+    /// Creates an empty profile, with a rare ratio of 5%.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the ratio of contexts below which a value is rare, and above which a missing key is
+    /// reported.
+    pub fn with_rare_ratio(mut self, ratio: f64) -> Self {
+        self.rare_ratio = ratio;
+        self
+    }
+
+    /// Adds the dump of `ctx` to the profile.
+    pub fn add(&mut self, ctx: &Context) {
+        self.contexts += 1;
+        for (k, v) in ctx.inner() {
+            let profile = self.keys.entry(k).or_default();
+            profile.count += 1;
+            if let Some(number) = v.as_f64().filter(|number| number.is_finite()) {
+                profile.numeric = Some(match profile.numeric {
+                    Some(numeric) => NumericStats {
+                        min: numeric.min.min(number),
+                        max: numeric.max.max(number),
+                        sum: numeric.sum + number,
+                        count: numeric.count + 1,
+                    },
+                    None => NumericStats {
+                        min: number,
+                        max: number,
+                        sum: number,
+                        count: 1,
+                    },
+                });
+            }
+            if profile.values.len() < AGGREGATE_VALUES_LIMIT || profile.values.contains_key(&v) {
+                *profile.values.entry(v).or_default() += 1;
+            } else {
+                profile.truncated = true;
+            }
+        }
+    }
+
+    /// Returns the number of contexts added.
+    pub fn len(&self) -> u64 {
+        self.contexts
+    }
+
+    /// Returns `true` if no context was added.
+    pub fn is_empty(&self) -> bool {
+        self.contexts == 0
+    }
+}
+
+impl<'a> Extend<&'a Context> for ContextProfile {
+    fn extend<I: IntoIterator<Item = &'a Context>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|ctx| self.add(ctx));
+    }
+}
+
+impl<'a> FromIterator<&'a Context> for ContextProfile {
+    fn from_iter<I: IntoIterator<Item = &'a Context>>(iter: I) -> Self {
+        let mut profile = Self::new();
+        profile.extend(iter);
+        profile
+    }
+}
+
+/// Kind of anomaly reported by [`Context::anomalies`], see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnomalyKind {
+    /// Key held by none of the profiled contexts.
+    UnseenKey,
+    /// Key held by nearly all the profiled contexts.
+    MissingKey,
+    /// Value never seen for the key.
+    UnseenValue,
+    /// Value seen in few of the profiled contexts.
+    RareValue,
+    /// Number outside the profiled range.
+    OutOfRange,
+}
+
+/// An unusual entry found by [`Context::anomalies`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    /// Kind of anomaly.
+    pub kind: AnomalyKind,
+    /// Key of the entry.
+    pub key: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+impl Context {
+    /// Returns the entries of the dump which are unusual compared to `baseline`, ordered by key.
+    ///
+    /// An empty baseline reports nothing.
+    pub fn anomalies(&self, baseline: &ContextProfile) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        if baseline.is_empty() {
+            return anomalies;
+        }
+        let data = self.inner();
+        let anomaly = |kind, key: &str, message: String| Anomaly {
+            kind,
+            key: key.to_string(),
+            message,
+        };
+        for (k, profile) in &baseline.keys {
+            let ratio = profile.count as f64 / baseline.contexts as f64;
+            if !data.contains_key(k) && ratio >= 1.0 - baseline.rare_ratio {
+                let message = format!("missing, while held by {} of {} contexts", profile.count, baseline.contexts);
+                anomalies.push(anomaly(AnomalyKind::MissingKey, k, message));
+            }
+        }
+        for (k, v) in &data {
+            let Some(profile) = baseline.keys.get(k) else {
+                anomalies.push(anomaly(
+                    AnomalyKind::UnseenKey,
+                    k,
+                    format!("held by none of {} contexts", baseline.contexts),
+                ));
+                continue;
+            };
+            let categorical = !profile.truncated && profile.values.len() as u64 * 2 <= profile.count;
+            match profile.values.get(v) {
+                None if categorical => {
+                    let message = format!("value {:?} never seen among {} distinct values", v, profile.values.len());
+                    anomalies.push(anomaly(AnomalyKind::UnseenValue, k, message));
+                }
+                Some(&seen) if categorical && (seen as f64) < profile.count as f64 * baseline.rare_ratio => {
+                    let message = format!("value {:?} seen in {} of {} contexts", v, seen, profile.count);
+                    anomalies.push(anomaly(AnomalyKind::RareValue, k, message));
+                }
+                _ => {
+                    if let (Some(number), Some(numeric)) = (v.as_f64(), profile.numeric) {
+                        if number < numeric.min || number > numeric.max {
+                            let message = format!("{} outside the range [{}, {}]", number, numeric.min, numeric.max);
+                            anomalies.push(anomaly(AnomalyKind::OutOfRange, k, message));
+                        }
+                    }
+                }
+            }
+        }
+        anomalies.sort_by(|a, b| a.key.cmp(&b.key));
+        anomalies
+    }
+}
@@ -0,0 +1,126 @@
+//! Versioned key aliases.
+//!
+//! Renaming a key used by many services cannot happen everywhere at once. [`Context::alias_key`]
+//! declares an old name as an alias of the new, canonical one: values inserted, looked up,
+//! removed, protected or tagged under the alias are stored under the canonical key, and dumps
+//! only hold the canonical key. While consumers still read the old name,
+//! [`Context::set_emit_aliases`] makes dumps also hold the value under each alias.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.alias_key("userId", "user_id");
+//! ctx.insert("userId".to_string(), Value::String("jane".to_string()));
+//! assert_eq!(ctx.get("user_id"), Some(&Value::String("jane".to_string())));
+//! assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["user_id"]);
+//!
+//! ctx.set_emit_aliases(true);
+//! assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["userId", "user_id"]);
+//! ```
+use crate::Context;
+use serde_value::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Aliases registered on a context.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Aliases {
+    /// Canonical key of each alias, both normalized.
+    keys: BTreeMap<String, String>,
+    /// Whether dumps also hold the value under each alias.
+    emit: bool,
+}
+
+impl Aliases {
+    /// Returns `true` if dumps hold values under aliases.
+    pub(crate) fn emits(&self) -> bool {
+        self.emit && !self.keys.is_empty()
+    }
+}
+
+impl Context {
+    /// Declares `alias` as an old name of `canonical`.
+    ///
+    /// A value stored under `alias` before the call is moved to `canonical`, along with its time
+    /// to live and section expiry, unless `canonical` already has one. Aliasing an alias resolves
+    /// to its canonical key, and aliasing a key to itself does nothing.
+    pub fn alias_key(&mut self, alias: &str, canonical: &str) {
+        let canonical = self.resolve_key(canonical).into_owned();
+        let alias = self.key_policy().normalize(alias).into_owned();
+        if alias == canonical {
+            return;
+        }
+        for target in self.aliases.keys.values_mut().filter(|target| **target == alias) {
+            *target = canonical.clone();
+        }
+        if !self.is_locked(&alias) {
+            if let Some(value) = self.data.remove(&alias) {
+                self.touch(&alias);
+                self.created.remove(&alias);
+                let ttl = self.ttls.remove(&alias);
+                let expiry = self.expiries.remove(&alias);
+                if !self.contains(&canonical) {
+                    self.insert_unchecked(canonical.clone(), value);
+                    if self.data.contains_key(&canonical) {
+                        if let Some(ttl) = ttl {
+                            self.ttls.insert(canonical.clone(), ttl);
+                        }
+                        if let Some(expiry) = expiry {
+                            self.expiries.insert(canonical.clone(), expiry);
+                        }
+                    }
+                }
+            }
+        }
+        self.aliases.keys.insert(alias, canonical);
+    }
+
+    /// Returns the canonical key of `alias`, if it is one.
+    pub fn canonical_key(&self, alias: &str) -> Option<&str> {
+        self.aliases.keys.get(self.key_policy().normalize(alias).as_ref()).map(String::as_str)
+    }
+
+    /// Returns the aliases of `canonical`, in order.
+    pub fn key_aliases(&self, canonical: &str) -> Vec<&str> {
+        let canonical = self.resolve_key(canonical);
+        self.aliases
+            .keys
+            .iter()
+            .filter(|(_, target)| **target == canonical)
+            .map(|(alias, _)| alias.as_str())
+            .collect()
+    }
+
+    /// Sets whether dumps also hold the value of each aliased key under its aliases, for
+    /// consumers still reading the old names. Disabled by default.
+    pub fn set_emit_aliases(&mut self, emit: bool) {
+        self.aliases.emit = emit;
+    }
+
+    /// Returns the key under which `k` is stored: normalized by the key policy, then resolved if
+    /// it is an alias.
+    pub(crate) fn resolve_key<'a>(&self, k: &'a str) -> Cow<'a, str> {
+        let k = self.key_policy().normalize(k);
+        match self.aliases.keys.get(k.as_ref()) {
+            Some(canonical) => Cow::Owned(canonical.clone()),
+            None => k,
+        }
+    }
+
+    /// Copies the values of aliased keys under their aliases, if enabled and not already present.
+    pub(crate) fn add_aliases(&self, data: &mut BTreeMap<String, Value>) {
+        if !self.aliases.emit {
+            return;
+        }
+        for (alias, canonical) in &self.aliases.keys {
+            if data.contains_key(alias) {
+                continue;
+            }
+            if let Some(value) = data.get(canonical).cloned() {
+                data.insert(alias.clone(), value);
+            }
+        }
+    }
+}
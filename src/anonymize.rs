@@ -0,0 +1,126 @@
+//! Deterministic pseudonymization.
+//!
+//! Dumps shared outside the team must not hold raw identifiers, yet analytics over them need to
+//! know that two errors concern the same user. [`Context::anonymize`] replaces the values stored
+//! under the given keys with pseudonyms: `anon_` followed by the first 32 hexadecimal digits of
+//! the HMAC-SHA256 of the value keyed by a secret salt. The same value always maps to the same
+//! pseudonym under the same salt, whatever the key holding it, and cannot be recovered without
+//! the salt.
+//!
+//! Keys are dotted paths, see [`Context::get_path`]. Strings are hashed as is and other scalars
+//! as their decimal or `true`/`false` rendering, so that `42u8` and `42u64` map to the same
+//! pseudonym. The scalars nested in maps and sequences are pseudonymized one by one, keeping the
+//! structure; null values are left as is. [`pseudonymize`] computes the pseudonym of a single
+//! value, to correlate dumps with other data sources.
+//!
+//! This module is only available when the "hash" feature is enabled.
+//!
+//! ```rust
+//! use cdumay_context::{pseudonymize, Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! ctx.insert("action".to_string(), Value::String("login".to_string()));
+//! ctx.anonymize(&["user", "client.ip"], b"s3cr3t").unwrap();
+//!
+//! let token = ctx.get_str("user").unwrap();
+//! assert!(token.starts_with("anon_"));
+//! assert_eq!(token.len(), 37);
+//! assert_eq!(token, pseudonymize(&Value::String("jane".to_string()), b"s3cr3t"));
+//! assert_eq!(ctx.get_str("action").unwrap(), "login");
+//! ```
+use crate::{Context, SharedContext};
+use serde_value::Value;
+use std::fmt::Write;
+
+/// Prefix of the pseudonyms.
+const PSEUDONYM_PREFIX: &str = "anon_";
+
+/// Number of leading bytes of the HMAC kept in pseudonyms.
+const PSEUDONYM_LEN: usize = 16;
+
+impl Context {
+    /// Replaces the values stored at the dotted `keys` with their pseudonyms under `salt`, see the
+    /// [module documentation](self). Missing keys are ignored.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if every value was replaced
+    /// * `Err(e)` containing the error of [`Context::insert_path`], such as a
+    ///   [`ProtectedKey`](crate::ProtectedKey) error, in which case the context is left unchanged
+    pub fn anonymize(&mut self, keys: &[&str], salt: &[u8]) -> cdumay_core::Result<()> {
+        let mut anonymized = self.clone();
+        for path in keys {
+            if let Some(value) = anonymized.get_path(path) {
+                let value = pseudonymize_value(value, salt);
+                anonymized.insert_path(path, value)?;
+            }
+        }
+        *self = anonymized;
+        Ok(())
+    }
+}
+
+impl SharedContext {
+    /// Atomically replaces the values stored at `keys` with their pseudonyms, see
+    /// [`Context::anonymize`].
+    pub fn anonymize(&self, keys: &[&str], salt: &[u8]) -> cdumay_core::Result<()> {
+        self.update(|ctx| ctx.anonymize(keys, salt))
+    }
+}
+
+/// Returns the pseudonym of the scalar `value` under `salt`, see the
+/// [module documentation](self).
+///
+/// Maps and sequences, which [`Context::anonymize`] walks through, are hashed as their `Debug`
+/// rendering.
+pub fn pseudonymize(value: &Value, salt: &[u8]) -> String {
+    let message = match value {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return pseudonymize(inner, salt),
+        Value::Bytes(bytes) => return hex_pseudonym(&hmac_sha256(salt, bytes)),
+        Value::Unit | Value::Option(None) | Value::Seq(_) | Value::Map(_) => format!("{:?}", value),
+    };
+    hex_pseudonym(&hmac_sha256(salt, message.as_bytes()))
+}
+
+/// Returns `value` with its scalars replaced by their pseudonyms, keeping maps, sequences and
+/// null values.
+fn pseudonymize_value(value: &Value, salt: &[u8]) -> Value {
+    match value {
+        Value::Unit | Value::Option(None) => value.clone(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => pseudonymize_value(inner, salt),
+        Value::Seq(items) => Value::Seq(items.iter().map(|item| pseudonymize_value(item, salt)).collect()),
+        Value::Map(map) => Value::Map(map.iter().map(|(k, v)| (k.clone(), pseudonymize_value(v, salt))).collect()),
+        scalar => Value::String(pseudonymize(scalar, salt)),
+    }
+}
+
+fn hex_pseudonym(digest: &[u8; 32]) -> String {
+    digest[..PSEUDONYM_LEN].iter().fold(PSEUDONYM_PREFIX.to_string(), |mut out, byte| {
+        let _ = write!(out, "{:02x}", byte);
+        out
+    })
+}
+
+/// Returns the HMAC-SHA256 of `message` keyed by `key`.
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    use hmac::Mac;
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
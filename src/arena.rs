@@ -0,0 +1,242 @@
+//! Contexts allocated in a caller-provided arena.
+//!
+//! Batch jobs creating and dropping millions of short-lived contexts spend a large part of their
+//! time in the global allocator. An [`ArenaContext`] allocates its keys, strings, bytes, sequences
+//! and maps in a [`bumpalo::Bump`] arena provided by the caller, which frees them all at once when
+//! it is reset or dropped. It dumps through serde like any context, and [`ArenaContext::to_context`]
+//! copies it to an owned [`Context`] when it must outlive the arena.
+//!
+//! This module is only available when the "allocator-api" feature is enabled, it builds on stable
+//! Rust.
+//!
+//! ```rust
+//! use bumpalo::Bump;
+//! use cdumay_context::{ArenaContext, ArenaValue};
+//! use serde_value::Value;
+//!
+//! let mut bump = Bump::new();
+//! for item in 0..3u64 {
+//!     let mut ctx = ArenaContext::new_in(&bump);
+//!     ctx.insert_str("job", "nightly-export");
+//!     ctx.insert("item", &Value::U64(item));
+//!     assert_eq!(ctx.get("job").and_then(ArenaValue::as_str), Some("nightly-export"));
+//!     drop(ctx);
+//!     bump.reset();
+//! }
+//! ```
+use crate::{Context, Contextualize};
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// A value allocated in the arena of an [`ArenaContext`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'bump> {
+    /// A null, unit or absent optional value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating-point number.
+    F64(f64),
+    /// A string.
+    Str(&'bump str),
+    /// A byte string.
+    Bytes(&'bump [u8]),
+    /// A sequence.
+    Seq(&'bump [ArenaValue<'bump>]),
+    /// A map, entries in key order.
+    Map(&'bump [(ArenaValue<'bump>, ArenaValue<'bump>)]),
+}
+
+impl<'bump> ArenaValue<'bump> {
+    /// Copies `value` into `bump`.
+    pub fn from_value_in(bump: &'bump Bump, value: &Value) -> Self {
+        match value {
+            Value::Unit | Value::Option(None) => ArenaValue::Null,
+            Value::Bool(v) => ArenaValue::Bool(*v),
+            Value::I8(v) => ArenaValue::I64(*v as i64),
+            Value::I16(v) => ArenaValue::I64(*v as i64),
+            Value::I32(v) => ArenaValue::I64(*v as i64),
+            Value::I64(v) => ArenaValue::I64(*v),
+            Value::U8(v) => ArenaValue::U64(*v as u64),
+            Value::U16(v) => ArenaValue::U64(*v as u64),
+            Value::U32(v) => ArenaValue::U64(*v as u64),
+            Value::U64(v) => ArenaValue::U64(*v),
+            Value::F32(v) => ArenaValue::F64(*v as f64),
+            Value::F64(v) => ArenaValue::F64(*v),
+            Value::Char(v) => ArenaValue::Str(bump.alloc_str(v.encode_utf8(&mut [0; 4]))),
+            Value::String(v) => ArenaValue::Str(bump.alloc_str(v)),
+            Value::Bytes(v) => ArenaValue::Bytes(bump.alloc_slice_copy(v)),
+            Value::Option(Some(v)) | Value::Newtype(v) => ArenaValue::from_value_in(bump, v),
+            Value::Seq(v) => ArenaValue::Seq(bump.alloc_slice_fill_iter(v.iter().map(|v| ArenaValue::from_value_in(bump, v)))),
+            Value::Map(v) => ArenaValue::Map(
+                bump.alloc_slice_fill_iter(
+                    v.iter()
+                        .map(|(k, v)| (ArenaValue::from_value_in(bump, k), ArenaValue::from_value_in(bump, v))),
+                ),
+            ),
+        }
+    }
+
+    /// Returns the value as a string slice, if it is a string.
+    pub fn as_str(&self) -> Option<&'bump str> {
+        match self {
+            ArenaValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns an owned copy of the value, allocated with the global allocator.
+    pub fn to_value(&self) -> Value {
+        match self {
+            ArenaValue::Null => Value::Unit,
+            ArenaValue::Bool(v) => Value::Bool(*v),
+            ArenaValue::I64(v) => Value::I64(*v),
+            ArenaValue::U64(v) => Value::U64(*v),
+            ArenaValue::F64(v) => Value::F64(*v),
+            ArenaValue::Str(v) => Value::String(v.to_string()),
+            ArenaValue::Bytes(v) => Value::Bytes(v.to_vec()),
+            ArenaValue::Seq(v) => Value::Seq(v.iter().map(ArenaValue::to_value).collect()),
+            ArenaValue::Map(v) => Value::Map(v.iter().map(|(k, v)| (k.to_value(), v.to_value())).collect()),
+        }
+    }
+}
+
+impl Serialize for ArenaValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ArenaValue::Null => serializer.serialize_unit(),
+            ArenaValue::Bool(v) => serializer.serialize_bool(*v),
+            ArenaValue::I64(v) => serializer.serialize_i64(*v),
+            ArenaValue::U64(v) => serializer.serialize_u64(*v),
+            ArenaValue::F64(v) => serializer.serialize_f64(*v),
+            ArenaValue::Str(v) => serializer.serialize_str(v),
+            ArenaValue::Bytes(v) => serializer.serialize_bytes(v),
+            ArenaValue::Seq(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items.iter() {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            ArenaValue::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries.iter() {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// A context allocated in a caller-provided arena, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ArenaContext<'bump> {
+    bump: &'bump Bump,
+    /// Entries sorted by key.
+    entries: BumpVec<'bump, (&'bump str, ArenaValue<'bump>)>,
+}
+
+impl<'bump> ArenaContext<'bump> {
+    /// Creates an empty context allocating in `bump`.
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self {
+            bump,
+            entries: BumpVec::new_in(bump),
+        }
+    }
+
+    /// Inserts an entry, replacing the value of an existing key.
+    fn put(&mut self, k: &str, v: ArenaValue<'bump>) {
+        match self.entries.binary_search_by(|(key, _)| (*key).cmp(k)) {
+            Ok(index) => self.entries[index].1 = v,
+            Err(index) => {
+                let k = self.bump.alloc_str(k);
+                self.entries.insert(index, (k, v))
+            }
+        }
+    }
+
+    /// Copies `v` into the arena and stores it under `k`, replacing any previous value.
+    pub fn insert(&mut self, k: &str, v: &Value) {
+        let v = ArenaValue::from_value_in(self.bump, v);
+        self.put(k, v);
+    }
+
+    /// Stores the string `v` under `k`, copying it into the arena without building a
+    /// [`Value`] first.
+    pub fn insert_str(&mut self, k: &str, v: &str) {
+        let v = ArenaValue::Str(self.bump.alloc_str(v));
+        self.put(k, v);
+    }
+
+    /// Retrieves the value stored under `k`.
+    pub fn get(&self, k: &str) -> Option<&ArenaValue<'bump>> {
+        self.entries
+            .binary_search_by(|(key, _)| (*key).cmp(k))
+            .ok()
+            .map(|index| &self.entries[index].1)
+    }
+
+    /// Removes the entry stored under `k`, returning its value. Its memory is only reclaimed with
+    /// the arena.
+    pub fn remove(&mut self, k: &str) -> Option<ArenaValue<'bump>> {
+        let index = self.entries.binary_search_by(|(key, _)| (*key).cmp(k)).ok()?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Iterates over the entries, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'bump str, &ArenaValue<'bump>)> {
+        self.entries.iter().map(|(k, v)| (*k, v))
+    }
+
+    /// Returns the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &'bump str> + '_ {
+        self.entries.iter().map(|(k, _)| *k)
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the context holds no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries as owned values, allocated with the global allocator.
+    pub fn inner(&self) -> BTreeMap<String, Value> {
+        self.entries.iter().map(|(k, v)| (k.to_string(), v.to_value())).collect()
+    }
+
+    /// Returns an owned context holding copies of the entries, which can outlive the arena.
+    pub fn to_context(&self) -> Context {
+        let mut ctx = Context::new();
+        ctx.extend(self.inner());
+        ctx
+    }
+}
+
+impl Serialize for ArenaContext<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (k, v) in self.entries.iter() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl From<&ArenaContext<'_>> for Context {
+    fn from(ctx: &ArenaContext<'_>) -> Self {
+        ctx.to_context()
+    }
+}
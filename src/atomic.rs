@@ -0,0 +1,80 @@
+//! Compare-and-set on values.
+//!
+//! [`SharedContext::compare_and_set`] and [`SharedContext::fetch_update`] let concurrent
+//! enrichers coordinate on a key without external locks: the comparison, or the computation of
+//! the new value, and the write run as a single atomic update. The same methods exist on
+//! [`Context`], for code written against both.
+//!
+//! Values are compared with `==`, so that numeric values only match if they have the same
+//! variant: `Value::U8(1)` does not match `Value::I64(1)`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SharedContext};
+//! use serde_value::Value;
+//!
+//! let shared = SharedContext::new(Context::new());
+//!
+//! // Once-only marker: only the first worker claims the job.
+//! let claimed = Value::String("worker-1".to_string());
+//! assert!(shared.compare_and_set("owner", None, claimed.clone()));
+//! assert!(!shared.compare_and_set("owner", None, Value::String("worker-2".to_string())));
+//! assert_eq!(shared.get("owner"), Some(claimed));
+//!
+//! // Read-modify-write without races.
+//! let previous = shared.fetch_update("attempts", |current| match current {
+//!     Some(Value::U64(n)) => Some(Value::U64(n + 1)),
+//!     _ => Some(Value::U64(1)),
+//! });
+//! assert_eq!(previous, Ok(None));
+//! assert_eq!(shared.get("attempts"), Some(Value::U64(1)));
+//! ```
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+
+impl Context {
+    /// Stores `new` under `k` if its current value is `expected`, `None` meaning that the key
+    /// must be missing.
+    ///
+    /// Returns `true` if the value was stored, `false` if the current value differs or the key
+    /// cannot be written, see [`Context::try_insert`].
+    pub fn compare_and_set(&mut self, k: &str, expected: Option<&Value>, new: Value) -> bool {
+        self.get(k) == expected && self.try_insert(k.to_string(), new).is_ok()
+    }
+
+    /// Stores the value returned by `f` under `k`, `f` being given the current value.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Option<Value>, Option<Value>>` which is:
+    /// * `Ok(previous)` containing the previous value if `f` returned a value and it was stored
+    /// * `Err(previous)` containing the current value if `f` returned `None` or the key cannot be
+    ///   written, see [`Context::try_insert`]
+    pub fn fetch_update<F>(&mut self, k: &str, f: F) -> Result<Option<Value>, Option<Value>>
+    where
+        F: FnOnce(Option<&Value>) -> Option<Value>,
+    {
+        let previous = self.get(k).cloned();
+        match f(previous.as_ref()).map(|new| self.try_insert(k.to_string(), new)) {
+            Some(Ok(())) => Ok(previous),
+            _ => Err(previous),
+        }
+    }
+}
+
+impl SharedContext {
+    /// Atomically stores `new` under `k` if its current value is `expected`, see
+    /// [`Context::compare_and_set`].
+    pub fn compare_and_set(&self, k: &str, expected: Option<&Value>, new: Value) -> bool {
+        self.update(|ctx| ctx.compare_and_set(k, expected, new))
+    }
+
+    /// Atomically stores the value returned by `f` under `k`, see [`Context::fetch_update`].
+    ///
+    /// `f` runs while other writers are blocked, and must not write to this shared context.
+    pub fn fetch_update<F>(&self, k: &str, f: F) -> Result<Option<Value>, Option<Value>>
+    where
+        F: FnOnce(Option<&Value>) -> Option<Value>,
+    {
+        self.update(|ctx| ctx.fetch_update(k, f))
+    }
+}
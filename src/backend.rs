@@ -0,0 +1,88 @@
+//! Storage backend of the entries of a [`Context`](crate::Context).
+//!
+//! Entries are stored in a `BTreeMap`, sorted by key. With the "ordered" feature they are stored
+//! in an `IndexMap` in the order they were inserted instead, the loaders keep the order of the
+//! top-level keys of the document, and new contexts default to
+//! [`KeyOrder::Insertion`](crate::KeyOrder::Insertion), so that a loaded context is dumped back
+//! in document order. The [`Contextualize`](crate::Contextualize) API is the same with both
+//! backends.
+use serde::Deserialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "ordered"))]
+type Map<V> = BTreeMap<String, V>;
+#[cfg(feature = "ordered")]
+type Map<V> = indexmap::IndexMap<String, V>;
+
+/// Top-level entries of a loaded document, in document order with the "ordered" feature.
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub(crate) type Loaded<V> = Map<V>;
+
+/// Iterator over the entries of a context, by reference.
+pub(crate) type Iter<'a> = <&'a Map<Value> as IntoIterator>::IntoIter;
+
+/// Entries of a context.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct Entries(Map<Value>);
+
+impl Entries {
+    pub(crate) fn get(&self, k: &str) -> Option<&Value> {
+        self.0.get(k)
+    }
+
+    pub(crate) fn get_mut(&mut self, k: &str) -> Option<&mut Value> {
+        self.0.get_mut(k)
+    }
+
+    pub(crate) fn contains_key(&self, k: &str) -> bool {
+        self.0.contains_key(k)
+    }
+
+    /// Inserts an entry, new keys after the existing ones with the "ordered" feature.
+    pub(crate) fn insert(&mut self, k: String, v: Value) -> Option<Value> {
+        self.0.insert(k, v)
+    }
+
+    /// Removes an entry, keeping the order of the others.
+    pub(crate) fn remove(&mut self, k: &str) -> Option<Value> {
+        #[cfg(feature = "ordered")]
+        return self.0.shift_remove(k);
+        #[cfg(not(feature = "ordered"))]
+        return self.0.remove(k);
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Borrows the entries as a `BTreeMap`, which only the default backend can do.
+    pub(crate) fn as_btree(&self) -> Option<&BTreeMap<String, Value>> {
+        #[cfg(feature = "ordered")]
+        return None;
+        #[cfg(not(feature = "ordered"))]
+        return Some(&self.0);
+    }
+
+    /// Returns a copy of the entries as a `BTreeMap`.
+    pub(crate) fn to_btree(&self) -> BTreeMap<String, Value> {
+        #[cfg(feature = "ordered")]
+        return self.0.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        #[cfg(not(feature = "ordered"))]
+        return self.0.clone();
+    }
+}
+
+impl<'a> IntoIterator for &'a Entries {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -0,0 +1,180 @@
+//! Binary encodings of contexts.
+//!
+//! [`Context::to_bincode`] (feature: "bincode") and [`Context::to_postcard`] (feature:
+//! "postcard") encode the dump of a context in a compact binary form, for snapshots where the
+//! overhead of JSON matters, such as crash dumps and ring buffers. Unlike text formats, these
+//! encodings are not self-describing, so values are encoded along with their exact
+//! `serde_value::Value` variant and decode to identical contexts, bytes included.
+//!
+//! ```rust
+//! # #[cfg(feature = "bincode")]
+//! # {
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("payload".to_string(), Value::Bytes(vec![0, 159, 146, 150]));
+//! ctx.insert("attempt".to_string(), Value::U8(3));
+//!
+//! let bytes = ctx.to_bincode().unwrap();
+//! assert_eq!(Context::from_bincode(&bytes).unwrap().inner(), ctx.inner());
+//! # }
+//! ```
+use crate::{Context, Contextualize, TypeMismatch, UnExpectedError};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Mirror of `serde_value::Value` which does not require a self-describing format.
+#[derive(Serialize, Deserialize)]
+enum BinaryValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Unit,
+    Option(Option<Box<BinaryValue>>),
+    Newtype(Box<BinaryValue>),
+    Seq(Vec<BinaryValue>),
+    Map(Vec<(BinaryValue, BinaryValue)>),
+    Bytes(Vec<u8>),
+}
+
+impl From<Value> for BinaryValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Bool(v) => BinaryValue::Bool(v),
+            Value::U8(v) => BinaryValue::U8(v),
+            Value::U16(v) => BinaryValue::U16(v),
+            Value::U32(v) => BinaryValue::U32(v),
+            Value::U64(v) => BinaryValue::U64(v),
+            Value::I8(v) => BinaryValue::I8(v),
+            Value::I16(v) => BinaryValue::I16(v),
+            Value::I32(v) => BinaryValue::I32(v),
+            Value::I64(v) => BinaryValue::I64(v),
+            Value::F32(v) => BinaryValue::F32(v),
+            Value::F64(v) => BinaryValue::F64(v),
+            Value::Char(v) => BinaryValue::Char(v),
+            Value::String(v) => BinaryValue::String(v),
+            Value::Unit => BinaryValue::Unit,
+            Value::Option(v) => BinaryValue::Option(v.map(|v| Box::new((*v).into()))),
+            Value::Newtype(v) => BinaryValue::Newtype(Box::new((*v).into())),
+            Value::Seq(v) => BinaryValue::Seq(v.into_iter().map(Into::into).collect()),
+            Value::Map(v) => BinaryValue::Map(v.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+            Value::Bytes(v) => BinaryValue::Bytes(v),
+        }
+    }
+}
+
+impl From<BinaryValue> for Value {
+    fn from(value: BinaryValue) -> Self {
+        match value {
+            BinaryValue::Bool(v) => Value::Bool(v),
+            BinaryValue::U8(v) => Value::U8(v),
+            BinaryValue::U16(v) => Value::U16(v),
+            BinaryValue::U32(v) => Value::U32(v),
+            BinaryValue::U64(v) => Value::U64(v),
+            BinaryValue::I8(v) => Value::I8(v),
+            BinaryValue::I16(v) => Value::I16(v),
+            BinaryValue::I32(v) => Value::I32(v),
+            BinaryValue::I64(v) => Value::I64(v),
+            BinaryValue::F32(v) => Value::F32(v),
+            BinaryValue::F64(v) => Value::F64(v),
+            BinaryValue::Char(v) => Value::Char(v),
+            BinaryValue::String(v) => Value::String(v),
+            BinaryValue::Unit => Value::Unit,
+            BinaryValue::Option(v) => Value::Option(v.map(|v| Box::new((*v).into()))),
+            BinaryValue::Newtype(v) => Value::Newtype(Box::new((*v).into())),
+            BinaryValue::Seq(v) => Value::Seq(v.into_iter().map(Into::into).collect()),
+            BinaryValue::Map(v) => Value::Map(v.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+            BinaryValue::Bytes(v) => Value::Bytes(v),
+        }
+    }
+}
+
+type BinaryDump = Vec<(String, BinaryValue)>;
+
+impl Context {
+    /// Encodes the dump of the context with bincode.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Vec<u8>>` which is:
+    /// * `Ok(bytes)` containing the encoded context on success
+    /// * `Err(e)` containing an [`UnExpectedError`] on failure
+    #[cfg(feature = "bincode")]
+    pub fn to_bincode(&self) -> cdumay_core::Result<Vec<u8>> {
+        bincode::serde::encode_to_vec(self.binary_dump(), bincode::config::standard()).map_err(|err| self.encode_error("bincode", err.to_string()))
+    }
+
+    /// Creates a new context from bytes produced by [`Context::to_bincode`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the decoded context on success
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the bytes are not a valid encoding
+    #[cfg(feature = "bincode")]
+    pub fn from_bincode(bytes: &[u8]) -> cdumay_core::Result<Context> {
+        let (dump, _) = bincode::serde::decode_from_slice::<BinaryDump, _>(bytes, bincode::config::standard())
+            .map_err(|err| decode_error("bincode", err.to_string()))?;
+        Ok(from_binary_dump(dump))
+    }
+
+    /// Encodes the dump of the context with postcard.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Vec<u8>>` which is:
+    /// * `Ok(bytes)` containing the encoded context on success
+    /// * `Err(e)` containing an [`UnExpectedError`] on failure
+    #[cfg(feature = "postcard")]
+    pub fn to_postcard(&self) -> cdumay_core::Result<Vec<u8>> {
+        postcard::to_allocvec(&self.binary_dump()).map_err(|err| self.encode_error("postcard", err.to_string()))
+    }
+
+    /// Creates a new context from bytes produced by [`Context::to_postcard`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the decoded context on success
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the bytes are not a valid encoding
+    #[cfg(feature = "postcard")]
+    pub fn from_postcard(bytes: &[u8]) -> cdumay_core::Result<Context> {
+        let dump = postcard::from_bytes::<BinaryDump>(bytes).map_err(|err| decode_error("postcard", err.to_string()))?;
+        Ok(from_binary_dump(dump))
+    }
+
+    fn binary_dump(&self) -> BinaryDump {
+        self.inner().into_iter().map(|(k, v)| (k, v.into())).collect()
+    }
+
+    fn encode_error(&self, format: &str, message: String) -> cdumay_core::Error {
+        UnExpectedError::new()
+            .with_message(format!("Failed to encode context with {}: {}", format, message))
+            .with_details(self.error_details())
+            .into()
+    }
+}
+
+fn from_binary_dump(dump: BinaryDump) -> Context {
+    let mut ctx = Context::new();
+    ctx.extend(dump.into_iter().map(|(k, v)| (k, v.into())).collect::<BTreeMap<String, Value>>());
+    ctx
+}
+
+fn decode_error(format: &str, message: String) -> cdumay_core::Error {
+    TypeMismatch::new()
+        .with_message(format!("Failed to decode {} context: {}", format, message))
+        .into()
+}
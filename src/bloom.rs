@@ -0,0 +1,68 @@
+//! Bloom filter summaries of keys.
+//!
+//! [`Context::key_filter`] summarizes the keys of a context in a compact bloom filter, so that a
+//! coordinator can ask whether any worker context mentions a key with [`Context::may_contain`]
+//! without shipping full dumps. A filter never misses a key of its context, but may report a key
+//! it does not hold, about once in a hundred lookups.
+//!
+//! The filter is a byte holding the number of hash functions, followed by the bits of the filter.
+//! Keys are hashed with FNV-1a, so filters built by different processes and versions of the crate
+//! can be compared.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("tenant".to_string(), Value::String("acme".to_string()));
+//!
+//! let filter = ctx.key_filter();
+//! assert!(Context::may_contain(&filter, "tenant"));
+//! ```
+use crate::hash::fnv1a;
+use crate::{Context, Contextualize};
+
+/// Number of hash functions, optimal for a 1% false positive rate.
+const HASHES: u8 = 7;
+/// Bits per key, for a 1% false positive rate.
+const BITS_PER_KEY: usize = 10;
+
+impl Context {
+    /// Returns a bloom filter of the keys of the dump of the context.
+    pub fn key_filter(&self) -> Vec<u8> {
+        let keys = self.inner().into_keys().collect::<Vec<_>>();
+        let bytes = (keys.len() * BITS_PER_KEY).div_ceil(8).max(1);
+        let mut filter = vec![0; bytes + 1];
+        filter[0] = HASHES;
+        let bits = bytes * 8;
+        for key in &keys {
+            for bit in bit_indexes(key, HASHES, bits) {
+                filter[1 + bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        filter
+    }
+
+    /// Returns `true` if `key` may be a key of the context `filter` was built from, see
+    /// [`Context::key_filter`].
+    ///
+    /// `key` is looked up as is: it must be given in normalized form for contexts with a
+    /// [`KeyPolicy`](crate::KeyPolicy). Invalid filters contain no key.
+    pub fn may_contain(filter: &[u8], key: &str) -> bool {
+        let Some((&hashes, bits)) = filter.split_first() else {
+            return false;
+        };
+        if bits.is_empty() {
+            return false;
+        }
+        bit_indexes(key, hashes, bits.len() * 8).all(|bit| bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
+
+/// Returns the bits set for `key`, by double hashing.
+fn bit_indexes(key: &str, hashes: u8, bits: usize) -> impl Iterator<Item = usize> {
+    let hash = fnv1a(key.as_bytes());
+    let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+    (0..u64::from(hashes)).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits as u64) as usize)
+}
@@ -0,0 +1,263 @@
+//! Zero-copy deserialization of contexts.
+//!
+//! A [`ContextRef`] is a read-only context whose strings and bytes borrow from the buffer it was
+//! deserialized from, so that looking at a few keys of a large dump does not copy every string
+//! of it. It deserializes from any self-describing serde format: [`ContextRef::from_json`]
+//! (feature: "json") and [`ContextRef::from_msgpack`] (feature: "msgpack") are provided.
+//! Strings which must be unescaped, such as JSON strings holding `\n`, are the only ones copied.
+//! [`ContextRef::to_context`] converts it to an owned [`Context`] when needed.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{BorrowedValue, ContextRef};
+//!
+//! let dump = r#"{"request_id":"abc","payload":{"items":[1,2,3]}}"#;
+//! let ctx = ContextRef::from_json(dump).unwrap();
+//! assert_eq!(ctx.get("request_id").and_then(BorrowedValue::as_str), Some("abc"));
+//! assert!(ctx.get("request_id").unwrap().is_borrowed());
+//! # }
+//! ```
+use crate::{Context, Contextualize};
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use serde_value::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A value borrowing its strings and bytes from the deserialized buffer, see [`ContextRef`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedValue<'a> {
+    /// A null or unit value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A negative integer.
+    I64(i64),
+    /// A non-negative integer.
+    U64(u64),
+    /// A floating-point number.
+    F64(f64),
+    /// A string.
+    Str(Cow<'a, str>),
+    /// A byte string.
+    Bytes(Cow<'a, [u8]>),
+    /// A sequence.
+    Seq(Vec<BorrowedValue<'a>>),
+    /// A map, entries in input order.
+    Map(Vec<(BorrowedValue<'a>, BorrowedValue<'a>)>),
+}
+
+impl<'a> BorrowedValue<'a> {
+    /// Returns the value as a string slice, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value is a string or bytes borrowed from the input.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, BorrowedValue::Str(Cow::Borrowed(_)) | BorrowedValue::Bytes(Cow::Borrowed(_)))
+    }
+
+    /// Looks up `key` if the value is a map.
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'a>> {
+        match self {
+            BorrowedValue::Map(entries) => entries.iter().find(|(k, _)| k.as_str() == Some(key)).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns an owned copy of the value.
+    pub fn to_value(&self) -> Value {
+        match self {
+            BorrowedValue::Null => Value::Unit,
+            BorrowedValue::Bool(v) => Value::Bool(*v),
+            BorrowedValue::I64(v) => Value::I64(*v),
+            BorrowedValue::U64(v) => Value::U64(*v),
+            BorrowedValue::F64(v) => Value::F64(*v),
+            BorrowedValue::Str(v) => Value::String(v.to_string()),
+            BorrowedValue::Bytes(v) => Value::Bytes(v.to_vec()),
+            BorrowedValue::Seq(v) => Value::Seq(v.iter().map(BorrowedValue::to_value).collect()),
+            BorrowedValue::Map(v) => Value::Map(v.iter().map(|(k, v)| (k.to_value(), v.to_value())).collect()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BorrowedValue<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BorrowedValueVisitor)
+    }
+}
+
+struct BorrowedValueVisitor;
+
+impl<'de> Visitor<'de> for BorrowedValueVisitor {
+    type Value = BorrowedValue<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Bool(v))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(match u64::try_from(v) {
+            Ok(v) => BorrowedValue::U64(v),
+            Err(_) => BorrowedValue::I64(v),
+        })
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::U64(v))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::F64(v))
+    }
+
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Str(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Str(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Str(Cow::Owned(v)))
+    }
+
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Bytes(Cow::Borrowed(v)))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Bytes(Cow::Owned(v.to_vec())))
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Bytes(Cow::Owned(v)))
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        BorrowedValue::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(BorrowedValue::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(BorrowedValue::Seq(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(BorrowedValue::Map(entries))
+    }
+}
+
+/// A read-only context borrowing from the buffer it was deserialized from, see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextRef<'a> {
+    data: BTreeMap<Cow<'a, str>, BorrowedValue<'a>>,
+}
+
+impl<'a> ContextRef<'a> {
+    /// Deserializes a context from a JSON dump, borrowing from `json`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<ContextRef>` which is:
+    /// * `Ok(context)` containing the context on success
+    /// * `Err(e)` containing the converted JSON error if `json` is not a JSON object
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &'a str) -> cdumay_core::Result<Self> {
+        use cdumay_core::ErrorConverter;
+        serde_json::from_str(json)
+            .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), BTreeMap::new()))
+    }
+
+    /// Deserializes a context from a MessagePack dump, borrowing from `bytes`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<ContextRef>` which is:
+    /// * `Ok(context)` containing the context on success
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if `bytes` is not a
+    ///   MessagePack map with string keys
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(bytes: &'a [u8]) -> cdumay_core::Result<Self> {
+        rmp_serde::from_slice(bytes).map_err(|err| {
+            crate::TypeMismatch::new()
+                .with_message(format!("Failed to load MessagePack context: {}", err))
+                .into()
+        })
+    }
+
+    /// Retrieves the value stored under `k`.
+    pub fn get(&self, k: &str) -> Option<&BorrowedValue<'a>> {
+        self.data.get(k)
+    }
+
+    /// Returns the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.data.keys().map(|k| k.as_ref())
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the context holds no entry.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns an owned context holding copies of the entries.
+    pub fn to_context(&self) -> Context {
+        let mut ctx = Context::new();
+        ctx.extend(self.data.iter().map(|(k, v)| (k.to_string(), v.to_value())).collect());
+        ctx
+    }
+}
+
+impl<'de> Deserialize<'de> for ContextRef<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match BorrowedValue::deserialize(deserializer)? {
+            BorrowedValue::Map(entries) => entries
+                .into_iter()
+                .map(|(k, v)| match k {
+                    BorrowedValue::Str(k) => Ok((k, v)),
+                    _ => Err(D::Error::custom("context keys must be strings")),
+                })
+                .collect::<Result<_, _>>()
+                .map(|data| ContextRef { data }),
+            _ => Err(D::Error::custom("expected a map of context entries")),
+        }
+    }
+}
+
+impl From<&ContextRef<'_>> for Context {
+    fn from(ctx: &ContextRef<'_>) -> Self {
+        ctx.to_context()
+    }
+}
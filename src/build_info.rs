@@ -0,0 +1,58 @@
+//! Build information.
+//!
+//! [`BuildInfo`] is filled by the application, typically from values embedded at compile time by
+//! its build script, and [`Context::with_build_info`] stores it under the [`BUILD_KEY`] key with
+//! the standard entry names `git_sha`, `build_date`, `profile` and `rustc_version`, so that all
+//! exporters find it at the same place (`build.git_sha`, ...).
+//!
+//! ```rust
+//! use cdumay_context::{BuildInfo, Context, Contextualize};
+//!
+//! let info = BuildInfo {
+//!     git_sha: Some("4f1c2e9".to_string()),
+//!     profile: Some("release".to_string()),
+//!     ..Default::default()
+//! };
+//! let ctx = Context::new().with_build_info(&info);
+//! assert_eq!(ctx.at("build").at("git_sha").as_str(), Some("4f1c2e9"));
+//! assert!(ctx.at("build").at("build_date").as_str().is_none());
+//! ```
+use crate::{Context, Contextualize};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which [`Context::with_build_info`] stores the build information.
+pub const BUILD_KEY: &str = crate::keys::BUILD;
+
+/// Build information of the application, stored under `build.*`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Commit the application was built from (`build.git_sha`).
+    pub git_sha: Option<String>,
+    /// Date of the build (`build.build_date`), preferably in RFC 3339 format.
+    pub build_date: Option<String>,
+    /// Cargo profile of the build (`build.profile`), e.g. `release`.
+    pub profile: Option<String>,
+    /// Version of the compiler (`build.rustc_version`).
+    pub rustc_version: Option<String>,
+}
+
+impl Context {
+    /// Stores the fields of `info` which are set under the [`BUILD_KEY`] key.
+    pub fn with_build_info(mut self, info: &BuildInfo) -> Self {
+        let build: BTreeMap<Value, Value> = [
+            ("git_sha", &info.git_sha),
+            ("build_date", &info.build_date),
+            ("profile", &info.profile),
+            ("rustc_version", &info.rustc_version),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((Value::String(key.to_string()), Value::String(value.clone()?))))
+        .collect();
+        if !build.is_empty() {
+            self.insert(BUILD_KEY.to_string(), Value::Map(build));
+        }
+        self
+    }
+}
@@ -0,0 +1,330 @@
+//! Fluent construction of contexts.
+//!
+//! This module provides [`ContextBuilder`], returned by [`Context::builder`], which accepts any
+//! `Serialize` value so that contexts can be assembled at error sites in a single expression.
+//!
+//! The builder also layers configuration sources in precedence order, each layer being deep
+//! merged into the previous ones, see [`MergeStrategy::DeepMerge`](crate::MergeStrategy::DeepMerge):
+//!
+//! * [`ContextBuilder::with_defaults`] adds a map of default values;
+//! * [`ContextBuilder::with_file`] adds a JSON, TOML or YAML file, in the format given by its
+//!   extension, and [`ContextBuilder::with_optional_file`] one which may not exist;
+//! * [`ContextBuilder::with_env`] adds the environment variables starting with a prefix, a double
+//!   underscore in their name separating nested keys (`APP_DB__HOST` sets `db.host`);
+//! * [`ContextBuilder::with_overrides`] adds `path=value` arguments, such as the `--set` options
+//!   of a command line.
+//!
+//! The builder records the [`SourceId`] of each value, given by [`ContextBuilder::origin`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SourceId};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! std::env::set_var("BUILDER_DOC_DB__PORT", "5433");
+//! let db = Value::Map(BTreeMap::from([
+//!     (Value::String("host".to_string()), Value::String("localhost".to_string())),
+//!     (Value::String("port".to_string()), Value::I64(5432)),
+//! ]));
+//!
+//! let builder = Context::builder()
+//!     .with_defaults(BTreeMap::from([("db".to_string(), db)]))
+//!     .with_env("BUILDER_DOC")
+//!     .with_overrides(["db.host=db.internal"]);
+//! assert_eq!(builder.origin("db.host"), Some(&SourceId::Overrides));
+//! assert_eq!(builder.origin("db.port"), Some(&SourceId::Env("BUILDER_DOC".to_string())));
+//!
+//! let ctx = builder.build().unwrap();
+//! assert_eq!(ctx.at("db").at("host").as_str(), Some("db.internal"));
+//! assert_eq!(ctx.at("db").at("port").as_i64(), Some(5433));
+//! ```
+use crate::env::coerce;
+use crate::mapkey::stringify;
+use crate::{Context, ContextDump, Contextualize, EnvOptions, FsStorage, MergeStrategy, UnExpectedError, ValidationError};
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Separator of nested keys in the names of environment variables, see
+/// [`ContextBuilder::with_env`].
+const ENV_NESTING_SEPARATOR: &str = "__";
+
+/// Source of a value set by a [`ContextBuilder`], see [`ContextBuilder::origin`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SourceId {
+    /// Set with [`ContextBuilder::with`], [`ContextBuilder::with_opt`] or [`ContextBuilder::merge`].
+    Builder,
+    /// Set with [`ContextBuilder::with_defaults`].
+    Defaults,
+    /// Read from a file with [`ContextBuilder::with_file`] or [`ContextBuilder::with_optional_file`].
+    File(PathBuf),
+    /// Read from the environment variables starting with the given prefix.
+    Env(String),
+    /// Set with [`ContextBuilder::with_overrides`].
+    Overrides,
+}
+
+/// Displays the source as `builder`, `defaults`, `file:<path>`, `env:<prefix>` or `overrides`.
+impl fmt::Display for SourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceId::Builder => f.write_str("builder"),
+            SourceId::Defaults => f.write_str("defaults"),
+            SourceId::File(path) => write!(f, "file:{}", path.display()),
+            SourceId::Env(prefix) => write!(f, "env:{}", prefix),
+            SourceId::Overrides => f.write_str("overrides"),
+        }
+    }
+}
+
+/// A fluent builder for [`Context`].
+///
+/// # Example
+///
+/// ```rust
+/// use cdumay_context::{Context, Contextualize};
+///
+/// let team: Option<&str> = None;
+/// let ctx = Context::builder()
+///     .with("user", 42)
+///     .with("roles", vec!["admin", "ops"])
+///     .with_opt("team", team)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(ctx.get("user"), Some(&serde_value::Value::I32(42)));
+/// assert!(ctx.get("team").is_none());
+/// ```
+#[derive(Debug, Default)]
+pub struct ContextBuilder {
+    data: BTreeMap<String, Value>,
+    origins: BTreeMap<String, SourceId>,
+    error: Option<cdumay_core::Error>,
+}
+
+impl ContextBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key with any serializable value, replacing a previous value for the same key.
+    pub fn with<T: Serialize>(mut self, key: impl Into<String>, value: T) -> Self {
+        let key = key.into();
+        match serde_value::to_value(value) {
+            Ok(value) => self.set(key, value),
+            Err(err) => {
+                let err = UnExpectedError::new()
+                    .with_message(format!("Failed to serialize context value '{}': {}", key, err))
+                    .with_details(self.data.clone());
+                self.fail(err.into());
+            }
+        }
+        self
+    }
+
+    /// Adds a key only if `value` is `Some`.
+    pub fn with_opt<T: Serialize>(self, key: impl Into<String>, value: Option<T>) -> Self {
+        match value {
+            Some(value) => self.with(key, value),
+            None => self,
+        }
+    }
+
+    /// Merges the dump of an existing context, overwriting keys already set on the builder.
+    pub fn merge<C: ContextDump>(mut self, other: &C) -> Self {
+        for (k, v) in other.dump() {
+            self.set(k, v);
+        }
+        self
+    }
+
+    /// Deep merges a layer of default values, see the [module documentation](self).
+    pub fn with_defaults(self, defaults: BTreeMap<String, Value>) -> Self {
+        self.layer(SourceId::Defaults, defaults)
+    }
+
+    /// Deep merges the file at `path`, in the format given by its extension.
+    ///
+    /// If the file cannot be loaded, [`ContextBuilder::build`] fails with the error of
+    /// [`Context::load_file`].
+    pub fn with_file(self, path: impl AsRef<Path>) -> Self {
+        self.file(path.as_ref(), true)
+    }
+
+    /// Deep merges the file at `path` if it exists, see [`ContextBuilder::with_file`].
+    pub fn with_optional_file(self, path: impl AsRef<Path>) -> Self {
+        self.file(path.as_ref(), false)
+    }
+
+    /// Deep merges the environment variables starting with `prefix`, their values being coerced
+    /// to booleans and numbers, see [`EnvOptions::with_coercion`].
+    pub fn with_env(self, prefix: &str) -> Self {
+        self.with_env_with(prefix, &EnvOptions::new().with_coercion(true))
+    }
+
+    /// Deep merges the environment variables starting with `prefix`, imported with `options`, see
+    /// [`Context::from_env_with`].
+    pub fn with_env_with(self, prefix: &str, options: &EnvOptions) -> Self {
+        let mut layer = BTreeMap::new();
+        for (k, v) in Context::from_env_with(Some(prefix), options) {
+            let path: Vec<&str> = k.split(ENV_NESTING_SEPARATOR).collect();
+            insert_nested(&mut layer, &path, v);
+        }
+        self.layer(SourceId::Env(prefix.to_string()), layer)
+    }
+
+    /// Deep merges `path=value` arguments, `path` being a dotted path and `value` being coerced to
+    /// a boolean or a number if it reads as one.
+    ///
+    /// If an argument has no `=`, [`ContextBuilder::build`] fails with a [`ValidationError`]
+    /// error.
+    pub fn with_overrides<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut layer = BTreeMap::new();
+        for arg in args {
+            match arg.as_ref().split_once('=') {
+                Some((path, value)) => {
+                    let path: Vec<&str> = path.trim().split('.').collect();
+                    insert_nested(&mut layer, &path, coerce(value));
+                }
+                None => {
+                    let err = ValidationError::new().with_message(format!("Invalid override '{}', expected path=value", arg.as_ref()));
+                    self.fail(err.into());
+                }
+            }
+        }
+        self.layer(SourceId::Overrides, layer)
+    }
+
+    /// Returns the source of the value at the dotted `path`.
+    ///
+    /// For a map, the source is returned only if every value of the map comes from it. Returns
+    /// `None` if no value was set at `path`.
+    pub fn origin(&self, path: &str) -> Option<&SourceId> {
+        if let Some(source) = self.origins.get(path) {
+            return Some(source);
+        }
+        let prefix = format!("{}.", path);
+        let mut sources = self
+            .origins
+            .range(prefix.clone()..)
+            .take_while(|(k, _)| k.starts_with(&prefix))
+            .map(|(_, source)| source);
+        let first = sources.next()?;
+        sources.all(|source| source == first).then_some(first)
+    }
+
+    /// Returns the source of each value set, by dotted path. Maps are not listed, only the values
+    /// they hold, empty maps excepted.
+    pub fn origins(&self) -> &BTreeMap<String, SourceId> {
+        &self.origins
+    }
+
+    /// Builds the context.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing every value added to the builder
+    /// * `Err(e)` containing the first error met: if a value failed to serialize, the key is
+    ///   given in the message and the values added before it are available in the details; or the
+    ///   error of a layer which could not be read
+    pub fn build(self) -> cdumay_core::Result<Context> {
+        match self.error {
+            Some(err) => Err(err),
+            None => {
+                let mut ctx = Context::new();
+                ctx.extend(self.data);
+                Ok(ctx)
+            }
+        }
+    }
+
+    /// Sets `key` to `value`, replacing its previous value.
+    fn set(&mut self, key: String, value: Value) {
+        forget(&mut self.origins, &key);
+        record(&mut self.origins, &key, &value, &SourceId::Builder);
+        self.data.insert(key, value);
+    }
+
+    /// Deep merges `layer`, its values coming from `source`.
+    fn layer(mut self, source: SourceId, layer: BTreeMap<String, Value>) -> Self {
+        for (k, v) in layer {
+            record(&mut self.origins, &k, &v, &source);
+            let v = match self.data.remove(&k) {
+                Some(existing) => MergeStrategy::DeepMerge.combine(existing, v),
+                None => v,
+            };
+            self.data.insert(k, v);
+        }
+        self
+    }
+
+    fn file(self, path: &Path, required: bool) -> Self {
+        match Context::load_file(&FsStorage, path) {
+            Ok(ctx) => self.layer(SourceId::File(path.to_path_buf()), ctx.inner()),
+            Err(err) if !required && err.code() == 404 => self,
+            Err(err) => {
+                let mut builder = self;
+                builder.fail(err);
+                builder
+            }
+        }
+    }
+
+    /// Keeps `err` if no error was met before.
+    fn fail(&mut self, err: cdumay_core::Error) {
+        self.error.get_or_insert(err);
+    }
+}
+
+/// Removes the origins of the value at `path` and of the values it holds.
+fn forget(origins: &mut BTreeMap<String, SourceId>, path: &str) {
+    let prefix = format!("{}.", path);
+    origins.retain(|k, _| k != path && !k.starts_with(&prefix));
+}
+
+/// Records `source` as the origin of the values of `value`, deep merged at `path`.
+fn record(origins: &mut BTreeMap<String, SourceId>, path: &str, value: &Value, source: &SourceId) {
+    match value {
+        Value::Map(map) if !map.is_empty() => {
+            origins.remove(path);
+            for (k, v) in map {
+                record(origins, &format!("{}.{}", path, stringify(k)), v, source);
+            }
+        }
+        // An empty map merged into an existing one leaves it as it is.
+        Value::Map(_) if origins.keys().any(|k| k.starts_with(&format!("{}.", path))) => {}
+        _ => {
+            forget(origins, path);
+            origins.insert(path.to_string(), source.clone());
+        }
+    }
+}
+
+/// Inserts `value` at the nested `path` of `layer`, creating the missing intermediate maps.
+fn insert_nested(layer: &mut BTreeMap<String, Value>, path: &[&str], value: Value) {
+    let Some((first, rest)) = path.split_first() else { return };
+    let value = rest
+        .iter()
+        .rev()
+        .fold(value, |value, k| Value::Map(BTreeMap::from([(Value::String(k.to_string()), value)])));
+    let value = match layer.remove(*first) {
+        Some(existing) => MergeStrategy::DeepMerge.combine(existing, value),
+        None => value,
+    };
+    layer.insert(first.to_string(), value);
+}
+
+impl Context {
+    /// Returns a [`ContextBuilder`] to construct a context fluently.
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+}
@@ -0,0 +1,154 @@
+//! Replay bundles.
+//!
+//! [`Context::export_bundle`] writes everything needed to rebuild a context elsewhere into a
+//! directory, so that the context of a failed job can be imported locally with
+//! [`Context::import_bundle`] and the job replayed:
+//!
+//! * `context.json`: the dump of the context;
+//! * `metadata.json`: the lifecycle state and revision of the context, and for each key its
+//!   sensitivity level, whether it is protected or frozen, and the revisions at which it was
+//!   created and last changed.
+//!
+//! The provenance of the context travels with the dump, under `propagation.hops`.
+//! [`Context::export_bundle_to`] and [`Context::import_bundle_from`] do the same through a
+//! [`ContextStorage`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("job".to_string(), Value::String("nightly-export".to_string()));
+//! ctx.set_sensitivity("job", Sensitivity::Public);
+//! ctx.freeze_key("job");
+//!
+//! let dir = std::env::temp_dir().join(format!("cdumay-bundle-doc-{}", std::process::id()));
+//! ctx.export_bundle(&dir).unwrap();
+//! let replayed = Context::import_bundle(&dir).unwrap();
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! assert_eq!(replayed.inner(), ctx.inner());
+//! assert_eq!(replayed.sensitivity("job"), Sensitivity::Public);
+//! assert!(replayed.is_frozen("job"));
+//! ```
+use crate::{Context, ContextStorage, Contextualize, FsStorage, Lifecycle, Sensitivity, TypeMismatch};
+use cdumay_core::ErrorConverter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const DUMP_FILE: &str = "context.json";
+const METADATA_FILE: &str = "metadata.json";
+
+/// Content of `metadata.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleMetadata {
+    lifecycle: Option<Lifecycle>,
+    revision: u64,
+    keys: BTreeMap<String, KeyMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyMetadata {
+    sensitivity: Sensitivity,
+    protected: bool,
+    frozen: bool,
+    created: Option<u64>,
+    changed: Option<u64>,
+}
+
+impl Context {
+    /// Writes the dump and metadata of the context into `dir`, created if needed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the bundle was written
+    /// * `Err(e)` containing an [`UnExpectedError`](crate::UnExpectedError) if a file cannot be written
+    pub fn export_bundle(&self, dir: impl AsRef<Path>) -> cdumay_core::Result<()> {
+        self.export_bundle_to(&FsStorage, dir)
+    }
+
+    /// Writes the dump and metadata of the context into `dir` in `storage`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the bundle was written
+    /// * `Err(e)` containing the error of the storage if a file cannot be written
+    pub fn export_bundle_to<S: ContextStorage + ?Sized>(&self, storage: &S, dir: impl AsRef<Path>) -> cdumay_core::Result<()> {
+        let dir = dir.as_ref();
+        let keys = self
+            .inner()
+            .into_keys()
+            .chain(self.sensitivity.keys().cloned())
+            .chain(self.protected.iter().cloned())
+            .map(|k| {
+                let metadata = KeyMetadata {
+                    sensitivity: self.sensitivity(&k),
+                    protected: self.protected.contains(&k),
+                    frozen: self.frozen.contains(&k),
+                    created: self.created.get(&k).copied(),
+                    changed: self.changes.get(&k).copied(),
+                };
+                (k, metadata)
+            })
+            .collect();
+        let metadata = BundleMetadata {
+            lifecycle: self.lifecycle,
+            revision: self.revision,
+            keys,
+        };
+        let metadata = serde_json::to_string_pretty(&metadata).map_err(|err| {
+            cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump bundle metadata".to_string()), self.error_details())
+        })?;
+        storage.write(&dir.join(DUMP_FILE), &self.to_json(true)?)?;
+        storage.write(&dir.join(METADATA_FILE), &metadata)
+    }
+
+    /// Creates a new context from a bundle written by [`Context::export_bundle`].
+    ///
+    /// The context gets a fresh change history: revisions recorded in the bundle are only
+    /// informative.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the imported context on success
+    /// * `Err(e)` containing a [`KeyNotFound`](crate::KeyNotFound) error if a file of the bundle is missing, or a
+    ///   [`TypeMismatch`] error if a file is invalid
+    pub fn import_bundle(dir: impl AsRef<Path>) -> cdumay_core::Result<Context> {
+        Context::import_bundle_from(&FsStorage, dir)
+    }
+
+    /// Creates a new context from a bundle written by [`Context::export_bundle_to`] into `dir` in
+    /// `storage`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the imported context on success
+    /// * `Err(e)` containing the error of the storage if a file cannot be read, or a
+    ///   [`TypeMismatch`] error if a file is invalid
+    pub fn import_bundle_from<S: ContextStorage + ?Sized>(storage: &S, dir: impl AsRef<Path>) -> cdumay_core::Result<Context> {
+        let dir = dir.as_ref();
+        let mut ctx = Context::from_json(&storage.read(&dir.join(DUMP_FILE))?)?;
+        let metadata: BundleMetadata = serde_json::from_str(&storage.read(&dir.join(METADATA_FILE))?).map_err(|err| {
+            cdumay_core::Error::from(
+                TypeMismatch::new()
+                    .with_message(format!("Invalid bundle metadata in {}: {}", dir.display(), err))
+                    .with_details(ctx.error_details()),
+            )
+        })?;
+        for (k, key) in metadata.keys {
+            ctx.set_sensitivity(&k, key.sensitivity);
+            if key.protected {
+                ctx.protect_key(&k);
+            }
+            if key.frozen {
+                ctx.freeze_key(&k);
+            }
+        }
+        ctx.lifecycle = metadata.lifecycle;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,148 @@
+//! Canonical encoding and content hashing.
+//!
+//! [`Context::canonical_bytes`] encodes the content of a context as compact JSON whose bytes only
+//! depend on the content, so that equal contexts can be compared, deduplicated or cached by their
+//! encoding whatever the order their keys were inserted in and whatever the format they were
+//! loaded from:
+//!
+//! - keys of the context and of nested maps are sorted by their UTF-8 bytes, non-string map keys
+//!   being rendered as strings;
+//! - integers are rendered in decimal whatever their width and sign, and floats with an integral
+//!   value as integers, so that `5u8`, `5i64` and `5.0` encode alike; other floats use their
+//!   shortest round-trip rendering, and non-finite ones the strings `"NaN"`, `"Infinity"` and
+//!   `"-Infinity"`;
+//! - units and `None` are rendered as `null`, options, newtypes and characters as their value,
+//!   and bytes as sequences of numbers;
+//! - strings are escaped as in JSON, with the short escapes for `\b`, `\f`, `\n`, `\r` and `\t`
+//!   and `\u00xx` for the other control characters.
+//!
+//! [`Context::content_hash`] (feature: "hash") returns the SHA-256 of this encoding.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut a = Context::new();
+//! a.insert("status".to_string(), Value::U16(503));
+//! a.insert("route".to_string(), Value::String("/orders".to_string()));
+//!
+//! let mut b = Context::new();
+//! b.insert("route".to_string(), Value::String("/orders".to_string()));
+//! b.insert("status".to_string(), Value::F64(503.0));
+//!
+//! assert_eq!(a.canonical_bytes(), br#"{"route":"/orders","status":503}"#.to_vec());
+//! assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl Context {
+    /// Returns the canonical encoding of the content of the context, see the
+    /// [module documentation](self).
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = String::new();
+        write_map(&mut out, self.inner().into_iter());
+        out.into_bytes()
+    }
+
+    /// Returns the SHA-256 of [`Context::canonical_bytes`], as a lowercase hexadecimal string.
+    ///
+    /// This method is only available when the "hash" feature is enabled.
+    #[cfg(feature = "hash")]
+    pub fn content_hash(&self) -> String {
+        use sha2::Digest;
+        sha2::Sha256::digest(self.canonical_bytes())
+            .iter()
+            .fold(String::with_capacity(64), |mut hex, byte| {
+                let _ = write!(hex, "{:02x}", byte);
+                hex
+            })
+    }
+}
+
+fn write_map(out: &mut String, entries: impl Iterator<Item = (String, Value)>) {
+    let sorted: BTreeMap<String, Value> = entries.collect();
+    out.push('{');
+    for (i, (k, v)) in sorted.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_str(out, &k);
+        out.push(':');
+        write_value(out, v);
+    }
+    out.push('}');
+}
+
+fn write_value(out: &mut String, value: Value) {
+    match value {
+        Value::Bool(b) => out.push_str(if b { "true" } else { "false" }),
+        Value::U8(n) => write_display(out, n),
+        Value::U16(n) => write_display(out, n),
+        Value::U32(n) => write_display(out, n),
+        Value::U64(n) => write_display(out, n),
+        Value::I8(n) => write_display(out, n),
+        Value::I16(n) => write_display(out, n),
+        Value::I32(n) => write_display(out, n),
+        Value::I64(n) => write_display(out, n),
+        Value::F32(n) => write_float(out, f64::from(n), n),
+        Value::F64(n) => write_float(out, n, n),
+        Value::Char(c) => write_str(out, c.encode_utf8(&mut [0; 4])),
+        Value::String(s) => write_str(out, &s),
+        Value::Unit | Value::Option(None) => out.push_str("null"),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => write_value(out, *inner),
+        Value::Seq(items) => write_seq(out, items),
+        Value::Bytes(bytes) => write_seq(out, bytes.into_iter().map(Value::U8).collect()),
+        Value::Map(map) => write_map(out, map.into_iter().map(|(k, v)| (stringify(&k), v))),
+    }
+}
+
+fn write_seq(out: &mut String, items: Vec<Value>) {
+    out.push('[');
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_value(out, item);
+    }
+    out.push(']');
+}
+
+fn write_display(out: &mut String, n: impl std::fmt::Display) {
+    let _ = write!(out, "{}", n);
+}
+
+/// Writes the float `n`, rendered as `shortest` so that an `f32` keeps its own shortest rendering.
+/// The display form of floats has no exponent nor trailing `.0`.
+fn write_float(out: &mut String, n: f64, shortest: impl std::fmt::Display) {
+    match n {
+        n if n.is_nan() => out.push_str("\"NaN\""),
+        n if n.is_infinite() => out.push_str(if n > 0.0 { "\"Infinity\"" } else { "\"-Infinity\"" }),
+        // Matches -0.0 as well.
+        0.0 => out.push('0'),
+        _ => write_display(out, shortest),
+    }
+}
+
+fn write_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,299 @@
+//! Runtime discovery of the compiled-in features.
+//!
+//! Libraries depending on this crate do not control which of its features the final application
+//! enables. [`capabilities`] reports the formats and integrations compiled in, and
+//! [`Context::dump_to`] and [`Context::load_from`] exist whatever the features, failing with a
+//! [`FeatureDisabled`] error when the format is not compiled in, so that such libraries can
+//! degrade gracefully instead of failing to build. The "full" feature enables every capability
+//! but [`Capability::Ordered`], which changes the default key order.
+//!
+//! ```rust
+//! use cdumay_context::{capabilities, Capability, Context, Contextualize, Format};
+//!
+//! let ctx = Context::new();
+//! match ctx.dump_to(Format::Json, false) {
+//!     Ok(json) => assert_eq!(json, "{}"),
+//!     Err(err) => {
+//!         assert!(!capabilities().contains(&Capability::Json));
+//!         assert_eq!(err.code(), 501);
+//!     }
+//! }
+//! ```
+use crate::{Context, FeatureDisabled, Format};
+
+/// A feature of the crate, see [`capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    /// JSON dumps and loads, feature "json".
+    Json,
+    /// YAML dumps and loads, feature "yaml".
+    Yaml,
+    /// TOML dumps and loads, feature "toml".
+    Toml,
+    /// Regex matchers in comparison reports, feature "regex".
+    Regex,
+    /// Lock-free reads of shared contexts, feature "arc-swap".
+    ArcSwap,
+    /// Parallel bulk operations, feature "rayon".
+    Rayon,
+    /// Context inheritance across Tokio tasks, feature "tokio".
+    Tokio,
+    /// `config` crate integration, feature "config".
+    Config,
+    /// `figment` crate integration, feature "figment".
+    Figment,
+    /// CLI arguments capture, feature "clap".
+    Clap,
+    /// Kubernetes Downward API metadata capture, feature "k8s".
+    K8s,
+    /// AWS Lambda invocation capture, feature "lambda".
+    Lambda,
+    /// Bincode binary snapshots, feature "bincode".
+    Bincode,
+    /// Postcard binary snapshots, feature "postcard".
+    Postcard,
+    /// File-backed crash ring buffers, feature "mmap".
+    Mmap,
+    /// Zero-copy MessagePack deserialization, feature "msgpack".
+    Msgpack,
+    /// SIMD-accelerated JSON parsing, feature "simd-json".
+    SimdJson,
+    /// Key order preserved by `serde_json::Map` conversions, feature "preserve-order".
+    PreserveOrder,
+    /// `#[derive(ContextDump)]`, feature "derive".
+    Derive,
+    /// Query strings and form bodies, feature "urlencoded".
+    Urlencoded,
+    /// `.env` files, feature "dotenv".
+    Dotenv,
+    /// `log` crate structured key-values, feature "log-kv".
+    LogKv,
+    /// Sentry scopes and events, feature "sentry".
+    Sentry,
+    /// FlatBuffers encoding and zero-copy views, feature "flatbuffers".
+    Flatbuffers,
+    /// HTTP header propagation, feature "http".
+    Http,
+    /// System, process and build information providers, feature "system".
+    System,
+    /// Hot reloading of context files, feature "watch".
+    Watch,
+    /// SHA-256 content hashes and HMAC-SHA256 pseudonyms, feature "hash".
+    Hash,
+    /// Compressed dumps, feature "compress".
+    Compress,
+    /// ANSI colored tables, feature "color".
+    Color,
+    /// RON dumps and loads, feature "ron".
+    Ron,
+    /// XML dumps and loads, feature "xml".
+    Xml,
+    /// Test assertions, fixtures and proptest strategies, feature "test-utils".
+    TestUtils,
+    /// Contexts allocated in a caller-provided arena, feature "allocator-api".
+    AllocatorApi,
+    /// Insertion-ordered storage and document-ordered loads, feature "ordered".
+    Ordered,
+}
+
+impl Capability {
+    /// Every capability, compiled in or not.
+    pub const ALL: &'static [Capability] = &[
+        Capability::Json,
+        Capability::Yaml,
+        Capability::Toml,
+        Capability::Regex,
+        Capability::ArcSwap,
+        Capability::Rayon,
+        Capability::Tokio,
+        Capability::Config,
+        Capability::Figment,
+        Capability::Clap,
+        Capability::K8s,
+        Capability::Lambda,
+        Capability::Bincode,
+        Capability::Postcard,
+        Capability::Mmap,
+        Capability::Msgpack,
+        Capability::SimdJson,
+        Capability::PreserveOrder,
+        Capability::Derive,
+        Capability::Urlencoded,
+        Capability::Dotenv,
+        Capability::LogKv,
+        Capability::Sentry,
+        Capability::Flatbuffers,
+        Capability::Http,
+        Capability::System,
+        Capability::Watch,
+        Capability::Hash,
+        Capability::Compress,
+        Capability::Color,
+        Capability::Ron,
+        Capability::Xml,
+        Capability::TestUtils,
+        Capability::AllocatorApi,
+        Capability::Ordered,
+    ];
+
+    /// Returns the name of the cargo feature enabling the capability.
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Capability::Json => "json",
+            Capability::Yaml => "yaml",
+            Capability::Toml => "toml",
+            Capability::Regex => "regex",
+            Capability::ArcSwap => "arc-swap",
+            Capability::Rayon => "rayon",
+            Capability::Tokio => "tokio",
+            Capability::Config => "config",
+            Capability::Figment => "figment",
+            Capability::Clap => "clap",
+            Capability::K8s => "k8s",
+            Capability::Lambda => "lambda",
+            Capability::Bincode => "bincode",
+            Capability::Postcard => "postcard",
+            Capability::Mmap => "mmap",
+            Capability::Msgpack => "msgpack",
+            Capability::SimdJson => "simd-json",
+            Capability::PreserveOrder => "preserve-order",
+            Capability::Derive => "derive",
+            Capability::Urlencoded => "urlencoded",
+            Capability::Dotenv => "dotenv",
+            Capability::LogKv => "log-kv",
+            Capability::Sentry => "sentry",
+            Capability::Flatbuffers => "flatbuffers",
+            Capability::Http => "http",
+            Capability::System => "system",
+            Capability::Watch => "watch",
+            Capability::Hash => "hash",
+            Capability::Compress => "compress",
+            Capability::Color => "color",
+            Capability::Ron => "ron",
+            Capability::Xml => "xml",
+            Capability::TestUtils => "test-utils",
+            Capability::AllocatorApi => "allocator-api",
+            Capability::Ordered => "ordered",
+        }
+    }
+
+    /// Returns `true` if the capability is compiled in.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            Capability::Json => cfg!(feature = "json"),
+            Capability::Yaml => cfg!(feature = "yaml"),
+            Capability::Toml => cfg!(feature = "toml"),
+            Capability::Regex => cfg!(feature = "regex"),
+            Capability::ArcSwap => cfg!(feature = "arc-swap"),
+            Capability::Rayon => cfg!(feature = "rayon"),
+            Capability::Tokio => cfg!(feature = "tokio"),
+            Capability::Config => cfg!(feature = "config"),
+            Capability::Figment => cfg!(feature = "figment"),
+            Capability::Clap => cfg!(feature = "clap"),
+            Capability::K8s => cfg!(feature = "k8s"),
+            Capability::Lambda => cfg!(feature = "lambda"),
+            Capability::Bincode => cfg!(feature = "bincode"),
+            Capability::Postcard => cfg!(feature = "postcard"),
+            Capability::Mmap => cfg!(feature = "mmap"),
+            Capability::Msgpack => cfg!(feature = "msgpack"),
+            Capability::SimdJson => cfg!(feature = "simd-json"),
+            Capability::PreserveOrder => cfg!(feature = "preserve-order"),
+            Capability::Derive => cfg!(feature = "derive"),
+            Capability::Urlencoded => cfg!(feature = "urlencoded"),
+            Capability::Dotenv => cfg!(feature = "dotenv"),
+            Capability::LogKv => cfg!(feature = "log-kv"),
+            Capability::Sentry => cfg!(feature = "sentry"),
+            Capability::Flatbuffers => cfg!(feature = "flatbuffers"),
+            Capability::Http => cfg!(feature = "http"),
+            Capability::System => cfg!(feature = "system"),
+            Capability::Watch => cfg!(feature = "watch"),
+            Capability::Hash => cfg!(feature = "hash"),
+            Capability::Compress => cfg!(feature = "compress"),
+            Capability::Color => cfg!(feature = "color"),
+            Capability::Ron => cfg!(feature = "ron"),
+            Capability::Xml => cfg!(feature = "xml"),
+            Capability::TestUtils => cfg!(feature = "test-utils"),
+            Capability::AllocatorApi => cfg!(feature = "allocator-api"),
+            Capability::Ordered => cfg!(feature = "ordered"),
+        }
+    }
+
+    /// Fails if the capability is not compiled in.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the capability is compiled in
+    /// * `Err(e)` containing a [`FeatureDisabled`] error naming the cargo feature to enable
+    pub fn require(&self) -> cdumay_core::Result<()> {
+        match self.is_enabled() {
+            true => Ok(()),
+            false => Err(FeatureDisabled::new()
+                .with_message(format!("Feature '{}' of cdumay_context is not enabled", self.feature()))
+                .into()),
+        }
+    }
+}
+
+impl From<Format> for Capability {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Json => Capability::Json,
+            Format::Toml => Capability::Toml,
+            Format::Yaml => Capability::Yaml,
+        }
+    }
+}
+
+/// Returns the capabilities compiled in.
+pub fn capabilities() -> Vec<Capability> {
+    Capability::ALL.iter().copied().filter(Capability::is_enabled).collect()
+}
+
+impl Context {
+    /// Serializes the context to `format`, `pretty` being ignored by YAML.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(dump)` containing the dump on success
+    /// * `Err(e)` containing a [`FeatureDisabled`] error if `format` is not compiled in, or the
+    ///   error of the dump
+    #[allow(unused_variables)]
+    pub fn dump_to(&self, format: Format, pretty: bool) -> cdumay_core::Result<String> {
+        Capability::from(format).require()?;
+        match format {
+            #[cfg(feature = "json")]
+            Format::Json => crate::Contextualize::to_json(self, pretty),
+            #[cfg(feature = "toml")]
+            Format::Toml => crate::Contextualize::to_toml(self, pretty),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => crate::Contextualize::to_yaml(self),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("format checked by require"),
+        }
+    }
+
+    /// Deserializes a context from `data` in `format`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the context on success
+    /// * `Err(e)` containing a [`FeatureDisabled`] error if `format` is not compiled in, or the
+    ///   error of the load
+    #[allow(unused_variables)]
+    pub fn load_from(format: Format, data: &str) -> cdumay_core::Result<Context> {
+        Capability::from(format).require()?;
+        match format {
+            #[cfg(feature = "json")]
+            Format::Json => <Context as crate::Contextualize>::from_json(data),
+            #[cfg(feature = "toml")]
+            Format::Toml => <Context as crate::Contextualize>::from_toml(data),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => <Context as crate::Contextualize>::from_yaml(data),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("format checked by require"),
+        }
+    }
+}
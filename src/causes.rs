@@ -0,0 +1,84 @@
+//! Error cause chains.
+//!
+//! [`Context::record_causes`] walks the [`source`](std::error::Error::source) chain of an error
+//! and records it under the `causes` entry of the `error` key, so that root causes survive the
+//! conversion into this crate's error types. Each cause is a map with its position in the chain
+//! (`index`, the error itself being `0`), its `type` name and its `message`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, UnExpectedError};
+//!
+//! let io = std::io::Error::other("disk full");
+//! let mut ctx = Context::new();
+//! ctx.record_causes(&io);
+//!
+//! let err: cdumay_core::Error = UnExpectedError::new().with_context(&ctx).into();
+//! let causes = err.details().get("error").cloned();
+//! assert!(causes.is_some());
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+const ERROR_KEY: &str = crate::keys::ERROR;
+const CAUSES_KEY: &str = "causes";
+
+impl Context {
+    /// Records the cause chain of `err` into `error.causes` and returns the number of causes.
+    ///
+    /// Other entries of the `error` key are preserved, a previous chain is replaced.
+    ///
+    /// The type of `err` is known statically, the types of its sources are taken from their
+    /// `Debug` output, which starts with the type name for derived implementations.
+    pub fn record_causes<E: Error + ?Sized + 'static>(&mut self, err: &E) -> usize {
+        let mut details = BTreeMap::new();
+        if let Some(entry) = self.get(ERROR_KEY) {
+            details.insert(ERROR_KEY.to_string(), entry.clone());
+        }
+        let count = record_causes_into(&mut details, err);
+        if let Some(entry) = details.remove(ERROR_KEY) {
+            self.insert(ERROR_KEY.to_string(), entry);
+        }
+        count
+    }
+}
+
+/// Records the cause chain of `err` into `error.causes` of an error details map.
+#[doc(hidden)]
+pub fn record_causes_into<E: Error + ?Sized + 'static>(details: &mut BTreeMap<String, Value>, err: &E) -> usize {
+    let name = std::any::type_name::<E>();
+    let name = if name.contains("dyn ") { debug_type_name(err) } else { name.to_string() };
+    let mut causes = vec![cause(0, name, err.to_string())];
+    let mut source = err.source();
+    while let Some(current) = source {
+        causes.push(cause(causes.len(), debug_type_name(current), current.to_string()));
+        source = current.source();
+    }
+    let count = causes.len();
+    let mut entry = match details.remove(ERROR_KEY) {
+        Some(Value::Map(map)) => map,
+        _ => BTreeMap::new(),
+    };
+    entry.insert(Value::String(CAUSES_KEY.to_string()), Value::Seq(causes));
+    details.insert(ERROR_KEY.to_string(), Value::Map(entry));
+    count
+}
+
+fn cause(index: usize, type_name: String, message: String) -> Value {
+    Value::Map(BTreeMap::from([
+        (Value::String("index".to_string()), Value::U64(index as u64)),
+        (Value::String("type".to_string()), Value::String(type_name)),
+        (Value::String("message".to_string()), Value::String(message)),
+    ]))
+}
+
+fn debug_type_name<E: Error + ?Sized>(err: &E) -> String {
+    let debug = format!("{err:?}");
+    let name: String = debug.chars().take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':').collect();
+    if name.is_empty() {
+        "unknown".to_string()
+    } else {
+        name
+    }
+}
@@ -0,0 +1,91 @@
+//! Capture of command-line arguments parsed by [clap](https://docs.rs/clap).
+//!
+//! [`Context::from_arg_matches`] records the effective invocation of a CLI tool, one key per
+//! argument, so that error reports show what was actually requested. Arguments whose id looks
+//! sensitive (see [`SENSITIVE_ARG_PATTERNS`]) are skipped, so secrets passed as flags never end
+//! up in a report.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use clap::{Arg, Command};
+//!
+//! let matches = Command::new("deploy")
+//!     .arg(Arg::new("env").long("env"))
+//!     .arg(Arg::new("api-token").long("api-token"))
+//!     .get_matches_from(["deploy", "--env", "prod", "--api-token", "s3cr3t"]);
+//!
+//! let ctx = Context::from_arg_matches(&matches);
+//! assert!(ctx.get("env").is_some());
+//! assert!(ctx.get("api-token").is_none());
+//! ```
+use crate::snapshot::glob_match;
+use crate::{Context, Contextualize};
+use clap::ArgMatches;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Argument id patterns skipped by [`Context::from_arg_matches`].
+///
+/// Patterns are matched against the lowercased id where `-` is replaced by `_`, and may contain
+/// `*` wildcards.
+pub const SENSITIVE_ARG_PATTERNS: &[&str] = &[
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*token*",
+    "*api_key*",
+    "*apikey*",
+    "*credential*",
+    "*private_key*",
+];
+
+impl Context {
+    /// Creates a context from parsed arguments, skipping those matching [`SENSITIVE_ARG_PATTERNS`].
+    ///
+    /// Each argument explicitly set or defaulted is stored under its id, as a string or as a
+    /// sequence of strings if it has several values. The subcommand, if any, is stored under the
+    /// `subcommand` key as a map with its `name` and its `args`.
+    pub fn from_arg_matches(matches: &ArgMatches) -> Context {
+        Self::from_arg_matches_with(matches, SENSITIVE_ARG_PATTERNS)
+    }
+
+    /// Creates a context from parsed arguments, skipping those matching `sensitive` patterns.
+    pub fn from_arg_matches_with(matches: &ArgMatches, sensitive: &[&str]) -> Context {
+        let mut ctx = Context::new();
+        ctx.extend(capture(matches, sensitive));
+        ctx
+    }
+}
+
+fn capture(matches: &ArgMatches, sensitive: &[&str]) -> BTreeMap<String, Value> {
+    let mut data = BTreeMap::new();
+    for id in matches.ids() {
+        let id = id.as_str();
+        if is_sensitive(id, sensitive) || matches.value_source(id).is_none() {
+            continue;
+        }
+        let Ok(Some(raw)) = matches.try_get_raw(id) else {
+            continue;
+        };
+        let mut values: Vec<Value> = raw.map(|v| Value::String(v.to_string_lossy().into_owned())).collect();
+        let value = match values.len() {
+            1 => values.remove(0),
+            _ => Value::Seq(values),
+        };
+        data.insert(id.to_string(), value);
+    }
+    if let Some((name, sub)) = matches.subcommand() {
+        let args = capture(sub, sensitive).into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+        let subcommand = BTreeMap::from([
+            (Value::String("name".to_string()), Value::String(name.to_string())),
+            (Value::String("args".to_string()), Value::Map(args)),
+        ]);
+        data.insert("subcommand".to_string(), Value::Map(subcommand));
+    }
+    data
+}
+
+fn is_sensitive(id: &str, patterns: &[&str]) -> bool {
+    let id = id.to_lowercase().replace('-', "_");
+    patterns.iter().any(|pattern| glob_match(pattern, &id))
+}
@@ -0,0 +1,322 @@
+//! Context comparison for regression testing.
+//!
+//! This module provides [`MatchOptions`] and [`MatchReport`], used by [`Context::compare`] and
+//! [`Context::assert_matches`] to check a context against an expected one while tolerating
+//! volatile keys, free-form values and small numeric drifts. With [`MatchOptions::subset`], only
+//! the expected paths are checked, as `assert_context!` does.
+
+use crate::{Context, Contextualize, ValueExt};
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Options controlling how two contexts are compared.
+///
+/// Paths are dotted keys (`http.status`), sequence items are addressed by their index
+/// (`warnings.0`).
+///
+/// # Example
+///
+/// ```rust
+/// use cdumay_context::{Context, Contextualize, MatchOptions};
+///
+/// let options = MatchOptions::new().ignore("timestamp").with_tolerance("duration", 0.5);
+/// let mut actual = Context::new();
+/// actual.insert("duration".to_string(), serde_value::Value::F64(1.2));
+/// actual.insert("timestamp".to_string(), serde_value::Value::U64(1700000000));
+/// let mut expected = Context::new();
+/// expected.insert("duration".to_string(), serde_value::Value::F64(1.0));
+///
+/// actual.assert_matches(&expected, &options);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MatchOptions {
+    subset: bool,
+    ignored: BTreeSet<String>,
+    tolerance: f64,
+    tolerances: BTreeMap<String, f64>,
+    #[cfg(feature = "regex")]
+    patterns: BTreeMap<String, regex::Regex>,
+}
+
+impl MatchOptions {
+    /// Creates options performing an exact comparison.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only checks the paths of the expected context: the keys of the actual context, and of its
+    /// nested maps, which are not expected are not reported. Sequences must still have the
+    /// expected length.
+    pub fn subset(mut self) -> Self {
+        self.subset = true;
+        self
+    }
+
+    /// Skips the given path (and everything below it) on both sides.
+    pub fn ignore(mut self, path: &str) -> Self {
+        self.ignored.insert(path.to_string());
+        self
+    }
+
+    /// Sets the absolute tolerance applied to every numeric value.
+    pub fn with_default_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the absolute tolerance applied to the numeric value at `path`.
+    pub fn with_tolerance(mut self, path: &str, tolerance: f64) -> Self {
+        self.tolerances.insert(path.to_string(), tolerance);
+        self
+    }
+
+    /// Requires the value at `path` to be a string matching `pattern`, whatever the expected value is.
+    ///
+    /// This method is only available when the "regex" feature is enabled.
+    #[cfg(feature = "regex")]
+    pub fn with_pattern(mut self, path: &str, pattern: regex::Regex) -> Self {
+        self.patterns.insert(path.to_string(), pattern);
+        self
+    }
+
+    fn is_ignored(&self, path: &str) -> bool {
+        self.ignored.contains(path)
+    }
+
+    fn tolerance_for(&self, path: &str) -> f64 {
+        self.tolerances.get(path).copied().unwrap_or(self.tolerance)
+    }
+}
+
+/// The reason why a path did not match.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// The path is expected but absent from the actual context.
+    Missing { expected: Value },
+    /// The path is present in the actual context but not expected.
+    Unexpected { actual: Value },
+    /// Both values exist but differ.
+    ValueDiffers { expected: Value, actual: Value },
+    /// Both values are numbers whose difference exceeds the allowed tolerance.
+    ToleranceExceeded { expected: Value, actual: Value, tolerance: f64 },
+    /// The actual value does not match the configured pattern.
+    PatternMismatch { pattern: String, actual: Value },
+}
+
+/// A single difference found while comparing two contexts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    /// Dotted path of the mismatching value.
+    pub path: String,
+    /// What went wrong at this path.
+    #[serde(flatten)]
+    pub kind: MismatchKind,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MismatchKind::Missing { expected } => write!(f, "{}: missing, expected {:?}", self.path, expected),
+            MismatchKind::Unexpected { actual } => write!(f, "{}: unexpected value {:?}", self.path, actual),
+            MismatchKind::ValueDiffers { expected, actual } => write!(f, "{}: expected {:?}, got {:?}", self.path, expected, actual),
+            MismatchKind::ToleranceExceeded { expected, actual, tolerance } => {
+                write!(f, "{}: expected {:?} (±{}), got {:?}", self.path, expected, tolerance, actual)
+            }
+            MismatchKind::PatternMismatch { pattern, actual } => write!(f, "{}: {:?} does not match /{}/", self.path, actual, pattern),
+        }
+    }
+}
+
+/// The structured result of a context comparison.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MatchReport {
+    mismatches: Vec<Mismatch>,
+}
+
+impl MatchReport {
+    /// Returns `true` if no mismatch was found.
+    pub fn is_match(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Returns the mismatches, ordered by path.
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+}
+
+impl fmt::Display for MatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_match() {
+            true => write!(f, "contexts match"),
+            false => {
+                write!(f, "{} mismatch(es):", self.mismatches.len())?;
+                for mismatch in &self.mismatches {
+                    write!(f, "\n  - {}", mismatch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Context {
+    /// Compares this context against `expected` and reports every difference.
+    ///
+    /// Values are compared by kind rather than by representation: integers of any width are equal
+    /// if their value is, `Some` and newtypes equal their value, a character equals a
+    /// one-character string and `None` equals the unit.
+    pub fn compare(&self, expected: &Context, options: &MatchOptions) -> MatchReport {
+        let mut report = MatchReport::default();
+        let actual = self.inner().into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+        let expected = expected.inner().into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+        compare_maps("", &actual, &expected, options, &mut report);
+        report
+    }
+
+    /// Asserts that this context matches `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the formatted [`MatchReport`] if any mismatch is found.
+    #[track_caller]
+    pub fn assert_matches(&self, expected: &Context, options: &MatchOptions) {
+        let report = self.compare(expected, options);
+        if !report.is_match() {
+            panic!("context does not match expected context: {}", report);
+        }
+    }
+}
+
+fn join(prefix: &str, key: &Value) -> String {
+    let key = match key {
+        Value::String(key) => key.clone(),
+        other => format!("{:?}", other),
+    };
+    match prefix.is_empty() {
+        true => key,
+        false => format!("{}.{}", prefix, key),
+    }
+}
+
+fn compare_maps(prefix: &str, actual: &BTreeMap<Value, Value>, expected: &BTreeMap<Value, Value>, options: &MatchOptions, report: &mut MatchReport) {
+    let keys: BTreeSet<&Value> = actual.keys().chain(expected.keys()).collect();
+    for key in keys {
+        let path = join(prefix, key);
+        compare_values(&path, actual.get(key), expected.get(key), options, report);
+    }
+}
+
+fn compare_values(path: &str, actual: Option<&Value>, expected: Option<&Value>, options: &MatchOptions, report: &mut MatchReport) {
+    if options.is_ignored(path) {
+        return;
+    }
+    #[cfg(feature = "regex")]
+    if let Some(pattern) = options.patterns.get(path) {
+        let matched = matches!(actual, Some(Value::String(value)) if pattern.is_match(value));
+        if !matched {
+            report.mismatches.push(Mismatch {
+                path: path.to_string(),
+                kind: MismatchKind::PatternMismatch {
+                    pattern: pattern.to_string(),
+                    actual: actual.cloned().unwrap_or(Value::Unit),
+                },
+            });
+        }
+        return;
+    }
+    let kind = match (actual.map(unwrap), expected.map(unwrap)) {
+        (None, None) => None,
+        (None, Some(expected)) => Some(MismatchKind::Missing { expected: expected.clone() }),
+        (Some(_), None) if options.subset => None,
+        (Some(actual), None) => Some(MismatchKind::Unexpected { actual: actual.clone() }),
+        (Some(Value::Map(actual)), Some(Value::Map(expected))) => return compare_maps(path, actual, expected, options, report),
+        (Some(Value::Seq(actual)), Some(Value::Seq(expected))) => {
+            for index in 0..actual.len().max(expected.len()) {
+                let path = format!("{}.{}", path, index);
+                match (actual.get(index), expected.get(index)) {
+                    (Some(actual), None) if !options.is_ignored(&path) => report.mismatches.push(Mismatch {
+                        path,
+                        kind: MismatchKind::Unexpected { actual: actual.clone() },
+                    }),
+                    (actual, expected) => compare_values(&path, actual, expected, options, report),
+                }
+            }
+            None
+        }
+        (Some(actual), Some(expected)) => match (actual.as_f64(), expected.as_f64()) {
+            (Some(_), Some(_)) if options.tolerance_for(path) == 0.0 && as_integer(actual).is_some() && as_integer(expected).is_some() => {
+                match as_integer(actual) == as_integer(expected) {
+                    true => None,
+                    false => Some(MismatchKind::ValueDiffers {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    }),
+                }
+            }
+            (Some(a), Some(e)) => {
+                let tolerance = options.tolerance_for(path);
+                match (a - e).abs() <= tolerance {
+                    true => None,
+                    false if tolerance == 0.0 => Some(MismatchKind::ValueDiffers {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    }),
+                    false => Some(MismatchKind::ToleranceExceeded {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                        tolerance,
+                    }),
+                }
+            }
+            _ => match same_scalar(actual, expected) {
+                true => None,
+                false => Some(MismatchKind::ValueDiffers {
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                }),
+            },
+        },
+    };
+    if let Some(kind) = kind {
+        report.mismatches.push(Mismatch {
+            path: path.to_string(),
+            kind,
+        });
+    }
+}
+
+/// Looks through `Some` and newtypes, which carry no meaning for the comparison.
+fn unwrap(value: &Value) -> &Value {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => unwrap(inner),
+        other => other,
+    }
+}
+
+/// Compares non-numeric values, a character being equal to a one-character string and `None` to
+/// the unit.
+fn same_scalar(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Char(c), Value::String(s)) | (Value::String(s), Value::Char(c)) => s.chars().eq([*c]),
+        (Value::Unit | Value::Option(None), Value::Unit | Value::Option(None)) => true,
+        (actual, expected) => actual == expected,
+    }
+}
+
+fn as_integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::U8(v) => Some(*v as i128),
+        Value::U16(v) => Some(*v as i128),
+        Value::U32(v) => Some(*v as i128),
+        Value::U64(v) => Some(*v as i128),
+        Value::I8(v) => Some(*v as i128),
+        Value::I16(v) => Some(*v as i128),
+        Value::I32(v) => Some(*v as i128),
+        Value::I64(v) => Some(*v as i128),
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+//! Compressed dumps.
+//!
+//! Contexts carrying request or response bodies can weigh hundreds of kilobytes once dumped,
+//! more than headers, queue messages or blob metadata accept. [`Context::compress`] dumps a
+//! context to a format and compresses the dump with a [`Codec`], and [`Context::decompress`]
+//! reverses it. [`Context::to_json_gz`] and [`Context::from_json_gz`] are shortcuts for gzipped
+//! JSON.
+//!
+//! Decompression fails with a [`SizeLimitExceeded`] error once the dump would exceed
+//! [`DECOMPRESSION_LIMIT`] bytes, so that a small malicious payload cannot expand to exhaust the
+//! memory.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{Codec, Context, Contextualize, Format};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("body".to_string(), Value::String("{\"items\":[]}".repeat(1000)));
+//!
+//! let bytes = ctx.to_json_gz(6).unwrap();
+//! assert!(bytes.len() < 1000);
+//! assert_eq!(Context::from_json_gz(&bytes).unwrap().inner(), ctx.inner());
+//!
+//! let bytes = ctx.compress(Format::Json, Codec::Zlib, 9).unwrap();
+//! assert_eq!(Context::decompress(Format::Json, Codec::Zlib, &bytes).unwrap().inner(), ctx.inner());
+//! # }
+//! ```
+use crate::{Context, Format, SizeLimitExceeded, TypeMismatch, UnExpectedError};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Maximum size, in bytes, of a decompressed dump.
+pub const DECOMPRESSION_LIMIT: usize = 64 * 1024 * 1024;
+
+/// A compression codec, see [`Context::compress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// Gzip (RFC 1952), as used by `Content-Encoding: gzip`.
+    Gzip,
+    /// Zlib (RFC 1950).
+    Zlib,
+    /// Raw deflate (RFC 1951), without header nor checksum.
+    Deflate,
+}
+
+impl Codec {
+    /// Returns the name of the codec, as used by `Content-Encoding` headers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Codec::Gzip => "gzip",
+            Codec::Zlib => "zlib",
+            Codec::Deflate => "deflate",
+        }
+    }
+
+    fn encode(&self, data: &[u8], level: u32) -> std::io::Result<Vec<u8>> {
+        let level = Compression::new(level.min(9));
+        match self {
+            Codec::Gzip => write_all(GzEncoder::new(Vec::new(), level), data)?.finish(),
+            Codec::Zlib => write_all(ZlibEncoder::new(Vec::new(), level), data)?.finish(),
+            Codec::Deflate => write_all(DeflateEncoder::new(Vec::new(), level), data)?.finish(),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> cdumay_core::Result<String> {
+        let reader: Box<dyn Read + '_> = match self {
+            Codec::Gzip => Box::new(GzDecoder::new(bytes)),
+            Codec::Zlib => Box::new(ZlibDecoder::new(bytes)),
+            Codec::Deflate => Box::new(DeflateDecoder::new(bytes)),
+        };
+        let mut data = Vec::new();
+        reader
+            .take(DECOMPRESSION_LIMIT as u64 + 1)
+            .read_to_end(&mut data)
+            .map_err(|err| self.decode_error(err.to_string()))?;
+        if data.len() > DECOMPRESSION_LIMIT {
+            return Err(SizeLimitExceeded::new()
+                .with_message(format!("Decompressed {} context exceeds {} bytes", self.name(), DECOMPRESSION_LIMIT))
+                .into());
+        }
+        String::from_utf8(data).map_err(|err| self.decode_error(err.to_string()))
+    }
+
+    fn decode_error(&self, message: String) -> cdumay_core::Error {
+        TypeMismatch::new()
+            .with_message(format!("Failed to decompress {} context: {}", self.name(), message))
+            .into()
+    }
+}
+
+fn write_all<W: Write>(mut encoder: W, data: &[u8]) -> std::io::Result<W> {
+    encoder.write_all(data)?;
+    Ok(encoder)
+}
+
+impl Context {
+    /// Dumps the context to `format`, compact, and compresses the dump with `codec` at `level`,
+    /// from `0` (no compression) to `9` (best compression), higher levels being capped to `9`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Vec<u8>>` which is:
+    /// * `Ok(bytes)` containing the compressed dump on success
+    /// * `Err(e)` containing a [`FeatureDisabled`](crate::FeatureDisabled) error if `format` is
+    ///   not compiled in, the error of the dump, or an [`UnExpectedError`] if the compression
+    ///   failed
+    pub fn compress(&self, format: Format, codec: Codec, level: u32) -> cdumay_core::Result<Vec<u8>> {
+        let dump = self.dump_to(format, false)?;
+        codec.encode(dump.as_bytes(), level).map_err(|err| {
+            UnExpectedError::new()
+                .with_message(format!("Failed to compress context with {}: {}", codec.name(), err))
+                .into()
+        })
+    }
+
+    /// Creates a new context from bytes produced by [`Context::compress`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the decompressed context on success
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the bytes are not valid for `codec`, a
+    ///   [`SizeLimitExceeded`] error if the dump exceeds [`DECOMPRESSION_LIMIT`], or the error of
+    ///   [`Context::load_from`]
+    pub fn decompress(format: Format, codec: Codec, bytes: &[u8]) -> cdumay_core::Result<Context> {
+        Context::load_from(format, &codec.decode(bytes)?)
+    }
+
+    /// Dumps the context to JSON and gzips it at `level`, see [`Context::compress`].
+    #[cfg(feature = "json")]
+    pub fn to_json_gz(&self, level: u32) -> cdumay_core::Result<Vec<u8>> {
+        self.compress(Format::Json, Codec::Gzip, level)
+    }
+
+    /// Creates a new context from gzipped JSON, see [`Context::decompress`].
+    #[cfg(feature = "json")]
+    pub fn from_json_gz(bytes: &[u8]) -> cdumay_core::Result<Context> {
+        Context::decompress(Format::Json, Codec::Gzip, bytes)
+    }
+}
@@ -0,0 +1,77 @@
+//! Conditional inserts.
+//!
+//! Enrichment code often inserts a value only if some condition holds. [`Context::insert_if`]
+//! takes the condition as a boolean and [`Context::insert_when`] as a predicate on the context,
+//! the value being computed only if the predicate holds. On a [`SharedContext`], the predicate,
+//! the computation and the insert run atomically with respect to other writers, so that the
+//! insert can depend on the current state of the context.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SharedContext};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! let retried = false;
+//! ctx.insert_if(retried, "retry".to_string(), Value::Bool(true));
+//! assert_eq!(ctx.get("retry"), None);
+//!
+//! let shared = SharedContext::new(ctx);
+//! let first = shared.insert_when(|ctx| ctx.get("owner").is_none(), "owner".to_string(), || Value::String("worker-1".to_string()));
+//! let second = shared.insert_when(|ctx| ctx.get("owner").is_none(), "owner".to_string(), || Value::String("worker-2".to_string()));
+//! assert!(first && !second);
+//! assert_eq!(shared.get("owner"), Some(Value::String("worker-1".to_string())));
+//! ```
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+
+impl Context {
+    /// Inserts a key-value pair if `condition` holds, see [`Contextualize::insert`].
+    ///
+    /// Returns `condition`.
+    pub fn insert_if(&mut self, condition: bool, k: String, v: Value) -> bool {
+        if condition {
+            self.insert(k, v);
+        }
+        condition
+    }
+
+    /// Inserts the value computed by `value` if `predicate` holds for the context, see
+    /// [`Contextualize::insert`]. The value is not computed otherwise.
+    ///
+    /// Returns whether `predicate` held.
+    pub fn insert_when<P, F>(&mut self, predicate: P, k: String, value: F) -> bool
+    where
+        P: FnOnce(&Context) -> bool,
+        F: FnOnce() -> Value,
+    {
+        let condition = predicate(self);
+        if condition {
+            self.insert(k, value());
+        }
+        condition
+    }
+}
+
+impl SharedContext {
+    /// Inserts a key-value pair into the shared context if `condition` holds.
+    ///
+    /// Returns `condition`.
+    pub fn insert_if(&self, condition: bool, k: String, v: Value) -> bool {
+        if condition {
+            self.insert(k, v);
+        }
+        condition
+    }
+
+    /// Inserts the value computed by `value` into the shared context if `predicate` holds for
+    /// it, atomically with respect to other writers.
+    ///
+    /// Returns whether `predicate` held.
+    pub fn insert_when<P, F>(&self, predicate: P, k: String, value: F) -> bool
+    where
+        P: FnOnce(&Context) -> bool,
+        F: FnOnce() -> Value,
+    {
+        self.update(|ctx| ctx.insert_when(predicate, k, value))
+    }
+}
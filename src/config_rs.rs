@@ -0,0 +1,116 @@
+//! Conversions between contexts and [config-rs](https://docs.rs/config) configurations.
+//!
+//! A resolved `config::Config` can be lifted into a [`Context`] with [`From`], so that services
+//! already using config-rs can attach their configuration to errors without copying keys by
+//! hand. The opposite conversion only exports the entries visible at
+//! [`Sensitivity::Internal`], so confidential and secret values never end up in a
+//! configuration built from a context.
+//!
+//! Note that `config::Config::try_from` is an inherent method of config-rs serializing any
+//! value, which takes precedence over [`TryFrom`]: use `try_into` instead.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let config = config::Config::builder()
+//!     .set_default("database.port", 5432).unwrap()
+//!     .set_default("debug", true).unwrap()
+//!     .build()
+//!     .unwrap();
+//!
+//! let mut ctx = Context::from(config);
+//! assert_eq!(ctx.get("debug"), Some(&Value::Bool(true)));
+//!
+//! ctx.insert("password".to_string(), Value::String("hunter2".to_string()));
+//! ctx.set_sensitivity("password", Sensitivity::Secret);
+//! let config: config::Config = (&ctx).try_into().unwrap();
+//! assert_eq!(config.get_int("database.port").unwrap(), 5432);
+//! assert!(config.get_string("password").is_err());
+//! ```
+use crate::{Context, ContextDump, Contextualize, Sensitivity, TypeMismatch};
+use ::config::{ConfigError, Map, Source, ValueKind};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl From<::config::Config> for Context {
+    /// Creates a context holding every top-level entry of the configuration.
+    fn from(config: ::config::Config) -> Self {
+        let mut ctx = Context::new();
+        ctx.extend(config.try_deserialize::<BTreeMap<String, Value>>().unwrap_or_default());
+        ctx
+    }
+}
+
+impl TryFrom<&Context> for ::config::Config {
+    type Error = cdumay_core::Error;
+
+    /// Builds a configuration from the entries of the context visible at [`Sensitivity::Internal`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<config::Config>` which is:
+    /// * `Ok(config::Config)` - The configuration
+    /// * `Err(e)` - A `TypeMismatch` error if a value cannot be represented in a configuration
+    fn try_from(ctx: &Context) -> Result<Self, Self::Error> {
+        let view = ctx.view(Sensitivity::Internal);
+        let mismatch = |message: String| -> cdumay_core::Error { TypeMismatch::new().with_message(message).with_details(view.dump()).into() };
+        let mut table = Map::new();
+        for (key, value) in view.dump() {
+            let value = to_config_value(&value).map_err(|err| mismatch(format!("Cannot convert '{}' into a configuration value: {}", key, err)))?;
+            table.insert(key, value);
+        }
+        ::config::Config::builder()
+            .add_source(ContextSource(table))
+            .build()
+            .map_err(|err| mismatch(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ContextSource(Map<String, ::config::Value>);
+
+impl Source for ContextSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, ::config::Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+fn to_config_value(value: &Value) -> Result<::config::Value, String> {
+    let kind = match value {
+        Value::Unit | Value::Option(None) => ValueKind::Nil,
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return to_config_value(inner),
+        Value::Bool(v) => ValueKind::Boolean(*v),
+        Value::U8(v) => ValueKind::U64(u64::from(*v)),
+        Value::U16(v) => ValueKind::U64(u64::from(*v)),
+        Value::U32(v) => ValueKind::U64(u64::from(*v)),
+        Value::U64(v) => ValueKind::U64(*v),
+        Value::I8(v) => ValueKind::I64(i64::from(*v)),
+        Value::I16(v) => ValueKind::I64(i64::from(*v)),
+        Value::I32(v) => ValueKind::I64(i64::from(*v)),
+        Value::I64(v) => ValueKind::I64(*v),
+        Value::F32(v) => ValueKind::Float(f64::from(*v)),
+        Value::F64(v) => ValueKind::Float(*v),
+        Value::Char(c) => ValueKind::String(c.to_string()),
+        Value::String(s) => ValueKind::String(s.clone()),
+        Value::Bytes(bytes) => ValueKind::Array(bytes.iter().map(|b| ::config::Value::new(None, u64::from(*b))).collect()),
+        Value::Seq(items) => ValueKind::Array(items.iter().map(to_config_value).collect::<Result<_, _>>()?),
+        Value::Map(map) => {
+            let mut table = Map::new();
+            for (key, value) in map {
+                let key = match key {
+                    Value::String(s) => s.clone(),
+                    Value::Char(c) => c.to_string(),
+                    other => return Err(format!("unsupported map key {:?}", other)),
+                };
+                table.insert(key, to_config_value(value)?);
+            }
+            ValueKind::Table(table)
+        }
+    };
+    Ok(::config::Value::new(None, kind))
+}
@@ -0,0 +1,199 @@
+//! Round-trip guarantees of dumps.
+//!
+//! Dumps fail with typed errors, naming the path of the offending value, instead of the errors
+//! of the underlying serializers, and [`Context::check_serializable`] runs the same checks without
+//! writing the dump. Its documentation lists what survives a round-trip through each format.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Format};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("parent_id".to_string(), Value::Option(None));
+//! assert!(ctx.check_serializable(Format::Json).is_ok());
+//! assert!(ctx.check_serializable(Format::Toml).unwrap_err().message().contains("'parent_id'"));
+//! ```
+//!
+//! [`conformance_vectors`] returns the canonical test vectors of the wire formats: contexts along
+//! with their expected serialization, byte for byte, per format and [`CONFORMANCE_VERSION`]. The
+//! expected serializations are also shipped as golden files under the `conformance` directory of
+//! the crate, see [`ConformanceVector::file_name`], so that implementations in other languages
+//! can check that they load and dump contexts the same way.
+//!
+//! ```rust
+//! use cdumay_context::{capabilities, conformance_vectors, Capability, Context, Contextualize};
+//!
+//! for vector in conformance_vectors() {
+//!     if capabilities().contains(&Capability::from(vector.format)) {
+//!         let mut ctx = Context::new();
+//!         ctx.extend(vector.input.clone());
+//!         assert_eq!(ctx.dump_to(vector.format, vector.pretty).unwrap(), vector.expected, "{}", vector.file_name());
+//!     }
+//! }
+//! ```
+use crate::{Context, Format, ValidationError};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Version of the wire formats described by [`conformance_vectors`].
+pub const CONFORMANCE_VERSION: u32 = 1;
+
+/// A context and its expected serialization, see [`conformance_vectors`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceVector {
+    /// Name of the vector, shared by its serializations in every format.
+    pub name: &'static str,
+    /// Version of the wire format.
+    pub version: u32,
+    /// Format of the serialization.
+    pub format: Format,
+    /// Whether the serialization is pretty-printed, only meaningful for JSON.
+    pub pretty: bool,
+    /// Entries of the context.
+    pub input: BTreeMap<String, Value>,
+    /// Expected serialization of the context.
+    pub expected: &'static str,
+}
+
+impl ConformanceVector {
+    /// Returns the path of the golden file holding [`ConformanceVector::expected`], relative to
+    /// the `conformance` directory of the crate, such as `v1/nested.pretty.json`.
+    pub fn file_name(&self) -> String {
+        let extension = match (self.format, self.pretty) {
+            (Format::Json, false) => "json",
+            (Format::Json, true) => "pretty.json",
+            (Format::Toml, _) => "toml",
+            (Format::Yaml, _) => "yaml",
+        };
+        format!("v{}/{}.{}", self.version, self.name, extension)
+    }
+}
+
+/// Expected serializations of a vector: compact JSON, pretty JSON, TOML and YAML.
+macro_rules! golden {
+    ($name:literal) => {
+        [
+            (Format::Json, false, include_str!(concat!("../conformance/v1/", $name, ".json"))),
+            (Format::Json, true, include_str!(concat!("../conformance/v1/", $name, ".pretty.json"))),
+            (Format::Toml, false, include_str!(concat!("../conformance/v1/", $name, ".toml"))),
+            (Format::Yaml, false, include_str!(concat!("../conformance/v1/", $name, ".yaml"))),
+        ]
+    };
+}
+
+/// Returns the canonical test vectors of the wire formats, see the [module documentation](self).
+pub fn conformance_vectors() -> Vec<ConformanceVector> {
+    let s = |v: &str| Value::String(v.to_string());
+    let inputs = [
+        ("empty", BTreeMap::new(), golden!("empty")),
+        (
+            "scalars",
+            BTreeMap::from([
+                ("attempt".to_string(), Value::U8(3)),
+                ("offset".to_string(), Value::I64(-42)),
+                ("ratio".to_string(), Value::F64(0.5)),
+                ("retry".to_string(), Value::Bool(true)),
+                ("user".to_string(), s("jane")),
+            ]),
+            golden!("scalars"),
+        ),
+        (
+            "nested",
+            BTreeMap::from([
+                (
+                    "request".to_string(),
+                    Value::Map(BTreeMap::from([
+                        (s("headers"), Value::Map(BTreeMap::from([(s("accept"), s("application/json"))]))),
+                        (s("method"), s("GET")),
+                    ])),
+                ),
+                ("tags".to_string(), Value::Seq(vec![s("api"), s("v2")])),
+            ]),
+            golden!("nested"),
+        ),
+        (
+            "unicode",
+            BTreeMap::from([("city".to_string(), s("Zürich")), ("message".to_string(), s("line 1\nline \"2\""))]),
+            golden!("unicode"),
+        ),
+    ];
+    inputs
+        .into_iter()
+        .flat_map(|(name, input, golden)| {
+            golden.map(|(format, pretty, expected)| ConformanceVector {
+                name,
+                version: CONFORMANCE_VERSION,
+                format,
+                pretty,
+                input: input.clone(),
+                expected,
+            })
+        })
+        .collect()
+}
+
+impl Format {
+    /// Fails if `value`, stored at `path`, holds a variant the format cannot write.
+    pub(crate) fn check_supported(&self, path: &str, value: &Value) -> cdumay_core::Result<()> {
+        let unsupported = |kind: &str| -> cdumay_core::Result<()> {
+            Err(ValidationError::new()
+                .with_message(format!("{} at '{}' cannot be written to {}", kind, path, self))
+                .into())
+        };
+        match (self, value) {
+            (Format::Toml, Value::Unit | Value::Option(None)) => unsupported("Null value"),
+            (Format::Yaml, Value::Bytes(_)) => unsupported("Byte string"),
+            (_, Value::Option(Some(inner)) | Value::Newtype(inner)) => self.check_supported(path, inner),
+            (_, Value::Seq(items)) => items
+                .iter()
+                .enumerate()
+                .try_for_each(|(index, item)| self.check_supported(&format!("{}.{}", path, index), item)),
+            (_, Value::Map(map)) => map.iter().try_for_each(|(k, v)| match k {
+                Value::String(k) => self.check_supported(&format!("{}.{}", path, k), v),
+                other => self.check_supported(&format!("{}.{:?}", path, other), v),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Context {
+    /// Checks that the context can be dumped to `format`.
+    ///
+    /// A context dumped with [`Contextualize::to_json`](crate::Contextualize::to_json),
+    /// [`Contextualize::to_toml`](crate::Contextualize::to_toml) or
+    /// [`Contextualize::to_yaml`](crate::Contextualize::to_yaml) and loaded back with the matching
+    /// `from_*` function holds the same data, up to the following normalizations:
+    ///
+    /// | Value                     | JSON                     | TOML                       | YAML                       |
+    /// |---------------------------|--------------------------|----------------------------|----------------------------|
+    /// | `Bool`, `String`          | preserved                | preserved                  | preserved                  |
+    /// | `Char`                    | `String`                 | `String`                   | `String`                   |
+    /// | integers                  | `U64`, `I64` if negative | `I64`                      | `U64`, `I64` if negative   |
+    /// | integers above `i64::MAX` | preserved                | [`PrecisionLoss`] error    | preserved                  |
+    /// | `F32`                     | `F64`, shortest decimal  | `F64`, exact widening      | `F64`, shortest decimal    |
+    /// | `F64`                     | preserved                | preserved                  | preserved                  |
+    /// | non-finite floats         | [`PrecisionLoss`] error  | preserved                  | preserved                  |
+    /// | `Unit`, `Option(None)`    | `Unit`                   | [`ValidationError`] error  | `Unit`                     |
+    /// | `Option(Some)`, `Newtype` | inner value              | inner value                | inner value                |
+    /// | `Bytes`                   | `Seq` of integers        | `Seq` of integers          | [`ValidationError`] error  |
+    /// | `Seq`                     | preserved                | preserved                  | preserved                  |
+    /// | `Map`                     | keys as strings          | keys as strings            | keys as strings            |
+    ///
+    /// Numbers follow the [`NumericPolicy`](crate::NumericPolicy) of the context, whose default is
+    /// shown above, and non-string map keys its [`MapKeyPolicy`](crate::MapKeyPolicy). Values of
+    /// nested sequences and maps are normalized the same way.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the dump would succeed
+    /// * `Err(e)` containing the error the dump would fail with: [`PrecisionLoss`],
+    ///   [`InvalidMapKey`] or [`ValidationError`], naming the path of the first offending value
+    ///
+    /// [`PrecisionLoss`]: crate::PrecisionLoss
+    /// [`InvalidMapKey`]: crate::InvalidMapKey
+    pub fn check_serializable(&self, format: Format) -> cdumay_core::Result<()> {
+        self.ordered_for(format, &self.effective_config(None)).map(|_| ())
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! This module provides the [`Contextualize`] trait, which defines a generic interface for
 //! managing key-value data with support for various serialization formats.
+use crate::alias::Aliases;
+use crate::backend::Entries;
+use crate::defaults::Defaults;
+use crate::deferred::Deferred;
+use crate::deprecation::Deprecations;
+use crate::transform::Transformers;
+use crate::{ContextConfig, KeyAccess, KeyOrder, KeyPolicy, Lifecycle, Priority, Sensitivity};
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
 use cdumay_core::ErrorConverter;
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// A trait for types that can be converted into a serializable context map.
 ///
@@ -14,6 +23,12 @@ use std::collections::BTreeMap;
 /// It is commonly used to extract structured debug or error context
 /// from a type in a generic and serializable form.
 ///
+/// With the "derive" feature, `#[derive(ContextDump)]` implements it for any struct with named
+/// fields implementing `Serialize`, each field being dumped under its name. Field attributes
+/// adjust the dump: `#[context(rename = "name")]` dumps the field under `name`,
+/// `#[context(skip)]` leaves it out and `#[context(flatten)]` dumps the entries of a field which
+/// serializes as a map instead of the field itself.
+///
 /// # Example
 ///
 /// ```rust
@@ -122,6 +137,15 @@ pub trait Contextualize: Sized + Serialize {
     /// * `data` - A map of key-value pairs to add to the context
     fn extend(&mut self, data: BTreeMap<String, serde_value::Value>);
 
+    /// Extends the context with entries given in order, used by the loaders to insert the keys in
+    /// document order.
+    ///
+    /// The default implementation collects the entries and calls [`Contextualize::extend`],
+    /// implementors keeping the insertion order should insert them one by one.
+    fn extend_ordered(&mut self, entries: impl IntoIterator<Item = (String, serde_value::Value)>) {
+        self.extend(entries.into_iter().collect());
+    }
+
     /// Returns a clone of the internal key-value store.
     ///
     /// # Returns
@@ -129,6 +153,186 @@ pub trait Contextualize: Sized + Serialize {
     /// Returns a `BTreeMap` containing all key-value pairs in the context.
     fn inner(&self) -> BTreeMap<String, serde_value::Value>;
 
+    /// Returns the key-value store, borrowed when the implementation can.
+    ///
+    /// The default implementation returns [`Contextualize::inner`], implementors holding their
+    /// entries in a map should borrow it. The provided serialization methods use it, so that
+    /// dumping a context does not copy it first.
+    fn inner_ref(&self) -> Cow<'_, BTreeMap<String, serde_value::Value>> {
+        Cow::Owned(self.inner())
+    }
+
+    /// Returns the entries attached as details to the errors raised about the context.
+    ///
+    /// The default implementation returns [`Contextualize::inner`], a [`Context`] leaves out its
+    /// secret and confidential values, see [`Context::view`].
+    fn error_details(&self) -> BTreeMap<String, serde_value::Value> {
+        self.inner()
+    }
+
+    /// Removes a key from the context, returning its value if it was present.
+    ///
+    /// The default implementation rebuilds the context from [`Contextualize::inner`], implementors
+    /// should provide a cheaper one.
+    fn remove(&mut self, k: &str) -> Option<serde_value::Value> {
+        let mut data = self.inner();
+        let value = data.remove(k)?;
+        *self = Self::new();
+        self.extend(data);
+        Some(value)
+    }
+
+    /// Removes all the keys from the context.
+    ///
+    /// The default implementation replaces the context with [`Contextualize::new`].
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns `true` if a value is stored under `k`.
+    fn contains_key(&self, k: &str) -> bool {
+        self.get(k).is_some()
+    }
+
+    /// Returns the number of entries of the context.
+    fn len(&self) -> usize {
+        self.inner_ref().len()
+    }
+
+    /// Returns `true` if the context has no entries.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the entries of [`Contextualize::inner`], in key order.
+    fn iter(&self) -> std::collections::btree_map::IntoIter<String, serde_value::Value> {
+        self.inner().into_iter()
+    }
+
+    /// Iterates over the keys of [`Contextualize::inner`], in order.
+    fn keys(&self) -> std::collections::btree_map::IntoKeys<String, serde_value::Value> {
+        self.inner().into_keys()
+    }
+
+    /// Iterates over the values of [`Contextualize::inner`], in key order.
+    fn values(&self) -> std::collections::btree_map::IntoValues<String, serde_value::Value> {
+        self.inner().into_values()
+    }
+
+    /// Renders the context on one line as `key=value` pairs separated by commas, nested maps as
+    /// `{key=value}` and sequences as `[value, value]`, quoting ambiguous strings.
+    fn to_logfmt(&self) -> String {
+        crate::render::logfmt(self.inner_ref().iter().map(|(k, v)| (k.as_str(), v)))
+    }
+
+    /// Renders the context as an aligned two-column table, the entries of nested maps and
+    /// sequences on indented rows below their parent key.
+    fn to_table(&self) -> String {
+        crate::render::table(self.inner_ref().iter().map(|(k, v)| (k.as_str(), v)), false)
+    }
+
+    /// Renders the context as [`Contextualize::to_table`] does, keys in bold, numbers and
+    /// booleans in yellow and null values dimmed with ANSI escape codes.
+    ///
+    /// This method is only available when the "color" feature is enabled.
+    #[cfg(feature = "color")]
+    fn to_table_colored(&self) -> String {
+        crate::render::table(self.inner_ref().iter().map(|(k, v)| (k.as_str(), v)), true)
+    }
+
+    /// Returns the string stored under `k`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<&str>` which is:
+    /// * `Ok(value)` if the key holds a string
+    /// * `Err(e)` containing a [`KeyNotFound`](crate::KeyNotFound) error if the key does not exist
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if the value is not a string
+    fn get_str(&self, k: &str) -> cdumay_core::Result<&str> {
+        crate::typed::typed(self, k, "string", crate::ValueExt::as_str)
+    }
+
+    /// Returns the integer stored under `k` as an `i64`, see [`Contextualize::get_str`] for the
+    /// errors.
+    fn get_i64(&self, k: &str) -> cdumay_core::Result<i64> {
+        crate::typed::typed(self, k, "i64", crate::ValueExt::as_i64)
+    }
+
+    /// Returns the number stored under `k` as an `f64`, see [`Contextualize::get_str`] for the
+    /// errors.
+    fn get_f64(&self, k: &str) -> cdumay_core::Result<f64> {
+        crate::typed::typed(self, k, "f64", crate::ValueExt::as_f64)
+    }
+
+    /// Returns the boolean stored under `k`, see [`Contextualize::get_str`] for the errors.
+    fn get_bool(&self, k: &str) -> cdumay_core::Result<bool> {
+        crate::typed::typed(self, k, "bool", crate::ValueExt::as_bool)
+    }
+
+    /// Stores `ulid` under `k` in its canonical rendering, see [`Ulid::encode`](crate::Ulid::encode).
+    fn insert_ulid(&mut self, k: String, ulid: u128) {
+        self.insert(k, serde_value::Value::String(crate::Ulid::encode(ulid)));
+    }
+
+    /// Returns the ULID stored under `k`, see [`Contextualize::get_str`] for the errors.
+    ///
+    /// The value must be a string accepted by [`Ulid::decode`](crate::Ulid::decode).
+    fn get_ulid(&self, k: &str) -> cdumay_core::Result<u128> {
+        crate::typed::typed(self, k, "ulid", crate::id::as_ulid)
+    }
+
+    /// Stores the 64-bit snowflake id `snowflake` under `k` as a decimal string.
+    fn insert_snowflake(&mut self, k: String, snowflake: u64) {
+        self.insert(k, serde_value::Value::String(snowflake.to_string()));
+    }
+
+    /// Returns the snowflake id stored under `k`, see [`Contextualize::get_str`] for the errors.
+    ///
+    /// The value must be a decimal string or a non-negative integer fitting in an `u64`.
+    fn get_snowflake(&self, k: &str) -> cdumay_core::Result<u64> {
+        crate::typed::typed(self, k, "snowflake", crate::id::as_snowflake)
+    }
+
+    /// Returns the value stored under `k`, deserialized into `T`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<T>` which is:
+    /// * `Ok(value)` if the key exists and its value deserializes into `T`
+    /// * `Err(e)` containing a [`KeyNotFound`](crate::KeyNotFound) error if the key does not exist
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if the value does not
+    ///   match `T`
+    fn get_as<T: serde::de::DeserializeOwned>(&self, k: &str) -> cdumay_core::Result<T> {
+        crate::typed::deserialized(self, k)
+    }
+
+    /// Creates a new context from the file at `path`, in the format given by its extension:
+    /// `.json`, `.toml`, `.yaml` or `.yml`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(context)` containing the loaded context on success
+    /// * `Err(e)` containing a [`KeyNotFound`](crate::KeyNotFound) error if there is no file at `path`,
+    ///   a [`ValidationError`](crate::ValidationError) error if the extension is unknown, a
+    ///   [`FeatureDisabled`](crate::FeatureDisabled) error if the format is not compiled in, or
+    ///   the error of the read or of the load, with the path under the `path` key of its details
+    fn from_file(path: impl AsRef<std::path::Path>) -> cdumay_core::Result<Self> {
+        crate::storage::load_path(path.as_ref())
+    }
+
+    /// Writes the context, pretty-printed, to the file at `path` in `format`, or in the format
+    /// given by its extension if `format` is `None`. Parent directories are created if needed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the file was written
+    /// * `Err(e)` containing the same errors as [`Contextualize::from_file`]
+    fn save_to_file(&self, path: impl AsRef<std::path::Path>, format: Option<crate::Format>) -> cdumay_core::Result<()> {
+        crate::storage::save_path(self, path.as_ref(), format)
+    }
+
     /// Creates a new context from a JSON string.
     ///
     /// This method is only available when the "json" feature is enabled.
@@ -158,12 +362,20 @@ pub trait Contextualize: Sized + Serialize {
     fn from_json(json: &str) -> cdumay_core::Result<Self> {
         Ok({
             let mut ctx = Self::new();
-            let details = serde_json::from_str::<BTreeMap<String, serde_json::Value>>(json)
-                .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), ctx.inner()))?
-                .into_iter()
-                .map(|(key, value)| (key, serde_value::Value::deserialize(value).unwrap()))
-                .collect();
-            ctx.extend(details);
+            let details = crate::metrics::observe_load(crate::Format::Json, json.len(), || {
+                #[cfg(feature = "simd-json")]
+                let parsed = crate::simd::from_str(json);
+                #[cfg(not(feature = "simd-json"))]
+                let parsed = serde_json::from_str::<crate::backend::Loaded<serde_json::Value>>(json);
+                parsed
+                    .map_err(|err| {
+                        cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), ctx.error_details())
+                    })?
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, crate::interop::from_json_value(value)?)))
+                    .collect::<cdumay_core::Result<Vec<_>>>()
+            })?;
+            ctx.extend_ordered(details);
             ctx
         })
     }
@@ -184,16 +396,20 @@ pub trait Contextualize: Sized + Serialize {
     #[cfg(feature = "json")]
     fn to_json(&self, pretty: bool) -> cdumay_core::Result<String> {
         match pretty {
-            true => Ok(serde_json::to_string_pretty(&self.inner())
-                .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.inner()))?),
-            false => Ok(serde_json::to_string(&self.inner())
-                .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.inner()))?),
+            true => Ok(serde_json::to_string_pretty(self.inner_ref().as_ref()).map_err(|err| {
+                cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details())
+            })?),
+            false => Ok(serde_json::to_string(self.inner_ref().as_ref()).map_err(|err| {
+                cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details())
+            })?),
         }
     }
 
     /// Creates a new context from a TOML string.
     ///
-    /// This method is only available when the "toml" feature is enabled.
+    /// This method is only available when the "toml" feature is enabled. Datetimes are read as
+    /// strings, or as tagged values if the global configuration says so, see
+    /// [`ContextConfig::with_tagged_values`].
     ///
     /// # Parameters
     ///
@@ -206,19 +422,9 @@ pub trait Contextualize: Sized + Serialize {
     /// * `Err(e)` containing the error on failure
     #[cfg(feature = "toml")]
     fn from_toml(toml: &str) -> cdumay_core::Result<Self> {
-        Ok({
-            let mut ctx = Self::new();
-            ctx.extend({
-                toml::from_str::<BTreeMap<String, serde_value::Value>>(toml)
-                    .map_err(|err| {
-                        cdumay_toml::TomlDeserializeErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), ctx.inner())
-                    })?
-                    .into_iter()
-                    .map(|(key, value)| (key, serde_value::Value::deserialize(value).unwrap()))
-                    .collect()
-            });
-            ctx
-        })
+        let mut ctx = Self::new();
+        ctx.extend_ordered(crate::tagged::load_toml(toml, ContextConfig::global().tagged_values())?);
+        Ok(ctx)
     }
 
     /// Serializes the context to a TOML string.
@@ -237,18 +443,21 @@ pub trait Contextualize: Sized + Serialize {
     #[cfg(feature = "toml")]
     fn to_toml(&self, pretty: bool) -> cdumay_core::Result<String> {
         match pretty {
-            true => Ok(toml::to_string_pretty(&self.inner()).map_err(|err| {
-                cdumay_toml::TomlSerializeErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.inner())
+            true => Ok(toml::to_string_pretty(self.inner_ref().as_ref()).map_err(|err| {
+                cdumay_toml::TomlSerializeErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details())
             })?),
-            false => Ok(toml::to_string(&self.inner()).map_err(|err| {
-                cdumay_toml::TomlSerializeErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.inner())
+            false => Ok(toml::to_string(self.inner_ref().as_ref()).map_err(|err| {
+                cdumay_toml::TomlSerializeErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details())
             })?),
         }
     }
 
     /// Creates a new context from a YAML string.
     ///
-    /// This method is only available when the "yaml" feature is enabled.
+    /// This method is only available when the "yaml" feature is enabled. Anchors, aliases and
+    /// merge keys are resolved, see [`YAML_EXPANSION_LIMIT`](crate::YAML_EXPANSION_LIMIT). Tags are
+    /// dropped, or kept as tagged values if the global configuration says so, see
+    /// [`ContextConfig::with_tagged_values`].
     ///
     /// # Parameters
     ///
@@ -258,20 +467,13 @@ pub trait Contextualize: Sized + Serialize {
     ///
     /// Returns `cdumay_core::Result<Self>` which is:
     /// * `Ok(context)` containing the parsed context on success
-    /// * `Err(e)` containing the error on failure
+    /// * `Err(e)` containing the error on failure, a `YamlExpansionLimit` error if the document
+    ///   expands too much
     #[cfg(feature = "yaml")]
     fn from_yaml(yaml: &str) -> cdumay_core::Result<Self> {
-        Ok({
-            let mut ctx = Self::new();
-            ctx.extend({
-                serde_yaml::from_str::<BTreeMap<String, serde_json::Value>>(yaml)
-                    .map_err(|err| cdumay_yaml::YamlErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), ctx.inner()))?
-                    .into_iter()
-                    .map(|(key, value)| (key, serde_value::Value::deserialize(value).unwrap()))
-                    .collect()
-            });
-            ctx
-        })
+        let mut ctx = Self::new();
+        ctx.extend_ordered(crate::yaml::load(yaml, ContextConfig::global().tagged_values())?);
+        Ok(ctx)
     }
 
     /// Serializes the context to a YAML string.
@@ -285,35 +487,286 @@ pub trait Contextualize: Sized + Serialize {
     /// * `Err(e)` containing the error on failure
     #[cfg(feature = "yaml")]
     fn to_yaml(&self) -> cdumay_core::Result<String> {
-        Ok(serde_yaml::to_string(&self.inner())
-            .map_err(|err| cdumay_yaml::YamlErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.inner()))?)
+        serde_yaml::to_string(self.inner_ref().as_ref())
+            .map_err(|err| cdumay_yaml::YamlErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details()))
+    }
+
+    /// Creates a new context from a RON string, a map or a struct such as `Config(level: 3)`.
+    /// Numbers are read with the smallest type holding them.
+    ///
+    /// This method is only available when the "ron" feature is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `ron` - A string containing a RON map or struct with string keys
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(context)` containing the parsed context on success
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error, with the `line` and
+    ///   the `column` of the failure in its details if the string does not parse
+    #[cfg(feature = "ron")]
+    fn from_ron(ron: &str) -> cdumay_core::Result<Self> {
+        let mut ctx = Self::new();
+        ctx.extend(crate::ron_rs::load(ron)?);
+        Ok(ctx)
+    }
+
+    /// Serializes the context to a RON string.
+    ///
+    /// This method is only available when the "ron" feature is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `pretty` - If true, the output will be pretty-printed with proper indentation
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(string)` containing the RON string on success
+    /// * `Err(e)` containing the error on failure
+    #[cfg(feature = "ron")]
+    fn to_ron(&self, pretty: bool) -> cdumay_core::Result<String> {
+        crate::ron_rs::dump(self.inner_ref().as_ref(), pretty, || self.error_details())
+    }
+
+    /// Creates a new context from an XML document, an entry per element held by the root
+    /// element. Values are read as strings, maps and sequences, XML having no types.
+    ///
+    /// This method is only available when the "xml" feature is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `xml` - A string containing a well-formed XML document
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(context)` containing the parsed context on success
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if the document is
+    ///   not well-formed or mixes text and elements
+    #[cfg(feature = "xml")]
+    fn from_xml(xml: &str) -> cdumay_core::Result<Self> {
+        let mut ctx = Self::new();
+        ctx.extend(crate::xml::load(xml)?);
+        Ok(ctx)
+    }
+
+    /// Serializes the context to an XML document, an element named after its key per entry
+    /// under a `root_tag` element.
+    ///
+    /// This method is only available when the "xml" feature is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `root_tag` - The name of the root element
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(string)` containing the XML document on success
+    /// * `Err(e)` containing an [`InvalidMapKey`](crate::InvalidMapKey) error if `root_tag` or a
+    ///   key is not a valid XML name, or a [`ValidationError`](crate::ValidationError) error
+    ///   for bytes and sequences nested in sequences
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "xml")]
+    /// # {
+    /// use cdumay_context::{Context, Contextualize};
+    /// use serde_value::Value;
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.insert("status".to_string(), Value::U16(404));
+    /// let xml = ctx.to_xml("context").unwrap();
+    /// assert_eq!(xml, r#"<?xml version="1.0" encoding="UTF-8"?><context><status>404</status></context>"#);
+    /// assert_eq!(Context::from_xml(&xml).unwrap().get("status"), Some(&Value::String("404".to_string())));
+    /// # }
+    /// ```
+    #[cfg(feature = "xml")]
+    fn to_xml(&self, root_tag: &str) -> cdumay_core::Result<String> {
+        crate::xml::dump(self.inner_ref().iter().map(|(k, v)| (k.as_str(), v)), root_tag, &|| self.error_details())
     }
 }
 
 /// A dynamic key-value context container that can store heterogeneous data.
 ///
-/// Internally uses a `BTreeMap<String, serde_value::Value>`, allowing you
-/// to insert any serializable value while preserving insertion order and
+/// Internally uses a `BTreeMap<String, serde_value::Value>`, or an `IndexMap` keeping the
+/// insertion order with the "ordered" feature, allowing you to insert any serializable value and
 /// allowing serialization/deserialization.
-#[derive(Default, Serialize, Deserialize, Debug)]
+#[derive(Default, Deserialize, Debug, Clone)]
 pub struct Context {
     /// The internal map storing the context data.
-    data: BTreeMap<String, serde_value::Value>,
+    pub(crate) data: Entries,
+    /// Values computed on demand, see [`Context::insert_lazy`].
+    #[serde(skip)]
+    pub(crate) deferred: BTreeMap<String, Deferred>,
+    /// How keys are normalized on insertion and lookup.
+    #[serde(skip)]
+    key_policy: KeyPolicy,
+    /// Keys which cannot be overwritten once set.
+    #[serde(skip)]
+    pub(crate) protected: BTreeSet<String>,
+    /// Keys whose value can never change again.
+    #[serde(skip)]
+    pub(crate) frozen: BTreeSet<String>,
+    /// Monotonic counter incremented on every change.
+    #[serde(skip)]
+    pub(crate) revision: u64,
+    /// Revision of the last change of each key.
+    #[serde(skip)]
+    pub(crate) changes: BTreeMap<String, u64>,
+    /// Revision at which each present key was created.
+    #[serde(skip)]
+    pub(crate) created: BTreeMap<String, u64>,
+    /// Delta synchronization state, see [`Context::apply_delta`].
+    #[serde(skip)]
+    pub(crate) sync: SyncState,
+    /// Revision at which each key was removed locally, until acknowledged, see
+    /// [`Context::acknowledge_delta`].
+    #[serde(skip)]
+    pub(crate) tombstones: BTreeMap<String, u64>,
+    /// Sensitivity level of each tagged key, see [`Context::set_sensitivity`].
+    #[serde(skip)]
+    pub(crate) sensitivity: BTreeMap<String, Sensitivity>,
+    /// Priority of each tagged key, see [`Context::set_priority`].
+    #[serde(skip)]
+    pub(crate) priorities: BTreeMap<String, Priority>,
+    /// Order of the keys in serialized output, see [`Context::set_key_order`].
+    #[serde(skip)]
+    pub(crate) key_order: KeyOrder,
+    /// Output transformers, see [`Context::add_transformer`].
+    #[serde(skip)]
+    pub(crate) transformers: Transformers,
+    /// Process-unique identifier, see [`Context::id`].
+    #[serde(skip)]
+    pub(crate) id: std::sync::OnceLock<String>,
+    /// Lifecycle state, see [`Context::start_lifecycle`].
+    #[serde(skip)]
+    pub(crate) lifecycle: Option<Lifecycle>,
+    /// Default values, see [`Context::register_default`].
+    #[serde(skip)]
+    pub(crate) defaults: Defaults,
+    /// Configuration of the context, see [`Context::set_config`].
+    #[serde(skip)]
+    pub(crate) config: ContextConfig,
+    /// Key aliases, see [`Context::alias_key`].
+    #[serde(skip)]
+    pub(crate) aliases: Aliases,
+    /// Digest of each entry, see [`Context::seal_values`].
+    #[serde(skip)]
+    pub(crate) seals: BTreeMap<String, u64>,
+    /// Deprecated keys, see [`Context::deprecate_key`].
+    #[serde(skip)]
+    pub(crate) deprecations: Deprecations,
+    /// Keys holding several values, see [`Context::insert_append`].
+    #[serde(skip)]
+    pub(crate) multi: BTreeSet<String>,
+    /// Expiry of time-boxed sections, see [`Context::section_with_ttl`].
+    #[serde(skip)]
+    pub(crate) expiries: BTreeMap<String, std::time::Instant>,
+    /// Expiry of entries inserted with a time to live, see [`Context::insert_with_ttl`].
+    #[serde(skip)]
+    pub(crate) ttls: BTreeMap<String, std::time::Instant>,
+}
+
+/// Delta synchronization state of a mirrored context.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SyncState {
+    /// Version of the last delta applied from the peer.
+    pub(crate) peer_version: Option<u64>,
+    /// Local revision right after the last delta was applied.
+    pub(crate) revision: u64,
+}
+
+impl Context {
+    /// Creates a new, empty `Context` using the given key policy.
+    pub fn with_key_policy(key_policy: KeyPolicy) -> Self {
+        Self {
+            key_policy,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the key policy of this context.
+    pub fn key_policy(&self) -> KeyPolicy {
+        self.key_policy
+    }
+
+    /// Returns `true` if a value, deferred or not, is stored under the normalized key `k`.
+    pub(crate) fn contains(&self, k: &str) -> bool {
+        self.data.contains_key(k) || self.deferred.contains_key(k)
+    }
+
+    /// Returns `true` if a value is stored under `k` and must not be overwritten, or if the
+    /// context is sealed.
+    pub(crate) fn is_locked(&self, k: &str) -> bool {
+        self.is_sealed() || ((self.protected.contains(k) || self.frozen.contains(k)) && self.contains(k))
+    }
+
+    /// Inserts a value, bypassing key protection but not frozen keys nor sealing.
+    pub(crate) fn insert_unchecked(&mut self, k: String, v: serde_value::Value) {
+        if self.replace_entry(&k) {
+            self.data.insert(k, v);
+        }
+    }
+
+    /// Records that a new value is about to be stored under `k`, forgetting the removal, the
+    /// deferred value, the multi-value history and the TTL of the previous one; the window of a
+    /// section is kept. Returns `false`, recording nothing, if `k` is frozen or the context
+    /// sealed.
+    pub(crate) fn replace_entry(&mut self, k: &str) -> bool {
+        if self.frozen.contains(k) || self.is_sealed() {
+            return false;
+        }
+        self.touch(k);
+        if !self.contains(k) {
+            self.created.insert(k.to_string(), self.revision);
+        }
+        self.deferred.remove(k);
+        self.multi.remove(k);
+        self.tombstones.remove(k);
+        self.ttls.remove(k);
+        true
+    }
+
+    /// Records a change of `k` for dirty tracking.
+    pub(crate) fn touch(&mut self, k: &str) {
+        self.revision += 1;
+        self.changes.insert(k.to_string(), self.revision);
+    }
 }
 
 impl Contextualize for Context {
-    /// Creates a new, empty `Context`.
+    /// Creates a new, empty `Context`, with the key policy and key order of the global
+    /// configuration, see [`ContextConfig`].
     fn new() -> Self {
-        Self::default()
+        let config = ContextConfig::global();
+        let mut ctx = Self::with_key_policy(config.key_policy());
+        ctx.key_order = config.key_order();
+        ctx
     }
 
     /// Inserts a key-value pair into the context.
     ///
+    /// Values stored under protected keys are left untouched, use [`Context::try_insert`] to be
+    /// notified or [`Context::force_insert`] to overwrite them.
+    ///
     /// # Arguments
     /// * `k` - The key as a `String`.
     /// * `v` - The value as a `serde_value::Value`.
     fn insert(&mut self, k: String, v: serde_value::Value) {
-        self.data.insert(k, v);
+        self.warn_deprecated(&k, KeyAccess::Write);
+        let k = match self.resolve_key(&k) {
+            Cow::Owned(normalized) => normalized,
+            Cow::Borrowed(_) => k,
+        };
+        if !self.is_locked(&k) {
+            crate::metrics::record(|metrics| metrics.on_insert(&k));
+            self.insert_unchecked(k, v);
+        }
     }
 
     /// Retrieves a reference to a value associated with the given key.
@@ -322,26 +775,169 @@ impl Contextualize for Context {
     /// * `k` - The key as a string slice.
     ///
     /// # Returns
-    /// * `Some(&Value)` if the key exists, or `None` otherwise.
+    /// * `Some(&Value)` if the key exists and has not expired, see [`Context::insert_with_ttl`],
+    ///   or has a default value, see [`Context::register_default`], or `None` otherwise.
     fn get(&self, k: &str) -> Option<&serde_value::Value> {
-        self.data.get(k)
+        self.warn_deprecated(k, KeyAccess::Read);
+        let k = self.resolve_key(k);
+        match self.data.get(k.as_ref()) {
+            Some(value) if !self.is_expired_entry(&k) => Some(value),
+            Some(_) => self.default_value(&k),
+            None if self.deferred.contains_key(k.as_ref()) => None,
+            None => self.default_value(&k),
+        }
     }
 
     /// Extends the context with the given key-value pairs.
     ///
-    /// Existing keys will be overwritten.
+    /// Existing keys will be overwritten, except protected ones.
     ///
     /// # Arguments
     /// * `data` - A `BTreeMap` of key-value pairs to insert.
     fn extend(&mut self, data: BTreeMap<String, serde_value::Value>) {
-        self.data.extend(data);
+        data.into_iter().for_each(|(k, v)| self.insert(k, v));
+    }
+
+    /// Inserts the entries one by one, in the given order.
+    fn extend_ordered(&mut self, entries: impl IntoIterator<Item = (String, serde_value::Value)>) {
+        entries.into_iter().for_each(|(k, v)| self.insert(k, v));
     }
 
     /// Returns a cloned copy of the internal map.
     ///
-    /// Useful for inspection or when you need owned data.
+    /// Useful for inspection or when you need owned data. Deferred values are evaluated and
+    /// registered transformers are applied, see [`Context::add_transformer`]. Keys without a value
+    /// hold their default one, see [`Context::register_default`]. Expired sections and entries are
+    /// left out, see [`Context::section_with_ttl`] and [`Context::insert_with_ttl`].
     fn inner(&self) -> BTreeMap<String, serde_value::Value> {
-        self.data.clone()
+        let mut data = self.data.to_btree();
+        data.extend(self.deferred.iter().map(|(k, deferred)| (k.clone(), deferred.evaluate())));
+        self.drop_expired_sections(&mut data);
+        self.drop_expired_entries(&mut data);
+        self.add_defaults(&mut data);
+        self.add_aliases(&mut data);
+        self.transformers.apply(data)
+    }
+
+    /// Borrows the internal map when [`Contextualize::inner`] would return it unchanged, that is
+    /// when the context has no deferred values, time-boxed sections or entries, default values,
+    /// emitted aliases nor transformers. The "ordered" backend is never borrowed.
+    fn inner_ref(&self) -> Cow<'_, BTreeMap<String, serde_value::Value>> {
+        match self.data.as_btree() {
+            Some(data)
+                if self.deferred.is_empty()
+                    && self.expiries.is_empty()
+                    && self.ttls.is_empty()
+                    && !self.has_defaults()
+                    && !self.aliases.emits()
+                    && self.transformers.is_empty() =>
+            {
+                Cow::Borrowed(data)
+            }
+            _ => Cow::Owned(self.inner()),
+        }
+    }
+
+    /// Returns the entries of [`Sensitivity::Internal`](crate::Sensitivity) level and below, so
+    /// that secret and confidential values never end up in error details.
+    fn error_details(&self) -> BTreeMap<String, serde_value::Value> {
+        self.view(crate::Sensitivity::Internal).dump()
+    }
+
+    /// Removes a key from the context, returning its value if it was present.
+    ///
+    /// Protected and frozen keys are not removed, nor any key of a sealed context.
+    fn remove(&mut self, k: &str) -> Option<serde_value::Value> {
+        let k = self.resolve_key(k).into_owned();
+        if self.is_locked(&k) {
+            return None;
+        }
+        let value = match self.deferred.remove(&k) {
+            Some(deferred) => deferred.evaluate(),
+            None => self.data.remove(&k)?,
+        };
+        self.touch(&k);
+        self.created.remove(&k);
+        self.multi.remove(&k);
+        self.expiries.remove(&k);
+        self.ttls.remove(&k);
+        self.tombstones.insert(k, self.revision);
+        Some(value)
+    }
+
+    /// Removes all the keys from the context, except protected and frozen ones. A sealed context
+    /// is left untouched.
+    fn clear(&mut self) {
+        let keys: Vec<String> = self.data.keys().chain(self.deferred.keys()).cloned().collect();
+        for k in keys {
+            self.remove(&k);
+        }
+    }
+
+    /// Returns `true` if a value, deferred or not, is stored under `k` and has not expired.
+    /// Default values, see [`Context::register_default`], are not taken into account.
+    fn contains_key(&self, k: &str) -> bool {
+        let k = self.resolve_key(k);
+        self.contains(&k) && !self.is_expired_entry(&k)
+    }
+
+    /// Returns the number of values, deferred or not, stored in the context and not expired.
+    fn len(&self) -> usize {
+        self.data.len() + self.deferred.len() - self.expired_entries().len()
+    }
+
+    /// Renders the context as `key=value` pairs, keys ordered as set by
+    /// [`Context::set_key_order`].
+    fn to_logfmt(&self) -> String {
+        crate::render::logfmt(self.ordered_entries().iter().map(|(k, v)| (k.as_str(), v)))
+    }
+
+    /// Renders the context as a table, keys ordered as set by [`Context::set_key_order`].
+    fn to_table(&self) -> String {
+        crate::render::table(self.ordered_entries().iter().map(|(k, v)| (k.as_str(), v)), false)
+    }
+
+    /// Renders the context as a colored table, keys ordered as set by
+    /// [`Context::set_key_order`].
+    #[cfg(feature = "color")]
+    fn to_table_colored(&self) -> String {
+        crate::render::table(self.ordered_entries().iter().map(|(k, v)| (k.as_str(), v)), true)
+    }
+
+    /// Serializes the context to a JSON string, keys ordered as set by [`Context::set_key_order`],
+    /// with the configuration in effect, see [`Context::effective_config`].
+    #[cfg(feature = "json")]
+    fn to_json(&self, pretty: bool) -> cdumay_core::Result<String> {
+        self.to_json_with(pretty, &ContextConfig::new())
+    }
+
+    /// Serializes the context to a TOML string, keys ordered as set by [`Context::set_key_order`],
+    /// with the configuration in effect, see [`Context::effective_config`].
+    #[cfg(feature = "toml")]
+    fn to_toml(&self, pretty: bool) -> cdumay_core::Result<String> {
+        self.to_toml_with(pretty, &ContextConfig::new())
+    }
+
+    /// Serializes the context to a YAML string, keys ordered as set by [`Context::set_key_order`],
+    /// with the configuration in effect, see [`Context::effective_config`].
+    #[cfg(feature = "yaml")]
+    fn to_yaml(&self) -> cdumay_core::Result<String> {
+        self.to_yaml_with(&ContextConfig::new())
+    }
+
+    /// Serializes the context to a RON string, keys ordered as set by [`Context::set_key_order`].
+    #[cfg(feature = "ron")]
+    fn to_ron(&self, pretty: bool) -> cdumay_core::Result<String> {
+        crate::ron_rs::dump(&self.ordered(), pretty, || self.error_details())
+    }
+
+    /// Serializes the context to an XML document, elements ordered as set by
+    /// [`Context::set_key_order`].
+    #[cfg(feature = "xml")]
+    fn to_xml(&self, root_tag: &str) -> cdumay_core::Result<String> {
+        crate::xml::dump(self.ordered_entries().iter().map(|(k, v)| (k.as_str(), v)), root_tag, &|| {
+            self.error_details()
+        })
     }
 }
 
@@ -352,6 +948,50 @@ impl Contextualize for Context {
 /// structured logging without mutating the original instance.
 impl ContextDump for Context {
     fn dump(&self) -> BTreeMap<String, serde_value::Value> {
-        self.data.clone()
+        self.inner()
+    }
+}
+
+/// Serializes the context data, deferred values included, keys ordered as set by
+/// [`Context::set_key_order`].
+impl Serialize for Context {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Context", 1)?;
+        state.serialize_field("data", &self.ordered())?;
+        state.end()
+    }
+}
+
+/// Returns the value stored under the given key.
+///
+/// # Panics
+///
+/// Panics if the key is not present. Use [`Contextualize::get`] or [`Context::at`] for a
+/// non-panicking lookup.
+impl std::ops::Index<&str> for Context {
+    type Output = serde_value::Value;
+
+    fn index(&self, k: &str) -> &Self::Output {
+        self.get(k).unwrap_or_else(|| panic!("key '{}' not found in context", k))
+    }
+}
+
+/// Returns a mutable reference to the value stored under the given key.
+///
+/// # Panics
+///
+/// Panics if the key is not present, keys must be created with [`Contextualize::insert`], or if
+/// the key is protected.
+impl std::ops::IndexMut<&str> for Context {
+    fn index_mut(&mut self, k: &str) -> &mut Self::Output {
+        let key = self.resolve_key(k).into_owned();
+        if self.is_locked(&key) {
+            panic!("key '{}' is protected", k);
+        }
+        if self.data.contains_key(&key) {
+            self.touch(&key);
+        }
+        self.data.get_mut(&key).unwrap_or_else(|| panic!("key '{}' not found in context", k))
     }
 }
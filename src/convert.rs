@@ -0,0 +1,121 @@
+//! Conversions between [`Context`] and common map and JSON types.
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let ctx = Context::from(vec![("user".to_string(), serde_value::Value::U64(42))]);
+//! let map: BTreeMap<String, serde_value::Value> = ctx.into();
+//! assert_eq!(map.len(), 1);
+//! ```
+use crate::{Context, Contextualize};
+#[cfg(feature = "json")]
+use cdumay_core::ErrorConverter;
+#[cfg(feature = "json")]
+use serde::Deserialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl From<BTreeMap<String, Value>> for Context {
+    fn from(data: BTreeMap<String, Value>) -> Self {
+        let mut ctx = Context::new();
+        ctx.extend(data);
+        ctx
+    }
+}
+
+impl From<Vec<(String, Value)>> for Context {
+    fn from(data: Vec<(String, Value)>) -> Self {
+        Context::from(data.into_iter().collect::<BTreeMap<String, Value>>())
+    }
+}
+
+impl From<Context> for BTreeMap<String, Value> {
+    fn from(ctx: Context) -> Self {
+        ctx.inner()
+    }
+}
+
+impl From<Context> for Vec<(String, Value)> {
+    fn from(ctx: Context) -> Self {
+        ctx.inner().into_iter().collect()
+    }
+}
+
+/// Converts a JSON object into a context.
+///
+/// This conversion is only available when the "json" feature is enabled, any other JSON value
+/// than an object is rejected. See the conversion from a `serde_json::Map`.
+#[cfg(feature = "json")]
+impl TryFrom<serde_json::Value> for Context {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Object(map) => Context::try_from(map),
+            other => {
+                let data = BTreeMap::<String, Value>::deserialize(other).map_err(|err| {
+                    cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), BTreeMap::new())
+                })?;
+                Ok(Context::from(data))
+            }
+        }
+    }
+}
+
+/// Converts a JSON object into a context, keeping the order of its keys.
+///
+/// This conversion is only available when the "json" feature is enabled. Keys are inserted in
+/// the order of the object and the context gets [`KeyOrder::Insertion`](crate::KeyOrder), so
+/// that converting it back gives the same order. Objects are only ordered when the
+/// "preserve-order" feature is enabled, alphabetically otherwise.
+#[cfg(feature = "json")]
+impl TryFrom<serde_json::Map<String, serde_json::Value>> for Context {
+    type Error = cdumay_core::Error;
+
+    fn try_from(map: serde_json::Map<String, serde_json::Value>) -> Result<Self, Self::Error> {
+        let mut ctx = Context::new();
+        for (k, v) in map {
+            ctx.insert(k, crate::interop::from_json_value(v)?);
+        }
+        ctx.set_key_order(crate::KeyOrder::Insertion);
+        Ok(ctx)
+    }
+}
+
+/// Converts a context into a JSON object, keys ordered as set by [`Context::set_key_order`]
+/// when the "preserve-order" feature is enabled.
+///
+/// This conversion is only available when the "json" feature is enabled, it applies the
+/// configuration in effect as [`Contextualize::to_json`] does.
+#[cfg(feature = "json")]
+impl TryFrom<Context> for serde_json::Map<String, serde_json::Value> {
+    type Error = cdumay_core::Error;
+
+    fn try_from(ctx: Context) -> Result<Self, Self::Error> {
+        let config = ctx.effective_config(None);
+        let encoded_len = |entries: &crate::order::OrderedEntries| serde_json::to_string(entries).map_or(usize::MAX, |dump| dump.len());
+        let ordered = ctx.ordered_for(crate::Format::Json, &config)?;
+        let ordered = ctx.fit(ordered, &config, encoded_len);
+        let mut map = serde_json::Map::new();
+        for (k, v) in ordered.0 {
+            let value = serde_json::to_value(v).map_err(|err| {
+                cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), ctx.error_details())
+            })?;
+            map.insert(k, value);
+        }
+        Ok(map)
+    }
+}
+
+/// Converts a context into a JSON object, see the conversion into a `serde_json::Map`.
+///
+/// This conversion is only available when the "json" feature is enabled.
+#[cfg(feature = "json")]
+impl TryFrom<Context> for serde_json::Value {
+    type Error = cdumay_core::Error;
+
+    fn try_from(ctx: Context) -> Result<Self, Self::Error> {
+        serde_json::Map::try_from(ctx).map(serde_json::Value::Object)
+    }
+}
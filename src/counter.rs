@@ -0,0 +1,88 @@
+//! Numeric counters and accumulators.
+//!
+//! [`Context::increment`] and [`Context::max`] update integer values in place, so that lightweight
+//! per-operation metrics accumulate in the context. On a [`SharedContext`](crate::SharedContext),
+//! the same methods run as a single atomic update, without read-modify-write races between
+//! threads.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::new();
+//! ctx.increment("bytes_read", 4096).unwrap();
+//! ctx.increment("bytes_read", 1024).unwrap();
+//! ctx.max("peak_rss", 300).unwrap();
+//! ctx.max("peak_rss", 200).unwrap();
+//!
+//! assert_eq!(ctx.at("bytes_read").as_i64(), Some(5120));
+//! assert_eq!(ctx.at("peak_rss").as_i64(), Some(300));
+//! ```
+use crate::{Context, Contextualize, SharedContext, TypeMismatch, ValueExt};
+use serde_value::Value;
+
+impl Context {
+    /// Adds `by` to the integer stored under `k`, starting from zero if the key is missing.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<i64>` which is:
+    /// * `Ok(value)` containing the updated value
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the stored value is not an integer or
+    ///   the result overflows, or a [`ProtectedKey`](crate::ProtectedKey) error if the key is
+    ///   protected
+    pub fn increment(&mut self, k: &str, by: i64) -> cdumay_core::Result<i64> {
+        let value = match self.integer(k)? {
+            Some(current) => current
+                .checked_add(by)
+                .ok_or_else(|| self.mismatch(format!("Context counter '{}' overflowed", k)))?,
+            None => by,
+        };
+        self.try_insert(k.to_string(), Value::I64(value))?;
+        Ok(value)
+    }
+
+    /// Stores `value` under `k` if it is greater than the integer currently stored, or if the key
+    /// is missing.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<i64>` which is:
+    /// * `Ok(value)` containing the maximum
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the stored value is not an integer, or a
+    ///   [`ProtectedKey`](crate::ProtectedKey) error if the key is protected
+    pub fn max(&mut self, k: &str, value: i64) -> cdumay_core::Result<i64> {
+        match self.integer(k)? {
+            Some(current) if current >= value => Ok(current),
+            _ => {
+                self.try_insert(k.to_string(), Value::I64(value))?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn integer(&self, k: &str) -> cdumay_core::Result<Option<i64>> {
+        match self.get(k) {
+            None => Ok(None),
+            Some(value) => match value.as_i64() {
+                Some(current) => Ok(Some(current)),
+                None => Err(self.mismatch(format!("Context key '{}' does not hold an integer", k))),
+            },
+        }
+    }
+
+    pub(crate) fn mismatch(&self, message: String) -> cdumay_core::Error {
+        TypeMismatch::new().with_message(message).with_details(self.error_details()).into()
+    }
+}
+
+impl SharedContext {
+    /// Atomically adds `by` to the integer stored under `k`, see [`Context::increment`].
+    pub fn increment(&self, k: &str, by: i64) -> cdumay_core::Result<i64> {
+        self.update(|ctx| ctx.increment(k, by))
+    }
+
+    /// Atomically keeps the maximum of `value` and the integer stored under `k`, see [`Context::max`].
+    pub fn max(&self, k: &str, value: i64) -> cdumay_core::Result<i64> {
+        self.update(|ctx| ctx.max(k, value))
+    }
+}
@@ -0,0 +1,202 @@
+//! Crash ring buffer of serialized contexts.
+//!
+//! A [`CrashRing`] keeps the last serialized contexts in a buffer allocated once, so that they
+//! survive the state of the process when it crashes: [`CrashRing::new`] allocates it in memory,
+//! where a crash handler can reach it, and [`CrashRing::create`] (feature: "mmap") maps it to a
+//! file, which outlives the process and is read back by a post-mortem tool with
+//! [`CrashRing::recover`].
+//!
+//! The buffer is a header followed by fixed-size slots, overwritten in turn. Each slot records
+//! its sequence number last, so that a slot interrupted while being written is ignored on
+//! recovery. Entries larger than a slot are truncated. The header stores the number of slots and
+//! their size on 32 bits, larger rings are rejected.
+//!
+//! ```rust
+//! use cdumay_context::CrashRing;
+//!
+//! let mut ring = CrashRing::new(2, 64).unwrap();
+//! ring.push(br#"{"step":"download"}"#);
+//! ring.push(br#"{"step":"extract"}"#);
+//! ring.push(br#"{"step":"install"}"#);
+//!
+//! let entries = CrashRing::recover(ring.as_bytes());
+//! assert_eq!(entries, vec![br#"{"step":"extract"}"#.to_vec(), br#"{"step":"install"}"#.to_vec()]);
+//! ```
+use crate::SizeLimitExceeded;
+#[cfg(feature = "json")]
+use crate::{Context, Contextualize};
+
+const MAGIC: &[u8; 8] = b"CDCRING1";
+const HEADER_LEN: usize = 24;
+const SLOT_HEADER_LEN: usize = 12;
+
+/// Ring buffer keeping the last serialized contexts, see the [module documentation](self).
+pub struct CrashRing {
+    storage: Storage,
+    slots: usize,
+    slot_size: usize,
+    next_seq: u64,
+}
+
+enum Storage {
+    Memory(Box<[u8]>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::MmapMut),
+}
+
+impl CrashRing {
+    /// Allocates a ring of `slots` entries of at most `slot_size` bytes each in memory.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(ring)` with the allocated ring
+    /// * `Err(e)` containing a [`SizeLimitExceeded`] error if `slots` or `slot_size` exceeds
+    ///   `u32::MAX`, or if the buffer size overflows
+    pub fn new(slots: usize, slot_size: usize) -> cdumay_core::Result<Self> {
+        let storage = Storage::Memory(vec![0; buffer_len(slots, slot_size)?].into_boxed_slice());
+        Ok(Self::init(storage, slots, slot_size))
+    }
+
+    /// Creates or truncates the file at `path` and maps a ring of `slots` entries of at most
+    /// `slot_size` bytes each to it.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(ring)` with the mapped ring
+    /// * `Err(e)` containing a [`SizeLimitExceeded`] error if the ring is too large, as for
+    ///   [`CrashRing::new`], or the I/O error raised while creating or mapping the file
+    #[cfg(feature = "mmap")]
+    pub fn create(path: impl AsRef<std::path::Path>, slots: usize, slot_size: usize) -> cdumay_core::Result<Self> {
+        let path = path.as_ref();
+        let len = buffer_len(slots, slot_size)?;
+        let io_error = |err: std::io::Error| crate::storage::io_error(&err, path);
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(io_error)?;
+        file.set_len(len as u64).map_err(io_error)?;
+        // SAFETY: the file was just created by this process, which is the only one expected to
+        // write it until it is recovered.
+        let map = unsafe { memmap2::MmapMut::map_mut(&file) }.map_err(io_error)?;
+        Ok(Self::init(Storage::Mapped(map), slots, slot_size))
+    }
+
+    fn init(storage: Storage, slots: usize, slot_size: usize) -> Self {
+        let mut ring = Self {
+            storage,
+            slots,
+            slot_size,
+            next_seq: 1,
+        };
+        let buffer = ring.buffer_mut();
+        buffer[..8].copy_from_slice(MAGIC);
+        // Both sizes fit in 32 bits, as checked by `buffer_len`.
+        buffer[8..12].copy_from_slice(&(slots as u32).to_le_bytes());
+        buffer[12..16].copy_from_slice(&(slot_size as u32).to_le_bytes());
+        ring
+    }
+
+    /// Stores `entry` in place of the oldest one, returning `false` if it had to be truncated.
+    pub fn push(&mut self, entry: &[u8]) -> bool {
+        if self.slots == 0 {
+            return false;
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        // At most `slot_size`, which fits in 32 bits.
+        let len = entry.len().min(self.slot_size);
+        let start = HEADER_LEN + ((seq - 1) as usize % self.slots) * (SLOT_HEADER_LEN + self.slot_size);
+        let buffer = self.buffer_mut();
+        buffer[start..start + 8].copy_from_slice(&0u64.to_le_bytes());
+        buffer[start + 8..start + 12].copy_from_slice(&(len as u32).to_le_bytes());
+        buffer[start + SLOT_HEADER_LEN..start + SLOT_HEADER_LEN + len].copy_from_slice(&entry[..len]);
+        buffer[start..start + 8].copy_from_slice(&seq.to_le_bytes());
+        buffer[16..24].copy_from_slice(&seq.to_le_bytes());
+        len == entry.len()
+    }
+
+    /// Stores the compact JSON serialization of `ctx`, see [`CrashRing::push`].
+    #[cfg(feature = "json")]
+    pub fn push_context(&mut self, ctx: &Context) -> cdumay_core::Result<bool> {
+        Ok(self.push(ctx.to_json(false)?.as_bytes()))
+    }
+
+    /// Returns the raw buffer, as written to the mapped file.
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.storage {
+            Storage::Memory(buffer) => buffer,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map,
+        }
+    }
+
+    /// Flushes the mapped file to disk, if any.
+    pub fn flush(&self) -> std::io::Result<()> {
+        match &self.storage {
+            Storage::Memory(_) => Ok(()),
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map.flush(),
+        }
+    }
+
+    /// Returns the complete entries of a ring buffer, oldest first.
+    ///
+    /// Returns nothing if `buffer` is not a ring buffer.
+    pub fn recover(buffer: &[u8]) -> Vec<Vec<u8>> {
+        if buffer.len() < HEADER_LEN || &buffer[..8] != MAGIC {
+            return Vec::new();
+        }
+        let read_u32 = |at: usize| u32::from_le_bytes(buffer[at..at + 4].try_into().unwrap_or_default()) as usize;
+        let (slots, slot_size) = (read_u32(8), read_u32(12));
+        let mut entries: Vec<(u64, Vec<u8>)> = (0..slots)
+            .map_while(|slot| slot.checked_mul(SLOT_HEADER_LEN + slot_size)?.checked_add(HEADER_LEN))
+            .take_while(|start| start + SLOT_HEADER_LEN <= buffer.len())
+            .filter_map(|start| {
+                let seq = u64::from_le_bytes(buffer[start..start + 8].try_into().unwrap_or_default());
+                let len = read_u32(start + 8).min(slot_size);
+                let data = buffer.get(start + SLOT_HEADER_LEN..start + SLOT_HEADER_LEN + len)?;
+                (seq != 0).then(|| (seq, data.to_vec()))
+            })
+            .collect();
+        entries.sort_by_key(|(seq, _)| *seq);
+        entries.into_iter().map(|(_, data)| data).collect()
+    }
+
+    fn buffer_mut(&mut self) -> &mut [u8] {
+        match &mut self.storage {
+            Storage::Memory(buffer) => buffer,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map,
+        }
+    }
+}
+
+impl std::fmt::Debug for CrashRing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrashRing")
+            .field("slots", &self.slots)
+            .field("slot_size", &self.slot_size)
+            .field("next_seq", &self.next_seq)
+            .finish()
+    }
+}
+
+/// Returns the length of the buffer of a ring, whose header stores `slots` and `slot_size` on 32
+/// bits.
+fn buffer_len(slots: usize, slot_size: usize) -> cdumay_core::Result<usize> {
+    let len = SLOT_HEADER_LEN
+        .checked_add(slot_size)
+        .and_then(|slot| slot.checked_mul(slots))
+        .and_then(|len| len.checked_add(HEADER_LEN));
+    match (u32::try_from(slots), u32::try_from(slot_size), len) {
+        (Ok(_), Ok(_), Some(len)) => Ok(len),
+        _ => Err(SizeLimitExceeded::new()
+            .with_message(format!("Crash ring of {} slots of {} bytes exceeds the supported size", slots, slot_size))
+            .into()),
+    }
+}
@@ -0,0 +1,175 @@
+//! Conflict-free replicated context for concurrent writers.
+//!
+//! [`CrdtContext`] stores each key as a last-writer-wins register stamped with a Lamport timestamp
+//! and the id of the writing node. Merging two replicas keeps, for each key, the entry with the
+//! highest `(timestamp, node)` pair, which makes merges commutative, associative and idempotent:
+//! replicas updated independently converge whatever the order in which they are reconciled.
+//! Removals are recorded as tombstones so that they win over older writes.
+//!
+//! ```rust
+//! use cdumay_context::{Contextualize, CrdtContext};
+//! use serde_value::Value;
+//!
+//! let mut a = CrdtContext::with_node("a");
+//! let mut b = CrdtContext::with_node("b");
+//! a.insert("status".to_string(), Value::String("running".to_string()));
+//! b.insert("progress".to_string(), Value::U8(50));
+//! b.insert("status".to_string(), Value::String("paused".to_string()));
+//!
+//! let mut ab = a.clone();
+//! ab.merge(&b);
+//! let mut ba = b.clone();
+//! ba.merge(&a);
+//! assert_eq!(ab.inner(), ba.inner());
+//! ```
+use crate::{ContextDump, Contextualize};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NODE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A last-writer-wins register.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LwwEntry {
+    /// The value, `None` for a tombstone.
+    pub value: Option<serde_value::Value>,
+    /// Lamport timestamp of the write.
+    pub timestamp: u64,
+    /// Id of the node which performed the write.
+    pub node: String,
+}
+
+impl LwwEntry {
+    fn wins_over(&self, other: &LwwEntry) -> bool {
+        (self.timestamp, &self.node) > (other.timestamp, &other.node)
+    }
+}
+
+/// A context whose replicas can be updated concurrently and merged without coordination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrdtContext {
+    node: String,
+    clock: u64,
+    entries: BTreeMap<String, LwwEntry>,
+}
+
+impl CrdtContext {
+    /// Creates an empty replica owned by the given node id.
+    ///
+    /// Node ids must be unique among the replicas which are merged together.
+    pub fn with_node(node: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            clock: 0,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the node id of this replica.
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    /// Returns the current Lamport clock of this replica.
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Returns the register of a key, including tombstones.
+    pub fn entry(&self, k: &str) -> Option<&LwwEntry> {
+        self.entries.get(k)
+    }
+
+    /// Drops the tombstones written at or before the Lamport timestamp `acknowledged`, returning
+    /// how many were dropped.
+    ///
+    /// Tombstones must be kept until every replica has merged them: call this method with the
+    /// lowest clock acknowledged by all the replicas, otherwise a stale replica would bring the
+    /// removed keys back on the next merge.
+    pub fn purge_tombstones(&mut self, acknowledged: u64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.value.is_some() || entry.timestamp > acknowledged);
+        before - self.entries.len()
+    }
+
+    /// Merges another replica into this one.
+    pub fn merge(&mut self, other: &CrdtContext) {
+        for (key, entry) in &other.entries {
+            let replace = match self.entries.get(key) {
+                Some(local) => entry.wins_over(local),
+                None => true,
+            };
+            if replace {
+                self.entries.insert(key.clone(), entry.clone());
+            }
+        }
+        self.clock = self.clock.max(other.clock);
+    }
+
+    fn write(&mut self, k: String, value: Option<serde_value::Value>) {
+        self.clock += 1;
+        let entry = LwwEntry {
+            value,
+            timestamp: self.clock,
+            node: self.node.clone(),
+        };
+        self.entries.insert(k, entry);
+    }
+}
+
+impl Contextualize for CrdtContext {
+    /// Creates a replica with a node id unique within the process.
+    ///
+    /// Use [`CrdtContext::with_node`] to choose an id unique across processes.
+    fn new() -> Self {
+        Self::with_node(format!("{}-{}", std::process::id(), NODE_COUNTER.fetch_add(1, Ordering::Relaxed)))
+    }
+
+    fn insert(&mut self, k: String, v: serde_value::Value) {
+        self.write(k, Some(v));
+    }
+
+    fn get(&self, k: &str) -> Option<&serde_value::Value> {
+        self.entries.get(k).and_then(|entry| entry.value.as_ref())
+    }
+
+    fn extend(&mut self, data: BTreeMap<String, serde_value::Value>) {
+        data.into_iter().for_each(|(k, v)| self.insert(k, v));
+    }
+
+    fn inner(&self) -> BTreeMap<String, serde_value::Value> {
+        self.entries
+            .iter()
+            .filter_map(|(k, entry)| entry.value.clone().map(|v| (k.clone(), v)))
+            .collect()
+    }
+
+    /// Removes a key by writing a tombstone, returning the previous value if any.
+    fn remove(&mut self, k: &str) -> Option<serde_value::Value> {
+        let previous = self.entries.get(k).and_then(|entry| entry.value.clone());
+        self.write(k.to_string(), None);
+        previous
+    }
+
+    /// Removes all the keys by writing tombstones, keeping the node id and the clock.
+    fn clear(&mut self) {
+        let keys: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.value.is_some())
+            .map(|(k, _)| k.clone())
+            .collect();
+        keys.into_iter().for_each(|k| self.write(k, None));
+    }
+
+    fn len(&self) -> usize {
+        self.entries.values().filter(|entry| entry.value.is_some()).count()
+    }
+}
+
+impl ContextDump for CrdtContext {
+    fn dump(&self) -> BTreeMap<String, serde_value::Value> {
+        self.inner()
+    }
+}
@@ -0,0 +1,60 @@
+//! Deadline metadata.
+//!
+//! [`Context::set_deadline`] stores a deadline in the context as a number of milliseconds since
+//! the Unix epoch under the [`DEADLINE_KEY`] key. Unlike an `Instant`, this form survives
+//! serialization, so the deadline propagates along with the rest of the context through headers
+//! and queues, as gRPC deadlines do.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use std::time::{Duration, Instant};
+//!
+//! let mut ctx = Context::new();
+//! assert!(ctx.remaining().is_none());
+//!
+//! ctx.set_deadline(Instant::now() + Duration::from_secs(30));
+//! assert!(ctx.remaining().unwrap() > Duration::from_secs(25));
+//! assert!(!ctx.is_expired());
+//! ```
+use crate::{Context, Contextualize, ValueExt};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Key under which [`Context::set_deadline`] stores the deadline.
+pub const DEADLINE_KEY: &str = crate::keys::DEADLINE;
+
+impl Context {
+    /// Sets the deadline of the operation described by this context.
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        let now = Instant::now();
+        let at = match deadline >= now {
+            true => SystemTime::now() + (deadline - now),
+            false => SystemTime::now() - (now - deadline),
+        };
+        let millis = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        self.insert(
+            DEADLINE_KEY.to_string(),
+            serde_value::Value::U64(u64::try_from(millis).unwrap_or(u64::MAX)),
+        );
+    }
+
+    /// Returns the deadline, if one is set.
+    pub fn deadline(&self) -> Option<Instant> {
+        let now = Instant::now();
+        let at = UNIX_EPOCH + Duration::from_millis(self.get(DEADLINE_KEY)?.as_u64()?);
+        Some(match at.duration_since(SystemTime::now()) {
+            Ok(remaining) => now + remaining,
+            Err(err) => now.checked_sub(err.duration()).unwrap_or(now),
+        })
+    }
+
+    /// Returns the time left before the deadline, `Duration::ZERO` if it has passed, or `None` if no
+    /// deadline is set.
+    pub fn remaining(&self) -> Option<Duration> {
+        Some(self.deadline()?.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns `true` if a deadline is set and has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_some_and(|remaining| remaining.is_zero())
+    }
+}
@@ -0,0 +1,132 @@
+//! Default values.
+//!
+//! Config-style consumers want the effective value of a key without populating every context
+//! with every setting. [`Context::register_default`] registers a closure computing the default
+//! value of a key for one context, [`Context::register_global_default`] for every context of the
+//! process. The closure is evaluated the first time the default is needed, and memoized.
+//!
+//! When a key has no value, [`Contextualize::get`](crate::Contextualize::get) falls back to its default, and dumps include it
+//! along with the [`DEFAULTED_KEY`] entry, which lists the keys holding a default value. Inserting
+//! a value overrides the default; removing it brings the default back.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.register_default("timeout_ms", || Value::U64(5000));
+//! assert_eq!(ctx.get("timeout_ms"), Some(&Value::U64(5000)));
+//! assert_eq!(ctx.inner().get("defaulted"), Some(&Value::Seq(vec![Value::String("timeout_ms".to_string())])));
+//!
+//! ctx.insert("timeout_ms".to_string(), Value::U64(100));
+//! assert_eq!(ctx.get("timeout_ms"), Some(&Value::U64(100)));
+//! assert!(!ctx.inner().contains_key("defaulted"));
+//! ```
+use crate::Context;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Key under which dumps list the keys holding a default value.
+pub const DEFAULTED_KEY: &str = crate::keys::DEFAULTED;
+
+/// Defaults registered for every context, leaked so that references to their values live as
+/// long as the process.
+static GLOBAL_DEFAULTS: RwLock<BTreeMap<String, &'static DefaultValue>> = RwLock::new(BTreeMap::new());
+
+/// A default value, computed on first use.
+pub(crate) struct DefaultValue {
+    value: OnceLock<Value>,
+    init: Box<dyn Fn() -> Value + Send + Sync>,
+}
+
+impl DefaultValue {
+    fn new<F: Fn() -> Value + Send + Sync + 'static>(f: F) -> Self {
+        Self {
+            value: OnceLock::new(),
+            init: Box::new(f),
+        }
+    }
+
+    fn get(&self) -> &Value {
+        self.value.get_or_init(|| (self.init)())
+    }
+}
+
+/// Defaults registered on a context. Clones of a context share the memoized values.
+#[derive(Clone, Default)]
+pub(crate) struct Defaults(BTreeMap<String, Arc<DefaultValue>>);
+
+impl std::fmt::Debug for Defaults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.0.keys()).finish()
+    }
+}
+
+impl Context {
+    /// Registers the default value of `k` for this context, computed by `f` on first use.
+    pub fn register_default<F>(&mut self, k: &str, f: F)
+    where
+        F: Fn() -> Value + Send + Sync + 'static,
+    {
+        let k = self.resolve_key(k).into_owned();
+        self.defaults.0.insert(k, Arc::new(DefaultValue::new(f)));
+    }
+
+    /// Registers the default value of `k` for every context, computed by `f` on first use.
+    ///
+    /// Defaults registered on a context take precedence. `k` is matched against normalized keys,
+    /// so it must be registered in normalized form for contexts with a [`KeyPolicy`](crate::KeyPolicy).
+    /// Global defaults live as long as the process, even once replaced: they are meant to be
+    /// registered once, at startup.
+    pub fn register_global_default<F>(k: &str, f: F)
+    where
+        F: Fn() -> Value + Send + Sync + 'static,
+    {
+        let default: &'static DefaultValue = Box::leak(Box::new(DefaultValue::new(f)));
+        GLOBAL_DEFAULTS
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(k.to_string(), default);
+    }
+
+    /// Returns `true` if `k` has no value and falls back to a default one.
+    pub fn is_defaulted(&self, k: &str) -> bool {
+        let k = self.resolve_key(k);
+        !self.contains(&k) && self.default_value(&k).is_some()
+    }
+
+    /// Returns the default value of the normalized key `k`, if any.
+    pub(crate) fn default_value(&self, k: &str) -> Option<&Value> {
+        if let Some(default) = self.defaults.0.get(k) {
+            return Some(default.get());
+        }
+        let global = *GLOBAL_DEFAULTS.read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(k)?;
+        Some(global.get())
+    }
+
+    /// Returns `true` if default values are registered on the context or for every context.
+    pub(crate) fn has_defaults(&self) -> bool {
+        !self.defaults.0.is_empty() || !GLOBAL_DEFAULTS.read().unwrap_or_else(|poisoned| poisoned.into_inner()).is_empty()
+    }
+
+    /// Adds the default values of the keys missing from `data`, listing them under
+    /// [`DEFAULTED_KEY`].
+    pub(crate) fn add_defaults(&self, data: &mut BTreeMap<String, Value>) {
+        let mut keys: Vec<String> = self.defaults.0.keys().cloned().collect();
+        keys.extend(GLOBAL_DEFAULTS.read().unwrap_or_else(|poisoned| poisoned.into_inner()).keys().cloned());
+        let mut defaulted = Vec::new();
+        for k in keys {
+            if data.contains_key(&k) {
+                continue;
+            }
+            if let Some(value) = self.default_value(&k) {
+                data.insert(k.clone(), value.clone());
+                defaulted.push(Value::String(k));
+            }
+        }
+        if !defaulted.is_empty() && !data.contains_key(DEFAULTED_KEY) {
+            data.insert(DEFAULTED_KEY.to_string(), Value::Seq(defaulted));
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Values computed only when the context is dumped.
+//!
+//! Some diagnostics, such as configuration digests or queue depths, are expensive to compute and
+//! only useful if an error is actually reported. [`Context::insert_lazy`] stores a closure which
+//! is evaluated the first time the context is dumped or serialized, and memoized afterwards.
+//!
+//! Other values, such as memory usage or in-flight requests, must be fresh when the error is
+//! reported: [`Context::insert_live`] stores a closure which is evaluated again at every dump.
+//!
+//! Deferred values are only visible through [`Contextualize::inner`](crate::Contextualize::inner),
+//! [`ContextDump::dump`](crate::ContextDump::dump), serialization and the views built on them, such as
+//! [`Context::render`] or [`Context::dump_truncated`]: [`Contextualize::get`](crate::Contextualize::get) does not evaluate them.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert_lazy("config_digest", || Value::String("4f2a".to_string()));
+//! assert!(ctx.get("config_digest").is_none());
+//! assert_eq!(ctx.inner().get("config_digest"), Some(&Value::String("4f2a".to_string())));
+//! ```
+use crate::Context;
+use serde_value::Value;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A value computed on demand.
+#[derive(Clone)]
+pub(crate) enum Deferred {
+    /// Evaluated once, then memoized. Clones of a context share the memoized value.
+    Lazy(Arc<Lazy>),
+    /// Evaluated at every dump.
+    Live(Arc<Mutex<Box<dyn Fn() -> Value + Send>>>),
+}
+
+pub(crate) struct Lazy {
+    value: OnceLock<Value>,
+    init: Mutex<Option<Box<dyn FnOnce() -> Value + Send>>>,
+}
+
+impl Deferred {
+    /// Returns the current value, evaluating the closure if needed.
+    pub(crate) fn evaluate(&self) -> Value {
+        match self {
+            Deferred::Lazy(lazy) => lazy
+                .value
+                .get_or_init(|| {
+                    let init = lazy.init.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+                    init.map(|f| f()).unwrap_or(Value::Unit)
+                })
+                .clone(),
+            Deferred::Live(f) => (f.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))(),
+        }
+    }
+}
+
+/// Deferred values are equal if they share their closure, as in clones of a context.
+impl PartialEq for Deferred {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Deferred::Lazy(a), Deferred::Lazy(b)) => Arc::ptr_eq(a, b),
+            (Deferred::Live(a), Deferred::Live(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Deferred {}
+
+impl std::fmt::Debug for Deferred {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Deferred::Lazy(lazy) => match lazy.value.get() {
+                Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+                None => f.write_str("Lazy(<pending>)"),
+            },
+            Deferred::Live(_) => f.write_str("Live(<fn>)"),
+        }
+    }
+}
+
+impl Context {
+    /// Inserts a value computed by `f` the first time the context is dumped or serialized.
+    ///
+    /// The result is memoized, `f` is called at most once. Like [`Contextualize::insert`](crate::Contextualize::insert), values
+    /// stored under protected or frozen keys are left untouched.
+    pub fn insert_lazy<F>(&mut self, k: impl Into<String>, f: F)
+    where
+        F: FnOnce() -> Value + Send + 'static,
+    {
+        let lazy = Lazy {
+            value: OnceLock::new(),
+            init: Mutex::new(Some(Box::new(f))),
+        };
+        self.insert_deferred(k.into(), Deferred::Lazy(Arc::new(lazy)));
+    }
+
+    /// Inserts a value computed by `f` every time the context is dumped or serialized.
+    ///
+    /// Live values are never memoized, so two dumps of the same context may differ. This also
+    /// applies to everything built on dumps: [`Context::compare`] and [`Context::to_snapshot`]
+    /// see the value of the moment, and [`Context::export_delta`] only exports a live value when
+    /// its key was inserted since the checkpoint, as changes of the source are not tracked. Like [`Contextualize::insert`](crate::Contextualize::insert),
+    /// values stored under protected or frozen keys are left untouched.
+    pub fn insert_live<F>(&mut self, k: impl Into<String>, f: F)
+    where
+        F: Fn() -> Value + Send + 'static,
+    {
+        self.insert_deferred(k.into(), Deferred::Live(Arc::new(Mutex::new(Box::new(f)))));
+    }
+
+    fn insert_deferred(&mut self, k: String, deferred: Deferred) {
+        let k = self.resolve_key(&k).into_owned();
+        if self.is_locked(&k) || !self.replace_entry(&k) {
+            return;
+        }
+        self.data.remove(&k);
+        self.deferred.insert(k, deferred);
+    }
+
+    /// Returns `true` if a deferred value is stored under `k`.
+    pub fn is_deferred(&self, k: &str) -> bool {
+        self.deferred.contains_key(self.resolve_key(k).as_ref())
+    }
+}
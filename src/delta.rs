@@ -0,0 +1,176 @@
+//! Delta synchronization between mirrored contexts.
+//!
+//! Building on dirty tracking, [`Context::export_delta`] produces a compact [`Delta`] of the
+//! additions, updates and removals made since a checkpoint, and [`Context::apply_delta`] replays
+//! it on a mirror. The mirror remembers the version of the last applied delta, so that a missed
+//! or out-of-order delta and concurrent local modifications are reported as [`DeltaConflict`]
+//! errors instead of silently diverging.
+//!
+//! Keys removed locally are kept as tombstones until the peer acknowledges the delta carrying the
+//! removal, see [`Context::acknowledge_delta`]. Meanwhile, adds and updates of these keys received
+//! from the peer are ignored, so that a peer which has not seen the removal yet does not resurrect
+//! them.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut worker = Context::new();
+//! let mut coordinator = Context::new();
+//!
+//! worker.insert("state".to_string(), Value::String("running".to_string()));
+//! let delta = worker.export_delta(cdumay_context::Checkpoint::default());
+//! coordinator.apply_delta(&delta).unwrap();
+//!
+//! let checkpoint = worker.checkpoint();
+//! worker.insert("state".to_string(), Value::String("done".to_string()));
+//! coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+//! assert_eq!(coordinator.get("state"), Some(&Value::String("done".to_string())));
+//! ```
+use crate::context::SyncState;
+use crate::{Checkpoint, Context, Contextualize, DeltaConflict};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The changes made to a context between two versions.
+///
+/// Field names are shortened and empty sets are omitted to keep the serialized form compact.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Delta {
+    /// Version of the exporting context the delta starts from.
+    #[serde(rename = "b")]
+    pub base: u64,
+    /// Version of the exporting context once the delta is applied.
+    #[serde(rename = "v")]
+    pub version: u64,
+    /// Keys created since `base`.
+    #[serde(rename = "a", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub adds: BTreeMap<String, serde_value::Value>,
+    /// Keys which existed at `base` and changed since.
+    #[serde(rename = "u", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub updates: BTreeMap<String, serde_value::Value>,
+    /// Keys which existed at `base` and were removed since.
+    #[serde(rename = "r", default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub removes: BTreeSet<String>,
+}
+
+impl Delta {
+    /// Returns `true` if the delta carries no change.
+    pub fn is_empty(&self) -> bool {
+        self.adds.is_empty() && self.updates.is_empty() && self.removes.is_empty()
+    }
+}
+
+impl Context {
+    /// Exports the changes made since `since` as a [`Delta`].
+    ///
+    /// Only stored entries are exported: deferred values are evaluated, but defaults, aliases and
+    /// transformers are left to the peer, and a removed key is exported as removed even if it has
+    /// a default.
+    pub fn export_delta(&self, since: Checkpoint) -> Delta {
+        let mut delta = Delta {
+            base: since.0,
+            version: self.revision,
+            ..Delta::default()
+        };
+        for key in self.dirty_keys(since) {
+            let created_after = self.created.get(&key).map(|rev| *rev > since.0);
+            let value = self
+                .data
+                .get(&key)
+                .cloned()
+                .or_else(|| self.deferred.get(&key).map(|deferred| deferred.evaluate()));
+            match (value, created_after) {
+                (Some(value), Some(true)) => {
+                    delta.adds.insert(key, value);
+                }
+                (Some(value), _) => {
+                    delta.updates.insert(key, value);
+                }
+                (None, _) => {
+                    delta.removes.insert(key);
+                }
+            }
+        }
+        delta
+    }
+
+    /// Applies a delta exported by a peer context.
+    ///
+    /// The delta is applied atomically: on conflict the context is left unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the delta was applied
+    /// * `Err(e)` containing a [`DeltaConflict`] error if the delta does not follow the last applied
+    ///   one, or if it touches keys modified locally since the last synchronization
+    ///
+    /// Adds and updates of keys removed locally before the last synchronization and not
+    /// acknowledged yet are ignored, the removal winning, see [`Context::acknowledge_delta`].
+    pub fn apply_delta(&mut self, delta: &Delta) -> cdumay_core::Result<()> {
+        let expected_base = self.sync.peer_version.unwrap_or(0);
+        if delta.base != expected_base {
+            return Err(self.delta_conflict(format!(
+                "Context delta starts at version {} but the last applied version is {}",
+                delta.base, expected_base
+            )));
+        }
+        let locally_modified = |key: &String| self.changes.get(key).is_some_and(|rev| *rev > self.sync.revision);
+        let mut conflicts: BTreeSet<&String> = BTreeSet::new();
+        conflicts.extend(
+            delta
+                .adds
+                .iter()
+                .filter(|(k, v)| !self.tombstones.contains_key(*k) && self.get(k).is_some_and(|local| local != *v))
+                .map(|(k, _)| k),
+        );
+        conflicts.extend(
+            delta
+                .updates
+                .iter()
+                .filter(|(k, v)| locally_modified(k) && self.get(k) != Some(*v))
+                .map(|(k, _)| k),
+        );
+        conflicts.extend(delta.removes.iter().filter(|k| locally_modified(k) && self.get(k).is_some()));
+        if !conflicts.is_empty() {
+            let keys: Vec<&str> = conflicts.into_iter().map(String::as_str).collect();
+            return Err(self.delta_conflict(format!("Context delta conflicts with local changes on key(s): {}", keys.join(", "))));
+        }
+        for (key, value) in delta.adds.iter().chain(delta.updates.iter()) {
+            if !self.tombstones.contains_key(key) {
+                self.insert(key.clone(), value.clone());
+            }
+        }
+        for key in &delta.removes {
+            if self.remove(key).is_some() {
+                self.tombstones.remove(key);
+            }
+        }
+        self.sync = SyncState {
+            peer_version: Some(delta.version),
+            revision: self.revision,
+        };
+        Ok(())
+    }
+
+    /// Returns the keys removed locally whose removal was not acknowledged by the peer yet, in key
+    /// order.
+    pub fn tombstones(&self) -> Vec<String> {
+        self.tombstones.keys().cloned().collect()
+    }
+
+    /// Records that the peer applied `delta`, exported by this context, dropping the tombstones of
+    /// the removals it carries.
+    ///
+    /// Returns the number of tombstones dropped.
+    pub fn acknowledge_delta(&mut self, delta: &Delta) -> usize {
+        let before = self.tombstones.len();
+        self.tombstones.retain(|_, rev| *rev > delta.version);
+        before - self.tombstones.len()
+    }
+
+    fn delta_conflict(&self, message: String) -> cdumay_core::Error {
+        DeltaConflict::new().with_message(message).with_details(self.error_details()).into()
+    }
+}
@@ -0,0 +1,146 @@
+//! Key deprecation.
+//!
+//! Platform teams steer the keys used across services through the shared crate:
+//! [`Context::deprecate_key`] marks a key as deprecated, so that every read or write of it through
+//! [`Contextualize::get`](crate::Contextualize::get), [`Contextualize::insert`](crate::Contextualize::insert)
+//! and the guarded inserts reports a [`DeprecationWarning`] to
+//! [`ContextMetrics::on_deprecated_key`](crate::ContextMetrics::on_deprecated_key). Reads and
+//! writes keep working, and each one is reported.
+//!
+//! [`Context::migrate_key`] also declares the deprecated key as an [alias](Context::alias_key)
+//! of its replacement, so that values are migrated as they are written.
+//!
+//! ```rust
+//! use cdumay_context::{set_context_metrics, Context, ContextMetrics, Contextualize, DeprecationWarning};
+//! use serde_value::Value;
+//! use std::sync::Mutex;
+//!
+//! struct Warnings(Mutex<Vec<String>>);
+//!
+//! impl ContextMetrics for Warnings {
+//!     fn on_deprecated_key(&self, warning: &DeprecationWarning) {
+//!         self.0.lock().unwrap().push(warning.to_string());
+//!     }
+//! }
+//!
+//! # let warnings = std::sync::Arc::new(Warnings(Mutex::new(Vec::new())));
+//! # set_context_metrics(warnings.clone());
+//! let mut ctx = Context::new();
+//! ctx.migrate_key("userId", "user_id", "renamed in 2.0");
+//! ctx.insert("userId".to_string(), Value::String("jane".to_string()));
+//! assert_eq!(ctx.get("user_id"), Some(&Value::String("jane".to_string())));
+//! # cdumay_context::clear_context_metrics();
+//! # assert_eq!(*warnings.0.lock().unwrap(), vec!["write of deprecated key 'userId', use 'user_id': renamed in 2.0"]);
+//! ```
+use crate::Context;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Deprecated key, see [`Context::deprecate_key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Deprecation {
+    message: String,
+    replacement: Option<String>,
+}
+
+/// Access to a deprecated key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAccess {
+    /// The value of the key was looked up.
+    Read,
+    /// A value was stored under the key.
+    Write,
+}
+
+/// A deprecated key was read or written, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationWarning {
+    /// Deprecated key, normalized.
+    pub key: String,
+    /// Message given when the key was deprecated.
+    pub message: String,
+    /// Key replacing it, if it is migrated.
+    pub replacement: Option<String>,
+    /// Kind of access.
+    pub access: KeyAccess,
+}
+
+impl fmt::Display for DeprecationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let access = match self.access {
+            KeyAccess::Read => "read",
+            KeyAccess::Write => "write",
+        };
+        write!(f, "{} of deprecated key '{}'", access, self.key)?;
+        if let Some(replacement) = &self.replacement {
+            write!(f, ", use '{}'", replacement)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl Context {
+    /// Marks `key` as deprecated, reads and writes of it being reported with `message`.
+    pub fn deprecate_key(&mut self, key: &str, message: &str) {
+        let key = self.key_policy().normalize(key).into_owned();
+        self.deprecations.insert(
+            key,
+            Deprecation {
+                message: message.to_string(),
+                replacement: None,
+            },
+        );
+    }
+
+    /// Marks `key` as deprecated in favor of `replacement`, and declares it as an alias of
+    /// `replacement`, see [`Context::alias_key`]: the value of `key`, if any, is moved, and
+    /// reads and writes of `key` are reported with `message` and go to `replacement`.
+    pub fn migrate_key(&mut self, key: &str, replacement: &str, message: &str) {
+        self.alias_key(key, replacement);
+        let key = self.key_policy().normalize(key).into_owned();
+        let replacement = self.resolve_key(replacement).into_owned();
+        if key != replacement {
+            self.deprecations.insert(
+                key,
+                Deprecation {
+                    message: message.to_string(),
+                    replacement: Some(replacement),
+                },
+            );
+        }
+    }
+
+    /// Returns `true` if `key` is deprecated.
+    pub fn is_deprecated(&self, key: &str) -> bool {
+        self.deprecations.contains_key(self.key_policy().normalize(key).as_ref())
+    }
+
+    /// Returns the deprecated keys and their messages, in order.
+    pub fn deprecated_keys(&self) -> Vec<(&str, &str)> {
+        self.deprecations
+            .iter()
+            .map(|(k, deprecation)| (k.as_str(), deprecation.message.as_str()))
+            .collect()
+    }
+
+    /// Reports `access` to `key` if it is deprecated.
+    pub(crate) fn warn_deprecated(&self, key: &str, access: KeyAccess) {
+        if self.deprecations.is_empty() {
+            return;
+        }
+        let key = self.key_policy().normalize(key);
+        if let Some(deprecation) = self.deprecations.get(key.as_ref()) {
+            crate::metrics::record(|metrics| {
+                metrics.on_deprecated_key(&DeprecationWarning {
+                    key: key.into_owned(),
+                    message: deprecation.message.clone(),
+                    replacement: deprecation.replacement.clone(),
+                    access,
+                })
+            });
+        }
+    }
+}
+
+/// Deprecated keys of a context.
+pub(crate) type Deprecations = BTreeMap<String, Deprecation>;
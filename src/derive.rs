@@ -0,0 +1,31 @@
+//! Support of the `ContextDump` derive macro, see [`ContextDump`](crate::ContextDump).
+//!
+//! The functions of this module are called by the generated code and are not part of the public
+//! API.
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Stores the serialization of `value` under `name`, skipping values which fail to serialize.
+pub fn dump_field<T: Serialize + ?Sized>(dump: &mut BTreeMap<String, Value>, name: &str, value: &T) {
+    if let Ok(value) = serde_value::to_value(value) {
+        dump.insert(name.to_string(), value);
+    }
+}
+
+/// Stores the entries of the serialization of `value`, skipping values which do not serialize as
+/// a map.
+pub fn dump_flatten<T: Serialize + ?Sized>(dump: &mut BTreeMap<String, Value>, value: &T) {
+    let Ok(Value::Map(map)) = serde_value::to_value(value) else { return };
+    for (k, v) in map {
+        let k = match k {
+            Value::String(k) => k,
+            Value::Char(c) => c.to_string(),
+            other => match crate::ValueExt::as_i64(&other) {
+                Some(n) => n.to_string(),
+                None => continue,
+            },
+        };
+        dump.insert(k, v);
+    }
+}
@@ -0,0 +1,128 @@
+//! Differences between two contexts.
+//!
+//! [`Context::diff`] compares the dumps of two contexts, such as snapshots taken at two points of
+//! a pipeline, and lists the keys added, removed and changed, with their old and new values.
+//! [`Context::apply`] replays a [`ContextDiff`] on another context. A diff serializes with serde
+//! and displays as one line per key, `+` for added keys, `-` for removed keys and `~` for changed
+//! keys, so that failed assertions on contexts show what changed.
+//!
+//! Unlike [`Context::compare`], which checks a context against an expected one with tolerances,
+//! a diff is exact and only looks at top-level keys: a change in a nested map is reported as a
+//! change of its top-level key.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut before = Context::new();
+//! before.insert("state".to_string(), Value::String("running".to_string()));
+//! before.insert("worker".to_string(), Value::U8(1));
+//!
+//! let mut after = before.clone();
+//! after.insert("state".to_string(), Value::String("done".to_string()));
+//! after.insert("exit_code".to_string(), Value::U8(0));
+//! after.remove("worker");
+//!
+//! let diff = before.diff(&after);
+//! assert_eq!(diff.to_string(), "+ exit_code: 0\n- worker: 1\n~ state: \"running\" -> \"done\"");
+//!
+//! before.apply(&diff);
+//! assert_eq!(before, after);
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Old and new values of a key changed between two contexts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange {
+    /// Value in the first context.
+    pub old: Value,
+    /// Value in the second context.
+    pub new: Value,
+}
+
+/// Keys added, removed and changed between two contexts, see the [module documentation](self).
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextDiff {
+    /// Keys only found in the second context, with their value.
+    pub added: BTreeMap<String, Value>,
+    /// Keys only found in the first context, with their value.
+    pub removed: BTreeMap<String, Value>,
+    /// Keys found in both contexts with different values.
+    pub changed: BTreeMap<String, ValueChange>,
+}
+
+impl ContextDiff {
+    /// Returns `true` if the contexts hold the same dump.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Returns the number of keys added, removed or changed.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+}
+
+impl fmt::Display for ContextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let added = self.added.iter().map(|(k, v)| format!("+ {}: {}", k, render(v)));
+        let removed = self.removed.iter().map(|(k, v)| format!("- {}: {}", k, render(v)));
+        let changed = self
+            .changed
+            .iter()
+            .map(|(k, change)| format!("~ {}: {} -> {}", k, render(&change.old), render(&change.new)));
+        write!(f, "{}", added.chain(removed).chain(changed).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+impl Context {
+    /// Returns the keys added, removed and changed from this context to `other`.
+    pub fn diff(&self, other: &Context) -> ContextDiff {
+        let mut before = self.inner();
+        let mut diff = ContextDiff::default();
+        for (k, new) in other.inner() {
+            match before.remove(&k) {
+                None => {
+                    diff.added.insert(k, new);
+                }
+                Some(old) if old != new => {
+                    diff.changed.insert(k, ValueChange { old, new });
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = before;
+        diff
+    }
+
+    /// Replays `diff`, storing the added and new values and removing the removed keys, whatever
+    /// the current values are.
+    ///
+    /// Like [`Contextualize::insert`] and [`Contextualize::remove`], protected and frozen keys are
+    /// left untouched.
+    pub fn apply(&mut self, diff: &ContextDiff) {
+        for k in diff.removed.keys() {
+            self.remove(k);
+        }
+        for (k, v) in &diff.added {
+            self.insert(k.clone(), v.clone());
+        }
+        for (k, change) in &diff.changed {
+            self.insert(k.clone(), change.new.clone());
+        }
+    }
+}
+
+/// Renders strings quoted and other values as in map keys.
+fn render(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        Value::Char(c) => format!("{:?}", c.to_string()),
+        other => stringify(other),
+    }
+}
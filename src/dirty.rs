@@ -0,0 +1,71 @@
+//! Tracking of the keys changed since a checkpoint.
+//!
+//! Every write to a [`Context`] is numbered. [`Context::checkpoint`] returns a marker which can
+//! later be passed to [`Context::dirty_since`] to get only the entries changed after it, so that
+//! periodic synchronizations do not resend the full map.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("env".to_string(), Value::String("prod".to_string()));
+//! let checkpoint = ctx.checkpoint();
+//!
+//! ctx.insert("step".to_string(), Value::U64(2));
+//! assert_eq!(ctx.dirty_keys(checkpoint), vec!["step".to_string()]);
+//! assert!(ctx.dirty_since(checkpoint).contains_key("step"));
+//! assert!(!ctx.dirty_since(checkpoint).contains_key("env"));
+//! ```
+use crate::{Context, Contextualize};
+#[cfg(feature = "json")]
+use cdumay_core::ErrorConverter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A marker of the state of a context at a given time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Checkpoint(pub u64);
+
+impl Context {
+    /// Returns a marker of the current state of the context.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.revision)
+    }
+
+    /// Returns the keys changed after `checkpoint`, in key order.
+    pub fn dirty_keys(&self, checkpoint: Checkpoint) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter(|(_, rev)| **rev > checkpoint.0)
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Returns the entries changed after `checkpoint`.
+    pub fn dirty_since(&self, checkpoint: Checkpoint) -> BTreeMap<String, serde_value::Value> {
+        let mut data = self.inner();
+        self.dirty_keys(checkpoint)
+            .into_iter()
+            .filter_map(|k| data.remove(&k).map(|v| (k, v)))
+            .collect()
+    }
+
+    /// Serializes the entries changed after `checkpoint` to a JSON string.
+    ///
+    /// This method is only available when the "json" feature is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `checkpoint` - The marker returned by a previous call to [`Context::checkpoint`]
+    /// * `pretty` - If true, the output will be pretty-printed with proper indentation
+    #[cfg(feature = "json")]
+    pub fn to_json_dirty(&self, checkpoint: Checkpoint, pretty: bool) -> cdumay_core::Result<String> {
+        let dirty = self.dirty_since(checkpoint);
+        let result = match pretty {
+            true => serde_json::to_string_pretty(&dirty),
+            false => serde_json::to_string(&dirty),
+        };
+        result.map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details()))
+    }
+}
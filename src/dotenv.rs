@@ -0,0 +1,206 @@
+//! `.env` files.
+//!
+//! [`Context::from_dotenv`] reads the `KEY=value` lines of a `.env` file, so that a context can
+//! serve as a single configuration object across configuration and environment files, and
+//! [`Context::to_dotenv`] writes it back. The syntax is the one of the common dotenv loaders:
+//!
+//! * blank lines and lines starting with `#` are ignored, as well as an `export ` prefix;
+//! * unquoted values are trimmed and end at the first ` #`, which starts a comment;
+//! * single-quoted values are taken literally;
+//! * double-quoted values may span several lines and support the `\n`, `\r`, `\t`, `\"`, `\\` and
+//!   `\$` escapes.
+//!
+//! All values are read as strings, variables such as `${HOME}` are not expanded. Keys keep their
+//! case.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let ctx = Context::from_dotenv("# database\nexport DB_HOST=localhost # local\nGREETING=\"hello\\nworld\"\n").unwrap();
+//! assert_eq!(ctx.get_str("DB_HOST").unwrap(), "localhost");
+//! assert_eq!(ctx.get_str("GREETING").unwrap(), "hello\nworld");
+//! assert_eq!(ctx.to_dotenv().unwrap(), "DB_HOST=localhost\nGREETING=\"hello\\nworld\"\n");
+//! ```
+use crate::{Context, Contextualize, TypeMismatch, ValidationError};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Creates a context from the content of a `.env` file, see the
+    /// [module documentation](self).
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` holding a string for each key, the last definition of a key winning
+    /// * `Err(e)` containing a [`TypeMismatch`] error naming the line of a line without `=`, of an
+    ///   empty key, of an unterminated quoted value or of trailing characters after a quoted value
+    pub fn from_dotenv(content: &str) -> cdumay_core::Result<Context> {
+        let mut data = BTreeMap::new();
+        let mut lines = content.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(parse_error(index, "expected KEY=value"));
+            };
+            let key = key.trim();
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return Err(parse_error(index, "invalid key"));
+            }
+            let value = value.trim_start();
+            let value = match value.chars().next() {
+                Some('\'') => {
+                    let (value, rest) = value[1..]
+                        .split_once('\'')
+                        .ok_or_else(|| parse_error(index, "unterminated single-quoted value"))?;
+                    check_trailing(index, rest)?;
+                    value.to_string()
+                }
+                Some('"') => {
+                    let mut quoted = value[1..].to_string();
+                    let (value, rest) = loop {
+                        if let Some(parsed) = unescape(&quoted) {
+                            break parsed;
+                        }
+                        let Some((_, next)) = lines.next() else {
+                            return Err(parse_error(index, "unterminated double-quoted value"));
+                        };
+                        quoted.push('\n');
+                        quoted.push_str(next);
+                    };
+                    check_trailing(index, &rest)?;
+                    value
+                }
+                _ => match value.find(" #") {
+                    Some(comment) => value[..comment].trim_end().to_string(),
+                    None => value.trim_end().to_string(),
+                },
+            };
+            data.insert(key.to_string(), Value::String(value));
+        }
+        let mut ctx = Context::new();
+        ctx.extend(data);
+        Ok(ctx)
+    }
+
+    /// Serializes the context as the content of a `.env` file, one `KEY=value` line per entry in
+    /// key order.
+    ///
+    /// Scalars are written as strings and null values as empty values. Values are double-quoted
+    /// when they hold characters other than letters, digits and `_-./:@+,`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(content)` on success
+    /// * `Err(e)` containing a [`ValidationError`] error if a value is a map, a sequence or bytes,
+    ///   or if a key is empty or holds whitespace, `=` or `#`
+    pub fn to_dotenv(&self) -> cdumay_core::Result<String> {
+        let mut out = String::new();
+        for (k, v) in self.inner() {
+            if k.is_empty() || k.contains(|c: char| c.is_whitespace() || c == '=' || c == '#') {
+                return Err(ValidationError::new()
+                    .with_message(format!("Key '{}' cannot be written to a .env file", k))
+                    .into());
+            }
+            let value = flat_string(&v).ok_or_else(|| -> cdumay_core::Error {
+                ValidationError::new()
+                    .with_message(format!("Value of '{}' cannot be written to a .env file as a flat string", k))
+                    .into()
+            })?;
+            out.push_str(&k);
+            out.push('=');
+            match value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:@+,".contains(c)) {
+                true => out.push_str(&value),
+                false => out.push_str(&quote(&value)),
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Returns the rendering of a scalar `value`, `None` for maps, sequences and bytes.
+fn flat_string(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Unit | Value::Option(None) => String::new(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return flat_string(inner),
+        Value::Seq(_) | Value::Map(_) | Value::Bytes(_) => return None,
+    })
+}
+
+/// Returns `value` double-quoted, with the characters special in double-quoted values escaped.
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '"' | '\\' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Reads a double-quoted value, opening quote excluded, returning the value and the remainder of
+/// the input after the closing quote, or `None` if the closing quote is missing.
+fn unescape(quoted: &str) -> Option<(String, String)> {
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, quoted[index + 1..].to_string())),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                c @ ('"' | '\\' | '$') => value.push(c),
+                c => {
+                    value.push('\\');
+                    value.push(c);
+                }
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Fails if `rest`, found after a quoted value, is neither blank nor a comment.
+fn check_trailing(index: usize, rest: &str) -> cdumay_core::Result<()> {
+    let rest = rest.trim_start();
+    match rest.is_empty() || rest.starts_with('#') {
+        true => Ok(()),
+        false => Err(parse_error(index, "unexpected characters after the quoted value")),
+    }
+}
+
+fn parse_error(index: usize, reason: &str) -> cdumay_core::Error {
+    TypeMismatch::new()
+        .with_message(format!("Failed to load .env content at line {}: {}", index + 1, reason))
+        .into()
+}
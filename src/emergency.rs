@@ -0,0 +1,275 @@
+//! Allocation-free emergency dump.
+//!
+//! [`Context::emergency_dump`] writes the context as compact JSON to a file descriptor without
+//! allocating on the heap, through a buffer on the stack and direct `write` calls, so that it can
+//! be called from a signal handler (`SIGSEGV`, `SIGABRT`, ...) where the allocator may be in an
+//! inconsistent state. To stay allocation-free, the dump differs from regular ones:
+//!
+//! * values are written as stored, without [transformers](crate::ValueTransformer);
+//! * deferred values are not evaluated, they are written as `"[deferred]"`;
+//! * [`Sensitivity::Confidential`](crate::Sensitivity::Confidential) and
+//!   [`Sensitivity::Secret`](crate::Sensitivity::Secret) values are written as `"[redacted]"`,
+//!   as well as values matching the [redacted keys](crate::ContextConfig::redact) of the context
+//!   configuration; those of the global configuration, behind a lock, are not applied, and values
+//!   whose path is longer than 256 bytes are redacted as soon as a pattern is set;
+//! * bytes are written as arrays of numbers, non-string map keys as strings, composite keys
+//!   holding their compact JSON.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use std::os::fd::AsFd;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("step".to_string(), serde_value::Value::String("install".to_string()));
+//! ctx.emergency_dump(std::io::stderr().as_fd()).unwrap();
+//! ```
+use crate::snapshot::glob_match;
+use crate::{Context, Sensitivity, REDACTED};
+use serde_value::Value;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::mem::ManuallyDrop;
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd};
+
+const BUFFER_LEN: usize = 1024;
+const PATH_LEN: usize = 256;
+
+impl Context {
+    /// Writes the context as a line of compact JSON to `fd`, without heap allocation.
+    ///
+    /// `fd` is left open.
+    pub fn emergency_dump(&self, fd: BorrowedFd<'_>) -> std::io::Result<()> {
+        // SAFETY: `fd` is open for the whole call, and the file is never dropped, so that it is
+        // not closed.
+        let file = ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(fd.as_raw_fd()) });
+        let mut out = StackWriter {
+            file: &file,
+            buffer: [0; BUFFER_LEN],
+            len: 0,
+            error: None,
+        };
+        let _ = self.write_emergency(&mut out);
+        out.flush();
+        match out.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn write_emergency(&self, out: &mut StackWriter) -> std::fmt::Result {
+        out.write_char('{')?;
+        let mut path = Path::new(&self.config.redacted);
+        let keys = self.data.keys().chain(self.deferred.keys().filter(|k| !self.data.contains_key(k)));
+        for (index, key) in keys.enumerate() {
+            if index > 0 {
+                out.write_char(',')?;
+            }
+            write_string(out, key)?;
+            out.write_char(':')?;
+            let state = path.push(key);
+            match (self.sensitivity.get(key), self.data.get(key)) {
+                (Some(Sensitivity::Confidential | Sensitivity::Secret), _) => write_string(out, REDACTED)?,
+                (_, Some(value)) => write_value(out, value, &mut path)?,
+                (_, None) => write_string(out, "[deferred]")?,
+            }
+            path.truncate(state);
+        }
+        out.write_str("}\n")
+    }
+}
+
+/// Buffers output on the stack, writing it to the file when full.
+struct StackWriter<'a> {
+    file: &'a std::fs::File,
+    buffer: [u8; BUFFER_LEN],
+    len: usize,
+    error: Option<std::io::Error>,
+}
+
+impl StackWriter<'_> {
+    fn flush(&mut self) {
+        if self.error.is_none() && self.len > 0 {
+            let mut file = self.file;
+            if let Err(err) = file.write_all(&self.buffer[..self.len]) {
+                self.error = Some(err);
+            }
+        }
+        self.len = 0;
+    }
+}
+
+impl std::fmt::Write for StackWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for chunk in s.as_bytes().chunks(BUFFER_LEN) {
+            if self.len + chunk.len() > BUFFER_LEN {
+                self.flush();
+            }
+            self.buffer[self.len..self.len + chunk.len()].copy_from_slice(chunk);
+            self.len += chunk.len();
+        }
+        match self.error {
+            Some(_) => Err(std::fmt::Error),
+            None => Ok(()),
+        }
+    }
+}
+
+fn write_value(out: &mut dyn std::fmt::Write, value: &Value, path: &mut Path) -> std::fmt::Result {
+    if path.is_redacted() {
+        return write_string(out, REDACTED);
+    }
+    match value {
+        Value::Bool(v) => write!(out, "{}", v),
+        Value::U8(v) => write!(out, "{}", v),
+        Value::U16(v) => write!(out, "{}", v),
+        Value::U32(v) => write!(out, "{}", v),
+        Value::U64(v) => write!(out, "{}", v),
+        Value::I8(v) => write!(out, "{}", v),
+        Value::I16(v) => write!(out, "{}", v),
+        Value::I32(v) => write!(out, "{}", v),
+        Value::I64(v) => write!(out, "{}", v),
+        Value::F32(v) if v.is_finite() => write!(out, "{}", v),
+        Value::F64(v) if v.is_finite() => write!(out, "{}", v),
+        Value::F32(_) | Value::F64(_) | Value::Unit | Value::Option(None) => out.write_str("null"),
+        Value::Char(v) => write_string(out, v.encode_utf8(&mut [0; 4])),
+        Value::String(v) => write_string(out, v),
+        Value::Option(Some(v)) | Value::Newtype(v) => write_value(out, v, path),
+        Value::Seq(values) => {
+            out.write_char('[')?;
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                let state = path.push(&index);
+                write_value(out, value, path)?;
+                path.truncate(state);
+            }
+            out.write_char(']')
+        }
+        Value::Bytes(bytes) => {
+            out.write_char('[')?;
+            for (index, byte) in bytes.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write!(out, "{}", byte)?;
+            }
+            out.write_char(']')
+        }
+        Value::Map(map) => {
+            out.write_char('{')?;
+            for (index, (key, value)) in map.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                out.write_char('"')?;
+                write_key(&mut Escaped(out), key)?;
+                out.write_str("\":")?;
+                let state = match key {
+                    Value::String(key) => path.push(key),
+                    key => path.push(&format_args!("{:?}", key)),
+                };
+                write_value(out, value, path)?;
+                path.truncate(state);
+            }
+            out.write_char('}')
+        }
+    }
+}
+
+/// Writes a map key as the text of a JSON string, composite keys as their compact JSON.
+fn write_key(out: &mut dyn std::fmt::Write, key: &Value) -> std::fmt::Result {
+    match key {
+        Value::String(key) => out.write_str(key),
+        Value::Char(key) => out.write_char(*key),
+        Value::F32(key) => write!(out, "{}", key),
+        Value::F64(key) => write!(out, "{}", key),
+        Value::Option(Some(key)) | Value::Newtype(key) => write_key(out, key),
+        key => write_value(out, key, &mut Path::new(&[])),
+    }
+}
+
+fn write_string(out: &mut dyn std::fmt::Write, s: &str) -> std::fmt::Result {
+    out.write_char('"')?;
+    Escaped(out).write_str(s)?;
+    out.write_char('"')
+}
+
+/// Escapes the text written to a JSON string.
+struct Escaped<'a>(&'a mut dyn std::fmt::Write);
+
+impl std::fmt::Write for Escaped<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.write_str("\\\"")?,
+                '\\' => self.0.write_str("\\\\")?,
+                '\n' => self.0.write_str("\\n")?,
+                '\r' => self.0.write_str("\\r")?,
+                '\t' => self.0.write_str("\\t")?,
+                c if (c as u32) < 0x20 => write!(self.0, "\\u{:04x}", c as u32)?,
+                c => self.0.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Dotted path of the value being written, built on the stack and matched against the redacted
+/// key patterns as [`ContextConfig`](crate::ContextConfig) does for regular dumps.
+struct Path<'a> {
+    patterns: &'a [String],
+    buffer: [u8; PATH_LEN],
+    len: usize,
+    overflow: bool,
+}
+
+impl<'a> Path<'a> {
+    fn new(patterns: &'a [String]) -> Self {
+        Self {
+            patterns,
+            buffer: [0; PATH_LEN],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Appends `segment` to the path, returning the state to restore with [`Path::truncate`].
+    fn push(&mut self, segment: &dyn std::fmt::Display) -> (usize, bool) {
+        let state = (self.len, self.overflow);
+        if !self.patterns.is_empty() {
+            if self.len > 0 {
+                let _ = self.write_char('.');
+            }
+            let _ = write!(self, "{}", segment);
+        }
+        state
+    }
+
+    fn truncate(&mut self, (len, overflow): (usize, bool)) {
+        self.len = len;
+        self.overflow = overflow;
+    }
+
+    /// Returns `true` if the value at this path is redacted, paths too long to be matched being
+    /// redacted.
+    fn is_redacted(&self) -> bool {
+        !self.patterns.is_empty()
+            && (self.overflow
+                || std::str::from_utf8(&self.buffer[..self.len]).is_ok_and(|path| self.patterns.iter().any(|pattern| glob_match(pattern, path))))
+    }
+}
+
+impl std::fmt::Write for Path<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self.len + s.len() > PATH_LEN {
+            true => self.overflow = true,
+            false if !self.overflow => {
+                self.buffer[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+                self.len += s.len();
+            }
+            false => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Emission hooks.
+//!
+//! Consent and compliance rules, such as stripping fields for some tenants, belong in one place
+//! rather than at every sink call site. Hooks installed with [`before_emit`] receive the dump of a
+//! context about to be emitted and return a [`Decision`]: let it through, replace it, or stop it.
+//! Hooks run in installation order, each one receiving the dump returned by the previous ones.
+//!
+//! Emission is done in two phases: [`Context::prepare_emit`] dumps the context and runs the hooks,
+//! returning the dump to hand to the sink, or `None` if a hook denied it. [`Context::emit`] goes
+//! through it, and sinks built outside of the lifecycle should too.
+//!
+//! ```rust
+//! use cdumay_context::{before_emit, clear_emit_hooks, Context, Contextualize, Decision};
+//! use serde_value::Value;
+//!
+//! before_emit(|dump| match dump.get("tenant") {
+//!     Some(Value::String(tenant)) if tenant.starts_with("eu-") => {
+//!         let mut dump = dump.clone();
+//!         dump.remove("client_ip");
+//!         Decision::Modify(dump)
+//!     }
+//!     _ => Decision::Allow,
+//! });
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("tenant".to_string(), Value::String("eu-west".to_string()));
+//! ctx.insert("client_ip".to_string(), Value::String("10.0.0.1".to_string()));
+//! let dump = ctx.prepare_emit().unwrap();
+//! assert!(!dump.contains_key("client_ip"));
+//! clear_emit_hooks();
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Hook deciding what becomes of a dump about to be emitted, see [`before_emit`].
+type EmitHook = Arc<dyn Fn(&BTreeMap<String, Value>) -> Decision + Send + Sync>;
+
+/// Hooks installed with [`before_emit`], in installation order.
+static HOOKS: RwLock<Vec<EmitHook>> = RwLock::new(Vec::new());
+
+/// What an emission hook decides for a dump, see [`before_emit`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// The dump is emitted as it is.
+    Allow,
+    /// The dump is not emitted.
+    Deny,
+    /// The given dump is emitted instead.
+    Modify(BTreeMap<String, Value>),
+}
+
+/// Installs `hook` for the whole process, after the hooks installed before.
+pub fn before_emit<F>(hook: F)
+where
+    F: Fn(&BTreeMap<String, Value>) -> Decision + Send + Sync + 'static,
+{
+    HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner()).push(Arc::new(hook));
+}
+
+/// Removes the hooks installed with [`before_emit`].
+pub fn clear_emit_hooks() {
+    HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+}
+
+impl Context {
+    /// Dumps the context and runs the hooks installed with [`before_emit`] on the dump.
+    ///
+    /// Returns the dump to emit, or `None` if a hook denied the emission, in which case the
+    /// following hooks are not run.
+    pub fn prepare_emit(&self) -> Option<BTreeMap<String, Value>> {
+        let hooks = HOOKS.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        let mut dump = self.inner();
+        for hook in hooks {
+            match hook(&dump) {
+                Decision::Allow => {}
+                Decision::Deny => return None,
+                Decision::Modify(modified) => dump = modified,
+            }
+        }
+        Some(dump)
+    }
+}
@@ -0,0 +1,125 @@
+//! In-place updates.
+//!
+//! [`Context::get_mut`] and [`Context::entry`] update a value where it is stored, instead of
+//! reading it, cloning it and inserting it back, so that counters and lists are updated in one
+//! step. Like [`Contextualize::insert`], they leave protected and frozen keys and sealed contexts
+//! untouched, and a key modified in place is recorded as changed for dirty tracking.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! for _ in 0..3 {
+//!     ctx.entry("retries")
+//!         .and_modify(|retries| {
+//!             if let Value::U64(n) = retries {
+//!                 *n += 1;
+//!             }
+//!         })
+//!         .or_insert(Value::U64(1));
+//! }
+//! assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+//!
+//! if let Value::Seq(items) = ctx.entry("tags").or_insert(Value::Seq(vec![])) {
+//!     items.push(Value::String("slow".to_string()));
+//! }
+//! assert_eq!(ctx.get("tags"), Some(&Value::Seq(vec![Value::String("slow".to_string())])));
+//! ```
+use crate::{Context, Contextualize, KeyAccess};
+use serde_value::Value;
+
+impl Context {
+    /// Returns a mutable reference to the value stored under `k`.
+    ///
+    /// A deferred value is evaluated and stored in place of its function. Default values, see
+    /// [`Context::register_default`], are not stored values.
+    ///
+    /// Returns `None` if no value is stored under `k`, or if `k` is protected or frozen, or the
+    /// context sealed.
+    pub fn get_mut(&mut self, k: &str) -> Option<&mut Value> {
+        self.warn_deprecated(k, KeyAccess::Write);
+        self.value_mut(k)
+    }
+
+    fn value_mut(&mut self, k: &str) -> Option<&mut Value> {
+        let k = self.resolve_key(k).into_owned();
+        if !self.contains(&k) || self.is_locked(&k) {
+            return None;
+        }
+        if let Some(deferred) = self.deferred.remove(&k) {
+            self.data.insert(k.clone(), deferred.evaluate());
+        }
+        self.touch(&k);
+        self.data.get_mut(&k)
+    }
+
+    /// Returns the entry of `k`, for in-place updates, see the [module documentation](self).
+    pub fn entry(&mut self, k: &str) -> ContextEntry<'_> {
+        ContextEntry {
+            ctx: self,
+            key: k.to_string(),
+        }
+    }
+}
+
+/// Entry of a context, returned by [`Context::entry`].
+#[derive(Debug)]
+pub struct ContextEntry<'a> {
+    ctx: &'a mut Context,
+    key: String,
+}
+
+impl<'a> ContextEntry<'a> {
+    /// Returns the key of the entry, as given to [`Context::entry`].
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns `true` if a value, deferred or not, is stored under the key.
+    pub fn is_occupied(&self) -> bool {
+        self.ctx.contains(&self.ctx.resolve_key(&self.key))
+    }
+
+    /// Calls `f` with the value stored under the key, if any and if the key can be modified.
+    pub fn and_modify<F: FnOnce(&mut Value)>(self, f: F) -> Self {
+        if let Some(value) = self.ctx.get_mut(&self.key) {
+            f(value);
+        }
+        self
+    }
+
+    /// Stores `default` under the key if no value is stored, and returns a mutable reference to
+    /// the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is protected or frozen, or the context sealed, as
+    /// [`IndexMut`](std::ops::IndexMut) does.
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.or_insert_with(|| default)
+    }
+
+    /// Stores the result of `f` under the key if no value is stored, and returns a mutable
+    /// reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is protected or frozen, or the context sealed, see
+    /// [`ContextEntry::or_insert`].
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, f: F) -> &'a mut Value {
+        let value = match self.is_occupied() {
+            true => self.ctx.get_mut(&self.key),
+            false => {
+                self.ctx.insert(self.key.clone(), f());
+                self.ctx.value_mut(&self.key)
+            }
+        };
+        value.unwrap_or_else(|| panic!("key '{}' is protected", self.key))
+    }
+
+    /// Stores a unit value if no value is stored, see [`ContextEntry::or_insert`].
+    pub fn or_default(self) -> &'a mut Value {
+        self.or_insert_with(|| Value::Unit)
+    }
+}
@@ -0,0 +1,178 @@
+//! Capture of environment variables.
+//!
+//! [`Context::from_env`] seeds a context from the environment of the process, one key per
+//! variable. With a prefix such as `MYAPP` or `MYAPP_`, only the variables starting with `MYAPP_`
+//! are imported, under their name stripped of it. [`EnvOptions`] controls the
+//! case of the keys (lowercase by default), the opt-in coercion of values to booleans and numbers,
+//! and the variables skipped because their name looks sensitive (see [`ENV_SENSITIVE_PATTERNS`]).
+//! Variables whose name or value is not valid UTF-8 are skipped.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, EnvOptions};
+//! use serde_value::Value;
+//!
+//! std::env::set_var("MYAPP_LOG_LEVEL", "debug");
+//! std::env::set_var("MYAPP_WORKERS", "4");
+//! std::env::set_var("MYAPP_DB_PASSWORD", "s3cr3t");
+//!
+//! let ctx = Context::from_env(Some("MYAPP"));
+//! assert_eq!(ctx.get_str("log_level").unwrap(), "debug");
+//! assert_eq!(ctx.get_str("workers").unwrap(), "4");
+//! assert!(!ctx.contains_key("db_password"));
+//!
+//! let ctx = Context::from_env_with(Some("MYAPP"), &EnvOptions::new().with_coercion(true));
+//! assert_eq!(ctx.get("workers"), Some(&Value::I64(4)));
+//! ```
+use crate::snapshot::glob_match;
+use crate::{Context, Contextualize};
+use serde_value::Value;
+
+/// Variable name patterns skipped by [`Context::from_env`].
+///
+/// Patterns are matched against the lowercased name, prefix included, and may contain `*`
+/// wildcards.
+pub const ENV_SENSITIVE_PATTERNS: &[&str] = &[
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*token*",
+    "*api_key*",
+    "*apikey*",
+    "*credential*",
+    "*private_key*",
+];
+
+/// Case of the keys of the variables imported by [`Context::from_env_with`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCase {
+    /// Keys are lowercased (default).
+    #[default]
+    Lower,
+    /// Keys are uppercased.
+    Upper,
+    /// Keys keep the case of the variable names.
+    Preserve,
+}
+
+/// Options of [`Context::from_env_with`].
+#[derive(Debug, Clone)]
+pub struct EnvOptions {
+    key_case: KeyCase,
+    coercion: bool,
+    sensitive: Vec<String>,
+}
+
+impl Default for EnvOptions {
+    fn default() -> Self {
+        Self {
+            key_case: KeyCase::default(),
+            coercion: false,
+            sensitive: ENV_SENSITIVE_PATTERNS.iter().map(|pattern| pattern.to_string()).collect(),
+        }
+    }
+}
+
+impl EnvOptions {
+    /// Creates options importing values as strings under lowercase keys, skipping the variables
+    /// matching [`ENV_SENSITIVE_PATTERNS`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the case of the keys.
+    pub fn with_key_case(mut self, key_case: KeyCase) -> Self {
+        self.key_case = key_case;
+        self
+    }
+
+    /// Enables the coercion of values: `true` and `false` become booleans, integers `I64` (or
+    /// `U64` above `i64::MAX`) and finite decimal numbers `F64`. Other values stay strings.
+    pub fn with_coercion(mut self, coercion: bool) -> Self {
+        self.coercion = coercion;
+        self
+    }
+
+    /// Replaces the patterns of the variable names to skip, see [`ENV_SENSITIVE_PATTERNS`].
+    pub fn with_sensitive(mut self, patterns: &[&str]) -> Self {
+        self.sensitive = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        self
+    }
+
+    /// Returns the key and the value under which the variable `name` is imported, if it is.
+    fn entry(&self, prefix: Option<&str>, name: &str, value: &str) -> Option<(String, Value)> {
+        let key = match prefix {
+            Some(prefix) if prefix.is_empty() || prefix.ends_with('_') => name.strip_prefix(prefix)?,
+            Some(prefix) => name.strip_prefix(prefix)?.strip_prefix('_')?,
+            None => name,
+        };
+        let lowercase = name.to_lowercase();
+        if key.is_empty() || self.sensitive.iter().any(|pattern| glob_match(pattern, &lowercase)) {
+            return None;
+        }
+        let key = match self.key_case {
+            KeyCase::Lower => key.to_lowercase(),
+            KeyCase::Upper => key.to_uppercase(),
+            KeyCase::Preserve => key.to_string(),
+        };
+        let value = match self.coercion {
+            true => coerce(value),
+            false => Value::String(value.to_string()),
+        };
+        Some((key, value))
+    }
+}
+
+impl Context {
+    /// Creates a context from the environment variables starting with `prefix`, or all of them,
+    /// with the default [`EnvOptions`].
+    pub fn from_env(prefix: Option<&str>) -> Context {
+        Self::from_env_with(prefix, &EnvOptions::default())
+    }
+
+    /// Creates a context from the environment variables starting with `prefix`, or all of them.
+    pub fn from_env_with(prefix: Option<&str>, options: &EnvOptions) -> Context {
+        let mut ctx = Context::new();
+        ctx.extend_from_env_with(prefix, options);
+        ctx
+    }
+
+    /// Inserts the environment variables starting with `prefix`, or all of them, with the default
+    /// [`EnvOptions`].
+    pub fn extend_from_env(&mut self, prefix: Option<&str>) {
+        self.extend_from_env_with(prefix, &EnvOptions::default())
+    }
+
+    /// Inserts the environment variables starting with `prefix`, or all of them.
+    ///
+    /// Values are stored with [`Contextualize::insert`], so that protected keys are left untouched.
+    pub fn extend_from_env_with(&mut self, prefix: Option<&str>, options: &EnvOptions) {
+        for (name, value) in std::env::vars_os() {
+            let (Some(name), Some(value)) = (name.to_str(), value.to_str()) else {
+                continue;
+            };
+            if let Some((k, v)) = options.entry(prefix, name, value) {
+                self.insert(k, v);
+            }
+        }
+    }
+}
+
+/// Returns `value` as a boolean or a number if it reads as one, as a string otherwise.
+pub(crate) fn coerce(value: &str) -> Value {
+    match value {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(number) = value.parse::<i64>() {
+        return Value::I64(number);
+    }
+    if let Ok(number) = value.parse::<u64>() {
+        return Value::U64(number);
+    }
+    let decimal = value.bytes().all(|c| c.is_ascii_digit() || matches!(c, b'.' | b'-' | b'+' | b'e' | b'E'));
+    match value.parse::<f64>() {
+        Ok(number) if decimal && number.is_finite() => Value::F64(number),
+        _ => Value::String(value.to_string()),
+    }
+}
@@ -2,8 +2,63 @@ use cdumay_core::{define_errors, define_kinds};
 
 define_kinds! {
     GenericContextError = (500, "Generic context error"),
+    ContextValueError = (400, "Invalid context value"),
+    ProtectedKeyError = (409, "Protected context key"),
+    FrozenKeyError = (409, "Frozen context key"),
+    DeltaConflictError = (409, "Context delta conflict"),
+    NotFoundError = (404, "Not found"),
+    ConflictError = (409, "Conflict"),
+    TimeoutError = (504, "Timeout"),
+    UnauthorizedError = (401, "Unauthorized"),
+    InvalidStateError = (409, "Invalid context state"),
+    PrecisionLossError = (400, "Numeric precision loss"),
+    InvalidMapKeyError = (400, "Invalid map key"),
+    FeatureDisabledError = (501, "Feature disabled"),
+    YamlExpansionLimitError = (413, "YAML expansion limit exceeded"),
+    QuotaExceededError = (429, "Quota exceeded"),
+    ContextValidationError = (422, "Context validation error"),
+    ContextSerializationError = (500, "Context serialization error"),
+    SizeLimitExceededError = (413, "Size limit exceeded"),
 }
 
 define_errors! {
-    UnExpectedError = GenericContextError
+    UnExpectedError = GenericContextError,
+    TypeMismatch = ContextValueError,
+    ProtectedKey = ProtectedKeyError,
+    FrozenKey = FrozenKeyError,
+    DeltaConflict = DeltaConflictError,
+    KeyNotFound = NotFoundError,
+    Conflict = ConflictError,
+    Timeout = TimeoutError,
+    Unauthorized = UnauthorizedError,
+    InvalidState = InvalidStateError,
+    PrecisionLoss = PrecisionLossError,
+    InvalidMapKey = InvalidMapKeyError,
+    FeatureDisabled = FeatureDisabledError,
+    YamlExpansionLimit = YamlExpansionLimitError,
+    QuotaExceeded = QuotaExceededError,
+    SerializationError = ContextSerializationError,
+    ValidationError = ContextValidationError,
+    SizeLimitExceeded = SizeLimitExceededError,
+}
+
+crate::impl_with_context! {
+    UnExpectedError,
+    TypeMismatch,
+    ProtectedKey,
+    FrozenKey,
+    DeltaConflict,
+    KeyNotFound,
+    Conflict,
+    Timeout,
+    Unauthorized,
+    InvalidState,
+    PrecisionLoss,
+    InvalidMapKey,
+    FeatureDisabled,
+    YamlExpansionLimit,
+    QuotaExceeded,
+    SerializationError,
+    ValidationError,
+    SizeLimitExceeded,
 }
@@ -0,0 +1,141 @@
+//! Error surface of the crate.
+//!
+//! Every fallible function of the crate returns a `cdumay_core::Error`, built from one of the
+//! error types re-exported here. The [`ContextError`] trait classifies these errors, as well as
+//! the serialization errors of the `cdumay_json`, `cdumay_toml` and `cdumay_yaml` converters, into
+//! the stable [`ContextErrorKind`] categories, so that callers can branch on a failure without
+//! parsing error classes:
+//!
+//! ```rust
+//! use cdumay_context::errors::{ContextError, ContextErrorKind};
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let ctx = Context::new();
+//! let err = ctx.get_str("missing").unwrap_err();
+//! assert_eq!(err.kind(), ContextErrorKind::KeyNotFound);
+//! assert_eq!(ContextError::code(&err), 404);
+//! ```
+//!
+//! Errors declared downstream with [`define_context_errors!`](crate::define_context_errors) are
+//! classified as [`ContextErrorKind::Other`].
+pub use crate::error::{
+    Conflict, ConflictError, ContextSerializationError, ContextValidationError, ContextValueError, DeltaConflict, DeltaConflictError,
+    FeatureDisabled, FeatureDisabledError, FrozenKey, FrozenKeyError, GenericContextError, InvalidMapKey, InvalidMapKeyError, InvalidState,
+    InvalidStateError, KeyNotFound, NotFoundError, PrecisionLoss, PrecisionLossError, ProtectedKey, ProtectedKeyError, QuotaExceeded,
+    QuotaExceededError, SerializationError, SizeLimitExceeded, SizeLimitExceededError, Timeout, TimeoutError, TypeMismatch, UnExpectedError,
+    Unauthorized, UnauthorizedError, ValidationError, YamlExpansionLimit, YamlExpansionLimitError,
+};
+
+/// Category of an error, see [`ContextError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContextErrorKind {
+    /// A key or an entry is missing ([`KeyNotFound`]).
+    KeyNotFound,
+    /// A value does not have the expected type or cannot be converted without loss
+    /// ([`TypeMismatch`], [`PrecisionLoss`]).
+    TypeMismatch,
+    /// A value or a file name is not accepted, or a context does not match its schema
+    /// ([`ValidationError`], [`InvalidMapKey`]).
+    Validation,
+    /// A context failed to be read or written by a format backend ([`SerializationError`]).
+    Serialization,
+    /// A key cannot be changed or accessed ([`ProtectedKey`], [`FrozenKey`], [`Unauthorized`]).
+    Access,
+    /// The operation conflicts with the state of the context ([`Conflict`], [`DeltaConflict`],
+    /// [`InvalidState`]).
+    Conflict,
+    /// A limit was reached ([`Timeout`], [`QuotaExceeded`], [`YamlExpansionLimit`],
+    /// [`SizeLimitExceeded`]).
+    Limits,
+    /// The operation needs a feature which is not enabled ([`FeatureDisabled`]).
+    Unsupported,
+    /// An unexpected failure, such as an I/O error ([`UnExpectedError`]).
+    Internal,
+    /// An error which does not come from this crate.
+    Other,
+}
+
+/// Accessors shared by the errors of the crate.
+pub trait ContextError {
+    /// Returns the category of the error.
+    fn kind(&self) -> ContextErrorKind;
+
+    /// Returns the numerical code of the error, an HTTP status code.
+    fn code(&self) -> u16;
+
+    /// Returns the context key the error is about, if it was created with `for_key`, see
+    /// [`KeyNotFound::for_key`].
+    fn key(&self) -> Option<String>;
+}
+
+impl ContextError for cdumay_core::Error {
+    fn kind(&self) -> ContextErrorKind {
+        kind_of(self.class().split("::").nth(1).unwrap_or_default())
+    }
+
+    fn code(&self) -> u16 {
+        cdumay_core::Error::code(self)
+    }
+
+    fn key(&self) -> Option<String> {
+        crate::error_key_of(self.details_ref())
+    }
+}
+
+/// Returns the category of the errors of the kind `kind`, as found in error classes.
+fn kind_of(kind: &str) -> ContextErrorKind {
+    match kind {
+        "NotFoundError" => ContextErrorKind::KeyNotFound,
+        "InvalidMapKeyError" | "ContextValidationError" => ContextErrorKind::Validation,
+        "ContextValueError" | "PrecisionLossError" => ContextErrorKind::TypeMismatch,
+        "JsonSyntax" | "JsonData" | "JsonEof" | "JsonIo" | "TomlData" | "YamlData" | "ContextSerializationError" => ContextErrorKind::Serialization,
+        "ProtectedKeyError" | "FrozenKeyError" | "UnauthorizedError" => ContextErrorKind::Access,
+        "ConflictError" | "DeltaConflictError" | "InvalidStateError" => ContextErrorKind::Conflict,
+        "TimeoutError" | "QuotaExceededError" | "YamlExpansionLimitError" | "SizeLimitExceededError" => ContextErrorKind::Limits,
+        "FeatureDisabledError" => ContextErrorKind::Unsupported,
+        "GenericContextError" => ContextErrorKind::Internal,
+        _ => ContextErrorKind::Other,
+    }
+}
+
+macro_rules! impl_context_error {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl ContextError for $name {
+                fn kind(&self) -> ContextErrorKind {
+                    kind_of($name::kind.name())
+                }
+
+                fn code(&self) -> u16 {
+                    $name::code(self)
+                }
+
+                fn key(&self) -> Option<String> {
+                    $name::key(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_context_error! {
+    UnExpectedError,
+    TypeMismatch,
+    ProtectedKey,
+    FrozenKey,
+    DeltaConflict,
+    Conflict,
+    Timeout,
+    Unauthorized,
+    InvalidState,
+    PrecisionLoss,
+    InvalidMapKey,
+    FeatureDisabled,
+    YamlExpansionLimit,
+    QuotaExceeded,
+    KeyNotFound,
+    SerializationError,
+    ValidationError,
+    SizeLimitExceeded,
+}
@@ -0,0 +1,84 @@
+//! OpenMetrics exemplar labels.
+//!
+//! An OpenMetrics exemplar carries a tiny label set: the label names and values together must not
+//! exceed [`EXEMPLAR_LABELS_LIMIT`] UTF-8 characters. [`Context::to_exemplar_labels`] selects the
+//! scalar entries of the context fitting a budget, in [priority](crate::Priority) order, so that
+//! error counters carry the trace and request identifiers of the context.
+//!
+//! Keys are turned into valid label names by replacing the characters outside `[a-zA-Z0-9_]` with
+//! `_`, and prefixing a leading digit with `_`. Maps, sequences, bytes and null values are skipped,
+//! as well as entries which do not fit the remaining budget.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Priority};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("trace.id".to_string(), Value::String("4bf92f3577b34da6a3ce929d0e0e4736".to_string()));
+//! ctx.insert("payload".to_string(), Value::String("x".repeat(200)));
+//! ctx.insert("attempt".to_string(), Value::U8(2));
+//! ctx.set_priority("trace.id", Priority::Critical);
+//!
+//! let labels = ctx.to_exemplar_labels(128);
+//! assert_eq!(labels, vec![
+//!     ("trace_id".to_string(), "4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+//!     ("attempt".to_string(), "2".to_string()),
+//! ]);
+//! ```
+use crate::Context;
+use serde_value::Value;
+
+/// Maximum number of UTF-8 characters of the label names and values of an OpenMetrics exemplar.
+pub const EXEMPLAR_LABELS_LIMIT: usize = 128;
+
+impl Context {
+    /// Returns the exemplar labels of the context, whose names and values together take at most
+    /// `max_len` UTF-8 characters, capped to [`EXEMPLAR_LABELS_LIMIT`].
+    ///
+    /// Labels are returned in the order of [`Context::ordered_entries`], higher priorities first.
+    pub fn to_exemplar_labels(&self, max_len: usize) -> Vec<(String, String)> {
+        let mut budget = max_len.min(EXEMPLAR_LABELS_LIMIT);
+        let mut labels: Vec<(String, String)> = Vec::new();
+        for (k, v) in self.ordered_entries() {
+            let Some(value) = label_value(&v) else { continue };
+            let name = label_name(&k);
+            let len = name.chars().count() + value.chars().count();
+            if len > budget || labels.iter().any(|(existing, _)| *existing == name) {
+                continue;
+            }
+            budget -= len;
+            labels.push((name, value));
+        }
+        labels
+    }
+}
+
+/// Returns `k` as a label name matching `[a-zA-Z_][a-zA-Z0-9_]*`.
+fn label_name(k: &str) -> String {
+    let name: String = k.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    match name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        true => format!("_{}", name),
+        false => name,
+    }
+}
+
+/// Returns the rendering of a scalar `value`, `None` for other values.
+fn label_value(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return label_value(inner),
+        Value::Unit | Value::Option(None) | Value::Seq(_) | Value::Map(_) | Value::Bytes(_) => return None,
+    })
+}
@@ -0,0 +1,82 @@
+//! Panicking accessors with context-aware messages.
+//!
+//! [`Context::expect_key`] and [`Context::unwrap_get`] are meant for tests and prototypes, where
+//! panicking is acceptable but a blind `unwrap()` on [`Contextualize::get`] would hide the state
+//! of the context. Their panic messages include a dump of the context, where
+//! [`Sensitivity::Confidential`] and [`Sensitivity::Secret`] values are redacted, truncated to
+//! [`PANIC_DUMP_LIMIT`] characters.
+//!
+//! ```rust,should_panic
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), serde_value::Value::String("abc".to_string()));
+//!
+//! // panics with: missing key `user_id` in context: { "request_id": "abc" }
+//! ctx.expect_key("user_id");
+//! ```
+use crate::snapshot::write_value;
+use crate::{Context, Contextualize, Sensitivity, SnapshotOptions};
+use serde::de::DeserializeOwned;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Maximum number of characters of the context dump included in panic messages.
+pub const PANIC_DUMP_LIMIT: usize = 1024;
+
+impl Context {
+    /// Returns the value stored under `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is missing, with a redacted dump of the context in the message.
+    #[track_caller]
+    pub fn expect_key(&self, k: &str) -> &Value {
+        match self.get(k) {
+            Some(value) => value,
+            None => panic!("missing key `{}` in context: {}", k, self.panic_dump()),
+        }
+    }
+
+    /// Returns the value stored under `k`, deserialized into `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is missing or its value cannot be deserialized into `T`, with a redacted
+    /// dump of the context in the message.
+    #[track_caller]
+    pub fn unwrap_get<T: DeserializeOwned>(&self, k: &str) -> T {
+        match self.expect_key(k).clone().deserialize_into() {
+            Ok(value) => value,
+            Err(err) => panic!(
+                "invalid value for key `{}` in context ({}: {}): {}",
+                k,
+                std::any::type_name::<T>(),
+                err,
+                self.panic_dump()
+            ),
+        }
+    }
+
+    pub(crate) fn panic_dump(&self) -> String {
+        let map: BTreeMap<Value, Value> = self
+            .inner()
+            .into_iter()
+            .map(|(k, v)| {
+                let v = match self.sensitivity(&k) {
+                    Sensitivity::Confidential | Sensitivity::Secret => Value::String("[redacted]".to_string()),
+                    _ => v,
+                };
+                (Value::String(k), v)
+            })
+            .collect();
+        let options = SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+        let mut out = String::new();
+        write_value(&mut out, "", &Value::Map(map), &options, 0);
+        let out = out.lines().map(str::trim_start).collect::<Vec<&str>>().join(" ");
+        match out.char_indices().nth(PANIC_DUMP_LIMIT) {
+            Some((index, _)) => format!("{}... (truncated)", &out[..index]),
+            None => out,
+        }
+    }
+}
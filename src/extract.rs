@@ -0,0 +1,114 @@
+//! Typed extraction and injection of whole contexts.
+//!
+//! [`Context::extract`] deserializes the whole context into a struct, and [`Context::extract_at`]
+//! the subtree stored under a key or a dotted path. [`Context::inject`] goes the other way,
+//! serializing a struct and storing each of its fields as an entry, so that typed code can hand
+//! its state to the context without building values by hand.
+//!
+//! Unlike [`Context::project`], extraction stops at the first failed field; unlike
+//! [`Context::section`], a missing key is an error.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Request {
+//!     request_id: String,
+//!     attempt: u8,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Db {
+//!     host: String,
+//! }
+//!
+//! let mut ctx = Context::new();
+//! ctx.inject(&Request { request_id: "abc".to_string(), attempt: 2 }).unwrap();
+//! ctx.insert_path("db.host", serde_value::Value::String("db-1".to_string())).unwrap();
+//!
+//! assert_eq!(ctx.get_str("request_id").unwrap(), "abc");
+//! assert_eq!(ctx.extract::<Request>().unwrap(), Request { request_id: "abc".to_string(), attempt: 2 });
+//! assert_eq!(ctx.extract_at::<Db>("db").unwrap(), Db { host: "db-1".to_string() });
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize, KeyNotFound, TypeMismatch, UnExpectedError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_value::Value;
+
+impl Context {
+    /// Deserializes the dump of the context into `T`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<T>` which is:
+    /// * `Ok(value)` if the context matches `T`
+    /// * `Err(e)` containing a [`TypeMismatch`] error if it does not
+    pub fn extract<T: DeserializeOwned>(&self) -> cdumay_core::Result<T> {
+        let map = self.inner().into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+        T::deserialize(Value::Map(map)).map_err(|err| {
+            TypeMismatch::new()
+                .with_message(format!("Failed to extract {} from context: {}", std::any::type_name::<T>(), err))
+                .with_details(self.error_details())
+                .into()
+        })
+    }
+
+    /// Deserializes the value stored under `key`, or under the dotted path `key`, into `T`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<T>` which is:
+    /// * `Ok(value)` if the value matches `T`
+    /// * `Err(e)` containing a [`KeyNotFound`] error if there is no value under `key`
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the value does not match `T`
+    pub fn extract_at<T: DeserializeOwned>(&self, key: &str) -> cdumay_core::Result<T> {
+        let Some(value) = self.get(key).or_else(|| self.get_path(key)) else {
+            return Err(KeyNotFound::new()
+                .with_message(format!("Context key '{}' not found", key))
+                .with_details(self.error_details())
+                .into());
+        };
+        T::deserialize(value.clone()).map_err(|err| {
+            self.mismatch(format!(
+                "Failed to extract {} from context key '{}': {}",
+                std::any::type_name::<T>(),
+                key,
+                err
+            ))
+        })
+    }
+
+    /// Serializes `value` and stores each of its fields as an entry, replacing the previous
+    /// values of these keys. Non-string map keys are rendered as strings.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the fields were stored
+    /// * `Err(e)` containing an [`UnExpectedError`] if `value` cannot be serialized
+    /// * `Err(e)` containing a [`TypeMismatch`] error if `value` is not serialized as a map, as
+    ///   structs are
+    pub fn inject<T: Serialize>(&mut self, value: &T) -> cdumay_core::Result<()> {
+        let serialized = serde_value::to_value(value).map_err(|err| {
+            UnExpectedError::new()
+                .with_message(format!("Failed to serialize {} into context: {}", std::any::type_name::<T>(), err))
+                .with_details(self.error_details())
+        })?;
+        match unwrap(serialized) {
+            Value::Map(map) => {
+                self.extend(map.into_iter().map(|(k, v)| (stringify(&k), v)).collect());
+                Ok(())
+            }
+            _ => Err(self.mismatch(format!("Cannot inject {} into context: not a map", std::any::type_name::<T>()))),
+        }
+    }
+}
+
+fn unwrap(value: Value) -> Value {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => unwrap(*inner),
+        value => value,
+    }
+}
@@ -0,0 +1,55 @@
+//! Integration with [figment](https://docs.rs/figment) configuration stacks.
+//!
+//! A [`Context`] is a `figment::Provider`, so it can be merged as a layer of a `Figment`. As for
+//! the other exports, only the entries visible at [`Sensitivity::Internal`] are provided.
+//! [`Context::from_figment`] goes the other way and extracts the resolved configuration of a
+//! `Figment` into a context.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use figment::Figment;
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("workers".to_string(), Value::U8(4));
+//!
+//! let figment = Figment::new().merge(&ctx);
+//! assert_eq!(figment.extract_inner::<u8>("workers").unwrap(), 4);
+//!
+//! let ctx = Context::from_figment(&figment).unwrap();
+//! assert!(ctx.get("workers").is_some());
+//! ```
+use crate::{Context, ContextDump, Contextualize, Sensitivity, TypeMismatch};
+use figment::providers::Serialized;
+use figment::value::{Dict, Map};
+use figment::{Figment, Metadata, Profile, Provider};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Creates a context holding every top-level entry of the configuration resolved by `figment`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(Context)` - The context
+    /// * `Err(e)` - A `TypeMismatch` error if the configuration cannot be extracted
+    pub fn from_figment(figment: &Figment) -> cdumay_core::Result<Context> {
+        let data = figment
+            .extract::<BTreeMap<String, Value>>()
+            .map_err(|err| TypeMismatch::new().with_message(format!("Failed to extract configuration: {}", err)))?;
+        let mut ctx = Context::new();
+        ctx.extend(data);
+        Ok(ctx)
+    }
+}
+
+impl Provider for Context {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("cdumay_context::Context")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        Serialized::defaults(self.view(Sensitivity::Internal).dump()).data()
+    }
+}
@@ -0,0 +1,57 @@
+//! Feature flag state capture.
+//!
+//! A [`FlagProvider`] exposes the state of the feature flags of the application, whatever system
+//! manages them, and [`Context::capture_flags`] stores it under the [`FLAGS_KEY`] key, so that error
+//! contexts show which flags were active (`flags.new_checkout`, ...). Any closure returning the
+//! flags is a provider:
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use std::collections::BTreeMap;
+//!
+//! let provider = || BTreeMap::from([("new_checkout".to_string(), true)]);
+//! let ctx = Context::new().capture_flags(&provider);
+//! assert_eq!(ctx.at("flags").at("new_checkout").as_bool(), Some(true));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which [`Context::capture_flags`] stores the feature flags.
+pub const FLAGS_KEY: &str = crate::keys::FLAGS;
+
+/// Source of feature flag states.
+pub trait FlagProvider {
+    /// Returns the state of each feature flag, by name.
+    fn flags(&self) -> BTreeMap<String, bool>;
+}
+
+impl<F> FlagProvider for F
+where
+    F: Fn() -> BTreeMap<String, bool>,
+{
+    fn flags(&self) -> BTreeMap<String, bool> {
+        self()
+    }
+}
+
+impl FlagProvider for BTreeMap<String, bool> {
+    fn flags(&self) -> BTreeMap<String, bool> {
+        self.clone()
+    }
+}
+
+impl Context {
+    /// Stores the flags returned by `provider`, if any, under the [`FLAGS_KEY`] key.
+    pub fn capture_flags<P: FlagProvider + ?Sized>(mut self, provider: &P) -> Self {
+        let flags: BTreeMap<Value, Value> = provider
+            .flags()
+            .into_iter()
+            .map(|(name, enabled)| (Value::String(name), Value::Bool(enabled)))
+            .collect();
+        if !flags.is_empty() {
+            self.insert(FLAGS_KEY.to_string(), Value::Map(flags));
+        }
+        self
+    }
+}
@@ -0,0 +1,541 @@
+//! FlatBuffers encoding of contexts.
+//!
+//! [`Context::to_flatbuffer`] encodes the dump of a context following the schema shipped in
+//! `schema/context.fbs`, so that a process in any language can read it with the accessors `flatc`
+//! generates. [`FlatContextView::from_flatbuffer`] verifies such a buffer once and then reads it in
+//! place: looking up a key or a nested value does not copy or allocate, which suits sidecars
+//! reading contexts from shared memory or a ring buffer.
+//!
+//! Entries are sorted by key, map keys being rendered as strings, so that lookups are binary
+//! searches. Integers are widened to 64 bits, floats to `f64` and characters to strings, so
+//! [`FlatContextView::to_context`] may not give back the exact `serde_value::Value` variants.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, FlatContextView};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("attempt".to_string(), Value::U8(3));
+//!
+//! let bytes = ctx.to_flatbuffer();
+//! let view = FlatContextView::from_flatbuffer(&bytes).unwrap();
+//! assert_eq!(view.get("request_id").and_then(|v| v.as_str()), Some("abc"));
+//! assert_eq!(view.get("attempt").and_then(|v| v.as_u64()), Some(3));
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize, TypeMismatch};
+use flatbuffers::{FlatBufferBuilder, WIPOffset};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// File identifier of the buffers produced by [`Context::to_flatbuffer`].
+pub const FLATBUFFER_IDENTIFIER: &str = "CDCX";
+
+/// Tables of `schema/context.fbs`, written as `flatc --rust` would generate them.
+mod fb {
+    use flatbuffers::{FlatBufferBuilder, Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector, Verifiable, Verifier, WIPOffset};
+
+    pub const KIND_NULL: u8 = 0;
+    pub const KIND_BOOL: u8 = 1;
+    pub const KIND_UNSIGNED: u8 = 2;
+    pub const KIND_SIGNED: u8 = 3;
+    pub const KIND_FLOAT: u8 = 4;
+    pub const KIND_STRING: u8 = 5;
+    pub const KIND_BYTES: u8 = 6;
+    pub const KIND_SEQ: u8 = 7;
+    pub const KIND_MAP: u8 = 8;
+
+    pub type Entries<'a> = Vector<'a, ForwardsUOffset<Entry<'a>>>;
+    pub type Items<'a> = Vector<'a, ForwardsUOffset<Value<'a>>>;
+
+    #[derive(Copy, Clone)]
+    pub struct Value<'a> {
+        _tab: Table<'a>,
+    }
+
+    impl<'a> Follow<'a> for Value<'a> {
+        type Inner = Value<'a>;
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: unsafe { Table::new(buf, loc) },
+            }
+        }
+    }
+
+    impl<'a> Value<'a> {
+        pub const VT_KIND: VOffsetT = 4;
+        pub const VT_BOOLEAN: VOffsetT = 6;
+        pub const VT_UNSIGNED: VOffsetT = 8;
+        pub const VT_SIGNED: VOffsetT = 10;
+        pub const VT_FLOAT: VOffsetT = 12;
+        pub const VT_STRING: VOffsetT = 14;
+        pub const VT_BYTES: VOffsetT = 16;
+        pub const VT_ITEMS: VOffsetT = 18;
+        pub const VT_ENTRIES: VOffsetT = 20;
+
+        // Safety: the accessors below are only reached through verified buffers.
+        pub fn kind(&self) -> u8 {
+            unsafe { self._tab.get::<u8>(Self::VT_KIND, Some(KIND_NULL)).unwrap() }
+        }
+        pub fn boolean(&self) -> bool {
+            unsafe { self._tab.get::<bool>(Self::VT_BOOLEAN, Some(false)).unwrap() }
+        }
+        pub fn unsigned(&self) -> u64 {
+            unsafe { self._tab.get::<u64>(Self::VT_UNSIGNED, Some(0)).unwrap() }
+        }
+        pub fn signed(&self) -> i64 {
+            unsafe { self._tab.get::<i64>(Self::VT_SIGNED, Some(0)).unwrap() }
+        }
+        pub fn float(&self) -> f64 {
+            unsafe { self._tab.get::<f64>(Self::VT_FLOAT, Some(0.0)).unwrap() }
+        }
+        pub fn string(&self) -> Option<&'a str> {
+            unsafe { self._tab.get::<ForwardsUOffset<&str>>(Self::VT_STRING, None) }
+        }
+        pub fn bytes(&self) -> Option<Vector<'a, u8>> {
+            unsafe { self._tab.get::<ForwardsUOffset<Vector<'a, u8>>>(Self::VT_BYTES, None) }
+        }
+        pub fn items(&self) -> Option<Items<'a>> {
+            unsafe { self._tab.get::<ForwardsUOffset<Items<'a>>>(Self::VT_ITEMS, None) }
+        }
+        pub fn entries(&self) -> Option<Entries<'a>> {
+            unsafe { self._tab.get::<ForwardsUOffset<Entries<'a>>>(Self::VT_ENTRIES, None) }
+        }
+    }
+
+    impl Verifiable for Value<'_> {
+        fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+            v.visit_table(pos)?
+                .visit_field::<u8>("kind", Self::VT_KIND, false)?
+                .visit_field::<bool>("boolean", Self::VT_BOOLEAN, false)?
+                .visit_field::<u64>("unsigned", Self::VT_UNSIGNED, false)?
+                .visit_field::<i64>("signed", Self::VT_SIGNED, false)?
+                .visit_field::<f64>("float", Self::VT_FLOAT, false)?
+                .visit_field::<ForwardsUOffset<&str>>("string", Self::VT_STRING, false)?
+                .visit_field::<ForwardsUOffset<Vector<'_, u8>>>("bytes", Self::VT_BYTES, false)?
+                .visit_field::<ForwardsUOffset<Items<'_>>>("items", Self::VT_ITEMS, false)?
+                .visit_field::<ForwardsUOffset<Entries<'_>>>("entries", Self::VT_ENTRIES, false)?
+                .finish();
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    pub struct ValueArgs<'a> {
+        pub kind: u8,
+        pub boolean: bool,
+        pub unsigned: u64,
+        pub signed: i64,
+        pub float: f64,
+        pub string: Option<WIPOffset<&'a str>>,
+        pub bytes: Option<WIPOffset<Vector<'a, u8>>>,
+        pub items: Option<WIPOffset<Items<'a>>>,
+        pub entries: Option<WIPOffset<Entries<'a>>>,
+    }
+
+    impl<'a> Value<'a> {
+        pub fn create<'bldr: 'a>(fbb: &mut FlatBufferBuilder<'bldr>, args: ValueArgs<'bldr>) -> WIPOffset<Value<'bldr>> {
+            let start = fbb.start_table();
+            fbb.push_slot::<u64>(Self::VT_UNSIGNED, args.unsigned, 0);
+            fbb.push_slot::<i64>(Self::VT_SIGNED, args.signed, 0);
+            fbb.push_slot::<f64>(Self::VT_FLOAT, args.float, 0.0);
+            if let Some(x) = args.string {
+                fbb.push_slot_always(Self::VT_STRING, x);
+            }
+            if let Some(x) = args.bytes {
+                fbb.push_slot_always(Self::VT_BYTES, x);
+            }
+            if let Some(x) = args.items {
+                fbb.push_slot_always(Self::VT_ITEMS, x);
+            }
+            if let Some(x) = args.entries {
+                fbb.push_slot_always(Self::VT_ENTRIES, x);
+            }
+            fbb.push_slot::<bool>(Self::VT_BOOLEAN, args.boolean, false);
+            fbb.push_slot::<u8>(Self::VT_KIND, args.kind, KIND_NULL);
+            WIPOffset::new(fbb.end_table(start).value())
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct Entry<'a> {
+        _tab: Table<'a>,
+    }
+
+    impl<'a> Follow<'a> for Entry<'a> {
+        type Inner = Entry<'a>;
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: unsafe { Table::new(buf, loc) },
+            }
+        }
+    }
+
+    impl<'a> Entry<'a> {
+        pub const VT_KEY: VOffsetT = 4;
+        pub const VT_VALUE: VOffsetT = 6;
+
+        pub fn key(&self) -> &'a str {
+            unsafe { self._tab.get::<ForwardsUOffset<&str>>(Self::VT_KEY, None).unwrap() }
+        }
+        pub fn value(&self) -> Option<Value<'a>> {
+            unsafe { self._tab.get::<ForwardsUOffset<Value>>(Self::VT_VALUE, None) }
+        }
+
+        pub fn create<'bldr: 'a>(
+            fbb: &mut FlatBufferBuilder<'bldr>,
+            key: WIPOffset<&'bldr str>,
+            value: WIPOffset<Value<'bldr>>,
+        ) -> WIPOffset<Entry<'bldr>> {
+            let start = fbb.start_table();
+            fbb.push_slot_always(Self::VT_VALUE, value);
+            fbb.push_slot_always(Self::VT_KEY, key);
+            let end = fbb.end_table(start);
+            fbb.required(end, Self::VT_KEY, "key");
+            WIPOffset::new(end.value())
+        }
+    }
+
+    impl Verifiable for Entry<'_> {
+        fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+            v.visit_table(pos)?
+                .visit_field::<ForwardsUOffset<&str>>("key", Self::VT_KEY, true)?
+                .visit_field::<ForwardsUOffset<Value>>("value", Self::VT_VALUE, false)?
+                .finish();
+            Ok(())
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct Context<'a> {
+        _tab: Table<'a>,
+    }
+
+    impl<'a> Follow<'a> for Context<'a> {
+        type Inner = Context<'a>;
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: unsafe { Table::new(buf, loc) },
+            }
+        }
+    }
+
+    impl<'a> Context<'a> {
+        pub const VT_ENTRIES: VOffsetT = 4;
+
+        pub fn entries(&self) -> Option<Entries<'a>> {
+            unsafe { self._tab.get::<ForwardsUOffset<Entries<'a>>>(Self::VT_ENTRIES, None) }
+        }
+
+        pub fn create<'bldr: 'a>(fbb: &mut FlatBufferBuilder<'bldr>, entries: WIPOffset<Entries<'bldr>>) -> WIPOffset<Context<'bldr>> {
+            let start = fbb.start_table();
+            fbb.push_slot_always(Self::VT_ENTRIES, entries);
+            WIPOffset::new(fbb.end_table(start).value())
+        }
+    }
+
+    impl Verifiable for Context<'_> {
+        fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+            v.visit_table(pos)?
+                .visit_field::<ForwardsUOffset<Entries<'_>>>("entries", Self::VT_ENTRIES, false)?
+                .finish();
+            Ok(())
+        }
+    }
+}
+
+impl Context {
+    /// Encodes the dump of the context as a FlatBuffer, see the [module documentation](self).
+    ///
+    /// This method is only available when the "flatbuffers" feature is enabled.
+    pub fn to_flatbuffer(&self) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let entries = create_entries(&mut fbb, self.inner_ref().iter().map(|(k, v)| (k.clone(), v)).collect());
+        let root = fb::Context::create(&mut fbb, entries);
+        fbb.finish(root, Some(FLATBUFFER_IDENTIFIER));
+        fbb.finished_data().to_vec()
+    }
+}
+
+fn create_entries<'bldr>(fbb: &mut FlatBufferBuilder<'bldr>, entries: BTreeMap<String, &Value>) -> WIPOffset<fb::Entries<'bldr>> {
+    let entries: Vec<_> = entries
+        .into_iter()
+        .map(|(k, v)| {
+            let value = create_value(fbb, v);
+            let key = fbb.create_string(&k);
+            fb::Entry::create(fbb, key, value)
+        })
+        .collect();
+    fbb.create_vector(&entries)
+}
+
+fn create_value<'bldr>(fbb: &mut FlatBufferBuilder<'bldr>, value: &Value) -> WIPOffset<fb::Value<'bldr>> {
+    let args = match value {
+        Value::Bool(b) => fb::ValueArgs {
+            kind: fb::KIND_BOOL,
+            boolean: *b,
+            ..Default::default()
+        },
+        Value::U8(n) => unsigned(u64::from(*n)),
+        Value::U16(n) => unsigned(u64::from(*n)),
+        Value::U32(n) => unsigned(u64::from(*n)),
+        Value::U64(n) => unsigned(*n),
+        Value::I8(n) => signed(i64::from(*n)),
+        Value::I16(n) => signed(i64::from(*n)),
+        Value::I32(n) => signed(i64::from(*n)),
+        Value::I64(n) => signed(*n),
+        Value::F32(n) => fb::ValueArgs {
+            kind: fb::KIND_FLOAT,
+            float: f64::from(*n),
+            ..Default::default()
+        },
+        Value::F64(n) => fb::ValueArgs {
+            kind: fb::KIND_FLOAT,
+            float: *n,
+            ..Default::default()
+        },
+        Value::Char(c) => fb::ValueArgs {
+            kind: fb::KIND_STRING,
+            string: Some(fbb.create_string(&c.to_string())),
+            ..Default::default()
+        },
+        Value::String(s) => fb::ValueArgs {
+            kind: fb::KIND_STRING,
+            string: Some(fbb.create_string(s)),
+            ..Default::default()
+        },
+        Value::Bytes(bytes) => fb::ValueArgs {
+            kind: fb::KIND_BYTES,
+            bytes: Some(fbb.create_vector(bytes)),
+            ..Default::default()
+        },
+        Value::Unit | Value::Option(None) => fb::ValueArgs::default(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return create_value(fbb, inner),
+        Value::Seq(items) => {
+            let items: Vec<_> = items.iter().map(|item| create_value(fbb, item)).collect();
+            fb::ValueArgs {
+                kind: fb::KIND_SEQ,
+                items: Some(fbb.create_vector(&items)),
+                ..Default::default()
+            }
+        }
+        Value::Map(map) => {
+            let entries = create_entries(fbb, map.iter().map(|(k, v)| (stringify(k), v)).collect());
+            fb::ValueArgs {
+                kind: fb::KIND_MAP,
+                entries: Some(entries),
+                ..Default::default()
+            }
+        }
+    };
+    fb::Value::create(fbb, args)
+}
+
+fn unsigned<'a>(n: u64) -> fb::ValueArgs<'a> {
+    fb::ValueArgs {
+        kind: fb::KIND_UNSIGNED,
+        unsigned: n,
+        ..Default::default()
+    }
+}
+
+fn signed<'a>(n: i64) -> fb::ValueArgs<'a> {
+    fb::ValueArgs {
+        kind: fb::KIND_SIGNED,
+        signed: n,
+        ..Default::default()
+    }
+}
+
+/// Zero-copy, read-only view of a context encoded with [`Context::to_flatbuffer`].
+#[derive(Clone, Copy)]
+pub struct FlatContextView<'a> {
+    entries: Option<fb::Entries<'a>>,
+}
+
+impl<'a> FlatContextView<'a> {
+    /// Verifies `bytes` and returns a view reading the context in place.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<FlatContextView>` which is:
+    /// * `Ok(view)` if `bytes` hold a context encoded with [`Context::to_flatbuffer`]
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the identifier is missing or the buffer
+    ///   is not valid
+    pub fn from_flatbuffer(bytes: &'a [u8]) -> cdumay_core::Result<FlatContextView<'a>> {
+        if !flatbuffers::buffer_has_identifier(bytes, FLATBUFFER_IDENTIFIER, false) {
+            return Err(decode_error(format!("missing {} file identifier", FLATBUFFER_IDENTIFIER)));
+        }
+        let root = flatbuffers::root::<fb::Context>(bytes).map_err(|err| decode_error(err.to_string()))?;
+        Ok(FlatContextView { entries: root.entries() })
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.map(|entries| entries.len()).unwrap_or(0)
+    }
+
+    /// Returns `true` if the context has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value under `key`.
+    pub fn get(&self, key: &str) -> Option<FlatValue<'a>> {
+        lookup(self.entries, key)
+    }
+
+    /// Iterates over the entries, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, FlatValue<'a>)> {
+        iter_entries(self.entries)
+    }
+
+    /// Decodes the whole view into a new context.
+    pub fn to_context(&self) -> Context {
+        let mut ctx = Context::new();
+        ctx.extend(self.iter().map(|(k, v)| (k.to_string(), v.to_value())).collect());
+        ctx
+    }
+}
+
+/// Value read in place from a [`FlatContextView`].
+///
+/// Accessors return `None` when the value is of another kind.
+#[derive(Clone, Copy)]
+pub struct FlatValue<'a> {
+    value: Option<fb::Value<'a>>,
+}
+
+impl<'a> FlatValue<'a> {
+    fn kind(&self) -> u8 {
+        self.value.map(|value| value.kind()).unwrap_or(fb::KIND_NULL)
+    }
+
+    /// Returns `true` for null values.
+    pub fn is_null(&self) -> bool {
+        self.kind() == fb::KIND_NULL
+    }
+
+    /// Returns the boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        self.value.filter(|value| value.kind() == fb::KIND_BOOL).map(|value| value.boolean())
+    }
+
+    /// Returns the unsigned integer, or the signed one if it is not negative.
+    pub fn as_u64(&self) -> Option<u64> {
+        let value = self.value?;
+        match value.kind() {
+            fb::KIND_UNSIGNED => Some(value.unsigned()),
+            fb::KIND_SIGNED => u64::try_from(value.signed()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the signed integer, or the unsigned one if it fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        let value = self.value?;
+        match value.kind() {
+            fb::KIND_SIGNED => Some(value.signed()),
+            fb::KIND_UNSIGNED => i64::try_from(value.unsigned()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the float.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.value.filter(|value| value.kind() == fb::KIND_FLOAT).map(|value| value.float())
+    }
+
+    /// Returns the string, borrowed from the buffer.
+    pub fn as_str(&self) -> Option<&'a str> {
+        self.value
+            .filter(|value| value.kind() == fb::KIND_STRING)
+            .map(|value| value.string().unwrap_or_default())
+    }
+
+    /// Returns the bytes, borrowed from the buffer.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        self.value
+            .filter(|value| value.kind() == fb::KIND_BYTES)
+            .map(|value| value.bytes().map(|bytes| bytes.bytes()).unwrap_or_default())
+    }
+
+    /// Iterates over the items of a sequence.
+    pub fn items(&self) -> Option<impl Iterator<Item = FlatValue<'a>>> {
+        let value = self.value.filter(|value| value.kind() == fb::KIND_SEQ)?;
+        Some(
+            value
+                .items()
+                .into_iter()
+                .flat_map(|items| items.iter())
+                .map(|item| FlatValue { value: Some(item) }),
+        )
+    }
+
+    /// Iterates over the entries of a map, in key order.
+    pub fn entries(&self) -> Option<impl Iterator<Item = (&'a str, FlatValue<'a>)>> {
+        let value = self.value.filter(|value| value.kind() == fb::KIND_MAP)?;
+        Some(iter_entries(value.entries()))
+    }
+
+    /// Returns the value under `key` in a map.
+    pub fn get(&self, key: &str) -> Option<FlatValue<'a>> {
+        let value = self.value.filter(|value| value.kind() == fb::KIND_MAP)?;
+        lookup(value.entries(), key)
+    }
+
+    /// Decodes the value, integers as `U64` or `I64`, floats as `F64` and nulls as `Unit`.
+    pub fn to_value(&self) -> Value {
+        let Some(value) = self.value else {
+            return Value::Unit;
+        };
+        match value.kind() {
+            fb::KIND_BOOL => Value::Bool(value.boolean()),
+            fb::KIND_UNSIGNED => Value::U64(value.unsigned()),
+            fb::KIND_SIGNED => Value::I64(value.signed()),
+            fb::KIND_FLOAT => Value::F64(value.float()),
+            fb::KIND_STRING => Value::String(self.as_str().unwrap_or_default().to_string()),
+            fb::KIND_BYTES => Value::Bytes(self.as_bytes().unwrap_or_default().to_vec()),
+            fb::KIND_SEQ => Value::Seq(self.items().into_iter().flatten().map(|item| item.to_value()).collect()),
+            fb::KIND_MAP => Value::Map(
+                self.entries()
+                    .into_iter()
+                    .flatten()
+                    .map(|(k, v)| (Value::String(k.to_string()), v.to_value()))
+                    .collect(),
+            ),
+            _ => Value::Unit,
+        }
+    }
+}
+
+impl std::fmt::Debug for FlatValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_value().fmt(f)
+    }
+}
+
+impl std::fmt::Debug for FlatContextView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+fn lookup<'a>(entries: Option<fb::Entries<'a>>, key: &str) -> Option<FlatValue<'a>> {
+    let entry = entries?.lookup_by_key(key, |entry, key| entry.key().cmp(key))?;
+    Some(FlatValue { value: entry.value() })
+}
+
+fn iter_entries<'a>(entries: Option<fb::Entries<'a>>) -> impl Iterator<Item = (&'a str, FlatValue<'a>)> {
+    entries
+        .into_iter()
+        .flat_map(|entries| entries.iter())
+        .map(|entry| (entry.key(), FlatValue { value: entry.value() }))
+}
+
+fn decode_error(message: String) -> cdumay_core::Error {
+    TypeMismatch::new()
+        .with_message(format!("Failed to decode flatbuffers context: {}", message))
+        .into()
+}
@@ -0,0 +1,134 @@
+//! Write protection of context keys.
+//!
+//! Protected keys, such as tracing identifiers, can be set once but are not overwritten by later
+//! calls to [`Contextualize::insert`] or [`Contextualize::extend`]. [`Context::try_insert`] and
+//! [`Context::try_extend`] report the conflict as a [`ProtectedKey`] error, while
+//! [`Context::force_insert`] explicitly overwrites the value.
+//!
+//! Frozen keys go further: once [`Context::freeze_key`] or [`Context::insert_once`] was called,
+//! the value never changes again, not even through [`Context::force_insert`], and write attempts
+//! through the `try_*` methods fail with a [`FrozenKey`] error.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.protect_key("request_id");
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!
+//! // Upstream payloads cannot clobber the tracing key...
+//! ctx.insert("request_id".to_string(), Value::String("evil".to_string()));
+//! assert_eq!(ctx.get("request_id"), Some(&Value::String("abc".to_string())));
+//! assert!(ctx.try_insert("request_id".to_string(), Value::String("evil".to_string())).is_err());
+//!
+//! // ...unless explicitly forced.
+//! ctx.force_insert("request_id".to_string(), Value::String("def".to_string()));
+//! assert_eq!(ctx.get("request_id"), Some(&Value::String("def".to_string())));
+//! ```
+use crate::{Context, Contextualize, FrozenKey, KeyAccess, ProtectedKey};
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Declares `k` as protected: once set, its value is only replaced by [`Context::force_insert`].
+    pub fn protect_key(&mut self, k: &str) {
+        let k = self.resolve_key(k).into_owned();
+        self.protected.insert(k);
+    }
+
+    /// Returns `true` if `k` was declared as protected.
+    pub fn is_protected(&self, k: &str) -> bool {
+        self.protected.contains(self.resolve_key(k).as_ref())
+    }
+
+    /// Inserts a key-value pair, failing if it would overwrite a protected key.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the value was inserted
+    /// * `Err(e)` containing a [`ProtectedKey`] error if the key is protected and already set
+    /// * `Err(e)` containing an [`InvalidState`](crate::InvalidState) error if the context is sealed
+    pub fn try_insert(&mut self, k: String, v: serde_value::Value) -> cdumay_core::Result<()> {
+        self.check_writable([k.as_str()])?;
+        self.insert(k, v);
+        Ok(())
+    }
+
+    /// Extends the context, failing without any change if a protected key would be overwritten.
+    pub fn try_extend(&mut self, data: BTreeMap<String, serde_value::Value>) -> cdumay_core::Result<()> {
+        self.check_writable(data.keys().map(String::as_str))?;
+        self.extend(data);
+        Ok(())
+    }
+
+    /// Inserts a key-value pair, overwriting the value even if the key is protected.
+    ///
+    /// Frozen keys are left untouched.
+    pub fn force_insert(&mut self, k: String, v: serde_value::Value) {
+        self.warn_deprecated(&k, KeyAccess::Write);
+        let k = self.resolve_key(&k).into_owned();
+        self.insert_unchecked(k, v);
+    }
+
+    /// Inserts a key-value pair only if the key is not set yet, then freezes it.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the value was inserted
+    /// * `Err(e)` containing a [`FrozenKey`] error if the key already has a value
+    /// * `Err(e)` containing an [`InvalidState`](crate::InvalidState) error if the context is sealed
+    pub fn insert_once(&mut self, k: String, v: serde_value::Value) -> cdumay_core::Result<()> {
+        self.warn_deprecated(&k, KeyAccess::Write);
+        self.check_not_sealed()?;
+        let k = self.resolve_key(&k).into_owned();
+        if self.contains(&k) {
+            return Err(FrozenKey::new()
+                .with_message(format!("Context key '{}' can only be set once", k))
+                .with_details(self.error_details())
+                .into());
+        }
+        self.insert_unchecked(k.clone(), v);
+        self.frozen.insert(k);
+        Ok(())
+    }
+
+    /// Freezes the current value of `k` so that it can never change again.
+    ///
+    /// Returns `false` if the key has no value, in which case nothing is frozen.
+    pub fn freeze_key(&mut self, k: &str) -> bool {
+        let k = self.resolve_key(k).into_owned();
+        if !self.contains(&k) {
+            return false;
+        }
+        self.frozen.insert(k);
+        true
+    }
+
+    /// Returns `true` if the value of `k` is frozen.
+    pub fn is_frozen(&self, k: &str) -> bool {
+        self.frozen.contains(self.resolve_key(k).as_ref())
+    }
+
+    pub(crate) fn check_writable<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> cdumay_core::Result<()> {
+        self.check_not_sealed()?;
+        let locked: Vec<String> = keys
+            .into_iter()
+            .map(|k| self.resolve_key(k).into_owned())
+            .filter(|k| self.is_locked(k))
+            .collect();
+        let frozen: Vec<&str> = locked.iter().map(String::as_str).filter(|k| self.frozen.contains(*k)).collect();
+        match (locked.is_empty(), frozen.is_empty()) {
+            (true, _) => Ok(()),
+            (false, false) => Err(FrozenKey::new()
+                .with_message(format!("Cannot modify frozen context key(s): {}", frozen.join(", ")))
+                .with_details(self.error_details())
+                .into()),
+            (false, true) => Err(ProtectedKey::new()
+                .with_message(format!("Cannot overwrite protected context key(s): {}", locked.join(", ")))
+                .with_details(self.error_details())
+                .into()),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! HTTP request and response summaries.
+//!
+//! [`Context::record_http_request`] and [`Context::record_http_response`] store a standardized
+//! summary of an HTTP exchange under the [`HTTP_KEY`] key, as `http.request` and `http.response`
+//! maps. Header names are lowercased, headers matching [`SENSITIVE_HEADERS`] are redacted, and
+//! only the first [`HTTP_HEADERS_LIMIT`] headers are kept, each value truncated to
+//! [`HTTP_VALUE_LIMIT`] characters, so that summaries never leak credentials nor grow unbounded.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use std::time::Duration;
+//!
+//! let mut ctx = Context::new();
+//! ctx.record_http_request("GET", "/orders/42", [("Authorization", "Bearer s3cr3t")], None);
+//! ctx.record_http_response(404, [("Content-Type", "application/json")], Duration::from_millis(12));
+//!
+//! let http = ctx.at("http");
+//! assert_eq!(http.at("request").at("headers").at("authorization").as_str(), Some("[redacted]"));
+//! assert_eq!(http.at("response").at("status").as_u64(), Some(404));
+//! assert_eq!(http.at("response").at("latency_ms").as_u64(), Some(12));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Key under which HTTP summaries are stored.
+pub const HTTP_KEY: &str = crate::keys::HTTP;
+
+/// Lowercased names of the headers whose values are redacted.
+pub const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+
+/// Maximum number of headers kept in a summary.
+pub const HTTP_HEADERS_LIMIT: usize = 32;
+
+/// Maximum number of characters kept of the URI and of each header value.
+pub const HTTP_VALUE_LIMIT: usize = 256;
+
+impl Context {
+    /// Records a summary of an outgoing or incoming request under `http.request`.
+    ///
+    /// `body_len` is the size of the body in bytes, if known.
+    pub fn record_http_request<I, K, V>(&mut self, method: &str, uri: &str, headers: I, body_len: Option<u64>)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut request = BTreeMap::from([
+            (Value::String("method".to_string()), Value::String(method.to_uppercase())),
+            (Value::String("uri".to_string()), Value::String(truncate(uri))),
+            (Value::String("headers".to_string()), summarize_headers(headers)),
+        ]);
+        if let Some(body_len) = body_len {
+            request.insert(Value::String("body_len".to_string()), Value::U64(body_len));
+        }
+        self.record_http("request", request);
+    }
+
+    /// Records a summary of a response under `http.response`, with its latency in milliseconds.
+    pub fn record_http_response<I, K, V>(&mut self, status: u16, headers: I, latency: Duration)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        let response = BTreeMap::from([
+            (Value::String("status".to_string()), Value::U16(status)),
+            (Value::String("headers".to_string()), summarize_headers(headers)),
+            (Value::String("latency_ms".to_string()), Value::U64(latency_ms)),
+        ]);
+        self.record_http("response", response);
+    }
+
+    fn record_http(&mut self, part: &str, summary: BTreeMap<Value, Value>) {
+        let mut http = match self.get(HTTP_KEY) {
+            Some(Value::Map(http)) => http.clone(),
+            _ => BTreeMap::new(),
+        };
+        http.insert(Value::String(part.to_string()), Value::Map(summary));
+        self.insert(HTTP_KEY.to_string(), Value::Map(http));
+    }
+}
+
+fn summarize_headers<I, K, V>(headers: I) -> Value
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut summary: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in headers {
+        let name = name.as_ref().to_lowercase();
+        let value = match SENSITIVE_HEADERS.contains(&name.as_str()) {
+            true => "[redacted]".to_string(),
+            false => truncate(value.as_ref()),
+        };
+        let full = summary.len() >= HTTP_HEADERS_LIMIT;
+        match summary.get_mut(&name) {
+            Some(existing) if existing != "[redacted]" => *existing = truncate(&format!("{}, {}", existing, value)),
+            Some(_) => {}
+            None if !full => {
+                summary.insert(name, value);
+            }
+            None => {}
+        }
+    }
+    Value::Map(summary.into_iter().map(|(k, v)| (Value::String(k), Value::String(v))).collect())
+}
+
+fn truncate(value: &str) -> String {
+    match value.char_indices().nth(HTTP_VALUE_LIMIT) {
+        Some((index, _)) => format!("{}...", &value[..index]),
+        None => value.to_string(),
+    }
+}
@@ -0,0 +1,252 @@
+//! HTTP header propagation.
+//!
+//! [`Context::to_headers`] writes a small context as HTTP headers, one header per entry, so that
+//! it travels with outgoing requests, and [`Context::from_headers`] rebuilds it in the downstream
+//! service. Only the entries visible at [`Sensitivity::Internal`] are written, so confidential and
+//! secret values never leave the process.
+//!
+//! Header names are the prefix followed by the key, bytes of the key outside `[a-z0-9-_.]` being
+//! percent-encoded, so that keys keep their case although header names do not. Header values are:
+//!
+//! * strings, with `%`, spaces and bytes outside visible ASCII percent-encoded;
+//! * `base64:` followed by the standard base64 encoding of bytes;
+//! * `json:` followed by the percent-encoded JSON encoding of any other value, map keys being
+//!   rendered as strings.
+//!
+//! Strings starting with `json:` or `base64:` have their first byte percent-encoded. Values which
+//! cannot be decoded are read as strings.
+//!
+//! Proxies and servers limit the size of headers: [`Context::to_headers_within`] leaves out the
+//! lowest-priority entries beyond a size limit, as [`Context::dump_within`] does, and records
+//! their keys under `propagation.dropped`. [`Context::to_headers`] applies the maximum size of the
+//! configuration in effect, see [`ContextConfig::with_max_size`](crate::ContextConfig::with_max_size).
+//!
+//! The receiving service is recorded in `propagation.hops`, which travels with the other entries,
+//! so that the path of the context across services can be traced, see [`Context::hops`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("tenant".to_string(), Value::String("café".to_string()));
+//! ctx.insert("retries".to_string(), Value::U8(3));
+//!
+//! let headers = ctx.to_headers("x-ctx-");
+//! assert_eq!(headers["x-ctx-tenant"], "caf%C3%A9");
+//! assert_eq!(headers["x-ctx-retries"], "json:3");
+//!
+//! let ctx = Context::from_headers(&headers, "x-ctx-", "billing");
+//! assert_eq!(ctx.get("tenant"), Some(&Value::String("café".to_string())));
+//! assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+//! assert_eq!(ctx.hops()[0].service, "billing");
+//! ```
+use crate::mapkey::stringify;
+use crate::transform::{base64, BASE64_ALPHABET};
+use crate::{Context, Contextualize, Sensitivity};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Transport recorded by [`Context::from_headers`] in `propagation.hops`.
+pub const HEADERS_TRANSPORT: &str = "headers";
+
+/// Prefix of header values holding JSON.
+const JSON_PREFIX: &str = "json:";
+
+/// Prefix of header values holding base64 encoded bytes.
+const BASE64_PREFIX: &str = "base64:";
+
+impl Context {
+    /// Encodes the entries visible at [`Sensitivity::Internal`] as headers named after `prefix`,
+    /// see the [module documentation](self).
+    ///
+    /// The prefix is lowercased; entries whose header name would not be valid, because of an
+    /// invalid prefix, are left out. If the configuration in effect has a maximum size, the
+    /// headers are limited to it as [`Context::to_headers_within`] does.
+    ///
+    /// This method is only available when the "http" feature is enabled.
+    pub fn to_headers(&self, prefix: &str) -> HeaderMap {
+        self.to_headers_within(prefix, self.effective_config(None).max_size().unwrap_or(usize::MAX))
+    }
+
+    /// Encodes the entries visible at [`Sensitivity::Internal`] as headers named after `prefix`,
+    /// without the lowest-priority entries if the headers exceed `max_len` bytes, the name, the
+    /// value and the `: ` and CRLF separators of each header being counted.
+    ///
+    /// The keys of the entries left out are recorded under `propagation.dropped`, see
+    /// [`Context::dump_within`].
+    ///
+    /// This method is only available when the "http" feature is enabled.
+    pub fn to_headers_within(&self, prefix: &str, max_len: usize) -> HeaderMap {
+        let prefix = prefix.to_lowercase();
+        let view = self.view(Sensitivity::Internal);
+        let entries = self.ordered_entries().into_iter().filter(|(k, _)| view.is_visible(k)).collect();
+        let encoded_len = |entries: &[(String, Value)]| -> usize {
+            entries
+                .iter()
+                .filter_map(|(k, v)| header(&prefix, k, v))
+                .map(|(name, value)| name.as_str().len() + value.len() + 4)
+                .sum()
+        };
+        let kept = self.trim_entries(entries, max_len, encoded_len);
+        kept.iter().filter_map(|(k, v)| header(&prefix, k, v)).collect()
+    }
+
+    /// Creates a new context from the headers named after `prefix` written by
+    /// [`Context::to_headers`]; other headers are ignored. The reception by `service` is recorded
+    /// as a hop through the [`HEADERS_TRANSPORT`] transport, see [`Context::record_hop`].
+    ///
+    /// Integers decode as `U64` or `I64`, floats as `F64` and nulls as `Unit`.
+    ///
+    /// This method is only available when the "http" feature is enabled.
+    pub fn from_headers(headers: &HeaderMap, prefix: &str, service: &str) -> Context {
+        let prefix = prefix.to_lowercase();
+        let mut data = BTreeMap::new();
+        for (name, value) in headers {
+            if let Some(key) = name.as_str().strip_prefix(&prefix) {
+                data.entry(decode(key)).or_insert_with(|| decode_value(value.as_bytes()));
+            }
+        }
+        let mut ctx = Context::new();
+        ctx.extend(data);
+        ctx.record_hop(service, HEADERS_TRANSPORT);
+        ctx
+    }
+}
+
+/// Returns the header holding the entry `k`, if its name and value are valid.
+fn header(prefix: &str, k: &str, v: &Value) -> Option<(HeaderName, HeaderValue)> {
+    let name = HeaderName::from_bytes(format!("{}{}", prefix, encode_name(k)).as_bytes()).ok()?;
+    let value = HeaderValue::from_str(&encode_value(v)).ok()?;
+    Some((name, value))
+}
+
+fn encode_name(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        match byte {
+            b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "%{:02X}", byte);
+            }
+        }
+    }
+    out
+}
+
+fn encode_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => encode_string(s),
+        Value::Char(c) => encode_string(&c.to_string()),
+        Value::Bytes(bytes) => format!("{}{}", BASE64_PREFIX, base64(bytes)),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => encode_value(inner),
+        other => format!("{}{}", JSON_PREFIX, encode(&to_json(other).to_string())),
+    }
+}
+
+fn encode_string(s: &str) -> String {
+    match s.starts_with(JSON_PREFIX) || s.starts_with(BASE64_PREFIX) {
+        true => format!("%{:02X}{}", s.as_bytes()[0], encode(&s[1..])),
+        false => encode(s),
+    }
+}
+
+/// Percent-encodes `%` and the bytes outside visible ASCII.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'%' => out.push_str("%25"),
+            0x21..=0x7e => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "%{:02X}", byte);
+            }
+        }
+    }
+    out
+}
+
+fn decode_value(bytes: &[u8]) -> Value {
+    let raw = String::from_utf8_lossy(bytes);
+    if let Some(json) = raw.strip_prefix(JSON_PREFIX) {
+        if let Ok(value) = serde_json::from_str::<Value>(&decode(json)) {
+            return value;
+        }
+    }
+    if let Some(encoded) = raw.strip_prefix(BASE64_PREFIX) {
+        if let Some(bytes) = unbase64(encoded) {
+            return Value::Bytes(bytes);
+        }
+    }
+    Value::String(decode(&raw))
+}
+
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (
+            bytes[i],
+            bytes.get(i + 1).and_then(|&digit| hex(digit)),
+            bytes.get(i + 2).and_then(|&digit| hex(digit)),
+        ) {
+            (b'%', Some(high), Some(low)) => {
+                out.push(high << 4 | low);
+                i += 3;
+            }
+            (byte, _, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Decodes standard base64, padded or not.
+fn unbase64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.trim_end_matches('=').as_bytes();
+    if encoded.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.chunks(4) {
+        let mut n = 0u32;
+        for (i, byte) in chunk.iter().enumerate() {
+            n |= (BASE64_ALPHABET.iter().position(|digit| digit == byte)? as u32) << (18 - 6 * i);
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+/// Converts a `serde_value::Value` into a JSON value, map keys being rendered as strings.
+fn to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Bool(b) => serde_json::Value::from(*b),
+        Value::U8(n) => serde_json::Value::from(*n),
+        Value::U16(n) => serde_json::Value::from(*n),
+        Value::U32(n) => serde_json::Value::from(*n),
+        Value::U64(n) => serde_json::Value::from(*n),
+        Value::I8(n) => serde_json::Value::from(*n),
+        Value::I16(n) => serde_json::Value::from(*n),
+        Value::I32(n) => serde_json::Value::from(*n),
+        Value::I64(n) => serde_json::Value::from(*n),
+        Value::F32(n) => serde_json::Value::from(*n),
+        Value::F64(n) => serde_json::Value::from(*n),
+        Value::Char(c) => serde_json::Value::from(c.to_string()),
+        Value::String(s) => serde_json::Value::from(s.as_str()),
+        Value::Unit | Value::Option(None) => serde_json::Value::Null,
+        Value::Option(Some(inner)) | Value::Newtype(inner) => to_json(inner),
+        Value::Seq(items) => serde_json::Value::Array(items.iter().map(to_json).collect()),
+        Value::Map(map) => serde_json::Value::Object(map.iter().map(|(k, v)| (stringify(k), to_json(v))).collect()),
+        Value::Bytes(bytes) => serde_json::Value::from(bytes.as_slice()),
+    }
+}
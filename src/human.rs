@@ -0,0 +1,205 @@
+//! Human-readable rendering of contexts.
+//!
+//! [`Context::to_human`] renders a context as an indented, YAML-like text meant for support
+//! engineers, with an optional formatting layer configured by [`HumanFormat`]: thousands
+//! separators in numbers and timestamps stored as milliseconds since the Unix epoch (such as
+//! `deadline` or the `start` of phases) rendered as dates with an explicit UTC offset. Machine
+//! formats (`to_json`, `to_toml`, `to_yaml`, serde serialization) are not affected.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, HumanFormat};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("bytes_read".to_string(), Value::U64(1234567));
+//! ctx.insert("deadline".to_string(), Value::U64(1700000000000));
+//!
+//! let text = ctx.to_human(&HumanFormat::new().with_utc_offset(60));
+//! assert_eq!(text, "bytes_read: 1,234,567\ndeadline: 2023-11-14 23:13:20.000 +01:00\n");
+//! ```
+use crate::snapshot::glob_match;
+use crate::Context;
+use serde_value::Value;
+use std::fmt::Write;
+
+/// Formatting options of [`Context::to_human`].
+#[derive(Debug, Clone)]
+pub struct HumanFormat {
+    thousands_separator: Option<char>,
+    utc_offset: i32,
+    timestamp_keys: Vec<String>,
+}
+
+impl Default for HumanFormat {
+    fn default() -> Self {
+        Self {
+            thousands_separator: Some(','),
+            utc_offset: 0,
+            timestamp_keys: ["deadline", "phases.*.start", "attempt_history.*.at"]
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl HumanFormat {
+    /// Creates options with `,` as thousands separator, rendering the timestamps written by this
+    /// crate (`deadline`, phase starts and attempt times) in UTC.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the thousands separator, `None` disables grouping.
+    pub fn with_thousands_separator(mut self, separator: Option<char>) -> Self {
+        self.thousands_separator = separator;
+        self
+    }
+
+    /// Sets the offset from UTC, in minutes, used to render timestamps.
+    pub fn with_utc_offset(mut self, minutes: i32) -> Self {
+        self.utc_offset = minutes;
+        self
+    }
+
+    /// Renders integers whose dotted path matches `pattern` as timestamps.
+    ///
+    /// The pattern may contain `*` wildcards, see [`SnapshotOptions::mask_key`](crate::SnapshotOptions::mask_key).
+    pub fn timestamp_key(mut self, pattern: &str) -> Self {
+        self.timestamp_keys.push(pattern.to_string());
+        self
+    }
+
+    fn is_timestamp(&self, path: &str) -> bool {
+        self.timestamp_keys.iter().any(|pattern| glob_match(pattern, path))
+    }
+
+    fn integer(&self, value: i128) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let mut out = String::new();
+        if value < 0 {
+            out.push('-');
+        }
+        for (index, c) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                if let Some(separator) = self.thousands_separator {
+                    out.push(separator);
+                }
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    fn float(&self, value: f64) -> String {
+        let repr = format!("{:?}", value);
+        match (value.is_finite(), repr.split_once('.')) {
+            (true, Some((int, frac))) if !repr.contains('e') => match int.parse::<i128>() {
+                Ok(int) if int == 0 && value.is_sign_negative() => format!("-0.{}", frac),
+                Ok(int) => format!("{}.{}", self.integer(int), frac),
+                Err(_) => repr,
+            },
+            _ => repr,
+        }
+    }
+
+    fn timestamp(&self, millis: i128) -> String {
+        let millis = millis + self.utc_offset as i128 * 60_000;
+        let (days, ms) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+        let (year, month, day) = civil_from_days(days);
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let offset = self.utc_offset.unsigned_abs();
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03} {}{:02}:{:02}",
+            year,
+            month,
+            day,
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000,
+            sign,
+            offset / 60,
+            offset % 60
+        )
+    }
+
+    fn scalar(&self, path: &str, value: &Value) -> String {
+        let integer = match value {
+            Value::U8(v) => Some(*v as i128),
+            Value::U16(v) => Some(*v as i128),
+            Value::U32(v) => Some(*v as i128),
+            Value::U64(v) => Some(*v as i128),
+            Value::I8(v) => Some(*v as i128),
+            Value::I16(v) => Some(*v as i128),
+            Value::I32(v) => Some(*v as i128),
+            Value::I64(v) => Some(*v as i128),
+            _ => None,
+        };
+        match (integer, value) {
+            (Some(v), _) if self.is_timestamp(path) => self.timestamp(v),
+            (Some(v), _) => self.integer(v),
+            (_, Value::F32(v)) => self.float(*v as f64),
+            (_, Value::F64(v)) => self.float(*v),
+            (_, Value::Bool(v)) => v.to_string(),
+            (_, Value::Char(c)) => c.to_string(),
+            (_, Value::String(s)) => s.clone(),
+            (_, Value::Bytes(bytes)) => format!("<{} bytes>", bytes.len()),
+            _ => "null".to_string(),
+        }
+    }
+}
+
+/// Converts a number of days since 1970-01-01 into a `(year, month, day)` date.
+fn civil_from_days(days: i128) -> (i128, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+impl Context {
+    /// Renders the context as indented text for human readers, see [`HumanFormat`].
+    pub fn to_human(&self, format: &HumanFormat) -> String {
+        let mut out = String::new();
+        for (key, value) in self.ordered_entries() {
+            write_entry(&mut out, &key, &key, &value, format, 0);
+        }
+        out
+    }
+}
+
+fn write_entry(out: &mut String, path: &str, label: &str, value: &Value, format: &HumanFormat, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => write_entry(out, path, label, inner, format, depth),
+        Value::Map(map) if !map.is_empty() => {
+            let _ = writeln!(out, "{}{}:", indent, label);
+            for (k, v) in map {
+                let key = format.scalar("", k);
+                write_entry(out, &format!("{}.{}", path, key), &key, v, format, depth + 1);
+            }
+        }
+        Value::Seq(items) if !items.is_empty() => {
+            let _ = writeln!(out, "{}{}:", indent, label);
+            for (index, item) in items.iter().enumerate() {
+                write_entry(out, &format!("{}.{}", path, index), "-", item, format, depth + 1);
+            }
+        }
+        Value::Map(_) => {
+            let _ = writeln!(out, "{}{}: {{}}", indent, label);
+        }
+        Value::Seq(_) => {
+            let _ = writeln!(out, "{}{}: []", indent, label);
+        }
+        scalar => {
+            let _ = writeln!(out, "{}{}: {}", indent, label, format.scalar(path, scalar));
+        }
+    }
+}
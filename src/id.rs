@@ -0,0 +1,198 @@
+//! Identifier generation.
+//!
+//! Context ids, see [`Context::id`], and the parent ids recorded by [`Context::fork`] are produced
+//! by an [`IdGenerator`], chosen with [`ContextConfig::with_id_generator`] globally or per
+//! context. [`Context::generate_id`] produces ids in the same format for any other purpose, such
+//! as trace or correlation ids. Built-in generators are:
+//!
+//! * [`ProcessCounter`] (default): `<pid>-<counter>`, unique within the process;
+//! * [`UuidV4`]: random UUID;
+//! * [`UuidV7`]: time-ordered UUID;
+//! * [`Ulid`]: time-ordered, lexicographically sortable identifier.
+//!
+//! Any `Fn() -> String` closure can be used as a custom generator. Random bits come from the
+//! randomly seeded hasher of the standard library: ids are unique, but not meant to be
+//! unpredictable secrets.
+//!
+//! Independently of the generators, [`Contextualize::insert_ulid`] and
+//! [`Contextualize::insert_snowflake`] store ULIDs and 64-bit snowflake ids under their canonical
+//! string rendering: 26 uppercase characters of Crockford's base 32 for ULIDs, decimal digits for
+//! snowflakes, which keeps them exact in formats limited to 53-bit integers.
+//! [`Contextualize::get_ulid`] and [`Contextualize::get_snowflake`] read them back, also accepting
+//! lowercase ULIDs and snowflakes stored as integers.
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextConfig, Contextualize, UuidV7};
+//!
+//! let ctx = Context::with_config(ContextConfig::new().with_id_generator(UuidV7));
+//! assert_eq!(ctx.id().len(), 36);
+//! assert_eq!(&ctx.id()[14..15], "7");
+//! assert_eq!(ctx.fork().parent_id(), Some(ctx.id()));
+//!
+//! let ctx = Context::with_config(ContextConfig::new().with_id_generator(|| "req-42".to_string()));
+//! assert_eq!(ctx.id(), "req-42");
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert_ulid("request_id".to_string(), 0x0191_2f3c_7a00_0000_0000_0000_0000_002a);
+//! assert_eq!(ctx.get_str("request_id").unwrap(), "01J4QKRYG0000000000000001A");
+//! assert_eq!(ctx.get_ulid("request_id").unwrap(), 0x0191_2f3c_7a00_0000_0000_0000_0000_002a);
+//!
+//! ctx.insert_snowflake("tweet_id".to_string(), 1_541_815_603_606_036_480);
+//! assert_eq!(ctx.get_str("tweet_id").unwrap(), "1541815603606036480");
+//! assert_eq!(ctx.get_snowflake("tweet_id").unwrap(), 1_541_815_603_606_036_480);
+//! ```
+use crate::{Context, ValueExt};
+use serde_value::Value;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static CONTEXT_COUNTER: AtomicU64 = AtomicU64::new(0);
+static RANDOM_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Crockford's base 32 alphabet, used by ULIDs.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Generator of identifiers, see the [module documentation](self).
+pub trait IdGenerator: Send + Sync {
+    /// Returns a new identifier.
+    fn generate(&self) -> String;
+}
+
+impl<F: Fn() -> String + Send + Sync> IdGenerator for F {
+    fn generate(&self) -> String {
+        self()
+    }
+}
+
+/// `<pid>-<counter>` identifiers, unique within the process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessCounter;
+
+impl IdGenerator for ProcessCounter {
+    fn generate(&self) -> String {
+        format!("{}-{}", std::process::id(), CONTEXT_COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Random UUIDs (version 4), hyphenated and lowercase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UuidV4;
+
+impl IdGenerator for UuidV4 {
+    fn generate(&self) -> String {
+        format_uuid(random_u128(), 4)
+    }
+}
+
+/// Time-ordered UUIDs (version 7), hyphenated and lowercase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UuidV7;
+
+impl IdGenerator for UuidV7 {
+    fn generate(&self) -> String {
+        let bits = ((unix_millis() as u128) << 80) | (random_u128() & ((1 << 80) - 1));
+        format_uuid(bits, 7)
+    }
+}
+
+/// ULIDs: 26 characters of Crockford's base 32, a millisecond timestamp followed by 80 random bits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Ulid;
+
+impl IdGenerator for Ulid {
+    fn generate(&self) -> String {
+        Ulid::encode(((unix_millis() as u128) << 80) | (random_u128() & ((1 << 80) - 1)))
+    }
+}
+
+impl Ulid {
+    /// Returns the canonical rendering of `ulid`: 26 uppercase characters of Crockford's base 32.
+    pub fn encode(ulid: u128) -> String {
+        (0..26)
+            .rev()
+            .map(|index| CROCKFORD[((ulid >> (index * 5)) & 0x1f) as usize] as char)
+            .collect()
+    }
+
+    /// Parses a ULID rendered by [`Ulid::encode`], case-insensitively.
+    ///
+    /// Returns `None` if `ulid` is not 26 characters of Crockford's base 32 or overflows 128 bits.
+    pub fn decode(ulid: &str) -> Option<u128> {
+        if ulid.len() != 26 || !matches!(ulid.as_bytes()[0], b'0'..=b'7') {
+            return None;
+        }
+        ulid.bytes().try_fold(0u128, |bits, c| {
+            let digit = CROCKFORD.iter().position(|&d| d == c.to_ascii_uppercase())?;
+            Some((bits << 5) | digit as u128)
+        })
+    }
+}
+
+/// Generator held by a [`ContextConfig`](crate::ContextConfig), compared by identity.
+#[derive(Clone)]
+pub(crate) struct SharedIdGenerator(pub(crate) Arc<dyn IdGenerator>);
+
+impl fmt::Debug for SharedIdGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IdGenerator")
+    }
+}
+
+impl PartialEq for SharedIdGenerator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Context {
+    /// Returns a new identifier from the generator of the configuration in effect, see
+    /// [`ContextConfig::with_id_generator`](crate::ContextConfig::with_id_generator).
+    pub fn generate_id(&self) -> String {
+        self.effective_config(None).id_generator().generate()
+    }
+}
+
+/// Returns the ULID held by `value`, see [`Contextualize::get_ulid`](crate::Contextualize::get_ulid).
+pub(crate) fn as_ulid(value: &Value) -> Option<u128> {
+    Ulid::decode(value.as_str()?)
+}
+
+/// Returns the snowflake held by `value`, see
+/// [`Contextualize::get_snowflake`](crate::Contextualize::get_snowflake).
+pub(crate) fn as_snowflake(value: &Value) -> Option<u64> {
+    match value.as_str() {
+        Some(v) if !v.is_empty() && v.bytes().all(|c| c.is_ascii_digit()) => v.parse().ok(),
+        Some(_) => None,
+        None => value.as_u64(),
+    }
+}
+
+/// Returns 128 random bits.
+pub(crate) fn random_u128() -> u128 {
+    let random = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(RANDOM_COUNTER.fetch_add(1, Ordering::Relaxed));
+        hasher.finish() as u128
+    };
+    (random() << 64) | random()
+}
+
+/// Returns the number of milliseconds since the Unix epoch, on 48 bits.
+fn unix_millis() -> u64 {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+    millis & ((1 << 48) - 1)
+}
+
+/// Formats `bits` as a hyphenated UUID of the given version, with the RFC 9562 variant.
+fn format_uuid(bits: u128, version: u128) -> String {
+    let bits = (bits & !(0xf << 76)) | (version << 76);
+    let bits = (bits & !(0b11 << 62)) | (0b10 << 62);
+    let hex = format!("{:032x}", bits);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
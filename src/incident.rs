@@ -0,0 +1,204 @@
+//! Markdown incident summaries.
+//!
+//! [`Context::render_incident_summary`] renders a template into a Markdown block to paste into
+//! incident channels. Templates are Markdown with placeholders:
+//!
+//! * `{{path}}`: the value at the dotted path, see [`Context::get_path`], or `-` if missing;
+//! * `{{#error}}`: the `error` key, with its cause chain recorded by
+//!   [`Context::record_causes`];
+//! * `{{#phases}}`: a table of the phases tracked by [`Context::enter_phase`];
+//! * `{{#attempts}}`: the attempts recorded by [`Context::record_attempt`];
+//! * `{{#system}}`: the build, Kubernetes and AWS Lambda information under `build.*`, `k8s.*`
+//!   and `lambda.*`.
+//!
+//! Sections render nothing when the context does not hold their keys. Values go through the
+//! redaction of the configuration in effect (see [`ContextConfig::redact`]) and keys tagged
+//! [`Sensitivity::Secret`] are shown as [`REDACTED`], so that summaries can be shared as they are.
+//! [`INCIDENT_SUMMARY_TEMPLATE`] combines all the sections.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("service".to_string(), Value::String("billing".to_string()));
+//! ctx.insert("api_key".to_string(), Value::String("s3cr3t".to_string()));
+//! ctx.set_sensitivity("api_key", Sensitivity::Secret);
+//! ctx.enter_phase("charge");
+//! ctx.record_attempt("card declined");
+//!
+//! let summary = ctx.render_incident_summary("**{{service}}** failed (key: {{api_key}})\n\n{{#phases}}{{#attempts}}");
+//! assert!(summary.starts_with("**billing** failed (key: [redacted])"));
+//! assert!(summary.contains("| charge | running |"));
+//! assert!(summary.contains("1. card declined"));
+//! ```
+//!
+//! [`ContextConfig::redact`]: crate::ContextConfig::redact
+//! [`Sensitivity::Secret`]: crate::Sensitivity::Secret
+use crate::{keys, Context, Contextualize, Sensitivity, ValueExt, REDACTED};
+use serde_value::Value;
+use std::fmt::Write;
+
+/// Default template of [`Context::render_incident_summary`], combining every section.
+pub const INCIDENT_SUMMARY_TEMPLATE: &str = "### Incident summary\n\n{{#error}}{{#phases}}{{#attempts}}{{#system}}";
+
+impl Context {
+    /// Renders `template` into a Markdown incident summary, see the [module documentation](self).
+    ///
+    /// Placeholders which are not closed are kept as they are, unknown sections render nothing.
+    pub fn render_incident_summary(&self, template: &str) -> String {
+        let ctx = self.redacted();
+        let mut out = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else { break };
+            out.push_str(&rest[..start]);
+            let name = rest[start + 2..start + end].trim();
+            match name.strip_prefix('#') {
+                Some("error") => error_section(&ctx, &mut out),
+                Some("phases") => phases_section(&ctx, &mut out),
+                Some("attempts") => attempts_section(&ctx, &mut out),
+                Some("system") => system_section(&ctx, &mut out),
+                Some(_) => {}
+                None => out.push_str(&ctx.get_path(name).map_or_else(|| "-".to_string(), inline)),
+            }
+            rest = &rest[start + end + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Returns a copy of the entries of the context, redacted for sharing.
+    pub(crate) fn redacted(&self) -> Context {
+        let config = self.effective_config(None);
+        let mut ctx = Context::new();
+        for (k, v) in self.inner() {
+            let v = match self.sensitivity(&k) {
+                Sensitivity::Secret => Some(Value::String(REDACTED.to_string())),
+                _ => config.apply(&k, v),
+            };
+            if let Some(v) = v {
+                ctx.insert(k, v);
+            }
+        }
+        ctx
+    }
+}
+
+fn error_section(ctx: &Context, out: &mut String) {
+    let Some(error) = ctx.get(keys::ERROR) else { return };
+    out.push_str("#### Error\n\n");
+    match error.as_map() {
+        Some(map) => {
+            for (k, v) in map.iter().filter(|(k, _)| k.as_str() != Some("causes")) {
+                let _ = writeln!(out, "- **{}:** {}", inline(k), inline(v));
+            }
+            let causes = ctx.at(keys::ERROR).at("causes").as_seq().unwrap_or_default();
+            if !causes.is_empty() {
+                out.push_str("\nCauses:\n\n");
+                for (index, cause) in causes.iter().enumerate() {
+                    let cause = crate::ValueRef::new(Some(cause));
+                    let kind = cause.at("type").value().map_or_else(|| "-".to_string(), inline);
+                    let message = cause.at("message").value().map_or_else(|| "-".to_string(), inline);
+                    let _ = writeln!(out, "{}. `{}`: {}", index + 1, kind, message);
+                }
+            }
+        }
+        None => {
+            let _ = writeln!(out, "- **message:** {}", inline(error));
+        }
+    }
+    out.push('\n');
+}
+
+fn phases_section(ctx: &Context, out: &mut String) {
+    let phases = ctx.at(keys::PHASES).as_seq().unwrap_or_default();
+    if phases.is_empty() {
+        return;
+    }
+    out.push_str("#### Phases\n\n| Phase | Duration |\n|---|---|\n");
+    for phase in phases {
+        let phase = crate::ValueRef::new(Some(phase));
+        let name = phase.at("name").value().map_or_else(|| "-".to_string(), inline);
+        let duration = phase
+            .at("duration")
+            .as_u64()
+            .map_or_else(|| "running".to_string(), |duration| format!("{} ms", duration));
+        let _ = writeln!(out, "| {} | {} |", name.replace('|', "\\|"), duration);
+    }
+    out.push('\n');
+}
+
+fn attempts_section(ctx: &Context, out: &mut String) {
+    let Some(attempt) = ctx.at(keys::ATTEMPT).as_u64() else { return };
+    out.push_str("#### Attempts\n\n");
+    match ctx.at(keys::MAX_ATTEMPTS).as_u64() {
+        Some(max) => {
+            let _ = writeln!(out, "{} of {} attempts", attempt, max);
+        }
+        None => {
+            let _ = writeln!(out, "{} attempts", attempt);
+        }
+    }
+    let history = ctx.at(keys::ATTEMPT_HISTORY).as_seq().unwrap_or_default();
+    if !history.is_empty() {
+        out.push('\n');
+        for entry in history {
+            let entry = crate::ValueRef::new(Some(entry));
+            let number = entry.at("attempt").as_u64().unwrap_or_default();
+            let error = entry.at("error").value().map_or_else(|| "-".to_string(), inline);
+            let _ = writeln!(out, "{}. {}", number, error);
+        }
+    }
+    out.push('\n');
+}
+
+fn system_section(ctx: &Context, out: &mut String) {
+    let entries: Vec<(String, Value)> = ctx
+        .flatten()
+        .into_iter()
+        .filter(|(k, _)| {
+            [keys::BUILD, keys::K8S, keys::LAMBDA]
+                .iter()
+                .any(|prefix| k.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.')))
+        })
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    out.push_str("#### System\n\n");
+    for (k, v) in entries {
+        let _ = writeln!(out, "- **{}:** {}", k, inline(&v));
+    }
+    out.push('\n');
+}
+
+/// Renders `value` on a single line.
+pub(crate) fn inline(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.replace(['\r', '\n'], " "),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Unit | Value::Option(None) => "null".to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => inline(inner),
+        Value::Bytes(bytes) => format!("<{} bytes>", bytes.len()),
+        Value::Seq(items) => format!("[{}]", items.iter().map(inline).collect::<Vec<_>>().join(", ")),
+        Value::Map(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(k, v)| format!("{}: {}", inline(k), inline(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
@@ -0,0 +1,190 @@
+//! Searchable index of context dumps.
+//!
+//! A [`ContextIndex`] ingests many contexts, for example archived dumps loaded from NDJSON with
+//! [`ContextIndex::ingest_ndjson`] (feature: "json"), and finds those whose values match a set of
+//! conditions, such as `env=prod` and `error.kind=Timeout`. Nested maps are indexed under dotted
+//! paths, and each item of a sequence under the path of the sequence.
+//!
+//! Contexts are identified by their [`Context::fingerprint`], a hash of their canonical
+//! rendering: identical dumps are indexed once.
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextIndex, Contextualize};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let mut prod = Context::new();
+//! prod.insert("env".to_string(), Value::String("prod".to_string()));
+//! prod.insert(
+//!     "error".to_string(),
+//!     Value::Map(BTreeMap::from([(Value::String("kind".to_string()), Value::String("Timeout".to_string()))])),
+//! );
+//! let mut staging = prod.clone();
+//! staging.insert("env".to_string(), Value::String("staging".to_string()));
+//!
+//! let mut index = ContextIndex::new();
+//! index.insert(&prod);
+//! index.insert(&staging);
+//!
+//! let fingerprint = prod.fingerprint();
+//! assert_eq!(index.find(&[("env", "prod"), ("error.kind", "Timeout")]), vec![fingerprint.as_str()]);
+//! assert_eq!(index.find(&[("error.kind", "Timeout")]).len(), 2);
+//! ```
+use crate::hash::fnv1a;
+use crate::snapshot::write_value;
+use crate::{Context, Contextualize, SnapshotOptions};
+use serde_value::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An index of context dumps, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct ContextIndex {
+    /// Dumps by fingerprint.
+    dumps: BTreeMap<String, BTreeMap<String, Value>>,
+    /// Fingerprints of the dumps holding each value, by path and value.
+    postings: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl ContextIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes the dump of `ctx`, returning its fingerprint.
+    pub fn insert(&mut self, ctx: &Context) -> String {
+        let fingerprint = ctx.fingerprint();
+        if !self.dumps.contains_key(&fingerprint) {
+            let dump = ctx.inner();
+            for (k, v) in &dump {
+                self.index_value(&fingerprint, k, v);
+            }
+            self.dumps.insert(fingerprint.clone(), dump);
+        }
+        fingerprint
+    }
+
+    /// Indexes each context of an NDJSON stream, one JSON dump per line, returning the number of
+    /// lines read. Blank lines are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<usize>` which is:
+    /// * `Ok(count)` containing the number of contexts read
+    /// * `Err(e)` containing an [`UnExpectedError`](crate::UnExpectedError) if the stream cannot
+    ///   be read, or the error of [`Contextualize::from_json`] if a line is not a valid dump
+    #[cfg(feature = "json")]
+    pub fn ingest_ndjson<R: std::io::BufRead>(&mut self, reader: R) -> cdumay_core::Result<usize> {
+        let mut count = 0;
+        for line in reader.lines() {
+            let line = line.map_err(|err| {
+                crate::UnExpectedError::new().with_message(format!("Failed to read NDJSON contexts after {} lines: {}", count, err))
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.insert(&Context::from_json(&line)?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns the number of indexed dumps.
+    pub fn len(&self) -> usize {
+        self.dumps.len()
+    }
+
+    /// Returns `true` if no dump is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.dumps.is_empty()
+    }
+
+    /// Returns the dump with the given fingerprint.
+    pub fn get(&self, fingerprint: &str) -> Option<&BTreeMap<String, Value>> {
+        self.dumps.get(fingerprint)
+    }
+
+    /// Returns the fingerprints of the dumps matching every `(path, value)` condition, in order.
+    ///
+    /// Values are compared as text: strings as is, numbers in decimal, booleans as `true` or
+    /// `false` and missing options as `null`. Without conditions, every dump matches.
+    pub fn find(&self, conditions: &[(&str, &str)]) -> Vec<&str> {
+        let mut matches: Option<BTreeSet<&str>> = None;
+        for (path, value) in conditions {
+            let fingerprints: BTreeSet<&str> = self
+                .postings
+                .get(*path)
+                .and_then(|values| values.get(*value))
+                .map(|fingerprints| fingerprints.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&fingerprints).copied().collect(),
+                None => fingerprints,
+            });
+        }
+        match matches {
+            Some(matches) => matches.into_iter().collect(),
+            None => self.dumps.keys().map(String::as_str).collect(),
+        }
+    }
+
+    fn index_value(&mut self, fingerprint: &str, path: &str, value: &Value) {
+        match value {
+            Value::Option(Some(value)) | Value::Newtype(value) => self.index_value(fingerprint, path, value),
+            Value::Map(map) => {
+                for (k, v) in map {
+                    if let Some(k) = as_text(k) {
+                        self.index_value(fingerprint, &format!("{}.{}", path, k), v);
+                    }
+                }
+            }
+            Value::Seq(values) => values.iter().for_each(|v| self.index_value(fingerprint, path, v)),
+            value => {
+                if let Some(text) = as_text(value) {
+                    self.postings
+                        .entry(path.to_string())
+                        .or_default()
+                        .entry(text)
+                        .or_default()
+                        .insert(fingerprint.to_string());
+                }
+            }
+        }
+    }
+}
+
+impl Context {
+    /// Returns a fingerprint of the dump of the context: a 64-bit FNV-1a hash of its canonical
+    /// rendering, in hexadecimal.
+    ///
+    /// Contexts with equal dumps have equal fingerprints, whatever their key order.
+    pub fn fingerprint(&self) -> String {
+        let options = SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+        let dump = Value::Map(self.inner().into_iter().map(|(k, v)| (Value::String(k), v)).collect());
+        let mut canonical = String::new();
+        write_value(&mut canonical, "", &dump, &options, 0);
+        format!("{:016x}", fnv1a(canonical.as_bytes()))
+    }
+}
+
+/// Returns the text a scalar value is matched against.
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(v) => Some(v.clone()),
+        Value::Char(v) => Some(v.to_string()),
+        Value::Bool(v) => Some(v.to_string()),
+        Value::U8(v) => Some(v.to_string()),
+        Value::U16(v) => Some(v.to_string()),
+        Value::U32(v) => Some(v.to_string()),
+        Value::U64(v) => Some(v.to_string()),
+        Value::I8(v) => Some(v.to_string()),
+        Value::I16(v) => Some(v.to_string()),
+        Value::I32(v) => Some(v.to_string()),
+        Value::I64(v) => Some(v.to_string()),
+        Value::F32(v) => Some(v.to_string()),
+        Value::F64(v) => Some(v.to_string()),
+        Value::Unit | Value::Option(None) => Some("null".to_string()),
+        Value::Option(Some(v)) | Value::Newtype(v) => as_text(v),
+        Value::Seq(_) | Value::Map(_) | Value::Bytes(_) => None,
+    }
+}
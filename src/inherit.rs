@@ -0,0 +1,214 @@
+//! Context inheritance across threads and tasks.
+//!
+//! [`Context::fork`] produces a child context which starts as a snapshot of its parent and records
+//! the parent's [`Context::id`] under the `parent_id` key, so that errors raised by a worker can be
+//! related to the request which spawned it.
+//!
+//! A context can also be installed as the *current* context of a thread with [`Context::scope`].
+//! [`Context::spawn_thread`] forks the current context into the new thread, and with the "tokio"
+//! feature, [`Context::scope_task`] and [`Context::spawn_task`] do the same for tasks, so that the
+//! context is not lost at spawn points. [`Context::enter`] installs a context until the returned
+//! [`ContextGuard`] is dropped, [`Context::with_current`] updates the current context in place, and
+//! errors pick it up with `with_current_context`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! let parent_id = ctx.id().to_string();
+//!
+//! let child = ctx.scope(|| Context::spawn_thread(|| Context::current().unwrap()).join().unwrap());
+//! assert_eq!(child.get("request_id"), Some(&Value::String("abc".to_string())));
+//! assert_eq!(child.parent_id(), Some(parent_id.as_str()));
+//!
+//! let guard = Context::new().enter();
+//! Context::with_current(|ctx| ctx.insert("user".to_string(), Value::String("jane".to_string())));
+//! let err: cdumay_core::Error = cdumay_context::KeyNotFound::new().with_current_context().into();
+//! assert_eq!(err.details()["user"], Value::String("jane".to_string()));
+//! drop(guard);
+//! assert!(Context::current().is_none());
+//! ```
+use crate::{Context, Contextualize, ValueExt};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+/// Key under which [`Context::fork`] records the id of the parent context.
+pub const PARENT_ID_KEY: &str = crate::keys::PARENT_ID;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "tokio")]
+tokio::task_local! {
+    static TASK_CURRENT: RefCell<Option<Context>>;
+}
+
+/// Puts a context taken out of its slot back when dropped, see [`Context::with_current`].
+struct Restore<'a> {
+    slot: &'a RefCell<Option<Context>>,
+    ctx: Context,
+}
+
+impl Restore<'_> {
+    /// Runs `f` on the context held by `slot`, taken out of it meanwhile.
+    fn run<R>(slot: &RefCell<Option<Context>>, f: impl FnOnce(&mut Context) -> R) -> Option<R> {
+        let ctx = slot.borrow_mut().take()?;
+        let mut restore = Restore { slot, ctx };
+        Some(f(&mut restore.ctx))
+    }
+}
+
+impl Drop for Restore<'_> {
+    fn drop(&mut self) {
+        *self.slot.borrow_mut() = Some(std::mem::take(&mut self.ctx));
+    }
+}
+
+/// Keeps a context installed as the current context of the thread, see [`Context::enter`].
+///
+/// Dropping the guard restores the context which was current before. Guards are expected to be
+/// dropped in the reverse order of their creation, which scoping them to a block ensures.
+#[must_use = "the context is uninstalled when the guard is dropped"]
+#[derive(Debug)]
+pub struct ContextGuard {
+    previous: Option<Context>,
+    // The guard restores a thread-local, it must be dropped on the thread which created it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ContextGuard {
+    /// Uninstalls the context, returning it with the changes made while it was current.
+    pub fn exit(self) -> Context {
+        let mut guard = self;
+        let previous = guard.previous.take();
+        let ctx = CURRENT.with(|current| std::mem::replace(&mut *current.borrow_mut(), previous));
+        std::mem::forget(guard);
+        ctx.unwrap_or_default()
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+impl Context {
+    /// Returns the identifier of this context, unique within the process.
+    ///
+    /// The identifier is generated on first use by the generator of the configuration in effect,
+    /// see [`Context::generate_id`], and shared by clones, but not serialized.
+    pub fn id(&self) -> &str {
+        self.id.get_or_init(|| self.generate_id())
+    }
+
+    /// Returns the id of the context this one was forked from, if any.
+    pub fn parent_id(&self) -> Option<&str> {
+        self.get(PARENT_ID_KEY)?.as_str()
+    }
+
+    /// Creates a child context holding a snapshot of this context.
+    ///
+    /// The child is a clone of this context with its own id, a fresh change history, delta
+    /// synchronization state and lifecycle, and the parent id recorded under [`PARENT_ID_KEY`],
+    /// inserted after the keys of the parent. If the parent has seals, the parent id is sealed as
+    /// well.
+    pub fn fork(&self) -> Context {
+        let key = self.key_policy().normalize(PARENT_ID_KEY).into_owned();
+        let parent_id = serde_value::Value::String(self.id().to_string());
+        let mut child = self.clone();
+        child.id = std::sync::OnceLock::new();
+        child.changes.clear();
+        child.tombstones.clear();
+        child.sync = crate::context::SyncState::default();
+        child.lifecycle = None;
+        if child.has_seals() {
+            child.seals.insert(key.clone(), crate::tamper::digest(&parent_id));
+        }
+        let frozen = std::mem::take(&mut child.frozen);
+        child.insert_unchecked(key, parent_id);
+        child.frozen = frozen;
+        child
+    }
+
+    /// Returns a clone of the current context of the task or thread, if any.
+    pub fn current() -> Option<Context> {
+        #[cfg(feature = "tokio")]
+        if let Ok(ctx) = TASK_CURRENT.try_with(|current| current.borrow().clone()) {
+            return ctx;
+        }
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` on the current context of the task or thread, updating it in place.
+    ///
+    /// Returns `None`, without calling `f`, if there is no current context. The context is taken
+    /// out while `f` runs: [`Context::current`] and nested calls to `with_current` see no current
+    /// context, and the context is put back afterwards, even if `f` panics.
+    pub fn with_current<R>(f: impl FnOnce(&mut Context) -> R) -> Option<R> {
+        #[cfg(feature = "tokio")]
+        if TASK_CURRENT.try_with(|_| ()).is_ok() {
+            return TASK_CURRENT.with(|current| Restore::run(current, f));
+        }
+        CURRENT.with(|current| Restore::run(current, f))
+    }
+
+    /// Installs this context as the current context of the thread until the returned guard is
+    /// dropped.
+    pub fn enter(self) -> ContextGuard {
+        let previous = CURRENT.with(|current| current.borrow_mut().replace(self));
+        ContextGuard {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Runs `f` with this context installed as the current context of the thread.
+    ///
+    /// The previous current context is restored afterwards, even if `f` panics.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.enter();
+        f()
+    }
+
+    /// Spawns a thread running `f` with a fork of the current context, if any, installed.
+    pub fn spawn_thread<F, T>(f: F) -> std::thread::JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let child = Context::current().map(|ctx| ctx.fork());
+        std::thread::spawn(move || match child {
+            Some(ctx) => ctx.scope(f),
+            None => f(),
+        })
+    }
+
+    /// Runs `future` with this context installed as the current context of the task.
+    ///
+    /// This method is only available when the "tokio" feature is enabled.
+    #[cfg(feature = "tokio")]
+    pub async fn scope_task<F: std::future::Future>(self, future: F) -> F::Output {
+        TASK_CURRENT.scope(RefCell::new(Some(self)), future).await
+    }
+
+    /// Spawns a task running `future` with a fork of the current context, if any, installed.
+    ///
+    /// This method is only available when the "tokio" feature is enabled, it must be called from
+    /// within a Tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_task<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        match Context::current() {
+            Some(ctx) => tokio::spawn(ctx.fork().scope_task(future)),
+            None => tokio::spawn(future),
+        }
+    }
+}
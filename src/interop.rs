@@ -0,0 +1,184 @@
+//! Conversions between `serde_value::Value` and the values of the format backends.
+//!
+//! Each enabled backend gets a pair of functions converting its value type from and to
+//! `serde_value::Value`: [`from_json_value`] and [`to_json_value`] (feature: "json"),
+//! [`from_toml_value`] and [`to_toml_value`] (feature: "toml"), [`from_yaml_value`] and
+//! [`to_yaml_value`] (feature: "yaml"). They never panic: a value the other side cannot
+//! represent, such as a map with sequences as keys in JSON or a unit in TOML, gives a
+//! serialization error of the backend. TOML datetimes and YAML tags are converted as on load:
+//! dropped, or kept as tagged values if the global configuration says so, see
+//! [`ContextConfig::with_tagged_values`](crate::ContextConfig::with_tagged_values).
+//!
+//! The same conversions are available as `TryFrom` implementations between the backend values
+//! and [`ContextValue`], a wrapper of `serde_value::Value`, as both types are foreign to this
+//! crate:
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::interop::ContextValue;
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let value = ContextValue::try_from(serde_json::json!({"status": 404})).unwrap().into_inner();
+//! assert_eq!(value, Value::Map(BTreeMap::from([(Value::String("status".to_string()), Value::U64(404))])));
+//!
+//! let json = serde_json::Value::try_from(ContextValue(value)).unwrap();
+//! assert_eq!(json["status"], 404);
+//!
+//! let key = Value::Map(BTreeMap::from([(Value::Seq(vec![]), Value::Unit)]));
+//! assert!(serde_json::Value::try_from(ContextValue(key)).is_err());
+//! # }
+//! ```
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+use cdumay_core::ErrorConverter;
+#[cfg(any(feature = "json", feature = "toml"))]
+use serde::Deserialize;
+use serde_value::Value;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+use std::collections::BTreeMap;
+
+/// A `serde_value::Value` converted from and to the values of the format backends, see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ContextValue(pub Value);
+
+impl ContextValue {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl From<Value> for ContextValue {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ContextValue> for Value {
+    fn from(value: ContextValue) -> Self {
+        value.0
+    }
+}
+
+/// Converts a JSON value.
+///
+/// This function is only available when the "json" feature is enabled.
+#[cfg(feature = "json")]
+pub fn from_json_value(value: serde_json::Value) -> cdumay_core::Result<Value> {
+    Value::deserialize(value)
+        .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to convert value from JSON".to_string()), BTreeMap::new()))
+}
+
+/// Converts a value into JSON.
+///
+/// This function is only available when the "json" feature is enabled.
+#[cfg(feature = "json")]
+pub fn to_json_value(value: &Value) -> cdumay_core::Result<serde_json::Value> {
+    serde_json::to_value(value)
+        .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to convert value to JSON".to_string()), BTreeMap::new()))
+}
+
+/// Converts a TOML value, datetimes becoming strings or tagged values as on load.
+///
+/// This function is only available when the "toml" feature is enabled.
+#[cfg(feature = "toml")]
+pub fn from_toml_value(value: toml::Value) -> cdumay_core::Result<Value> {
+    Value::deserialize(value)
+        .map(|value| crate::tagged::toml_datetimes(value, crate::ContextConfig::global().tagged_values()))
+        .map_err(|err| {
+            cdumay_toml::TomlDeserializeErrorConverter::convert_error(&err, Some("Failed to convert value from TOML".to_string()), BTreeMap::new())
+        })
+}
+
+/// Converts a value into TOML.
+///
+/// This function is only available when the "toml" feature is enabled.
+#[cfg(feature = "toml")]
+pub fn to_toml_value(value: &Value) -> cdumay_core::Result<toml::Value> {
+    toml::Value::try_from(value).map_err(|err| {
+        cdumay_toml::TomlSerializeErrorConverter::convert_error(&err, Some("Failed to convert value to TOML".to_string()), BTreeMap::new())
+    })
+}
+
+/// Converts a YAML value, tags being dropped or kept as tagged values as on load.
+///
+/// This function is only available when the "yaml" feature is enabled. It does not fail, the
+/// `Result` keeps the signature in line with the other backends.
+#[cfg(feature = "yaml")]
+pub fn from_yaml_value(value: serde_yaml::Value) -> cdumay_core::Result<Value> {
+    Ok(crate::yaml::to_value(value, crate::ContextConfig::global().tagged_values()))
+}
+
+/// Converts a value into YAML.
+///
+/// This function is only available when the "yaml" feature is enabled.
+#[cfg(feature = "yaml")]
+pub fn to_yaml_value(value: &Value) -> cdumay_core::Result<serde_yaml::Value> {
+    serde_yaml::to_value(value)
+        .map_err(|err| cdumay_yaml::YamlErrorConverter::convert_error(&err, Some("Failed to convert value to YAML".to_string()), BTreeMap::new()))
+}
+
+/// Converts a JSON value, see [`from_json_value`].
+#[cfg(feature = "json")]
+impl TryFrom<serde_json::Value> for ContextValue {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        from_json_value(value).map(Self)
+    }
+}
+
+/// Converts a value into JSON, see [`to_json_value`].
+#[cfg(feature = "json")]
+impl TryFrom<ContextValue> for serde_json::Value {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: ContextValue) -> Result<Self, Self::Error> {
+        to_json_value(&value.0)
+    }
+}
+
+/// Converts a TOML value, see [`from_toml_value`].
+#[cfg(feature = "toml")]
+impl TryFrom<toml::Value> for ContextValue {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: toml::Value) -> Result<Self, Self::Error> {
+        from_toml_value(value).map(Self)
+    }
+}
+
+/// Converts a value into TOML, see [`to_toml_value`].
+///
+/// The inherent `toml::Value::try_from` serializing any value takes precedence over this
+/// implementation, convert with `try_into` instead.
+#[cfg(feature = "toml")]
+impl TryFrom<ContextValue> for toml::Value {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: ContextValue) -> Result<Self, Self::Error> {
+        to_toml_value(&value.0)
+    }
+}
+
+/// Converts a YAML value, see [`from_yaml_value`].
+#[cfg(feature = "yaml")]
+impl TryFrom<serde_yaml::Value> for ContextValue {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        from_yaml_value(value).map(Self)
+    }
+}
+
+/// Converts a value into YAML, see [`to_yaml_value`].
+#[cfg(feature = "yaml")]
+impl TryFrom<ContextValue> for serde_yaml::Value {
+    type Error = cdumay_core::Error;
+
+    fn try_from(value: ContextValue) -> Result<Self, Self::Error> {
+        to_yaml_value(&value.0)
+    }
+}
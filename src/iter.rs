@@ -0,0 +1,80 @@
+//! Standard collection traits.
+//!
+//! A [`Context`] iterates by value over the entries of its dump, see [`Contextualize::inner`], in
+//! key order, and by reference over its stored entries without copying them, as
+//! [`Contextualize::get`] sees them: deferred values, defaults and aliases are left out. It can be
+//! collected from and extended with `(String, Value)` pairs, and compares equal to another
+//! context storing the same entries, whatever their ids, settings and histories; deferred values
+//! are not evaluated by the comparison and are only equal to themselves, as in clones.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx: Context = [("attempt".to_string(), Value::U8(2))].into_iter().collect();
+//! Extend::extend(&mut ctx, [("user".to_string(), Value::String("jane".to_string()))]);
+//! ctx.insert_live("in_flight", || Value::U8(3));
+//!
+//! let keys: Vec<&String> = (&ctx).into_iter().map(|(k, _)| k).collect();
+//! assert_eq!(keys, vec!["attempt", "user"]);
+//! assert_eq!(ctx.clone().into_iter().count(), 3);
+//! assert_eq!(ctx["attempt"], Value::U8(2));
+//! assert_eq!(ctx.clone(), ctx);
+//! ```
+//!
+//! As [`Contextualize::extend`] and [`Extend::extend`] share their name, `ctx.extend(...)` calls
+//! [`Context::extend`], which takes a map as the former does; use `Extend::extend(&mut ctx, ...)`
+//! to extend a context with an iterator.
+use crate::backend::Iter;
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::{btree_map, BTreeMap};
+
+impl Context {
+    /// Extends the context with the given key-value pairs, see [`Contextualize::extend`].
+    pub fn extend(&mut self, data: BTreeMap<String, Value>) {
+        Contextualize::extend(self, data)
+    }
+}
+
+impl IntoIterator for Context {
+    type Item = (String, Value);
+    type IntoIter = btree_map::IntoIter<String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Context {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl FromIterator<(String, Value)> for Context {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut ctx = Context::new();
+        Extend::extend(&mut ctx, iter);
+        ctx
+    }
+}
+
+/// Inserts the pairs in order, as [`Contextualize::insert`] does.
+impl Extend<(String, Value)> for Context {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(k, v)| self.insert(k, v));
+    }
+}
+
+/// Compares the stored entries of the contexts, deferred values being equal only if shared.
+impl PartialEq for Context {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.deferred == other.deferred
+    }
+}
+
+impl Eq for Context {}
@@ -0,0 +1,136 @@
+//! JSON:API rendering of errors.
+//!
+//! [`JsonApiError`] is the JSON:API error object of a [`cdumay_core::Error`]: its code is the
+//! `status` (as a string, as the specification requires), its class the `code` and its message
+//! the `detail`. [`JsonApiError::with_context`] fills `meta` with the [`Sensitivity::Public`]
+//! entries of a context, like [`ProblemDetails::with_context`](crate::ProblemDetails::with_context)
+//! does for extension members. Responses carry error objects in a [`JsonApiErrors`] document.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, JsonApiError, JsonApiErrors, KeyNotFound, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("db_host".to_string(), Value::String("10.0.0.12".to_string()));
+//! ctx.set_sensitivity("request_id", Sensitivity::Public);
+//!
+//! let err: cdumay_core::Error = KeyNotFound::new().into();
+//! let document = JsonApiErrors::from(JsonApiError::from(&err).with_context(&ctx));
+//! assert_eq!(document.errors[0].status, "404");
+//! assert!(document.errors[0].meta.contains_key("request_id"));
+//! assert!(!document.errors[0].meta.contains_key("db_host"));
+//! ```
+use crate::{Context, ContextDump, ContextView, Sensitivity};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Media type of serialized [`JsonApiErrors`] documents.
+pub const JSON_API_CONTENT_TYPE: &str = "application/vnd.api+json";
+
+/// A JSON:API error object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonApiError {
+    /// Unique identifier of this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// HTTP status code, as a string.
+    pub status: String,
+    /// Application-specific error code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Short summary of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Explanation specific to this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Non-standard meta-information about the error.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, Value>,
+}
+
+impl JsonApiError {
+    /// Sets the identifier of this occurrence of the problem.
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Sets the summary of the problem.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Adds the [`Sensitivity::Public`] entries of `ctx` to `meta`.
+    pub fn with_context(self, ctx: &Context) -> Self {
+        self.with_view(&ctx.view(Sensitivity::Public))
+    }
+
+    /// Adds the entries visible in `view` to `meta`, for audiences other than customers.
+    pub fn with_view(mut self, view: &ContextView) -> Self {
+        self.meta.extend(view.dump());
+        self
+    }
+}
+
+impl From<&cdumay_core::Error> for JsonApiError {
+    /// Converts an error with an empty `meta`: its details are internal by nature, see
+    /// [`JsonApiError::with_context`] to expose public context entries.
+    fn from(err: &cdumay_core::Error) -> Self {
+        let status = match err.code() {
+            code @ 100..=599 => code,
+            _ => 500,
+        };
+        Self {
+            id: None,
+            status: status.to_string(),
+            code: Some(err.class().to_string()),
+            title: None,
+            detail: Some(err.message().to_string()).filter(|message| !message.is_empty()),
+            meta: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<cdumay_core::Error> for JsonApiError {
+    fn from(err: cdumay_core::Error) -> Self {
+        Self::from(&err)
+    }
+}
+
+/// A JSON:API top-level document holding errors.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonApiErrors {
+    /// The error objects.
+    pub errors: Vec<JsonApiError>,
+}
+
+impl JsonApiErrors {
+    /// Serializes the document to a JSON string.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, pretty: bool) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        match pretty {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        }
+        .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump JSON:API errors".to_string()), BTreeMap::new()))
+    }
+}
+
+impl From<JsonApiError> for JsonApiErrors {
+    fn from(error: JsonApiError) -> Self {
+        Self { errors: vec![error] }
+    }
+}
+
+impl FromIterator<JsonApiError> for JsonApiErrors {
+    fn from_iter<I: IntoIterator<Item = JsonApiError>>(iter: I) -> Self {
+        Self {
+            errors: iter.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! JUnit XML properties.
+//!
+//! [`Context::to_junit_properties`] renders the context as the `<properties>` element of a JUnit
+//! XML test case or test suite, so that CI failures carry the runtime context of the failing test
+//! into test report tooling. Nested maps and sequences are flattened under dotted names, see
+//! [`Context::flatten`], and values go through the same redaction as
+//! [incident summaries](Context::render_incident_summary).
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("fixture".to_string(), Value::String("<empty db>".to_string()));
+//! ctx.insert("seed".to_string(), Value::U64(42));
+//!
+//! assert_eq!(
+//!     ctx.to_junit_properties(),
+//!     "<properties>\n  <property name=\"fixture\" value=\"&lt;empty db&gt;\"/>\n  <property name=\"seed\" value=\"42\"/>\n</properties>\n"
+//! );
+//! ```
+use crate::incident::inline;
+use crate::Context;
+
+impl Context {
+    /// Renders the context as a JUnit XML `<properties>` element, one `<property>` per flattened
+    /// entry in key order, see the [module documentation](self).
+    pub fn to_junit_properties(&self) -> String {
+        let mut out = String::from("<properties>\n");
+        for (k, v) in self.redacted().flatten() {
+            out.push_str(&format!("  <property name=\"{}\" value=\"{}\"/>\n", escape(&k), escape(&inline(&v))));
+        }
+        out.push_str("</properties>\n");
+        out
+    }
+}
+
+/// Escapes `text` for a double-quoted XML attribute, dropping the characters XML 1.0 forbids.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' => out.push_str("&#9;"),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
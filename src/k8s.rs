@@ -0,0 +1,95 @@
+//! Kubernetes metadata capture.
+//!
+//! [`Context::with_k8s_metadata`] records where an in-cluster service runs under the `k8s` key,
+//! from the environment variables and files conventionally exposed through the Downward API:
+//!
+//! | Entry             | Source                                                          |
+//! |-------------------|-----------------------------------------------------------------|
+//! | `pod_name`        | `POD_NAME`, or `HOSTNAME`                                       |
+//! | `namespace`       | `POD_NAMESPACE`, or the service account `namespace` file        |
+//! | `node_name`       | `NODE_NAME`                                                     |
+//! | `pod_ip`          | `POD_IP`                                                        |
+//! | `pod_uid`         | `POD_UID`                                                       |
+//! | `service_account` | `POD_SERVICE_ACCOUNT`                                           |
+//! | `labels`          | the `labels` file of the Downward API volume (`/etc/podinfo`)   |
+//!
+//! Missing sources are silently ignored, so the same code runs outside a cluster.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let ctx = Context::new().with_k8s_metadata();
+//! if let Some(pod) = ctx.at("k8s").at("pod_name").as_str() {
+//!     println!("running in pod {}", pod);
+//! }
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Default mount point of the Downward API volume.
+pub const K8S_PODINFO_DIR: &str = "/etc/podinfo";
+
+const K8S_KEY: &str = crate::keys::K8S;
+const SERVICE_ACCOUNT_NAMESPACE: &str = "/var/run/secrets/kubernetes.io/serviceaccount/namespace";
+
+impl Context {
+    /// Records the Kubernetes metadata of the running pod under the `k8s` key.
+    ///
+    /// Labels are read from the Downward API volume mounted at [`K8S_PODINFO_DIR`].
+    pub fn with_k8s_metadata(self) -> Self {
+        self.with_k8s_metadata_from(K8S_PODINFO_DIR)
+    }
+
+    /// Records the Kubernetes metadata of the running pod, reading labels from `podinfo_dir`.
+    ///
+    /// Nothing is recorded if no metadata is available.
+    pub fn with_k8s_metadata_from(mut self, podinfo_dir: impl AsRef<Path>) -> Self {
+        let mut metadata = BTreeMap::new();
+        let mut set = |key: &str, value: Option<String>| {
+            if let Some(value) = value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()) {
+                metadata.insert(Value::String(key.to_string()), Value::String(value));
+            }
+        };
+        set("pod_name", env("POD_NAME").or_else(|| env("HOSTNAME")));
+        set(
+            "namespace",
+            env("POD_NAMESPACE").or_else(|| std::fs::read_to_string(SERVICE_ACCOUNT_NAMESPACE).ok()),
+        );
+        set("node_name", env("NODE_NAME"));
+        set("pod_ip", env("POD_IP"));
+        set("pod_uid", env("POD_UID"));
+        set("service_account", env("POD_SERVICE_ACCOUNT"));
+        if let Ok(content) = std::fs::read_to_string(podinfo_dir.as_ref().join("labels")) {
+            let labels = parse_labels(&content);
+            if !labels.is_empty() {
+                metadata.insert(Value::String("labels".to_string()), Value::Map(labels));
+            }
+        }
+        if !metadata.is_empty() {
+            self.insert(K8S_KEY.to_string(), Value::Map(metadata));
+        }
+        self
+    }
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Parses the `key="value"` lines of a Downward API labels file.
+fn parse_labels(content: &str) -> BTreeMap<Value, Value> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+            (
+                Value::String(key.trim().to_string()),
+                Value::String(value.replace("\\\"", "\"").replace("\\\\", "\\")),
+            )
+        })
+        .collect()
+}
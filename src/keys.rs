@@ -0,0 +1,108 @@
+//! Well-known key names.
+//!
+//! The built-in capture helpers and exporters store their entries under the names defined here,
+//! and applications should use them as well for the entries every service shares, so that the
+//! same information is found under the same key across services. Nested entries are given as
+//! dotted paths, see [`Context::get_path`](crate::Context::get_path).
+//!
+//! ```rust
+//! use cdumay_context::{keys, Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert(keys::REQUEST_ID.to_string(), Value::String("abc".to_string()));
+//! ctx.record_attempt("timeout");
+//! assert_eq!(ctx.get_str(keys::REQUEST_ID).unwrap(), "abc");
+//! assert!(ctx.contains_key(keys::ATTEMPT));
+//! ```
+
+/// Identifier of the request being served.
+pub const REQUEST_ID: &str = "request_id";
+
+/// Identifier of the distributed trace the request belongs to.
+pub const TRACE_ID: &str = "trace_id";
+
+/// Tenant the request is served for, also set in the details of
+/// [`QuotaExceeded`](crate::QuotaExceeded) errors.
+pub const TENANT: &str = "tenant";
+
+/// Deployment environment, such as `prod` or `staging`.
+pub const ENV: &str = "env";
+
+/// Error information, holding the cause chain under [`ERROR_CAUSES`].
+pub const ERROR: &str = "error";
+
+/// Cause chain recorded by [`Context::record_causes`](crate::Context::record_causes).
+pub const ERROR_CAUSES: &str = "error.causes";
+
+/// Category of the error, such as the class of a `cdumay_core::Error`.
+pub const ERROR_KIND: &str = "error.kind";
+
+/// Context key an error is about, set by the `for_key` constructors of errors, such as
+/// [`KeyNotFound::for_key`](crate::KeyNotFound::for_key).
+pub const ERROR_KEY: &str = "error.key";
+
+/// HTTP exchange recorded by [`Context::record_http_request`](crate::Context::record_http_request)
+/// and [`Context::record_http_response`](crate::Context::record_http_response).
+pub const HTTP: &str = "http";
+
+/// Status code of the recorded HTTP response.
+pub const HTTP_STATUS: &str = "http.response.status";
+
+/// SQL statement recorded by [`Context::record_sql`](crate::Context::record_sql).
+pub const DB: &str = "db";
+
+/// Build information, see [`Context::with_build_info`](crate::Context::with_build_info).
+pub const BUILD: &str = "build";
+
+/// Kubernetes metadata captured with the "k8s" feature.
+pub const K8S: &str = "k8s";
+
+/// Host information recorded by the [`SystemProvider`](crate::SystemProvider) of the "system"
+/// feature.
+pub const SYSTEM: &str = "system";
+
+/// Process information recorded by the [`ProcessProvider`](crate::ProcessProvider) of the
+/// "system" feature.
+pub const PROCESS: &str = "process";
+
+/// AWS Lambda invocation metadata captured with the "lambda" feature.
+pub const LAMBDA: &str = "lambda";
+
+/// Phases tracked by [`Context::enter_phase`](crate::Context::enter_phase).
+pub const PHASES: &str = "phases";
+
+/// Number of attempts recorded by [`Context::record_attempt`](crate::Context::record_attempt).
+pub const ATTEMPT: &str = "attempt";
+
+/// Attempt limit set by [`Context::set_max_attempts`](crate::Context::set_max_attempts).
+pub const MAX_ATTEMPTS: &str = "max_attempts";
+
+/// Errors of the last attempts recorded by
+/// [`Context::record_attempt`](crate::Context::record_attempt).
+pub const ATTEMPT_HISTORY: &str = "attempt_history";
+
+/// Deadline of the operation, see [`Context::set_deadline`](crate::Context::set_deadline).
+pub const DEADLINE: &str = "deadline";
+
+/// Id of the context a context was forked from, see [`Context::fork`](crate::Context::fork).
+pub const PARENT_ID: &str = "parent_id";
+
+/// Propagation hops recorded by [`Context::record_hop`](crate::Context::record_hop).
+pub const PROPAGATION: &str = "propagation";
+
+/// Feature flags captured by [`Context::capture_flags`](crate::Context::capture_flags).
+pub const FLAGS: &str = "flags";
+
+/// Active configuration profile.
+pub const PROFILE: &str = "profile";
+
+/// Keys holding a default value.
+pub const DEFAULTED: &str = "defaulted";
+
+/// Entries replaced in lossy dumps, see [`Context::to_json_lossy`](crate::Context::to_json_lossy).
+pub const UNSERIALIZABLE: &str = "unserializable";
+
+/// Number of entries dropped from a dump truncated by
+/// [`Context::dump_truncated`](crate::Context::dump_truncated).
+pub const TRUNCATED: &str = "truncated";
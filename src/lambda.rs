@@ -0,0 +1,87 @@
+//! AWS Lambda invocation capture.
+//!
+//! Serverless error reports need the basic facts of the invocation. They are recorded under the
+//! `lambda` key:
+//!
+//! * [`Context::from_lambda_env`] reads the function configuration from the runtime environment
+//!   (`function_name`, `function_version`, `memory_limit_mb`, `region`, `log_group`,
+//!   `log_stream`);
+//! * [`Context::record_lambda_invocation`] adds the facts of an invocation from a
+//!   `lambda_runtime::Context` (`request_id`, `function_arn`, `memory_limit_mb`,
+//!   `remaining_time_ms`, `xray_trace_id`), and sets the [`DEADLINE_KEY`](crate::DEADLINE_KEY)
+//!   of the context to the invocation deadline.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::from_lambda_env();
+//! let invocation = lambda_runtime::Context::default();
+//! ctx.record_lambda_invocation(&invocation);
+//! assert!(ctx.at("lambda").at("request_id").as_str().is_some());
+//! ```
+use crate::{Context, Contextualize, DEADLINE_KEY};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LAMBDA_KEY: &str = crate::keys::LAMBDA;
+
+impl Context {
+    /// Creates a context holding the function configuration exposed by the Lambda runtime
+    /// environment under the `lambda` key.
+    ///
+    /// Nothing is recorded outside of a Lambda environment.
+    pub fn from_lambda_env() -> Context {
+        let mut entries = BTreeMap::new();
+        for (key, var) in [
+            ("function_name", "AWS_LAMBDA_FUNCTION_NAME"),
+            ("function_version", "AWS_LAMBDA_FUNCTION_VERSION"),
+            ("region", "AWS_REGION"),
+            ("log_group", "AWS_LAMBDA_LOG_GROUP_NAME"),
+            ("log_stream", "AWS_LAMBDA_LOG_STREAM_NAME"),
+        ] {
+            if let Ok(value) = std::env::var(var) {
+                entries.insert(key, Value::String(value));
+            }
+        }
+        if let Some(memory) = std::env::var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE").ok().and_then(|v| v.parse().ok()) {
+            entries.insert("memory_limit_mb", Value::U64(memory));
+        }
+        let mut ctx = Context::new();
+        ctx.merge_lambda(entries);
+        ctx
+    }
+
+    /// Records the facts of the invocation described by `invocation` under the `lambda` key.
+    pub fn record_lambda_invocation(&mut self, invocation: &lambda_runtime::Context) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let now = u64::try_from(now).unwrap_or(u64::MAX);
+        let mut entries = BTreeMap::from([
+            ("request_id", Value::String(invocation.request_id.clone())),
+            ("function_arn", Value::String(invocation.invoked_function_arn.clone())),
+            ("remaining_time_ms", Value::U64(invocation.deadline.saturating_sub(now))),
+        ]);
+        if let Ok(memory) = u64::try_from(invocation.env_config.memory) {
+            entries.insert("memory_limit_mb", Value::U64(memory));
+        }
+        if let Some(trace_id) = &invocation.xray_trace_id {
+            entries.insert("xray_trace_id", Value::String(trace_id.clone()));
+        }
+        self.merge_lambda(entries);
+        if invocation.deadline > 0 {
+            self.insert(DEADLINE_KEY.to_string(), Value::U64(invocation.deadline));
+        }
+    }
+
+    fn merge_lambda(&mut self, entries: BTreeMap<&str, Value>) {
+        if entries.is_empty() {
+            return;
+        }
+        let mut lambda = match self.get(LAMBDA_KEY) {
+            Some(Value::Map(map)) => map.clone(),
+            _ => BTreeMap::new(),
+        };
+        lambda.extend(entries.into_iter().map(|(k, v)| (Value::String(k.to_string()), v)));
+        self.insert(LAMBDA_KEY.to_string(), Value::Map(lambda));
+    }
+}
@@ -0,0 +1,140 @@
+//! Layered child contexts.
+//!
+//! A [`ScopedContext`] is a layer on top of a shared parent [`Context`], typically a request
+//! context on top of the process-wide base one. The parent is held through an `Arc` and never
+//! copied: lookups fall back to it, writes only touch the layer, and [`Contextualize::inner`] and
+//! [`ContextDump::dump`] return the merged view, values of the layer overriding those of the
+//! parent. Dropping the scoped context, or calling [`ScopedContext::pop`], discards the keys of
+//! the layer only.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut base = Context::new();
+//! base.insert("service".to_string(), Value::String("billing".to_string()));
+//! base.insert("env".to_string(), Value::String("prod".to_string()));
+//! let base = Arc::new(base);
+//!
+//! let mut request = base.child();
+//! request.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! request.insert("env".to_string(), Value::String("canary".to_string()));
+//! assert_eq!(request.get_str("service").unwrap(), "billing");
+//! assert_eq!(request.get_str("env").unwrap(), "canary");
+//! assert_eq!(request.len(), 3);
+//!
+//! let base = request.pop();
+//! assert_eq!(base.get_str("env").unwrap(), "prod");
+//! assert!(!base.contains_key("request_id"));
+//! ```
+use crate::{Context, ContextDump, Contextualize};
+use serde::{Serialize, Serializer};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A context layered on top of a shared parent, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ScopedContext {
+    parent: Arc<Context>,
+    layer: Context,
+}
+
+impl ScopedContext {
+    /// Creates an empty layer on top of `parent`.
+    pub fn new(parent: Arc<Context>) -> Self {
+        let layer = Context::with_key_policy(parent.key_policy());
+        Self { parent, layer }
+    }
+
+    /// Returns the parent context.
+    pub fn parent(&self) -> &Arc<Context> {
+        &self.parent
+    }
+
+    /// Returns the layer, holding only the entries written to this context.
+    pub fn layer(&self) -> &Context {
+        &self.layer
+    }
+
+    /// Returns the layer mutably, to use the methods of [`Context`] on it.
+    pub fn layer_mut(&mut self) -> &mut Context {
+        &mut self.layer
+    }
+
+    /// Discards the layer, returning the parent context.
+    pub fn pop(self) -> Arc<Context> {
+        self.parent
+    }
+}
+
+impl Context {
+    /// Creates an empty [`ScopedContext`] layered on top of this context.
+    pub fn child(self: &Arc<Self>) -> ScopedContext {
+        ScopedContext::new(Arc::clone(self))
+    }
+}
+
+impl Contextualize for ScopedContext {
+    /// Creates an empty layer on top of an empty parent.
+    fn new() -> Self {
+        Self::new(Arc::new(Context::new()))
+    }
+
+    /// Inserts a key-value pair into the layer, the parent is left untouched.
+    fn insert(&mut self, k: String, v: Value) {
+        self.layer.insert(k, v);
+    }
+
+    /// Returns the value of `k` in the layer, or in the parent if the layer does not hold it.
+    fn get(&self, k: &str) -> Option<&Value> {
+        self.layer.get(k).or_else(|| self.parent.get(k))
+    }
+
+    fn extend(&mut self, data: BTreeMap<String, Value>) {
+        self.layer.extend(data);
+    }
+
+    /// Returns the entries of the parent, overridden by those of the layer.
+    fn inner(&self) -> BTreeMap<String, Value> {
+        let mut data = self.parent.inner();
+        data.extend(self.layer.inner());
+        data
+    }
+
+    /// Returns the error details of the parent, overridden by those of the layer.
+    fn error_details(&self) -> BTreeMap<String, Value> {
+        let mut data = self.parent.error_details();
+        data.extend(self.layer.error_details());
+        data
+    }
+
+    /// Removes a key from the layer, returning its value if it was present. The value of the
+    /// parent, if any, is visible again.
+    fn remove(&mut self, k: &str) -> Option<Value> {
+        self.layer.remove(k)
+    }
+
+    /// Removes all the keys of the layer.
+    fn clear(&mut self) {
+        self.layer.clear();
+    }
+
+    fn contains_key(&self, k: &str) -> bool {
+        self.layer.contains_key(k) || self.parent.contains_key(k)
+    }
+}
+
+impl ContextDump for ScopedContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.inner()
+    }
+}
+
+/// Serializes the merged view of the context.
+impl Serialize for ScopedContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner().serialize(serializer)
+    }
+}
@@ -9,11 +9,141 @@
 //! # Features
 //!
 //! - Generic context management through the `Contextualize` trait and a `Context` struct
+//! - Fluent construction with `Context::builder()` accepting any `Serialize` value
 //! - Support for multiple serialization formats (with feature flags):
 //!   - JSON (feature: "json")
 //!   - TOML (feature: "toml")
 //!   - YAML (feature: "yaml")
+//! - Context comparison reports for regression testing (regex matchers with feature: "regex")
+//! - Canonical, redacted snapshots for `insta`-style snapshot testing
+//! - Thread-safe `SharedContext`, with lock-free reads (feature: "arc-swap")
+//! - Parallel bulk operations for very large contexts (feature: "rayon")
+//! - Context inheritance across spawned threads and tasks (Tokio tasks with feature: "tokio")
+//! - Deadline metadata propagated with the context
+//! - Per-key sensitivity levels and filtered views for customer-visible output
+//! - Pluggable value transformers applied to every dump and serialization
+//! - Configurable key ordering in serialized output
+//! - Human-readable rendering with thousands separators and dates in a chosen UTC offset
 //! - Type-safe error handling with the `cdumay_core::Error` struct
+//! - Downstream error kinds declared with `define_context_errors!`
+//! - Error cause chains recorded under `error.causes`
+//! - Underlying sources attached to errors with `with_source`
+//! - Panicking accessors with redacted context dumps for tests and prototypes
+//! - Build information stored under standard `build.*` keys
+//! - Vendor-neutral capture of active feature flags under `flags.*`
+//! - HTTP request and response summaries with redacted headers under `http.*`
+//! - SQL statement capture with literal stripping and parameter scrubbing under `db.*`
+//! - HTTP status and gRPC code mapping of error kinds
+//! - Problem Details (RFC 9457) rendering of errors with public context entries
+//! - JSON:API error objects with public context entries as `meta`
+//! - HTTP error responses filtered by audience (feature: "json")
+//! - Optional lifecycle states with enforced transitions and exactly-once emission
+//! - Provenance of propagated contexts recorded under `propagation.hops`
+//! - Size-aware dumps dropping lowest-priority keys to fit transport limits
+//! - Crash ring buffer of the last serialized contexts, optionally file-backed (feature: "mmap")
+//! - Allocation-free emergency dump callable from signal handlers (Unix)
+//! - Replay bundles holding the dump and per-key metadata of a context (feature: "json")
+//! - Lazily computed default values, per context or for every context
+//! - Environment-specific overlays selected by profile
+//! - Expansion of a template context over a matrix of values for job fan-out
+//! - Per-key priorities protecting essential keys from trimming
+//! - Bloom filter summaries of keys for cheap remote existence checks
+//! - Searchable index of context dumps, loadable from NDJSON
+//! - Aggregation of key frequencies, distinct values and numeric statistics across contexts, optionally with Laplace noise
+//! - Detection of unseen keys, rare values and out-of-range numbers against a profile of historical contexts
+//! - Downsampled timelines of snapshots of long-lived contexts
+//! - Zero-copy deserialization of read-only contexts from JSON (feature: "json") and MessagePack (feature: "msgpack")
+//! - SIMD-accelerated parsing of large JSON payloads (feature: "simd-json")
+//! - Streaming parsing of huge JSON dumps, one entry at a time (feature: "json")
+//! - Numeric precision policy applied consistently to JSON, TOML and YAML dumps
+//! - Non-string map key policy (stringify, reject or drop) applied to dumps
+//! - Documented round-trip guarantees per format, checked up front with `check_serializable`
+//! - Linting of suspected secrets, oversized values and other problematic entries before emission
+//! - Central `ContextConfig` of policies, redaction and size limits, set globally, per context or per call
+//! - Runtime discovery of compiled-in features, with `FeatureDisabled` errors instead of missing methods (all features: "full")
+//! - Versioned key aliases resolving old key names, dumped under the canonical name and optionally the old one too
+//! - Pruning of the context attached to errors to the keys relevant to their kind
+//! - Conversions from and to `serde_json::Map` keeping the key order (feature: "json", ordered with "preserve-order")
+//! - YAML anchors, aliases and merge keys resolved on load, with a guard against expansion bombs (feature: "yaml")
+//! - Optional preservation of YAML tags and TOML datetimes as tagged values, written back on dump (features: "toml", "yaml")
+//! - Per-entry digests reporting the entries added, modified or removed by untrusted code
+//! - File loading, saving and watching through a pluggable storage, with filesystem and in-memory storages
+//! - Fork trees of contexts, rendered as JSON or as Graphviz DOT graphs
+//! - Graphviz DOT rendering of forks and propagation hops with selected key labels
+//! - Per-tenant context registries with quotas on the number and size of contexts
+//! - Metrics hooks for inserts, dumps, loads, evictions and errors
+//! - Key deprecation with warnings on reads and writes, and optional migration to a replacement key
+//! - Context schemas declared in code, exported as JSON Schema (feature: "json") and Markdown documentation
+//! - Conditional inserts, atomic on shared contexts
+//! - Typed accessors reporting missing keys and type mismatches as errors
+//! - Compare-and-set and fetch-update on shared contexts
+//! - Key removal, clearing and size inspection on every context type
+//! - Pluggable id generators (process counter, UUIDv4, UUIDv7, ULID or custom) for context ids
+//! - Typed helpers storing ULIDs and snowflake ids under their canonical string rendering
+//! - Dotted path access to nested maps and flattening for flat logging backends
+//! - Merging with overwrite, keep-existing, deep merge and sequence append strategies
+//! - OpenMetrics exemplar labels selected by key priority within the 128-character budget
+//! - Wire format conformance vectors and golden files for implementations in other languages
+//! - Anonymization replacing identifiers with deterministic HMAC-SHA256 pseudonyms (feature: "hash")
+//! - Import of environment variables by prefix, with key case normalization and opt-in value coercion
+//! - `from_file` and `save_to_file` on every context type, with the format detected from the file extension
+//! - Redacted Markdown incident summaries of the error, phases, attempts and system information
+//! - `errors` module classifying every error of the crate into stable `ContextErrorKind` categories
+//! - `keys` module of the well-known key names used by the built-in helpers
+//! - `with_context!` blocks pushing entries for their duration, timing them and enriching their errors
+//! - Multi-value keys with `insert_append` and `get_all`, dumped as sequences
+//! - `ctx` and `with_ctx` on any `Result` to attach the context to its error
+//! - JUnit XML `<properties>` rendering, to attach the context to test reports
+//! - `ScopedContext` child layers reading through to a shared parent context without copying it
+//! - `testing` module of test doubles: `RecordingContext` recording every call and `FailingContext` whose serializers return an injected error
+//! - Log target routing from rules on the context content, with `route_target`
+//! - Ambient current context with `Context::enter` guards, `with_current` updates and errors capturing it with `with_current_context`
+//! - Delta sync tombstones keeping local removals from being resurrected by stale peers until acknowledged
+//! - Time-boxed sections with `section_with_ttl`, left out of dumps once their window is over
+//! - `before_emit` hooks allowing, denying or modifying dumps before emission, for central consent and compliance rules
+//! - Stable sampling fingerprints from selected keys, for consistent sampling decisions across services
+//! - Lossy dumps replacing unserializable entries with placeholders instead of failing, with `to_json_lossy`, `to_toml_lossy` and `to_yaml_lossy`
+//! - Typed extraction of the whole context or a subtree with `extract` and `extract_at`, and injection of struct fields with `inject`
+//! - Standard collection traits: iteration by value or reference, `FromIterator`, `Extend`, `PartialEq`, and `iter`, `keys` and `values` on every context type
+//! - Entry API and `get_mut` for in-place updates of counters and lists
+//! - Key-level diffs between contexts with `diff`, displayed and serialized, and replayed with `apply`
+//! - JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7386) updates, and JSON Patch documents between two contexts with `create_patch` (feature: "json")
+//! - `TrackedContext` recording an audit history of inserts, removals and extensions with timestamps and actor labels, serialized alongside the state
+//! - Validation of contexts against schemas with custom rules, reporting every violation with a dedicated error kind
+//! - One error taxonomy, `KeyNotFound`, `TypeMismatch`, `SerializationError`, `ValidationError` and `SizeLimitExceeded` among others, and `for_key` constructors recording the offending key under `error.key`
+//! - Truncated dumps bounding value sizes, entry count and nesting depth, for safe error details
+//! - `${key}` template interpolation of context values into messages, with configurable missing-key handling
+//! - Prefix filtering with `filter_prefix`, in-place filtering with `retain`, and `NamespacedContext` views prefixing their keys
+//! - Default key order of new contexts set by the configuration, e.g. insertion order for every context with `ContextConfig::with_key_order`
+//! - Layered configuration with `ContextBuilder`: defaults, files, environment variables and command-line overrides deep merged in precedence order, with the source of each value given by `origin`
+//! - Canonical encoding with `canonical_bytes`, independent of insertion order, numeric widths and source format
+//! - Entries with a time to live, inserted with `insert_with_ttl`, hidden once expired and removed with `purge_expired`
+//! - `BoundedLruContext` capped to a number of entries, evicting the least recently used ones, with eviction hooks and hit, miss and eviction counters
+//! - `inner_ref` borrowing the entries of a context when it can, used by the serialization methods to avoid copying it
+//! - `interop` module converting values from and to JSON, TOML and YAML values without panicking, also as `TryFrom` implementations on `ContextValue`
+//! - Logfmt-style `Display` and `to_logfmt`, and aligned tables with `to_table` on every context type, colored with ANSI escape codes (feature: "color")
+//! - Conversions from and to config-rs configurations (feature: "config")
+//! - Contexts as figment providers (feature: "figment")
+//! - Capture of parsed CLI arguments without secrets (feature: "clap")
+//! - Kubernetes Downward API metadata capture (feature: "k8s")
+//! - AWS Lambda invocation capture (feature: "lambda")
+//! - Compact binary snapshots (features: "bincode", "postcard")
+//! - Query string and form body encoding with percent-encoding and repeated keys (feature: "urlencoded")
+//! - `.env` files reading and writing (feature: "dotenv")
+//! - `log` crate structured key-values, passing a context as the key-values of a record (feature: "log-kv")
+//! - Sentry scopes and events built from contexts and errors (feature: "sentry")
+//! - FlatBuffers encoding with zero-copy views across process boundaries (feature: "flatbuffers")
+//! - Context propagation through HTTP headers within a size limit, recording the receiving service in `propagation.hops` (feature: "http")
+//! - System, process and build information providers composed with `with_providers` (feature: "system")
+//! - Hot reloading of context files on OS change notifications, with atomic swaps and change subscriptions (feature: "watch")
+//! - SHA-256 content hashes of the canonical encoding, for deduplication and caching (feature: "hash")
+//! - Gzip, zlib and deflate compressed dumps in any format, with `to_json_gz` and `from_json_gz` shortcuts (feature: "compress")
+//! - RON dumps and loads with `to_ron` and `from_ron` (feature: "ron")
+//! - XML dumps and loads with `to_xml` and `from_xml`, an element per key under a root element (feature: "xml")
+//! - `assert_context!` subset assertions, `ContextBuilder::fixture` and proptest strategies generating random contexts (feature: "test-utils")
+//! - `#[derive(ContextDump)]` with `rename`, `skip` and `flatten` field attributes (feature: "derive")
+//! - `ArenaContext` allocating its entries in a caller-provided bump arena, for batch jobs creating millions of contexts (feature: "allocator-api")
+//! - Entries stored in insertion order, documents loaded in key order and dumped back in that order by default (feature: "ordered", left out of "full" as it changes the default key order)
 //!
 //! # Example Usage
 //!
@@ -53,8 +183,312 @@
 //! }
 //! ```
 
+mod registry;
+#[doc(hidden)]
+pub use registry::{error_key_of, has_duplicate_codes, record_error_key};
+
 mod error;
-pub use error::{GenericContextError, UnExpectedError};
+mod hash;
+pub use error::{
+    Conflict, ConflictError, ContextSerializationError, ContextValidationError, ContextValueError, DeltaConflict, DeltaConflictError,
+    FeatureDisabled, FeatureDisabledError, FrozenKey, FrozenKeyError, GenericContextError, InvalidMapKey, InvalidMapKeyError, InvalidState,
+    InvalidStateError, KeyNotFound, NotFoundError, PrecisionLoss, PrecisionLossError, ProtectedKey, ProtectedKeyError, QuotaExceeded,
+    QuotaExceededError, SerializationError, SizeLimitExceeded, SizeLimitExceededError, Timeout, TimeoutError, TypeMismatch, UnExpectedError,
+    Unauthorized, UnauthorizedError, ValidationError, YamlExpansionLimit, YamlExpansionLimitError,
+};
+pub mod errors;
+pub mod interop;
+pub mod keys;
+pub mod testing;
 
+mod backend;
 mod context;
-pub use context::{ContextDump, Context, Contextualize};
+pub use context::{Context, ContextDump, Contextualize};
+
+mod policy;
+pub use policy::KeyPolicy;
+
+mod builder;
+pub use builder::{ContextBuilder, SourceId};
+
+mod convert;
+
+mod value;
+pub use value::{ValueExt, ValueRef};
+
+mod dirty;
+pub use dirty::Checkpoint;
+
+mod delta;
+pub use delta::Delta;
+
+mod crdt;
+pub use crdt::{CrdtContext, LwwEntry};
+
+mod shared;
+pub use shared::SharedContext;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+mod guard;
+
+mod ops;
+
+mod section;
+
+mod ttl;
+
+mod compare;
+pub use compare::{MatchOptions, MatchReport, Mismatch, MismatchKind};
+
+mod snapshot;
+pub use snapshot::SnapshotOptions;
+
+mod deferred;
+
+mod inherit;
+pub use inherit::{ContextGuard, PARENT_ID_KEY};
+
+mod deadline;
+pub use deadline::DEADLINE_KEY;
+
+mod retry;
+pub use retry::ATTEMPT_HISTORY_LIMIT;
+
+mod phase;
+
+mod counter;
+
+mod multi;
+mod seq;
+
+mod nested;
+
+mod sensitivity;
+pub use sensitivity::{ContextView, Sensitivity};
+
+mod transform;
+pub use transform::{Base64Bytes, RoundFloats, ValueTransformer};
+
+mod order;
+pub use order::KeyOrder;
+
+mod human;
+pub use human::HumanFormat;
+
+mod render;
+
+mod causes;
+mod project;
+#[doc(hidden)]
+pub use causes::record_causes_into;
+mod source;
+pub use source::WithSource;
+mod result;
+pub use result::ContextResultExt;
+mod expect;
+pub use expect::PANIC_DUMP_LIMIT;
+mod build_info;
+pub use build_info::{BuildInfo, BUILD_KEY};
+mod flags;
+pub use flags::{FlagProvider, FLAGS_KEY};
+mod http;
+pub use http::{HTTP_HEADERS_LIMIT, HTTP_KEY, HTTP_VALUE_LIMIT, SENSITIVE_HEADERS};
+mod sql;
+pub use sql::{normalize_sql, SqlParams, DB_KEY};
+mod status;
+pub use status::{register_grpc_code, GrpcCode, StatusMapping};
+mod problem;
+pub use problem::{ProblemDetails, PROBLEM_JSON_CONTENT_TYPE};
+mod jsonapi;
+pub use jsonapi::{JsonApiError, JsonApiErrors, JSON_API_CONTENT_TYPE};
+#[cfg(feature = "json")]
+mod response;
+#[cfg(feature = "json")]
+pub use response::{ErrorResponse, ResponseFormat};
+mod lifecycle;
+pub use lifecycle::Lifecycle;
+mod emission;
+pub use emission::{before_emit, clear_emit_hooks, Decision};
+mod provenance;
+pub use provenance::{Hop, PROPAGATION_HOPS_LIMIT, PROPAGATION_KEY};
+mod crash;
+mod trim;
+pub use crash::CrashRing;
+#[cfg(feature = "json")]
+mod bundle;
+mod defaults;
+#[cfg(unix)]
+mod emergency;
+pub use defaults::DEFAULTED_KEY;
+mod profile;
+pub use profile::{ProfiledContext, PROFILE_KEY};
+mod matrix;
+mod priority;
+pub use priority::Priority;
+mod bloom;
+mod index;
+pub use index::ContextIndex;
+mod aggregate;
+pub use aggregate::{Anomaly, AnomalyKind, ContextAggregator, ContextProfile, LaplaceNoise, AGGREGATE_VALUES_LIMIT};
+mod timeline;
+pub use timeline::{ContextTimeline, Sample};
+mod borrowed;
+pub use borrowed::{BorrowedValue, ContextRef};
+#[cfg(feature = "simd-json")]
+mod simd;
+#[cfg(feature = "simd-json")]
+pub use simd::SIMD_JSON_THRESHOLD;
+mod numeric;
+#[cfg(feature = "json")]
+mod stream;
+pub use numeric::{FloatHandling, Format, NumericPolicy, OnPrecisionLoss};
+mod mapkey;
+pub use mapkey::MapKeyPolicy;
+mod conformance;
+pub use conformance::{conformance_vectors, ConformanceVector, CONFORMANCE_VERSION};
+#[cfg(feature = "hash")]
+mod anonymize;
+#[cfg(feature = "hash")]
+pub use anonymize::pseudonymize;
+mod env;
+pub use env::{EnvOptions, KeyCase, ENV_SENSITIVE_PATTERNS};
+mod incident;
+pub use incident::INCIDENT_SUMMARY_TEMPLATE;
+mod junit;
+mod layer;
+pub use layer::ScopedContext;
+mod route;
+pub use route::{RouteRules, DEFAULT_ROUTE_TARGET};
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod lossy;
+mod sampling;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub use lossy::UNSERIALIZABLE_KEY;
+mod entry;
+mod extract;
+mod iter;
+pub use entry::ContextEntry;
+mod diff;
+pub use diff::{ContextDiff, ValueChange};
+#[cfg(feature = "json")]
+mod patch;
+mod tracked;
+pub use tracked::{Change, ChangeOp, TrackedContext};
+mod truncate;
+pub use truncate::{DumpLimits, TRUNCATED_KEY};
+mod template;
+pub use template::MissingKeyPolicy;
+mod namespace;
+pub use namespace::NamespacedContext;
+mod canonical;
+mod lint;
+mod scoped;
+pub use lint::{LintKind, LintOptions, LintWarning, LINT_SECRET_KEY_PATTERNS};
+mod settings;
+pub use settings::{ContextConfig, NullPolicy, REDACTED};
+mod capability;
+pub use capability::{capabilities, Capability};
+mod alias;
+mod relevance;
+pub use relevance::RelevanceRules;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+mod tagged;
+#[cfg(feature = "yaml")]
+mod yaml;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub use tagged::{TAG_KEY, TAG_VALUE_KEY, TOML_DATETIME_TAG};
+mod tamper;
+pub use tamper::{SealViolation, SealViolationKind};
+mod storage;
+pub use storage::{ContextStorage, ContextWatcher, FsStorage, MemoryStorage};
+mod tree;
+pub use tree::{ContextNode, ContextTree, DotOptions};
+mod tenant;
+pub use tenant::{QuotaEvent, TenantQuota, TenantRegistry, TenantUsage};
+mod metrics;
+pub use metrics::{clear_context_metrics, set_context_metrics, ContextMetrics, NoopMetrics};
+mod deprecation;
+pub use deprecation::{DeprecationWarning, KeyAccess};
+mod schema;
+pub use schema::{ContextSchema, KeySchema, ValidationErrors, ValueKind, Violation, ViolationKind};
+mod atomic;
+mod conditional;
+mod id;
+mod typed;
+pub use id::{IdGenerator, ProcessCounter, Ulid, UuidV4, UuidV7};
+mod merge;
+pub use merge::MergeStrategy;
+mod exemplar;
+pub use exemplar::EXEMPLAR_LABELS_LIMIT;
+mod lru;
+pub use lru::{BoundedLruContext, LruStats, DEFAULT_LRU_CAPACITY};
+#[cfg(feature = "allocator-api")]
+mod arena;
+#[cfg(feature = "allocator-api")]
+pub use arena::{ArenaContext, ArenaValue};
+#[cfg(feature = "derive")]
+mod derive;
+#[cfg(feature = "derive")]
+pub use cdumay_context_derive::ContextDump;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::derive::{dump_field, dump_flatten};
+    pub use serde_value::Value;
+}
+#[cfg(feature = "yaml")]
+pub use yaml::YAML_EXPANSION_LIMIT;
+#[cfg(feature = "clap")]
+mod clap_rs;
+#[cfg(feature = "config")]
+mod config_rs;
+#[cfg(feature = "figment")]
+mod figment_rs;
+#[cfg(feature = "clap")]
+pub use clap_rs::SENSITIVE_ARG_PATTERNS;
+#[cfg(feature = "k8s")]
+mod k8s;
+#[cfg(feature = "k8s")]
+pub use k8s::K8S_PODINFO_DIR;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+mod binary;
+#[cfg(feature = "dotenv")]
+mod dotenv;
+#[cfg(feature = "lambda")]
+mod lambda;
+#[cfg(feature = "log-kv")]
+mod log_kv;
+#[cfg(feature = "urlencoded")]
+mod urlencoded;
+#[cfg(feature = "log-kv")]
+pub use log_kv::ContextKv;
+#[cfg(feature = "sentry")]
+mod sentry_rs;
+#[cfg(feature = "sentry")]
+pub use sentry_rs::{sentry_event, SENTRY_CONTEXT_KEY};
+#[cfg(feature = "flatbuffers")]
+mod flatbuffer;
+#[cfg(feature = "flatbuffers")]
+pub use flatbuffer::{FlatContextView, FlatValue, FLATBUFFER_IDENTIFIER};
+#[cfg(feature = "http")]
+mod http_rs;
+#[cfg(feature = "http")]
+pub use http_rs::HEADERS_TRANSPORT;
+#[cfg(feature = "system")]
+mod providers;
+#[cfg(feature = "system")]
+pub use providers::{BuildInfoProvider, ContextProvider, ProcessProvider, SystemProvider};
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use watch::{FileWatch, WatchEvent};
+#[cfg(feature = "compress")]
+mod compress;
+#[cfg(feature = "compress")]
+pub use compress::{Codec, DECOMPRESSION_LIMIT};
+#[cfg(feature = "ron")]
+mod ron_rs;
+#[cfg(feature = "xml")]
+mod xml;
@@ -0,0 +1,143 @@
+//! Lifecycle states of contexts.
+//!
+//! Lifecycle tracking is optional: it starts with [`Context::start_lifecycle`], which puts the
+//! context in the [`Lifecycle::Building`] state. The context then moves forward only, one state
+//! at a time, each transition failing with an [`InvalidState`] error otherwise:
+//!
+//! * [`Context::activate`]: `Building` to `Active`, once the context is fully populated;
+//! * [`Context::seal`]: `Active` to `Sealed`, after which entries can no longer change: inserts
+//!   and removals are ignored and the `try_*` methods fail with an [`InvalidState`] error;
+//! * [`Context::emit`]: `Sealed` to `Emitted`, returning the dump of the context exactly once,
+//!   as let through by the emission hooks, see [`before_emit`](crate::before_emit).
+//!
+//! Since a sealed context cannot change, the dump returned by [`Context::emit`] is the final
+//! state of the context, which can be proven by comparing it with the context afterwards.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Lifecycle};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.start_lifecycle().unwrap();
+//! ctx.insert("user".to_string(), Value::U64(42));
+//! ctx.activate().unwrap();
+//! ctx.seal().unwrap();
+//!
+//! ctx.insert("user".to_string(), Value::U64(0));
+//! assert_eq!(ctx.get("user"), Some(&Value::U64(42)));
+//!
+//! let dump = ctx.emit().unwrap();
+//! assert_eq!(ctx.lifecycle(), Some(Lifecycle::Emitted));
+//! assert!(ctx.emit().is_err());
+//! assert_eq!(dump, ctx.inner());
+//! ```
+use crate::{Context, Contextualize, InvalidState, Unauthorized};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Lifecycle state of a context, see [`Context::start_lifecycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lifecycle {
+    /// The context is being populated.
+    Building,
+    /// The context is in use.
+    Active,
+    /// The entries of the context can no longer change.
+    Sealed,
+    /// The context was emitted, see [`Context::emit`].
+    Emitted,
+}
+
+impl Context {
+    /// Starts tracking the lifecycle of the context, in the [`Lifecycle::Building`] state.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the lifecycle was not tracked yet
+    /// * `Err(e)` containing an [`InvalidState`] error otherwise
+    pub fn start_lifecycle(&mut self) -> cdumay_core::Result<()> {
+        match self.lifecycle {
+            None => {
+                self.lifecycle = Some(Lifecycle::Building);
+                Ok(())
+            }
+            Some(state) => Err(self.invalid_state(state, "start the lifecycle of")),
+        }
+    }
+
+    /// Returns the lifecycle state of the context, or `None` if it is not tracked.
+    pub fn lifecycle(&self) -> Option<Lifecycle> {
+        self.lifecycle
+    }
+
+    /// Moves the context from [`Lifecycle::Building`] to [`Lifecycle::Active`].
+    pub fn activate(&mut self) -> cdumay_core::Result<()> {
+        self.transition(Lifecycle::Building, Lifecycle::Active, "activate")
+    }
+
+    /// Moves the context from [`Lifecycle::Active`] to [`Lifecycle::Sealed`].
+    pub fn seal(&mut self) -> cdumay_core::Result<()> {
+        self.transition(Lifecycle::Active, Lifecycle::Sealed, "seal")
+    }
+
+    /// Moves the context from [`Lifecycle::Sealed`] to [`Lifecycle::Emitted`], returning its dump
+    /// as let through by the emission hooks, see [`Context::prepare_emit`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<BTreeMap<String, Value>>` which is:
+    /// * `Ok(dump)` the first time the sealed context is emitted
+    /// * `Err(e)` containing an [`InvalidState`] error if the context is not sealed, including
+    ///   when it was already emitted, or an [`Unauthorized`] error if an emission hook denied it,
+    ///   in which case the context stays sealed
+    pub fn emit(&mut self) -> cdumay_core::Result<BTreeMap<String, Value>> {
+        self.transition(Lifecycle::Sealed, Lifecycle::Emitted, "emit")?;
+        match self.prepare_emit() {
+            Some(dump) => Ok(dump),
+            None => {
+                self.lifecycle = Some(Lifecycle::Sealed);
+                Err(Unauthorized::new()
+                    .with_message("Emission of the context was denied by a hook".to_string())
+                    .with_details(self.error_details())
+                    .into())
+            }
+        }
+    }
+
+    /// Returns `true` if the entries of the context can no longer change.
+    pub fn is_sealed(&self) -> bool {
+        self.lifecycle >= Some(Lifecycle::Sealed)
+    }
+
+    /// Fails with an [`InvalidState`] error if the context is sealed.
+    pub(crate) fn check_not_sealed(&self) -> cdumay_core::Result<()> {
+        match self.lifecycle {
+            Some(state) if self.is_sealed() => Err(self.invalid_state(state, "modify")),
+            _ => Ok(()),
+        }
+    }
+
+    fn transition(&mut self, from: Lifecycle, to: Lifecycle, action: &str) -> cdumay_core::Result<()> {
+        match self.lifecycle {
+            Some(state) if state == from => {
+                self.lifecycle = Some(to);
+                Ok(())
+            }
+            Some(state) => Err(self.invalid_state(state, action)),
+            None => Err(InvalidState::new()
+                .with_message(format!("Cannot {} a context whose lifecycle is not tracked", action))
+                .with_details(self.error_details())
+                .into()),
+        }
+    }
+
+    fn invalid_state(&self, state: Lifecycle, action: &str) -> cdumay_core::Error {
+        InvalidState::new()
+            .with_message(format!("Cannot {} a context in the {:?} state", action, state))
+            .with_details(self.error_details())
+            .into()
+    }
+}
@@ -0,0 +1,280 @@
+//! Context linting.
+//!
+//! [`Context::lint`] flags entries which should not be emitted as they are, so that context
+//! hygiene can be asserted in CI tests or checked at runtime before a context leaves the process:
+//!
+//! * [`LintKind::SuspectedSecret`]: a key named like a credential, or a value shaped like one
+//!   (bearer token, JWT, private key, cloud access key), not tagged [`Sensitivity::Secret`];
+//! * [`LintKind::LongValue`]: a string or byte string longer than
+//!   [`LintOptions::with_max_value_len`], or a sequence or map with more items than
+//!   [`LintOptions::with_max_items`];
+//! * [`LintKind::HighCardinalityKey`]: a key embedding an identifier, such as `user_48213377` or a
+//!   UUID, which multiplies the number of distinct keys in downstream indexes;
+//! * [`LintKind::NonPortableValue`]: a value which cannot be dumped to JSON, TOML or YAML, see
+//!   [`Context::check_serializable`];
+//! * [`LintKind::DuplicateValue`]: a value already stored under another key.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, LintKind};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("api_token".to_string(), Value::String("s3cr3t".to_string()));
+//!
+//! let warnings = ctx.lint();
+//! assert_eq!(warnings.len(), 1);
+//! assert_eq!(warnings[0].kind, LintKind::SuspectedSecret);
+//! assert_eq!(warnings[0].path, "api_token");
+//! ```
+use crate::snapshot::{glob_match, is_uuid};
+use crate::{Context, Contextualize, Format, Sensitivity};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Key patterns flagged as suspected secrets by [`Context::lint`].
+///
+/// Patterns are matched against the lowercased key where `-` is replaced by `_`, and may contain
+/// `*` wildcards.
+pub const LINT_SECRET_KEY_PATTERNS: &[&str] = &[
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*token*",
+    "*api_key*",
+    "*apikey*",
+    "*credential*",
+    "*private_key*",
+    "authorization",
+    "cookie",
+];
+
+/// Kind of problem reported by [`Context::lint`], see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintKind {
+    /// Credential not tagged [`Sensitivity::Secret`].
+    SuspectedSecret,
+    /// Value too long to be useful in a context.
+    LongValue,
+    /// Key embedding an identifier.
+    HighCardinalityKey,
+    /// Value which cannot be dumped to every format.
+    NonPortableValue,
+    /// Value already stored under another key.
+    DuplicateValue,
+}
+
+/// A problem found by [`Context::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// Kind of problem.
+    pub kind: LintKind,
+    /// Dotted path of the offending entry.
+    pub path: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Thresholds used by [`Context::lint_with`].
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    max_value_len: usize,
+    max_items: usize,
+    ignored: Vec<LintKind>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            max_value_len: 1024,
+            max_items: 100,
+            ignored: Vec::new(),
+        }
+    }
+}
+
+impl LintOptions {
+    /// Creates options flagging strings longer than 1024 bytes and collections of more than 100
+    /// items.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flags strings and byte strings longer than `len` bytes.
+    pub fn with_max_value_len(mut self, len: usize) -> Self {
+        self.max_value_len = len;
+        self
+    }
+
+    /// Flags sequences and maps holding more than `items` items.
+    pub fn with_max_items(mut self, items: usize) -> Self {
+        self.max_items = items;
+        self
+    }
+
+    /// Disables the check reporting `kind`.
+    pub fn ignore(mut self, kind: LintKind) -> Self {
+        self.ignored.push(kind);
+        self
+    }
+}
+
+impl Context {
+    /// Returns the problems found in the entries of the dump, with the default [`LintOptions`].
+    pub fn lint(&self) -> Vec<LintWarning> {
+        self.lint_with(&LintOptions::default())
+    }
+
+    /// Returns the problems found in the entries of the dump, ordered by key.
+    pub fn lint_with(&self, options: &LintOptions) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+        let mut seen: BTreeMap<&Value, &str> = BTreeMap::new();
+        let data = self.inner();
+        let config = self.effective_config(None);
+        for (k, v) in &data {
+            let secret = self.sensitivity(k) == Sensitivity::Secret;
+            walk(k, v, secret, options, &mut warnings);
+            for format in [Format::Json, Format::Toml, Format::Yaml] {
+                if let Err(err) = self.prepare(format, k, v.clone(), &config) {
+                    warnings.push(warning(LintKind::NonPortableValue, k, err.message()));
+                    break;
+                }
+            }
+            if is_distinctive(v) {
+                match seen.get(v) {
+                    Some(other) => warnings.push(warning(LintKind::DuplicateValue, k, &format!("Same value as '{}'", other))),
+                    None => {
+                        seen.insert(v, k);
+                    }
+                }
+            }
+        }
+        warnings.retain(|warning| !options.ignored.contains(&warning.kind));
+        warnings
+    }
+}
+
+fn warning(kind: LintKind, path: &str, message: &str) -> LintWarning {
+    LintWarning {
+        kind,
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Checks `value` stored at `path` and its nested values, `secret` if the entry is tagged
+/// [`Sensitivity::Secret`].
+fn walk(path: &str, mut value: &Value, secret: bool, options: &LintOptions, warnings: &mut Vec<LintWarning>) {
+    while let Value::Option(Some(inner)) | Value::Newtype(inner) = value {
+        value = inner;
+    }
+    let key = path.rsplit('.').next().unwrap_or(path);
+    if looks_high_cardinality(key) {
+        warnings.push(warning(
+            LintKind::HighCardinalityKey,
+            path,
+            &format!("Key '{}' embeds an identifier", key),
+        ));
+    }
+    match value {
+        _ if secret => {}
+        _ if looks_like_secret_key(key) => {
+            warnings.push(warning(
+                LintKind::SuspectedSecret,
+                path,
+                "Key looks like a credential but is not tagged secret",
+            ));
+        }
+        Value::String(s) if looks_like_secret_value(s) => {
+            warnings.push(warning(
+                LintKind::SuspectedSecret,
+                path,
+                "Value looks like a credential but is not tagged secret",
+            ));
+        }
+        _ => {}
+    }
+    let (len, items) = match value {
+        Value::String(s) => (s.len(), 0),
+        Value::Bytes(b) => (b.len(), 0),
+        Value::Seq(items) => (0, items.len()),
+        Value::Map(map) => (0, map.len()),
+        _ => (0, 0),
+    };
+    if len > options.max_value_len {
+        warnings.push(warning(
+            LintKind::LongValue,
+            path,
+            &format!("Value of {} bytes exceeds {}", len, options.max_value_len),
+        ));
+    }
+    if items > options.max_items {
+        warnings.push(warning(
+            LintKind::LongValue,
+            path,
+            &format!("Value of {} items exceeds {}", items, options.max_items),
+        ));
+    }
+    match value {
+        Value::Seq(items) => items
+            .iter()
+            .enumerate()
+            .for_each(|(index, item)| walk(&format!("{}.{}", path, index), item, secret, options, warnings)),
+        Value::Map(map) => map.iter().for_each(|(k, v)| {
+            if let Value::String(k) = k {
+                walk(&format!("{}.{}", path, k), v, secret, options, warnings)
+            }
+        }),
+        _ => {}
+    }
+}
+
+fn looks_like_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace('-', "_");
+    LINT_SECRET_KEY_PATTERNS.iter().any(|pattern| glob_match(pattern, &key))
+}
+
+fn looks_like_secret_value(value: &str) -> bool {
+    let is_base64url = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let jwt = value.starts_with("eyJ") && value.split('.').count() == 3 && value.split('.').all(is_base64url);
+    let aws = value.len() == 20
+        && (value.starts_with("AKIA") || value.starts_with("ASIA"))
+        && value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let bearer = value.len() > 7 && value[..7].eq_ignore_ascii_case("bearer ");
+    let basic = value.len() > 6 && value[..6].eq_ignore_ascii_case("basic ");
+    jwt || aws
+        || bearer
+        || basic
+        || value.contains("PRIVATE KEY-----")
+        || ["sk_live_", "ghp_", "gho_", "github_pat_", "xoxb-", "xoxp-"]
+            .iter()
+            .any(|prefix| value.starts_with(prefix))
+}
+
+fn looks_high_cardinality(key: &str) -> bool {
+    let uuid = key.len() >= 36 && (0..=key.len() - 36).any(|start| key.get(start..start + 36).is_some_and(is_uuid));
+    let number = key.split(|c: char| !c.is_ascii_digit()).any(|run| run.len() >= 6);
+    let hash = key
+        .split(|c: char| !c.is_ascii_hexdigit())
+        .any(|run| run.len() >= 16 && run.chars().any(|c| c.is_ascii_digit()));
+    uuid || number || hash
+}
+
+/// Returns `true` if two keys holding `value` are unlikely to be a coincidence.
+fn is_distinctive(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.len() >= 8,
+        Value::Bytes(b) => b.len() >= 8,
+        Value::Seq(items) => !items.is_empty(),
+        Value::Map(map) => !map.is_empty(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => is_distinctive(inner),
+        _ => false,
+    }
+}
@@ -0,0 +1,106 @@
+//! `log` crate structured key-values.
+//!
+//! With the "log-kv" feature, a context can be attached to the records of the `log` crate.
+//! [`Context::as_kv`] returns a [`ContextKv`], a redacted dump of the context which is both a
+//! [`Source`], holding one key-value pair per entry, and a [`ToValue`], holding the whole context
+//! as a map, so that it can be captured by the logging macros:
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! log::info!(ctx = ctx.as_kv(); "request received");
+//! ```
+//!
+//! [`Context`] itself implements [`Source`] over its stored entries, secrets being redacted.
+//! Scalars map to the matching primitive of [`log::kv::Value`], null values to
+//! [`log::kv::Value::null`], and sequences, maps and bytes are captured through `serde`.
+use crate::{Context, Contextualize, Sensitivity, REDACTED};
+use log::kv::{Error, Key, Source, ToValue, VisitSource};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// A redacted dump of a context, usable as the key-values of a `log` record, see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextKv(BTreeMap<String, Value>);
+
+impl ContextKv {
+    /// Returns the entries of the dump.
+    pub fn entries(&self) -> &BTreeMap<String, Value> {
+        &self.0
+    }
+}
+
+impl Source for ContextKv {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        for (k, v) in &self.0 {
+            visitor.visit_pair(Key::from_str(k), to_kv(v))?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: Key) -> Option<log::kv::Value<'_>> {
+        self.0.get(key.as_str()).map(to_kv)
+    }
+
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl ToValue for ContextKv {
+    fn to_value(&self) -> log::kv::Value<'_> {
+        log::kv::Value::from_serde(&self.0)
+    }
+}
+
+impl Context {
+    /// Returns a dump of the context to attach to `log` records, with the configuration in effect
+    /// applied and secrets redacted, see [`Context::effective_config`].
+    ///
+    /// This method is only available when the "log-kv" feature is enabled.
+    pub fn as_kv(&self) -> ContextKv {
+        ContextKv(self.redacted().inner())
+    }
+}
+
+/// Visits the stored entries of the context, secrets being redacted. Deferred values, default
+/// values, transformers and the configuration are not applied, use [`Context::as_kv`] for a full
+/// dump.
+impl Source for Context {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        for (k, v) in &self.data {
+            let value = match self.sensitivity(k) {
+                Sensitivity::Secret => log::kv::Value::from(REDACTED),
+                _ => to_kv(v),
+            };
+            visitor.visit_pair(Key::from_str(k), value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps a `serde_value::Value` into a `log::kv::Value`.
+fn to_kv(value: &Value) -> log::kv::Value<'_> {
+    match value {
+        Value::Bool(b) => log::kv::Value::from(*b),
+        Value::U8(n) => log::kv::Value::from(*n),
+        Value::U16(n) => log::kv::Value::from(*n),
+        Value::U32(n) => log::kv::Value::from(*n),
+        Value::U64(n) => log::kv::Value::from(*n),
+        Value::I8(n) => log::kv::Value::from(*n),
+        Value::I16(n) => log::kv::Value::from(*n),
+        Value::I32(n) => log::kv::Value::from(*n),
+        Value::I64(n) => log::kv::Value::from(*n),
+        Value::F32(n) => log::kv::Value::from(*n),
+        Value::F64(n) => log::kv::Value::from(*n),
+        Value::Char(c) => log::kv::Value::from(*c),
+        Value::String(s) => log::kv::Value::from(s.as_str()),
+        Value::Unit | Value::Option(None) => log::kv::Value::null(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => to_kv(inner),
+        Value::Seq(_) | Value::Map(_) | Value::Bytes(_) => log::kv::Value::from_serde(value),
+    }
+}
@@ -0,0 +1,124 @@
+//! Lossy dumps.
+//!
+//! A dump failing while an error is being reported loses the context when it is needed the most.
+//! [`Context::to_json_lossy`], [`Context::to_toml_lossy`] and [`Context::to_yaml_lossy`] never
+//! fail: an entry which cannot be written, because of the numeric or map key policy, a value the
+//! format does not support or a serializer error, is replaced with a
+//! `"<unserializable: reason>"` placeholder, and the reason is recorded under
+//! [`UNSERIALIZABLE_KEY`], as a map of the replaced keys. The configuration in effect applies as
+//! it does to the strict dumps, see [`Context::effective_config`].
+//!
+//! An entry stored under [`UNSERIALIZABLE_KEY`] is replaced when substitutions are recorded.
+//!
+//! ```rust
+//! # #[cfg(feature = "toml")]
+//! # {
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("job".to_string(), Value::String("export".to_string()));
+//! ctx.insert("parent".to_string(), Value::Option(None));
+//! assert!(ctx.to_toml(false).is_err());
+//!
+//! let dump = ctx.to_toml_lossy(false);
+//! assert!(dump.contains(r#"parent = "<unserializable: Null value at 'parent' cannot be written to TOML>""#));
+//! assert!(dump.contains("[unserializable]"));
+//! # }
+//! ```
+use crate::order::OrderedEntries;
+use crate::{Context, ContextConfig, Format};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which lossy dumps record the replaced entries and why.
+pub const UNSERIALIZABLE_KEY: &str = crate::keys::UNSERIALIZABLE;
+
+impl Context {
+    /// Serializes the context to a JSON string, replacing the entries which cannot be written,
+    /// see the [module documentation](self).
+    #[cfg(feature = "json")]
+    pub fn to_json_lossy(&self, pretty: bool) -> String {
+        self.lossy_dump(Format::Json, |entries: &OrderedEntries| match pretty {
+            true => serde_json::to_string_pretty(entries),
+            false => serde_json::to_string(entries),
+        })
+    }
+
+    /// Serializes the context to a TOML string, replacing the entries which cannot be written,
+    /// see the [module documentation](self).
+    #[cfg(feature = "toml")]
+    pub fn to_toml_lossy(&self, pretty: bool) -> String {
+        let format = self.effective_config(None).tagged_values().then_some(Format::Toml);
+        self.lossy_dump(Format::Toml, |entries: &OrderedEntries| {
+            let restored = crate::tagged::Restored { entries: &entries.0, format };
+            match pretty {
+                true => toml::to_string_pretty(&restored),
+                false => toml::to_string(&restored),
+            }
+        })
+    }
+
+    /// Serializes the context to a YAML string, replacing the entries which cannot be written,
+    /// see the [module documentation](self).
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml_lossy(&self) -> String {
+        let format = self.effective_config(None).tagged_values().then_some(Format::Yaml);
+        self.lossy_dump(Format::Yaml, |entries: &OrderedEntries| {
+            serde_yaml::to_string(&crate::tagged::Restored { entries: &entries.0, format })
+        })
+    }
+
+    fn lossy_dump<E, F>(&self, format: Format, encode: F) -> String
+    where
+        E: std::fmt::Display,
+        F: Fn(&OrderedEntries) -> Result<String, E>,
+    {
+        let config = self.effective_config(None);
+        let dump = crate::metrics::observe_dump(format, || {
+            let entries = self.lossy_entries(format, &config, &encode);
+            Ok(encode(&entries).unwrap_or_else(|err| {
+                // Every entry was checked alone, only their combination can still fail.
+                let substitutions = BTreeMap::from([(Value::String("*".to_string()), placeholder(&err))]);
+                let fallback = OrderedEntries(vec![(UNSERIALIZABLE_KEY.to_string(), Value::Map(substitutions))]);
+                encode(&fallback).unwrap_or_default()
+            }))
+        });
+        dump.unwrap_or_default()
+    }
+
+    fn lossy_entries<E, F>(&self, format: Format, config: &ContextConfig, encode: &F) -> OrderedEntries
+    where
+        E: std::fmt::Display,
+        F: Fn(&OrderedEntries) -> Result<String, E>,
+    {
+        let mut substitutions = BTreeMap::new();
+        let mut entries = Vec::new();
+        for (k, v) in self.ordered_entries() {
+            let prepared = match self.prepare(format, &k, v, config) {
+                Ok(None) => continue,
+                Ok(Some(value)) => encode(&OrderedEntries(vec![(k.clone(), value.clone())]))
+                    .map(|_| value)
+                    .map_err(|err| placeholder(&err)),
+                Err(err) => Err(placeholder(&err.message())),
+            };
+            let value = prepared.unwrap_or_else(|placeholder| {
+                substitutions.insert(Value::String(k.clone()), placeholder.clone());
+                placeholder
+            });
+            entries.push((k, value));
+        }
+        let mut entries = self.fit(OrderedEntries(entries), config, |entries| {
+            encode(entries).map_or(usize::MAX, |dump| dump.len())
+        });
+        if !substitutions.is_empty() {
+            entries.0.retain(|(k, _)| k != UNSERIALIZABLE_KEY);
+            entries.0.push((UNSERIALIZABLE_KEY.to_string(), Value::Map(substitutions)));
+        }
+        entries
+    }
+}
+
+fn placeholder(reason: &dyn std::fmt::Display) -> Value {
+    Value::String(format!("<unserializable: {}>", reason))
+}
@@ -0,0 +1,273 @@
+//! Capacity-bounded contexts.
+//!
+//! A [`BoundedLruContext`] holds at most a fixed number of entries: inserting a new key into a
+//! full context evicts the least recently used one, so that a per-connection scratch context
+//! cannot grow unboundedly. Inserting a key and reading it with [`Contextualize::get`] mark it as
+//! used, while [`Contextualize::contains_key`], [`BoundedLruContext::peek`] and dumps do not.
+//!
+//! Hooks set with [`BoundedLruContext::on_evict`] receive every evicted entry, and
+//! [`BoundedLruContext::stats`] counts the hits and misses of [`Contextualize::get`] and the
+//! evictions.
+//!
+//! ```rust
+//! use cdumay_context::{BoundedLruContext, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = BoundedLruContext::with_capacity(2);
+//! ctx.insert("a".to_string(), Value::U8(1));
+//! ctx.insert("b".to_string(), Value::U8(2));
+//! assert!(ctx.get("a").is_some());
+//! ctx.insert("c".to_string(), Value::U8(3));
+//! assert!(!ctx.contains_key("b"));
+//!
+//! let stats = ctx.stats();
+//! assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 0, 1));
+//! ```
+use crate::{ContextDump, Contextualize};
+use serde::{Serialize, Serializer};
+use serde_value::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Capacity of a [`BoundedLruContext`] created with [`Contextualize::new`].
+pub const DEFAULT_LRU_CAPACITY: usize = 1024;
+
+type EvictionHook = Arc<dyn Fn(&str, &Value) + Send + Sync>;
+
+/// Counters of a [`BoundedLruContext`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LruStats {
+    /// Reads of a present key.
+    pub hits: u64,
+    /// Reads of a missing key.
+    pub misses: u64,
+    /// Entries evicted to stay within the capacity.
+    pub evictions: u64,
+}
+
+/// Order in which the keys were last used.
+#[derive(Debug, Default, Clone)]
+struct Recency {
+    tick: u64,
+    ticks: BTreeMap<String, u64>,
+    order: BTreeMap<u64, String>,
+}
+
+impl Recency {
+    fn touch(&mut self, k: &str) {
+        self.tick += 1;
+        if let Some(previous) = self.ticks.insert(k.to_string(), self.tick) {
+            self.order.remove(&previous);
+        }
+        self.order.insert(self.tick, k.to_string());
+    }
+
+    fn remove(&mut self, k: &str) {
+        if let Some(tick) = self.ticks.remove(k) {
+            self.order.remove(&tick);
+        }
+    }
+
+    fn pop_oldest(&mut self) -> Option<String> {
+        let (_, k) = self.order.pop_first()?;
+        self.ticks.remove(&k);
+        Some(k)
+    }
+}
+
+/// A context evicting its least recently used entries, see the [module documentation](self).
+pub struct BoundedLruContext {
+    capacity: usize,
+    data: BTreeMap<String, Value>,
+    recency: Mutex<Recency>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    hooks: Vec<EvictionHook>,
+}
+
+impl std::fmt::Debug for BoundedLruContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoundedLruContext")
+            .field("capacity", &self.capacity)
+            .field("data", &self.data)
+            .field("stats", &self.stats())
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+/// Clones the entries, their recency, the counters and the hooks.
+impl Clone for BoundedLruContext {
+    fn clone(&self) -> Self {
+        let stats = self.stats();
+        Self {
+            capacity: self.capacity,
+            data: self.data.clone(),
+            recency: Mutex::new(self.lock().clone()),
+            hits: AtomicU64::new(stats.hits),
+            misses: AtomicU64::new(stats.misses),
+            evictions: AtomicU64::new(stats.evictions),
+            hooks: self.hooks.clone(),
+        }
+    }
+}
+
+impl BoundedLruContext {
+    /// Creates an empty context holding at most `capacity` entries. With a capacity of zero,
+    /// every inserted entry is evicted right away.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            data: BTreeMap::new(),
+            recency: Mutex::new(Recency::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Calls `hook` with the key and the value of every evicted entry.
+    pub fn on_evict<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &Value) + Send + Sync + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of entries, evicting the least recently used ones if the
+    /// context holds more.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Returns the value stored under `k` without marking it as used nor counting a hit or a
+    /// miss.
+    pub fn peek(&self, k: &str) -> Option<&Value> {
+        self.data.get(k)
+    }
+
+    /// Returns the keys, from the least to the most recently used.
+    pub fn keys_by_recency(&self) -> Vec<String> {
+        self.lock().order.values().cloned().collect()
+    }
+
+    /// Returns the counters.
+    pub fn stats(&self) -> LruStats {
+        LruStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the counters to zero.
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Recency> {
+        self.recency.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Evicts the least recently used entries until the context is within its capacity.
+    fn evict(&mut self) {
+        while self.data.len() > self.capacity {
+            let Some(k) = self.lock().pop_oldest() else {
+                return;
+            };
+            if let Some(value) = self.data.remove(&k) {
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                self.hooks.iter().for_each(|hook| hook(&k, &value));
+            }
+        }
+    }
+}
+
+impl Contextualize for BoundedLruContext {
+    /// Creates an empty context holding at most [`DEFAULT_LRU_CAPACITY`] entries.
+    fn new() -> Self {
+        Self::with_capacity(DEFAULT_LRU_CAPACITY)
+    }
+
+    /// Inserts a key-value pair, marking the key as used, and evicts the least recently used
+    /// entry if the context is over its capacity.
+    fn insert(&mut self, k: String, v: Value) {
+        self.lock().touch(&k);
+        self.data.insert(k, v);
+        self.evict();
+    }
+
+    /// Returns the value stored under `k`, marking the key as used and counting a hit, or counts
+    /// a miss.
+    fn get(&self, k: &str) -> Option<&Value> {
+        match self.data.get(k) {
+            Some(value) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.lock().touch(k);
+                Some(value)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Inserts the entries in key order, see [`Contextualize::insert`].
+    fn extend(&mut self, data: BTreeMap<String, Value>) {
+        data.into_iter().for_each(|(k, v)| self.insert(k, v));
+    }
+
+    fn inner(&self) -> BTreeMap<String, Value> {
+        self.data.clone()
+    }
+
+    fn inner_ref(&self) -> Cow<'_, BTreeMap<String, Value>> {
+        Cow::Borrowed(&self.data)
+    }
+
+    fn remove(&mut self, k: &str) -> Option<Value> {
+        self.lock().remove(k);
+        self.data.remove(k)
+    }
+
+    /// Removes all the entries, the counters are kept.
+    fn clear(&mut self) {
+        *self.lock() = Recency::default();
+        self.data.clear();
+    }
+
+    fn contains_key(&self, k: &str) -> bool {
+        self.data.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl ContextDump for BoundedLruContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.inner()
+    }
+}
+
+/// Serializes the entries, in key order.
+impl Serialize for BoundedLruContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
@@ -0,0 +1,133 @@
+//! Non-string map key policy.
+//!
+//! A [`Value::Map`] accepts keys of any type, but JSON and TOML only know string keys, and each
+//! serializer handles other keys its own way: some are converted, others fail with an error which
+//! does not say where the key is. The [`MapKeyPolicy`] of a context, set with
+//! [`Context::set_map_key_policy`], decides what happens to such keys in JSON, TOML and YAML
+//! dumps, so that the same context gives the same result in every format:
+//!
+//! * [`MapKeyPolicy::Stringify`] (default): keys are written as strings, scalars in their plain
+//!   form (`404`, `true`) and sequences or maps as one-line JSON;
+//! * [`MapKeyPolicy::Reject`]: the dump fails with an [`InvalidMapKey`] error naming the path of the
+//!   map;
+//! * [`MapKeyPolicy::Drop`]: entries with a non-string key are left out.
+//!
+//! When a stringified key equals a string key of the same map, the entry with the string key is
+//! kept.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{Context, Contextualize, MapKeyPolicy};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("retries".to_string(), Value::Map(BTreeMap::from([(Value::U16(503), Value::U8(2))])));
+//! assert_eq!(ctx.to_json(false).unwrap(), r#"{"retries":{"503":2}}"#);
+//!
+//! ctx.set_map_key_policy(MapKeyPolicy::Reject);
+//! assert_eq!(ctx.to_json(false).unwrap_err().code(), 400);
+//! # }
+//! ```
+use crate::snapshot::write_value;
+use crate::{Context, InvalidMapKey, SnapshotOptions};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// What to do with map keys which are not strings, see the [module documentation](self).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MapKeyPolicy {
+    /// Write keys as strings (default).
+    #[default]
+    Stringify,
+    /// Fail with an [`InvalidMapKey`] error.
+    Reject,
+    /// Leave the entry out.
+    Drop,
+}
+
+impl MapKeyPolicy {
+    /// Applies the policy to the maps nested in `value`, stored at `path`.
+    pub(crate) fn apply(&self, path: &str, value: Value) -> cdumay_core::Result<Value> {
+        match value {
+            Value::Option(Some(inner)) => Ok(Value::Option(Some(Box::new(self.apply(path, *inner)?)))),
+            Value::Newtype(inner) => Ok(Value::Newtype(Box::new(self.apply(path, *inner)?))),
+            Value::Seq(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| self.apply(&format!("{}.{}", path, index), item))
+                .collect::<cdumay_core::Result<_>>()
+                .map(Value::Seq),
+            Value::Map(map) => {
+                let mut entries = BTreeMap::new();
+                let mut others = Vec::new();
+                for (k, v) in map {
+                    match k {
+                        Value::String(k) => {
+                            let v = self.apply(&format!("{}.{}", path, k), v)?;
+                            entries.insert(Value::String(k), v);
+                        }
+                        k => others.push((k, v)),
+                    }
+                }
+                for (k, v) in others {
+                    let key = match self {
+                        MapKeyPolicy::Drop => continue,
+                        MapKeyPolicy::Reject => {
+                            return Err(InvalidMapKey::new()
+                                .with_message(format!("Map at '{}' has a non-string key {}", path, stringify(&k)))
+                                .into());
+                        }
+                        MapKeyPolicy::Stringify => stringify(&k),
+                    };
+                    if !entries.contains_key(&Value::String(key.clone())) {
+                        let v = self.apply(&format!("{}.{}", path, key), v)?;
+                        entries.insert(Value::String(key), v);
+                    }
+                }
+                Ok(Value::Map(entries))
+            }
+            value => Ok(value),
+        }
+    }
+}
+
+/// Renders a map key as a string.
+pub(crate) fn stringify(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::F32(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::Unit | Value::Option(None) => "null".to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => stringify(inner),
+        other => {
+            let options = SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+            let mut rendered = String::new();
+            write_value(&mut rendered, "", other, &options, 0);
+            rendered.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+        }
+    }
+}
+
+impl Context {
+    /// Sets how non-string map keys are written in dumps.
+    pub fn set_map_key_policy(&mut self, policy: MapKeyPolicy) {
+        self.config.map_key_policy = Some(policy);
+    }
+
+    /// Returns how non-string map keys are written in dumps, see [`Context::effective_config`].
+    pub fn map_key_policy(&self) -> MapKeyPolicy {
+        self.effective_config(None).map_key_policy()
+    }
+}
@@ -0,0 +1,49 @@
+//! Context templating for job fan-out.
+//!
+//! [`Context::expand`] derives one child context per combination of the values of a matrix, so
+//! that each job of a fan-out gets its own context. Children are [forks](Context::fork) of the
+//! template: they record its id under `parent_id`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("batch".to_string(), Value::String("2024-06-01".to_string()));
+//!
+//! let matrix = BTreeMap::from([
+//!     ("region".to_string(), vec![Value::String("eu".to_string()), Value::String("us".to_string())]),
+//!     ("shard".to_string(), vec![Value::U8(0), Value::U8(1), Value::U8(2)]),
+//! ]);
+//! let children = ctx.expand(matrix);
+//! assert_eq!(children.len(), 6);
+//! assert_eq!(children[4].get("region"), Some(&Value::String("us".to_string())));
+//! assert_eq!(children[4].get("shard"), Some(&Value::U8(1)));
+//! assert_eq!(children[4].get("batch"), ctx.get("batch"));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Returns one child context per combination of the values of `matrix`.
+    ///
+    /// Combinations are listed in key order, the values of the last key varying fastest. A key
+    /// without values yields no child, an empty matrix a single one. Values under protected or
+    /// frozen keys of the template are left untouched.
+    pub fn expand(&self, matrix: BTreeMap<String, Vec<Value>>) -> Vec<Context> {
+        let len = matrix.values().map(Vec::len).product();
+        let mut children = Vec::with_capacity(len);
+        for combination in 0..len {
+            let mut child = self.fork();
+            let mut index = combination;
+            for (k, values) in matrix.iter().rev() {
+                child.insert(k.clone(), values[index % values.len()].clone());
+                index /= values.len();
+            }
+            children.push(child);
+        }
+        children
+    }
+}
@@ -0,0 +1,90 @@
+//! Merging with strategies.
+//!
+//! [`Contextualize::extend`] overwrites top-level keys, which replaces nested maps as a whole.
+//! [`Context::merge`] takes a [`MergeStrategy`] instead, so that a request-scoped context can be
+//! layered over an application base context without losing nested sections.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, MergeStrategy};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! fn map(entries: &[(&str, Value)]) -> Value {
+//!     Value::Map(entries.iter().map(|(k, v)| (Value::String(k.to_string()), v.clone())).collect())
+//! }
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("app".to_string(), map(&[("name", Value::String("api".to_string())), ("version", Value::U8(1))]));
+//!
+//! let request = BTreeMap::from([("app".to_string(), map(&[("version", Value::U8(2))]))]);
+//! ctx.merge(request, MergeStrategy::DeepMerge);
+//!
+//! assert_eq!(ctx.at("app").at("name").as_str(), Some("api"));
+//! assert_eq!(ctx.at("app").at("version").as_u64(), Some(2));
+//! ```
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// How [`Context::merge`] combines incoming values with the existing ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Incoming values replace existing ones, as [`Contextualize::extend`] does (default).
+    #[default]
+    Overwrite,
+    /// Existing values are kept, incoming values are only stored under missing keys.
+    KeepExisting,
+    /// Maps are merged recursively, other incoming values replace existing ones.
+    DeepMerge,
+    /// Maps are merged recursively and incoming sequences are appended to existing ones, other
+    /// incoming values replace existing ones.
+    AppendSequences,
+}
+
+impl MergeStrategy {
+    /// Returns the combination of `existing` and `incoming`.
+    pub(crate) fn combine(&self, existing: Value, incoming: Value) -> Value {
+        match (self, existing, incoming) {
+            (MergeStrategy::Overwrite, _, incoming) => incoming,
+            (MergeStrategy::KeepExisting, existing, _) => existing,
+            (MergeStrategy::DeepMerge | MergeStrategy::AppendSequences, Value::Map(mut existing), Value::Map(incoming)) => {
+                for (k, v) in incoming {
+                    let v = match existing.remove(&k) {
+                        Some(current) => self.combine(current, v),
+                        None => v,
+                    };
+                    existing.insert(k, v);
+                }
+                Value::Map(existing)
+            }
+            (MergeStrategy::AppendSequences, Value::Seq(mut existing), Value::Seq(incoming)) => {
+                existing.extend(incoming);
+                Value::Seq(existing)
+            }
+            (_, _, incoming) => incoming,
+        }
+    }
+}
+
+impl Context {
+    /// Merges `other` into the context according to `strategy`.
+    ///
+    /// Values are stored with [`Contextualize::insert`], so that protected keys are left untouched.
+    pub fn merge(&mut self, other: BTreeMap<String, Value>, strategy: MergeStrategy) {
+        for (k, incoming) in other {
+            let value = match (self.get(&k), strategy) {
+                (Some(_), MergeStrategy::KeepExisting) => continue,
+                (Some(existing), _) => strategy.combine(existing.clone(), incoming),
+                (None, _) => incoming,
+            };
+            self.insert(k, value);
+        }
+    }
+}
+
+impl SharedContext {
+    /// Atomically merges `other` into the shared context, see [`Context::merge`].
+    pub fn merge(&self, other: BTreeMap<String, Value>, strategy: MergeStrategy) {
+        self.update(|ctx| ctx.merge(other, strategy))
+    }
+}
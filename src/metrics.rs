@@ -0,0 +1,161 @@
+//! Metrics hooks.
+//!
+//! [`ContextMetrics`] receives the internal events of the crate: inserts, dumps and loads with
+//! their size and duration, entries evicted to fit a maximum size, failed dumps and loads, and
+//! accesses to deprecated keys.
+//! Every method does nothing by default, so that an implementation forwarding to Prometheus or
+//! `metrics` only overrides the events it records. The hooks installed with
+//! [`set_context_metrics`] apply to the whole process, and cost a single atomic load per event
+//! while none is installed.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{clear_context_metrics, set_context_metrics, Context, ContextMetrics, Contextualize, Format};
+//! use serde_value::Value;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! #[derive(Default)]
+//! struct DumpedBytes(AtomicUsize);
+//!
+//! impl ContextMetrics for DumpedBytes {
+//!     fn on_dump(&self, _format: Format, bytes: usize, _elapsed: Duration) {
+//!         self.0.fetch_add(bytes, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! let metrics = Arc::new(DumpedBytes::default());
+//! set_context_metrics(metrics.clone());
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! let dump = ctx.to_json(false).unwrap();
+//! clear_context_metrics();
+//! assert_eq!(metrics.0.load(Ordering::Relaxed), dump.len());
+//! # }
+//! ```
+use crate::{DeprecationWarning, Format};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Hooks installed with [`set_context_metrics`].
+static METRICS: RwLock<Option<Arc<dyn ContextMetrics>>> = RwLock::new(None);
+
+/// Whether hooks are installed, checked before locking [`METRICS`].
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Receiver of the internal events of the crate, see the [module documentation](self).
+pub trait ContextMetrics: Send + Sync {
+    /// Called when a value is stored under `key`, protected and frozen keys left untouched being
+    /// ignored.
+    fn on_insert(&self, key: &str) {
+        let _ = key;
+    }
+
+    /// Called when a context was dumped to `format`, in `bytes` bytes.
+    fn on_dump(&self, format: Format, bytes: usize, elapsed: Duration) {
+        let _ = (format, bytes, elapsed);
+    }
+
+    /// Called when `bytes` bytes of `format` were parsed into a context.
+    fn on_load(&self, format: Format, bytes: usize, elapsed: Duration) {
+        let _ = (format, bytes, elapsed);
+    }
+
+    /// Called when the entry under `key` is left out of a dump to fit its maximum size.
+    fn on_eviction(&self, key: &str) {
+        let _ = key;
+    }
+
+    /// Called when a dump or a load fails.
+    fn on_error(&self, error: &cdumay_core::Error) {
+        let _ = error;
+    }
+
+    /// Called when a deprecated key is read or written, see [`Context::deprecate_key`](crate::Context::deprecate_key).
+    fn on_deprecated_key(&self, warning: &DeprecationWarning) {
+        let _ = warning;
+    }
+}
+
+/// Metrics ignoring every event.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMetrics;
+
+impl ContextMetrics for NoopMetrics {}
+
+impl<M: ContextMetrics + ?Sized> ContextMetrics for Arc<M> {
+    fn on_insert(&self, key: &str) {
+        (**self).on_insert(key)
+    }
+
+    fn on_dump(&self, format: Format, bytes: usize, elapsed: Duration) {
+        (**self).on_dump(format, bytes, elapsed)
+    }
+
+    fn on_load(&self, format: Format, bytes: usize, elapsed: Duration) {
+        (**self).on_load(format, bytes, elapsed)
+    }
+
+    fn on_eviction(&self, key: &str) {
+        (**self).on_eviction(key)
+    }
+
+    fn on_error(&self, error: &cdumay_core::Error) {
+        (**self).on_error(error)
+    }
+
+    fn on_deprecated_key(&self, warning: &DeprecationWarning) {
+        (**self).on_deprecated_key(warning)
+    }
+}
+
+/// Installs `metrics` for the whole process, replacing the hooks installed before.
+pub fn set_context_metrics<M: ContextMetrics + 'static>(metrics: M) {
+    *METRICS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(metrics));
+    INSTALLED.store(true, Ordering::Release);
+}
+
+/// Removes the hooks installed with [`set_context_metrics`].
+pub fn clear_context_metrics() {
+    INSTALLED.store(false, Ordering::Release);
+    *METRICS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Calls `f` with the installed hooks, if any.
+pub(crate) fn record(f: impl FnOnce(&dyn ContextMetrics)) {
+    if !INSTALLED.load(Ordering::Acquire) {
+        return;
+    }
+    let metrics = METRICS.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    if let Some(metrics) = metrics {
+        f(metrics.as_ref());
+    }
+}
+
+/// Runs the dump `f` to `format`, recording its size and duration, or its error.
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub(crate) fn observe_dump(format: Format, f: impl FnOnce() -> cdumay_core::Result<String>) -> cdumay_core::Result<String> {
+    let start = std::time::Instant::now();
+    let result = f();
+    match &result {
+        Ok(dump) => record(|metrics| metrics.on_dump(format, dump.len(), start.elapsed())),
+        Err(err) => record(|metrics| metrics.on_error(err)),
+    }
+    result
+}
+
+/// Runs the load `f` of `bytes` bytes of `format`, recording its duration, or its error.
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub(crate) fn observe_load<T>(format: Format, bytes: usize, f: impl FnOnce() -> cdumay_core::Result<T>) -> cdumay_core::Result<T> {
+    let start = std::time::Instant::now();
+    let result = f();
+    match &result {
+        Ok(_) => record(|metrics| metrics.on_load(format, bytes, start.elapsed())),
+        Err(err) => record(|metrics| metrics.on_error(err)),
+    }
+    result
+}
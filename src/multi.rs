@@ -0,0 +1,71 @@
+//! Keys holding several values.
+//!
+//! Repeated HTTP headers or CLI flags carry several values for the same name, in order.
+//! [`Context::insert_append`] adds a value to a key instead of replacing it, and
+//! [`Context::get_all`] returns all the values of a key. A key written with `insert_append`
+//! holds its values as a `Value::Seq`, so that dumps render them as sequences, even if it holds
+//! a single one. A value set before with [`Contextualize::insert`] becomes the first of the
+//! values, and writing the key again with `insert` makes it a single-valued key again.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert_append("accept", Value::String("text/html".to_string())).unwrap();
+//! ctx.insert_append("accept", Value::String("application/json".to_string())).unwrap();
+//! ctx.insert("host".to_string(), Value::String("example.com".to_string()));
+//!
+//! assert_eq!(ctx.get_all("accept").len(), 2);
+//! assert_eq!(ctx.get_all("host"), vec![&Value::String("example.com".to_string())]);
+//! assert!(ctx.get_all("missing").is_empty());
+//! assert_eq!(ctx.at("accept").nth(1).as_str(), Some("application/json"));
+//! ```
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+
+impl Context {
+    /// Appends `v` to the values of `k`, see the [module documentation](self).
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<usize>` which is:
+    /// * `Ok(count)` containing the number of values of `k`
+    /// * `Err(e)` containing a [`ProtectedKey`](crate::ProtectedKey) error if the key is protected
+    pub fn insert_append(&mut self, k: &str, v: Value) -> cdumay_core::Result<usize> {
+        let mut values: Vec<Value> = self.get_all(k).into_iter().cloned().collect();
+        values.push(v);
+        let count = values.len();
+        self.try_insert(k.to_string(), Value::Seq(values))?;
+        let k = self.resolve_key(k).into_owned();
+        self.multi.insert(k);
+        Ok(count)
+    }
+
+    /// Returns the values of `k` in order: those appended with [`Context::insert_append`], the
+    /// value set with [`Contextualize::insert`] otherwise, or none if the key is missing.
+    pub fn get_all(&self, k: &str) -> Vec<&Value> {
+        match self.get(k) {
+            Some(Value::Seq(items)) if self.is_multi(k) => items.iter().collect(),
+            Some(value) => vec![value],
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns `true` if `k` was written with [`Context::insert_append`].
+    pub fn is_multi(&self, k: &str) -> bool {
+        self.multi.contains(self.resolve_key(k).as_ref())
+    }
+}
+
+impl SharedContext {
+    /// Atomically appends `v` to the values of `k`, see [`Context::insert_append`].
+    pub fn insert_append(&self, k: &str, v: Value) -> cdumay_core::Result<usize> {
+        self.update(|ctx| ctx.insert_append(k, v))
+    }
+
+    /// Returns clones of the values of `k`, see [`Context::get_all`].
+    pub fn get_all(&self, k: &str) -> Vec<Value> {
+        self.read(|ctx| ctx.get_all(k).into_iter().cloned().collect())
+    }
+}
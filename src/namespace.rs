@@ -0,0 +1,137 @@
+//! Prefix filtering and namespaced views.
+//!
+//! A context shared by several subsystems mixes their keys, such as `http.method`, `db.statement`
+//! and `job.queue`. [`Context::filter_prefix`] copies the entries of one subsystem and
+//! [`Context::retain`] drops entries in place. A [`NamespacedContext`] is a view of a context
+//! whose keys are prefixed with a namespace and a dot, so that libraries can write into a shared
+//! context without clobbering each other's keys.
+//!
+//! Prefixes apply to top-level keys: a section stored as a map under `db` is a single `db` key,
+//! see [`Context::get_path`] to read into it.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, NamespacedContext};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! NamespacedContext::new(&mut ctx, "db").insert("statement", Value::String("SELECT 1".to_string()));
+//! NamespacedContext::new(&mut ctx, "job").insert("statement", Value::String("daily report".to_string()));
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!
+//! assert_eq!(ctx.get_str("db.statement").unwrap(), "SELECT 1");
+//! assert_eq!(ctx.filter_prefix("db.").keys().collect::<Vec<_>>(), vec!["db.statement"]);
+//!
+//! ctx.retain(|k, _| !k.starts_with("job."));
+//! assert_eq!(ctx.len(), 2);
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Returns a new context holding the entries whose key starts with `prefix`, the keys being
+    /// kept as they are.
+    ///
+    /// The new context has the key policy of this one, and none of its other settings.
+    pub fn filter_prefix(&self, prefix: &str) -> Context {
+        let prefix = self.resolve_key(prefix).into_owned();
+        let mut ctx = Context::with_key_policy(self.key_policy());
+        ctx.extend(self.inner().into_iter().filter(|(k, _)| k.starts_with(&prefix)).collect());
+        ctx
+    }
+
+    /// Removes the entries for which `f` returns `false`.
+    ///
+    /// Like [`Contextualize::remove`], protected and frozen keys are kept whatever `f` returns.
+    pub fn retain<F: FnMut(&str, &Value) -> bool>(&mut self, mut f: F) {
+        for (k, v) in self.inner() {
+            if !f(&k, &v) {
+                self.remove(&k);
+            }
+        }
+    }
+}
+
+/// A view of a context whose keys are prefixed, see the [module documentation](self).
+#[derive(Debug)]
+pub struct NamespacedContext<'a> {
+    ctx: &'a mut Context,
+    prefix: String,
+}
+
+impl<'a> NamespacedContext<'a> {
+    /// Creates a view of `ctx` whose keys are prefixed with `namespace` and a dot.
+    pub fn new(ctx: &'a mut Context, namespace: &str) -> Self {
+        let prefix = format!("{}.", namespace.trim_end_matches('.'));
+        Self { ctx, prefix }
+    }
+
+    /// Returns the prefix of the keys of the view, the namespace followed by a dot.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the full key of `k` in the underlying context.
+    pub fn full_key(&self, k: &str) -> String {
+        format!("{}{}", self.prefix, k)
+    }
+
+    /// Inserts `v` under the prefixed `k`, see [`Contextualize::insert`].
+    pub fn insert(&mut self, k: &str, v: Value) {
+        let k = self.full_key(k);
+        self.ctx.insert(k, v);
+    }
+
+    /// Returns the value stored under the prefixed `k`.
+    pub fn get(&self, k: &str) -> Option<&Value> {
+        self.ctx.get(&self.full_key(k))
+    }
+
+    /// Removes the prefixed `k`, returning its value if it was present.
+    pub fn remove(&mut self, k: &str) -> Option<Value> {
+        let k = self.full_key(k);
+        self.ctx.remove(&k)
+    }
+
+    /// Returns `true` if a value is stored under the prefixed `k`.
+    pub fn contains_key(&self, k: &str) -> bool {
+        self.ctx.contains_key(&self.full_key(k))
+    }
+
+    /// Inserts the key-value pairs, each key being prefixed.
+    pub fn extend(&mut self, data: BTreeMap<String, Value>) {
+        let data = data.into_iter().map(|(k, v)| (self.full_key(&k), v)).collect();
+        self.ctx.extend(data);
+    }
+
+    /// Returns the entries of the namespace, without their prefix.
+    pub fn inner(&self) -> BTreeMap<String, Value> {
+        let prefix = self.ctx.resolve_key(&self.prefix).into_owned();
+        self.ctx
+            .inner()
+            .into_iter()
+            .filter_map(|(k, v)| k.strip_prefix(&prefix).map(|k| (k.to_string(), v)))
+            .collect()
+    }
+
+    /// Returns the number of entries of the namespace.
+    pub fn len(&self) -> usize {
+        self.inner().len()
+    }
+
+    /// Returns `true` if the namespace holds no entry.
+    pub fn is_empty(&self) -> bool {
+        self.inner().is_empty()
+    }
+
+    /// Removes the entries of the namespace, protected and frozen keys excepted.
+    pub fn clear(&mut self) {
+        let prefix = self.ctx.resolve_key(&self.prefix).into_owned();
+        self.ctx.retain(|k, _| !k.starts_with(&prefix));
+    }
+
+    /// Returns the underlying context.
+    pub fn context(&self) -> &Context {
+        self.ctx
+    }
+}
@@ -0,0 +1,163 @@
+//! Nested maps.
+//!
+//! [`Context::update_map`] gives mutable access to the `Value::Map` stored under a key, creating
+//! it if absent, so that nested sections can be enriched without extracting and reinserting the
+//! whole subtree by hand.
+//!
+//! [`Context::get_path`] and [`Context::insert_path`] address nested values with dotted paths
+//! such as `request.headers.user_agent`, the first segment being the top-level key. Sequence items
+//! are addressed by their index when reading. [`Context::flatten`] produces the reverse view, a
+//! flat map with dotted keys, for logging backends which cannot handle nesting.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.update_map("http", |http| {
+//!     http.insert(Value::String("status".to_string()), Value::U16(503));
+//! })
+//! .unwrap();
+//! ctx.insert_path("http.headers.user_agent", Value::String("curl".to_string())).unwrap();
+//!
+//! assert_eq!(ctx.at("http").at("status").as_u64(), Some(503));
+//! assert_eq!(ctx.get_path("http.headers.user_agent"), Some(&Value::String("curl".to_string())));
+//! assert_eq!(
+//!     ctx.flatten().into_keys().collect::<Vec<_>>(),
+//!     vec!["http.headers.user_agent", "http.status"]
+//! );
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Runs `f` on the map stored under `k`, creating an empty map if the key is missing.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<R>` which is:
+    /// * `Ok(result)` containing the value returned by `f`
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if the stored value is
+    ///   not a map, or a [`ProtectedKey`](crate::ProtectedKey) error if the key is protected, in
+    ///   which case the context is left unchanged
+    pub fn update_map<R>(&mut self, k: &str, f: impl FnOnce(&mut BTreeMap<Value, Value>) -> R) -> cdumay_core::Result<R> {
+        let mut map = match self.get(k) {
+            None => BTreeMap::new(),
+            Some(Value::Map(map)) => map.clone(),
+            Some(_) => return Err(self.mismatch(format!("Context key '{}' does not hold a map", k))),
+        };
+        let result = f(&mut map);
+        self.try_insert(k.to_string(), Value::Map(map))?;
+        Ok(result)
+    }
+
+    /// Returns the value at the dotted `path`, looking up map keys and sequence indexes.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let mut segments = path.split('.');
+        let mut value = self.get(segments.next()?)?;
+        for segment in segments {
+            value = child(value, segment)?;
+        }
+        Some(value)
+    }
+
+    /// Stores `value` at the dotted `path`, creating the missing intermediate maps.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the value was stored
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if a prefix of the path
+    ///   holds a value which is not a map, or a [`ProtectedKey`](crate::ProtectedKey) error if the
+    ///   top-level key is protected, in which case the context is left unchanged
+    pub fn insert_path(&mut self, path: &str, value: Value) -> cdumay_core::Result<()> {
+        let (k, rest) = match path.split_once('.') {
+            Some(split) => split,
+            None => return self.try_insert(path.to_string(), value),
+        };
+        let mut map = match self.get(k) {
+            None => BTreeMap::new(),
+            Some(Value::Map(map)) => map.clone(),
+            Some(_) => return Err(self.mismatch(format!("Context key '{}' does not hold a map", k))),
+        };
+        insert_in(&mut map, k, rest, value).map_err(|prefix| self.mismatch(format!("Context path '{}' does not hold a map", prefix)))?;
+        self.try_insert(k.to_string(), Value::Map(map))
+    }
+
+    /// Returns the entries of the context with nested maps and sequences flattened under dotted
+    /// keys, such as `http.status` or `tags.0`. Empty maps and sequences are kept as values.
+    pub fn flatten(&self) -> BTreeMap<String, Value> {
+        let mut flat = BTreeMap::new();
+        for (k, v) in self.inner() {
+            flatten_into(&mut flat, k, v);
+        }
+        flat
+    }
+}
+
+impl SharedContext {
+    /// Atomically runs `f` on the map stored under `k`, see [`Context::update_map`].
+    pub fn update_map<R>(&self, k: &str, f: impl FnOnce(&mut BTreeMap<Value, Value>) -> R) -> cdumay_core::Result<R> {
+        self.update(|ctx| ctx.update_map(k, f))
+    }
+
+    /// Returns a clone of the value at the dotted `path`, see [`Context::get_path`].
+    pub fn get_path(&self, path: &str) -> Option<Value> {
+        self.read(|ctx| ctx.get_path(path).cloned())
+    }
+
+    /// Atomically stores `value` at the dotted `path`, see [`Context::insert_path`].
+    pub fn insert_path(&self, path: &str, value: Value) -> cdumay_core::Result<()> {
+        self.update(|ctx| ctx.insert_path(path, value))
+    }
+}
+
+/// Returns the item of `value` addressed by `segment`, looking through transparent wrappers.
+fn child<'a>(value: &'a Value, segment: &str) -> Option<&'a Value> {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => child(inner, segment),
+        Value::Map(map) => map.get(&Value::String(segment.to_string())),
+        Value::Seq(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
+
+/// Stores `value` at `path` in `map`, found at `prefix`, returning the path of the first value
+/// which is not a map on failure, `map` being left unchanged.
+fn insert_in(map: &mut BTreeMap<Value, Value>, prefix: &str, path: &str, value: Value) -> Result<(), String> {
+    match path.split_once('.') {
+        None => {
+            map.insert(Value::String(path.to_string()), value);
+            Ok(())
+        }
+        Some((k, rest)) => {
+            let prefix = format!("{}.{}", prefix, k);
+            match map.entry(Value::String(k.to_string())).or_insert_with(|| Value::Map(BTreeMap::new())) {
+                Value::Map(inner) => insert_in(inner, &prefix, rest, value),
+                _ => Err(prefix),
+            }
+        }
+    }
+}
+
+/// Adds `value` to `flat` under `path`, nested maps and sequences being flattened.
+fn flatten_into(flat: &mut BTreeMap<String, Value>, path: String, value: Value) {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => flatten_into(flat, path, *inner),
+        Value::Map(map) if !map.is_empty() => {
+            for (k, v) in map {
+                flatten_into(flat, format!("{}.{}", path, stringify(&k)), v);
+            }
+        }
+        Value::Seq(items) if !items.is_empty() => {
+            for (index, item) in items.into_iter().enumerate() {
+                flatten_into(flat, format!("{}.{}", path, index), item);
+            }
+        }
+        value => {
+            flat.insert(path, value);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Numeric precision policy.
+//!
+//! Text formats do not represent every number a context can hold: TOML integers are signed 64-bit
+//! integers, JSON has no representation for non-finite floats, and consumers such as JavaScript
+//! only read integers up to 2^53 exactly. The [`NumericPolicy`] of a context, set with
+//! [`Context::set_numeric_policy`], decides what happens to such numbers in JSON, TOML and YAML
+//! dumps, instead of leaving it to each format:
+//!
+//! * [`OnPrecisionLoss::Error`] (default): the dump fails with a [`PrecisionLoss`] error naming the
+//!   path of the number;
+//! * [`OnPrecisionLoss::Stringify`]: the number is written as a string, which round-trips exactly.
+//!
+//! [`NumericPolicy::with_max_integer`] restricts the range of integers considered exact, and
+//! [`FloatHandling::Stringify`] writes every float as a string, for consumers which must not parse
+//! them back with a different precision.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{Context, Contextualize, NumericPolicy, OnPrecisionLoss};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("order_id".to_string(), Value::U64(9007199254740993));
+//! ctx.set_numeric_policy(NumericPolicy::new().with_max_integer(NumericPolicy::JAVASCRIPT_SAFE_INTEGER));
+//! assert_eq!(ctx.to_json(false).unwrap_err().code(), 400);
+//!
+//! ctx.set_numeric_policy(
+//!     NumericPolicy::new()
+//!         .with_max_integer(NumericPolicy::JAVASCRIPT_SAFE_INTEGER)
+//!         .on_precision_loss(OnPrecisionLoss::Stringify),
+//! );
+//! assert_eq!(ctx.to_json(false).unwrap(), r#"{"order_id":"9007199254740993"}"#);
+//! # }
+//! ```
+use crate::order::OrderedEntries;
+use crate::{Context, ContextConfig, PrecisionLoss};
+use serde_value::Value;
+
+/// A serialization format of contexts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// JSON, see [`Contextualize::to_json`](crate::Contextualize::to_json).
+    Json,
+    /// TOML, see [`Contextualize::to_toml`](crate::Contextualize::to_toml).
+    Toml,
+    /// YAML, see [`Contextualize::to_yaml`](crate::Contextualize::to_yaml).
+    Yaml,
+}
+
+impl Format {
+    /// Returns `true` if `value` is represented exactly by the format.
+    fn holds_integer(&self, value: i128) -> bool {
+        match self {
+            Format::Toml => i64::try_from(value).is_ok(),
+            Format::Json | Format::Yaml => true,
+        }
+    }
+
+    /// Returns `true` if the format represents non-finite floats.
+    fn holds_non_finite(&self) -> bool {
+        !matches!(self, Format::Json)
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Format::Json => "JSON",
+            Format::Toml => "TOML",
+            Format::Yaml => "YAML",
+        })
+    }
+}
+
+/// What to do with a number a format cannot represent exactly.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnPrecisionLoss {
+    /// Fail with a [`PrecisionLoss`] error (default).
+    #[default]
+    Error,
+    /// Write the number as a string.
+    Stringify,
+}
+
+/// How floats are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FloatHandling {
+    /// As numbers, non-finite floats being subject to [`OnPrecisionLoss`] in formats without a
+    /// representation for them (default).
+    #[default]
+    Preserve,
+    /// As strings, in their shortest exact decimal representation.
+    Stringify,
+}
+
+/// How numbers are written in dumps, see the [module documentation](self).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NumericPolicy {
+    on_loss: OnPrecisionLoss,
+    max_integer: Option<u64>,
+    floats: FloatHandling,
+}
+
+impl NumericPolicy {
+    /// Largest integer read exactly by JavaScript, 2^53 - 1.
+    pub const JAVASCRIPT_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+    /// Creates the default policy: numbers are preserved, and dumps fail if a format cannot
+    /// represent one exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets what happens to numbers which cannot be represented exactly.
+    pub fn on_precision_loss(mut self, on_loss: OnPrecisionLoss) -> Self {
+        self.on_loss = on_loss;
+        self
+    }
+
+    /// Considers integers whose magnitude exceeds `max` as not representable exactly.
+    pub fn with_max_integer(mut self, max: u64) -> Self {
+        self.max_integer = Some(max);
+        self
+    }
+
+    /// Sets how floats are written.
+    pub fn with_float_handling(mut self, floats: FloatHandling) -> Self {
+        self.floats = floats;
+        self
+    }
+
+    /// Applies the policy to `value`, stored at `path`, for `format`.
+    pub(crate) fn apply(&self, format: Format, path: &str, value: Value) -> cdumay_core::Result<Value> {
+        let integer = match &value {
+            Value::U8(v) => Some(i128::from(*v)),
+            Value::U16(v) => Some(i128::from(*v)),
+            Value::U32(v) => Some(i128::from(*v)),
+            Value::U64(v) => Some(i128::from(*v)),
+            Value::I8(v) => Some(i128::from(*v)),
+            Value::I16(v) => Some(i128::from(*v)),
+            Value::I32(v) => Some(i128::from(*v)),
+            Value::I64(v) => Some(i128::from(*v)),
+            _ => None,
+        };
+        if let Some(integer) = integer {
+            let exact = format.holds_integer(integer) && self.max_integer.is_none_or(|max| integer.unsigned_abs() <= u128::from(max));
+            return match exact {
+                true => Ok(value),
+                false => self.lose(format, path, integer.to_string()),
+            };
+        }
+        let float = match &value {
+            Value::F32(v) => Some((f64::from(*v), v.to_string())),
+            Value::F64(v) => Some((*v, v.to_string())),
+            _ => None,
+        };
+        if let Some((float, text)) = float {
+            return match (self.floats, float.is_finite() || format.holds_non_finite()) {
+                (FloatHandling::Stringify, _) => Ok(Value::String(text)),
+                (FloatHandling::Preserve, true) => Ok(value),
+                (FloatHandling::Preserve, false) => self.lose(format, path, text),
+            };
+        }
+        match value {
+            Value::Option(Some(inner)) => Ok(Value::Option(Some(Box::new(self.apply(format, path, *inner)?)))),
+            Value::Newtype(inner) => Ok(Value::Newtype(Box::new(self.apply(format, path, *inner)?))),
+            Value::Seq(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| self.apply(format, &format!("{}.{}", path, index), item))
+                .collect::<cdumay_core::Result<_>>()
+                .map(Value::Seq),
+            Value::Map(map) => map
+                .into_iter()
+                .map(|(k, v)| {
+                    let child = match &k {
+                        Value::String(k) => format!("{}.{}", path, k),
+                        other => format!("{}.{:?}", path, other),
+                    };
+                    Ok((k, self.apply(format, &child, v)?))
+                })
+                .collect::<cdumay_core::Result<_>>()
+                .map(Value::Map),
+            value => Ok(value),
+        }
+    }
+
+    fn lose(&self, format: Format, path: &str, text: String) -> cdumay_core::Result<Value> {
+        match self.on_loss {
+            OnPrecisionLoss::Stringify => Ok(Value::String(text)),
+            OnPrecisionLoss::Error => Err(PrecisionLoss::new()
+                .with_message(format!("Number {} at '{}' cannot be represented exactly in {}", text, path, format))
+                .into()),
+        }
+    }
+}
+
+impl Context {
+    /// Sets how numbers are written in dumps.
+    pub fn set_numeric_policy(&mut self, policy: NumericPolicy) {
+        self.config.numeric_policy = Some(policy);
+    }
+
+    /// Returns how numbers are written in dumps, see [`Context::effective_config`].
+    pub fn numeric_policy(&self) -> NumericPolicy {
+        self.effective_config(None).numeric_policy()
+    }
+
+    /// Checks that every number of the dump can be written to `format` under the numeric policy.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if no number would be lost
+    /// * `Err(e)` containing a [`PrecisionLoss`] error naming the first number which would be lost
+    pub fn check_numbers(&self, format: Format) -> cdumay_core::Result<()> {
+        let config = self.effective_config(None);
+        self.ordered_entries().into_iter().try_for_each(|(k, v)| {
+            let v = config.map_key_policy().apply(&k, v)?;
+            config.numeric_policy().apply(format, &k, v).map(|_| ())
+        })
+    }
+
+    /// Returns the ordered entries of the dump, prepared for `format` with [`Context::prepare`].
+    pub(crate) fn ordered_for(&self, format: Format, config: &ContextConfig) -> cdumay_core::Result<OrderedEntries> {
+        self.ordered_entries()
+            .into_iter()
+            .filter_map(|(k, v)| self.prepare(format, &k, v, config).map(|v| v.map(|v| (k, v))).transpose())
+            .collect::<cdumay_core::Result<_>>()
+            .map(OrderedEntries)
+    }
+
+    /// Applies `config` to `value`, stored under `k`, failing if `format` does not support it.
+    ///
+    /// Returns `None` if the entry is left out of the dump.
+    pub(crate) fn prepare(&self, format: Format, k: &str, value: Value, config: &ContextConfig) -> cdumay_core::Result<Option<Value>> {
+        let Some(value) = config.apply(k, value) else {
+            return Ok(None);
+        };
+        let value = config.map_key_policy().apply(k, value)?;
+        let value = config.numeric_policy().apply(format, k, value)?;
+        format.check_supported(k, &value)?;
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,99 @@
+//! Operators to compose contexts.
+//!
+//! * `a + b` merges `b` into `a`, values of `b` overwrite those of `a` (like [`Contextualize::extend`]).
+//! * `a | b` adds the keys of `b` missing from `a`, values of `a` are kept.
+//!
+//! The left-hand side keeps its settings (key policy, protected and frozen keys). Contexts can also
+//! be summed or collected from iterators, later contexts overwriting earlier ones.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let base = Context::from(vec![("env".to_string(), Value::String("prod".to_string()))]);
+//! let request = Context::from(vec![("user".to_string(), Value::U64(42))]);
+//! let step = Context::from(vec![("env".to_string(), Value::String("staging".to_string()))]);
+//!
+//! let ctx = base + request + step;
+//! assert_eq!(ctx.get("env"), Some(&Value::String("staging".to_string())));
+//! assert_eq!(ctx.get("user"), Some(&Value::U64(42)));
+//! ```
+use crate::{Context, Contextualize};
+use std::ops::{Add, AddAssign, BitOr, BitOrAssign};
+
+impl AddAssign<&Context> for Context {
+    fn add_assign(&mut self, rhs: &Context) {
+        self.extend(rhs.inner());
+    }
+}
+
+impl AddAssign for Context {
+    fn add_assign(&mut self, rhs: Context) {
+        *self += &rhs;
+    }
+}
+
+impl Add<&Context> for Context {
+    type Output = Context;
+
+    fn add(mut self, rhs: &Context) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl Add for Context {
+    type Output = Context;
+
+    fn add(self, rhs: Context) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl BitOrAssign<&Context> for Context {
+    fn bitor_assign(&mut self, rhs: &Context) {
+        let missing = rhs.inner().into_iter().filter(|(k, _)| self.get(k).is_none()).collect();
+        self.extend(missing);
+    }
+}
+
+impl BitOrAssign for Context {
+    fn bitor_assign(&mut self, rhs: Context) {
+        *self |= &rhs;
+    }
+}
+
+impl BitOr<&Context> for Context {
+    type Output = Context;
+
+    fn bitor(mut self, rhs: &Context) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOr for Context {
+    type Output = Context;
+
+    fn bitor(self, rhs: Context) -> Self::Output {
+        self | &rhs
+    }
+}
+
+impl std::iter::Sum for Context {
+    fn sum<I: Iterator<Item = Context>>(iter: I) -> Self {
+        iter.fold(Context::new(), |acc, ctx| acc + ctx)
+    }
+}
+
+impl<'a> std::iter::Sum<&'a Context> for Context {
+    fn sum<I: Iterator<Item = &'a Context>>(iter: I) -> Self {
+        iter.fold(Context::new(), |acc, ctx| acc + ctx)
+    }
+}
+
+impl FromIterator<Context> for Context {
+    fn from_iter<I: IntoIterator<Item = Context>>(iter: I) -> Self {
+        iter.into_iter().sum()
+    }
+}
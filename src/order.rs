@@ -0,0 +1,92 @@
+//! Key ordering of serialized output.
+//!
+//! Dumps are `BTreeMap`s and therefore sorted alphabetically, but human readers of serialized
+//! contexts want the important keys on top. [`Context::set_key_order`] selects the order used by
+//! serde serialization and the `to_json`, `to_toml` and `to_yaml` helpers:
+//!
+//! * [`KeyOrder::Alphabetical`] (default, unless set otherwise by
+//!   [`ContextConfig::with_key_order`](crate::ContextConfig::with_key_order));
+//! * [`KeyOrder::Insertion`]: keys in the order they were first inserted, the default with the
+//!   "ordered" feature, under which the loaders insert the keys in document order;
+//! * [`KeyOrder::Priority`]: the listed keys first, in list order, then the others
+//!   alphabetically.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, KeyOrder};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::U8(1));
+//! ctx.insert("error".to_string(), Value::String("boom".to_string()));
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!
+//! ctx.set_key_order(KeyOrder::Priority(vec!["request_id".to_string(), "error".to_string()]));
+//! let keys: Vec<String> = ctx.ordered_entries().into_iter().map(|(k, _)| k).collect();
+//! assert_eq!(keys, vec!["request_id", "error", "user"]);
+//! ```
+use crate::{Context, Contextualize};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_value::Value;
+
+/// Order of the keys in serialized output.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Keys sorted alphabetically (default, unless the "ordered" feature is enabled).
+    #[cfg_attr(not(feature = "ordered"), default)]
+    Alphabetical,
+    /// Keys in the order they were first inserted (default with the "ordered" feature).
+    ///
+    /// Keys loaded by deserializing a context have no insertion record and come last,
+    /// alphabetically.
+    #[cfg_attr(feature = "ordered", default)]
+    Insertion,
+    /// The listed keys first, in list order, then the others alphabetically.
+    Priority(Vec<String>),
+}
+
+/// Context entries serialized as a map, in order.
+pub(crate) struct OrderedEntries(pub(crate) Vec<(String, Value)>);
+
+impl Serialize for OrderedEntries {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (k, v) in &self.0 {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl Context {
+    /// Sets the order of the keys in serialized output.
+    pub fn set_key_order(&mut self, order: KeyOrder) {
+        self.key_order = order;
+    }
+
+    /// Returns the order of the keys in serialized output.
+    pub fn key_order(&self) -> &KeyOrder {
+        &self.key_order
+    }
+
+    /// Returns the entries of the dump, in the order of [`Context::key_order`], higher
+    /// [priorities](Context::set_priority) first.
+    pub fn ordered_entries(&self) -> Vec<(String, Value)> {
+        let mut entries: Vec<(String, Value)> = self.inner().into_iter().collect();
+        match &self.key_order {
+            KeyOrder::Alphabetical => {}
+            KeyOrder::Insertion => entries.sort_by_key(|(k, _)| self.created.get(k).copied().unwrap_or(u64::MAX)),
+            KeyOrder::Priority(keys) => {
+                let keys: Vec<String> = keys.iter().map(|k| self.resolve_key(k).into_owned()).collect();
+                entries.sort_by_key(|(k, _)| keys.iter().position(|p| p == k).unwrap_or(usize::MAX))
+            }
+        }
+        if !self.priorities.is_empty() {
+            entries.sort_by_key(|(k, _)| std::cmp::Reverse(self.priorities.get(k).copied().unwrap_or_default()));
+        }
+        entries
+    }
+
+    pub(crate) fn ordered(&self) -> OrderedEntries {
+        OrderedEntries(self.ordered_entries())
+    }
+}
@@ -0,0 +1,72 @@
+//! Parallel bulk operations, backed by rayon.
+//!
+//! This module is only available when the "rayon" feature is enabled. It targets very large
+//! contexts, such as thousands of keys batch-loaded from archives:
+//!
+//! * [`Context::par_extend`] builds the incoming map in parallel before applying it;
+//! * [`Context::par_retain`] evaluates the predicate on all entries in parallel;
+//! * [`Context::to_json_par`] (with the "json" feature) serializes top-level values in parallel.
+//!
+//! Writes still go through [`Contextualize::insert`] and [`Context::remove`], so key policy,
+//! protected and frozen keys and dirty tracking behave as with the sequential methods.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.par_extend((0..1000u64).map(|i| (format!("key_{}", i), Value::U64(i))).collect::<Vec<_>>());
+//! ctx.par_retain(|_, v| matches!(v, Value::U64(i) if i % 2 == 0));
+//! assert_eq!(ctx.inner().len(), 500);
+//! ```
+use crate::{Context, Contextualize};
+#[cfg(feature = "json")]
+use cdumay_core::ErrorConverter;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Extends the context with entries collected in parallel.
+    pub fn par_extend<I>(&mut self, data: I)
+    where
+        I: IntoParallelIterator<Item = (String, serde_value::Value)>,
+    {
+        let data: BTreeMap<String, serde_value::Value> = data.into_par_iter().collect();
+        self.extend(data);
+    }
+
+    /// Retains only the entries for which `f` returns `true`, evaluating `f` in parallel.
+    ///
+    /// Protected and frozen keys are kept whatever the predicate says.
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(&str, &serde_value::Value) -> bool + Sync,
+    {
+        let data = self.inner();
+        let rejected: Vec<&String> = data.par_iter().filter(|(k, v)| !f(k, v)).map(|(k, _)| k).collect();
+        for key in rejected {
+            self.remove(key);
+        }
+    }
+
+    /// Serializes the context to a compact JSON string, serializing top-level values in parallel.
+    ///
+    /// The output is identical to `to_json(false)`. This method is only available when both the
+    /// "rayon" and "json" features are enabled.
+    #[cfg(feature = "json")]
+    pub fn to_json_par(&self) -> cdumay_core::Result<String> {
+        let config = self.effective_config(None);
+        let ordered = self.ordered_for(crate::Format::Json, &config)?;
+        let data = self
+            .fit(ordered, &config, |entries| {
+                serde_json::to_string(entries).map_or(usize::MAX, |dump| dump.len())
+            })
+            .0;
+        let entries: Vec<String> = data
+            .par_iter()
+            .map(|(k, v)| Ok(format!("{}:{}", serde_json::to_string(k)?, serde_json::to_string(v)?)))
+            .collect::<Result<Vec<String>, serde_json::Error>>()
+            .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details()))?;
+        Ok(format!("{{{}}}", entries.join(",")))
+    }
+}
@@ -0,0 +1,307 @@
+//! JSON Patch and JSON Merge Patch documents.
+//!
+//! This module is only available when the "json" feature is enabled. It applies incremental
+//! updates sent as RFC 6902 JSON Patch documents, with [`Context::apply_json_patch`], and as
+//! RFC 7386 JSON Merge Patch documents, with [`Context::apply_merge_patch`], to the dump of a
+//! context seen as a JSON object. [`Context::create_patch`] produces the JSON Patch document
+//! transforming a context into another.
+//!
+//! Patches are applied atomically: if an operation fails, or if the patch would modify a
+//! protected or frozen key, the context is left unchanged. Only the top-level keys modified by
+//! the patch are written back, so that the other values keep their exact types.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("state".to_string(), Value::String("running".to_string()));
+//!
+//! ctx.apply_json_patch(r#"[{"op": "replace", "path": "/state", "value": "done"}]"#).unwrap();
+//! ctx.apply_merge_patch(r#"{"exit_code": 0}"#).unwrap();
+//! assert_eq!(ctx.get("state"), Some(&Value::String("done".to_string())));
+//! assert_eq!(ctx.get("exit_code"), Some(&Value::U64(0)));
+//!
+//! let patch = Context::new().create_patch(&ctx).unwrap();
+//! let mut mirror = Context::new();
+//! mirror.apply_json_patch(&patch).unwrap();
+//! assert_eq!(mirror, ctx);
+//! ```
+use crate::{Conflict, Context, Contextualize, KeyNotFound, TypeMismatch};
+use cdumay_core::ErrorConverter;
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::BTreeMap;
+
+impl Context {
+    /// Applies an RFC 6902 JSON Patch document.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if every operation was applied
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the document is not a valid patch, or
+    ///   if an operation does not apply to the targeted value
+    /// * `Err(e)` containing a [`KeyNotFound`] error if an operation targets a missing value
+    /// * `Err(e)` containing a [`Conflict`] error if a `test` operation fails
+    /// * `Err(e)` containing a [`ProtectedKey`](crate::ProtectedKey) or
+    ///   [`FrozenKey`](crate::FrozenKey) error if the patch modifies a protected or frozen key
+    pub fn apply_json_patch(&mut self, patch: &str) -> cdumay_core::Result<()> {
+        let operations = match self.load_patch(patch)? {
+            JsonValue::Array(operations) => operations,
+            _ => return Err(self.invalid_patch("JSON Patch must be an array of operations".to_string())),
+        };
+        let mut document = self.to_document()?;
+        for operation in operations {
+            self.apply_operation(&mut document, operation)?;
+        }
+        match document {
+            JsonValue::Object(document) => self.write_document(document),
+            _ => Err(self.invalid_patch("JSON Patch must leave the context as an object".to_string())),
+        }
+    }
+
+    /// Applies an RFC 7386 JSON Merge Patch document: `null` members remove keys, objects are
+    /// merged recursively and any other value replaces the current one.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the patch was applied
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the document is not a JSON object
+    /// * `Err(e)` containing a [`ProtectedKey`](crate::ProtectedKey) or
+    ///   [`FrozenKey`](crate::FrozenKey) error if the patch modifies a protected or frozen key
+    pub fn apply_merge_patch(&mut self, patch: &str) -> cdumay_core::Result<()> {
+        let patch = match self.load_patch(patch)? {
+            JsonValue::Object(patch) => patch,
+            _ => return Err(self.invalid_patch("JSON Merge Patch must be an object".to_string())),
+        };
+        let mut document = self.to_document()?;
+        merge(&mut document, JsonValue::Object(patch));
+        match document {
+            JsonValue::Object(document) => self.write_document(document),
+            _ => unreachable!("merging an object always gives an object"),
+        }
+    }
+
+    /// Returns the RFC 6902 JSON Patch document transforming this context into `other`.
+    ///
+    /// Nested maps are compared member by member, sequences and other values are replaced as a
+    /// whole.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(string)` containing the JSON Patch document on success
+    /// * `Err(e)` containing the error if a context holds a value which cannot be represented in
+    ///   JSON
+    pub fn create_patch(&self, other: &Context) -> cdumay_core::Result<String> {
+        let mut operations = Vec::new();
+        diff(String::new(), &self.to_document()?, &other.to_document()?, &mut operations);
+        serde_json::to_string(&operations)
+            .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump patch".to_string()), self.error_details()))
+    }
+
+    fn load_patch(&self, patch: &str) -> cdumay_core::Result<JsonValue> {
+        serde_json::from_str(patch)
+            .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load patch".to_string()), self.error_details()))
+    }
+
+    fn to_document(&self) -> cdumay_core::Result<JsonValue> {
+        serde_json::to_value(self.inner())
+            .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), self.error_details()))
+    }
+
+    /// Writes back the top-level keys of `document` which differ from the dump, and removes the
+    /// keys it no longer holds, after checking that all of them can be modified.
+    fn write_document(&mut self, document: Map<String, JsonValue>) -> cdumay_core::Result<()> {
+        let mut current = match self.to_document()? {
+            JsonValue::Object(current) => current,
+            _ => Map::new(),
+        };
+        let mut updates = BTreeMap::new();
+        for (k, v) in document {
+            if current.remove(&k).as_ref() != Some(&v) {
+                updates.insert(k, v);
+            }
+        }
+        self.check_writable(updates.keys().chain(current.keys()).map(String::as_str))?;
+        let mut values = BTreeMap::new();
+        for (k, v) in updates {
+            let value = serde_value::Value::deserialize(v).map_err(|err| {
+                cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load patch".to_string()), self.error_details())
+            })?;
+            values.insert(k, value);
+        }
+        for k in current.keys() {
+            self.remove(k);
+        }
+        Contextualize::extend(self, values);
+        Ok(())
+    }
+
+    fn apply_operation(&self, document: &mut JsonValue, operation: JsonValue) -> cdumay_core::Result<()> {
+        let member = |name: &str| {
+            operation
+                .get(name)
+                .ok_or_else(|| self.invalid_patch(format!("JSON Patch operation {} has no '{}' member", operation, name)))
+        };
+        let pointer = |name: &str| {
+            member(name)?
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| self.invalid_patch(format!("JSON Patch operation {} has a non-string '{}' member", operation, name)))
+        };
+        let op = pointer("op")?;
+        let path = pointer("path")?;
+        match op.as_str() {
+            "add" => self.pointer_add(document, &path, member("value")?.clone()),
+            "remove" => self.pointer_take(document, &path).map(|_| ()),
+            "replace" => {
+                if !path.is_empty() {
+                    self.pointer_take(document, &path)?;
+                }
+                self.pointer_add(document, &path, member("value")?.clone())
+            }
+            "move" => {
+                let from = pointer("from")?;
+                if path.starts_with(&format!("{}/", from)) {
+                    return Err(self.invalid_patch(format!("Cannot move '{}' into its own child '{}'", from, path)));
+                }
+                let value = self.pointer_take(document, &from)?;
+                self.pointer_add(document, &path, value)
+            }
+            "copy" => {
+                let from = pointer("from")?;
+                let value = self.pointer_lookup(document, &from)?.clone();
+                self.pointer_add(document, &path, value)
+            }
+            "test" => match self.pointer_lookup(document, &path)? == member("value")? {
+                true => Ok(()),
+                false => Err(Conflict::new()
+                    .with_message(format!("JSON Patch test failed on '{}'", path))
+                    .with_details(self.error_details())
+                    .into()),
+            },
+            other => Err(self.invalid_patch(format!("Unknown JSON Patch operation '{}'", other))),
+        }
+    }
+
+    fn pointer_lookup<'a>(&self, document: &'a JsonValue, path: &str) -> cdumay_core::Result<&'a JsonValue> {
+        self.check_pointer(path)?;
+        document.pointer(path).ok_or_else(|| self.missing_pointer(path))
+    }
+
+    fn pointer_add(&self, document: &mut JsonValue, path: &str, value: JsonValue) -> cdumay_core::Result<()> {
+        let Some((parent, token)) = self.split_pointer(path)? else {
+            *document = value;
+            return Ok(());
+        };
+        match document.pointer_mut(parent).ok_or_else(|| self.missing_pointer(parent))? {
+            JsonValue::Object(map) => {
+                map.insert(token, value);
+                Ok(())
+            }
+            JsonValue::Array(items) => {
+                let index = match token.as_str() {
+                    "-" => items.len(),
+                    _ => self.pointer_index(&token, items.len() + 1, path)?,
+                };
+                items.insert(index, value);
+                Ok(())
+            }
+            _ => Err(self.invalid_patch(format!("Cannot add '{}' to a value which is neither an object nor an array", path))),
+        }
+    }
+
+    fn pointer_take(&self, document: &mut JsonValue, path: &str) -> cdumay_core::Result<JsonValue> {
+        let Some((parent, token)) = self.split_pointer(path)? else {
+            return Err(self.invalid_patch("Cannot remove the whole context".to_string()));
+        };
+        match document.pointer_mut(parent).ok_or_else(|| self.missing_pointer(parent))? {
+            JsonValue::Object(map) => map.remove(&token).ok_or_else(|| self.missing_pointer(path)),
+            JsonValue::Array(items) => {
+                let index = self.pointer_index(&token, items.len(), path)?;
+                Ok(items.remove(index))
+            }
+            _ => Err(self.missing_pointer(path)),
+        }
+    }
+
+    /// Splits a JSON Pointer into the pointer of the parent and the unescaped last token, or
+    /// returns `None` for the whole document.
+    fn split_pointer<'a>(&self, path: &'a str) -> cdumay_core::Result<Option<(&'a str, String)>> {
+        self.check_pointer(path)?;
+        Ok(path
+            .rfind('/')
+            .map(|pos| (&path[..pos], path[pos + 1..].replace("~1", "/").replace("~0", "~"))))
+    }
+
+    fn check_pointer(&self, path: &str) -> cdumay_core::Result<()> {
+        match path.is_empty() || path.starts_with('/') {
+            true => Ok(()),
+            false => Err(self.invalid_patch(format!("Invalid JSON Pointer '{}'", path))),
+        }
+    }
+
+    /// Parses an array index lower than `bound`, rejecting leading zeros as RFC 6901 does.
+    fn pointer_index(&self, token: &str, bound: usize, path: &str) -> cdumay_core::Result<usize> {
+        let valid = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit()) && (token == "0" || !token.starts_with('0'));
+        match token.parse::<usize>() {
+            Ok(index) if valid && index < bound => Ok(index),
+            Ok(_) if valid => Err(self.missing_pointer(path)),
+            _ => Err(self.invalid_patch(format!("Invalid array index in '{}'", path))),
+        }
+    }
+
+    fn missing_pointer(&self, path: &str) -> cdumay_core::Error {
+        KeyNotFound::new()
+            .with_message(format!("No value at '{}'", path))
+            .with_details(self.error_details())
+            .into()
+    }
+
+    fn invalid_patch(&self, message: String) -> cdumay_core::Error {
+        TypeMismatch::new().with_message(message).with_details(self.error_details()).into()
+    }
+}
+
+fn merge(target: &mut JsonValue, patch: JsonValue) {
+    match patch {
+        JsonValue::Object(patch) => {
+            if !target.is_object() {
+                *target = JsonValue::Object(Map::new());
+            }
+            if let JsonValue::Object(map) = target {
+                for (k, v) in patch {
+                    match v {
+                        JsonValue::Null => {
+                            map.remove(&k);
+                        }
+                        v => merge(map.entry(k).or_insert(JsonValue::Null), v),
+                    }
+                }
+            }
+        }
+        patch => *target = patch,
+    }
+}
+
+fn diff(path: String, before: &JsonValue, after: &JsonValue, operations: &mut Vec<JsonValue>) {
+    match (before, after) {
+        _ if before == after => {}
+        (JsonValue::Object(before), JsonValue::Object(after)) => {
+            let child = |k: &str| format!("{}/{}", path, k.replace('~', "~0").replace('/', "~1"));
+            for k in before.keys().filter(|k| !after.contains_key(*k)) {
+                operations.push(serde_json::json!({"op": "remove", "path": child(k)}));
+            }
+            for (k, v) in after {
+                match before.get(k) {
+                    Some(old) => diff(child(k), old, v, operations),
+                    None => operations.push(serde_json::json!({"op": "add", "path": child(k), "value": v})),
+                }
+            }
+        }
+        _ => operations.push(serde_json::json!({"op": "replace", "path": path, "value": after})),
+    }
+}
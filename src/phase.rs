@@ -0,0 +1,81 @@
+//! Phase tracking for multi-step operations.
+//!
+//! [`Context::enter_phase`] and [`Context::exit_phase`] maintain a `phases` timeline in the
+//! context. Each phase is a map holding its `name`, its `start` (in milliseconds since the Unix
+//! epoch) and, once exited, its `duration` in milliseconds. Entering a phase exits the current
+//! one, so when an operation fails, the dump shows which phase was running and how long each of
+//! the previous ones took.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::new();
+//! ctx.enter_phase("download");
+//! ctx.enter_phase("extract");
+//! assert_eq!(ctx.current_phase(), Some("extract"));
+//! assert!(ctx.at("phases").nth(0).at("duration").as_u64().is_some());
+//!
+//! ctx.exit_phase();
+//! assert_eq!(ctx.current_phase(), None);
+//! ```
+use crate::{Context, Contextualize, ValueRef};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const PHASES_KEY: &str = crate::keys::PHASES;
+
+fn now_millis() -> u64 {
+    u64::try_from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()).unwrap_or(u64::MAX)
+}
+
+fn field(name: &str) -> Value {
+    Value::String(name.to_string())
+}
+
+impl Context {
+    /// Starts the phase `name`, exiting the current phase if any.
+    pub fn enter_phase(&mut self, name: impl Into<String>) {
+        let mut phases = self.phases();
+        close(&mut phases);
+        let phase = BTreeMap::from([(field("name"), Value::String(name.into())), (field("start"), Value::U64(now_millis()))]);
+        phases.push(Value::Map(phase));
+        self.insert(PHASES_KEY.to_string(), Value::Seq(phases));
+    }
+
+    /// Exits the current phase, returning its duration, or `None` if no phase is running.
+    pub fn exit_phase(&mut self) -> Option<Duration> {
+        let mut phases = self.phases();
+        let duration = close(&mut phases)?;
+        self.insert(PHASES_KEY.to_string(), Value::Seq(phases));
+        Some(duration)
+    }
+
+    /// Returns the name of the running phase, if any.
+    pub fn current_phase(&self) -> Option<&str> {
+        let last = self.at(PHASES_KEY).as_seq()?.last()?;
+        let last = ValueRef::new(Some(last));
+        match last.at("duration").is_missing() {
+            true => last.at("name").as_str(),
+            false => None,
+        }
+    }
+
+    fn phases(&self) -> Vec<Value> {
+        self.at(PHASES_KEY).as_seq().map(<[Value]>::to_vec).unwrap_or_default()
+    }
+}
+
+/// Sets the duration of the last phase if it is still running.
+fn close(phases: &mut [Value]) -> Option<Duration> {
+    let Some(Value::Map(phase)) = phases.last_mut() else {
+        return None;
+    };
+    if phase.contains_key(&field("duration")) {
+        return None;
+    }
+    let start = ValueRef::new(phase.get(&field("start"))).as_u64().unwrap_or_default();
+    let duration = now_millis().saturating_sub(start);
+    phase.insert(field("duration"), Value::U64(duration));
+    Some(Duration::from_millis(duration))
+}
@@ -0,0 +1,36 @@
+//! Policies controlling how a context stores its data.
+use std::borrow::Cow;
+
+/// How keys are compared when inserting and looking up values.
+///
+/// # Example
+///
+/// ```rust
+/// use cdumay_context::{Context, Contextualize, KeyPolicy};
+///
+/// let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+/// ctx.insert("Content-Type".to_string(), serde_value::Value::String("text/plain".to_string()));
+///
+/// assert!(ctx.get("content-type").is_some());
+/// assert!(ctx.get("CONTENT-TYPE").is_some());
+/// assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["content-type"]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPolicy {
+    /// Keys are stored and looked up as-is (default).
+    #[default]
+    CaseSensitive,
+    /// Keys are lowercased on insertion and lookups ignore case.
+    CaseInsensitive,
+}
+
+impl KeyPolicy {
+    /// Returns the key as it is stored under this policy.
+    pub fn normalize<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        match self {
+            KeyPolicy::CaseSensitive => Cow::Borrowed(key),
+            KeyPolicy::CaseInsensitive if key.chars().any(char::is_uppercase) => Cow::Owned(key.to_lowercase()),
+            KeyPolicy::CaseInsensitive => Cow::Borrowed(key),
+        }
+    }
+}
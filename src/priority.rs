@@ -0,0 +1,56 @@
+//! Key priorities.
+//!
+//! Each key of a [`Context`] carries a [`Priority`], set with [`Context::set_priority`]. Keys
+//! which were never tagged are [`Priority::Normal`]. Priorities take precedence over the
+//! [key order](crate::KeyOrder): higher-priority keys come first in ordered output, and
+//! [`Context::dump_within`] drops the lowest-priority keys first. [`Priority::Critical`] keys are
+//! never dropped, so that essential identifiers survive trimming.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, Priority};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("payload".to_string(), Value::String("x".repeat(1000)));
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! ctx.set_priority("request_id", Priority::Critical);
+//! ctx.set_priority("payload", Priority::Low);
+//!
+//! let keys: Vec<String> = ctx.ordered_entries().into_iter().map(|(k, _)| k).collect();
+//! assert_eq!(keys, vec!["request_id", "user", "payload"]);
+//!
+//! let dump = ctx.dump_within(0, |dump| format!("{:?}", dump).len());
+//! assert!(dump.contains_key("request_id"));
+//! assert!(!dump.contains_key("user"));
+//! ```
+use crate::Context;
+use serde::{Deserialize, Serialize};
+
+/// How essential the value stored under a key is, from least to most essential.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Dropped first when space runs out.
+    Low,
+    /// Untagged keys (default).
+    #[default]
+    Normal,
+    /// Dropped only once all lower-priority keys are.
+    High,
+    /// Never dropped.
+    Critical,
+}
+
+impl Context {
+    /// Sets the priority of `k`, whether or not a value is stored under it yet.
+    pub fn set_priority(&mut self, k: &str, priority: Priority) {
+        let k = self.resolve_key(k).into_owned();
+        self.priorities.insert(k, priority);
+    }
+
+    /// Returns the priority of `k`.
+    pub fn priority(&self, k: &str) -> Priority {
+        self.priorities.get(self.resolve_key(k).as_ref()).copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,121 @@
+//! Problem Details (RFC 9457) rendering of errors.
+//!
+//! [`ProblemDetails`] is the `application/problem+json` representation of a
+//! [`cdumay_core::Error`]: the class of the error is the `title`, its message the `detail` and
+//! its code the `status`. [`ProblemDetails::with_context`] adds the [`Sensitivity::Public`]
+//! entries of a context as extension members, so that only what was explicitly marked as public
+//! reaches the client.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, KeyNotFound, ProblemDetails, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("db_host".to_string(), Value::String("10.0.0.12".to_string()));
+//! ctx.set_sensitivity("request_id", Sensitivity::Public);
+//!
+//! let err: cdumay_core::Error = KeyNotFound::new().with_message("Order 42 not found".to_string()).into();
+//! let problem = ProblemDetails::from(&err).with_type("https://example.com/problems/not-found").with_context(&ctx);
+//! assert_eq!(problem.status, 404);
+//! assert_eq!(problem.detail.as_deref(), Some("Order 42 not found"));
+//! assert!(problem.extensions.contains_key("request_id"));
+//! assert!(!problem.extensions.contains_key("db_host"));
+//! ```
+use crate::{Context, ContextDump, ContextView, Sensitivity};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Media type of serialized [`ProblemDetails`].
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Members defined by RFC 9457, which extensions cannot override.
+const STANDARD_MEMBERS: &[&str] = &["type", "title", "status", "detail", "instance"];
+
+/// A problem details object, as defined by RFC 9457.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    /// URI reference identifying the problem type, `about:blank` by default.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type.
+    pub title: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// URI reference identifying this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension members.
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl ProblemDetails {
+    /// Sets the problem type URI.
+    pub fn with_type(mut self, problem_type: &str) -> Self {
+        self.problem_type = problem_type.to_string();
+        self
+    }
+
+    /// Sets the URI of this occurrence of the problem.
+    pub fn with_instance(mut self, instance: &str) -> Self {
+        self.instance = Some(instance.to_string());
+        self
+    }
+
+    /// Adds the [`Sensitivity::Public`] entries of `ctx` as extension members.
+    ///
+    /// Entries named after a standard member are skipped.
+    pub fn with_context(self, ctx: &Context) -> Self {
+        self.with_view(&ctx.view(Sensitivity::Public))
+    }
+
+    /// Adds the entries visible in `view` as extension members, for audiences other than
+    /// customers.
+    ///
+    /// Entries named after a standard member are skipped.
+    pub fn with_view(mut self, view: &ContextView) -> Self {
+        self.extensions
+            .extend(view.dump().into_iter().filter(|(k, _)| !STANDARD_MEMBERS.contains(&k.as_str())));
+        self
+    }
+
+    /// Serializes the problem details to a JSON string.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, pretty: bool) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        match pretty {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        }
+        .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump problem details".to_string()), BTreeMap::new()))
+    }
+}
+
+impl From<&cdumay_core::Error> for ProblemDetails {
+    /// Converts an error without extension members: its details are internal by nature, see
+    /// [`ProblemDetails::with_context`] to expose public context entries.
+    fn from(err: &cdumay_core::Error) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: err.class().to_string(),
+            status: match err.code() {
+                code @ 100..=599 => code,
+                _ => 500,
+            },
+            detail: Some(err.message().to_string()).filter(|message| !message.is_empty()),
+            instance: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<cdumay_core::Error> for ProblemDetails {
+    fn from(err: cdumay_core::Error) -> Self {
+        Self::from(&err)
+    }
+}
@@ -0,0 +1,134 @@
+//! Environment-specific overlays.
+//!
+//! A [`ProfiledContext`] holds a base context and one overlay per profile (`dev`, `staging`,
+//! `prod`, ...). Selecting a profile merges its overlay over the base, and the effective context
+//! records the active profile under [`PROFILE_KEY`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextDump, Contextualize, ProfiledContext};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let mut base = Context::new();
+//! base.insert("service".to_string(), Value::String("billing".to_string()));
+//! base.insert("log_level".to_string(), Value::String("debug".to_string()));
+//!
+//! let prod = BTreeMap::from([("log_level".to_string(), Value::String("warn".to_string()))]);
+//! let profiled = ProfiledContext::new(base).overlay("prod", prod).select("prod").unwrap();
+//!
+//! let dump = profiled.dump();
+//! assert_eq!(dump.get("service"), Some(&Value::String("billing".to_string())));
+//! assert_eq!(dump.get("log_level"), Some(&Value::String("warn".to_string())));
+//! assert_eq!(dump.get("profile"), Some(&Value::String("prod".to_string())));
+//! ```
+use crate::{Context, ContextDump, Contextualize, KeyNotFound};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which the effective context records the active profile.
+pub const PROFILE_KEY: &str = crate::keys::PROFILE;
+
+/// A base context with per-profile overlays, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct ProfiledContext {
+    base: Context,
+    overlays: BTreeMap<String, BTreeMap<String, Value>>,
+    active: Option<String>,
+}
+
+impl ProfiledContext {
+    /// Creates a profiled context over `base`, without any profile selected.
+    pub fn new(base: Context) -> Self {
+        Self { base, ..Self::default() }
+    }
+
+    /// Registers the overlay of `profile`, replacing any previous one.
+    ///
+    /// Keys are normalized with the key policy of the base.
+    pub fn overlay(mut self, profile: impl Into<String>, data: BTreeMap<String, Value>) -> Self {
+        let policy = self.base.key_policy();
+        let data = data.into_iter().map(|(k, v)| (policy.normalize(&k).into_owned(), v)).collect();
+        self.overlays.insert(profile.into(), data);
+        self
+    }
+
+    /// Selects the profile whose overlay is merged over the base.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Self>` which is:
+    /// * `Ok(self)` with `profile` selected
+    /// * `Err(e)` containing a [`KeyNotFound`] error if no overlay is registered for `profile`
+    pub fn select(mut self, profile: &str) -> cdumay_core::Result<Self> {
+        self.switch(profile)?;
+        Ok(self)
+    }
+
+    /// Switches to another profile, see [`ProfiledContext::select`].
+    pub fn switch(&mut self, profile: &str) -> cdumay_core::Result<()> {
+        if !self.overlays.contains_key(profile) {
+            return Err(KeyNotFound::new()
+                .with_message(format!("Unknown context profile '{}'", profile))
+                .with_details(self.base.error_details())
+                .into());
+        }
+        self.active = Some(profile.to_string());
+        Ok(())
+    }
+
+    /// Deselects the active profile, leaving the base alone.
+    pub fn deselect(&mut self) {
+        self.active = None;
+    }
+
+    /// Returns the active profile, if any.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Returns the registered profiles, in order.
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.overlays.keys().map(String::as_str)
+    }
+
+    /// Returns the base context.
+    pub fn base(&self) -> &Context {
+        &self.base
+    }
+
+    /// Returns a mutable reference to the base context.
+    pub fn base_mut(&mut self) -> &mut Context {
+        &mut self.base
+    }
+
+    /// Retrieves the effective value stored under `k`, as in [`ProfiledContext::context`].
+    pub fn get(&self, k: &str) -> Option<&Value> {
+        let k = self.base.resolve_key(k);
+        match self.active_overlay() {
+            Some(overlay) if !self.base.is_locked(&k) => overlay.get(k.as_ref()).or_else(|| self.base.get(&k)),
+            _ => self.base.get(&k),
+        }
+    }
+
+    /// Returns the effective context: the base with the active overlay merged over it.
+    ///
+    /// Protected and frozen keys of the base are not overridden by the overlay.
+    pub fn context(&self) -> Context {
+        let mut ctx = self.base.clone();
+        if let (Some(profile), Some(overlay)) = (&self.active, self.active_overlay()) {
+            ctx.extend(overlay.clone());
+            ctx.insert(PROFILE_KEY.to_string(), Value::String(profile.clone()));
+        }
+        ctx
+    }
+
+    fn active_overlay(&self) -> Option<&BTreeMap<String, Value>> {
+        self.overlays.get(self.active.as_ref()?)
+    }
+}
+
+impl ContextDump for ProfiledContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.context().inner()
+    }
+}
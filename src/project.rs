@@ -0,0 +1,378 @@
+//! Projection of contexts into structs with complete error reports.
+//!
+//! [`Context::project`] deserializes the context into a struct, like [`Context::section`] does
+//! for a single key. When the projection fails, the returned [`TypeMismatch`] lists every
+//! missing and ill-typed field at once instead of only the first one, under the `missing_fields`
+//! and `invalid_fields` keys of its details.
+//!
+//! Only the fields of the struct are read, other context keys are ignored.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, ValueExt};
+//! use serde::Deserialize;
+//! use serde_value::Value;
+//!
+//! #[derive(Deserialize)]
+//! struct Handler {
+//!     user_id: u64,
+//!     tenant: String,
+//!     region: String,
+//!     dry_run: Option<bool>,
+//! }
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user_id".to_string(), Value::String("forty-two".to_string()));
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!
+//! let err = ctx.project::<Handler>().err().unwrap();
+//! assert_eq!(err.details()["missing_fields"], Value::Seq(vec![
+//!     Value::String("tenant".to_string()),
+//!     Value::String("region".to_string()),
+//! ]));
+//! assert!(err.details()["invalid_fields"].as_map().unwrap().contains_key(&Value::String("user_id".to_string())));
+//! ```
+//!
+//! Fields which failed are replaced by placeholder values while the projection is retried, so
+//! that the following ones can be checked. Types which cannot be built from placeholders, such
+//! as untagged enums, stop the report at their field.
+use crate::{Context, Contextualize, TypeMismatch};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_value::{DeserializerError, Value, ValueDeserializer};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+
+type Error = DeserializerError;
+
+impl Context {
+    /// Deserializes the context into `T`, reporting every missing and ill-typed field on failure.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<T, TypeMismatch>` which is:
+    /// * `Ok(value)` if every field of `T` could be read from the context
+    /// * `Err(e)` containing a [`TypeMismatch`] listing the failed fields, whose details also
+    ///   hold the context entries, see [`Contextualize::error_details`]
+    pub fn project<T: DeserializeOwned>(&self) -> Result<T, TypeMismatch> {
+        let data = self.inner();
+        let mut missing: Vec<&'static str> = Vec::new();
+        let mut invalid: BTreeMap<&'static str, String> = BTreeMap::new();
+        let mut fatal = None;
+        let mut filled = BTreeSet::new();
+        loop {
+            let current = Cell::new(None);
+            let projector = Projector {
+                ctx: self,
+                data: &data,
+                filled: &filled,
+                current: &current,
+            };
+            match T::deserialize(projector) {
+                Ok(value) if missing.is_empty() && invalid.is_empty() => return Ok(value),
+                Ok(_) => break,
+                Err(DeserializerError::MissingField(field)) if !filled.contains(field) => {
+                    missing.push(field);
+                    filled.insert(field);
+                }
+                Err(err) => match current.get() {
+                    Some(field) if !filled.contains(field) => {
+                        invalid.insert(field, err.to_string());
+                        filled.insert(field);
+                    }
+                    _ => {
+                        fatal = Some(err.to_string());
+                        break;
+                    }
+                },
+            }
+        }
+        let mut reasons = Vec::new();
+        if !missing.is_empty() {
+            reasons.push(format!("missing fields: {}", missing.join(", ")));
+        }
+        if !invalid.is_empty() {
+            reasons.push(format!(
+                "invalid fields: {}",
+                invalid.iter().map(|(k, v)| format!("{} ({})", k, v)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        reasons.extend(fatal);
+        let mut details = self.error_details();
+        details.insert(
+            "missing_fields".to_string(),
+            Value::Seq(missing.iter().map(|f| Value::String(f.to_string())).collect()),
+        );
+        details.insert(
+            "invalid_fields".to_string(),
+            Value::Map(
+                invalid
+                    .into_iter()
+                    .map(|(k, v)| (Value::String(k.to_string()), Value::String(v)))
+                    .collect(),
+            ),
+        );
+        Err(TypeMismatch::new()
+            .with_message(format!(
+                "Failed to project context into {}: {}",
+                std::any::type_name::<T>(),
+                reasons.join("; ")
+            ))
+            .with_details(details))
+    }
+}
+
+/// Deserializer reading the fields of a struct from the context entries.
+struct Projector<'a> {
+    ctx: &'a Context,
+    data: &'a BTreeMap<String, Value>,
+    filled: &'a BTreeSet<&'static str>,
+    current: &'a Cell<Option<&'static str>>,
+}
+
+impl<'de> de::Deserializer<'de> for Projector<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let map = self.data.iter().map(|(k, v)| (Value::String(k.clone()), v.clone())).collect();
+        ValueDeserializer::<Error>::new(Value::Map(map)).deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        let fields = fields
+            .iter()
+            .filter_map(|field| match self.filled.contains(field) {
+                true => Some((*field, None)),
+                false => self.data.get(self.ctx.resolve_key(field).as_ref()).map(|v| (*field, Some(v.clone()))),
+            })
+            .collect::<Vec<_>>();
+        visitor.visit_map(Fields {
+            fields: fields.into_iter(),
+            value: None,
+            current: self.current,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Struct fields read from the context, `None` values being placeholders.
+struct Fields<'a> {
+    fields: std::vec::IntoIter<(&'static str, Option<Value>)>,
+    value: Option<(&'static str, Option<Value>)>,
+    current: &'a Cell<Option<&'static str>>,
+}
+
+impl<'de> de::MapAccess<'de> for Fields<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some((field, value)) => {
+                self.value = Some((field, value));
+                seed.deserialize(field.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (field, value) = self
+            .value
+            .take()
+            .ok_or_else(|| <Error as de::Error>::custom("value requested before key"))?;
+        self.current.set(Some(field));
+        let result = match value {
+            Some(value) => seed.deserialize(ValueDeserializer::<Error>::new(value)),
+            None => seed.deserialize(Placeholder),
+        }?;
+        self.current.set(None);
+        Ok(result)
+    }
+}
+
+/// Deserializer producing a default-looking value of whatever type is requested.
+struct Placeholder;
+
+impl<'de> de::Deserializer<'de> for Placeholder {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_char('\0')
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_none()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(Placeholder)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Placeholders(0))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(PlaceholderFields(&[]))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(PlaceholderFields(fields))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        match variants.first() {
+            Some(variant) => visitor.visit_enum(PlaceholderVariant(variant)),
+            None => Err(<Error as de::Error>::custom("enum without variants")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct identifier ignored_any
+    }
+}
+
+/// A sequence of placeholders.
+struct Placeholders(usize);
+
+impl<'de> de::SeqAccess<'de> for Placeholders {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        match self.0 {
+            0 => Ok(None),
+            _ => {
+                self.0 -= 1;
+                seed.deserialize(Placeholder).map(Some)
+            }
+        }
+    }
+}
+
+/// Struct fields set to placeholders.
+struct PlaceholderFields(&'static [&'static str]);
+
+impl<'de> de::MapAccess<'de> for PlaceholderFields {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.0.split_first() {
+            Some((field, rest)) => {
+                self.0 = rest;
+                seed.deserialize((*field).into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(Placeholder)
+    }
+}
+
+/// The first variant of an enum, with placeholder content.
+struct PlaceholderVariant(&'static str);
+
+impl<'de> de::EnumAccess<'de> for PlaceholderVariant {
+    type Error = Error;
+    type Variant = Placeholder;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Placeholder), Error> {
+        Ok((seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.0))?, Placeholder))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Placeholder {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(Placeholder)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(PlaceholderFields(fields))
+    }
+}
@@ -0,0 +1,94 @@
+//! Provenance of propagated contexts.
+//!
+//! Each time a context is received from another service, [`Context::record_hop`] appends an
+//! entry to `propagation.hops`, a map with the `service` which received the context, the
+//! `transport` it came through (`headers`, `baggage`, `kafka`, ...) and the time it was received
+//! (`at`, in milliseconds since the Unix epoch). Only the last [`PROPAGATION_HOPS_LIMIT`] hops are
+//! kept, oldest first, so that the path of a context across services can be traced without the
+//! context growing with each hop.
+//!
+//! The receivers of the crate record the hop themselves: [`Context::from_headers`] (feature:
+//! "http") takes the name of the receiving service for that purpose. Contexts received through
+//! other transports, such as Kafka messages decoded with [`Contextualize::from_json`], must call
+//! [`Context::record_hop`] after decoding.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::new();
+//! ctx.record_hop("gateway", "headers");
+//! ctx.record_hop("billing", "kafka");
+//!
+//! let hops = ctx.hops();
+//! assert_eq!(hops.len(), 2);
+//! assert_eq!(hops[1].service, "billing");
+//! assert_eq!(ctx.at("propagation").at("hops").nth(0).at("transport").as_str(), Some("headers"));
+//! ```
+use crate::{Context, Contextualize, ValueRef};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Key under which propagation metadata is stored.
+pub const PROPAGATION_KEY: &str = crate::keys::PROPAGATION;
+
+/// Maximum number of hops kept in `propagation.hops`.
+pub const PROPAGATION_HOPS_LIMIT: usize = 16;
+
+const HOPS_KEY: &str = "hops";
+
+/// A service a context went through, see [`Context::record_hop`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hop {
+    /// Name of the service which received the context.
+    pub service: String,
+    /// Time the context was received, in milliseconds since the Unix epoch.
+    pub at: u64,
+    /// Transport the context was received through.
+    pub transport: String,
+}
+
+impl Context {
+    /// Records that `service` received the context through `transport`.
+    pub fn record_hop(&mut self, service: &str, transport: &str) {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let hop = BTreeMap::from([
+            (Value::String("service".to_string()), Value::String(service.to_string())),
+            (Value::String("at".to_string()), Value::U64(u64::try_from(at).unwrap_or(u64::MAX))),
+            (Value::String("transport".to_string()), Value::String(transport.to_string())),
+        ]);
+        let mut propagation = self.at(PROPAGATION_KEY).as_map().cloned().unwrap_or_default();
+        let hops_key = Value::String(HOPS_KEY.to_string());
+        let mut hops = ValueRef::new(propagation.get(&hops_key))
+            .as_seq()
+            .map(<[Value]>::to_vec)
+            .unwrap_or_default();
+        hops.push(Value::Map(hop));
+        if hops.len() > PROPAGATION_HOPS_LIMIT {
+            hops.drain(..hops.len() - PROPAGATION_HOPS_LIMIT);
+        }
+        propagation.insert(hops_key, Value::Seq(hops));
+        self.insert(PROPAGATION_KEY.to_string(), Value::Map(propagation));
+    }
+
+    /// Returns the recorded hops, oldest first.
+    pub fn hops(&self) -> Vec<Hop> {
+        hops_of(self.at(PROPAGATION_KEY))
+    }
+}
+
+/// Returns the hops recorded in `propagation`, the value stored under [`PROPAGATION_KEY`].
+pub(crate) fn hops_of(propagation: ValueRef<'_>) -> Vec<Hop> {
+    propagation
+        .at(HOPS_KEY)
+        .as_seq()
+        .unwrap_or_default()
+        .iter()
+        .map(|hop| ValueRef::new(Some(hop)))
+        .map(|hop| Hop {
+            service: hop.at("service").as_str().unwrap_or_default().to_string(),
+            at: hop.at("at").as_u64().unwrap_or_default(),
+            transport: hop.at("transport").as_str().unwrap_or_default().to_string(),
+        })
+        .collect()
+}
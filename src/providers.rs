@@ -0,0 +1,158 @@
+//! Composable providers of host, process and build information.
+//!
+//! A [`ContextProvider`] returns entries to store in a context, and [`Context::with_providers`]
+//! composes several of them, the entries of a provider replacing those of the previous ones under
+//! the same key. The built-in providers record what every service reports along with its errors:
+//!
+//! | Provider              | Key       | Entries                                               |
+//! |-----------------------|-----------|-------------------------------------------------------|
+//! | [`SystemProvider`]    | `system`  | `hostname`, `os`, `arch`                              |
+//! | [`ProcessProvider`]   | `process` | `pid`, `executable`, `version`, `uptime_secs`         |
+//! | [`BuildInfoProvider`] | `build`   | the fields of [`BuildInfo`] which are set             |
+//!
+//! Missing sources, such as an unknown hostname, are silently ignored.
+//!
+//! ```rust
+//! use cdumay_context::{BuildInfo, BuildInfoProvider, Context, Contextualize, ProcessProvider, SystemProvider};
+//!
+//! let process = ProcessProvider::new().with_version("1.4.2");
+//! let build = BuildInfoProvider::from(BuildInfo { git_sha: Some("4f1c2e9".to_string()), ..Default::default() });
+//! let ctx = Context::new().with_providers(&[&SystemProvider, &process, &build]);
+//!
+//! assert_eq!(ctx.at("system").at("os").as_str(), Some(std::env::consts::OS));
+//! assert_eq!(ctx.at("process").at("version").as_str(), Some("1.4.2"));
+//! assert_eq!(ctx.at("build").at("git_sha").as_str(), Some("4f1c2e9"));
+//! ```
+use crate::{BuildInfo, Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// A source of entries, see [`Context::with_providers`].
+pub trait ContextProvider {
+    /// Returns the entries to store in the context.
+    fn provide(&self) -> BTreeMap<String, Value>;
+}
+
+/// Provides the host name, operating system and architecture under the `system` key.
+///
+/// The host name is read from the `HOSTNAME` or `COMPUTERNAME` environment variables, or from
+/// `/etc/hostname`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemProvider;
+
+impl ContextProvider for SystemProvider {
+    fn provide(&self) -> BTreeMap<String, Value> {
+        let hostname = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok());
+        section(
+            crate::keys::SYSTEM,
+            [
+                ("hostname", hostname.map(|h| Value::String(h.trim().to_string()))),
+                ("os", Some(Value::String(std::env::consts::OS.to_string()))),
+                ("arch", Some(Value::String(std::env::consts::ARCH.to_string()))),
+            ],
+        )
+    }
+}
+
+/// Provides the process id, executable name, binary version and uptime under the `process` key.
+///
+/// The uptime is measured from the creation of the provider, which should therefore be created
+/// when the process starts and kept for its whole life.
+#[derive(Debug, Clone)]
+pub struct ProcessProvider {
+    started: Instant,
+    version: Option<String>,
+}
+
+impl ProcessProvider {
+    /// Creates a provider whose uptime starts now.
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            version: None,
+        }
+    }
+
+    /// Sets the version of the binary, typically `env!("CARGO_PKG_VERSION")` of the application.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+}
+
+impl Default for ProcessProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextProvider for ProcessProvider {
+    fn provide(&self) -> BTreeMap<String, Value> {
+        let executable = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()));
+        section(
+            crate::keys::PROCESS,
+            [
+                ("pid", Some(Value::U32(std::process::id()))),
+                ("executable", executable.map(Value::String)),
+                ("version", self.version.clone().map(Value::String)),
+                ("uptime_secs", Some(Value::U64(self.started.elapsed().as_secs()))),
+            ],
+        )
+    }
+}
+
+/// Provides build information under the `build` key, as [`Context::with_build_info`] stores it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BuildInfoProvider {
+    info: BuildInfo,
+}
+
+impl BuildInfoProvider {
+    /// Creates a provider of `info`.
+    pub fn new(info: BuildInfo) -> Self {
+        Self { info }
+    }
+}
+
+impl From<BuildInfo> for BuildInfoProvider {
+    fn from(info: BuildInfo) -> Self {
+        Self::new(info)
+    }
+}
+
+impl ContextProvider for BuildInfoProvider {
+    fn provide(&self) -> BTreeMap<String, Value> {
+        Context::new().with_build_info(&self.info).inner()
+    }
+}
+
+impl Context {
+    /// Stores the entries of each provider in turn, see the [module documentation](self).
+    ///
+    /// Like [`Contextualize::extend`], values stored under protected or frozen keys are left
+    /// untouched.
+    pub fn with_providers(mut self, providers: &[&dyn ContextProvider]) -> Self {
+        for provider in providers {
+            Contextualize::extend(&mut self, provider.provide());
+        }
+        self
+    }
+}
+
+/// Returns the entries which are set as a map under `key`, nothing if none is set.
+fn section<const N: usize>(key: &str, entries: [(&str, Option<Value>); N]) -> BTreeMap<String, Value> {
+    let map: BTreeMap<Value, Value> = entries
+        .into_iter()
+        .filter_map(|(k, v)| Some((Value::String(k.to_string()), v?)))
+        .collect();
+    match map.is_empty() {
+        true => BTreeMap::new(),
+        false => BTreeMap::from([(key.to_string(), Value::Map(map))]),
+    }
+}
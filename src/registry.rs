@@ -0,0 +1,195 @@
+//! Registration of downstream error kinds.
+//!
+//! [`define_context_errors!`](crate::define_context_errors) lets downstream crates declare their
+//! own error kinds and errors in a namespace module, so that domain errors look identical to the
+//! built-in ones: they are generated by the same `cdumay_core` macros and get the same
+//! `with_context`, `with_relevant_context` and `with_source` methods.
+//!
+//! Codes must be unique within a namespace, a duplicate is a compile-time error:
+//!
+//! ```compile_fail
+//! cdumay_context::define_context_errors! {
+//!     billing {
+//!         PaymentRequired = PaymentRequiredError(402, "Payment required"),
+//!         CardDeclined = CardDeclinedError(402, "Card declined"),
+//!     }
+//! }
+//! ```
+//!
+//! The generated code refers to `cdumay_core` and `serde_value`, which must be dependencies of
+//! the calling crate.
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+const ERROR_KEY_FIELD: &str = "key";
+
+/// Returns `true` if `codes` contains the same code twice.
+#[doc(hidden)]
+pub const fn has_duplicate_codes(codes: &[u16]) -> bool {
+    let mut i = 0;
+    while i < codes.len() {
+        let mut j = i + 1;
+        while j < codes.len() {
+            if codes[i] == codes[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Records `key` under [`keys::ERROR_KEY`](crate::keys::ERROR_KEY) in `details`, keeping the
+/// other entries of `error`.
+#[doc(hidden)]
+pub fn record_error_key(details: &mut BTreeMap<String, Value>, key: &str) {
+    let mut entry = match details.remove(crate::keys::ERROR) {
+        Some(Value::Map(map)) => map,
+        _ => BTreeMap::new(),
+    };
+    entry.insert(Value::String(ERROR_KEY_FIELD.to_string()), Value::String(key.to_string()));
+    details.insert(crate::keys::ERROR.to_string(), Value::Map(entry));
+}
+
+/// Returns the key recorded by [`record_error_key`] in `details`, if any.
+#[doc(hidden)]
+pub fn error_key_of(details: &BTreeMap<String, Value>) -> Option<String> {
+    match details.get(crate::keys::ERROR)? {
+        Value::Map(entry) => match entry.get(&Value::String(ERROR_KEY_FIELD.to_string()))? {
+            Value::String(key) => Some(key.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds `for_key`, `key`, `with_context`, `with_current_context`, `with_relevant_context` and `with_source` to error types generated by `cdumay_core::define_errors!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_with_context {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl $name {
+                /// Creates an error about the context key `key`, recorded under
+                /// [`keys::ERROR_KEY`]($crate::keys::ERROR_KEY) in the details, merged with the dump
+                /// of `ctx`.
+                pub fn for_key<C: $crate::ContextDump + ?Sized>(key: &str, ctx: &C) -> Self {
+                    let mut details = std::collections::BTreeMap::new();
+                    $crate::record_error_key(&mut details, key);
+                    Self::new()
+                        .with_message(format!("{} (key '{}')", Self::kind.description(), key))
+                        .with_details(details)
+                        .with_context(ctx)
+                }
+
+                /// Returns the context key the error is about, if it was created with `for_key`.
+                pub fn key(&self) -> Option<String> {
+                    $crate::error_key_of(&self.details())
+                }
+
+                /// Merges the dump of `ctx` into the details of the error.
+                ///
+                /// Details already set on the error take precedence over context entries.
+                pub fn with_context<C: $crate::ContextDump + ?Sized>(self, ctx: &C) -> Self {
+                    let mut details = ctx.dump();
+                    details.extend(self.details());
+                    self.with_details(details)
+                }
+
+                /// Merges the dump of the current context of the task or thread, if any, see
+                /// [`Context::current`]($crate::Context::current), into the details of the error.
+                ///
+                /// Details already set on the error take precedence over context entries.
+                pub fn with_current_context(self) -> Self {
+                    match $crate::Context::current() {
+                        Some(ctx) => self.with_context(&ctx),
+                        None => self,
+                    }
+                }
+
+                /// Merges the entries of `ctx` relevant to the kind of the error into its details,
+                /// as selected by the global [`RelevanceRules`]($crate::RelevanceRules).
+                ///
+                /// Details already set on the error take precedence over context entries.
+                pub fn with_relevant_context<C: $crate::ContextDump + ?Sized>(self, ctx: &C) -> Self {
+                    self.with_relevant_context_by(ctx, &$crate::RelevanceRules::global())
+                }
+
+                /// Merges the entries of `ctx` relevant to the kind of the error into its details,
+                /// as selected by `rules`.
+                ///
+                /// Details already set on the error take precedence over context entries.
+                pub fn with_relevant_context_by<C: $crate::ContextDump + ?Sized>(self, ctx: &C, rules: &$crate::RelevanceRules) -> Self {
+                    let mut details = rules.select(Self::kind, ctx.dump());
+                    details.extend(self.details());
+                    self.with_details(details)
+                }
+
+                /// Attaches the underlying `source` of the error.
+                ///
+                /// The cause chain of `source` is recorded under `error.causes` in the details.
+                pub fn with_source<S>(self, source: S) -> $crate::WithSource<Self>
+                where
+                    S: std::error::Error + Send + Sync + 'static,
+                {
+                    let mut details = self.details();
+                    $crate::record_causes_into(&mut details, &source);
+                    $crate::WithSource::new(self.with_details(details), source)
+                }
+            }
+        )*
+    };
+}
+
+/// Declares error kinds and errors in a namespace module.
+///
+/// Each line declares an error and its kind, with the kind's code and description. The kinds
+/// in a public module named after the namespace, and the errors get the `with_context` and
+/// `with_source` methods.
+/// in a public module named after the namespace, and the errors get a `with_context` method.
+///
+/// # Example
+///
+/// ```rust
+/// use cdumay_context::{Context, Contextualize};
+///
+/// cdumay_context::define_context_errors! {
+///     billing {
+///         PaymentRequired = PaymentRequiredError(402, "Payment required"),
+///         InvoiceNotFound = InvoiceNotFoundError(404, "Invoice not found"),
+///     }
+/// }
+///
+/// let mut ctx = Context::new();
+/// ctx.insert("invoice".to_string(), serde_value::Value::U64(42));
+///
+/// let err: cdumay_core::Error = billing::InvoiceNotFound::new().with_context(&ctx).into();
+/// assert_eq!(err.code(), 404);
+/// assert!(err.details().contains_key("invoice"));
+/// ```
+#[macro_export]
+macro_rules! define_context_errors {
+    ($namespace:ident { $($error:ident = $kind:ident($code:literal, $description:literal)),* $(,)? }) => {
+        pub mod $namespace {
+            use cdumay_core::{define_errors, define_kinds};
+
+            const _: () = assert!(
+                !$crate::has_duplicate_codes(&[$($code),*]),
+                concat!("duplicate error code in namespace `", stringify!($namespace), "`")
+            );
+
+            define_kinds! {
+                $($kind = ($code, $description)),*
+            }
+
+            define_errors! {
+                $($error = $kind),*
+            }
+
+            $crate::impl_with_context! {
+                $($error),*
+            }
+        }
+    };
+}
@@ -0,0 +1,113 @@
+//! Context pruning by relevance to an error kind.
+//!
+//! A context may hold hundreds of entries, most of them unrelated to a given failure. The
+//! [`RelevanceRules`] map error kinds to the key patterns worth attaching to errors of that kind,
+//! and `with_relevant_context`, available on every error declared by this crate or with
+//! [`define_context_errors!`](crate::define_context_errors), only merges the matching entries of
+//! the context into the error details.
+//!
+//! Patterns may contain `*` wildcards, a pattern ending with `.*` also matching the key before
+//! the dot, so that `net.*` selects both `net.peer` and a map stored under `net`. Errors of kinds
+//! without a rule get the fallback patterns if set, or the whole context otherwise.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, RelevanceRules, Timeout, TimeoutError};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("net.peer".to_string(), Value::String("10.0.0.1".to_string()));
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//!
+//! let rules = RelevanceRules::new().with_rule(TimeoutError, &["net.*"]).with_common(&["request_id"]);
+//! let err = Timeout::new().with_relevant_context_by(&ctx, &rules);
+//! assert_eq!(err.details().keys().collect::<Vec<_>>(), vec!["net.peer", "request_id"]);
+//! ```
+use crate::snapshot::glob_match;
+use cdumay_core::ErrorKind;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Rules applied by `with_relevant_context`, set with [`RelevanceRules::set_global`].
+static GLOBAL_RULES: RwLock<RelevanceRules> = RwLock::new(RelevanceRules::new());
+
+/// Key patterns relevant to each error kind, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelevanceRules {
+    rules: BTreeMap<&'static str, Vec<String>>,
+    common: Vec<String>,
+    fallback: Option<Vec<String>>,
+}
+
+impl RelevanceRules {
+    /// Creates rules attaching the whole context to errors of every kind.
+    pub const fn new() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+            common: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    /// Returns the rules applied by `with_relevant_context`, empty unless set with
+    /// [`RelevanceRules::set_global`].
+    pub fn global() -> RelevanceRules {
+        GLOBAL_RULES.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Sets the rules applied by `with_relevant_context`.
+    pub fn set_global(rules: RelevanceRules) {
+        *GLOBAL_RULES.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = rules;
+    }
+
+    /// Attaches the entries matching `patterns` to errors of `kind`, in addition to the patterns
+    /// already set for it.
+    pub fn with_rule(mut self, kind: ErrorKind, patterns: &[&str]) -> Self {
+        self.rules.entry(kind.name()).or_default().extend(patterns.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Attaches the entries matching `patterns` to errors of every kind, such as correlation
+    /// identifiers.
+    pub fn with_common(mut self, patterns: &[&str]) -> Self {
+        self.common.extend(patterns.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Attaches the entries matching `patterns` to errors of kinds without a rule, instead of the
+    /// whole context.
+    pub fn with_fallback(mut self, patterns: &[&str]) -> Self {
+        self.fallback.get_or_insert_with(Vec::new).extend(patterns.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Returns the patterns set for `kind`, or the fallback patterns, `None` meaning the whole
+    /// context. Common patterns are not included.
+    pub fn patterns(&self, kind: ErrorKind) -> Option<&[String]> {
+        self.rules.get(kind.name()).or(self.fallback.as_ref()).map(Vec::as_slice)
+    }
+
+    /// Returns `true` if `key` is relevant to errors of `kind`.
+    pub fn is_relevant(&self, kind: ErrorKind, key: &str) -> bool {
+        self.matches(self.patterns(kind), key)
+    }
+
+    /// Keeps the entries of `data` relevant to errors of `kind`.
+    pub fn select(&self, kind: ErrorKind, mut data: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        let patterns = self.patterns(kind);
+        data.retain(|k, _| self.matches(patterns, k));
+        data
+    }
+
+    fn matches(&self, patterns: Option<&[String]>, key: &str) -> bool {
+        match patterns {
+            None => true,
+            Some(patterns) => patterns.iter().chain(&self.common).any(|pattern| matches(pattern, key)),
+        }
+    }
+}
+
+fn matches(pattern: &str, key: &str) -> bool {
+    glob_match(pattern, key) || pattern.strip_suffix(".*").is_some_and(|prefix| glob_match(prefix, key))
+}
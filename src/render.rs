@@ -0,0 +1,193 @@
+//! Compact and tabular text rendering of contexts.
+//!
+//! [`Contextualize::to_logfmt`] renders a context on a single line of `key=value` pairs
+//! separated by commas, for log lines; it is also the [`Display`](std::fmt::Display) rendering
+//! of [`Context`]. Strings holding spaces, quotes, commas, `=` or brackets are quoted, nested maps
+//! are rendered as `{key=value}` and sequences as `[value, value]`.
+//!
+//! [`Contextualize::to_table`] renders a context as an aligned two-column table for CLI tools,
+//! the entries of nested maps and sequences on their own rows below their parent key, indented.
+//! With the "color" feature, [`Contextualize::to_table_colored`] highlights it with ANSI escape
+//! codes.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("http".to_string(), Value::Map(BTreeMap::from([(Value::String("status".to_string()), Value::U16(404))])));
+//! ctx.insert("user".to_string(), Value::String("Jane Doe".to_string()));
+//!
+//! assert_eq!(ctx.to_string(), r#"http={status=404}, user="Jane Doe""#);
+//! assert_eq!(ctx.to_table(), "http\n  status  404\nuser      Jane Doe\n");
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::fmt;
+use std::fmt::Write;
+
+/// Renders `entries` as `key=value` pairs separated by commas.
+pub(crate) fn logfmt<'a>(entries: impl IntoIterator<Item = (&'a str, &'a Value)>) -> String {
+    let mut out = String::new();
+    for (index, (k, v)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        write_text(&mut out, k);
+        out.push('=');
+        write_compact(&mut out, v);
+    }
+    out
+}
+
+fn write_compact(out: &mut String, value: &Value) {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => write_compact(out, inner),
+        Value::Map(map) => {
+            out.push('{');
+            for (index, (k, v)) in map.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_text(out, &scalar(k));
+                out.push('=');
+                write_compact(out, v);
+            }
+            out.push('}');
+        }
+        Value::Seq(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_compact(out, item);
+            }
+            out.push(']');
+        }
+        Value::String(s) => write_text(out, s),
+        value => out.push_str(&scalar(value)),
+    }
+}
+
+/// Writes `text`, quoted and escaped if it would be ambiguous otherwise.
+fn write_text(out: &mut String, text: &str) {
+    let quote = text.is_empty() || text.chars().any(|c| c.is_whitespace() || c.is_control() || "\"=,{}[]".contains(c));
+    match quote {
+        true => {
+            let _ = write!(out, "{:?}", text);
+        }
+        false => out.push_str(text),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Bool(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::F32(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::Char(c) => c.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Bytes(bytes) => format!("<{} bytes>", bytes.len()),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => scalar(inner),
+        Value::Map(_) => "{}".to_string(),
+        Value::Seq(_) => "[]".to_string(),
+        Value::Unit | Value::Option(None) => "null".to_string(),
+    }
+}
+
+/// A row of a table: a label indented by its depth and a value, if it is not a parent row.
+struct Row {
+    depth: usize,
+    label: String,
+    value: Option<(String, Style)>,
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Text,
+    Number,
+    Null,
+}
+
+fn rows(out: &mut Vec<Row>, label: String, value: &Value, depth: usize) {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => rows(out, label, inner, depth),
+        Value::Map(map) if !map.is_empty() => {
+            out.push(Row { depth, label, value: None });
+            map.iter().for_each(|(k, v)| rows(out, scalar(k), v, depth + 1));
+        }
+        Value::Seq(items) if !items.is_empty() => {
+            out.push(Row { depth, label, value: None });
+            items
+                .iter()
+                .enumerate()
+                .for_each(|(index, item)| rows(out, format!("[{}]", index), item, depth + 1));
+        }
+        value => {
+            let style = match value {
+                Value::String(_) | Value::Char(_) | Value::Map(_) | Value::Seq(_) => Style::Text,
+                Value::Unit | Value::Option(None) | Value::Bytes(_) => Style::Null,
+                _ => Style::Number,
+            };
+            out.push(Row {
+                depth,
+                label,
+                value: Some((scalar(value), style)),
+            });
+        }
+    }
+}
+
+/// Renders `entries` as an aligned table, highlighted with ANSI escape codes if `color`.
+pub(crate) fn table<'a>(entries: impl IntoIterator<Item = (&'a str, &'a Value)>, color: bool) -> String {
+    let mut table = Vec::new();
+    for (k, v) in entries {
+        rows(&mut table, k.to_string(), v, 0);
+    }
+    let width = table
+        .iter()
+        .filter(|row| row.value.is_some())
+        .map(|row| row.depth * 2 + row.label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for row in table {
+        let indent = "  ".repeat(row.depth);
+        let label = match color {
+            true => format!("\x1b[1m{}\x1b[0m", row.label),
+            false => row.label.clone(),
+        };
+        match row.value {
+            None => {
+                let _ = writeln!(out, "{}{}", indent, label);
+            }
+            Some((value, style)) => {
+                let padding = " ".repeat(width - row.depth * 2 - row.label.chars().count() + 2);
+                let value = match (color, style) {
+                    (false, _) | (true, Style::Text) => value,
+                    (true, Style::Number) => format!("\x1b[33m{}\x1b[0m", value),
+                    (true, Style::Null) => format!("\x1b[2m{}\x1b[0m", value),
+                };
+                let _ = writeln!(out, "{}{}{}{}", indent, label, padding, value);
+            }
+        }
+    }
+    out
+}
+
+/// Renders the context with [`Contextualize::to_logfmt`].
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_logfmt())
+    }
+}
@@ -0,0 +1,92 @@
+//! HTTP error responses.
+//!
+//! [`ErrorResponse::new`] builds the status, content type and body of the HTTP response
+//! reporting an error, in the [`ResponseFormat`] the service follows, independently of the web
+//! framework. The context entries included in the body are those visible to the `audience`: a
+//! public API passes [`Sensitivity::Public`], an internal service [`Sensitivity::Internal`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, ErrorResponse, KeyNotFound, ResponseFormat, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("db_host".to_string(), Value::String("10.0.0.12".to_string()));
+//! ctx.set_sensitivity("request_id", Sensitivity::Public);
+//!
+//! let err: cdumay_core::Error = KeyNotFound::new().into();
+//! let response = ErrorResponse::new(&err, &ctx, ResponseFormat::ProblemDetails, Sensitivity::Public).unwrap();
+//! assert_eq!(response.status, 404);
+//! assert_eq!(response.content_type, "application/problem+json");
+//! assert!(response.body.contains("request_id"));
+//! assert!(!response.body.contains("db_host"));
+//! ```
+use crate::{Context, ContextDump, JsonApiError, JsonApiErrors, ProblemDetails, Sensitivity, JSON_API_CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE};
+use cdumay_core::ErrorConverter;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Format of the body of error responses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// A [`ProblemDetails`] object (default).
+    #[default]
+    ProblemDetails,
+    /// A [`JsonApiErrors`] document.
+    JsonApi,
+    /// A plain JSON object with the `code`, `class`, `message` and `details` of the error.
+    PlainJson,
+}
+
+impl ResponseFormat {
+    /// Returns the media type of bodies in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::ProblemDetails => PROBLEM_JSON_CONTENT_TYPE,
+            ResponseFormat::JsonApi => JSON_API_CONTENT_TYPE,
+            ResponseFormat::PlainJson => "application/json",
+        }
+    }
+}
+
+/// An HTTP response reporting an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Value of the `Content-Type` header.
+    pub content_type: &'static str,
+    /// JSON body.
+    pub body: String,
+}
+
+impl ErrorResponse {
+    /// Builds the response reporting `err`, with the entries of `ctx` visible to `audience`.
+    ///
+    /// The details of `err` are not included, as they usually hold a full dump of the context:
+    /// only the filtered entries of `ctx` are.
+    pub fn new(err: &cdumay_core::Error, ctx: &Context, format: ResponseFormat, audience: Sensitivity) -> cdumay_core::Result<Self> {
+        let view = ctx.view(audience);
+        let problem = ProblemDetails::from(err);
+        let status = problem.status;
+        let body = match format {
+            ResponseFormat::ProblemDetails => serde_json::to_string(&problem.with_view(&view)),
+            ResponseFormat::JsonApi => serde_json::to_string(&JsonApiErrors::from(JsonApiError::from(err).with_view(&view))),
+            ResponseFormat::PlainJson => {
+                let details: BTreeMap<Value, Value> = view.dump().into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+                serde_json::to_string(&BTreeMap::from([
+                    ("code", Value::U16(status)),
+                    ("class", Value::String(err.class().to_string())),
+                    ("message", Value::String(err.message().to_string())),
+                    ("details", Value::Map(details)),
+                ]))
+            }
+        }
+        .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump error response".to_string()), BTreeMap::new()))?;
+        Ok(Self {
+            status,
+            content_type: format.content_type(),
+            body,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+//! Context attached to the errors of any `Result`.
+//!
+//! [`ContextResultExt`] is implemented for every `Result` whose error converts into a
+//! `cdumay_core::Error`. [`ctx`](ContextResultExt::ctx) merges the dump of a context into the
+//! details of the error, and [`with_ctx`](ContextResultExt::with_ctx) computes the details only
+//! on error. As with `with_context`, details already set on the error take precedence over
+//! context entries.
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextResultExt, Contextualize, KeyNotFound};
+//! use serde_value::Value;
+//!
+//! fn find_user(id: u64) -> Result<String, KeyNotFound> {
+//!     Err(KeyNotFound::new().with_message(format!("User {} not found", id)))
+//! }
+//!
+//! fn handle(ctx: &Context) -> cdumay_core::Result<String> {
+//!     let user = find_user(42).ctx(ctx)?;
+//!     Ok(user)
+//! }
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!
+//! let err = handle(&ctx).unwrap_err();
+//! assert_eq!(err.code(), 404);
+//! assert_eq!(err.details().get("request_id"), Some(&Value::String("abc".to_string())));
+//! ```
+use crate::ContextDump;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Extension methods attaching context to the error of a `Result`, see the
+/// [module documentation](self).
+pub trait ContextResultExt<T> {
+    /// Converts the error into a `cdumay_core::Error` with the dump of `ctx` merged into its
+    /// details.
+    fn ctx<C: ContextDump + ?Sized>(self, ctx: &C) -> cdumay_core::Result<T>;
+
+    /// Converts the error into a `cdumay_core::Error` with the entries returned by `f`, called
+    /// only on error, merged into its details.
+    fn with_ctx<F: FnOnce() -> BTreeMap<String, Value>>(self, f: F) -> cdumay_core::Result<T>;
+}
+
+impl<T, E: Into<cdumay_core::Error>> ContextResultExt<T> for Result<T, E> {
+    fn ctx<C: ContextDump + ?Sized>(self, ctx: &C) -> cdumay_core::Result<T> {
+        self.with_ctx(|| ctx.dump())
+    }
+
+    fn with_ctx<F: FnOnce() -> BTreeMap<String, Value>>(self, f: F) -> cdumay_core::Result<T> {
+        self.map_err(|err| merge_details(err.into(), f()))
+    }
+}
+
+/// Returns `err` with `details` merged into its details, those of the error taking precedence.
+pub(crate) fn merge_details(err: cdumay_core::Error, mut details: BTreeMap<String, Value>) -> cdumay_core::Error {
+    details.extend(err.details());
+    cdumay_core::Error::new(err.code(), err.class().to_string(), err.message().to_string(), details)
+}
@@ -0,0 +1,76 @@
+//! Retry bookkeeping.
+//!
+//! Retry loops record each failed attempt with [`Context::record_attempt`], which maintains the
+//! following keys, so that the final error carries a consistent account of what happened:
+//!
+//! * `attempt`: number of attempts recorded so far;
+//! * `max_attempts`: the limit set with [`Context::set_max_attempts`], if any;
+//! * `attempt_history`: the last [`ATTEMPT_HISTORY_LIMIT`] attempts, oldest first, each one
+//!   being a map with the `attempt` number, the `error` summary and the time it was recorded
+//!   (`at`, in milliseconds since the Unix epoch).
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut ctx = Context::new();
+//! ctx.set_max_attempts(3);
+//! while ctx.has_attempts_left() {
+//!     ctx.record_attempt("connection refused");
+//! }
+//! assert_eq!(ctx.attempt(), 3);
+//! assert_eq!(ctx.at("attempt_history").as_seq().map(|h| h.len()), Some(3));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of attempts kept in the `attempt_history` key.
+pub const ATTEMPT_HISTORY_LIMIT: usize = 10;
+
+const ATTEMPT_KEY: &str = crate::keys::ATTEMPT;
+const MAX_ATTEMPTS_KEY: &str = crate::keys::MAX_ATTEMPTS;
+const ATTEMPT_HISTORY_KEY: &str = crate::keys::ATTEMPT_HISTORY;
+
+impl Context {
+    /// Records a failed attempt described by `error` and returns the number of attempts so far.
+    pub fn record_attempt(&mut self, error: impl Into<String>) -> u64 {
+        let attempt = self.attempt() + 1;
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let entry = BTreeMap::from([
+            (Value::String("attempt".to_string()), Value::U64(attempt)),
+            (Value::String("error".to_string()), Value::String(error.into())),
+            (Value::String("at".to_string()), Value::U64(u64::try_from(at).unwrap_or(u64::MAX))),
+        ]);
+        let mut history = self.at(ATTEMPT_HISTORY_KEY).as_seq().map(<[Value]>::to_vec).unwrap_or_default();
+        history.push(Value::Map(entry));
+        if history.len() > ATTEMPT_HISTORY_LIMIT {
+            history.drain(..history.len() - ATTEMPT_HISTORY_LIMIT);
+        }
+        self.insert(ATTEMPT_KEY.to_string(), Value::U64(attempt));
+        self.insert(ATTEMPT_HISTORY_KEY.to_string(), Value::Seq(history));
+        attempt
+    }
+
+    /// Sets the maximum number of attempts.
+    pub fn set_max_attempts(&mut self, max_attempts: u64) {
+        self.insert(MAX_ATTEMPTS_KEY.to_string(), Value::U64(max_attempts));
+    }
+
+    /// Returns the number of attempts recorded so far.
+    pub fn attempt(&self) -> u64 {
+        self.at(ATTEMPT_KEY).as_u64().unwrap_or(0)
+    }
+
+    /// Returns the maximum number of attempts, if set.
+    pub fn max_attempts(&self) -> Option<u64> {
+        self.at(MAX_ATTEMPTS_KEY).as_u64()
+    }
+
+    /// Returns `false` once as many attempts as allowed by `max_attempts` have been recorded.
+    ///
+    /// Always returns `true` if no maximum is set.
+    pub fn has_attempts_left(&self) -> bool {
+        self.max_attempts().is_none_or(|max| self.attempt() < max)
+    }
+}
@@ -0,0 +1,75 @@
+//! RON backend, see [`Contextualize::from_ron`](crate::Contextualize::from_ron) and
+//! [`Contextualize::to_ron`](crate::Contextualize::to_ron).
+//!
+//! There is no `cdumay_ron` crate, the errors of `ron` are converted by the converters of this
+//! module: a document that does not parse gives a [`TypeMismatch`] error with the line and the
+//! column of the failure in its details, and a failed dump a [`SerializationError`].
+use crate::{SerializationError, TypeMismatch, UnExpectedError};
+use cdumay_core::ErrorConverter;
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Converts the errors raised while dumping to RON.
+pub(crate) struct RonSerializeErrorConverter;
+
+impl ErrorConverter for RonSerializeErrorConverter {
+    type Error = ron::Error;
+
+    fn convert(err: &ron::Error, text: String, context: BTreeMap<String, Value>) -> cdumay_core::Error {
+        match err {
+            ron::Error::Io(_) | ron::Error::Fmt => UnExpectedError::new().with_message(text).with_details(context).into(),
+            _ => SerializationError::new().with_message(text).with_details(context).into(),
+        }
+    }
+}
+
+/// Converts the errors raised while loading RON, with the position of the failure.
+pub(crate) struct RonDeserializeErrorConverter;
+
+impl ErrorConverter for RonDeserializeErrorConverter {
+    type Error = ron::error::SpannedError;
+
+    fn convert(err: &ron::error::SpannedError, text: String, mut context: BTreeMap<String, Value>) -> cdumay_core::Error {
+        context.insert("line".to_string(), Value::U64(err.span.start.line as u64));
+        context.insert("column".to_string(), Value::U64(err.span.start.col as u64));
+        match err.code {
+            ron::Error::Io(_) | ron::Error::Fmt => UnExpectedError::new().with_message(text).with_details(context).into(),
+            _ => TypeMismatch::new().with_message(text).with_details(context).into(),
+        }
+    }
+}
+
+/// Parses a RON map or struct with string keys.
+pub(crate) fn load(ron: &str) -> cdumay_core::Result<BTreeMap<String, Value>> {
+    let value = ron::from_str(ron)
+        .map_err(|err| RonDeserializeErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), BTreeMap::new()))?;
+    let not_a_map = || {
+        TypeMismatch::new()
+            .with_message("Failed to load context: RON document is not a map with string keys".to_string())
+            .into()
+    };
+    match value {
+        Value::Map(map) => map
+            .into_iter()
+            .map(|(k, v)| match k {
+                Value::String(k) => Ok((k, v)),
+                _ => Err(not_a_map()),
+            })
+            .collect(),
+        _ => Err(not_a_map()),
+    }
+}
+
+/// Dumps `entries` to RON, pretty-printed if `pretty`, with `details` in the error on failure.
+pub(crate) fn dump<T: Serialize + ?Sized>(
+    entries: &T,
+    pretty: bool,
+    details: impl FnOnce() -> BTreeMap<String, Value>,
+) -> cdumay_core::Result<String> {
+    let dump = match pretty {
+        true => ron::ser::to_string_pretty(entries, ron::ser::PrettyConfig::default()),
+        false => ron::to_string(entries),
+    };
+    dump.map_err(|err| RonSerializeErrorConverter::convert_error(&err, Some("Failed to dump context".to_string()), details()))
+}
@@ -0,0 +1,111 @@
+//! Log target routing.
+//!
+//! Structured logging layers often send dumps to different indices depending on what the context
+//! holds. The [`RouteRules`] map conditions on the context to a log target, and
+//! [`Context::route_target`] returns the target of the first rule whose conditions all hold, or
+//! the default target.
+//!
+//! A condition is a dotted path and a value pattern, which may contain `*` wildcards: it holds if
+//! the value found at the path, rendered as a string, matches the pattern. The path is first
+//! looked up as a key, then through nested maps and sequences, see [`Context::get_path`]; a
+//! missing value never matches, so `*` checks that a value is set.
+//!
+//! ```rust
+//! use cdumay_context::{keys, Context, Contextualize, RouteRules};
+//! use serde_value::Value;
+//!
+//! let rules = RouteRules::new()
+//!     .with_rule("payments.errors", &[("domain", "payments"), (keys::ERROR, "*")])
+//!     .with_rule("payments", &[("domain", "payments")])
+//!     .with_default("app");
+//!
+//! let mut ctx = Context::new();
+//! assert_eq!(ctx.route_target_by(&rules), "app");
+//! ctx.insert("domain".to_string(), Value::String("payments".to_string()));
+//! assert_eq!(ctx.route_target_by(&rules), "payments");
+//! ctx.insert(keys::ERROR.to_string(), Value::String("card declined".to_string()));
+//! assert_eq!(ctx.route_target_by(&rules), "payments.errors");
+//! ```
+use crate::mapkey::stringify;
+use crate::snapshot::glob_match;
+use crate::{Context, Contextualize};
+use std::sync::RwLock;
+
+/// Rules applied by [`Context::route_target`], set with [`RouteRules::set_global`].
+static GLOBAL_RULES: RwLock<RouteRules> = RwLock::new(RouteRules::new());
+
+/// Target used when no rule matches, unless set with [`RouteRules::with_default`].
+pub const DEFAULT_ROUTE_TARGET: &str = "default";
+
+/// Log targets selected from the content of a context, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteRules {
+    rules: Vec<(String, Vec<(String, String)>)>,
+    default: Option<String>,
+}
+
+impl RouteRules {
+    /// Creates rules routing every context to [`DEFAULT_ROUTE_TARGET`].
+    pub const fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: None,
+        }
+    }
+
+    /// Returns the rules applied by [`Context::route_target`], empty unless set with
+    /// [`RouteRules::set_global`].
+    pub fn global() -> RouteRules {
+        GLOBAL_RULES.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Sets the rules applied by [`Context::route_target`].
+    pub fn set_global(rules: RouteRules) {
+        *GLOBAL_RULES.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = rules;
+    }
+
+    /// Routes the contexts matching all the `(path, pattern)` `conditions` to `target`. Rules are
+    /// tried in the order they were added, a rule without conditions matching every context.
+    pub fn with_rule(mut self, target: &str, conditions: &[(&str, &str)]) -> Self {
+        self.rules.push((
+            target.to_string(),
+            conditions.iter().map(|(path, pattern)| (path.to_string(), pattern.to_string())).collect(),
+        ));
+        self
+    }
+
+    /// Routes the contexts matching no rule to `target` instead of [`DEFAULT_ROUTE_TARGET`].
+    pub fn with_default(mut self, target: &str) -> Self {
+        self.default = Some(target.to_string());
+        self
+    }
+
+    /// Returns the target of the first rule matching `ctx`, or the default target.
+    pub fn target(&self, ctx: &Context) -> &str {
+        self.rules
+            .iter()
+            .find(|(_, conditions)| conditions.iter().all(|(path, pattern)| matches(ctx, path, pattern)))
+            .map(|(target, _)| target.as_str())
+            .or(self.default.as_deref())
+            .unwrap_or(DEFAULT_ROUTE_TARGET)
+    }
+}
+
+impl Context {
+    /// Returns the log target of the context according to the global rules, see
+    /// [`RouteRules::set_global`].
+    pub fn route_target(&self) -> String {
+        self.route_target_by(&RouteRules::global())
+    }
+
+    /// Returns the log target of the context according to `rules`.
+    pub fn route_target_by(&self, rules: &RouteRules) -> String {
+        rules.target(self).to_string()
+    }
+}
+
+fn matches(ctx: &Context, path: &str, pattern: &str) -> bool {
+    ctx.get(path)
+        .or_else(|| ctx.get_path(path))
+        .is_some_and(|value| glob_match(pattern, &stringify(value)))
+}
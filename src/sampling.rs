@@ -0,0 +1,69 @@
+//! Consistent sampling decisions.
+//!
+//! Tail-based sampling only works across services if every hop keeps or drops the dumps of a
+//! request together. [`Context::sampling_fingerprint`] derives a stable 64-bit value from
+//! selected keys, such as the tenant, the route or the error kind, and [`Context::is_sampled`]
+//! compares it with a rate, so that services sharing these keys take the same decision without
+//! coordination.
+//!
+//! The fingerprint is a 64-bit FNV-1a hash which other implementations can reproduce. For each
+//! key, in the given order, the hash is fed with the UTF-8 bytes of the key and a `0x1f` byte,
+//! then with a `0x01` byte followed by the UTF-8 rendering of the value if the key holds one, or
+//! with a `0x00` byte otherwise, and finally with a `0x1e` byte. Keys are dotted paths, looked up
+//! as keys first, then through nested maps and sequences, see [`Context::get_path`]. Scalars are
+//! rendered as their display form, so that `503` and `"503"` give the same fingerprint, and null
+//! values as `null`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut gateway = Context::new();
+//! gateway.insert("tenant".to_string(), Value::String("acme".to_string()));
+//! gateway.insert("route".to_string(), Value::String("/orders".to_string()));
+//! gateway.insert("user".to_string(), Value::String("jane".to_string()));
+//!
+//! let mut backend = Context::new();
+//! backend.insert("tenant".to_string(), Value::String("acme".to_string()));
+//! backend.insert("route".to_string(), Value::String("/orders".to_string()));
+//!
+//! let keys = ["tenant", "route"];
+//! assert_eq!(gateway.sampling_fingerprint(&keys), backend.sampling_fingerprint(&keys));
+//! assert_eq!(gateway.is_sampled(&keys, 0.1), backend.is_sampled(&keys, 0.1));
+//! ```
+use crate::hash::Fnv1a;
+use crate::mapkey::stringify;
+use crate::{Context, Contextualize};
+use std::hash::Hasher;
+
+impl Context {
+    /// Returns a stable 64-bit fingerprint of the values under `keys`, see the
+    /// [module documentation](self).
+    pub fn sampling_fingerprint(&self, keys: &[&str]) -> u64 {
+        let mut hasher = Fnv1a::default();
+        let mut feed = |bytes: &[u8]| hasher.write(bytes);
+        for key in keys {
+            feed(key.as_bytes());
+            feed(&[0x1f]);
+            match self.get(key).or_else(|| self.get_path(key)) {
+                Some(value) => {
+                    feed(&[0x01]);
+                    feed(stringify(value).as_bytes());
+                }
+                None => feed(&[0x00]),
+            }
+            feed(&[0x1e]);
+        }
+        hasher.finish()
+    }
+
+    /// Returns `true` if the context is sampled at `rate`, between `0.0`, nothing being sampled,
+    /// and `1.0`, everything being sampled.
+    ///
+    /// The 53 high bits of [`Context::sampling_fingerprint`], read as a fraction of `2^53`, are
+    /// compared with `rate`, so that contexts sampled at a rate are also sampled at any higher one.
+    pub fn is_sampled(&self, keys: &[&str], rate: f64) -> bool {
+        let position = (self.sampling_fingerprint(keys) >> 11) as f64 / (1u64 << 53) as f64;
+        position < rate
+    }
+}
@@ -0,0 +1,427 @@
+//! Declared context schemas.
+//!
+//! A [`ContextSchema`] declares, in code, the keys a context is expected to hold, with the kind
+//! and meaning of their values. It is the canonical documentation of what an error context
+//! contains: [`ContextSchema::to_markdown_docs`] and, with the "json" feature,
+//! [`ContextSchema::to_json_schema`] generate the documentation from it, so that it cannot drift
+//! from the code.
+//!
+//! ```rust
+//! use cdumay_context::{ContextSchema, ValueKind};
+//!
+//! let schema = ContextSchema::new()
+//!     .with_title("Job context")
+//!     .required("tenant_id", ValueKind::String, "Tenant running the job")
+//!     .optional("attempt", ValueKind::Integer, "Attempt number, starting at 0");
+//!
+//! let docs = schema.to_markdown_docs();
+//! assert!(docs.starts_with("# Job context\n"));
+//! assert!(docs.contains("| `tenant_id` | string | yes | Tenant running the job |"));
+//! ```
+//!
+//! A schema also validates contexts before they are used, e.g. before dispatching a job:
+//! [`ContextSchema::validate`] checks that the required keys are present, that the declared keys
+//! hold values of the declared kind and that the custom rules added with
+//! [`ContextSchema::with_rule`] hold, and reports all the violations at once as
+//! [`ValidationErrors`]. Each violation converts into a [`ValidationError`] recording the key it
+//! is about, and the whole report into a single [`ValidationError`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextSchema, Contextualize, ValueKind, ViolationKind};
+//! use serde_value::Value;
+//!
+//! let schema = ContextSchema::new()
+//!     .required("tenant_id", ValueKind::String, "Tenant running the job")
+//!     .required("attempt", ValueKind::Integer, "Attempt number, starting at 0")
+//!     .with_rule("attempt", "non-negative", |value| !matches!(value, Value::I64(n) if *n < 0));
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("attempt".to_string(), Value::I64(-1));
+//!
+//! let errors = schema.validate(&ctx).unwrap_err();
+//! assert_eq!(errors.len(), 2);
+//! assert_eq!(errors.violations()[0].kind, ViolationKind::Missing);
+//! assert_eq!(errors.violations()[1].kind, ViolationKind::Rule("non-negative".to_string()));
+//! ```
+use crate::{Contextualize, ValidationError};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Kind of value expected under a key of a [`ContextSchema`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueKind {
+    /// String or character.
+    String,
+    /// Signed or unsigned integer.
+    Integer,
+    /// Floating-point or integer number.
+    Number,
+    /// Boolean.
+    Bool,
+    /// Sequence.
+    Seq,
+    /// Map.
+    Map,
+    /// Any value.
+    Any,
+}
+
+impl ValueKind {
+    /// Returns the JSON Schema type of the kind, `None` for [`ValueKind::Any`].
+    pub fn json_type(&self) -> Option<&'static str> {
+        match self {
+            ValueKind::String => Some("string"),
+            ValueKind::Integer => Some("integer"),
+            ValueKind::Number => Some("number"),
+            ValueKind::Bool => Some("boolean"),
+            ValueKind::Seq => Some("array"),
+            ValueKind::Map => Some("object"),
+            ValueKind::Any => None,
+        }
+    }
+
+    /// Returns `true` if `value` is of this kind, optional and newtype values being unwrapped.
+    pub fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (ValueKind::Any, _) => true,
+            (kind, Value::Option(Some(inner)) | Value::Newtype(inner)) => kind.matches(inner),
+            (ValueKind::String, Value::String(_) | Value::Char(_)) => true,
+            (ValueKind::Integer | ValueKind::Number, Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_)) => true,
+            (ValueKind::Integer | ValueKind::Number, Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_)) => true,
+            (ValueKind::Number, Value::F32(_) | Value::F64(_)) => true,
+            (ValueKind::Bool, Value::Bool(_)) => true,
+            (ValueKind::Seq, Value::Seq(_)) => true,
+            (ValueKind::Map, Value::Map(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.json_type().unwrap_or("any"))
+    }
+}
+
+/// Key declared in a [`ContextSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySchema {
+    /// Key, as stored in the context.
+    pub key: String,
+    /// Kind of its value.
+    pub kind: ValueKind,
+    /// Whether the key must be present.
+    pub required: bool,
+    /// Meaning of the value.
+    pub description: String,
+}
+
+type Check = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// Custom validation rule of a [`ContextSchema`], see [`ContextSchema::with_rule`].
+#[derive(Clone)]
+struct Rule {
+    key: String,
+    name: String,
+    check: Check,
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule")
+            .field("key", &self.key)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Rules are equal if they have the same key and name and share the same function.
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.name == other.name && Arc::ptr_eq(&self.check, &other.check)
+    }
+}
+
+impl Eq for Rule {}
+
+/// Keys expected in a context, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextSchema {
+    title: Option<String>,
+    description: Option<String>,
+    keys: Vec<KeySchema>,
+    rules: Vec<Rule>,
+}
+
+impl ContextSchema {
+    /// Creates a schema without keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title of the generated documentation, `Context` by default.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Sets the description of the generated documentation.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Declares the required `key`, replacing any previous declaration of it.
+    pub fn required(self, key: &str, kind: ValueKind, description: &str) -> Self {
+        self.with_key(KeySchema {
+            key: key.to_string(),
+            kind,
+            required: true,
+            description: description.to_string(),
+        })
+    }
+
+    /// Declares the optional `key`, replacing any previous declaration of it.
+    pub fn optional(self, key: &str, kind: ValueKind, description: &str) -> Self {
+        self.with_key(KeySchema {
+            key: key.to_string(),
+            kind,
+            required: false,
+            description: description.to_string(),
+        })
+    }
+
+    /// Declares a key, replacing any previous declaration of it.
+    pub fn with_key(mut self, key: KeySchema) -> Self {
+        match self.keys.iter_mut().find(|declared| declared.key == key.key) {
+            Some(declared) => *declared = key,
+            None => self.keys.push(key),
+        }
+        self
+    }
+
+    /// Adds the custom rule `name`, checked by `check` on the value of `key` when it is present
+    /// and, if declared, of the declared kind.
+    ///
+    /// Rules are checked in the order they are added, several rules may apply to the same key.
+    pub fn with_rule<F>(mut self, key: &str, name: &str, check: F) -> Self
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        self.rules.push(Rule {
+            key: key.to_string(),
+            name: name.to_string(),
+            check: Arc::new(check),
+        });
+        self
+    }
+
+    /// Checks `ctx` against the schema, keys other than the declared ones being allowed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), ValidationErrors>` which is:
+    /// * `Ok(())` if the context matches the schema
+    /// * `Err(errors)` containing all the violations, those of the declared keys in declaration
+    ///   order first, then those of the rules in the order they were added
+    pub fn validate<C: Contextualize>(&self, ctx: &C) -> Result<(), ValidationErrors> {
+        let mut violations = Vec::new();
+        for declared in &self.keys {
+            match ctx.get(&declared.key) {
+                None if declared.required => violations.push(Violation {
+                    key: declared.key.clone(),
+                    kind: ViolationKind::Missing,
+                    message: format!("Required context key '{}' is missing", declared.key),
+                }),
+                Some(value) if !declared.kind.matches(value) => violations.push(Violation {
+                    key: declared.key.clone(),
+                    kind: ViolationKind::WrongKind(declared.kind),
+                    message: format!("Context key '{}' must hold a value of kind {}", declared.key, declared.kind),
+                }),
+                _ => {}
+            }
+        }
+        for rule in &self.rules {
+            let checked = ctx
+                .get(&rule.key)
+                .filter(|value| self.key(&rule.key).is_none_or(|declared| declared.kind.matches(value)));
+            if checked.is_some_and(|value| !(rule.check)(value)) {
+                violations.push(Violation {
+                    key: rule.key.clone(),
+                    kind: ViolationKind::Rule(rule.name.clone()),
+                    message: format!("Context key '{}' does not satisfy rule '{}'", rule.key, rule.name),
+                });
+            }
+        }
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors {
+                violations,
+                details: ctx.inner(),
+            }),
+        }
+    }
+
+    /// Returns the title of the generated documentation.
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or("Context")
+    }
+
+    /// Returns the declared keys, in declaration order.
+    pub fn keys(&self) -> &[KeySchema] {
+        &self.keys
+    }
+
+    /// Returns the declaration of `key`, if any.
+    pub fn key(&self, key: &str) -> Option<&KeySchema> {
+        self.keys.iter().find(|declared| declared.key == key)
+    }
+
+    /// Returns the schema as a JSON Schema (draft 2020-12) of the dump of a context, keys other
+    /// than the declared ones being allowed.
+    ///
+    /// This method is only available when the "json" feature is enabled.
+    #[cfg(feature = "json")]
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let properties: serde_json::Map<String, serde_json::Value> = self
+            .keys
+            .iter()
+            .map(|declared| {
+                let mut property = serde_json::Map::new();
+                if let Some(json_type) = declared.kind.json_type() {
+                    property.insert("type".to_string(), json_type.into());
+                }
+                property.insert("description".to_string(), declared.description.as_str().into());
+                (declared.key.clone(), serde_json::Value::Object(property))
+            })
+            .collect();
+        let required: Vec<serde_json::Value> = self
+            .keys
+            .iter()
+            .filter(|declared| declared.required)
+            .map(|declared| declared.key.as_str().into())
+            .collect();
+        let mut schema = serde_json::Map::new();
+        schema.insert("$schema".to_string(), "https://json-schema.org/draft/2020-12/schema".into());
+        schema.insert("title".to_string(), self.title().into());
+        if let Some(description) = &self.description {
+            schema.insert("description".to_string(), description.as_str().into());
+        }
+        schema.insert("type".to_string(), "object".into());
+        schema.insert("properties".to_string(), serde_json::Value::Object(properties));
+        schema.insert("required".to_string(), serde_json::Value::Array(required));
+        schema.insert("additionalProperties".to_string(), true.into());
+        serde_json::Value::Object(schema)
+    }
+
+    /// Returns the schema as a Markdown document: the title, the description and a table of the
+    /// keys in declaration order.
+    pub fn to_markdown_docs(&self) -> String {
+        let mut docs = format!("# {}\n\n", self.title());
+        if let Some(description) = &self.description {
+            docs.push_str(description);
+            docs.push_str("\n\n");
+        }
+        docs.push_str("| Key | Type | Required | Description |\n| --- | --- | --- | --- |\n");
+        for declared in &self.keys {
+            docs.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                declared.key,
+                declared.kind,
+                if declared.required { "yes" } else { "no" },
+                markdown_cell(&declared.description)
+            ));
+        }
+        docs
+    }
+}
+
+/// Kind of a [`Violation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A required key is missing.
+    Missing,
+    /// A declared key holds a value of another kind than the declared one.
+    WrongKind(ValueKind),
+    /// A value does not satisfy the named custom rule.
+    Rule(String),
+}
+
+/// A violation of a [`ContextSchema`] by a context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Key in violation.
+    pub key: String,
+    /// Kind of violation.
+    pub kind: ViolationKind,
+    /// Human-readable description of the violation.
+    pub message: String,
+}
+
+/// Violations found by [`ContextSchema::validate`], never empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationErrors {
+    violations: Vec<Violation>,
+    details: BTreeMap<String, Value>,
+}
+
+impl ValidationErrors {
+    /// Returns the violations, see [`ContextSchema::validate`] for their order.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns the number of violations.
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    /// Returns `false`, a validation failure always holds at least one violation.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns one [`ValidationError`] per violation, with the dump of the validated context as
+    /// details and the key of the violation recorded as the key of the error, see
+    /// [`ValidationError::key`].
+    pub fn errors(&self) -> Vec<cdumay_core::Error> {
+        self.violations
+            .iter()
+            .map(|violation| {
+                let mut details = self.details.clone();
+                crate::record_error_key(&mut details, &violation.key);
+                ValidationError::new()
+                    .with_message(violation.message.clone())
+                    .with_details(details)
+                    .into()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<&str> = self.violations.iter().map(|violation| violation.message.as_str()).collect();
+        f.write_str(&messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Converts the violations into a single [`ValidationError`] listing them all, with the
+/// dump of the validated context as details.
+impl From<ValidationErrors> for cdumay_core::Error {
+    fn from(errors: ValidationErrors) -> Self {
+        ValidationError::new()
+            .with_message(format!("Context validation failed: {}", errors))
+            .with_details(errors.details)
+            .into()
+    }
+}
+
+/// Escapes `text` for a Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
@@ -0,0 +1,126 @@
+//! Instrumented blocks.
+//!
+//! [`with_context!`](crate::with_context) runs a block with extra entries pushed on a context,
+//! restoring the previous values of these keys afterwards, and records the time spent in the
+//! block under `<name>_elapsed_ms`, `<name>` being the value of the first entry. The block
+//! returns a `Result` whose error converts into a `cdumay_core::Error`: on `Err`, the entries of
+//! the context, pushed ones included, are merged into the details of the error, details already
+//! set on the error taking precedence. On panic, the block panics again with a redacted dump of
+//! the context in the message, see [`Context::expect_key`].
+//!
+//! The macro is sugar for [`Context::scoped`], within the block the context is reborrowed
+//! under the same name.
+//!
+//! ```rust
+//! use cdumay_context::{with_context, Context, Contextualize, KeyNotFound};
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("step".to_string(), serde_value::Value::String("init".to_string()));
+//!
+//! let result: cdumay_core::Result<()> = with_context!(ctx, { "step" => "upload", "bucket" => "logs" }, {
+//!     assert_eq!(ctx.get_str("bucket").unwrap(), "logs");
+//!     Err(KeyNotFound::new().with_message("no such bucket".to_string()))
+//! });
+//!
+//! let err = result.unwrap_err();
+//! assert_eq!(err.details()["bucket"], serde_value::Value::String("logs".to_string()));
+//! assert!(err.details().contains_key("upload_elapsed_ms"));
+//! assert_eq!(ctx.get_str("step").unwrap(), "init");
+//! assert!(!ctx.contains_key("bucket"));
+//! ```
+use crate::mapkey::stringify;
+use crate::result::merge_details;
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Instant;
+
+impl Context {
+    /// Runs `f` with `entries` pushed on the context, see the [module documentation](self).
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<T>` which is:
+    /// * `Ok(value)` if `f` succeeded
+    /// * `Err(e)` containing the error of `f`, with the entries of the context merged into its
+    ///   details
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics, with a redacted dump of the context in the message.
+    #[track_caller]
+    pub fn scoped<T, E, F>(&mut self, entries: Vec<(String, Value)>, f: F) -> cdumay_core::Result<T>
+    where
+        E: Into<cdumay_core::Error>,
+        F: FnOnce(&mut Context) -> Result<T, E>,
+    {
+        let name = entries.first().map_or_else(|| "scope".to_string(), |(_, v)| stringify(v));
+        let mut previous = Vec::with_capacity(entries.len());
+        for (k, v) in entries {
+            previous.push((k.clone(), self.get(&k).cloned()));
+            self.insert(k, v);
+        }
+        let start = Instant::now();
+        let result = catch_unwind(AssertUnwindSafe(|| f(self)));
+        let elapsed = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.insert(format!("{}_elapsed_ms", name), Value::U64(elapsed));
+        let result = match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(err)) => Err(merge_details(err.into(), self.error_details())),
+            Err(payload) => {
+                let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+                    (Some(message), _) => message.to_string(),
+                    (_, Some(message)) => message.clone(),
+                    _ => "Box<dyn Any>".to_string(),
+                };
+                let dump = self.panic_dump();
+                self.restore(previous);
+                panic!("{} in `{}` with context: {}", message, name, dump);
+            }
+        };
+        self.restore(previous);
+        result
+    }
+
+    /// Restores the `previous` values of the keys pushed by [`Context::scoped`], in reverse order.
+    fn restore(&mut self, previous: Vec<(String, Option<Value>)>) {
+        for (k, v) in previous.into_iter().rev() {
+            match v {
+                Some(v) => self.insert(k, v),
+                None => {
+                    self.remove(&k);
+                }
+            }
+        }
+    }
+}
+
+/// Runs a block with entries pushed on a context, see [`Context::scoped`](crate::Context::scoped).
+///
+/// The first argument is the name of a `Context` variable, the second the entries to push as
+/// `key => value` pairs, values being serializable, and the third the block, which must evaluate
+/// to a `Result` whose error converts into a `cdumay_core::Error`. The macro evaluates to a
+/// `cdumay_core::Result`.
+///
+/// ```rust
+/// use cdumay_context::{with_context, Context, Contextualize};
+///
+/// let mut ctx = Context::new();
+/// let size = with_context!(ctx, { "step" => "compress", "level" => 9 }, {
+///     assert_eq!(ctx.get("level"), Some(&serde_value::Value::I32(9)));
+///     Ok::<_, cdumay_core::Error>(42)
+/// });
+/// assert_eq!(size.unwrap(), 42);
+/// assert!(ctx.get("compress_elapsed_ms").is_some());
+/// ```
+///
+/// The generated code refers to `serde_value`, which must be a dependency of the calling crate.
+#[macro_export]
+macro_rules! with_context {
+    ($ctx:ident, { $($key:expr => $value:expr),* $(,)? }, $body:block) => {
+        $ctx.scoped(
+            vec![$((::std::string::String::from($key), ::serde_value::to_value(&$value).unwrap_or(::serde_value::Value::Unit))),*],
+            |$ctx| $body,
+        )
+    };
+}
@@ -0,0 +1,134 @@
+//! Typed access to nested sections of a context.
+//!
+//! A section is a map stored under a top-level key, such as `http` or `db`. [`Context::section`]
+//! deserializes it into a struct and [`Context::set_section`] writes a struct back.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct HttpInfo {
+//!     method: String,
+//!     status: u16,
+//! }
+//!
+//! let mut ctx = Context::new();
+//! ctx.set_section("http", &HttpInfo { method: "GET".to_string(), status: 200 }).unwrap();
+//!
+//! let http: Option<HttpInfo> = ctx.section("http").unwrap();
+//! assert_eq!(http.unwrap().status, 200);
+//! ```
+//!
+//! [`Context::section_with_ttl`] opens a time-boxed section, which stops appearing in dumps and
+//! serializations once its window is over, so that verbose debugging entries can be enabled
+//! temporarily without a cleanup afterwards:
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//! use std::time::Duration;
+//!
+//! let mut ctx = Context::new();
+//! ctx.section_with_ttl("burst_debug", Duration::ZERO).unwrap();
+//! ctx.insert_path("burst_debug.sql", Value::String("SELECT 1".to_string())).unwrap();
+//! assert!(ctx.get("burst_debug").is_some());
+//! assert!(!ctx.inner().contains_key("burst_debug"));
+//! ```
+use crate::{Context, Contextualize, TypeMismatch, UnExpectedError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+impl Context {
+    /// Deserializes the value stored under `key` into `T`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Option<T>>` which is:
+    /// * `Ok(Some(section))` if the key exists and matches `T`
+    /// * `Ok(None)` if the key does not exist
+    /// * `Err(e)` containing a [`TypeMismatch`] error if the value does not match `T`
+    pub fn section<T: DeserializeOwned>(&self, key: &str) -> cdumay_core::Result<Option<T>> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(value) => T::deserialize(value.clone()).map(Some).map_err(|err| {
+                TypeMismatch::new()
+                    .with_message(format!("Failed to load context section '{}': {}", key, err))
+                    .with_details(self.error_details())
+                    .into()
+            }),
+        }
+    }
+
+    /// Serializes `section` and stores it under `key`, replacing any previous value.
+    pub fn set_section<T: Serialize>(&mut self, key: &str, section: &T) -> cdumay_core::Result<()> {
+        let value = serde_value::to_value(section).map_err(|err| {
+            UnExpectedError::new()
+                .with_message(format!("Failed to serialize context section '{}': {}", key, err))
+                .with_details(self.error_details())
+        })?;
+        self.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    /// Opens a section under `key` which is left out of dumps and serializations once `ttl` has
+    /// elapsed, see [`Contextualize::inner`].
+    ///
+    /// An empty map is stored under `key` if it is missing. Calling this method again restarts the
+    /// window, writing to the section does not. The section can still be read with
+    /// [`Contextualize::get`] after its window, until it is removed, either explicitly or with
+    /// [`Context::prune_expired_sections`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the section is open
+    /// * `Err(e)` containing a [`TypeMismatch`] error if `key` holds a value other than a map
+    pub fn section_with_ttl(&mut self, key: &str, ttl: Duration) -> cdumay_core::Result<()> {
+        match self.get(key) {
+            None => self.insert(key.to_string(), Value::Map(BTreeMap::new())),
+            Some(Value::Map(_)) => {}
+            Some(_) => return Err(self.mismatch(format!("Context key '{}' does not hold a section", key))),
+        }
+        let key = self.resolve_key(key).into_owned();
+        match Instant::now().checked_add(ttl) {
+            Some(expiry) => self.expiries.insert(key, expiry),
+            None => self.expiries.remove(&key),
+        };
+        Ok(())
+    }
+
+    /// Returns the time left before the section under `key` expires, `Duration::ZERO` once
+    /// expired, or `None` if the section is not time-boxed.
+    pub fn section_expires_in(&self, key: &str) -> Option<Duration> {
+        let expiry = self.expiries.get(self.resolve_key(key).as_ref())?;
+        Some(expiry.saturating_duration_since(Instant::now()))
+    }
+
+    /// Removes the expired sections, returning their keys.
+    pub fn prune_expired_sections(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        let expired: Vec<String> = self
+            .expiries
+            .iter()
+            .filter(|(_, expiry)| **expiry <= now)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for k in &expired {
+            self.remove(k);
+            self.expiries.remove(k);
+        }
+        expired
+    }
+
+    /// Removes the expired sections from `data`.
+    pub(crate) fn drop_expired_sections(&self, data: &mut BTreeMap<String, Value>) {
+        if !self.expiries.is_empty() {
+            let now = Instant::now();
+            data.retain(|k, _| self.expiries.get(k).is_none_or(|expiry| *expiry > now));
+        }
+    }
+}
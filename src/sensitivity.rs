@@ -0,0 +1,120 @@
+//! Sensitivity levels and filtered views of contexts.
+//!
+//! Each key of a [`Context`] carries a [`Sensitivity`] level, set with
+//! [`Context::set_sensitivity`]. Keys which were never tagged are [`Sensitivity::Internal`], so
+//! that nothing reaches customer-visible output unless explicitly marked as public.
+//!
+//! [`Context::view`] returns a read-only [`ContextView`] exposing only the entries at or below a
+//! given level. A single context can then feed both customer-visible errors and internal
+//! diagnostics:
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextDump, Contextualize, Sensitivity};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("db_host".to_string(), Value::String("10.0.0.12".to_string()));
+//! ctx.set_sensitivity("request_id", Sensitivity::Public);
+//!
+//! let public = ctx.view(Sensitivity::Public);
+//! assert!(public.get("request_id").is_some());
+//! assert!(public.get("db_host").is_none());
+//! assert_eq!(ctx.view(Sensitivity::Internal).dump().len(), 2);
+//! ```
+use crate::{Context, ContextDump, Contextualize};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How sensitive the value stored under a key is, from least to most sensitive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sensitivity {
+    /// May be shown to anyone, including customers.
+    Public,
+    /// Reserved to internal diagnostics (default).
+    #[default]
+    Internal,
+    /// Business or personal data, restricted to the people handling the incident.
+    Confidential,
+    /// Credentials and other secrets, never exported.
+    Secret,
+}
+
+impl Context {
+    /// Sets the sensitivity level of `k`, whether or not a value is stored under it yet.
+    pub fn set_sensitivity(&mut self, k: &str, sensitivity: Sensitivity) {
+        let k = self.resolve_key(k).into_owned();
+        self.sensitivity.insert(k, sensitivity);
+    }
+
+    /// Returns the sensitivity level of `k`.
+    pub fn sensitivity(&self, k: &str) -> Sensitivity {
+        self.sensitivity.get(self.resolve_key(k).as_ref()).copied().unwrap_or_default()
+    }
+
+    /// Returns a read-only view of the entries at or below `level`.
+    pub fn view(&self, level: Sensitivity) -> ContextView<'_> {
+        ContextView { ctx: self, level }
+    }
+}
+
+/// A read-only view of a [`Context`] filtered by sensitivity level, see [`Context::view`].
+///
+/// The view serializes like a context, so it can be handed to any serde serializer, and
+/// [`ContextView::to_context`] gives access to the format helpers of [`Contextualize`].
+#[derive(Debug, Clone, Copy)]
+pub struct ContextView<'a> {
+    ctx: &'a Context,
+    level: Sensitivity,
+}
+
+impl<'a> ContextView<'a> {
+    /// Returns the level of this view.
+    pub fn level(&self) -> Sensitivity {
+        self.level
+    }
+
+    /// Returns `true` if the value stored under `k` is visible in this view.
+    pub fn is_visible(&self, k: &str) -> bool {
+        self.ctx.sensitivity(k) <= self.level
+    }
+
+    /// Retrieves the value stored under `k` if it is visible in this view.
+    pub fn get(&self, k: &str) -> Option<&'a serde_value::Value> {
+        match self.is_visible(k) {
+            true => self.ctx.get(k),
+            false => None,
+        }
+    }
+
+    /// Returns a new context holding only the visible entries, with their sensitivity levels.
+    pub fn to_context(&self) -> Context {
+        let mut ctx = Context::with_key_policy(self.ctx.key_policy());
+        ctx.extend(self.dump());
+        ctx.sensitivity = self
+            .ctx
+            .sensitivity
+            .iter()
+            .filter(|(_, level)| **level <= self.level)
+            .map(|(k, l)| (k.clone(), *l))
+            .collect();
+        ctx
+    }
+}
+
+impl ContextDump for ContextView<'_> {
+    fn dump(&self) -> BTreeMap<String, serde_value::Value> {
+        self.ctx.inner().into_iter().filter(|(k, _)| self.is_visible(k)).collect()
+    }
+}
+
+/// Serializes the visible entries, in the same shape as a [`Context`].
+impl Serialize for ContextView<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Context", 1)?;
+        state.serialize_field("data", &self.dump())?;
+        state.end()
+    }
+}
@@ -0,0 +1,103 @@
+//! [Sentry](https://docs.rs/sentry) integration.
+//!
+//! [`Context::attach_to_scope`] attaches a context to a `sentry::Scope` as a structured context
+//! named after [`SENTRY_CONTEXT_KEY`], so that every event captured within the scope carries it.
+//! [`sentry_event`] turns an error, such as an [`UnExpectedError`](crate::UnExpectedError), into a
+//! Sentry event: its class becomes the exception type, its message the exception value and its
+//! details, which hold the context attached with `with_context`, the extra data of the event.
+//!
+//! Only the entries visible at [`Sensitivity::Internal`] are attached to scopes, so confidential
+//! and secret values never leave the process. Values are converted into JSON values, map keys
+//! being rendered as strings.
+//!
+//! ```rust
+//! use cdumay_context::{sentry_event, Context, Contextualize, UnExpectedError};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("job".to_string(), Value::String("export".to_string()));
+//!
+//! let mut scope = sentry_core::Scope::default();
+//! ctx.attach_to_scope(&mut scope);
+//!
+//! let event = sentry_event(UnExpectedError::new().with_message("export failed".to_string()).with_context(&ctx));
+//! assert_eq!(event.exception[0].value.as_deref(), Some("export failed"));
+//! assert_eq!(event.extra["job"], "export");
+//! ```
+use crate::mapkey::stringify;
+use crate::{Context, ContextDump, Sensitivity};
+use sentry_core::protocol::{self, Event, Exception, Level, Map};
+use sentry_core::Scope;
+use serde_value::Value;
+
+/// Name of the Sentry context [`Context::attach_to_scope`] sets.
+pub const SENTRY_CONTEXT_KEY: &str = "context";
+
+impl Context {
+    /// Converts the entries visible at [`Sensitivity::Internal`] into a Sentry context.
+    ///
+    /// This method is only available when the "sentry" feature is enabled.
+    pub fn to_sentry_context(&self) -> protocol::Context {
+        protocol::Context::Other(
+            self.view(Sensitivity::Internal)
+                .dump()
+                .iter()
+                .map(|(k, v)| (k.clone(), to_json(v)))
+                .collect(),
+        )
+    }
+
+    /// Sets the context on `scope` under [`SENTRY_CONTEXT_KEY`], see [`Context::to_sentry_context`].
+    ///
+    /// This method is only available when the "sentry" feature is enabled.
+    pub fn attach_to_scope(&self, scope: &mut Scope) {
+        scope.set_context(SENTRY_CONTEXT_KEY, self.to_sentry_context());
+    }
+}
+
+/// Converts an error into a Sentry event at the error level.
+///
+/// The exception of the event holds the class and the message of the error, the code of the error
+/// is set as the `code` tag and its details become the extra data of the event.
+///
+/// This function is only available when the "sentry" feature is enabled.
+pub fn sentry_event<E: Into<cdumay_core::Error>>(error: E) -> Event<'static> {
+    let error = error.into();
+    let exception = Exception {
+        ty: error.class().to_string(),
+        value: Some(error.message().to_string()),
+        ..Default::default()
+    };
+    Event {
+        level: Level::Error,
+        message: Some(error.message().to_string()),
+        exception: vec![exception].into(),
+        tags: Map::from([("code".to_string(), error.code().to_string())]),
+        extra: error.details().iter().map(|(k, v)| (k.clone(), to_json(v))).collect(),
+        ..Default::default()
+    }
+}
+
+/// Converts a `serde_value::Value` into a JSON value.
+fn to_json(value: &Value) -> protocol::Value {
+    match value {
+        Value::Bool(b) => protocol::Value::from(*b),
+        Value::U8(n) => protocol::Value::from(*n),
+        Value::U16(n) => protocol::Value::from(*n),
+        Value::U32(n) => protocol::Value::from(*n),
+        Value::U64(n) => protocol::Value::from(*n),
+        Value::I8(n) => protocol::Value::from(*n),
+        Value::I16(n) => protocol::Value::from(*n),
+        Value::I32(n) => protocol::Value::from(*n),
+        Value::I64(n) => protocol::Value::from(*n),
+        Value::F32(n) => protocol::Value::from(*n),
+        Value::F64(n) => protocol::Value::from(*n),
+        Value::Char(c) => protocol::Value::from(c.to_string()),
+        Value::String(s) => protocol::Value::from(s.as_str()),
+        Value::Unit | Value::Option(None) => protocol::Value::Null,
+        Value::Option(Some(inner)) | Value::Newtype(inner) => to_json(inner),
+        Value::Seq(items) => protocol::Value::Array(items.iter().map(to_json).collect()),
+        Value::Map(map) => protocol::Value::Object(map.iter().map(|(k, v)| (stringify(k), to_json(v))).collect()),
+        Value::Bytes(bytes) => protocol::Value::from(bytes.as_slice()),
+    }
+}
@@ -0,0 +1,92 @@
+//! List and set operations on sequence values.
+//!
+//! [`Context::push`] appends to a `Value::Seq`, creating it if absent, and [`Context::add_to_set`]
+//! appends only values which are not present yet. As with counters, the same methods on a
+//! [`SharedContext`](crate::SharedContext) run as a single atomic update.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.push("warnings", Value::String("disk almost full".to_string())).unwrap();
+//! ctx.add_to_set("features", Value::String("gzip".to_string())).unwrap();
+//! ctx.add_to_set("features", Value::String("gzip".to_string())).unwrap();
+//!
+//! assert_eq!(ctx.at("warnings").as_seq().map(|s| s.len()), Some(1));
+//! assert_eq!(ctx.at("features").as_seq().map(|s| s.len()), Some(1));
+//! ```
+use crate::{Context, Contextualize, SharedContext};
+use serde_value::Value;
+
+impl Context {
+    /// Appends `v` to the sequence stored under `k`, creating it if the key is missing.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<usize>` which is:
+    /// * `Ok(len)` containing the length of the sequence
+    /// * `Err(e)` containing a [`TypeMismatch`](crate::TypeMismatch) error if the stored value is
+    ///   not a sequence, or a [`ProtectedKey`](crate::ProtectedKey) error if the key is protected
+    pub fn push(&mut self, k: &str, v: Value) -> cdumay_core::Result<usize> {
+        self.push_capped(k, v, usize::MAX)
+    }
+
+    /// Appends `v` to the sequence stored under `k`, dropping the oldest items to keep at most
+    /// `max_len` of them.
+    ///
+    /// See [`Context::push`] for the returned value.
+    pub fn push_capped(&mut self, k: &str, v: Value, max_len: usize) -> cdumay_core::Result<usize> {
+        let mut items = self.sequence(k)?;
+        items.push(v);
+        if items.len() > max_len {
+            items.drain(..items.len() - max_len);
+        }
+        let len = items.len();
+        self.try_insert(k.to_string(), Value::Seq(items))?;
+        Ok(len)
+    }
+
+    /// Appends `v` to the sequence stored under `k` unless it is already present.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<bool>` which is:
+    /// * `Ok(true)` if the value was added, `Ok(false)` if it was already present
+    /// * `Err(e)` if the stored value is not a sequence or the key is protected, see
+    ///   [`Context::push`]
+    pub fn add_to_set(&mut self, k: &str, v: Value) -> cdumay_core::Result<bool> {
+        let mut items = self.sequence(k)?;
+        if items.contains(&v) {
+            return Ok(false);
+        }
+        items.push(v);
+        self.try_insert(k.to_string(), Value::Seq(items))?;
+        Ok(true)
+    }
+
+    fn sequence(&self, k: &str) -> cdumay_core::Result<Vec<Value>> {
+        match self.get(k) {
+            None => Ok(Vec::new()),
+            Some(Value::Seq(items)) => Ok(items.clone()),
+            Some(_) => Err(self.mismatch(format!("Context key '{}' does not hold a sequence", k))),
+        }
+    }
+}
+
+impl SharedContext {
+    /// Atomically appends `v` to the sequence stored under `k`, see [`Context::push`].
+    pub fn push(&self, k: &str, v: Value) -> cdumay_core::Result<usize> {
+        self.update(|ctx| ctx.push(k, v))
+    }
+
+    /// Atomically appends `v` to a bounded sequence, see [`Context::push_capped`].
+    pub fn push_capped(&self, k: &str, v: Value, max_len: usize) -> cdumay_core::Result<usize> {
+        self.update(|ctx| ctx.push_capped(k, v, max_len))
+    }
+
+    /// Atomically adds `v` to the set stored under `k`, see [`Context::add_to_set`].
+    pub fn add_to_set(&self, k: &str, v: Value) -> cdumay_core::Result<bool> {
+        self.update(|ctx| ctx.add_to_set(k, v))
+    }
+}
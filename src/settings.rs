@@ -0,0 +1,393 @@
+//! Central configuration of contexts.
+//!
+//! A [`ContextConfig`] gathers the knobs controlling how contexts store and dump their data: key
+//! policy, key order, numeric policy, map key policy, null policy, redacted keys, maximum dump
+//! size, tagged value preservation and id generator. It is resolved from three scopes, each overriding the settings of
+//! the previous one:
+//!
+//! 1. process-wide, with [`ContextConfig::set_global`], so that operators configure the behavior
+//!    once at startup;
+//! 2. per context, with [`Context::with_config`] or [`Context::set_config`];
+//! 3. per call, with [`Context::to_json_with`], [`Context::to_toml_with`],
+//!    [`Context::to_yaml_with`], [`Context::from_toml_with`] and [`Context::from_yaml_with`].
+//!
+//! Settings left unset in a scope fall back to the previous one, and redacted key patterns add up.
+//! The key policy of a context is fixed at its creation, by [`Context::new`] from the global
+//! configuration or by [`Context::with_config`]. So is its initial key order, so that setting
+//! [`KeyOrder::Insertion`] globally keeps the keys of every new context in the order they were
+//! recorded in JSON, TOML and YAML output, see [`Context::set_key_order`]. The "ordered" feature
+//! makes it the default and stores the entries in insertion order.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{Context, ContextConfig, Contextualize, NullPolicy};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::with_config(ContextConfig::new().redact("*.password"));
+//! ctx.insert("db".to_string(), Value::Map([(Value::String("password".to_string()), Value::String("hunter2".to_string()))].into()));
+//! ctx.insert("parent_id".to_string(), Value::Option(None));
+//! assert_eq!(ctx.to_json(false).unwrap(), r#"{"db":{"password":"[redacted]"},"parent_id":null}"#);
+//!
+//! let config = ContextConfig::new().with_null_policy(NullPolicy::Omit);
+//! assert_eq!(ctx.to_json_with(false, &config).unwrap(), r#"{"db":{"password":"[redacted]"}}"#);
+//! # }
+//! ```
+use crate::id::SharedIdGenerator;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+use crate::order::OrderedEntries;
+use crate::snapshot::glob_match;
+use crate::{Context, IdGenerator, KeyOrder, KeyPolicy, MapKeyPolicy, NumericPolicy, ProcessCounter};
+use serde_value::Value;
+use std::sync::{Arc, RwLock};
+
+/// Placeholder written instead of redacted values.
+pub const REDACTED: &str = "[redacted]";
+
+static GLOBAL_CONFIG: RwLock<ContextConfig> = RwLock::new(ContextConfig::new());
+
+/// What to do with null values in dumps.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NullPolicy {
+    /// Write null values (default).
+    #[default]
+    Keep,
+    /// Leave out the entries of the context and of nested maps holding a null value. Nulls in
+    /// sequences are kept, so that positions are preserved.
+    Omit,
+}
+
+/// Configuration of contexts, see the [module documentation](self).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ContextConfig {
+    pub(crate) key_policy: Option<KeyPolicy>,
+    pub(crate) key_order: Option<KeyOrder>,
+    pub(crate) numeric_policy: Option<NumericPolicy>,
+    pub(crate) map_key_policy: Option<MapKeyPolicy>,
+    pub(crate) null_policy: Option<NullPolicy>,
+    pub(crate) redacted: Vec<String>,
+    pub(crate) max_size: Option<usize>,
+    pub(crate) tagged_values: Option<bool>,
+    pub(crate) id_generator: Option<SharedIdGenerator>,
+}
+
+impl ContextConfig {
+    /// Creates a configuration leaving every setting unset.
+    pub const fn new() -> Self {
+        Self {
+            key_policy: None,
+            key_order: None,
+            numeric_policy: None,
+            map_key_policy: None,
+            null_policy: None,
+            redacted: Vec::new(),
+            max_size: None,
+            tagged_values: None,
+            id_generator: None,
+        }
+    }
+
+    /// Returns the process-wide configuration.
+    pub fn global() -> ContextConfig {
+        GLOBAL_CONFIG.read().map(|config| config.clone()).unwrap_or_default()
+    }
+
+    /// Replaces the process-wide configuration.
+    pub fn set_global(config: ContextConfig) {
+        if let Ok(mut global) = GLOBAL_CONFIG.write() {
+            *global = config;
+        }
+    }
+
+    /// Sets how keys are compared by contexts created with this configuration.
+    pub fn with_key_policy(mut self, policy: KeyPolicy) -> Self {
+        self.key_policy = Some(policy);
+        self
+    }
+
+    /// Sets the initial key order of contexts created with this configuration, see
+    /// [`Context::set_key_order`].
+    pub fn with_key_order(mut self, order: KeyOrder) -> Self {
+        self.key_order = Some(order);
+        self
+    }
+
+    /// Sets how numbers are written in dumps.
+    pub fn with_numeric_policy(mut self, policy: NumericPolicy) -> Self {
+        self.numeric_policy = Some(policy);
+        self
+    }
+
+    /// Sets how non-string map keys are written in dumps.
+    pub fn with_map_key_policy(mut self, policy: MapKeyPolicy) -> Self {
+        self.map_key_policy = Some(policy);
+        self
+    }
+
+    /// Sets what happens to null values in dumps.
+    pub fn with_null_policy(mut self, policy: NullPolicy) -> Self {
+        self.null_policy = Some(policy);
+        self
+    }
+
+    /// Writes [`REDACTED`] instead of every value whose dotted path matches `pattern` in dumps.
+    ///
+    /// The pattern may contain `*` wildcards matching any sequence of characters, for example
+    /// `*.password` or `auth.*`.
+    pub fn redact(mut self, pattern: &str) -> Self {
+        self.redacted.push(pattern.to_string());
+        self
+    }
+
+    /// Limits the size of dumps, and of headers when the "http" feature is enabled, to `bytes`,
+    /// dropping their lowest-priority entries as [`Context::dump_within`] does.
+    pub fn with_max_size(mut self, bytes: usize) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Keeps YAML tags and TOML datetimes as tagged values when loading, and writes them back in
+    /// dumps to the same format, see [`TAG_KEY`](crate::TAG_KEY).
+    pub fn with_tagged_values(mut self, preserve: bool) -> Self {
+        self.tagged_values = Some(preserve);
+        self
+    }
+
+    /// Sets the generator of context ids, see [`Context::id`] and [`Context::generate_id`].
+    pub fn with_id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.id_generator = Some(SharedIdGenerator(Arc::new(generator)));
+        self
+    }
+
+    /// Returns the key policy, [`KeyPolicy::CaseSensitive`] if unset.
+    pub fn key_policy(&self) -> KeyPolicy {
+        self.key_policy.unwrap_or_default()
+    }
+
+    /// Returns the key order, [`KeyOrder::Alphabetical`] if unset, [`KeyOrder::Insertion`] with
+    /// the "ordered" feature.
+    pub fn key_order(&self) -> KeyOrder {
+        self.key_order.clone().unwrap_or_default()
+    }
+
+    /// Returns the numeric policy, [`NumericPolicy::new`] if unset.
+    pub fn numeric_policy(&self) -> NumericPolicy {
+        self.numeric_policy.unwrap_or_default()
+    }
+
+    /// Returns the map key policy, [`MapKeyPolicy::Stringify`] if unset.
+    pub fn map_key_policy(&self) -> MapKeyPolicy {
+        self.map_key_policy.unwrap_or_default()
+    }
+
+    /// Returns the null policy, [`NullPolicy::Keep`] if unset.
+    pub fn null_policy(&self) -> NullPolicy {
+        self.null_policy.unwrap_or_default()
+    }
+
+    /// Returns the redacted key patterns.
+    pub fn redacted_keys(&self) -> &[String] {
+        &self.redacted
+    }
+
+    /// Returns the maximum size of dumps, if any.
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Returns `true` if tagged values are preserved, `false` if unset.
+    pub fn tagged_values(&self) -> bool {
+        self.tagged_values.unwrap_or_default()
+    }
+
+    /// Returns the generator of context ids, [`ProcessCounter`] if unset.
+    pub fn id_generator(&self) -> Arc<dyn IdGenerator> {
+        match &self.id_generator {
+            Some(generator) => generator.0.clone(),
+            None => Arc::new(ProcessCounter),
+        }
+    }
+
+    /// Returns this configuration with the settings of `over` applied on top.
+    pub fn merge(&self, over: &ContextConfig) -> ContextConfig {
+        ContextConfig {
+            key_policy: over.key_policy.or(self.key_policy),
+            key_order: over.key_order.clone().or_else(|| self.key_order.clone()),
+            numeric_policy: over.numeric_policy.or(self.numeric_policy),
+            map_key_policy: over.map_key_policy.or(self.map_key_policy),
+            null_policy: over.null_policy.or(self.null_policy),
+            redacted: self.redacted.iter().chain(&over.redacted).cloned().collect(),
+            max_size: over.max_size.or(self.max_size),
+            tagged_values: over.tagged_values.or(self.tagged_values),
+            id_generator: over.id_generator.clone().or_else(|| self.id_generator.clone()),
+        }
+    }
+
+    /// Applies the redaction and null policy to `value`, stored at `path`, returning `None` if the
+    /// entry is left out.
+    pub(crate) fn apply(&self, path: &str, value: Value) -> Option<Value> {
+        if self.redacted.iter().any(|pattern| glob_match(pattern, path)) {
+            return Some(Value::String(REDACTED.to_string()));
+        }
+        let omit = self.null_policy() == NullPolicy::Omit;
+        match value {
+            Value::Unit | Value::Option(None) if omit => None,
+            Value::Option(Some(inner)) => self.apply(path, *inner).map(|inner| Value::Option(Some(Box::new(inner)))),
+            Value::Newtype(inner) => self.apply(path, *inner).map(|inner| Value::Newtype(Box::new(inner))),
+            Value::Seq(items) => Some(Value::Seq(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| self.apply(&format!("{}.{}", path, index), item).unwrap_or(Value::Unit))
+                    .collect(),
+            )),
+            Value::Map(map) => Some(Value::Map(
+                map.into_iter()
+                    .filter_map(|(k, v)| {
+                        let child = match &k {
+                            Value::String(k) => format!("{}.{}", path, k),
+                            other => format!("{}.{:?}", path, other),
+                        };
+                        self.apply(&child, v).map(|v| (k, v))
+                    })
+                    .collect(),
+            )),
+            value => Some(value),
+        }
+    }
+}
+
+impl Context {
+    /// Creates an empty context with the configuration `config`, on top of the global one.
+    pub fn with_config(config: ContextConfig) -> Self {
+        let effective = ContextConfig::global().merge(&config);
+        let mut ctx = Context::with_key_policy(effective.key_policy());
+        ctx.key_order = effective.key_order();
+        ctx.config = config;
+        ctx
+    }
+
+    /// Replaces the configuration of the context, the key policy and key order excepted.
+    pub fn set_config(&mut self, config: ContextConfig) {
+        self.config = config;
+    }
+
+    /// Returns the configuration of the context, without the global settings.
+    pub fn config(&self) -> &ContextConfig {
+        &self.config
+    }
+
+    /// Returns the configuration in effect: the global one, overridden by the one of the context
+    /// and by `call`, if any.
+    pub fn effective_config(&self, call: Option<&ContextConfig>) -> ContextConfig {
+        let config = ContextConfig::global().merge(&self.config);
+        match call {
+            Some(call) => config.merge(call),
+            None => config,
+        }
+    }
+
+    /// Drops the lowest-priority entries of `entries` if their size, as measured by
+    /// `encoded_len`, exceeds the maximum size of `config`.
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    pub(crate) fn fit<F>(&self, entries: OrderedEntries, config: &ContextConfig, encoded_len: F) -> OrderedEntries
+    where
+        F: Fn(&OrderedEntries) -> usize,
+    {
+        match config.max_size {
+            Some(max_len) => OrderedEntries(self.trim_entries(entries.0, max_len, |entries| encoded_len(&OrderedEntries(entries.to_vec())))),
+            None => entries,
+        }
+    }
+
+    /// Serializes the context to a JSON string, with `config` applied on top of the configuration
+    /// in effect.
+    #[cfg(feature = "json")]
+    pub fn to_json_with(&self, pretty: bool, config: &ContextConfig) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        let config = self.effective_config(Some(config));
+        let encode = |entries: &OrderedEntries| match pretty {
+            true => serde_json::to_string_pretty(entries),
+            false => serde_json::to_string(entries),
+        };
+        crate::metrics::observe_dump(crate::Format::Json, || {
+            let ordered = self.ordered_for(crate::Format::Json, &config)?;
+            let ordered = self.fit(ordered, &config, |entries| encode(entries).map_or(usize::MAX, |dump| dump.len()));
+            encode(&ordered).map_err(|err| {
+                cdumay_json::JsonErrorConverter::convert_error(
+                    &err,
+                    Some("Failed to dump context".to_string()),
+                    crate::Contextualize::error_details(self),
+                )
+            })
+        })
+    }
+
+    /// Serializes the context to a TOML string, with `config` applied on top of the configuration
+    /// in effect.
+    #[cfg(feature = "toml")]
+    pub fn to_toml_with(&self, pretty: bool, config: &ContextConfig) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        let config = self.effective_config(Some(config));
+        let format = config.tagged_values().then_some(crate::Format::Toml);
+        let encode = |entries: &OrderedEntries| {
+            let restored = crate::tagged::Restored { entries: &entries.0, format };
+            match pretty {
+                true => toml::to_string_pretty(&restored),
+                false => toml::to_string(&restored),
+            }
+        };
+        crate::metrics::observe_dump(crate::Format::Toml, || {
+            let ordered = self.ordered_for(crate::Format::Toml, &config)?;
+            let ordered = self.fit(ordered, &config, |entries| encode(entries).map_or(usize::MAX, |dump| dump.len()));
+            encode(&ordered).map_err(|err| {
+                cdumay_toml::TomlSerializeErrorConverter::convert_error(
+                    &err,
+                    Some("Failed to dump context".to_string()),
+                    crate::Contextualize::error_details(self),
+                )
+            })
+        })
+    }
+
+    /// Serializes the context to a YAML string, with `config` applied on top of the configuration
+    /// in effect.
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml_with(&self, config: &ContextConfig) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        let config = self.effective_config(Some(config));
+        let format = config.tagged_values().then_some(crate::Format::Yaml);
+        let encode = |entries: &OrderedEntries| serde_yaml::to_string(&crate::tagged::Restored { entries: &entries.0, format });
+        crate::metrics::observe_dump(crate::Format::Yaml, || {
+            let ordered = self.ordered_for(crate::Format::Yaml, &config)?;
+            let ordered = self.fit(ordered, &config, |entries| encode(entries).map_or(usize::MAX, |dump| dump.len()));
+            encode(&ordered).map_err(|err| {
+                cdumay_yaml::YamlErrorConverter::convert_error(
+                    &err,
+                    Some("Failed to dump context".to_string()),
+                    crate::Contextualize::error_details(self),
+                )
+            })
+        })
+    }
+
+    /// Creates a new context from a TOML string, with `config` applied on top of the global
+    /// configuration. The context keeps `config`, see [`Context::with_config`].
+    #[cfg(feature = "toml")]
+    pub fn from_toml_with(toml: &str, config: &ContextConfig) -> cdumay_core::Result<Context> {
+        let effective = ContextConfig::global().merge(config);
+        let mut ctx = Context::with_config(config.clone());
+        crate::Contextualize::extend_ordered(&mut ctx, crate::tagged::load_toml(toml, effective.tagged_values())?);
+        Ok(ctx)
+    }
+
+    /// Creates a new context from a YAML string, with `config` applied on top of the global
+    /// configuration. The context keeps `config`, see [`Context::with_config`].
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_with(yaml: &str, config: &ContextConfig) -> cdumay_core::Result<Context> {
+        let effective = ContextConfig::global().merge(config);
+        let mut ctx = Context::with_config(config.clone());
+        crate::Contextualize::extend_ordered(&mut ctx, crate::yaml::load(yaml, effective.tagged_values())?);
+        Ok(ctx)
+    }
+}
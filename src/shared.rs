@@ -0,0 +1,133 @@
+//! Thread-safe shared contexts.
+//!
+//! [`SharedContext`] is a cheaply cloneable handle on a [`Context`] shared between threads. Every
+//! write goes through [`SharedContext::update`], which runs atomically with respect to other
+//! writers. Two modes are available:
+//!
+//! * [`SharedContext::new`] protects the context with a `RwLock`;
+//! * [`SharedContext::lock_free`] (feature "arc-swap") lets readers load an `Arc` snapshot
+//!   without taking any lock, while writers copy the context, modify the copy and swap it in.
+//!   This suits hot read paths, such as every log line reading the context, at the cost of a
+//!   clone per write.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SharedContext};
+//! use serde_value::Value;
+//!
+//! let shared = SharedContext::new(Context::new());
+//! let writer = shared.clone();
+//! std::thread::spawn(move || writer.insert("step".to_string(), Value::U8(1))).join().unwrap();
+//!
+//! assert_eq!(shared.get("step"), Some(Value::U8(1)));
+//! assert_eq!(shared.snapshot().get("step"), Some(&Value::U8(1)));
+//! ```
+use crate::{Context, ContextDump, Contextualize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Allocated once behind an `Arc`, boxing the locked context would only add an indirection.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum Backend {
+    Locked(RwLock<Context>),
+    #[cfg(feature = "arc-swap")]
+    Swap {
+        current: arc_swap::ArcSwap<Context>,
+        writer: std::sync::Mutex<()>,
+    },
+}
+
+/// A context shared between threads.
+#[derive(Debug, Clone)]
+pub struct SharedContext {
+    backend: Arc<Backend>,
+}
+
+impl Default for SharedContext {
+    fn default() -> Self {
+        Self::new(Context::new())
+    }
+}
+
+impl SharedContext {
+    /// Shares `ctx` behind a `RwLock`.
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            backend: Arc::new(Backend::Locked(RwLock::new(ctx))),
+        }
+    }
+
+    /// Shares `ctx` so that reads never take a lock, writes copy and swap the whole context.
+    ///
+    /// This method is only available when the "arc-swap" feature is enabled.
+    #[cfg(feature = "arc-swap")]
+    pub fn lock_free(ctx: Context) -> Self {
+        Self {
+            backend: Arc::new(Backend::Swap {
+                current: arc_swap::ArcSwap::from_pointee(ctx),
+                writer: std::sync::Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Returns `true` if reads do not take a lock.
+    pub fn is_lock_free(&self) -> bool {
+        !matches!(*self.backend, Backend::Locked(_))
+    }
+
+    /// Returns an immutable snapshot of the context.
+    ///
+    /// In lock-free mode this is a reference count increment, otherwise the context is cloned.
+    pub fn snapshot(&self) -> Arc<Context> {
+        match &*self.backend {
+            Backend::Locked(lock) => Arc::new(lock.read().unwrap_or_else(|err| err.into_inner()).clone()),
+            #[cfg(feature = "arc-swap")]
+            Backend::Swap { current, .. } => current.load_full(),
+        }
+    }
+
+    /// Runs `f` with a shared reference to the context.
+    pub fn read<R>(&self, f: impl FnOnce(&Context) -> R) -> R {
+        match &*self.backend {
+            Backend::Locked(lock) => f(&lock.read().unwrap_or_else(|err| err.into_inner())),
+            #[cfg(feature = "arc-swap")]
+            Backend::Swap { current, .. } => f(&current.load()),
+        }
+    }
+
+    /// Runs `f` with a mutable reference to the context, atomically with respect to other writers.
+    pub fn update<R>(&self, f: impl FnOnce(&mut Context) -> R) -> R {
+        match &*self.backend {
+            Backend::Locked(lock) => f(&mut lock.write().unwrap_or_else(|err| err.into_inner())),
+            #[cfg(feature = "arc-swap")]
+            Backend::Swap { current, writer } => {
+                let _guard = writer.lock().unwrap_or_else(|err| err.into_inner());
+                let mut ctx = Context::clone(&current.load());
+                let result = f(&mut ctx);
+                current.store(Arc::new(ctx));
+                result
+            }
+        }
+    }
+
+    /// Inserts a key-value pair into the shared context.
+    pub fn insert(&self, k: String, v: serde_value::Value) {
+        self.update(|ctx| ctx.insert(k, v))
+    }
+
+    /// Extends the shared context with the given key-value pairs.
+    pub fn extend(&self, data: BTreeMap<String, serde_value::Value>) {
+        self.update(|ctx| ctx.extend(data))
+    }
+
+    /// Returns a clone of the value stored under `k`.
+    pub fn get(&self, k: &str) -> Option<serde_value::Value> {
+        self.read(|ctx| ctx.get(k).cloned())
+    }
+}
+
+impl ContextDump for SharedContext {
+    fn dump(&self) -> BTreeMap<String, serde_value::Value> {
+        self.read(|ctx| ctx.dump())
+    }
+}
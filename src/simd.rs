@@ -0,0 +1,30 @@
+//! SIMD-accelerated JSON parsing.
+//!
+//! With the "simd-json" feature, [`Contextualize::from_json`](crate::Contextualize::from_json)
+//! parses payloads of at least [`SIMD_JSON_THRESHOLD`] bytes with `simd-json`, which is
+//! significantly faster than `serde_json` on large documents. Smaller payloads do not amortize
+//! the copy `simd-json` parses in place, and go through `serde_json`.
+//!
+//! Semantics are unchanged: values are decoded to the same variants, and when `simd-json`
+//! rejects a payload, it is parsed again with `serde_json`, so that errors are reported and
+//! mapped exactly as without the feature.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SIMD_JSON_THRESHOLD};
+//!
+//! let json = format!(r#"{{"payload":"{}"}}"#, "x".repeat(SIMD_JSON_THRESHOLD));
+//! let ctx = Context::from_json(&json).unwrap();
+//! assert_eq!(ctx.at("payload").as_str().map(str::len), Some(SIMD_JSON_THRESHOLD));
+//! ```
+
+/// Size from which JSON payloads are parsed with `simd-json`, in bytes.
+pub const SIMD_JSON_THRESHOLD: usize = 64 * 1024;
+
+/// Parses a JSON object, with `simd-json` if `json` is large enough.
+pub(crate) fn from_str(json: &str) -> serde_json::Result<crate::backend::Loaded<serde_json::Value>> {
+    if json.len() < SIMD_JSON_THRESHOLD {
+        return serde_json::from_str(json);
+    }
+    let mut bytes = json.as_bytes().to_vec();
+    simd_json::serde::from_slice(&mut bytes).or_else(|_| serde_json::from_str(json))
+}
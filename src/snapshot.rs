@@ -0,0 +1,237 @@
+//! Canonical, redacted rendering of contexts for snapshot testing.
+//!
+//! [`Context::to_snapshot`] renders a context as pretty-printed JSON with sorted keys, where
+//! volatile values (UUIDs, timestamps, configured keys) are replaced by stable placeholders.
+//! The output is meant to be fed to snapshot tools such as `insta`:
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SnapshotOptions};
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), serde_value::Value::String("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()));
+//! ctx.insert("user".to_string(), serde_value::Value::String("alice".to_string()));
+//!
+//! let snapshot = ctx.to_snapshot(&SnapshotOptions::new());
+//! assert_eq!(snapshot, "{\n  \"request_id\": \"[uuid]\",\n  \"user\": \"alice\"\n}");
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Options controlling which values are masked in a snapshot.
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    masked_keys: Vec<String>,
+    detect_uuids: bool,
+    detect_timestamps: bool,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            masked_keys: Vec::new(),
+            detect_uuids: true,
+            detect_timestamps: true,
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Creates options masking UUIDs and RFC 3339 timestamps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Masks every value whose dotted path matches `pattern`.
+    ///
+    /// The pattern may contain `*` wildcards matching any sequence of characters, for
+    /// example `*.created_at` or `job.*`.
+    pub fn mask_key(mut self, pattern: &str) -> Self {
+        self.masked_keys.push(pattern.to_string());
+        self
+    }
+
+    /// Enables or disables the automatic masking of UUID-shaped strings.
+    pub fn with_uuid_detection(mut self, enabled: bool) -> Self {
+        self.detect_uuids = enabled;
+        self
+    }
+
+    /// Enables or disables the automatic masking of RFC 3339 timestamps.
+    pub fn with_timestamp_detection(mut self, enabled: bool) -> Self {
+        self.detect_timestamps = enabled;
+        self
+    }
+
+    fn placeholder(&self, path: &str, value: &Value) -> Option<&'static str> {
+        if self.masked_keys.iter().any(|pattern| glob_match(pattern, path)) {
+            return Some("[masked]");
+        }
+        match value {
+            Value::String(s) if self.detect_uuids && is_uuid(s) => Some("[uuid]"),
+            Value::String(s) if self.detect_timestamps && is_timestamp(s) => Some("[timestamp]"),
+            _ => None,
+        }
+    }
+}
+
+impl Context {
+    /// Renders the context in a canonical, redacted form suitable for snapshot assertions.
+    ///
+    /// Keys are sorted, output is indented with two spaces and masked values are replaced by
+    /// `"[masked]"`, `"[uuid]"` or `"[timestamp]"`.
+    pub fn to_snapshot(&self, options: &SnapshotOptions) -> String {
+        let map: BTreeMap<Value, Value> = self.inner().into_iter().map(|(k, v)| (Value::String(k), v)).collect();
+        let mut out = String::new();
+        write_value(&mut out, "", &Value::Map(map), options, 0);
+        out
+    }
+}
+
+pub(crate) fn write_value(out: &mut String, path: &str, value: &Value, options: &SnapshotOptions, depth: usize) {
+    if !path.is_empty() {
+        if let Some(placeholder) = options.placeholder(path, value) {
+            write_string(out, placeholder);
+            return;
+        }
+    }
+    let indent = "  ".repeat(depth + 1);
+    match value {
+        Value::Map(map) if map.is_empty() => out.push_str("{}"),
+        Value::Map(map) => {
+            let mut entries: Vec<(String, &Value)> = map.iter().map(|(k, v)| (key_to_string(k), v)).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                out.push_str(if index == 0 { "\n" } else { ",\n" });
+                out.push_str(&indent);
+                write_string(out, &key);
+                out.push_str(": ");
+                let child = match path.is_empty() {
+                    true => key,
+                    false => format!("{}.{}", path, key),
+                };
+                write_value(out, &child, value, options, depth + 1);
+            }
+            let _ = write!(out, "\n{}}}", "  ".repeat(depth));
+        }
+        Value::Seq(items) if items.is_empty() => out.push_str("[]"),
+        Value::Seq(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                out.push_str(if index == 0 { "\n" } else { ",\n" });
+                out.push_str(&indent);
+                write_value(out, &format!("{}.{}", path, index), item, options, depth + 1);
+            }
+            let _ = write!(out, "\n{}]", "  ".repeat(depth));
+        }
+        Value::Option(Some(inner)) | Value::Newtype(inner) => write_value(out, path, inner, options, depth),
+        Value::Option(None) | Value::Unit => out.push_str("null"),
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::U8(v) => out.push_str(&v.to_string()),
+        Value::U16(v) => out.push_str(&v.to_string()),
+        Value::U32(v) => out.push_str(&v.to_string()),
+        Value::U64(v) => out.push_str(&v.to_string()),
+        Value::I8(v) => out.push_str(&v.to_string()),
+        Value::I16(v) => out.push_str(&v.to_string()),
+        Value::I32(v) => out.push_str(&v.to_string()),
+        Value::I64(v) => out.push_str(&v.to_string()),
+        Value::F32(v) if v.is_finite() => out.push_str(&format!("{:?}", v)),
+        Value::F64(v) if v.is_finite() => out.push_str(&format!("{:?}", v)),
+        Value::F32(_) | Value::F64(_) => out.push_str("null"),
+        Value::Char(c) => write_string(out, &c.to_string()),
+        Value::String(s) => write_string(out, s),
+        Value::Bytes(bytes) => {
+            let items = bytes.iter().map(|b| b.to_string()).collect::<Vec<String>>();
+            let _ = write!(out, "[{}]", items.join(", "));
+        }
+    }
+}
+
+fn key_to_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Matches `text` against a pattern where `*` stands for any sequence of characters.
+///
+/// The match runs on bytes, which is equivalent for UTF-8 and does not allocate, so that it can
+/// be used by [`Context::emergency_dump`](crate::Context::emergency_dump).
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+    let (mut p, mut t) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some(p);
+            mark = t;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some(s) = star {
+            p = s + 1;
+            mark += 1;
+            t = mark;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+pub(crate) fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_timestamp(s: &str) -> bool {
+    let b = s.as_bytes();
+    let digits = |range: std::ops::Range<usize>| range.into_iter().all(|i| b[i].is_ascii_digit());
+    b.len() >= 19
+        && digits(0..4)
+        && b[4] == b'-'
+        && digits(5..7)
+        && b[7] == b'-'
+        && digits(8..10)
+        && (b[10] == b'T' || b[10] == b't' || b[10] == b' ')
+        && digits(11..13)
+        && b[13] == b':'
+        && digits(14..16)
+        && b[16] == b':'
+        && digits(17..19)
+}
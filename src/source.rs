@@ -0,0 +1,88 @@
+//! Underlying sources of generated errors.
+//!
+//! The error structs generated by `cdumay_core` cannot hold a source, so
+//! `with_source` wraps them in a [`WithSource`], which implements
+//! [`std::error::Error::source`] and lets tools walking the chain (`anyhow`, `tracing-error`,
+//! [`Context::record_causes`](crate::Context::record_causes)) see the underlying error. The
+//! cause chain of the source is also recorded under `error.causes` in the error details, so it
+//! survives the conversion into `cdumay_core::Error`.
+//!
+//! ```rust
+//! use std::error::Error;
+//! use cdumay_context::{Context, Contextualize, UnExpectedError};
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("path".to_string(), serde_value::Value::String("/etc/app.toml".to_string()));
+//!
+//! let io = std::io::Error::other("permission denied");
+//! let err = UnExpectedError::new().with_context(&ctx).with_source(io);
+//! assert_eq!(err.source().map(|s| s.to_string()), Some("permission denied".to_string()));
+//!
+//! let err: cdumay_core::Error = err.into();
+//! assert!(err.details().contains_key("path"));
+//! assert!(err.details().contains_key("error"));
+//! ```
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+/// An error with its underlying source.
+#[derive(Debug)]
+pub struct WithSource<E> {
+    error: E,
+    source: Box<dyn Error + Send + Sync + 'static>,
+}
+
+impl<E> WithSource<E> {
+    /// Wraps `error` with its underlying `source`.
+    ///
+    /// Unlike the `with_source` method of the generated errors, this does not record the cause
+    /// chain in the error details.
+    pub fn new(error: E, source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+        Self {
+            error,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the wrapped error.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Returns the wrapped error, dropping its source.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+
+    /// Returns the wrapped error and its source.
+    pub fn into_parts(self) -> (E, Box<dyn Error + Send + Sync + 'static>) {
+        (self.error, self.source)
+    }
+}
+
+impl<E> Deref for WithSource<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        &self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for WithSource<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for WithSource<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl<E: Into<cdumay_core::Error>> From<WithSource<E>> for cdumay_core::Error {
+    fn from(err: WithSource<E>) -> Self {
+        err.error.into()
+    }
+}
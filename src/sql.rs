@@ -0,0 +1,198 @@
+//! SQL statement capture.
+//!
+//! [`Context::record_sql`] stores the statement behind a database error under the [`DB_KEY`] key,
+//! without the sensitive values it may carry: the statement is normalized by [`normalize_sql`],
+//! which replaces string and numeric literals with `?` and drops comments, and bind parameters
+//! are never stored raw. Depending on [`SqlParams`], `db.params` holds either the type of each
+//! parameter or, with the "hash" feature, a hash of its value keyed by a secret salt.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SqlParams};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.record_sql_with(
+//!     "SELECT * FROM users WHERE email = $1 AND status = 'active'",
+//!     &[Value::String("jane@example.com".to_string())],
+//!     SqlParams::Placeholders,
+//! );
+//!
+//! let db = ctx.at("db");
+//! assert_eq!(db.at("statement").as_str(), Some("SELECT * FROM users WHERE email = $1 AND status = ?"));
+//! assert_eq!(db.at("params").as_seq().unwrap()[0], Value::String("<string>".to_string()));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which [`Context::record_sql`] stores the statement.
+pub const DB_KEY: &str = crate::keys::DB;
+
+/// Representation of bind parameters in `db.params`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum SqlParams {
+    /// The type of each parameter, e.g. `<string>` (default).
+    #[default]
+    Placeholders,
+    /// The HMAC-SHA256 of each parameter keyed by the given secret salt, truncated to 128 bits,
+    /// e.g. `hmac-sha256:4f0c3b9e9d3c8e1e0f6b8a1c2d3e4f50`.
+    ///
+    /// Equal values hash equally under the same salt, which allows correlating reports, and
+    /// values from a small domain, such as ids or emails, cannot be recovered by brute force
+    /// without the salt.
+    ///
+    /// This variant is only available when the "hash" feature is enabled.
+    #[cfg(feature = "hash")]
+    Hashed(Vec<u8>),
+}
+
+impl Context {
+    /// Records `statement` and the placeholders of its `params` under the [`DB_KEY`] key.
+    pub fn record_sql(&mut self, statement: &str, params: &[Value]) {
+        self.record_sql_with(statement, params, SqlParams::default())
+    }
+
+    /// Records `statement` and its `params`, represented according to `mode`, under the
+    /// [`DB_KEY`] key.
+    pub fn record_sql_with(&mut self, statement: &str, params: &[Value], mode: SqlParams) {
+        let params = params
+            .iter()
+            .map(|param| match &mode {
+                SqlParams::Placeholders => Value::String(format!("<{}>", type_name(param))),
+                #[cfg(feature = "hash")]
+                SqlParams::Hashed(salt) => Value::String(hash(param, salt)),
+            })
+            .collect();
+        let db = BTreeMap::from([
+            (Value::String("statement".to_string()), Value::String(normalize_sql(statement))),
+            (Value::String("params".to_string()), Value::Seq(params)),
+        ]);
+        self.insert(DB_KEY.to_string(), Value::Map(db));
+    }
+}
+
+/// Replaces string and numeric literals of `statement` with `?`, drops comments and collapses
+/// whitespace.
+///
+/// String literals end at an unescaped quote, a quote being escaped by doubling it (`''`) or by a
+/// backslash (`\'`), and dollar-quoted strings (`$$...$$`, `$tag$...$tag$`) are literals too.
+/// Quoted identifiers (`"name"`, `` `name` ``) and numbered placeholders (`$1`, `:1`) are kept.
+pub fn normalize_sql(statement: &str) -> String {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut out = String::with_capacity(statement.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' => {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\\' || (chars[i] == '\'' && chars.get(i + 1) == Some(&'\'')) {
+                        i += 2;
+                    } else if chars[i] == '\'' {
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+                i += 1;
+                out.push('?');
+            }
+            '$' if dollar_tag(&chars[i..]).is_some() && !out.ends_with(|p: char| p.is_alphanumeric() || matches!(p, '_' | '$')) => {
+                let len = dollar_tag(&chars[i..]).unwrap_or(1);
+                let tag = &chars[i..i + len];
+                i += len;
+                while i < chars.len() && !chars[i..].starts_with(tag) {
+                    i += 1;
+                }
+                i += len;
+                out.push('?');
+            }
+            '"' | '`' => {
+                let end = chars[i + 1..].iter().position(|&q| q == c).map_or(chars.len(), |p| i + 2 + p);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                push_space(&mut out);
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                push_space(&mut out);
+            }
+            c if c.is_whitespace() => {
+                push_space(&mut out);
+                i += 1;
+            }
+            c if c.is_ascii_digit() && !out.ends_with(|p: char| p.is_alphanumeric() || matches!(p, '_' | '$' | ':' | '.')) => {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                out.push('?');
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Returns the length of the `$tag$` delimiter opening the dollar-quoted string `chars` starts
+/// with, if any.
+fn dollar_tag(chars: &[char]) -> Option<usize> {
+    let end = chars.iter().skip(1).position(|c| *c == '$')? + 1;
+    let tag = &chars[1..end];
+    let valid = !tag.first().is_some_and(char::is_ascii_digit) && tag.iter().all(|c| c.is_alphanumeric() || *c == '_');
+    valid.then_some(end + 1)
+}
+
+fn push_space(out: &mut String) {
+    if !out.is_empty() && !out.ends_with(' ') {
+        out.push(' ');
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "bool",
+        Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) => "unsigned",
+        Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) => "integer",
+        Value::F32(_) | Value::F64(_) => "float",
+        Value::Char(_) | Value::String(_) => "string",
+        Value::Unit | Value::Option(None) => "null",
+        Value::Option(Some(value)) | Value::Newtype(value) => type_name(value),
+        Value::Seq(_) => "array",
+        Value::Map(_) => "object",
+        Value::Bytes(_) => "bytes",
+    }
+}
+
+/// Returns the truncated HMAC-SHA256 of the canonical rendering of `value` keyed by `salt`.
+#[cfg(feature = "hash")]
+fn hash(value: &Value, salt: &[u8]) -> String {
+    use std::fmt::Write;
+    let options = crate::SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+    let mut canonical = String::new();
+    crate::snapshot::write_value(&mut canonical, "", value, &options, 0);
+    crate::anonymize::hmac_sha256(salt, canonical.as_bytes())[..16]
+        .iter()
+        .fold("hmac-sha256:".to_string(), |mut out, byte| {
+            let _ = write!(out, "{:02x}", byte);
+            out
+        })
+}
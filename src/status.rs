@@ -0,0 +1,133 @@
+//! Transport status mapping of error kinds.
+//!
+//! [`StatusMapping`] translates an [`ErrorKind`] into the status a transport layer should return:
+//! [`StatusMapping::http_status`] for HTTP and [`StatusMapping::grpc_code`] for gRPC. The kinds of
+//! this crate have a dedicated gRPC code, e.g. [`ProtectedKeyError`](crate::ProtectedKeyError)
+//! maps to [`GrpcCode::FailedPrecondition`] while [`DeltaConflictError`](crate::DeltaConflictError)
+//! maps to [`GrpcCode::Aborted`], although both have code 409. Other kinds, including those
+//! declared with [`define_context_errors!`](crate::define_context_errors), are mapped from their
+//! code, following the usual HTTP to gRPC correspondence, unless a code was registered for them
+//! with [`register_grpc_code`].
+//!
+//! ```rust
+//! use cdumay_context::{register_grpc_code, GrpcCode, StatusMapping, TimeoutError};
+//! use cdumay_core::ErrorKind;
+//!
+//! assert_eq!(TimeoutError.http_status(), 504);
+//! assert_eq!(TimeoutError.grpc_code(), GrpcCode::DeadlineExceeded);
+//! assert_eq!(ErrorKind("RateLimited", 429, "Rate limited").grpc_code(), GrpcCode::ResourceExhausted);
+//!
+//! let payment_required = ErrorKind("PaymentRequired", 402, "Payment required");
+//! assert_eq!(payment_required.grpc_code(), GrpcCode::Unknown);
+//! register_grpc_code(payment_required.clone(), GrpcCode::FailedPrecondition);
+//! assert_eq!(payment_required.grpc_code(), GrpcCode::FailedPrecondition);
+//! ```
+use crate::error::{
+    ConflictError, ContextSerializationError, ContextValidationError, ContextValueError, DeltaConflictError, FeatureDisabledError, FrozenKeyError,
+    GenericContextError, InvalidMapKeyError, InvalidStateError, NotFoundError, PrecisionLossError, ProtectedKeyError, QuotaExceededError,
+    SizeLimitExceededError, TimeoutError, UnauthorizedError, YamlExpansionLimitError,
+};
+use cdumay_core::ErrorKind;
+use std::sync::RwLock;
+
+/// gRPC codes registered with [`register_grpc_code`].
+static REGISTERED: RwLock<Vec<(ErrorKind, GrpcCode)>> = RwLock::new(Vec::new());
+
+/// Registers `code` as the gRPC code of `kind` for the whole process, replacing the code
+/// registered before or the one `kind` would be mapped to.
+pub fn register_grpc_code(kind: ErrorKind, code: GrpcCode) {
+    let mut registered = REGISTERED.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registered.retain(|(registered, _)| *registered != kind);
+    registered.push((kind, code));
+}
+
+/// gRPC status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum GrpcCode {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+/// Maps error kinds to transport statuses.
+pub trait StatusMapping {
+    /// Returns the HTTP status code to respond with.
+    fn http_status(&self) -> u16;
+
+    /// Returns the gRPC status code to respond with.
+    fn grpc_code(&self) -> GrpcCode;
+}
+
+impl StatusMapping for ErrorKind {
+    /// Returns the code of the kind if it is a valid HTTP status, `500` otherwise.
+    fn http_status(&self) -> u16 {
+        match self.code() {
+            code @ 100..=599 => code,
+            _ => 500,
+        }
+    }
+
+    fn grpc_code(&self) -> GrpcCode {
+        const KINDS: [(ErrorKind, GrpcCode); 18] = [
+            (GenericContextError, GrpcCode::Internal),
+            (ContextValueError, GrpcCode::InvalidArgument),
+            (ProtectedKeyError, GrpcCode::FailedPrecondition),
+            (FrozenKeyError, GrpcCode::FailedPrecondition),
+            (DeltaConflictError, GrpcCode::Aborted),
+            (NotFoundError, GrpcCode::NotFound),
+            (ConflictError, GrpcCode::Aborted),
+            (TimeoutError, GrpcCode::DeadlineExceeded),
+            (UnauthorizedError, GrpcCode::Unauthenticated),
+            (InvalidStateError, GrpcCode::FailedPrecondition),
+            (ContextValidationError, GrpcCode::InvalidArgument),
+            (ContextSerializationError, GrpcCode::Internal),
+            (PrecisionLossError, GrpcCode::InvalidArgument),
+            (InvalidMapKeyError, GrpcCode::InvalidArgument),
+            (FeatureDisabledError, GrpcCode::Unimplemented),
+            (YamlExpansionLimitError, GrpcCode::ResourceExhausted),
+            (QuotaExceededError, GrpcCode::ResourceExhausted),
+            (SizeLimitExceededError, GrpcCode::ResourceExhausted),
+        ];
+        let registered = REGISTERED.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match registered.iter().chain(KINDS.iter()).find(|(kind, _)| kind == self) {
+            Some((_, code)) => *code,
+            None => grpc_code_from_http(self.http_status()),
+        }
+    }
+}
+
+fn grpc_code_from_http(status: u16) -> GrpcCode {
+    match status {
+        200..=299 => GrpcCode::Ok,
+        400 => GrpcCode::InvalidArgument,
+        401 => GrpcCode::Unauthenticated,
+        403 => GrpcCode::PermissionDenied,
+        404 => GrpcCode::NotFound,
+        408 | 504 => GrpcCode::DeadlineExceeded,
+        409 => GrpcCode::Aborted,
+        412 => GrpcCode::FailedPrecondition,
+        413 => GrpcCode::ResourceExhausted,
+        416 => GrpcCode::OutOfRange,
+        429 => GrpcCode::ResourceExhausted,
+        499 => GrpcCode::Cancelled,
+        501 => GrpcCode::Unimplemented,
+        502 | 503 => GrpcCode::Unavailable,
+        500..=599 => GrpcCode::Internal,
+        _ => GrpcCode::Unknown,
+    }
+}
@@ -0,0 +1,342 @@
+//! Storage abstraction for context files.
+//!
+//! Files are read and written through a [`ContextStorage`], so that targets without `std::fs`,
+//! such as WASI or embedded ones, and tests can supply their own storage. The crate provides
+//! [`FsStorage`], backed by the filesystem, and [`MemoryStorage`], backed by a map.
+//!
+//! * [`Context::load_file`] and [`Context::save_file`] read and write a context in the format
+//!   given by the file extension: `.json`, `.toml`, `.yaml` or `.yml`;
+//! * [`Context::watch_file`] returns a [`ContextWatcher`] reloading the context when the file
+//!   changes, by polling so that no thread nor OS notification is needed;
+//! * [`Context::export_bundle_to`] and [`Context::import_bundle_from`] write and read replay
+//!   bundles.
+//!
+//! [`Contextualize::from_file`] and [`Contextualize::save_to_file`] are shortcuts for any context
+//! type, reading and writing the filesystem directly. Their errors hold the path of the file
+//! under the `path` key of their details.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cdumay_context::{Context, Contextualize, MemoryStorage};
+//! use serde_value::Value;
+//!
+//! let storage = MemoryStorage::new();
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! ctx.save_file(&storage, "app/context.json").unwrap();
+//!
+//! let mut watcher = Context::watch_file(&storage, "app/context.json");
+//! assert_eq!(watcher.poll().unwrap().unwrap().inner(), ctx.inner());
+//! assert!(watcher.poll().unwrap().is_none());
+//!
+//! ctx.insert("user".to_string(), Value::String("john".to_string()));
+//! ctx.save_file(&storage, "app/context.json").unwrap();
+//! assert_eq!(watcher.poll().unwrap().unwrap().get("user"), Some(&Value::String("john".to_string())));
+//! # }
+//! ```
+use crate::{Capability, Context, Contextualize, Format, KeyNotFound, UnExpectedError, ValidationError};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Where context files are read and written, see the [module documentation](self).
+pub trait ContextStorage {
+    /// Reads the content of the file at `path`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(content)` containing the content of the file
+    /// * `Err(e)` containing a [`KeyNotFound`] error if there is no file at `path`, or the error of
+    ///   the storage
+    fn read(&self, path: &Path) -> cdumay_core::Result<String>;
+
+    /// Writes `content` to the file at `path`, replacing it and creating its parent directories
+    /// if needed.
+    fn write(&self, path: &Path, content: &str) -> cdumay_core::Result<()>;
+
+    /// Returns a token which changes whenever the file at `path` changes, `None` if there is no
+    /// file.
+    fn version(&self, path: &Path) -> cdumay_core::Result<Option<u64>>;
+}
+
+impl<S: ContextStorage + ?Sized> ContextStorage for &S {
+    fn read(&self, path: &Path) -> cdumay_core::Result<String> {
+        (**self).read(path)
+    }
+
+    fn write(&self, path: &Path, content: &str) -> cdumay_core::Result<()> {
+        (**self).write(path, content)
+    }
+
+    fn version(&self, path: &Path) -> cdumay_core::Result<Option<u64>> {
+        (**self).version(path)
+    }
+}
+
+impl<S: ContextStorage + ?Sized> ContextStorage for Arc<S> {
+    fn read(&self, path: &Path) -> cdumay_core::Result<String> {
+        (**self).read(path)
+    }
+
+    fn write(&self, path: &Path, content: &str) -> cdumay_core::Result<()> {
+        (**self).write(path, content)
+    }
+
+    fn version(&self, path: &Path) -> cdumay_core::Result<Option<u64>> {
+        (**self).version(path)
+    }
+}
+
+/// Storage backed by the filesystem.
+///
+/// Versions are derived from the modification time and size of files.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FsStorage;
+
+impl ContextStorage for FsStorage {
+    fn read(&self, path: &Path) -> cdumay_core::Result<String> {
+        std::fs::read_to_string(path).map_err(|err| io_error(&err, path))
+    }
+
+    fn write(&self, path: &Path, content: &str) -> cdumay_core::Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|err| io_error(&err, parent))?;
+        }
+        std::fs::write(path, content).map_err(|err| io_error(&err, path))
+    }
+
+    fn version(&self, path: &Path) -> cdumay_core::Result<Option<u64>> {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(io_error(&err, path)),
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Ok(Some(modified.wrapping_mul(0x100000001b3) ^ metadata.len()))
+    }
+}
+
+/// Storage backed by a map, for tests and targets without a filesystem.
+///
+/// Versions count the writes to each file.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    files: Mutex<BTreeMap<PathBuf, (String, u64)>>,
+}
+
+impl MemoryStorage {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the file at `path`, returning its content if it was present.
+    pub fn remove(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.lock().remove(path.as_ref()).map(|(content, _)| content)
+    }
+
+    /// Returns the paths of the files, in order.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, (String, u64)>> {
+        self.files.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ContextStorage for MemoryStorage {
+    fn read(&self, path: &Path) -> cdumay_core::Result<String> {
+        match self.lock().get(path) {
+            Some((content, _)) => Ok(content.clone()),
+            None => Err(KeyNotFound::new().with_message(format!("{}: no such file", path.display())).into()),
+        }
+    }
+
+    fn write(&self, path: &Path, content: &str) -> cdumay_core::Result<()> {
+        let mut files = self.lock();
+        let version = files.get(path).map_or(0, |(_, version)| version + 1);
+        files.insert(path.to_path_buf(), (content.to_string(), version));
+        Ok(())
+    }
+
+    fn version(&self, path: &Path) -> cdumay_core::Result<Option<u64>> {
+        Ok(self.lock().get(path).map(|(_, version)| *version))
+    }
+}
+
+/// Reloads a context file when it changes, see [`Context::watch_file`].
+#[derive(Debug)]
+pub struct ContextWatcher<S> {
+    storage: S,
+    path: PathBuf,
+    version: Option<u64>,
+}
+
+impl<S: ContextStorage> ContextWatcher<S> {
+    /// Returns the context loaded from the file if it changed since the previous call, the first
+    /// call loading it if it exists.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Option<Context>>` which is:
+    /// * `Ok(Some(context))` containing the context if the file changed
+    /// * `Ok(None)` if the file did not change or does not exist
+    /// * `Err(e)` containing the error of [`Context::load_file`], the file being loaded again by
+    ///   the next call
+    pub fn poll(&mut self) -> cdumay_core::Result<Option<Context>> {
+        let version = self.storage.version(&self.path)?;
+        if version == self.version {
+            return Ok(None);
+        }
+        let ctx = match version {
+            Some(_) => Some(Context::load_file(&self.storage, &self.path)?),
+            None => None,
+        };
+        self.version = version;
+        Ok(ctx)
+    }
+
+    /// Returns the path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Format {
+    /// Returns the format of the file at `path`, given by its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Format> {
+        match path.as_ref().extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+fn format_of(path: &Path) -> cdumay_core::Result<Format> {
+    Format::from_path(path).ok_or_else(|| {
+        ValidationError::new()
+            .with_message(format!(
+                "{}: unsupported file extension, expected json, toml, yaml or yml",
+                path.display()
+            ))
+            .into()
+    })
+}
+
+impl Context {
+    /// Creates a new context from the file at `path` in `storage`, in the format given by its
+    /// extension.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Context>` which is:
+    /// * `Ok(context)` containing the loaded context on success
+    /// * `Err(e)` containing a [`ValidationError`] error if the extension is unknown, a
+    ///   [`FeatureDisabled`](crate::FeatureDisabled) error if the format is not compiled in, or
+    ///   the error of the storage or of the load
+    pub fn load_file<S: ContextStorage + ?Sized>(storage: &S, path: impl AsRef<Path>) -> cdumay_core::Result<Context> {
+        let path = path.as_ref();
+        let format = format_of(path)?;
+        Context::load_from(format, &storage.read(path)?)
+    }
+
+    /// Writes the context, pretty-printed, to the file at `path` in `storage`, in the format given
+    /// by its extension.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the file was written
+    /// * `Err(e)` containing a [`ValidationError`] error if the extension is unknown, a
+    ///   [`FeatureDisabled`](crate::FeatureDisabled) error if the format is not compiled in, or
+    ///   the error of the storage or of the dump
+    pub fn save_file<S: ContextStorage + ?Sized>(&self, storage: &S, path: impl AsRef<Path>) -> cdumay_core::Result<()> {
+        let path = path.as_ref();
+        let format = format_of(path)?;
+        storage.write(path, &self.dump_to(format, true)?)
+    }
+
+    /// Returns a watcher reloading the context from the file at `path` in `storage` when it
+    /// changes.
+    pub fn watch_file<S: ContextStorage>(storage: S, path: impl AsRef<Path>) -> ContextWatcher<S> {
+        ContextWatcher {
+            storage,
+            path: path.as_ref().to_path_buf(),
+            version: None,
+        }
+    }
+}
+
+/// Loads a context of type `C` from the file at `path`, see [`Contextualize::from_file`].
+#[allow(unused_variables)]
+pub(crate) fn load_path<C: Contextualize>(path: &Path) -> cdumay_core::Result<C> {
+    let load = || -> cdumay_core::Result<C> {
+        let format = format_of(path)?;
+        Capability::from(format).require()?;
+        let content = FsStorage.read(path)?;
+        match format {
+            #[cfg(feature = "json")]
+            Format::Json => C::from_json(&content),
+            #[cfg(feature = "toml")]
+            Format::Toml => C::from_toml(&content),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => C::from_yaml(&content),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("format checked by require"),
+        }
+    };
+    load().map_err(|err| with_path(err, path))
+}
+
+/// Writes `ctx` to the file at `path`, see [`Contextualize::save_to_file`].
+pub(crate) fn save_path<C: Contextualize>(ctx: &C, path: &Path, format: Option<Format>) -> cdumay_core::Result<()> {
+    let save = || -> cdumay_core::Result<()> {
+        let format = match format {
+            Some(format) => format,
+            None => format_of(path)?,
+        };
+        FsStorage.write(path, &dump_pretty(ctx, format)?)
+    };
+    save().map_err(|err| with_path(err, path))
+}
+
+/// Dumps `ctx` to `format`, pretty-printed.
+#[allow(unused_variables)]
+fn dump_pretty<C: Contextualize>(ctx: &C, format: Format) -> cdumay_core::Result<String> {
+    Capability::from(format).require()?;
+    match format {
+        #[cfg(feature = "json")]
+        Format::Json => ctx.to_json(true),
+        #[cfg(feature = "toml")]
+        Format::Toml => ctx.to_toml(true),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => ctx.to_yaml(),
+        #[allow(unreachable_patterns)]
+        _ => unreachable!("format checked by require"),
+    }
+}
+
+/// Returns `err` with `path` stored under the `path` key of its details.
+fn with_path(err: cdumay_core::Error, path: &Path) -> cdumay_core::Error {
+    let mut details = err.details();
+    details.insert("path".to_string(), serde_value::Value::String(path.display().to_string()));
+    cdumay_core::Error::new(err.code(), err.class().to_string(), err.message().to_string(), details)
+}
+
+/// Converts an I/O error on `path`.
+pub(crate) fn io_error(err: &std::io::Error, path: &Path) -> cdumay_core::Error {
+    let message = format!("{}: {}", path.display(), err);
+    match err.kind() {
+        std::io::ErrorKind::NotFound => KeyNotFound::new().with_message(message).into(),
+        _ => UnExpectedError::new().with_message(message).into(),
+    }
+}
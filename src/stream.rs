@@ -0,0 +1,104 @@
+//! Streaming JSON parsing of huge dumps.
+//!
+//! [`Context::from_json_stream`] reads a JSON dump from a reader and hands each top-level entry
+//! to a callback as soon as it is parsed, without ever holding the whole map in memory: only one
+//! entry is materialized at a time. The callback can stop the parsing early by returning
+//! [`ControlFlow::Break`], for example once the keys it looks for were found.
+//!
+//! Values are decoded to the same variants as with
+//! [`Contextualize::from_json`](crate::Contextualize::from_json).
+//!
+//! ```rust
+//! use cdumay_context::Context;
+//! use std::ops::ControlFlow;
+//!
+//! let dump = r#"{"request_id":"abc","payload":[1,2,3],"user":"jane"}"#;
+//! let mut request_id = None;
+//! Context::from_json_stream(dump.as_bytes(), |key, value| match key.as_str() {
+//!     "request_id" => {
+//!         request_id = Some(value);
+//!         ControlFlow::Break(())
+//!     }
+//!     _ => ControlFlow::Continue(()),
+//! })
+//! .unwrap();
+//! assert_eq!(request_id, Some(serde_value::Value::String("abc".to_string())));
+//! ```
+use crate::Context;
+use cdumay_core::ErrorConverter;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufReader, Read};
+use std::ops::ControlFlow;
+
+impl Context {
+    /// Parses the JSON dump read from `reader`, calling `f` with each top-level entry in input
+    /// order until it returns [`ControlFlow::Break`].
+    ///
+    /// The reader is buffered internally. Once `f` stops the parsing, the rest of the input is
+    /// neither read nor validated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<()>` which is:
+    /// * `Ok(())` if the dump was parsed up to its end or until `f` stopped
+    /// * `Err(e)` containing the converted JSON error if the input is not a JSON object or cannot
+    ///   be read
+    pub fn from_json_stream<R, F>(reader: R, f: F) -> cdumay_core::Result<()>
+    where
+        R: Read,
+        F: FnMut(String, Value) -> ControlFlow<()>,
+    {
+        let mut visitor = EntryVisitor { f, stopped: false };
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+        let result = (&mut deserializer).deserialize_map(&mut visitor).and_then(|()| match visitor.stopped {
+            true => Ok(()),
+            false => deserializer.end(),
+        });
+        match result {
+            Err(_) if visitor.stopped => Ok(()),
+            result => result
+                .map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), BTreeMap::new())),
+        }
+    }
+}
+
+struct EntryVisitor<F> {
+    f: F,
+    stopped: bool,
+}
+
+impl<'de, F: FnMut(String, Value) -> ControlFlow<()>> Visitor<'de> for &mut EntryVisitor<F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of context entries")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(JsonValue)?;
+            if (self.f)(key, value).is_break() {
+                self.stopped = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a value the way [`Contextualize::from_json`](crate::Contextualize::from_json) does,
+/// through `serde_json::Value`.
+struct JsonValue;
+
+impl<'de> DeserializeSeed<'de> for JsonValue {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Value::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,149 @@
+//! Tagged value preservation.
+//!
+//! YAML tags (`!secret abc`) and TOML datetimes have no [`Value`] counterpart: by default, loading
+//! drops YAML tags and reads TOML datetimes as strings. With
+//! [`ContextConfig::with_tagged_values`](crate::ContextConfig::with_tagged_values), they are kept
+//! as maps holding the tag under [`TAG_KEY`] and the value under [`TAG_VALUE_KEY`], TOML
+//! datetimes being tagged [`TOML_DATETIME_TAG`], and dumps to the same format write them back as
+//! they were. Other formats dump such maps as they are.
+//!
+//! ```rust
+//! # #[cfg(feature = "yaml")]
+//! # {
+//! use cdumay_context::{Context, ContextConfig, Contextualize, TAG_KEY};
+//! use serde_value::Value;
+//!
+//! let config = ContextConfig::new().with_tagged_values(true);
+//! let ctx = Context::from_yaml_with("token: !vault secret/db\n", &config).unwrap();
+//! assert_eq!(ctx.at("token").at(TAG_KEY).as_str(), Some("!vault"));
+//! assert_eq!(ctx.to_yaml().unwrap(), "token: !vault secret/db\n");
+//!
+//! let ctx = Context::from_yaml("token: !vault secret/db\n").unwrap();
+//! assert_eq!(ctx.get("token"), Some(&Value::String("secret/db".to_string())));
+//! # }
+//! ```
+use crate::Format;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key holding the tag of a tagged value.
+pub const TAG_KEY: &str = "$tag";
+
+/// Key holding the value of a tagged value.
+pub const TAG_VALUE_KEY: &str = "value";
+
+/// Tag of TOML datetimes, whose value is their string form.
+pub const TOML_DATETIME_TAG: &str = "!toml:datetime";
+
+/// Field under which the `toml` crate reads datetimes into a [`Value`].
+#[cfg(feature = "toml")]
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// Returns the tagged value holding `value` under `tag`.
+pub(crate) fn tagged(tag: String, value: Value) -> Value {
+    Value::Map(BTreeMap::from([
+        (Value::String(TAG_KEY.to_string()), Value::String(tag)),
+        (Value::String(TAG_VALUE_KEY.to_string()), value),
+    ]))
+}
+
+/// Returns the tag and the value of a tagged value.
+fn as_tagged(value: &Value) -> Option<(&str, &Value)> {
+    let Value::Map(map) = value else {
+        return None;
+    };
+    match (
+        map.len(),
+        map.get(&Value::String(TAG_KEY.to_string())),
+        map.get(&Value::String(TAG_VALUE_KEY.to_string())),
+    ) {
+        (2, Some(Value::String(tag)), Some(inner)) => Some((tag, inner)),
+        _ => None,
+    }
+}
+
+/// Parses a TOML table, datetimes being tagged values if `preserve` or strings otherwise.
+#[cfg(feature = "toml")]
+pub(crate) fn load_toml(toml: &str, preserve: bool) -> cdumay_core::Result<crate::backend::Loaded<Value>> {
+    use cdumay_core::ErrorConverter;
+    crate::metrics::observe_load(Format::Toml, toml.len(), || {
+        let data = toml::from_str::<crate::backend::Loaded<Value>>(toml).map_err(|err| {
+            cdumay_toml::TomlDeserializeErrorConverter::convert_error(&err, Some("Failed to load context".to_string()), BTreeMap::new())
+        })?;
+        Ok(data.into_iter().map(|(k, v)| (k, toml_datetimes(v, preserve))).collect())
+    })
+}
+
+#[cfg(feature = "toml")]
+pub(crate) fn toml_datetimes(value: Value, preserve: bool) -> Value {
+    match value {
+        Value::Map(map) => {
+            if let (1, Some(Value::String(datetime))) = (map.len(), map.get(&Value::String(TOML_DATETIME_FIELD.to_string()))) {
+                let datetime = Value::String(datetime.clone());
+                return match preserve {
+                    true => tagged(TOML_DATETIME_TAG.to_string(), datetime),
+                    false => datetime,
+                };
+            }
+            Value::Map(map.into_iter().map(|(k, v)| (k, toml_datetimes(v, preserve))).collect())
+        }
+        Value::Seq(items) => Value::Seq(items.into_iter().map(|item| toml_datetimes(item, preserve)).collect()),
+        value => value,
+    }
+}
+
+/// Dump entries whose tagged values are written back as tags of `format`, if any.
+pub(crate) struct Restored<'a> {
+    pub(crate) entries: &'a [(String, Value)],
+    pub(crate) format: Option<Format>,
+}
+
+impl Serialize for Restored<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (k, v) in self.entries {
+            map.serialize_entry(k, &RestoredValue(v, self.format))?;
+        }
+        map.end()
+    }
+}
+
+struct RestoredValue<'a>(&'a Value, Option<Format>);
+
+impl Serialize for RestoredValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let RestoredValue(value, format) = *self;
+        match (format, format.and(as_tagged(value))) {
+            #[cfg(feature = "toml")]
+            (Some(Format::Toml), Some((TOML_DATETIME_TAG, Value::String(datetime)))) => datetime
+                .parse::<toml::value::Datetime>()
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+            #[cfg(feature = "yaml")]
+            (Some(Format::Yaml), Some((tag, inner))) => serde_yaml::value::TaggedValue {
+                tag: serde_yaml::value::Tag::new(tag),
+                value: serde_yaml::to_value(RestoredValue(inner, format)).map_err(serde::ser::Error::custom)?,
+            }
+            .serialize(serializer),
+            _ => match value {
+                Value::Seq(items) => {
+                    let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                    for item in items {
+                        seq.serialize_element(&RestoredValue(item, format))?;
+                    }
+                    seq.end()
+                }
+                Value::Map(entries) => {
+                    let mut map = serializer.serialize_map(Some(entries.len()))?;
+                    for (k, v) in entries {
+                        map.serialize_entry(k, &RestoredValue(v, format))?;
+                    }
+                    map.end()
+                }
+                Value::Option(Some(inner)) => serializer.serialize_some(&RestoredValue(inner, format)),
+                value => value.serialize(serializer),
+            },
+        }
+    }
+}
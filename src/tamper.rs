@@ -0,0 +1,117 @@
+//! Per-entry tamper detection.
+//!
+//! Before handing a context to code it does not control, such as a plugin, the owner calls
+//! [`Context::seal_values`] to record a digest of every entry of the dump. [`Context::verify_seals`]
+//! then reports the entries added, modified or removed since, whatever the order of the changes
+//! and even if the context was cloned and its change history lost.
+//!
+//! Digests are 64-bit FNV-1a hashes of the values, types included, so that replacing `10u32` by
+//! `10u64` is reported. They detect accidental or careless changes, not a forger able to compute
+//! hash collisions. Digests are kept with the context but are not part of its dump.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, SealViolationKind};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! ctx.insert("role".to_string(), Value::String("reader".to_string()));
+//! ctx.seal_values();
+//!
+//! let mut plugin_ctx = ctx.clone();
+//! plugin_ctx.insert("role".to_string(), Value::String("admin".to_string()));
+//!
+//! let violations = plugin_ctx.verify_seals();
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].kind, SealViolationKind::Modified);
+//! assert_eq!(violations[0].key, "role");
+//! ```
+use crate::hash::Fnv1a;
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Kind of change reported by [`Context::verify_seals`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SealViolationKind {
+    /// Entry absent when the values were sealed.
+    Added,
+    /// Entry whose value changed.
+    Modified,
+    /// Entry removed.
+    Removed,
+}
+
+/// An entry changed since [`Context::seal_values`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealViolation {
+    /// Kind of change.
+    pub kind: SealViolationKind,
+    /// Key of the entry.
+    pub key: String,
+}
+
+impl fmt::Display for SealViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = match self.kind {
+            SealViolationKind::Added => "added",
+            SealViolationKind::Modified => "modified",
+            SealViolationKind::Removed => "removed",
+        };
+        write!(f, "{}: {} since sealing", self.key, change)
+    }
+}
+
+impl Context {
+    /// Records a digest of every entry of the dump, replacing the digests recorded before.
+    pub fn seal_values(&mut self) {
+        self.seals = self.inner_ref().iter().map(|(k, v)| (k.clone(), digest(v))).collect();
+    }
+
+    /// Forgets the digests recorded by [`Context::seal_values`].
+    pub fn unseal_values(&mut self) {
+        self.seals.clear();
+    }
+
+    /// Returns `true` if digests were recorded by [`Context::seal_values`].
+    pub fn has_seals(&self) -> bool {
+        !self.seals.is_empty()
+    }
+
+    /// Returns the entries added, modified or removed since [`Context::seal_values`], ordered by
+    /// key. Nothing is reported if no digests were recorded.
+    pub fn verify_seals(&self) -> Vec<SealViolation> {
+        if self.seals.is_empty() {
+            return Vec::new();
+        }
+        let data = self.inner();
+        let mut violations: Vec<SealViolation> = self
+            .seals
+            .iter()
+            .filter_map(|(k, sealed)| match data.get(k) {
+                None => Some(SealViolation {
+                    kind: SealViolationKind::Removed,
+                    key: k.clone(),
+                }),
+                Some(value) if digest(value) != *sealed => Some(SealViolation {
+                    kind: SealViolationKind::Modified,
+                    key: k.clone(),
+                }),
+                Some(_) => None,
+            })
+            .collect();
+        violations.extend(data.keys().filter(|k| !self.seals.contains_key(*k)).map(|k| SealViolation {
+            kind: SealViolationKind::Added,
+            key: k.clone(),
+        }));
+        violations.sort_by(|a, b| a.key.cmp(&b.key));
+        violations
+    }
+}
+
+pub(crate) fn digest(value: &Value) -> u64 {
+    let mut hasher = Fnv1a::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,131 @@
+//! Interpolation of context values into messages.
+//!
+//! [`Context::render`] replaces the `${path}` placeholders of a template by the value at the
+//! dotted path, see [`Context::get_path`], rendered on a single line. `$${` writes a literal `${`,
+//! and a placeholder which is not closed is kept as it is. Secret keys and the redaction patterns
+//! of the configuration apply, as in [`Context::render_incident_summary`], so that a message never
+//! discloses what a dump would not.
+//!
+//! What happens to placeholders of missing keys is set by [`MissingKeyPolicy`]: an error by
+//! default, or an empty string, or the placeholder kept as it is, with [`Context::render_with`].
+//!
+//! [`Context::render_value`] renders the templates found in the strings of a stored value, at any
+//! depth, so that messages can be stored along with the context they refer to. Rendered values
+//! are not rendered again.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, MissingKeyPolicy};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("app".to_string(), Value::String("billing".to_string()));
+//! ctx.insert("env".to_string(), Value::String("prod".to_string()));
+//! ctx.insert("duration".to_string(), Value::F64(12.5));
+//!
+//! let message = ctx.render("deploy of ${app} to ${env} failed after ${duration}s").unwrap();
+//! assert_eq!(message, "deploy of billing to prod failed after 12.5s");
+//!
+//! assert!(ctx.render("${region}").is_err());
+//! assert_eq!(ctx.render_with("region: ${region}", MissingKeyPolicy::Keep).unwrap(), "region: ${region}");
+//! ```
+use crate::incident::inline;
+use crate::{Context, KeyNotFound};
+use serde_value::Value;
+
+/// What [`Context::render_with`] does with placeholders of missing keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MissingKeyPolicy {
+    /// Fails with a [`KeyNotFound`] error.
+    #[default]
+    Error,
+    /// Renders an empty string.
+    Empty,
+    /// Keeps the placeholder as it is.
+    Keep,
+}
+
+impl Context {
+    /// Renders `template`, failing on missing keys, see the [module documentation](self).
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(string)` containing the rendered template on success
+    /// * `Err(e)` containing a [`KeyNotFound`] error if a placeholder refers to a missing key
+    pub fn render(&self, template: &str) -> cdumay_core::Result<String> {
+        self.render_with(template, MissingKeyPolicy::Error)
+    }
+
+    /// Renders `template`, placeholders of missing keys being handled as set by `policy`.
+    pub fn render_with(&self, template: &str, policy: MissingKeyPolicy) -> cdumay_core::Result<String> {
+        interpolate(&self.redacted(), template, policy)
+    }
+
+    /// Returns the value at the dotted `path`, with the templates found in its strings rendered,
+    /// failing on missing keys.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<Value>` which is:
+    /// * `Ok(value)` containing the rendered value on success
+    /// * `Err(e)` containing a [`KeyNotFound`] error if no value is stored at `path` or if a
+    ///   placeholder refers to a missing key
+    pub fn render_value(&self, path: &str) -> cdumay_core::Result<Value> {
+        self.render_value_with(path, MissingKeyPolicy::Error)
+    }
+
+    /// Returns the value at the dotted `path`, with the templates found in its strings rendered,
+    /// placeholders of missing keys being handled as set by `policy`.
+    ///
+    /// A missing `path` is an error whatever the policy.
+    pub fn render_value_with(&self, path: &str, policy: MissingKeyPolicy) -> cdumay_core::Result<Value> {
+        let value = self.get_path(path).ok_or_else(|| KeyNotFound::for_key(path, self))?;
+        render_strings(&self.redacted(), value.clone(), policy)
+    }
+}
+
+fn render_strings(ctx: &Context, value: Value, policy: MissingKeyPolicy) -> cdumay_core::Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(interpolate(ctx, &s, policy)?),
+        Value::Seq(items) => Value::Seq(
+            items
+                .into_iter()
+                .map(|item| render_strings(ctx, item, policy))
+                .collect::<cdumay_core::Result<_>>()?,
+        ),
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(k, v)| Ok((k, render_strings(ctx, v, policy)?)))
+                .collect::<cdumay_core::Result<_>>()?,
+        ),
+        Value::Option(Some(inner)) => Value::Option(Some(Box::new(render_strings(ctx, *inner, policy)?))),
+        Value::Newtype(inner) => Value::Newtype(Box::new(render_strings(ctx, *inner, policy)?)),
+        other => other,
+    })
+}
+
+/// Renders `template` with the values of `ctx`, already redacted.
+fn interpolate(ctx: &Context, template: &str, policy: MissingKeyPolicy) -> cdumay_core::Result<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(end) = rest[start..].find('}') else { break };
+        out.push_str(&rest[..start]);
+        let path = rest[start + 2..start + end].trim();
+        match (ctx.get_path(path), policy) {
+            (Some(value), _) => out.push_str(&inline(value)),
+            (None, MissingKeyPolicy::Error) => return Err(KeyNotFound::for_key(path, ctx).into()),
+            (None, MissingKeyPolicy::Empty) => {}
+            (None, MissingKeyPolicy::Keep) => out.push_str(&rest[start..start + end + 1]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
@@ -0,0 +1,264 @@
+//! Per-tenant context quotas.
+//!
+//! A [`TenantRegistry`] holds the live contexts of each tenant sharing a worker, so that a
+//! single tenant flooding contexts cannot degrade the others: [`TenantRegistry::register`] fails
+//! with a [`QuotaExceeded`] error once the tenant would exceed its [`TenantQuota`], a maximum
+//! number of contexts and a maximum total size. Sizes are estimated from the keys and values of
+//! the dumps, strings counting their length and scalars their width.
+//!
+//! Hooks set with [`TenantRegistry::on_event`] receive every admission, rejection and release
+//! with the resulting usage of the tenant, to feed metrics.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, TenantQuota, TenantRegistry};
+//! use serde_value::Value;
+//!
+//! let registry = TenantRegistry::new().with_default_quota(TenantQuota::new().with_max_contexts(1));
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("job".to_string(), Value::String("export".to_string()));
+//! let id = registry.register("acme", ctx).unwrap();
+//! assert_eq!(registry.usage("acme").contexts, 1);
+//!
+//! let err = registry.register("acme", Context::new()).unwrap_err();
+//! assert_eq!(err.code(), 429);
+//! assert!(registry.register("globex", Context::new()).is_ok());
+//!
+//! registry.release("acme", &id);
+//! assert!(registry.register("acme", Context::new()).is_ok());
+//! ```
+use crate::{Context, Contextualize, QuotaExceeded};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Limits of a tenant, see [`TenantRegistry`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    max_contexts: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl TenantQuota {
+    /// Creates an unlimited quota.
+    pub const fn new() -> Self {
+        Self {
+            max_contexts: None,
+            max_bytes: None,
+        }
+    }
+
+    /// Limits the number of contexts held by the tenant.
+    pub const fn with_max_contexts(mut self, contexts: usize) -> Self {
+        self.max_contexts = Some(contexts);
+        self
+    }
+
+    /// Limits the estimated total size, in bytes, of the contexts held by the tenant.
+    pub const fn with_max_bytes(mut self, bytes: usize) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Returns the maximum number of contexts, if limited.
+    pub fn max_contexts(&self) -> Option<usize> {
+        self.max_contexts
+    }
+
+    /// Returns the maximum total size, if limited.
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+}
+
+/// Contexts held by a tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantUsage {
+    /// Number of contexts.
+    pub contexts: usize,
+    /// Estimated total size of the contexts, in bytes.
+    pub bytes: usize,
+}
+
+/// Event passed to the hooks of a [`TenantRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaEvent {
+    /// A context was registered.
+    Admitted,
+    /// A context was refused, the quota of the tenant being exceeded.
+    Rejected,
+    /// A context was released.
+    Released,
+}
+
+type Hook = Arc<dyn Fn(&str, QuotaEvent, TenantUsage) + Send + Sync>;
+
+#[derive(Default)]
+struct Tenants {
+    quotas: BTreeMap<String, TenantQuota>,
+    contexts: BTreeMap<String, BTreeMap<String, (Context, usize)>>,
+}
+
+impl Tenants {
+    fn usage(&self, tenant: &str) -> TenantUsage {
+        self.contexts.get(tenant).map_or_else(TenantUsage::default, |contexts| TenantUsage {
+            contexts: contexts.len(),
+            bytes: contexts.values().map(|(_, bytes)| bytes).sum(),
+        })
+    }
+}
+
+/// Live contexts of each tenant, within quotas, see the [module documentation](self).
+#[derive(Default)]
+pub struct TenantRegistry {
+    default_quota: TenantQuota,
+    tenants: Mutex<Tenants>,
+    hooks: Vec<Hook>,
+}
+
+impl std::fmt::Debug for TenantRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantRegistry")
+            .field("default_quota", &self.default_quota)
+            .field("tenants", &self.tenants())
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl TenantRegistry {
+    /// Creates a registry without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the quota of tenants without their own quota.
+    pub fn with_default_quota(mut self, quota: TenantQuota) -> Self {
+        self.default_quota = quota;
+        self
+    }
+
+    /// Calls `hook` with the tenant, the event and the resulting usage of the tenant on every
+    /// registration, rejection and release.
+    pub fn on_event<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, QuotaEvent, TenantUsage) + Send + Sync + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Sets the quota of `tenant`. Contexts already registered are kept even if they exceed it.
+    pub fn set_quota(&self, tenant: &str, quota: TenantQuota) {
+        self.lock().quotas.insert(tenant.to_string(), quota);
+    }
+
+    /// Returns the quota of `tenant`.
+    pub fn quota(&self, tenant: &str) -> TenantQuota {
+        self.lock().quotas.get(tenant).copied().unwrap_or(self.default_quota)
+    }
+
+    /// Registers `ctx` for `tenant`, replacing the context with the same [id](Context::id) if
+    /// any.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<String>` which is:
+    /// * `Ok(id)` containing the id of the context if it was registered
+    /// * `Err(e)` containing a [`QuotaExceeded`] error, with the tenant, its usage and its limit
+    ///   in the details, if the tenant would exceed its quota
+    pub fn register(&self, tenant: &str, ctx: Context) -> cdumay_core::Result<String> {
+        let id = ctx.id().to_string();
+        let bytes = estimated_size(&ctx);
+        let mut tenants = self.lock();
+        let quota = tenants.quotas.get(tenant).copied().unwrap_or(self.default_quota);
+        let mut usage = tenants.usage(tenant);
+        if let Some((_, replaced)) = tenants.contexts.get(tenant).and_then(|contexts| contexts.get(&id)) {
+            usage.contexts -= 1;
+            usage.bytes -= replaced;
+        }
+        let exceeded = match (quota.max_contexts, quota.max_bytes) {
+            (Some(max), _) if usage.contexts + 1 > max => Some(("contexts", usage.contexts + 1, max)),
+            (_, Some(max)) if usage.bytes + bytes > max => Some(("bytes", usage.bytes + bytes, max)),
+            _ => None,
+        };
+        if let Some((limit, requested, max)) = exceeded {
+            let usage = tenants.usage(tenant);
+            drop(tenants);
+            self.notify(tenant, QuotaEvent::Rejected, usage);
+            return Err(QuotaExceeded::new()
+                .with_message(format!(
+                    "Tenant '{}' would hold {} {}, more than its quota of {}",
+                    tenant, requested, limit, max
+                ))
+                .with_details(BTreeMap::from([
+                    (crate::keys::TENANT.to_string(), Value::String(tenant.to_string())),
+                    ("quota.limit".to_string(), Value::String(limit.to_string())),
+                    ("quota.max".to_string(), Value::U64(max as u64)),
+                    ("quota.requested".to_string(), Value::U64(requested as u64)),
+                ]))
+                .into());
+        }
+        tenants.contexts.entry(tenant.to_string()).or_default().insert(id.clone(), (ctx, bytes));
+        let usage = tenants.usage(tenant);
+        drop(tenants);
+        self.notify(tenant, QuotaEvent::Admitted, usage);
+        Ok(id)
+    }
+
+    /// Removes the context with the given id from `tenant`, returning it if it was registered.
+    pub fn release(&self, tenant: &str, id: &str) -> Option<Context> {
+        let mut tenants = self.lock();
+        let contexts = tenants.contexts.get_mut(tenant)?;
+        let (ctx, _) = contexts.remove(id)?;
+        if contexts.is_empty() {
+            tenants.contexts.remove(tenant);
+        }
+        let usage = tenants.usage(tenant);
+        drop(tenants);
+        self.notify(tenant, QuotaEvent::Released, usage);
+        Some(ctx)
+    }
+
+    /// Returns a clone of the context with the given id registered for `tenant`, if any.
+    pub fn get(&self, tenant: &str, id: &str) -> Option<Context> {
+        self.lock().contexts.get(tenant)?.get(id).map(|(ctx, _)| ctx.clone())
+    }
+
+    /// Returns the contexts held by `tenant`.
+    pub fn usage(&self, tenant: &str) -> TenantUsage {
+        self.lock().usage(tenant)
+    }
+
+    /// Returns the tenants holding contexts, in order.
+    pub fn tenants(&self) -> Vec<String> {
+        self.lock().contexts.keys().cloned().collect()
+    }
+
+    fn notify(&self, tenant: &str, event: QuotaEvent, usage: TenantUsage) {
+        self.hooks.iter().for_each(|hook| hook(tenant, event, usage));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Tenants> {
+        self.tenants.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns the estimated size of the dump of `ctx`, in bytes.
+fn estimated_size(ctx: &Context) -> usize {
+    ctx.inner_ref().iter().map(|(k, v)| k.len() + value_size(v)).sum()
+}
+
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Bool(_) | Value::U8(_) | Value::I8(_) | Value::Unit | Value::Option(None) => 1,
+        Value::U16(_) | Value::I16(_) => 2,
+        Value::U32(_) | Value::I32(_) | Value::F32(_) | Value::Char(_) => 4,
+        Value::U64(_) | Value::I64(_) | Value::F64(_) => 8,
+        Value::String(s) => s.len(),
+        Value::Bytes(bytes) => bytes.len(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => value_size(inner),
+        Value::Seq(items) => items.iter().map(value_size).sum(),
+        Value::Map(map) => map.iter().map(|(k, v)| value_size(k) + value_size(v)).sum(),
+    }
+}
@@ -0,0 +1,547 @@
+//! Test doubles for code written against [`Contextualize`].
+//!
+//! [`RecordingContext`] stores its entries like a [`Context`] and records every call made to it,
+//! so that a test can assert how the code under test used the context, e.g. that it inserted a
+//! key exactly once:
+//!
+//! ```rust
+//! use cdumay_context::testing::{Call, RecordingContext};
+//! use cdumay_context::Contextualize;
+//! use serde_value::Value;
+//!
+//! fn tag_request<C: Contextualize>(ctx: &mut C) {
+//!     if !ctx.contains_key("request_id") {
+//!         ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//!     }
+//! }
+//!
+//! let mut ctx = RecordingContext::new();
+//! tag_request(&mut ctx);
+//! tag_request(&mut ctx);
+//! ctx.assert_inserted_once("request_id");
+//! assert_eq!(ctx.calls()[1], Call::ContainsKey("request_id".to_string()));
+//! ```
+//!
+//! [`FailingContext`] behaves like a [`Context`] except that its serializers return an injected
+//! error, to exercise the error paths of the code under test deterministically:
+//!
+//! ```rust
+//! use cdumay_context::testing::FailingContext;
+//! use cdumay_context::{Contextualize, Timeout};
+//!
+//! let ctx = FailingContext::with_error(Timeout::new().with_message("disk is slow".to_string()).into());
+//! assert!(serde_value::to_value(&ctx).is_err());
+//! # #[cfg(feature = "json")]
+//! assert_eq!(ctx.to_json(false).unwrap_err().message(), "disk is slow");
+//! ```
+//!
+//! With the "test-utils" feature, the module also provides:
+//!
+//! * [`assert_context!`](crate::assert_context), asserting that a context holds some entries with
+//!   values compared by kind, and listing every mismatch on failure, see [`Context::compare`];
+//! * [`ContextBuilder::fixture`](crate::ContextBuilder::fixture) and
+//!   [`ContextBuilder::fixture_as`](crate::ContextBuilder::fixture_as), building fixtures of any
+//!   context type without handling errors;
+//! * [`arb_context`], a `proptest` strategy generating random contexts bounded by
+//!   [`ArbitraryParams`], also available as `any::<Context>()`, to property-test serializers and
+//!   merge logic.
+use crate::{Context, ContextDump, Contextualize, SerializationError};
+use serde::{Serialize, Serializer};
+use serde_value::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// A call made to a [`RecordingContext`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    /// [`Contextualize::new`].
+    New,
+    /// [`Contextualize::insert`] with the key and the value.
+    Insert(String, Value),
+    /// [`Contextualize::get`] with the key.
+    Get(String),
+    /// [`Contextualize::extend`] with the entries.
+    Extend(BTreeMap<String, Value>),
+    /// [`Contextualize::inner`] or [`Contextualize::inner_ref`].
+    Inner,
+    /// [`Contextualize::remove`] with the key.
+    Remove(String),
+    /// [`Contextualize::clear`].
+    Clear,
+    /// [`Contextualize::contains_key`] with the key.
+    ContainsKey(String),
+    /// [`Contextualize::len`].
+    Len,
+}
+
+/// A context recording the calls made to it, see the [module documentation](self).
+///
+/// Calls of the provided methods of [`Contextualize`] are recorded as the calls they make, e.g.
+/// [`Contextualize::get_str`] is recorded as a [`Call::Get`].
+#[derive(Debug, Default)]
+pub struct RecordingContext {
+    data: Context,
+    calls: Mutex<Vec<Call>>,
+}
+
+impl RecordingContext {
+    fn record(&self, call: Call) {
+        self.lock().push(call);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Call>> {
+        self.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the calls made so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.lock().clone()
+    }
+
+    /// Forgets the calls made so far, the entries are kept.
+    pub fn reset_calls(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the number of times `k` was written, by [`Contextualize::insert`] or as an entry
+    /// of [`Contextualize::extend`].
+    pub fn inserts(&self, k: &str) -> usize {
+        self.lock()
+            .iter()
+            .filter(|call| match call {
+                Call::Insert(key, _) => key == k,
+                Call::Extend(data) => data.contains_key(k),
+                _ => false,
+            })
+            .count()
+    }
+
+    /// Returns the number of times `k` was read by [`Contextualize::get`].
+    pub fn reads(&self, k: &str) -> usize {
+        self.lock().iter().filter(|call| matches!(call, Call::Get(key) if key == k)).count()
+    }
+
+    /// Returns the underlying context, holding the entries written so far.
+    pub fn context(&self) -> &Context {
+        &self.data
+    }
+
+    /// Asserts that `k` was written exactly once.
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the calls made, if `k` was never written or written several times.
+    pub fn assert_inserted_once(&self, k: &str) {
+        let inserts = self.inserts(k);
+        if inserts != 1 {
+            panic!(
+                "expected key '{}' to be inserted once, it was inserted {} times, calls: {:?}",
+                k,
+                inserts,
+                self.calls()
+            );
+        }
+    }
+
+    /// Asserts that `k` was never written.
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the calls made, if `k` was written.
+    pub fn assert_not_inserted(&self, k: &str) {
+        let inserts = self.inserts(k);
+        if inserts != 0 {
+            panic!(
+                "expected key '{}' not to be inserted, it was inserted {} times, calls: {:?}",
+                k,
+                inserts,
+                self.calls()
+            );
+        }
+    }
+}
+
+/// Clones the entries and the calls recorded so far.
+impl Clone for RecordingContext {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            calls: Mutex::new(self.calls()),
+        }
+    }
+}
+
+impl Contextualize for RecordingContext {
+    fn new() -> Self {
+        let ctx = Self::default();
+        ctx.record(Call::New);
+        ctx
+    }
+
+    fn insert(&mut self, k: String, v: Value) {
+        self.record(Call::Insert(k.clone(), v.clone()));
+        self.data.insert(k, v);
+    }
+
+    fn get(&self, k: &str) -> Option<&Value> {
+        self.record(Call::Get(k.to_string()));
+        self.data.get(k)
+    }
+
+    fn extend(&mut self, data: BTreeMap<String, Value>) {
+        self.record(Call::Extend(data.clone()));
+        self.data.extend(data);
+    }
+
+    fn inner(&self) -> BTreeMap<String, Value> {
+        self.record(Call::Inner);
+        self.data.inner()
+    }
+
+    fn inner_ref(&self) -> Cow<'_, BTreeMap<String, Value>> {
+        self.record(Call::Inner);
+        self.data.inner_ref()
+    }
+
+    fn remove(&mut self, k: &str) -> Option<Value> {
+        self.record(Call::Remove(k.to_string()));
+        self.data.remove(k)
+    }
+
+    fn clear(&mut self) {
+        self.record(Call::Clear);
+        self.data.clear();
+    }
+
+    fn contains_key(&self, k: &str) -> bool {
+        self.record(Call::ContainsKey(k.to_string()));
+        self.data.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.record(Call::Len);
+        self.data.len()
+    }
+}
+
+/// Returns the entries without recording a call.
+impl ContextDump for RecordingContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.data.dump()
+    }
+}
+
+/// Serializes the entries like a [`Context`], without recording a call.
+impl Serialize for RecordingContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+/// A context whose serializers fail, see the [module documentation](self).
+///
+/// Entries are stored and read like in a [`Context`], but `to_json`, `to_toml`, `to_yaml`, `to_ron`
+/// and `to_xml` return the injected error, and so does [`Contextualize::save_to_file`], while the
+/// [`Serialize`] implementation fails with its message.
+#[derive(Debug, Clone)]
+pub struct FailingContext {
+    data: Context,
+    error: cdumay_core::Error,
+}
+
+impl FailingContext {
+    /// Creates an empty context whose serializers return `error`.
+    pub fn with_error(error: cdumay_core::Error) -> Self {
+        Self { data: Context::new(), error }
+    }
+
+    /// Returns the injected error.
+    pub fn error(&self) -> &cdumay_core::Error {
+        &self.error
+    }
+
+    /// Replaces the injected error.
+    pub fn set_error(&mut self, error: cdumay_core::Error) {
+        self.error = error;
+    }
+}
+
+impl Contextualize for FailingContext {
+    /// Creates an empty context whose serializers return a [`SerializationError`].
+    fn new() -> Self {
+        Self::with_error(
+            SerializationError::new()
+                .with_message("Injected serialization failure".to_string())
+                .into(),
+        )
+    }
+
+    fn insert(&mut self, k: String, v: Value) {
+        self.data.insert(k, v);
+    }
+
+    fn get(&self, k: &str) -> Option<&Value> {
+        self.data.get(k)
+    }
+
+    fn extend(&mut self, data: BTreeMap<String, Value>) {
+        self.data.extend(data);
+    }
+
+    fn inner(&self) -> BTreeMap<String, Value> {
+        self.data.inner()
+    }
+
+    fn inner_ref(&self) -> Cow<'_, BTreeMap<String, Value>> {
+        self.data.inner_ref()
+    }
+
+    fn remove(&mut self, k: &str) -> Option<Value> {
+        self.data.remove(k)
+    }
+
+    fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    fn contains_key(&self, k: &str) -> bool {
+        self.data.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the injected error.
+    #[cfg(feature = "json")]
+    fn to_json(&self, _pretty: bool) -> cdumay_core::Result<String> {
+        Err(self.error.clone())
+    }
+
+    /// Returns the injected error.
+    #[cfg(feature = "toml")]
+    fn to_toml(&self, _pretty: bool) -> cdumay_core::Result<String> {
+        Err(self.error.clone())
+    }
+
+    /// Returns the injected error.
+    #[cfg(feature = "yaml")]
+    fn to_yaml(&self) -> cdumay_core::Result<String> {
+        Err(self.error.clone())
+    }
+
+    /// Returns the injected error.
+    #[cfg(feature = "ron")]
+    fn to_ron(&self, _pretty: bool) -> cdumay_core::Result<String> {
+        Err(self.error.clone())
+    }
+
+    /// Returns the injected error.
+    #[cfg(feature = "xml")]
+    fn to_xml(&self, _root_tag: &str) -> cdumay_core::Result<String> {
+        Err(self.error.clone())
+    }
+}
+
+impl ContextDump for FailingContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.data.dump()
+    }
+}
+
+/// Fails with the message of the injected error.
+impl Serialize for FailingContext {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom(self.error.message()))
+    }
+}
+
+/// Asserts that a context holds the given entries, other keys being ignored.
+///
+/// The context is compared with [`Context::compare`], which describes how the values are compared,
+/// and [`MatchOptions::subset`](crate::MatchOptions::subset). The expected values are any
+/// `Serialize` values. On failure, the panic message is the [`MatchReport`](crate::MatchReport)
+/// listing each mismatching path:
+///
+/// ```text
+/// context does not match expected context: 1 mismatch(es):
+///   - user.id: expected I32(42), got String("42")
+/// ```
+///
+/// This macro is only available when the "test-utils" feature is enabled. The generated code
+/// refers to `serde_value`, which must be a dependency of the calling crate.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "test-utils")]
+/// # {
+/// use cdumay_context::{assert_context, Context};
+/// use std::collections::BTreeMap;
+///
+/// let ctx = Context::builder().with("status", 404u16).with("user", BTreeMap::from([("id", 42), ("age", 37)])).fixture();
+/// assert_context!(ctx, { "status" => 404, "user" => BTreeMap::from([("id", 42u64)]) });
+/// # }
+/// ```
+#[cfg(feature = "test-utils")]
+#[macro_export]
+macro_rules! assert_context {
+    ($ctx:expr, { $($key:expr => $value:expr),* $(,)? }) => {
+        $crate::Context::from($crate::Contextualize::inner(&$ctx)).assert_matches(
+            &$crate::Context::from(::std::collections::BTreeMap::from([$((
+                ::std::string::String::from($key),
+                ::serde_value::to_value(&$value).expect("expected context values must serialize"),
+            )),*])),
+            &$crate::MatchOptions::new().subset(),
+        )
+    };
+}
+
+/// Fixture helpers, building contexts in tests without handling errors.
+///
+/// These methods are only available when the "test-utils" feature is enabled.
+#[cfg(feature = "test-utils")]
+impl crate::ContextBuilder {
+    /// Builds the context.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error of [`ContextBuilder::build`](crate::ContextBuilder::build) if it
+    /// fails.
+    #[track_caller]
+    pub fn fixture(self) -> Context {
+        self.build()
+            .unwrap_or_else(|err| panic!("failed to build context fixture: {}", err.message()))
+    }
+
+    /// Builds a context of type `C` holding the entries of the builder, e.g. a
+    /// [`RecordingContext`] to test code generic over [`Contextualize`].
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error of [`ContextBuilder::build`](crate::ContextBuilder::build) if it
+    /// fails.
+    #[track_caller]
+    pub fn fixture_as<C: Contextualize>(self) -> C {
+        let mut ctx = C::new();
+        ctx.extend(self.fixture().inner());
+        ctx
+    }
+}
+
+/// Re-export of the version of `proptest` the strategies of this module are built with.
+#[cfg(feature = "test-utils")]
+pub use proptest;
+
+/// Bounds of the contexts generated by [`arb_context`].
+///
+/// This type is only available when the "test-utils" feature is enabled.
+#[cfg(feature = "test-utils")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitraryParams {
+    /// Maximum number of entries of a context.
+    pub max_entries: usize,
+    /// Maximum nesting depth of the maps and sequences.
+    pub max_depth: u32,
+    /// Maximum number of items of a map or sequence.
+    pub max_items: usize,
+    /// Whether nulls are generated. They cannot be dumped to TOML.
+    pub nulls: bool,
+}
+
+#[cfg(feature = "test-utils")]
+impl Default for ArbitraryParams {
+    /// Up to 8 entries, 3 levels of nesting and 4 items per map or sequence, nulls included.
+    fn default() -> Self {
+        Self {
+            max_entries: 8,
+            max_depth: 3,
+            max_items: 4,
+            nulls: true,
+        }
+    }
+}
+
+/// Returns a strategy generating keys: a lowercase ASCII letter followed by up to 11 lowercase
+/// letters, digits or underscores, valid in every format and as an XML element name.
+///
+/// This function is only available when the "test-utils" feature is enabled.
+#[cfg(feature = "test-utils")]
+pub fn arb_key() -> proptest::strategy::BoxedStrategy<String> {
+    use proptest::strategy::Strategy;
+    "[a-z][a-z0-9_]{0,11}".boxed()
+}
+
+/// Returns a strategy generating values within `params`: booleans, `i64` integers, finite `f64`
+/// floats, strings without control characters, nulls if allowed, and sequences and maps with
+/// string keys of those, so that the contexts generated can be dumped to every format.
+///
+/// This function is only available when the "test-utils" feature is enabled.
+#[cfg(feature = "test-utils")]
+pub fn arb_value(params: ArbitraryParams) -> proptest::strategy::BoxedStrategy<Value> {
+    use proptest::prelude::*;
+    let finite = prop::num::f64::POSITIVE | prop::num::f64::NEGATIVE | prop::num::f64::NORMAL | prop::num::f64::ZERO;
+    let scalar = prop_oneof![
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::I64),
+        finite.prop_map(Value::F64),
+        "\\PC{0,16}".prop_map(Value::String),
+    ];
+    let leaf = match params.nulls {
+        true => prop_oneof![4 => scalar, 1 => Just(Value::Unit)].boxed(),
+        false => scalar.boxed(),
+    };
+    let items = params.max_items;
+    leaf.prop_recursive(params.max_depth, (params.max_entries * items) as u32, items as u32, move |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..=items).prop_map(Value::Seq),
+            prop::collection::btree_map(arb_key().prop_map(Value::String), inner, 0..=items).prop_map(Value::Map),
+        ]
+    })
+    .boxed()
+}
+
+/// Returns a strategy generating contexts of type `C` within `params`, see [`arb_key`] and
+/// [`arb_value`].
+///
+/// This function is only available when the "test-utils" feature is enabled.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(all(feature = "test-utils", feature = "json"))]
+/// # {
+/// use cdumay_context::testing::proptest::prelude::*;
+/// use cdumay_context::testing::{arb_context, ArbitraryParams};
+/// use cdumay_context::{Context, Contextualize};
+///
+/// proptest!(|(ctx in arb_context::<Context>(ArbitraryParams::default()))| {
+///     let loaded = Context::from_json(&ctx.to_json(false).unwrap()).unwrap();
+///     loaded.assert_matches(&ctx, &cdumay_context::MatchOptions::new());
+/// });
+/// # }
+/// ```
+#[cfg(feature = "test-utils")]
+pub fn arb_context<C: Contextualize + std::fmt::Debug + 'static>(params: ArbitraryParams) -> proptest::strategy::BoxedStrategy<C> {
+    use proptest::prelude::*;
+    prop::collection::btree_map(arb_key(), arb_value(params), 0..=params.max_entries)
+        .prop_map(|entries| {
+            let mut ctx = C::new();
+            ctx.extend(entries);
+            ctx
+        })
+        .boxed()
+}
+
+/// Generates contexts with [`arb_context`], e.g. with `any::<Context>()` or
+/// `any_with::<Context>(params)`.
+#[cfg(feature = "test-utils")]
+impl proptest::arbitrary::Arbitrary for Context {
+    type Parameters = ArbitraryParams;
+    type Strategy = proptest::strategy::BoxedStrategy<Context>;
+
+    fn arbitrary_with(params: ArbitraryParams) -> Self::Strategy {
+        arb_context(params)
+    }
+}
@@ -0,0 +1,131 @@
+//! Time series of context snapshots.
+//!
+//! A [`ContextTimeline`] keeps periodic snapshots of a long-lived context, such as the
+//! [`SharedContext`] of a worker, to diagnose slow drifts of its state. The timeline holds a fixed
+//! number of samples: once full, every other sample is dropped and only one snapshot out of two
+//! is recorded from then on, so that the timeline always covers the whole life of the context at
+//! a decreasing resolution, whatever the sampling period.
+//!
+//! [`ContextTimeline::render`] shows the evolution of selected keys, one line per sample where
+//! any of them changed:
+//!
+//! ```rust
+//! use cdumay_context::{Context, ContextTimeline, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! let mut timeline = ContextTimeline::new(16);
+//! for (at, depth) in [(1000, 3u64), (2000, 3), (3000, 7)] {
+//!     ctx.insert("queue_depth".to_string(), Value::U64(depth));
+//!     timeline.record_at(at, &ctx);
+//! }
+//!
+//! assert_eq!(timeline.render(&["queue_depth"]), "1000 queue_depth=3\n3000 queue_depth=7\n");
+//! ```
+use crate::snapshot::write_value;
+use crate::{Context, Contextualize, SharedContext, SnapshotOptions};
+use serde_value::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A snapshot of a context, see [`ContextTimeline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// Time of the snapshot, in milliseconds since the Unix epoch.
+    pub at: u64,
+    /// Dump of the context.
+    pub data: BTreeMap<String, Value>,
+}
+
+/// Downsampled time series of context snapshots, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ContextTimeline {
+    capacity: usize,
+    stride: u64,
+    skipped: u64,
+    samples: VecDeque<Sample>,
+}
+
+impl ContextTimeline {
+    /// Creates a timeline holding at most `capacity` samples, at least 2.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            capacity,
+            stride: 1,
+            skipped: 0,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a snapshot of `ctx`, taken now.
+    pub fn record(&mut self, ctx: &Context) {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        self.record_at(u64::try_from(at).unwrap_or(u64::MAX), ctx);
+    }
+
+    /// Records a snapshot of `shared`, taken now.
+    pub fn record_shared(&mut self, shared: &SharedContext) {
+        self.record(&shared.snapshot());
+    }
+
+    /// Records a snapshot of `ctx` taken at `at`, in milliseconds since the Unix epoch.
+    ///
+    /// Only one snapshot out of [`ContextTimeline::stride`] is kept.
+    pub fn record_at(&mut self, at: u64, ctx: &Context) {
+        if self.skipped + 1 < self.stride {
+            self.skipped += 1;
+            return;
+        }
+        self.skipped = 0;
+        if self.samples.len() == self.capacity {
+            self.samples = std::mem::take(&mut self.samples).into_iter().step_by(2).collect();
+            self.stride *= 2;
+        }
+        self.samples.push_back(Sample { at, data: ctx.inner() });
+    }
+
+    /// Returns the number of snapshots recorded for each one kept.
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Returns the samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    /// Returns the values of `k` over time, oldest first.
+    pub fn series(&self, k: &str) -> Vec<(u64, Option<&Value>)> {
+        self.samples.iter().map(|sample| (sample.at, sample.data.get(k))).collect()
+    }
+
+    /// Renders the evolution of `keys`, one line per sample where any of them changed.
+    ///
+    /// Each line starts with the time of the sample followed by the changed `key=value` pairs,
+    /// values in compact JSON and missing ones as `-`.
+    pub fn render(&self, keys: &[&str]) -> String {
+        let options = SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+        let mut out = String::new();
+        let mut previous: Option<&Sample> = None;
+        for sample in &self.samples {
+            let changed: Vec<String> = keys
+                .iter()
+                .filter(|k| previous.is_none_or(|previous| previous.data.get(**k) != sample.data.get(**k)))
+                .map(|k| match sample.data.get(*k) {
+                    Some(value) => {
+                        let mut rendered = String::new();
+                        write_value(&mut rendered, "", value, &options, 0);
+                        format!("{}={}", k, rendered.lines().map(str::trim).collect::<Vec<_>>().join(" "))
+                    }
+                    None => format!("{}=-", k),
+                })
+                .collect();
+            if !changed.is_empty() {
+                out.push_str(&format!("{} {}\n", sample.at, changed.join(" ")));
+            }
+            previous = Some(sample);
+        }
+        out
+    }
+}
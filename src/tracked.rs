@@ -0,0 +1,236 @@
+//! Audit history of long-lived contexts.
+//!
+//! A [`TrackedContext`] wraps a [`Context`] and records every insert, removal and extension made
+//! through it as an ordered changelog of [`Change`]s, each with the time it was made, in
+//! milliseconds since the Unix epoch, the old and new values and the actor label set when it was
+//! made, if any. Writes left without effect, on protected or frozen keys or on a sealed context,
+//! are not recorded.
+//!
+//! A tracked context serializes as a map holding the current `state` and the full `history`, and
+//! deserializes back from it, so that the history is kept alongside the state it led to. The
+//! dumps of [`Contextualize`], such as [`Contextualize::to_json`], only hold the current state.
+//!
+//! ```rust
+//! use cdumay_context::{ChangeOp, Context, Contextualize, TrackedContext};
+//! use serde_value::Value;
+//!
+//! let mut ctx = TrackedContext::from(Context::new()).with_actor("billing");
+//! ctx.insert("state".to_string(), Value::String("running".to_string()));
+//! ctx.set_actor("scheduler");
+//! ctx.insert("state".to_string(), Value::String("done".to_string()));
+//!
+//! assert_eq!(ctx.history().len(), 2);
+//! let changes = ctx.history_for("state");
+//! let last = changes.last().unwrap();
+//! assert_eq!(last.op, ChangeOp::Insert);
+//! assert_eq!(last.actor.as_deref(), Some("scheduler"));
+//! assert_eq!(last.old, Some(Value::String("running".to_string())));
+//! ```
+use crate::{Context, ContextDump, Contextualize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Operation recorded by a [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOp {
+    /// The key was set with [`Contextualize::insert`].
+    Insert,
+    /// The key was set with [`Contextualize::extend`].
+    Extend,
+    /// The key was removed with [`Contextualize::remove`] or [`Contextualize::clear`].
+    Remove,
+}
+
+/// An entry of the changelog of a [`TrackedContext`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// Time the change was made, in milliseconds since the Unix epoch.
+    pub at: u64,
+    /// Actor label set when the change was made, see [`TrackedContext::set_actor`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Operation which made the change.
+    pub op: ChangeOp,
+    /// Key changed, normalized with the key policy of the context.
+    pub key: String,
+    /// Value before the change, `None` if the key had no value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    /// Value after the change, `None` for removals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// A context recording the history of its changes, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct TrackedContext {
+    context: Context,
+    history: Vec<Change>,
+    actor: Option<String>,
+}
+
+impl TrackedContext {
+    /// Sets the actor label recorded with the next changes.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.set_actor(actor);
+        self
+    }
+
+    /// Sets the actor label recorded with the next changes, see [`TrackedContext::with_actor`].
+    pub fn set_actor(&mut self, actor: impl Into<String>) {
+        self.actor = Some(actor.into());
+    }
+
+    /// Stops recording an actor label with the next changes.
+    pub fn clear_actor(&mut self) {
+        self.actor = None;
+    }
+
+    /// Returns the actor label recorded with the next changes, if any.
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Returns the recorded changes, oldest first.
+    pub fn history(&self) -> &[Change] {
+        &self.history
+    }
+
+    /// Returns the recorded changes of `k`, oldest first.
+    pub fn history_for(&self, k: &str) -> Vec<&Change> {
+        let k = self.context.resolve_key(k);
+        self.history.iter().filter(|change| change.key == k).collect()
+    }
+
+    /// Returns the wrapped context.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Unwraps the context, dropping its history.
+    pub fn into_context(self) -> Context {
+        self.context
+    }
+
+    /// Inserts `v` under `k`, recording the change if it took effect.
+    fn write(&mut self, op: ChangeOp, k: String, v: Value) {
+        let key = self.context.resolve_key(&k).into_owned();
+        let writable = !self.context.is_locked(&key);
+        let old = self.context.get(&key).cloned();
+        self.context.insert(k, v.clone());
+        if writable {
+            self.record(op, key, old, Some(v));
+        }
+    }
+
+    fn record(&mut self, op: ChangeOp, key: String, old: Option<Value>, new: Option<Value>) {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        self.history.push(Change {
+            at: u64::try_from(at).unwrap_or(u64::MAX),
+            actor: self.actor.clone(),
+            op,
+            key,
+            old,
+            new,
+        });
+    }
+}
+
+/// Tracks the changes made to `context` from now on.
+impl From<Context> for TrackedContext {
+    fn from(context: Context) -> Self {
+        Self { context, ..Self::default() }
+    }
+}
+
+impl Context {
+    /// Wraps the context into a [`TrackedContext`], recording its changes from now on.
+    pub fn into_tracked(self) -> TrackedContext {
+        TrackedContext::from(self)
+    }
+}
+
+impl Contextualize for TrackedContext {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, k: String, v: Value) {
+        self.write(ChangeOp::Insert, k, v);
+    }
+
+    fn get(&self, k: &str) -> Option<&Value> {
+        self.context.get(k)
+    }
+
+    /// Sets each key in order, recording one change per key.
+    fn extend(&mut self, data: BTreeMap<String, Value>) {
+        for (k, v) in data {
+            self.write(ChangeOp::Extend, k, v);
+        }
+    }
+
+    fn inner(&self) -> BTreeMap<String, Value> {
+        self.context.inner()
+    }
+
+    fn error_details(&self) -> BTreeMap<String, Value> {
+        self.context.error_details()
+    }
+
+    fn remove(&mut self, k: &str) -> Option<Value> {
+        let key = self.context.resolve_key(k).into_owned();
+        let value = self.context.remove(&key)?;
+        self.record(ChangeOp::Remove, key, Some(value.clone()), None);
+        Some(value)
+    }
+
+    /// Removes the keys one by one, recording one change per key.
+    fn clear(&mut self) {
+        for k in self.context.inner().into_keys() {
+            self.remove(&k);
+        }
+    }
+
+    fn contains_key(&self, k: &str) -> bool {
+        self.context.contains_key(k)
+    }
+}
+
+impl ContextDump for TrackedContext {
+    fn dump(&self) -> BTreeMap<String, Value> {
+        self.inner()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Audited {
+    state: BTreeMap<String, Value>,
+    history: Vec<Change>,
+}
+
+/// Serializes the current state and the full history, as `state` and `history`.
+impl Serialize for TrackedContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Audited {
+            state: self.inner(),
+            history: self.history.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Restores a tracked context serialized with its history, without any actor label set.
+impl<'de> Deserialize<'de> for TrackedContext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let audited = Audited::deserialize(deserializer)?;
+        Ok(Self {
+            context: Context::from(audited.state),
+            history: audited.history,
+            actor: None,
+        })
+    }
+}
@@ -0,0 +1,173 @@
+//! Value transformers applied to dumps and serializations.
+//!
+//! A [`ValueTransformer`] rewrites values on their way out of a context, to centralize output
+//! policies such as encoding bytes or rounding floats. Transformers registered with
+//! [`Context::add_transformer`] apply to every dump and serialization of the context: they run
+//! in [`Contextualize::inner`], on which [`ContextDump::dump`](crate::ContextDump::dump), serde serialization and the
+//! `to_json`, `to_toml` and `to_yaml` helpers rely. [`Context::dump_with`] applies a transformer
+//! for a single call only.
+//!
+//! Transformers receive the dotted path of each value, top-level entries first, then the items of
+//! the maps and sequences they return. Any closure `Fn(&str, Value) -> Value` is a transformer:
+//!
+//! ```rust
+//! use cdumay_context::{Base64Bytes, Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("payload".to_string(), Value::Bytes(b"hi".to_vec()));
+//! ctx.insert("password".to_string(), Value::String("hunter2".to_string()));
+//! ctx.add_transformer(Base64Bytes);
+//! ctx.add_transformer(|path: &str, value: Value| match path {
+//!     "password" => Value::String("***".to_string()),
+//!     _ => value,
+//! });
+//!
+//! assert_eq!(ctx.inner().get("payload"), Some(&Value::String("aGk=".to_string())));
+//! assert_eq!(ctx.inner().get("password"), Some(&Value::String("***".to_string())));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Rewrites values when a context is dumped or serialized.
+pub trait ValueTransformer: Send + Sync {
+    /// Returns the value to output in place of `value`, found at the dotted `path`.
+    fn transform(&self, path: &str, value: Value) -> Value;
+}
+
+impl<F> ValueTransformer for F
+where
+    F: Fn(&str, Value) -> Value + Send + Sync,
+{
+    fn transform(&self, path: &str, value: Value) -> Value {
+        self(path, value)
+    }
+}
+
+/// Encodes `Value::Bytes` as standard base64 strings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base64Bytes;
+
+impl ValueTransformer for Base64Bytes {
+    fn transform(&self, _path: &str, value: Value) -> Value {
+        match value {
+            Value::Bytes(bytes) => Value::String(base64(&bytes)),
+            other => other,
+        }
+    }
+}
+
+/// Rounds floats to the given number of decimals.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundFloats(pub u32);
+
+impl ValueTransformer for RoundFloats {
+    fn transform(&self, _path: &str, value: Value) -> Value {
+        let factor = 10f64.powi(self.0 as i32);
+        match value {
+            Value::F32(v) => Value::F32(((v as f64 * factor).round() / factor) as f32),
+            Value::F64(v) => Value::F64((v * factor).round() / factor),
+            other => other,
+        }
+    }
+}
+
+/// Transformers registered on a context.
+#[derive(Clone, Default)]
+pub(crate) struct Transformers(Vec<Arc<dyn ValueTransformer>>);
+
+impl std::fmt::Debug for Transformers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transformers({})", self.0.len())
+    }
+}
+
+impl Transformers {
+    /// Returns `true` if no transformer is registered.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the transformers to every entry of `data`.
+    pub(crate) fn apply(&self, data: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        match self.0.is_empty() {
+            true => data,
+            false => {
+                let transformers: Vec<&dyn ValueTransformer> = self.0.iter().map(|t| t.as_ref()).collect();
+                transform_map(data, &transformers)
+            }
+        }
+    }
+}
+
+fn transform_map(data: BTreeMap<String, Value>, transformers: &[&dyn ValueTransformer]) -> BTreeMap<String, Value> {
+    data.into_iter().map(|(k, v)| (k.clone(), transform(&k, v, transformers))).collect()
+}
+
+fn transform(path: &str, value: Value, transformers: &[&dyn ValueTransformer]) -> Value {
+    match transformers.iter().fold(value, |value, t| t.transform(path, value)) {
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let child = format!("{}.{}", path, key_path(&k));
+                    (k, transform(&child, v, transformers))
+                })
+                .collect(),
+        ),
+        Value::Seq(items) => Value::Seq(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(index, v)| transform(&format!("{}.{}", path, index), v, transformers))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn key_path(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Standard base64 alphabet.
+pub(crate) const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `bytes` as standard, padded base64.
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
+
+impl Context {
+    /// Registers a transformer applied to every dump and serialization of this context.
+    ///
+    /// Transformers run in registration order.
+    pub fn add_transformer(&mut self, transformer: impl ValueTransformer + 'static) {
+        self.transformers.0.push(Arc::new(transformer));
+    }
+
+    /// Removes every registered transformer.
+    pub fn clear_transformers(&mut self) {
+        self.transformers.0.clear();
+    }
+
+    /// Dumps the context, applying `transformer` after the registered ones.
+    pub fn dump_with(&self, transformer: &dyn ValueTransformer) -> BTreeMap<String, Value> {
+        transform_map(self.inner(), &[transformer])
+    }
+}
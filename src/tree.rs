@@ -0,0 +1,284 @@
+//! Fork trees of contexts.
+//!
+//! Contexts record the id of the context they were [forked](Context::fork) from, not their
+//! children: [`Context::export_tree`] rebuilds the tree of a set of contexts, such as a request
+//! context and the contexts of the sub-tasks it spawned, from their `parent_id`. Each node holds
+//! the id and the dump of a context, with the redaction of its configuration applied, and the
+//! tree renders as JSON or as a Graphviz DOT graph of the forks and propagation hops, see
+//! [`DotOptions`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, DotOptions};
+//! use serde_value::Value;
+//!
+//! let mut request = Context::new();
+//! request.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! let mut upload = request.fork();
+//! upload.insert("task".to_string(), Value::String("upload".to_string()));
+//! let mut thumbnail = upload.fork();
+//! thumbnail.insert("size".to_string(), Value::U16(128));
+//!
+//! let tree = Context::export_tree([&thumbnail, &request, &upload]);
+//! assert_eq!(tree.roots.len(), 1);
+//! assert_eq!(tree.roots[0].id, request.id());
+//! assert_eq!(tree.roots[0].children[0].children[0].id, thumbnail.id());
+//! let dot = tree.to_dot_with(&DotOptions::new().with_labels(&["task", "size"]));
+//! assert!(dot.contains(&format!("\"{}\" -> \"{}\" [label=\"fork\"];", request.id(), upload.id())));
+//! ```
+use crate::provenance::hops_of;
+use crate::snapshot::glob_match;
+use crate::{Context, Contextualize, Hop, ValueRef, PARENT_ID_KEY, PROPAGATION_KEY};
+use serde::Serialize;
+use serde_value::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Maximum number of characters of a value in DOT labels.
+const DOT_VALUE_LEN: usize = 40;
+
+/// Tree of contexts built by [`Context::export_tree`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextTree {
+    /// Contexts whose parent is not part of the tree.
+    pub roots: Vec<ContextNode>,
+}
+
+/// Context of a [`ContextTree`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextNode {
+    /// Id of the context, see [`Context::id`].
+    pub id: String,
+    /// Id of the context it was forked from, if any, even if not part of the tree.
+    pub parent_id: Option<String>,
+    /// Dump of the context.
+    pub entries: BTreeMap<String, Value>,
+    /// Contexts forked from this one.
+    pub children: Vec<ContextNode>,
+}
+
+impl Context {
+    /// Returns the fork tree of `contexts`.
+    ///
+    /// Roots and children are listed in the order of `contexts`. A context whose parent is not in
+    /// `contexts` is a root, as is a context caught in a cycle of `parent_id`, which only happens
+    /// if the key was set by hand. Contexts sharing an id, such as clones, appear once.
+    pub fn export_tree<'a>(contexts: impl IntoIterator<Item = &'a Context>) -> ContextTree {
+        let mut nodes: Vec<ContextNode> = Vec::new();
+        for ctx in contexts {
+            if nodes.iter().all(|node| node.id != ctx.id()) {
+                nodes.push(ctx.tree_node());
+            }
+        }
+        let ids: BTreeSet<String> = nodes.iter().map(|node| node.id.clone()).collect();
+        let mut children: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut roots = Vec::new();
+        for (index, node) in nodes.iter().enumerate() {
+            match node.parent_id.as_ref().filter(|parent| ids.contains(*parent)) {
+                Some(parent) => children.entry(parent.clone()).or_default().push(index),
+                None => roots.push(index),
+            }
+        }
+        let mut slots: Vec<Option<ContextNode>> = nodes.into_iter().map(Some).collect();
+        let mut tree = Vec::new();
+        for index in roots {
+            if let Some(node) = take_subtree(index, &mut slots, &children) {
+                tree.push(node);
+            }
+        }
+        for index in 0..slots.len() {
+            if let Some(node) = take_subtree(index, &mut slots, &children) {
+                tree.push(node);
+            }
+        }
+        ContextTree { roots: tree }
+    }
+
+    fn tree_node(&self) -> ContextNode {
+        let config = self.effective_config(None);
+        ContextNode {
+            id: self.id().to_string(),
+            parent_id: self.parent_id().map(str::to_string),
+            entries: self
+                .inner()
+                .into_iter()
+                .filter_map(|(k, v)| config.apply(&k, v).map(|v| (k, v)))
+                .collect(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Moves the node at `index` and its descendants out of `slots`, `None` if already moved.
+fn take_subtree(index: usize, slots: &mut [Option<ContextNode>], children: &BTreeMap<String, Vec<usize>>) -> Option<ContextNode> {
+    let mut node = slots[index].take()?;
+    if let Some(indexes) = children.get(&node.id) {
+        node.children = indexes.iter().filter_map(|child| take_subtree(*child, slots, children)).collect();
+    }
+    Some(node)
+}
+
+impl ContextTree {
+    /// Returns the number of contexts in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes().len()
+    }
+
+    /// Returns `true` if the tree holds no context.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns the context with the given id, if any.
+    pub fn find(&self, id: &str) -> Option<&ContextNode> {
+        self.nodes().into_iter().find(|node| node.id == id)
+    }
+
+    /// Returns the contexts of the tree, each one before its children.
+    pub fn nodes(&self) -> Vec<&ContextNode> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<&ContextNode> = self.roots.iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            stack.extend(node.children.iter().rev());
+        }
+        nodes
+    }
+
+    /// Serializes the tree to a JSON string.
+    ///
+    /// This method is only available when the "json" feature is enabled.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, pretty: bool) -> cdumay_core::Result<String> {
+        use cdumay_core::ErrorConverter;
+        let dump = match pretty {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        };
+        dump.map_err(|err| cdumay_json::JsonErrorConverter::convert_error(&err, Some("Failed to dump context tree".to_string()), BTreeMap::new()))
+    }
+
+    /// Renders the tree as a Graphviz DOT graph, with the default [`DotOptions`].
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::new())
+    }
+
+    /// Renders the tree as a Graphviz DOT graph.
+    ///
+    /// Each context is a box labelled with its id and entries, values being cut to 40
+    /// characters, linked to its parent by a `fork` edge. Hops recorded since the parent, or all
+    /// the hops of roots, are ellipses labelled with the service, chained between the parent and
+    /// the context, the edge entering a hop being labelled with its transport.
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        let mut dot = String::from("digraph contexts {\n    node [shape=box];\n");
+        let mut stack: Vec<(&ContextNode, Option<&ContextNode>)> = self.roots.iter().rev().map(|node| (node, None)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let mut label = escape(&node.id);
+            label.push_str("\\l");
+            for (k, v) in node.entries.iter().filter(|(k, v)| options.is_label(k, v, parent)) {
+                let _ = write!(label, "{} = {}\\l", escape(k), escape(&dot_value(v)));
+            }
+            let id = escape(&node.id);
+            let _ = writeln!(dot, "    \"{}\" [label=\"{}\"];", id, label);
+            let mut previous = parent.map(|parent| (escape(&parent.id), "label=\"fork\"".to_string()));
+            if options.hops {
+                let inherited = parent.map(ContextNode::hops).unwrap_or_default();
+                let hops = node.hops().into_iter().filter(|hop| !inherited.contains(hop));
+                for (index, hop) in hops.enumerate() {
+                    let hop_id = format!("{}/hop/{}", id, index);
+                    let _ = writeln!(dot, "    \"{}\" [shape=ellipse, label=\"{}\"];", hop_id, escape(&hop.service));
+                    if let Some((from, _)) = previous {
+                        let _ = writeln!(dot, "    \"{}\" -> \"{}\" [label=\"{}\"];", from, hop_id, escape(&hop.transport));
+                    }
+                    previous = Some((hop_id, "style=dashed".to_string()));
+                }
+            }
+            if let Some((from, attributes)) = previous {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\" [{}];", from, id, attributes);
+            }
+            stack.extend(node.children.iter().rev().map(|child| (child, Some(node))));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl ContextNode {
+    /// Returns the hops recorded in the context, see [`Context::hops`].
+    pub fn hops(&self) -> Vec<Hop> {
+        hops_of(ValueRef::new(self.entries.get(PROPAGATION_KEY)))
+    }
+}
+
+/// How [`ContextTree::to_dot_with`] renders a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotOptions {
+    labels: Option<Vec<String>>,
+    hops: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DotOptions {
+    /// Creates options labelling contexts with the entries added or changed since their parent,
+    /// or all the entries for roots, and rendering hops.
+    pub fn new() -> Self {
+        Self { labels: None, hops: true }
+    }
+
+    /// Labels contexts with the entries whose key matches one of `patterns`, which may contain
+    /// `*` wildcards, whether they changed since the parent or not.
+    pub fn with_labels(mut self, patterns: &[&str]) -> Self {
+        self.labels.get_or_insert_with(Vec::new).extend(patterns.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Renders the hops of contexts, `true` by default.
+    pub fn with_hops(mut self, hops: bool) -> Self {
+        self.hops = hops;
+        self
+    }
+
+    fn is_label(&self, k: &str, v: &Value, parent: Option<&ContextNode>) -> bool {
+        match &self.labels {
+            Some(patterns) => patterns.iter().any(|pattern| glob_match(pattern, k)),
+            None => k != PARENT_ID_KEY && k != PROPAGATION_KEY && parent.is_none_or(|parent| parent.entries.get(k) != Some(v)),
+        }
+    }
+}
+
+/// Renders `value` on a single line.
+fn dot_value(value: &Value) -> String {
+    let rendered = match value {
+        Value::String(s) => format!("{:?}", s),
+        Value::Char(c) => format!("{:?}", c),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Unit | Value::Option(None) => "null".to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return dot_value(inner),
+        Value::Seq(items) => format!("[{} items]", items.len()),
+        Value::Map(map) => format!("{{{} entries}}", map.len()),
+        Value::Bytes(bytes) => format!("<{} bytes>", bytes.len()),
+    };
+    match rendered.chars().count() > DOT_VALUE_LEN {
+        true => format!("{}...", rendered.chars().take(DOT_VALUE_LEN).collect::<String>()),
+        false => rendered,
+    }
+}
+
+/// Escapes `text` for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,87 @@
+//! Size-aware dumps for transports with size limits.
+//!
+//! Headers, baggage and message brokers limit the size of what they carry, and intermediaries
+//! silently truncate oversized values. [`Context::dump_within`] returns a dump whose encoded size
+//! fits a limit, dropping the lowest-priority entries first: the last ones in the order set by
+//! [`Context::set_priority`] and [`Context::set_key_order`], so
+//! [`KeyOrder::Priority`](crate::KeyOrder::Priority) lists the keys to keep. Keys with the
+//! [`Priority::Critical`](crate::Priority::Critical) priority are never dropped. The names of the dropped keys are recorded under `propagation.dropped`, so the
+//! receiver knows the context is incomplete. The JSON, TOML and YAML dumps apply the same policy
+//! with [`ContextConfig::with_max_size`](crate::ContextConfig::with_max_size), and so do HTTP
+//! headers, see `Context::to_headers_within`.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, KeyOrder};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("payload".to_string(), Value::String("x".repeat(1000)));
+//! ctx.set_key_order(KeyOrder::Priority(vec!["request_id".to_string()]));
+//!
+//! // The size is measured by the encoder of the transport, here the Debug representation.
+//! let encoded_len = |dump: &_| format!("{:?}", dump).len();
+//! let dump = ctx.dump_within(200, encoded_len);
+//! assert!(dump.contains_key("request_id"));
+//! assert!(!dump.contains_key("payload"));
+//! ```
+use crate::{Context, Priority, ValueRef, PROPAGATION_KEY};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+const DROPPED_KEY: &str = "dropped";
+
+impl Context {
+    /// Returns the dump of the context, without its lowest-priority entries if its size, as
+    /// measured by `encoded_len`, exceeds `max_len`.
+    ///
+    /// The [`PROPAGATION_KEY`] entry and critical keys are never dropped. If the context does not
+    /// fit even without any other entry, the returned dump is still larger than `max_len`.
+    pub fn dump_within<F>(&self, max_len: usize, encoded_len: F) -> BTreeMap<String, Value>
+    where
+        F: Fn(&BTreeMap<String, Value>) -> usize,
+    {
+        self.trim_entries(self.ordered_entries(), max_len, |entries| encoded_len(&entries.iter().cloned().collect()))
+            .into_iter()
+            .collect()
+    }
+
+    /// Drops the last of `entries` until their size, as measured by `encoded_len`, fits
+    /// `max_len`, keeping the [`PROPAGATION_KEY`] entry, at its position, and critical keys.
+    pub(crate) fn trim_entries<F>(&self, mut kept: Vec<(String, Value)>, max_len: usize, encoded_len: F) -> Vec<(String, Value)>
+    where
+        F: Fn(&[(String, Value)]) -> usize,
+    {
+        let position = kept.iter().position(|(k, _)| k == PROPAGATION_KEY);
+        let propagation = position.map(|index| kept.remove(index).1);
+        let critical = kept.iter().take_while(|(k, _)| self.priority(k) == Priority::Critical).count();
+        let mut dropped: Vec<Value> = Vec::new();
+        loop {
+            let mut entries = kept.clone();
+            if let Some(propagation) = with_dropped(propagation.as_ref(), &dropped) {
+                let index = position.unwrap_or(entries.len()).min(entries.len());
+                entries.insert(index, (PROPAGATION_KEY.to_string(), propagation));
+            }
+            if kept.len() == critical || encoded_len(&entries) <= max_len {
+                return entries;
+            }
+            if let Some((k, _)) = kept.pop() {
+                crate::metrics::record(|metrics| metrics.on_eviction(&k));
+                dropped.insert(0, Value::String(k));
+            }
+        }
+    }
+}
+
+/// Returns the propagation entry with the `dropped` keys appended, if any.
+fn with_dropped(propagation: Option<&Value>, dropped: &[Value]) -> Option<Value> {
+    if dropped.is_empty() {
+        return propagation.cloned();
+    }
+    let mut map = ValueRef::new(propagation).as_map().cloned().unwrap_or_default();
+    let key = Value::String(DROPPED_KEY.to_string());
+    let mut all = ValueRef::new(map.get(&key)).as_seq().map(<[Value]>::to_vec).unwrap_or_default();
+    all.extend_from_slice(dropped);
+    map.insert(key, Value::Seq(all));
+    Some(Value::Map(map))
+}
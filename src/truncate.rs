@@ -0,0 +1,121 @@
+//! Bounded dumps for error details.
+//!
+//! A context attached to every error may carry huge values, such as request bodies or base64
+//! blobs, and produce multi-megabyte error payloads. [`Context::dump_truncated`] returns a dump
+//! bounded by [`DumpLimits`]:
+//!
+//! - strings and bytes longer than the value limit are replaced by a `"<truncated N bytes>"`
+//!   marker, `N` being their length;
+//! - maps and sequences nested deeper than the depth limit are replaced by a
+//!   `"<truncated N items>"` marker, `N` being their number of items;
+//! - only the first entries are kept, in the order set by [`Context::set_priority`] and
+//!   [`Context::set_key_order`], the number of dropped entries being recorded as a
+//!   `"<truncated N entries>"` marker under [`TRUNCATED_KEY`].
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize, DumpLimits, KeyNotFound};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+//! ctx.insert("body".to_string(), Value::String("x".repeat(1048576)));
+//!
+//! let limits = DumpLimits::new().with_max_value_len(1024).with_max_entries(50).with_max_depth(4);
+//! let err = KeyNotFound::new().with_details(ctx.dump_truncated(&limits));
+//! assert_eq!(err.details().get("body"), Some(&Value::String("<truncated 1048576 bytes>".to_string())));
+//! assert_eq!(err.details().get("request_id"), Some(&Value::String("abc".to_string())));
+//! ```
+use crate::Context;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Key under which a truncated dump records the number of dropped entries.
+pub const TRUNCATED_KEY: &str = crate::keys::TRUNCATED;
+
+/// Limits of a dump, see [`Context::dump_truncated`]. Every limit is unset by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DumpLimits {
+    max_value_len: Option<usize>,
+    max_entries: Option<usize>,
+    max_depth: Option<usize>,
+}
+
+impl DumpLimits {
+    /// Creates limits leaving every dimension unbounded.
+    pub const fn new() -> Self {
+        Self {
+            max_value_len: None,
+            max_entries: None,
+            max_depth: None,
+        }
+    }
+
+    /// Limits strings and bytes, at any depth, to `bytes`.
+    pub fn with_max_value_len(mut self, bytes: usize) -> Self {
+        self.max_value_len = Some(bytes);
+        self
+    }
+
+    /// Limits the number of top-level entries, the [`TRUNCATED_KEY`] marker not being counted.
+    pub fn with_max_entries(mut self, entries: usize) -> Self {
+        self.max_entries = Some(entries);
+        self
+    }
+
+    /// Limits the number of nested maps and sequences, a top-level map or sequence counting as
+    /// one level: with `0`, every map and sequence is replaced by a marker.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Returns the limit of strings and bytes, if any.
+    pub fn max_value_len(&self) -> Option<usize> {
+        self.max_value_len
+    }
+
+    /// Returns the limit of top-level entries, if any.
+    pub fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    /// Returns the limit of nested maps and sequences, if any.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn bound(&self, value: Value, depth: Option<usize>) -> Value {
+        match value {
+            Value::String(s) if self.max_value_len.is_some_and(|max| s.len() > max) => marker(s.len(), "bytes"),
+            Value::Bytes(b) if self.max_value_len.is_some_and(|max| b.len() > max) => marker(b.len(), "bytes"),
+            Value::Seq(items) if depth == Some(0) => marker(items.len(), "items"),
+            Value::Map(map) if depth == Some(0) => marker(map.len(), "items"),
+            Value::Seq(items) => Value::Seq(items.into_iter().map(|item| self.bound(item, depth.map(|d| d - 1))).collect()),
+            Value::Map(map) => Value::Map(map.into_iter().map(|(k, v)| (k, self.bound(v, depth.map(|d| d - 1)))).collect()),
+            Value::Option(Some(inner)) => Value::Option(Some(Box::new(self.bound(*inner, depth)))),
+            Value::Newtype(inner) => Value::Newtype(Box::new(self.bound(*inner, depth))),
+            other => other,
+        }
+    }
+}
+
+impl Context {
+    /// Returns the dump of the context bounded by `limits`, see the
+    /// [module documentation](self).
+    pub fn dump_truncated(&self, limits: &DumpLimits) -> BTreeMap<String, Value> {
+        let mut entries = self.ordered_entries();
+        let dropped = match limits.max_entries {
+            Some(max) if entries.len() > max => entries.split_off(max).len(),
+            _ => 0,
+        };
+        let mut dump: BTreeMap<String, Value> = entries.into_iter().map(|(k, v)| (k, limits.bound(v, limits.max_depth))).collect();
+        if dropped > 0 {
+            dump.insert(TRUNCATED_KEY.to_string(), marker(dropped, "entries"));
+        }
+        dump
+    }
+}
+
+fn marker(len: usize, unit: &str) -> Value {
+    Value::String(format!("<truncated {} {}>", len, unit))
+}
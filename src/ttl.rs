@@ -0,0 +1,91 @@
+//! Entries with a time to live.
+//!
+//! [`Context::insert_with_ttl`] stores an entry which expires once its time to live has elapsed:
+//! it is then left out of [`Contextualize::get`], [`Contextualize::contains_key`], dumps and
+//! serializations, as if it had been removed, so that the stale keys of a long-lived context do
+//! not linger. Expired entries keep their memory until [`Context::purge_expired`] removes them.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//! use std::time::Duration;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("worker".to_string(), Value::String("w-1".to_string()));
+//! ctx.insert_with_ttl("last_job_id".to_string(), Value::U64(42), Duration::ZERO);
+//! assert!(ctx.get("last_job_id").is_none());
+//! assert_eq!(ctx.len(), 1);
+//! assert_eq!(ctx.purge_expired(), vec!["last_job_id".to_string()]);
+//! ```
+//!
+//! Inserting a value again, without a time to live, makes the entry permanent.
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+impl Context {
+    /// Inserts a key-value pair which expires once `ttl` has elapsed, see the
+    /// [module documentation](crate::ttl).
+    ///
+    /// Like [`Contextualize::insert`], values stored under protected keys are left untouched.
+    pub fn insert_with_ttl(&mut self, k: String, v: Value, ttl: Duration) {
+        let key = self.resolve_key(&k).into_owned();
+        if self.is_locked(&key) {
+            return;
+        }
+        self.insert(k, v);
+        if self.data.contains_key(&key) {
+            match Instant::now().checked_add(ttl) {
+                Some(expiry) => self.ttls.insert(key, expiry),
+                None => self.ttls.remove(&key),
+            };
+        }
+    }
+
+    /// Returns the time left before the entry under `k` expires, `Duration::ZERO` once expired, or
+    /// `None` if the entry has no time to live.
+    pub fn expires_in(&self, k: &str) -> Option<Duration> {
+        let expiry = self.ttls.get(self.resolve_key(k).as_ref())?;
+        Some(expiry.saturating_duration_since(Instant::now()))
+    }
+
+    /// Removes the expired entries, returning their keys.
+    ///
+    /// Expired sections, see [`Context::section_with_ttl`], are removed by
+    /// [`Context::prune_expired_sections`].
+    pub fn purge_expired(&mut self) -> Vec<String> {
+        let expired = self.expired_entries();
+        for k in &expired {
+            self.remove(k);
+            self.ttls.remove(k);
+        }
+        expired
+    }
+
+    /// Returns the keys of the expired entries.
+    pub(crate) fn expired_entries(&self) -> Vec<String> {
+        if self.ttls.is_empty() {
+            return Vec::new();
+        }
+        let now = Instant::now();
+        self.ttls
+            .iter()
+            .filter(|(k, expiry)| **expiry <= now && self.contains(k))
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Returns `true` if the entry under the normalized key `k` has expired.
+    pub(crate) fn is_expired_entry(&self, k: &str) -> bool {
+        self.ttls.get(k).is_some_and(|expiry| *expiry <= Instant::now())
+    }
+
+    /// Removes the expired entries from `data`.
+    pub(crate) fn drop_expired_entries(&self, data: &mut BTreeMap<String, Value>) {
+        if !self.ttls.is_empty() {
+            let now = Instant::now();
+            data.retain(|k, _| self.ttls.get(k).is_none_or(|expiry| *expiry > now));
+        }
+    }
+}
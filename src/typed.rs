@@ -0,0 +1,99 @@
+//! Typed accessors.
+//!
+//! [`Contextualize::get_str`], [`Contextualize::get_i64`], [`Contextualize::get_f64`] and
+//! [`Contextualize::get_bool`] read a value of the expected type, and [`Contextualize::get_as`]
+//! deserializes it into any `T: DeserializeOwned`. A missing key is reported as a
+//! [`KeyNotFound`](crate::KeyNotFound) error and a value of another type as a
+//! [`TypeMismatch`](crate::TypeMismatch) error naming the key, the expected and the found type.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("user".to_string(), Value::String("jane".to_string()));
+//! ctx.insert("attempt".to_string(), Value::U8(3));
+//!
+//! assert_eq!(ctx.get_str("user").unwrap(), "jane");
+//! assert_eq!(ctx.get_i64("attempt").unwrap(), 3);
+//! assert_eq!(ctx.get_as::<u8>("attempt").unwrap(), 3);
+//!
+//! let err = ctx.get_bool("user").unwrap_err();
+//! assert_eq!(err.message(), "Invalid value for context key 'user': expected bool, found string");
+//! assert_eq!(ctx.get_str("missing").unwrap_err().code(), 404);
+//! ```
+use crate::{Contextualize, KeyNotFound, TypeMismatch};
+use serde::de::DeserializeOwned;
+use serde_value::Value;
+
+/// Returns the value of `k`, or a [`KeyNotFound`] error.
+pub(crate) fn required<'a, C: Contextualize>(ctx: &'a C, k: &str) -> cdumay_core::Result<&'a Value> {
+    ctx.get(k).ok_or_else(|| {
+        KeyNotFound::new()
+            .with_message(format!("Context key '{}' not found", k))
+            .with_details(ctx.error_details())
+            .into()
+    })
+}
+
+/// Returns the value of `k` converted by `convert`, or a [`TypeMismatch`] error naming
+/// `expected` if the conversion fails.
+pub(crate) fn typed<'a, C, T, F>(ctx: &'a C, k: &str, expected: &str, convert: F) -> cdumay_core::Result<T>
+where
+    C: Contextualize,
+    F: FnOnce(&'a Value) -> Option<T>,
+{
+    let value = required(ctx, k)?;
+    convert(value).ok_or_else(|| {
+        TypeMismatch::new()
+            .with_message(format!(
+                "Invalid value for context key '{}': expected {}, found {}",
+                k,
+                expected,
+                type_of(value)
+            ))
+            .with_details(ctx.error_details())
+            .into()
+    })
+}
+
+/// Returns the value of `k` deserialized into `T`.
+pub(crate) fn deserialized<C: Contextualize, T: DeserializeOwned>(ctx: &C, k: &str) -> cdumay_core::Result<T> {
+    let value = required(ctx, k)?;
+    value.clone().deserialize_into().map_err(|err| {
+        TypeMismatch::new()
+            .with_message(format!(
+                "Invalid value for context key '{}': expected {}, found {} ({})",
+                k,
+                std::any::type_name::<T>(),
+                type_of(value),
+                err
+            ))
+            .with_details(ctx.error_details())
+            .into()
+    })
+}
+
+/// Returns the name of the type of `value`, optional and newtype values being unwrapped.
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => type_of(inner),
+        Value::Option(None) | Value::Unit => "null",
+        Value::Bool(_) => "bool",
+        Value::U8(_) => "u8",
+        Value::U16(_) => "u16",
+        Value::U32(_) => "u32",
+        Value::U64(_) => "u64",
+        Value::I8(_) => "i8",
+        Value::I16(_) => "i16",
+        Value::I32(_) => "i32",
+        Value::I64(_) => "i64",
+        Value::F32(_) => "f32",
+        Value::F64(_) => "f64",
+        Value::Char(_) => "char",
+        Value::String(_) => "string",
+        Value::Bytes(_) => "bytes",
+        Value::Seq(_) => "sequence",
+        Value::Map(_) => "map",
+    }
+}
@@ -0,0 +1,151 @@
+//! `application/x-www-form-urlencoded` encoding of contexts.
+//!
+//! [`Context::to_query_string`] renders a small context as a query string, for debugging links,
+//! and [`Context::from_query_string`] reads incoming query strings or form bodies. Both follow the
+//! WHATWG URL standard: names and values are UTF-8, bytes outside `[a-zA-Z0-9*-._]` are
+//! percent-encoded and spaces are written as `+`.
+//!
+//! Sequences are written as repeated keys and nested maps under dotted keys, such as
+//! `http.status`, the maps and sequences nested in sequences being written under their index;
+//! null values and bytes are left out. When reading, all values are strings, a key repeated
+//! becomes a `Value::Seq` of its values in order, and dotted keys are kept as they are.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//! use serde_value::Value;
+//!
+//! let mut ctx = Context::new();
+//! ctx.insert("query".to_string(), Value::String("café & co".to_string()));
+//! ctx.insert("tag".to_string(), Value::Seq(vec![Value::String("a".to_string()), Value::String("b".to_string())]));
+//! assert_eq!(ctx.to_query_string(), "query=caf%C3%A9+%26+co&tag=a&tag=b");
+//!
+//! let ctx = Context::from_query_string("query=caf%C3%A9+%26+co&tag=a&tag=b&page=2");
+//! assert_eq!(ctx.get_str("query").unwrap(), "café & co");
+//! assert_eq!(ctx.get_str("page").unwrap(), "2");
+//! assert_eq!(ctx.get("tag"), Some(&Value::Seq(vec![Value::String("a".to_string()), Value::String("b".to_string())])));
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl Context {
+    /// Encodes the context as an `application/x-www-form-urlencoded` string, see the
+    /// [module documentation](self).
+    pub fn to_query_string(&self) -> String {
+        let mut pairs = Vec::new();
+        for (k, v) in self.inner() {
+            collect_pairs(&mut pairs, &k, &v);
+        }
+        pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Creates a context from an `application/x-www-form-urlencoded` string, such as a query
+    /// string without its leading `?`, see the [module documentation](self).
+    ///
+    /// Pairs without `=` get an empty value, invalid percent escapes are kept as they are and
+    /// invalid UTF-8 sequences are replaced with `U+FFFD`.
+    pub fn from_query_string(query: &str) -> Context {
+        let mut values: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            values.entry(decode(k)).or_default().push(decode(v));
+        }
+        let mut ctx = Context::new();
+        ctx.extend(
+            values
+                .into_iter()
+                .map(|(k, mut v)| match v.len() {
+                    1 => (k, Value::String(v.remove(0))),
+                    _ => (k, Value::Seq(v.into_iter().map(Value::String).collect())),
+                })
+                .collect(),
+        );
+        ctx
+    }
+}
+
+/// Adds the pairs encoding `value`, stored under `path`, to `pairs`.
+fn collect_pairs(pairs: &mut Vec<(String, String)>, path: &str, value: &Value) {
+    let scalar = match value {
+        Value::String(s) => s.clone(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::I8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::F32(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => return collect_pairs(pairs, path, inner),
+        Value::Seq(items) => {
+            for (index, item) in items.iter().enumerate() {
+                match item {
+                    Value::Map(_) | Value::Seq(_) => collect_pairs(pairs, &format!("{}.{}", path, index), item),
+                    item => collect_pairs(pairs, path, item),
+                }
+            }
+            return;
+        }
+        Value::Map(map) => {
+            for (k, v) in map {
+                collect_pairs(pairs, &format!("{}.{}", path, crate::mapkey::stringify(k)), v);
+            }
+            return;
+        }
+        Value::Unit | Value::Option(None) | Value::Bytes(_) => return,
+    };
+    pairs.push((path.to_string(), scalar));
+}
+
+/// Percent-encodes `s` with the `application/x-www-form-urlencoded` percent-encode set.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => out.push(byte as char),
+            b' ' => out.push('+'),
+            _ => {
+                let _ = write!(out, "%{:02X}", byte);
+            }
+        }
+    }
+    out
+}
+
+/// Decodes an `application/x-www-form-urlencoded` name or value.
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match (
+                bytes.get(i + 1).and_then(|&digit| hex(digit)),
+                bytes.get(i + 2).and_then(|&digit| hex(digit)),
+            ) {
+                (Some(high), Some(low)) => {
+                    out.push(high << 4 | low);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|digit| digit as u8)
+}
@@ -0,0 +1,211 @@
+//! Helpers to navigate `serde_value::Value` without pattern-matching by hand.
+//!
+//! [`ValueExt`] adds `as_*` accessors to `serde_value::Value`, and [`ValueRef`] wraps an optional
+//! reference to a value. Navigation never panics: looking up a missing key or walking through a
+//! non-map value yields an empty `ValueRef`, whose accessors all return `None`.
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//! use serde_value::Value;
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let mut http = BTreeMap::new();
+//! http.insert(Value::String("status".to_string()), Value::U16(404));
+//! let mut ctx = Context::new();
+//! ctx.insert("http".to_string(), Value::Map(http));
+//!
+//! assert_eq!(ctx.at("http").at("status").as_u64(), Some(404));
+//! assert_eq!(ctx.at("http").at("missing").as_u64(), None);
+//! assert_eq!(ctx.at("nope").at("status").as_u64(), None);
+//! ```
+use crate::{Context, Contextualize};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Typed accessors on `serde_value::Value`.
+///
+/// Accessors look through `Option` and newtype wrappers, and numeric accessors accept any
+/// integer variant as long as the value fits in the requested type.
+///
+/// # Example
+///
+/// ```rust
+/// use serde_value::Value;
+/// use cdumay_context::ValueExt;
+///
+/// assert_eq!(Value::U8(7).as_i64(), Some(7));
+/// assert_eq!(Value::I64(-1).as_u64(), None);
+/// assert_eq!(Value::String("ok".to_string()).as_str(), Some("ok"));
+/// assert_eq!(Value::Bool(true).as_str(), None);
+/// ```
+pub trait ValueExt {
+    /// Returns the value as a string slice if it is a string.
+    fn as_str(&self) -> Option<&str>;
+    /// Returns the value as an `i64` if it is an integer that fits.
+    fn as_i64(&self) -> Option<i64>;
+    /// Returns the value as an `u64` if it is a non-negative integer that fits.
+    fn as_u64(&self) -> Option<u64>;
+    /// Returns the value as an `f64` if it is a number.
+    fn as_f64(&self) -> Option<f64>;
+    /// Returns the value as a `bool` if it is a boolean.
+    fn as_bool(&self) -> Option<bool>;
+    /// Returns the value as a map if it is a map.
+    fn as_map(&self) -> Option<&BTreeMap<Value, Value>>;
+    /// Returns the value as a slice if it is a sequence.
+    fn as_seq(&self) -> Option<&[Value]>;
+}
+
+impl ValueExt for Value {
+    fn as_str(&self) -> Option<&str> {
+        match unwrap(self) {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match unwrap(self) {
+            Value::U8(v) => Some(*v as i64),
+            Value::U16(v) => Some(*v as i64),
+            Value::U32(v) => Some(*v as i64),
+            Value::U64(v) => i64::try_from(*v).ok(),
+            Value::I8(v) => Some(*v as i64),
+            Value::I16(v) => Some(*v as i64),
+            Value::I32(v) => Some(*v as i64),
+            Value::I64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match unwrap(self) {
+            Value::U8(v) => Some(*v as u64),
+            Value::U16(v) => Some(*v as u64),
+            Value::U32(v) => Some(*v as u64),
+            Value::U64(v) => Some(*v),
+            Value::I8(v) => u64::try_from(*v).ok(),
+            Value::I16(v) => u64::try_from(*v).ok(),
+            Value::I32(v) => u64::try_from(*v).ok(),
+            Value::I64(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match unwrap(self) {
+            Value::F32(v) => Some(*v as f64),
+            Value::F64(v) => Some(*v),
+            Value::U64(v) => Some(*v as f64),
+            Value::I64(v) => Some(*v as f64),
+            other => other.as_i64().map(|v| v as f64),
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match unwrap(self) {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<&BTreeMap<Value, Value>> {
+        match unwrap(self) {
+            Value::Map(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn as_seq(&self) -> Option<&[Value]> {
+        match unwrap(self) {
+            Value::Seq(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// A non-panicking reference to a (possibly missing) value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueRef<'a>(Option<&'a Value>);
+
+impl<'a> ValueRef<'a> {
+    /// Wraps an optional value reference.
+    pub fn new(value: Option<&'a Value>) -> Self {
+        Self(value.map(unwrap))
+    }
+
+    /// Looks up `key` if the referenced value is a map.
+    pub fn at(&self, key: &str) -> ValueRef<'a> {
+        match self.0 {
+            Some(Value::Map(map)) => ValueRef::new(map.get(&Value::String(key.to_string()))),
+            _ => ValueRef(None),
+        }
+    }
+
+    /// Returns the item at `index` if the referenced value is a sequence.
+    pub fn nth(&self, index: usize) -> ValueRef<'a> {
+        match self.0 {
+            Some(Value::Seq(items)) => ValueRef::new(items.get(index)),
+            _ => ValueRef(None),
+        }
+    }
+
+    /// Returns the referenced value, if any.
+    pub fn value(&self) -> Option<&'a Value> {
+        self.0
+    }
+
+    /// Returns `true` if nothing is referenced.
+    pub fn is_missing(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns the value as a string slice, see [`ValueExt::as_str`].
+    pub fn as_str(&self) -> Option<&'a str> {
+        self.0?.as_str()
+    }
+
+    /// Returns the value as an `i64`, see [`ValueExt::as_i64`].
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0?.as_i64()
+    }
+
+    /// Returns the value as an `u64`, see [`ValueExt::as_u64`].
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0?.as_u64()
+    }
+
+    /// Returns the value as an `f64`, see [`ValueExt::as_f64`].
+    pub fn as_f64(&self) -> Option<f64> {
+        self.0?.as_f64()
+    }
+
+    /// Returns the value as a `bool`, see [`ValueExt::as_bool`].
+    pub fn as_bool(&self) -> Option<bool> {
+        self.0?.as_bool()
+    }
+
+    /// Returns the value as a map, see [`ValueExt::as_map`].
+    pub fn as_map(&self) -> Option<&'a BTreeMap<Value, Value>> {
+        self.0?.as_map()
+    }
+
+    /// Returns the value as a slice, see [`ValueExt::as_seq`].
+    pub fn as_seq(&self) -> Option<&'a [Value]> {
+        self.0?.as_seq()
+    }
+}
+
+/// Transparent wrappers carry no meaning for navigation, look through them.
+fn unwrap(value: &Value) -> &Value {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => unwrap(inner),
+        other => other,
+    }
+}
+
+impl Context {
+    /// Returns a [`ValueRef`] on the value stored under `key`, which may be missing.
+    pub fn at(&self, key: &str) -> ValueRef<'_> {
+        ValueRef::new(self.get(key))
+    }
+}
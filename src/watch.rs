@@ -0,0 +1,179 @@
+//! Hot reloading of context files.
+//!
+//! [`Context::watch`] loads a context file and returns a [`FileWatch`], which reloads it as soon
+//! as the operating system reports a change of the file, through the `notify` crate. The context
+//! is held by a [`SharedContext`] and replaced as a whole on each reload, so that readers see
+//! either the previous content or the new one, never a partially loaded map. A file which cannot
+//! be loaded, such as a half-written one, leaves the context as it is.
+//!
+//! [`FileWatch::subscribe`] returns a channel receiving a [`WatchEvent`] for each reload changing
+//! the context, with the [`ContextDiff`] of the change, and for each failed reload.
+//!
+//! See [`Context::watch_file`] to poll a file instead, without any thread nor OS notification.
+//!
+//! ```rust,no_run
+//! use cdumay_context::{Context, WatchEvent};
+//!
+//! let watch = Context::watch("/etc/myapp/flags.json").unwrap();
+//! let events = watch.subscribe();
+//! std::thread::spawn(move || {
+//!     for event in events {
+//!         if let WatchEvent::Reloaded(diff) = event {
+//!             println!("flags changed:\n{}", diff);
+//!         }
+//!     }
+//! });
+//! let beta = watch.context().get("beta");
+//! ```
+use crate::{Context, ContextDiff, FsStorage, SharedContext, UnExpectedError};
+use notify::{RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// A change reported by a [`FileWatch`], see [`FileWatch::subscribe`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// The file was reloaded, with the changes of the context.
+    Reloaded(ContextDiff),
+    /// The file changed but could not be loaded, the context being left as it is. This may be
+    /// transient, as a file may be read while being written, and it is loaded again at its next
+    /// change.
+    Failed(cdumay_core::Error),
+}
+
+/// A context reloaded when its file changes, see the [module documentation](self).
+///
+/// Dropping the handle stops watching the file.
+pub struct FileWatch {
+    state: Arc<State>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+struct State {
+    path: PathBuf,
+    context: SharedContext,
+    subscribers: Mutex<Vec<Sender<WatchEvent>>>,
+}
+
+impl State {
+    /// Reloads the file, notifying the subscribers if the context changed or the load failed.
+    fn reload(&self) -> Option<WatchEvent> {
+        let event = match Context::load_file(&FsStorage, &self.path) {
+            Ok(ctx) => {
+                let diff = self.context.update(|current| {
+                    let diff = current.diff(&ctx);
+                    *current = ctx;
+                    diff
+                });
+                match diff.is_empty() {
+                    true => return None,
+                    false => WatchEvent::Reloaded(diff),
+                }
+            }
+            Err(err) => WatchEvent::Failed(err),
+        };
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        Some(event)
+    }
+}
+
+impl FileWatch {
+    /// Returns the shared context, holding the last content loaded.
+    pub fn context(&self) -> &SharedContext {
+        &self.state.context
+    }
+
+    /// Returns an immutable snapshot of the last content loaded.
+    pub fn snapshot(&self) -> Arc<Context> {
+        self.state.context.snapshot()
+    }
+
+    /// Returns a channel receiving the events of the next reloads.
+    ///
+    /// Reloads leaving the context unchanged, such as those triggered by a file being touched,
+    /// are not reported.
+    pub fn subscribe(&self) -> Receiver<WatchEvent> {
+        let (sender, receiver) = channel();
+        self.state
+            .subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(sender);
+        receiver
+    }
+
+    /// Reloads the file now, returning the event sent to the subscribers, if any.
+    pub fn reload(&self) -> Option<WatchEvent> {
+        self.state.reload()
+    }
+
+    /// Returns the path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.state.path
+    }
+}
+
+impl std::fmt::Debug for FileWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWatch")
+            .field("path", &self.state.path)
+            .field("context", &self.state.context)
+            .finish()
+    }
+}
+
+impl Context {
+    /// Loads the file at `path`, in the format given by its extension, and watches it for changes.
+    ///
+    /// The parent directory of the file is watched, so that files replaced by a rename, as most
+    /// editors and deployment tools do, keep being watched.
+    ///
+    /// This method is only available when the "watch" feature is enabled.
+    ///
+    /// # Returns
+    ///
+    /// Returns `cdumay_core::Result<FileWatch>` which is:
+    /// * `Ok(watch)` containing the handle on the loaded context on success
+    /// * `Err(e)` containing the error of [`Context::load_file`], or an [`UnExpectedError`] if the
+    ///   file cannot be watched
+    pub fn watch(path: impl AsRef<Path>) -> cdumay_core::Result<FileWatch> {
+        let path = path.as_ref().to_path_buf();
+        let ctx = Context::load_file(&FsStorage, &path)?;
+        let state = Arc::new(State {
+            path: path.clone(),
+            context: SharedContext::new(ctx),
+            subscribers: Mutex::new(Vec::new()),
+        });
+        let watched = Arc::downgrade(&state);
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            let concerned = event.kind.is_create() || event.kind.is_modify();
+            if concerned
+                && event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name().map(|name| name.to_os_string()) == file_name)
+            {
+                if let Some(state) = watched.upgrade() {
+                    state.reload();
+                }
+            }
+        })
+        .map_err(|err| watch_error(&err, &path))?;
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, RecursiveMode::NonRecursive).map_err(|err| watch_error(&err, &path))?;
+        Ok(FileWatch { state, _watcher: watcher })
+    }
+}
+
+fn watch_error(err: &notify::Error, path: &Path) -> cdumay_core::Error {
+    UnExpectedError::new()
+        .with_message(format!("{}: failed to watch: {}", path.display(), err))
+        .into()
+}
@@ -0,0 +1,269 @@
+//! XML backend, see [`Contextualize::from_xml`](crate::Contextualize::from_xml) and
+//! [`Contextualize::to_xml`](crate::Contextualize::to_xml).
+//!
+//! A context is written as a root element holding an element per entry, named after its key:
+//! scalars are written as text, `null` as an empty element, maps as nested elements and
+//! sequences as elements repeated under the same name.
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <context><status>404</status><tags>a</tags><tags>b</tags><user><name>Jane</name></user></context>
+//! ```
+//!
+//! XML has no types: on load, the text of an element is read as a string, an empty element
+//! such as `<key/>` as [`Value::Unit`], an element holding elements as a map and elements
+//! repeated under the same name as a sequence. A one-item sequence is thus read back as its item,
+//! and an empty one is not written at all. Attributes, comments and processing instructions are
+//! ignored, and the name of the root element is not checked.
+//!
+//! Keys must be valid XML names, bytes and sequences nested in sequences cannot be written. There
+//! is no `cdumay_xml` crate, the errors of `quick-xml` are converted by [`XmlErrorConverter`].
+use crate::{InvalidMapKey, TypeMismatch, UnExpectedError, ValidationError};
+use cdumay_core::ErrorConverter;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Converts the errors raised by `quick-xml`: I/O errors give an [`UnExpectedError`], invalid
+/// documents a [`TypeMismatch`] error.
+pub(crate) struct XmlErrorConverter;
+
+impl ErrorConverter for XmlErrorConverter {
+    type Error = quick_xml::Error;
+
+    fn convert(err: &quick_xml::Error, text: String, context: BTreeMap<String, Value>) -> cdumay_core::Error {
+        match err {
+            quick_xml::Error::Io(_) => UnExpectedError::new().with_message(text).with_details(context).into(),
+            _ => TypeMismatch::new().with_message(text).with_details(context).into(),
+        }
+    }
+}
+
+/// Returns `true` if `name` can be used as an element name.
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' => chars.all(|c| c.is_alphanumeric() || "_-.".contains(c)),
+        _ => false,
+    }
+}
+
+/// Returns the text written for the scalar `value`.
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => text(inner),
+        Value::Bool(v) => Some(v.to_string()),
+        Value::U8(v) => Some(v.to_string()),
+        Value::U16(v) => Some(v.to_string()),
+        Value::U32(v) => Some(v.to_string()),
+        Value::U64(v) => Some(v.to_string()),
+        Value::I8(v) => Some(v.to_string()),
+        Value::I16(v) => Some(v.to_string()),
+        Value::I32(v) => Some(v.to_string()),
+        Value::I64(v) => Some(v.to_string()),
+        Value::F32(v) => Some(v.to_string()),
+        Value::F64(v) => Some(v.to_string()),
+        Value::Char(c) => Some(c.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+struct Dump<'a> {
+    writer: Writer<Vec<u8>>,
+    details: &'a dyn Fn() -> BTreeMap<String, Value>,
+}
+
+impl Dump<'_> {
+    fn write(&mut self, event: Event<'_>) -> cdumay_core::Result<()> {
+        self.writer.write_event(event).map_err(|err| {
+            XmlErrorConverter::convert_error(&quick_xml::Error::from(err), Some("Failed to dump context".to_string()), (self.details)())
+        })
+    }
+
+    fn unsupported(&self, name: &str, message: &str) -> cdumay_core::Error {
+        let mut details = (self.details)();
+        details.insert("key".to_string(), Value::String(name.to_string()));
+        ValidationError::new()
+            .with_message(format!("Failed to dump context: {} under '{}'", message, name))
+            .with_details(details)
+            .into()
+    }
+
+    fn element(&mut self, name: &str, value: &Value, in_seq: bool) -> cdumay_core::Result<()> {
+        match value {
+            Value::Option(Some(inner)) | Value::Newtype(inner) => self.element(name, inner, in_seq),
+            Value::Unit | Value::Option(None) => self.write(Event::Empty(BytesStart::new(name))),
+            Value::Bytes(_) => Err(self.unsupported(name, "XML cannot represent bytes")),
+            Value::Seq(_) if in_seq => Err(self.unsupported(name, "XML cannot represent nested sequences")),
+            Value::Seq(items) => items.iter().try_for_each(|item| self.element(name, item, true)),
+            Value::Map(map) => {
+                self.write(Event::Start(BytesStart::new(name)))?;
+                for (k, v) in map {
+                    let child = text(k).unwrap_or_else(|| format!("{:?}", k));
+                    if !is_name(&child) {
+                        return Err(invalid_name(&child, (self.details)()));
+                    }
+                    self.element(&child, v, false)?;
+                }
+                self.write(Event::End(BytesEnd::new(name)))
+            }
+            scalar => {
+                let text = text(scalar).unwrap_or_default();
+                self.write(Event::Start(BytesStart::new(name)))?;
+                self.write(Event::Text(BytesText::new(&text)))?;
+                self.write(Event::End(BytesEnd::new(name)))
+            }
+        }
+    }
+}
+
+fn invalid_name(name: &str, mut details: BTreeMap<String, Value>) -> cdumay_core::Error {
+    details.insert("key".to_string(), Value::String(name.to_string()));
+    InvalidMapKey::new()
+        .with_message(format!("Failed to dump context: '{}' is not a valid XML element name", name))
+        .with_details(details)
+        .into()
+}
+
+/// Dumps `entries` under a `root_tag` element, with `details` in the error on failure.
+pub(crate) fn dump<'a>(
+    entries: impl IntoIterator<Item = (&'a str, &'a Value)>,
+    root_tag: &str,
+    details: &dyn Fn() -> BTreeMap<String, Value>,
+) -> cdumay_core::Result<String> {
+    if !is_name(root_tag) {
+        return Err(invalid_name(root_tag, details()));
+    }
+    let mut dump = Dump {
+        writer: Writer::new(Vec::new()),
+        details,
+    };
+    dump.write(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    dump.write(Event::Start(BytesStart::new(root_tag)))?;
+    for (k, v) in entries {
+        if !is_name(k) {
+            return Err(invalid_name(k, details()));
+        }
+        dump.element(k, v, false)?;
+    }
+    dump.write(Event::End(BytesEnd::new(root_tag)))?;
+    String::from_utf8(dump.writer.into_inner()).map_err(|err| {
+        UnExpectedError::new()
+            .with_message(format!("Failed to dump context: {}", err))
+            .with_details(details())
+            .into()
+    })
+}
+
+/// An element being read: its name, its text and its child elements.
+struct Element {
+    name: String,
+    text: String,
+    children: Vec<(String, Value)>,
+}
+
+impl Element {
+    fn new(start: &BytesStart<'_>) -> Self {
+        Self {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            text: String::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn into_value(self) -> cdumay_core::Result<Value> {
+        match self.children.is_empty() {
+            true => Ok(Value::String(self.text)),
+            false if self.text.trim().is_empty() => Ok(Value::Map(
+                grouped(self.children).into_iter().map(|(k, v)| (Value::String(k), v)).collect(),
+            )),
+            false => Err(malformed(&format!("element '{}' mixes text and elements", self.name))),
+        }
+    }
+}
+
+/// Groups the elements by name, repeated names giving a sequence.
+fn grouped(children: Vec<(String, Value)>) -> BTreeMap<String, Value> {
+    let mut groups: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for (name, value) in children {
+        groups.entry(name).or_default().push(value);
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut values)| match values.len() {
+            1 => (name, values.remove(0)),
+            _ => (name, Value::Seq(values)),
+        })
+        .collect()
+}
+
+fn malformed(message: &str) -> cdumay_core::Error {
+    TypeMismatch::new().with_message(format!("Failed to load context: {}", message)).into()
+}
+
+fn load_error(err: impl Into<quick_xml::Error>) -> cdumay_core::Error {
+    XmlErrorConverter::convert_error(&err.into(), Some("Failed to load context".to_string()), BTreeMap::new())
+}
+
+/// Parses an XML document into the entries held by its root element.
+pub(crate) fn load(xml: &str) -> cdumay_core::Result<BTreeMap<String, Value>> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        match reader.read_event().map_err(load_error)? {
+            Event::Start(start) => stack.push(Element::new(&start)),
+            Event::Empty(start) => match stack.last_mut() {
+                Some(parent) => parent.children.push((Element::new(&start).name, Value::Unit)),
+                None => return Ok(BTreeMap::new()),
+            },
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| malformed("unexpected end tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => {
+                        let name = element.name.clone();
+                        parent.children.push((name, element.into_value()?));
+                    }
+                    None if element.text.trim().is_empty() => return Ok(grouped(element.children)),
+                    None => return Err(malformed(&format!("root element '{}' holds text", element.name))),
+                }
+            }
+            Event::Text(text) => {
+                let text = text.decode().map_err(load_error)?;
+                match stack.last_mut() {
+                    Some(element) => element.text.push_str(&text),
+                    None if text.trim().is_empty() => {}
+                    None => return Err(malformed("text outside of the root element")),
+                }
+            }
+            Event::CData(data) => {
+                let data = data.decode().map_err(load_error)?;
+                stack
+                    .last_mut()
+                    .ok_or_else(|| malformed("CDATA outside of the root element"))?
+                    .text
+                    .push_str(&data);
+            }
+            Event::GeneralRef(reference) => {
+                let resolved = match reference.resolve_char_ref().map_err(load_error)? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = reference.decode().map_err(load_error)?;
+                        quick_xml::escape::resolve_predefined_entity(&name)
+                            .ok_or_else(|| malformed(&format!("unknown entity '&{};'", name)))?
+                            .to_string()
+                    }
+                };
+                stack
+                    .last_mut()
+                    .ok_or_else(|| malformed("entity outside of the root element"))?
+                    .text
+                    .push_str(&resolved);
+            }
+            Event::Eof if stack.is_empty() => return Err(malformed("missing root element")),
+            Event::Eof => return Err(malformed("unexpected end of document")),
+            Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_) => {}
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! YAML anchors, aliases and merge keys.
+//!
+//! [`Contextualize::from_yaml`](crate::Contextualize::from_yaml) resolves aliases (`*name`) to
+//! a copy of their anchored node (`&name`) and applies merge keys (`<<`), so that the loaded
+//! context only holds plain values. Keys set next to a merge key take precedence over the merged
+//! ones.
+//!
+//! Aliases can make a small document expand exponentially. Loading fails with a
+//! [`YamlExpansionLimit`] error if the document nests deeper than 128 levels, resolves aliases
+//! too many times, or expands to more than [`YAML_EXPANSION_LIMIT`] nodes, or to more nodes than
+//! it has bytes for larger documents.
+//!
+//! ```rust
+//! use cdumay_context::{Context, Contextualize};
+//!
+//! let yaml = "
+//! defaults: &defaults
+//!   retries: 3
+//!   timeout: 10
+//! upload:
+//!   <<: *defaults
+//!   timeout: 60
+//! ";
+//! let ctx = Context::from_yaml(yaml).unwrap();
+//! assert_eq!(ctx.at("upload").at("retries").as_u64(), Some(3));
+//! assert_eq!(ctx.at("upload").at("timeout").as_u64(), Some(60));
+//! ```
+use crate::backend::Loaded;
+use crate::YamlExpansionLimit;
+use cdumay_core::ErrorConverter;
+use serde_value::Value;
+use std::collections::BTreeMap;
+
+/// Maximum number of nodes a YAML document may expand to, unless it has more bytes.
+pub const YAML_EXPANSION_LIMIT: usize = 100_000;
+
+/// Parses a YAML mapping, with its aliases and merge keys resolved, and its tags kept as tagged
+/// values if `tagged`, see [`TAG_KEY`](crate::TAG_KEY).
+pub(crate) fn load(yaml: &str, tagged: bool) -> cdumay_core::Result<Loaded<Value>> {
+    crate::metrics::observe_load(crate::Format::Yaml, yaml.len(), || parse(yaml, tagged))
+}
+
+fn parse(yaml: &str, tagged: bool) -> cdumay_core::Result<Loaded<Value>> {
+    let mut document: serde_yaml::Value = serde_yaml::from_str(yaml).map_err(|err| convert(&err))?;
+    document.apply_merge().map_err(|err| convert(&err))?;
+    let max_nodes = YAML_EXPANSION_LIMIT.max(yaml.len());
+    if exceeds(&document, max_nodes) {
+        return Err(YamlExpansionLimit::new()
+            .with_message(format!("YAML document expands to more than {} nodes", max_nodes))
+            .into());
+    }
+    let data: Loaded<serde_yaml::Value> = match document {
+        serde_yaml::Value::Null => Loaded::new(),
+        document => serde_yaml::from_value(document).map_err(|err| convert(&err))?,
+    };
+    Ok(data.into_iter().map(|(k, v)| (k, to_value(v, tagged))).collect())
+}
+
+/// Converts a YAML error, the guards of the parser against deep nesting and repeated aliases
+/// giving a [`YamlExpansionLimit`] error.
+fn convert(err: &serde_yaml::Error) -> cdumay_core::Error {
+    let message = err.to_string();
+    match message.starts_with("recursion limit exceeded") || message.starts_with("repetition limit exceeded") {
+        true => YamlExpansionLimit::new()
+            .with_message(format!("Failed to load context: {}", message))
+            .into(),
+        false => cdumay_yaml::YamlErrorConverter::convert_error(err, Some("Failed to load context".to_string()), BTreeMap::new()),
+    }
+}
+
+/// Returns `true` if `document` holds more than `max_nodes` nodes.
+fn exceeds(document: &serde_yaml::Value, max_nodes: usize) -> bool {
+    let mut stack = vec![document];
+    let mut nodes = 0;
+    while let Some(node) = stack.pop() {
+        nodes += 1;
+        if nodes > max_nodes {
+            return true;
+        }
+        match node {
+            serde_yaml::Value::Sequence(items) => stack.extend(items),
+            serde_yaml::Value::Mapping(mapping) => mapping.iter().for_each(|(k, v)| stack.extend([k, v])),
+            serde_yaml::Value::Tagged(tagged) => stack.push(&tagged.value),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Converts a YAML value, numbers and booleans used as map keys being written as strings and
+/// tags being dropped unless `tagged`.
+pub(crate) fn to_value(value: serde_yaml::Value, tagged: bool) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Unit,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+            (Some(u), _, _) => Value::U64(u),
+            (_, Some(i), _) => Value::I64(i),
+            (_, _, f) => Value::F64(f.unwrap_or(f64::NAN)),
+        },
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => Value::Seq(items.into_iter().map(|item| to_value(item, tagged)).collect()),
+        serde_yaml::Value::Mapping(mapping) => Value::Map(
+            mapping
+                .into_iter()
+                .map(|(k, v)| {
+                    let k = match k {
+                        serde_yaml::Value::Bool(b) => Value::String(b.to_string()),
+                        serde_yaml::Value::Number(n) => Value::String(n.to_string()),
+                        k => to_value(k, tagged),
+                    };
+                    (k, to_value(v, tagged))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(value) if tagged => crate::tagged::tagged(value.tag.to_string(), to_value(value.value, tagged)),
+        serde_yaml::Value::Tagged(value) => to_value(value.value, tagged),
+    }
+}
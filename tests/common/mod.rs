@@ -0,0 +1,19 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use cdumay_context::{Context, Contextualize};
+use serde_value::Value;
+
+/// Returns a string value.
+pub fn s(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+/// Returns a context holding `entries`, inserted in order.
+pub fn context_of<const N: usize>(entries: [(&str, Value); N]) -> Context {
+    let mut ctx = Context::new();
+    for (key, value) in entries {
+        ctx.insert(key.to_string(), value);
+    }
+    ctx
+}
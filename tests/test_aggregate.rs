@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{AnomalyKind, Context, ContextAggregator, ContextProfile, Contextualize, LaplaceNoise, AGGREGATE_VALUES_LIMIT};
+    use serde_value::Value;
+
+    fn context(region: Option<&str>, latency: u64) -> Context {
+        let mut ctx = Context::new();
+        if let Some(region) = region {
+            ctx.insert("region".to_string(), Value::String(region.to_string()));
+        }
+        ctx.insert("latency_ms".to_string(), Value::U64(latency));
+        ctx
+    }
+
+    #[test]
+    fn test_summary() {
+        let contexts = [
+            context(Some("eu"), 100),
+            context(Some("us"), 300),
+            context(None, 200),
+            context(Some("eu"), 200),
+        ];
+        let aggregator: ContextAggregator = contexts.iter().collect();
+        assert_eq!(aggregator.len(), 4);
+        assert_eq!(aggregator.frequencies().get("region"), Some(&3));
+
+        let summary = aggregator.to_context();
+        assert_eq!(summary.at("contexts").as_u64(), Some(4));
+        let region = summary.at("keys").at("region");
+        assert_eq!(region.at("count").as_u64(), Some(3));
+        assert_eq!(region.at("distinct").as_u64(), Some(2));
+        assert_eq!(region.at("values").nth(0).as_str(), Some("eu"));
+        assert!(region.at("min").is_missing());
+
+        let latency = summary.at("keys").at("latency_ms");
+        assert_eq!(latency.at("min").as_f64(), Some(100.0));
+        assert_eq!(latency.at("max").as_f64(), Some(300.0));
+        assert_eq!(latency.at("avg").as_f64(), Some(200.0));
+        assert_eq!(latency.at("distinct").as_u64(), Some(3));
+        assert!(latency.at("truncated").is_missing());
+    }
+
+    #[test]
+    fn test_distinct_values_are_capped() {
+        let mut aggregator = ContextAggregator::new();
+        assert!(aggregator.is_empty());
+        for latency in 0..(AGGREGATE_VALUES_LIMIT as u64 * 2) {
+            aggregator.add(&context(None, latency));
+        }
+        let summary = aggregator.to_context();
+        let latency = summary.at("keys").at("latency_ms");
+        assert_eq!(latency.at("count").as_u64(), Some(AGGREGATE_VALUES_LIMIT as u64 * 2));
+        assert_eq!(latency.at("distinct").as_u64(), Some(AGGREGATE_VALUES_LIMIT as u64));
+        assert_eq!(latency.at("truncated").as_bool(), Some(true));
+        assert_eq!(latency.at("max").as_f64(), Some((AGGREGATE_VALUES_LIMIT * 2 - 1) as f64));
+    }
+
+    #[test]
+    fn test_laplace_noise_distribution() {
+        let noise = LaplaceNoise::new(0.5).with_sensitivity(10.0);
+        assert_eq!((noise.epsilon(), noise.sensitivity()), (0.5, 10.0));
+        let samples: Vec<f64> = (0..20_000).map(|_| noise.sample(1.0)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let mean_abs = samples.iter().map(|sample| sample.abs()).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.1, "{}", mean);
+        assert!((mean_abs - 2.0).abs() < 0.2, "{}", mean_abs);
+    }
+
+    #[test]
+    #[should_panic(expected = "epsilon must be a positive finite number")]
+    fn test_laplace_noise_rejects_invalid_epsilon() {
+        LaplaceNoise::new(0.0);
+    }
+
+    #[test]
+    fn test_noisy_summary() {
+        let contexts = [context(Some("eu"), 100), context(Some("us"), 300), context(None, 200)];
+        let aggregator: ContextAggregator = contexts.iter().collect();
+        let aggregator = aggregator.with_noise(LaplaceNoise::new(1e6).with_sensitivity(1.0));
+        assert!(aggregator.noise().is_some());
+
+        let summary = aggregator.to_context();
+        assert_eq!(summary.at("contexts").as_u64(), Some(3));
+        let region = summary.at("keys").at("region");
+        assert_eq!(region.at("count").as_u64(), Some(2));
+        assert_eq!(region.at("distinct").as_u64(), Some(2));
+        assert!(region.at("values").is_missing());
+
+        let latency = summary.at("keys").at("latency_ms");
+        assert!((latency.at("avg").as_f64().unwrap() - 200.0).abs() < 0.01);
+        assert_ne!(latency.at("max").as_f64(), Some(300.0));
+    }
+
+    fn history() -> ContextProfile {
+        let contexts: Vec<Context> = (0..100)
+            .map(|i| {
+                let mut ctx = context(
+                    Some(if i == 0 {
+                        "ap"
+                    } else if i % 2 == 0 {
+                        "eu"
+                    } else {
+                        "us"
+                    }),
+                    100 + i,
+                );
+                ctx.insert("request_id".to_string(), Value::String(format!("req-{}", i)));
+                ctx
+            })
+            .collect();
+        contexts.iter().collect()
+    }
+
+    fn kinds(ctx: &Context, profile: &ContextProfile) -> Vec<(String, AnomalyKind)> {
+        ctx.anomalies(profile).into_iter().map(|anomaly| (anomaly.key, anomaly.kind)).collect()
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let profile = history();
+        assert_eq!(profile.len(), 100);
+
+        let mut ctx = context(Some("eu"), 150);
+        ctx.insert("request_id".to_string(), Value::String("req-new".to_string()));
+        assert!(ctx.anomalies(&profile).is_empty());
+
+        let mut ctx = context(Some("sa"), 5000);
+        ctx.insert("debug".to_string(), Value::Bool(true));
+        assert_eq!(
+            kinds(&ctx, &profile),
+            vec![
+                ("debug".to_string(), AnomalyKind::UnseenKey),
+                ("latency_ms".to_string(), AnomalyKind::OutOfRange),
+                ("region".to_string(), AnomalyKind::UnseenValue),
+                ("request_id".to_string(), AnomalyKind::MissingKey),
+            ]
+        );
+
+        let anomalies = context(Some("ap"), 150).anomalies(&profile);
+        assert_eq!(anomalies[0].kind, AnomalyKind::RareValue);
+        assert_eq!(anomalies[0].to_string(), "region: value String(\"ap\") seen in 1 of 100 contexts");
+    }
+
+    #[test]
+    fn test_anomalies_thresholds() {
+        let profile = history().with_rare_ratio(0.0);
+        let ctx = context(Some("ap"), 150);
+        assert_eq!(kinds(&ctx, &profile), vec![("request_id".to_string(), AnomalyKind::MissingKey)]);
+        assert!(kinds(&ctx, &history().with_rare_ratio(0.01))
+            .iter()
+            .all(|(_, kind)| *kind == AnomalyKind::MissingKey));
+        assert!(ctx.anomalies(&ContextProfile::new()).is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, KeyPolicy, Sensitivity};
+    use std::time::Duration;
+
+    #[test]
+    fn test_alias_resolution() {
+        let mut ctx = Context::new();
+        ctx.alias_key("userId", "user_id");
+        ctx.insert("userId".to_string(), s("jane"));
+        assert_eq!(ctx.get("userId"), Some(&s("jane")));
+        assert_eq!(ctx["user_id"], s("jane"));
+        assert_eq!(ctx.canonical_key("userId"), Some("user_id"));
+        assert_eq!(ctx.canonical_key("user_id"), None);
+        assert_eq!(ctx.key_aliases("user_id"), vec!["userId"]);
+
+        ctx.set_sensitivity("userId", Sensitivity::Secret);
+        assert_eq!(ctx.sensitivity("user_id"), Sensitivity::Secret);
+
+        assert_eq!(ctx.remove("userId"), Some(s("jane")));
+        assert!(ctx.inner().is_empty());
+    }
+
+    #[test]
+    fn test_alias_moves_existing_value() {
+        let mut ctx = Context::new();
+        ctx.insert("userId".to_string(), s("jane"));
+        ctx.insert("uid".to_string(), s("john"));
+        ctx.alias_key("userId", "user_id");
+        assert_eq!(
+            ctx.inner().into_iter().collect::<Vec<_>>(),
+            vec![("uid".to_string(), s("john")), ("user_id".to_string(), s("jane"))]
+        );
+
+        ctx.alias_key("uid", "user_id");
+        assert_eq!(ctx.get("uid"), Some(&s("jane")));
+        assert_eq!(ctx.inner().len(), 1);
+    }
+
+    #[test]
+    fn test_alias_moves_ttl() {
+        let mut ctx = Context::new();
+        ctx.insert_with_ttl("a".to_string(), s("jane"), Duration::ZERO);
+        ctx.alias_key("a", "b");
+        assert!(ctx.get("b").is_none());
+        assert_eq!(ctx.len(), 0);
+        assert_eq!(ctx.purge_expired(), vec!["b".to_string()]);
+
+        ctx.insert("c".to_string(), s("john"));
+        ctx.insert_with_ttl("d".to_string(), s("jack"), Duration::ZERO);
+        ctx.alias_key("d", "c");
+        assert_eq!(ctx.get("d"), Some(&s("john")));
+        assert_eq!(ctx.len(), 1);
+
+        ctx.insert_with_ttl("e".to_string(), s("jill"), Duration::from_secs(3600));
+        ctx.alias_key("e", "f");
+        assert!(ctx.expires_in("f").is_some());
+        assert_eq!(ctx.len(), 2);
+    }
+
+    #[test]
+    fn test_alias_chain() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.alias_key("UID", "userId");
+        ctx.alias_key("userId", "user_id");
+        ctx.alias_key("user_id", "user_id");
+        assert_eq!(ctx.canonical_key("uid"), Some("user_id"));
+        assert_eq!(ctx.key_aliases("USERID"), vec!["uid", "userid"]);
+        ctx.insert("Uid".to_string(), s("jane"));
+        assert_eq!(ctx.get("user_id"), Some(&s("jane")));
+    }
+
+    #[test]
+    fn test_emit_aliases() {
+        let mut ctx = Context::new();
+        ctx.alias_key("userId", "user_id");
+        ctx.alias_key("tenantId", "tenant_id");
+        ctx.insert("user_id".to_string(), s("jane"));
+        ctx.set_emit_aliases(true);
+        let dump = ctx.inner();
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec!["userId", "user_id"]);
+        assert_eq!(dump["userId"], s("jane"));
+
+        let child = ctx.fork();
+        assert_eq!(child.get("userId"), Some(&s("jane")));
+        assert!(child.inner().contains_key("userId"));
+    }
+}
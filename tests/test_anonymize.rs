@@ -0,0 +1,62 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "hash")]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{pseudonymize, Context, Contextualize, SharedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_pseudonyms_are_hmac_sha256() {
+        assert_eq!(pseudonymize(&s("jane"), b"s3cr3t"), "anon_e76ef630b13d8f9719315814c378eb02");
+        assert_eq!(pseudonymize(&s(""), b""), "anon_b613679a0814d9ec772f95d778c35fc5");
+        assert_eq!(pseudonymize(&s(&"x".repeat(200)), &[b'k'; 100]), "anon_e326f00ce14194bb85e2683fe9b1a788");
+        assert_eq!(pseudonymize(&Value::U8(42), b"s"), "anon_94568d5108ef7f10a4703672424461bc");
+        assert_eq!(pseudonymize(&Value::I64(42), b"s"), pseudonymize(&s("42"), b"s"));
+        assert_ne!(pseudonymize(&s("jane"), b"other"), pseudonymize(&s("jane"), b"s3cr3t"));
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), s("jane"));
+        ctx.insert("owner".to_string(), s("jane"));
+        ctx.insert("parent".to_string(), Value::Option(None));
+        ctx.insert(
+            "client".to_string(),
+            Value::Map(BTreeMap::from([
+                (s("ip"), s("10.0.0.1")),
+                (s("ports"), Value::Seq(vec![Value::U16(80)])),
+                (s("agent"), s("curl")),
+            ])),
+        );
+        ctx.anonymize(&["user", "owner", "parent", "client.ip", "client.ports", "missing.key"], b"salt")
+            .unwrap();
+
+        assert_eq!(ctx.get_str("user").unwrap(), ctx.get_str("owner").unwrap());
+        assert_ne!(ctx.get_str("user").unwrap(), "jane");
+        assert_eq!(ctx.get("parent"), Some(&Value::Option(None)));
+        assert_eq!(ctx.at("client").at("ip").as_str(), Some(pseudonymize(&s("10.0.0.1"), b"salt").as_str()));
+        assert_eq!(
+            ctx.at("client").at("ports").nth(0).as_str(),
+            Some(pseudonymize(&Value::U16(80), b"salt").as_str())
+        );
+        assert_eq!(ctx.at("client").at("agent").as_str(), Some("curl"));
+        assert!(!ctx.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_anonymize_protected_key() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), s("jane"));
+        ctx.insert("tenant".to_string(), s("acme"));
+        ctx.protect_key("tenant");
+        let shared = SharedContext::new(ctx);
+        assert!(shared.anonymize(&["user", "tenant"], b"salt").is_err());
+        assert_eq!(shared.get("user"), Some(s("jane")));
+        shared.anonymize(&["user"], b"salt").unwrap();
+        assert_eq!(shared.get("user"), Some(Value::String(pseudonymize(&s("jane"), b"salt"))));
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "allocator-api")]
+mod tests {
+    use crate::common::s;
+    use bumpalo::Bump;
+    use cdumay_context::{ArenaContext, ArenaValue, Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_insert_and_get() {
+        let bump = Bump::new();
+        let mut ctx = ArenaContext::new_in(&bump);
+        assert!(ctx.is_empty());
+        ctx.insert_str("job", "export");
+        ctx.insert("item", &Value::U32(7));
+        ctx.insert("tags", &Value::Seq(vec![s("eu"), Value::Option(None), Value::Char('x')]));
+        ctx.insert_str("job", "nightly-export");
+
+        assert_eq!(ctx.len(), 3);
+        assert_eq!(ctx.keys().collect::<Vec<_>>(), vec!["item", "job", "tags"]);
+        assert_eq!(ctx.get("job").and_then(ArenaValue::as_str), Some("nightly-export"));
+        assert_eq!(ctx.get("item"), Some(&ArenaValue::U64(7)));
+        assert_eq!(
+            ctx.get("tags").map(ArenaValue::to_value),
+            Some(Value::Seq(vec![s("eu"), Value::Unit, s("x")]))
+        );
+        assert_eq!(ctx.remove("item"), Some(ArenaValue::U64(7)));
+        assert!(ctx.get("item").is_none());
+    }
+
+    #[test]
+    fn test_to_context() {
+        let bump = Bump::new();
+        let mut ctx = ArenaContext::new_in(&bump);
+        let user = Value::Map(BTreeMap::from([(s("name"), s("jane"))]));
+        ctx.insert("user", &user);
+        ctx.insert("offset", &Value::I8(-4));
+
+        let owned = Context::from(&ctx);
+        drop(ctx);
+        drop(bump);
+        assert_eq!(owned.get("user"), Some(&user));
+        assert_eq!(owned.get("offset"), Some(&Value::I64(-4)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serialize() {
+        let bump = Bump::new();
+        let mut ctx = ArenaContext::new_in(&bump);
+        ctx.insert_str("job", "export");
+        ctx.insert("user", &Value::Map(BTreeMap::from([(s("name"), s("jane"))])));
+        let json = serde_json::to_string(&ctx).unwrap();
+        assert_eq!(json, r#"{"job":"export","user":{"name":"jane"}}"#);
+        assert_eq!(Context::from_json(&json).unwrap().inner(), ctx.inner());
+    }
+
+    #[test]
+    fn test_reset_reuses_arena() {
+        let mut bump = Bump::new();
+        for item in 0..100u64 {
+            let mut ctx = ArenaContext::new_in(&bump);
+            ctx.insert_str("job", "nightly-export");
+            ctx.insert("item", &Value::U64(item));
+            assert_eq!(ctx.get("item"), Some(&ArenaValue::U64(item)));
+            drop(ctx);
+            bump.reset();
+        }
+        assert!(bump.allocated_bytes() > 0);
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+
+    #[test]
+    fn test_compare_and_set() {
+        let mut ctx = Context::new();
+        assert!(ctx.compare_and_set("state", None, Value::String("running".to_string())));
+        assert!(!ctx.compare_and_set("state", None, Value::String("other".to_string())));
+        assert!(ctx.compare_and_set("state", Some(&Value::String("running".to_string())), Value::String("done".to_string())));
+        assert_eq!(ctx.get("state"), Some(&Value::String("done".to_string())));
+        assert!(!ctx.compare_and_set("state", Some(&Value::String("running".to_string())), Value::Unit));
+    }
+
+    #[test]
+    fn test_compare_and_set_on_protected_key() {
+        let mut ctx = Context::new();
+        ctx.protect_key("request_id");
+        ctx.insert("request_id".to_string(), Value::U8(1));
+        assert!(!ctx.compare_and_set("request_id", Some(&Value::U8(1)), Value::U8(2)));
+        assert_eq!(ctx.get("request_id"), Some(&Value::U8(1)));
+    }
+
+    #[test]
+    fn test_fetch_update() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.fetch_update("marker", |_| None), Err(None));
+        assert_eq!(ctx.fetch_update("marker", |_| Some(Value::Bool(true))), Ok(None));
+        assert_eq!(ctx.fetch_update("marker", |_| Some(Value::Bool(false))), Ok(Some(Value::Bool(true))));
+        assert_eq!(ctx.fetch_update("marker", |_| None), Err(Some(Value::Bool(false))));
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        let shared = SharedContext::new(Context::new());
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        shared
+                            .fetch_update("count", |current| match current {
+                                Some(Value::U64(n)) => Some(Value::U64(n + 1)),
+                                _ => Some(Value::U64(1)),
+                            })
+                            .unwrap();
+                    }
+                    shared.compare_and_set("owner", None, Value::U8(worker))
+                })
+            })
+            .collect();
+        let owners = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter(|claimed| *claimed)
+            .count();
+        assert_eq!(owners, 1);
+        assert_eq!(shared.get("count"), Some(Value::U64(800)));
+    }
+
+    #[cfg(feature = "arc-swap")]
+    #[test]
+    fn test_lock_free() {
+        let shared = SharedContext::lock_free(Context::new());
+        assert!(shared.compare_and_set("owner", None, Value::U8(1)));
+        assert!(!shared.compare_and_set("owner", None, Value::U8(2)));
+        assert_eq!(shared.fetch_update("owner", |_| Some(Value::U8(3))), Ok(Some(Value::U8(1))));
+    }
+}
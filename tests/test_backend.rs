@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyOrder};
+    #[cfg(feature = "ordered")]
+    use serde_value::Value;
+
+    #[test]
+    fn test_default_key_order() {
+        let expected = match cfg!(feature = "ordered") {
+            true => KeyOrder::Insertion,
+            false => KeyOrder::Alphabetical,
+        };
+        assert_eq!(KeyOrder::default(), expected);
+        assert_eq!(Context::new().key_order(), &expected);
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn test_insertion_order() {
+        let mut ctx = Context::new();
+        for k in ["request_id", "zeta", "alpha", "mid"] {
+            ctx.insert(k.to_string(), Value::String(k.to_string()));
+        }
+        ctx.remove("zeta");
+        ctx.insert("zeta".to_string(), Value::U8(1));
+        ctx.insert("alpha".to_string(), Value::U8(2));
+        let keys: Vec<String> = ctx.ordered_entries().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["request_id", "alpha", "mid", "zeta"]);
+    }
+
+    #[cfg(all(feature = "ordered", feature = "json"))]
+    #[test]
+    fn test_json_roundtrip_order() {
+        let json = r#"{"zeta":1,"alpha":2,"request_id":"abc"}"#;
+        let ctx = Context::from_json(json).unwrap();
+        assert_eq!(ctx.to_json(false).unwrap(), json);
+
+        let mut extended = Context::new();
+        extended.insert("request_id".to_string(), Value::String("abc".to_string()));
+        extended.extend_ordered(Context::from_json(r#"{"zeta":1,"alpha":2}"#).unwrap().ordered_entries());
+        assert_eq!(extended.to_json(false).unwrap(), r#"{"request_id":"abc","zeta":1,"alpha":2}"#);
+    }
+
+    #[cfg(all(feature = "ordered", feature = "yaml"))]
+    #[test]
+    fn test_yaml_roundtrip_order() {
+        let yaml = "zeta: 1\nalpha: 2\n";
+        assert_eq!(Context::from_yaml(yaml).unwrap().to_yaml().unwrap(), yaml);
+    }
+
+    #[cfg(all(feature = "ordered", feature = "toml"))]
+    #[test]
+    fn test_toml_roundtrip_order() {
+        let toml = "zeta = 1\nalpha = 2\n";
+        assert_eq!(Context::from_toml(toml).unwrap().to_toml(false).unwrap(), toml);
+    }
+}
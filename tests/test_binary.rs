@@ -0,0 +1,44 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([
+            ("payload", Value::Bytes(vec![0, 159, 146, 150])),
+            ("attempt", Value::U8(3)),
+            ("ratio", Value::F64(0.25)),
+            ("parent", Value::Option(None)),
+            (
+                "user",
+                Value::Map(BTreeMap::from([
+                    (s("id"), Value::I64(-42)),
+                    (s("tags"), Value::Seq(vec![Value::Char('a'), Value::Unit])),
+                ])),
+            ),
+        ])
+    }
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn test_bincode_roundtrip() {
+        let ctx = context();
+        let bytes = ctx.to_bincode().unwrap();
+        assert_eq!(Context::from_bincode(&bytes).unwrap().inner(), ctx.inner());
+        assert!(Context::from_bincode(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn test_postcard_roundtrip() {
+        let ctx = context();
+        let bytes = ctx.to_postcard().unwrap();
+        assert_eq!(Context::from_postcard(&bytes).unwrap().inner(), ctx.inner());
+        assert!(Context::from_postcard(&[0xff]).is_err());
+    }
+}
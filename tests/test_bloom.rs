@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+
+    fn context(keys: usize) -> Context {
+        let mut ctx = Context::new();
+        for index in 0..keys {
+            ctx.insert(format!("key_{}", index), Value::U64(index as u64));
+        }
+        ctx
+    }
+
+    #[test]
+    fn test_no_false_negatives() {
+        let ctx = context(200);
+        let filter = ctx.key_filter();
+        assert!(filter.len() <= 1 + 200 * 10 / 8);
+        for index in 0..200 {
+            assert!(Context::may_contain(&filter, &format!("key_{}", index)));
+        }
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let filter = context(200).key_filter();
+        let false_positives = (0..10_000)
+            .filter(|index| Context::may_contain(&filter, &format!("other_{}", index)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_empty_and_invalid_filters() {
+        let filter = Context::new().key_filter();
+        assert!(!Context::may_contain(&filter, "request_id"));
+        assert!(!Context::may_contain(&[], "request_id"));
+        assert!(!Context::may_contain(&[7], "request_id"));
+    }
+
+    #[test]
+    fn test_filter_is_stable() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        assert_eq!(ctx.key_filter(), ctx.clone().key_filter());
+        assert_eq!(ctx.key_filter().len(), 3);
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "json", feature = "msgpack"))]
+    use crate::common::{context_of, s};
+    use cdumay_context::{BorrowedValue, ContextRef};
+    #[cfg(any(feature = "json", feature = "msgpack"))]
+    use cdumay_context::{Context, Contextualize};
+    use serde::Deserialize;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[cfg(any(feature = "json", feature = "msgpack"))]
+    fn context() -> Context {
+        context_of([
+            ("request_id", s("abc")),
+            ("attempt", Value::U64(2)),
+            ("offset", Value::I64(-4)),
+            ("ratio", Value::F64(0.5)),
+            ("tags", Value::Seq(vec![s("eu"), Value::Bool(true)])),
+            ("user", Value::Map(BTreeMap::from([(s("name"), s("jane"))]))),
+        ])
+    }
+
+    #[test]
+    fn test_deserialize_from_value() {
+        let value = Value::Map(BTreeMap::from([(Value::String("step".to_string()), Value::U8(3))]));
+        let ctx = ContextRef::deserialize(value).unwrap();
+        assert_eq!(ctx.get("step"), Some(&BorrowedValue::U64(3)));
+        assert_eq!(ctx.len(), 1);
+
+        assert!(ContextRef::deserialize(Value::U8(3)).is_err());
+        let value = Value::Map(BTreeMap::from([(Value::U8(1), Value::U8(3))]));
+        assert!(ContextRef::deserialize(value).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_from_json() {
+        let original = context();
+        let json = original.to_json(false).unwrap();
+        let ctx = ContextRef::from_json(&json).unwrap();
+        assert_eq!(
+            ctx.keys().collect::<Vec<_>>(),
+            vec!["attempt", "offset", "ratio", "request_id", "tags", "user"]
+        );
+        assert!(ctx.get("request_id").unwrap().is_borrowed());
+        assert_eq!(
+            ctx.get("user").and_then(|user| user.get("name")).and_then(BorrowedValue::as_str),
+            Some("jane")
+        );
+        assert_eq!(ctx.to_context().inner(), Context::from_json(&json).unwrap().inner());
+
+        let escaped = ContextRef::from_json(r#"{"message":"line\nbreak"}"#).unwrap();
+        assert_eq!(escaped.get("message").and_then(BorrowedValue::as_str), Some("line\nbreak"));
+        assert!(!escaped.get("message").unwrap().is_borrowed());
+
+        assert!(ContextRef::from_json("[1, 2]").is_err());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_from_msgpack() {
+        let original = context();
+        let bytes = rmp_serde::to_vec(&original.inner()).unwrap();
+        let ctx = ContextRef::from_msgpack(&bytes).unwrap();
+        assert!(ctx.get("request_id").unwrap().is_borrowed());
+        assert_eq!(ctx.get("offset"), Some(&BorrowedValue::I64(-4)));
+        assert_eq!(Context::from(&ctx).inner(), original.inner());
+
+        assert_eq!(ContextRef::from_msgpack(&[0xc1]).unwrap_err().code(), 400);
+    }
+}
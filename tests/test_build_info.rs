@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{BuildInfo, Context, Contextualize, BUILD_KEY};
+
+    #[test]
+    fn test_with_build_info() {
+        let info = BuildInfo {
+            git_sha: Some("4f1c2e9".to_string()),
+            build_date: Some("2024-05-01T10:00:00Z".to_string()),
+            profile: Some("release".to_string()),
+            rustc_version: Some("1.78.0".to_string()),
+        };
+        let ctx = Context::new().with_build_info(&info);
+        let build = ctx.at(BUILD_KEY);
+        assert_eq!(build.at("git_sha").as_str(), Some("4f1c2e9"));
+        assert_eq!(build.at("build_date").as_str(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(build.at("profile").as_str(), Some("release"));
+        assert_eq!(build.at("rustc_version").as_str(), Some("1.78.0"));
+    }
+
+    #[test]
+    fn test_with_empty_build_info() {
+        let ctx = Context::new().with_build_info(&BuildInfo::default());
+        assert!(ctx.get(BUILD_KEY).is_none());
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SourceId};
+    use serde::{Serialize, Serializer};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    struct Broken;
+
+    impl Serialize for Broken {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("broken value"))
+        }
+    }
+
+    #[test]
+    fn test_with_and_with_opt() {
+        let ctx = Context::builder()
+            .with("user", 42u64)
+            .with("name", "alice")
+            .with_opt("team", Some("core"))
+            .with_opt::<&str>("missing", None)
+            .build()
+            .unwrap();
+
+        assert_eq!(ctx.get("user"), Some(&Value::U64(42)));
+        assert_eq!(ctx.get("name"), Some(&Value::String("alice".to_string())));
+        assert_eq!(ctx.get("team"), Some(&Value::String("core".to_string())));
+        assert!(ctx.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut existing = Context::new();
+        existing.insert("env".to_string(), Value::String("prod".to_string()));
+        existing.insert("user".to_string(), Value::U64(1));
+
+        let ctx = Context::builder().with("user", 42u64).merge(&existing).with("step", 3u8).build().unwrap();
+        assert_eq!(ctx.get("env"), Some(&Value::String("prod".to_string())));
+        assert_eq!(ctx.get("user"), Some(&Value::U64(1)));
+        assert_eq!(ctx.get("step"), Some(&Value::U8(3)));
+    }
+
+    #[test]
+    fn test_serialization_failure() {
+        let err = Context::builder().with("ok", true).with("broken", Broken).build().unwrap_err();
+        assert!(err.message().contains("broken"));
+        assert!(err.details().contains_key("ok"));
+    }
+
+    fn map(entries: &[(&str, Value)]) -> Value {
+        Value::Map(entries.iter().map(|(k, v)| (Value::String(k.to_string()), v.clone())).collect())
+    }
+
+    #[test]
+    fn test_layers_and_origins() {
+        std::env::set_var("BUILDER_TEST_DB__POOL__SIZE", "8");
+        std::env::set_var("BUILDER_TEST_DEBUG", "true");
+        let defaults = BTreeMap::from([
+            (
+                "db".to_string(),
+                map(&[("host", Value::String("localhost".to_string())), ("port", Value::I64(5432))]),
+            ),
+            ("debug".to_string(), Value::Bool(false)),
+        ]);
+
+        let builder = Context::builder()
+            .with_defaults(defaults)
+            .with_env("BUILDER_TEST")
+            .with_overrides(["db.port=6432", "name =api"])
+            .with("region", "eu");
+        assert_eq!(builder.origin("db.host"), Some(&SourceId::Defaults));
+        assert_eq!(builder.origin("db.pool.size"), Some(&SourceId::Env("BUILDER_TEST".to_string())));
+        assert_eq!(builder.origin("db.pool"), Some(&SourceId::Env("BUILDER_TEST".to_string())));
+        assert_eq!(builder.origin("db.port"), Some(&SourceId::Overrides));
+        assert_eq!(builder.origin("db"), None);
+        assert_eq!(builder.origin("region"), Some(&SourceId::Builder));
+        assert_eq!(builder.origin("missing"), None);
+        assert_eq!(SourceId::Env("APP".to_string()).to_string(), "env:APP");
+
+        let ctx = builder.build().unwrap();
+        assert_eq!(ctx.at("db").at("host").as_str(), Some("localhost"));
+        assert_eq!(ctx.at("db").at("port").as_i64(), Some(6432));
+        assert_eq!(ctx.at("db").at("pool").at("size").as_i64(), Some(8));
+        assert_eq!(ctx.get("debug"), Some(&Value::Bool(true)));
+        assert_eq!(ctx.get_str("name").unwrap(), "api");
+    }
+
+    #[test]
+    fn test_replaced_values_origins() {
+        let builder = Context::builder()
+            .with_overrides(["db.host=a", "db.port=1"])
+            .with("db", "sqlite")
+            .with_overrides(["db.path=/tmp/db"]);
+        assert_eq!(builder.origins().len(), 1);
+        assert_eq!(builder.origin("db.path"), Some(&SourceId::Overrides));
+        assert_eq!(builder.build().unwrap().at("db").at("path").as_str(), Some("/tmp/db"));
+    }
+
+    #[test]
+    fn test_layer_failures() {
+        let err = Context::builder().with_overrides(["db.host"]).build().unwrap_err();
+        assert_eq!(err.code(), 422);
+
+        let ctx = Context::builder()
+            .with_optional_file("/nonexistent/app.json")
+            .with("ok", true)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.len(), 1);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_file_layer() {
+        let path = std::env::temp_dir().join(format!("cdumay_context_builder_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"db":{"host":"db.internal"}}"#).unwrap();
+        let builder = Context::builder()
+            .with_defaults(BTreeMap::from([("db".to_string(), map(&[("port", Value::I64(5432))]))]))
+            .with_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(builder.origin("db.host"), Some(&SourceId::File(path.clone())));
+        let ctx = builder.build().unwrap();
+        assert_eq!(ctx.at("db").at("host").as_str(), Some("db.internal"));
+        assert_eq!(ctx.at("db").at("port").as_i64(), Some(5432));
+        assert_eq!(Context::builder().with_file(&path).build().unwrap_err().code(), 404);
+    }
+}
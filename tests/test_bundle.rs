@@ -0,0 +1,53 @@
+#[cfg(test)]
+#[cfg(feature = "json")]
+mod tests {
+    use cdumay_context::{Context, Contextualize, Lifecycle, Sensitivity};
+    use serde_value::Value;
+    use std::path::PathBuf;
+
+    fn bundle_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("cdumay-bundle-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let mut ctx = Context::new();
+        ctx.start_lifecycle().unwrap();
+        ctx.protect_key("request_id");
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx.record_hop("gateway", "headers");
+        ctx.activate().unwrap();
+        ctx.seal().unwrap();
+
+        let dir = bundle_dir("roundtrip");
+        ctx.export_bundle(&dir).unwrap();
+        let metadata = std::fs::read_to_string(dir.join("metadata.json")).unwrap();
+        let replayed = Context::import_bundle(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(metadata.contains("\"protected\": true"));
+        assert_eq!(replayed.inner(), ctx.inner());
+        assert!(replayed.is_protected("request_id"));
+        assert_eq!(replayed.sensitivity("token"), Sensitivity::Secret);
+        assert_eq!(replayed.lifecycle(), Some(Lifecycle::Sealed));
+        assert_eq!(replayed.hops().len(), 1);
+    }
+
+    #[test]
+    fn test_import_missing_bundle() {
+        let err = Context::import_bundle(bundle_dir("missing")).unwrap_err();
+        assert_eq!(err.code(), 404);
+    }
+
+    #[test]
+    fn test_import_invalid_metadata() {
+        let dir = bundle_dir("invalid");
+        Context::new().export_bundle(&dir).unwrap();
+        std::fs::write(dir.join("metadata.json"), "[]").unwrap();
+        let result = Context::import_bundle(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.unwrap_err().code(), 400);
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_canonical_bytes() {
+        let nested = Value::Map(BTreeMap::from([
+            (Value::String("b".to_string()), Value::F32(0.1)),
+            (Value::U8(1), Value::Option(Some(Box::new(Value::Char('x'))))),
+            (
+                Value::String("a".to_string()),
+                Value::Seq(vec![Value::Unit, Value::F64(-0.0), Value::F64(f64::NAN)]),
+            ),
+        ]));
+        let mut ctx = Context::new();
+        ctx.insert("z".to_string(), Value::String("line\n\"quoted\"\u{1}".to_string()));
+        ctx.insert("nested".to_string(), nested);
+        ctx.insert("bytes".to_string(), Value::Bytes(vec![1, 2]));
+
+        let expected = r#"{"bytes":[1,2],"nested":{"1":"x","a":[null,0,"NaN"],"b":0.1},"z":"line\n\"quoted\"\u0001"}"#;
+        assert_eq!(String::from_utf8(ctx.canonical_bytes()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_canonical_bytes_ignore_construction() {
+        let mut a = Context::new();
+        a.insert("count".to_string(), Value::I64(3));
+        a.insert("ratio".to_string(), Value::F32(0.5));
+        a.insert_lazy("digest", || Value::String("4f2a".to_string()));
+
+        let mut b = Context::new();
+        b.insert("digest".to_string(), Value::String("4f2a".to_string()));
+        b.insert("ratio".to_string(), Value::F64(0.5));
+        b.insert("count".to_string(), Value::U8(3));
+
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        b.insert("count".to_string(), Value::U8(4));
+        assert_ne!(a.canonical_bytes(), b.canonical_bytes());
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            Context::new().content_hash(),
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+
+        let mut a = Context::new();
+        a.insert("status".to_string(), Value::U16(503));
+        let mut b = Context::new();
+        b.insert("status".to_string(), Value::F64(503.0));
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(a.content_hash().len(), 64);
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{capabilities, Capability, Context, Contextualize, Format};
+    use serde_value::Value;
+
+    #[test]
+    fn test_capabilities() {
+        let enabled = capabilities();
+        assert_eq!(enabled.contains(&Capability::Json), cfg!(feature = "json"));
+        assert_eq!(enabled.contains(&Capability::SimdJson), cfg!(feature = "simd-json"));
+        assert!(enabled.iter().all(Capability::is_enabled));
+        assert_eq!(Capability::ALL.len(), 35);
+        assert_eq!(Capability::ArcSwap.feature(), "arc-swap");
+        assert_eq!(Capability::from(Format::Toml), Capability::Toml);
+    }
+
+    #[test]
+    fn test_require() {
+        for capability in Capability::ALL {
+            match capability.require() {
+                Ok(()) => assert!(capability.is_enabled()),
+                Err(err) => {
+                    assert!(!capability.is_enabled());
+                    assert_eq!(err.code(), 501);
+                    assert!(err.class().contains("FeatureDisabled"));
+                    assert!(err.message().contains(&format!("'{}'", capability.feature())));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dump_and_load() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        for format in [Format::Json, Format::Toml, Format::Yaml] {
+            match Capability::from(format).is_enabled() {
+                true => {
+                    let dump = ctx.dump_to(format, false).unwrap();
+                    assert_eq!(Context::load_from(format, &dump).unwrap().inner(), ctx.inner());
+                }
+                false => {
+                    assert_eq!(ctx.dump_to(format, false).unwrap_err().code(), 501);
+                    assert_eq!(Context::load_from(format, "").unwrap_err().code(), 501);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct QueryFailed {
+        source: std::io::Error,
+    }
+
+    impl fmt::Display for QueryFailed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "query failed")
+        }
+    }
+
+    impl std::error::Error for QueryFailed {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    fn causes(ctx: &Context) -> Vec<Value> {
+        ctx.at("error").at("causes").as_seq().map(<[Value]>::to_vec).unwrap_or_default()
+    }
+
+    fn field(cause: &Value, name: &str) -> Option<Value> {
+        match cause {
+            Value::Map(map) => map.get(&Value::String(name.to_string())).cloned(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_record_causes() {
+        let err = QueryFailed {
+            source: std::io::Error::other("connection reset"),
+        };
+        let mut ctx = Context::new();
+        assert_eq!(ctx.record_causes(&err), 2);
+
+        let causes = causes(&ctx);
+        assert_eq!(field(&causes[0], "index"), Some(Value::U64(0)));
+        assert_eq!(
+            field(&causes[0], "type"),
+            Some(Value::String(std::any::type_name::<QueryFailed>().to_string()))
+        );
+        assert_eq!(field(&causes[0], "message"), Some(Value::String("query failed".into())));
+        assert_eq!(field(&causes[1], "index"), Some(Value::U64(1)));
+        assert_eq!(field(&causes[1], "type"), Some(Value::String("Custom".into())));
+        assert_eq!(field(&causes[1], "message"), Some(Value::String("connection reset".into())));
+    }
+
+    #[test]
+    fn test_record_causes_dyn() {
+        let err: Box<dyn std::error::Error> = Box::new(QueryFailed {
+            source: std::io::Error::other("timeout"),
+        });
+        let mut ctx = Context::new();
+        assert_eq!(ctx.record_causes(err.as_ref()), 2);
+        assert_eq!(field(&causes(&ctx)[0], "type"), Some(Value::String("QueryFailed".into())));
+    }
+
+    #[test]
+    fn test_record_causes_keeps_error_entries() {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "error".to_string(),
+            Value::Map([(Value::String("code".into()), Value::U64(500))].into_iter().collect()),
+        );
+        ctx.record_causes(&std::io::Error::other("first"));
+        ctx.record_causes(&std::io::Error::other("second"));
+
+        assert_eq!(ctx.at("error").at("code").as_u64(), Some(500));
+        let causes = causes(&ctx);
+        assert_eq!(causes.len(), 1);
+        assert_eq!(field(&causes[0], "message"), Some(Value::String("second".into())));
+    }
+}
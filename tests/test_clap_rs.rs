@@ -0,0 +1,60 @@
+#[cfg(test)]
+#[cfg(feature = "clap")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use clap::{Arg, ArgAction, Command};
+    use serde_value::Value;
+
+    fn command() -> Command {
+        Command::new("tool")
+            .arg(Arg::new("verbose").short('v').action(ArgAction::SetTrue))
+            .arg(Arg::new("region").long("region").default_value("eu"))
+            .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+            .arg(Arg::new("db-password").long("db-password"))
+            .subcommand(
+                Command::new("deploy")
+                    .arg(Arg::new("target"))
+                    .arg(Arg::new("SECRET_KEY").long("secret-key")),
+            )
+    }
+
+    #[test]
+    fn test_from_arg_matches() {
+        let matches = command().get_matches_from([
+            "tool",
+            "-v",
+            "--tag",
+            "a",
+            "--tag",
+            "b",
+            "--db-password",
+            "hunter2",
+            "deploy",
+            "web",
+            "--secret-key",
+            "xyz",
+        ]);
+        let ctx = Context::from_arg_matches(&matches);
+
+        assert_eq!(ctx.get("verbose"), Some(&Value::String("true".to_string())));
+        assert_eq!(ctx.get("region"), Some(&Value::String("eu".to_string())));
+        assert_eq!(
+            ctx.get("tag"),
+            Some(&Value::Seq(vec![Value::String("a".to_string()), Value::String("b".to_string())]))
+        );
+        assert!(ctx.get("db-password").is_none());
+        assert_eq!(ctx.at("subcommand").at("name").as_str(), Some("deploy"));
+        assert_eq!(ctx.at("subcommand").at("args").at("target").as_str(), Some("web"));
+        assert!(ctx.at("subcommand").at("args").at("SECRET_KEY").as_str().is_none());
+    }
+
+    #[test]
+    fn test_from_arg_matches_with() {
+        let matches = command().get_matches_from(["tool", "--region", "us", "--db-password", "hunter2"]);
+        let ctx = Context::from_arg_matches_with(&matches, &["region"]);
+
+        assert!(ctx.get("region").is_none());
+        assert_eq!(ctx.get("db-password"), Some(&Value::String("hunter2".to_string())));
+        assert!(ctx.get("tag").is_none());
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, MatchOptions, MismatchKind};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn http(status: u16, path: &str) -> Value {
+        let mut map = BTreeMap::new();
+        map.insert(Value::String("status".to_string()), Value::U16(status));
+        map.insert(Value::String("path".to_string()), Value::String(path.to_string()));
+        Value::Map(map)
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let mut actual = Context::new();
+        actual.insert("user".to_string(), Value::String("alice".to_string()));
+        actual.insert("http".to_string(), http(200, "/"));
+        let mut expected = Context::new();
+        expected.insert("user".to_string(), Value::String("alice".to_string()));
+        expected.insert("http".to_string(), http(200, "/"));
+
+        let report = actual.compare(&expected, &MatchOptions::new());
+        assert!(report.is_match());
+        actual.assert_matches(&expected, &MatchOptions::new());
+    }
+
+    #[test]
+    fn test_mismatch_report() {
+        let mut actual = Context::new();
+        actual.insert("http".to_string(), http(500, "/"));
+        actual.insert("extra".to_string(), Value::Bool(true));
+        let mut expected = Context::new();
+        expected.insert("http".to_string(), http(200, "/"));
+        expected.insert("user".to_string(), Value::String("alice".to_string()));
+
+        let report = actual.compare(&expected, &MatchOptions::new());
+        let paths: Vec<&str> = report.mismatches().iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["extra", "http.status", "user"]);
+        assert_eq!(report.mismatches()[0].kind, MismatchKind::Unexpected { actual: Value::Bool(true) });
+        assert_eq!(
+            report.mismatches()[2].kind,
+            MismatchKind::Missing {
+                expected: Value::String("alice".to_string())
+            }
+        );
+        assert!(report.to_string().contains("http.status"));
+    }
+
+    #[test]
+    fn test_subset() {
+        let mut actual = Context::new();
+        actual.insert("http".to_string(), http(404, "/"));
+        actual.insert("initial".to_string(), Value::Char('J'));
+        actual.insert("team".to_string(), Value::Option(None));
+        actual.insert(
+            "roles".to_string(),
+            Value::Seq(vec![Value::String("admin".to_string()), Value::String("ops".to_string())]),
+        );
+        let mut expected = Context::new();
+        expected.insert(
+            "http".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("status".to_string()), Value::I64(404))])),
+        );
+        expected.insert("initial".to_string(), Value::Option(Some(Box::new(Value::String("J".to_string())))));
+        expected.insert("team".to_string(), Value::Unit);
+        assert!(actual.compare(&expected, &MatchOptions::new().subset()).is_match());
+        assert!(!actual.compare(&expected, &MatchOptions::new()).is_match());
+
+        expected.insert("roles".to_string(), Value::Seq(vec![Value::String("admin".to_string())]));
+        expected.insert("user".to_string(), Value::Bool(true));
+        let report = actual.compare(&expected, &MatchOptions::new().subset());
+        let paths: Vec<&str> = report.mismatches().iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["roles.1", "user"]);
+        assert_eq!(
+            report.mismatches()[0].kind,
+            MismatchKind::Unexpected {
+                actual: Value::String("ops".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_ignored_keys_and_numbers() {
+        let mut actual = Context::new();
+        actual.insert("timestamp".to_string(), Value::U64(1_700_000_000));
+        actual.insert("duration".to_string(), Value::F64(1.2));
+        actual.insert("count".to_string(), Value::I64(3));
+        let mut expected = Context::new();
+        expected.insert("duration".to_string(), Value::F64(1.0));
+        expected.insert("count".to_string(), Value::U8(3));
+
+        let options = MatchOptions::new().ignore("timestamp");
+        let report = actual.compare(&expected, &options);
+        assert_eq!(report.mismatches().len(), 1);
+        assert_eq!(report.mismatches()[0].path, "duration");
+
+        let options = options.with_tolerance("duration", 0.1);
+        assert!(matches!(
+            actual.compare(&expected, &options).mismatches()[0].kind,
+            MismatchKind::ToleranceExceeded { .. }
+        ));
+        actual.assert_matches(&expected, &options.with_tolerance("duration", 0.25));
+    }
+
+    #[test]
+    #[should_panic(expected = "context does not match")]
+    fn test_assert_matches_panics() {
+        let mut actual = Context::new();
+        actual.insert("user".to_string(), Value::String("bob".to_string()));
+        let mut expected = Context::new();
+        expected.insert("user".to_string(), Value::String("alice".to_string()));
+        actual.assert_matches(&expected, &MatchOptions::new());
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_pattern_matchers() {
+        let mut actual = Context::new();
+        actual.insert("request_id".to_string(), Value::String("req-1234".to_string()));
+        let mut expected = Context::new();
+        expected.insert("request_id".to_string(), Value::String("whatever".to_string()));
+
+        let options = MatchOptions::new().with_pattern("request_id", regex::Regex::new(r"^req-\d+$").unwrap());
+        assert!(actual.compare(&expected, &options).is_match());
+
+        let options = MatchOptions::new().with_pattern("request_id", regex::Regex::new(r"^id-\d+$").unwrap());
+        assert!(matches!(
+            actual.compare(&expected, &options).mismatches()[0].kind,
+            MismatchKind::PatternMismatch { .. }
+        ));
+    }
+}
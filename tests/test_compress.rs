@@ -0,0 +1,52 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "compress")]
+mod tests {
+    use crate::common::context_of;
+    use cdumay_context::{Codec, Context, Contextualize, Format, DECOMPRESSION_LIMIT};
+    use serde_value::Value;
+
+    fn context() -> Context {
+        context_of([("body", Value::String("lorem ipsum ".repeat(500))), ("status", Value::U64(502))])
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_gz_roundtrip() {
+        let ctx = context();
+        let bytes = ctx.to_json_gz(9).unwrap();
+        assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+        assert!(bytes.len() < ctx.to_json(false).unwrap().len() / 10);
+        assert_eq!(Context::from_json_gz(&bytes).unwrap().inner(), ctx.inner());
+        assert_eq!(ctx.to_json_gz(42).unwrap(), bytes);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_codecs() {
+        let ctx = context();
+        for codec in [Codec::Gzip, Codec::Zlib, Codec::Deflate] {
+            let bytes = ctx.compress(Format::Json, codec, 6).unwrap();
+            assert_eq!(Context::decompress(Format::Json, codec, &bytes).unwrap().inner(), ctx.inner());
+        }
+        assert_eq!(Codec::Gzip.name(), "gzip");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = Context::decompress(Format::Json, Codec::Gzip, b"not gzip").unwrap_err();
+        assert_eq!(err.code(), 400);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_decompression_limit() {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&vec![b' '; DECOMPRESSION_LIMIT + 1]).unwrap();
+        let bomb = encoder.finish().unwrap();
+        let err = Context::from_json_gz(&bomb).unwrap_err();
+        assert_eq!(err.code(), 413);
+    }
+}
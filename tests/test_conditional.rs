@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_insert_if() {
+        let mut ctx = Context::new();
+        assert!(ctx.insert_if(true, "a".to_string(), Value::U8(1)));
+        assert!(!ctx.insert_if(false, "b".to_string(), Value::U8(2)));
+        assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["a"]);
+    }
+
+    #[test]
+    fn test_insert_when_is_lazy() {
+        let mut ctx = Context::new();
+        let computed = Cell::new(0);
+        let compute = || {
+            computed.set(computed.get() + 1);
+            Value::U8(1)
+        };
+        assert!(!ctx.insert_when(|ctx| ctx.get("enabled").is_some(), "a".to_string(), compute));
+        assert_eq!(computed.get(), 0);
+
+        ctx.insert("enabled".to_string(), Value::Bool(true));
+        assert!(ctx.insert_when(|ctx| ctx.get("enabled").is_some(), "a".to_string(), compute));
+        assert_eq!(computed.get(), 1);
+        assert_eq!(ctx.get("a"), Some(&Value::U8(1)));
+    }
+
+    #[test]
+    fn test_insert_when_respects_protection() {
+        let mut ctx = Context::new();
+        ctx.protect_key("a");
+        ctx.insert("a".to_string(), Value::U8(1));
+        assert!(ctx.insert_when(|_| true, "a".to_string(), || Value::U8(2)));
+        assert_eq!(ctx.get("a"), Some(&Value::U8(1)));
+    }
+
+    #[test]
+    fn test_shared_insert_when_is_atomic() {
+        let shared = SharedContext::new(Context::new());
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.insert_when(|ctx| ctx.get("owner").is_none(), "owner".to_string(), || Value::U8(worker)))
+            })
+            .collect();
+        let winners = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|won| *won).count();
+        assert_eq!(winners, 1);
+        assert!(shared.get("owner").is_some());
+        assert!(shared.insert_if(true, "b".to_string(), Value::Unit));
+        assert!(!shared.insert_if(false, "c".to_string(), Value::Unit));
+        assert_eq!(shared.get("c"), None);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+#[cfg(feature = "config")]
+mod tests {
+    use cdumay_context::{Context, Contextualize, Sensitivity};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_from_config() {
+        let config = config::Config::builder()
+            .set_default("database.host", "localhost")
+            .unwrap()
+            .set_default("database.port", 5432)
+            .unwrap()
+            .set_default("features", vec!["a", "b"])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let ctx = Context::from(config);
+        assert_eq!(ctx.at("database").at("host").as_str(), Some("localhost"));
+        assert_eq!(ctx.at("database").at("port").as_i64(), Some(5432));
+        assert_eq!(ctx.at("features").as_seq().map(|s| s.len()), Some(2));
+    }
+
+    #[test]
+    fn test_try_from_context() {
+        let mut ctx = Context::new();
+        ctx.insert("name".to_string(), Value::String("api".to_string()));
+        ctx.insert("workers".to_string(), Value::U8(4));
+        ctx.insert(
+            "database".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("port".to_string()), Value::U16(5432))])),
+        );
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.insert("email".to_string(), Value::String("alice@example.com".to_string()));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx.set_sensitivity("email", Sensitivity::Confidential);
+
+        let config: config::Config = (&ctx).try_into().unwrap();
+        assert_eq!(config.get_string("name").unwrap(), "api");
+        assert_eq!(config.get_int("workers").unwrap(), 4);
+        assert_eq!(config.get_int("database.port").unwrap(), 5432);
+        assert!(config.get_string("token").is_err());
+        assert!(config.get_string("email").is_err());
+    }
+
+    #[test]
+    fn test_try_from_context_invalid_key() {
+        let mut ctx = Context::new();
+        ctx.insert("codes".to_string(), Value::Map(BTreeMap::from([(Value::U16(404), Value::Bool(true))])));
+
+        let err = <config::Config as TryFrom<&Context>>::try_from(&ctx).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err.message().contains("codes"));
+    }
+}
@@ -0,0 +1,273 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{capabilities, conformance_vectors, Capability, Context, Contextualize, Format, CONFORMANCE_VERSION};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_check_serializable() {
+        let mut ctx = Context::new();
+        ctx.insert("payload".to_string(), Value::Seq(vec![Value::Bytes(vec![1, 2])]));
+        ctx.insert("parent".to_string(), Value::Map(BTreeMap::from([(s("id"), Value::Option(None))])));
+        assert!(ctx.check_serializable(Format::Json).is_ok());
+
+        let err = ctx.check_serializable(Format::Toml).unwrap_err();
+        assert_eq!(err.code(), 422);
+        assert!(err.class().contains("ValidationError"));
+        assert!(err.message().contains("'parent.id'"), "{}", err.message());
+
+        let err = ctx.check_serializable(Format::Yaml).unwrap_err();
+        assert!(err.message().contains("'payload.0'"), "{}", err.message());
+    }
+
+    #[test]
+    fn test_check_serializable_policies() {
+        let mut ctx = Context::new();
+        ctx.insert("ratio".to_string(), Value::F64(f64::INFINITY));
+        assert!(ctx.check_serializable(Format::Json).unwrap_err().class().contains("PrecisionLoss"));
+        assert!(ctx.check_serializable(Format::Toml).is_ok());
+
+        ctx.set_map_key_policy(cdumay_context::MapKeyPolicy::Reject);
+        ctx.insert("ratio".to_string(), Value::Map(BTreeMap::from([(Value::U8(1), Value::U8(1))])));
+        assert!(ctx.check_serializable(Format::Yaml).unwrap_err().class().contains("InvalidMapKey"));
+    }
+
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    mod round_trip {
+        use super::s;
+        use cdumay_context::{Context, Contextualize, Format};
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+        use serde_value::Value;
+
+        const STRINGS: &[&str] = &[
+            "",
+            "a",
+            "null",
+            "~",
+            "yes",
+            "1e3",
+            "0x10",
+            "-",
+            "a.b",
+            "2024-01-01",
+            "é\"\\\n\t\u{0}",
+            "[masked]",
+        ];
+
+        fn random_key(rng: &mut StdRng) -> Value {
+            match rng.random_range(0..8) {
+                0 => Value::U16(rng.random()),
+                1 => Value::Bool(rng.random()),
+                _ => s(&(0..rng.random_range(1..4)).map(|_| rng.random_range('a'..='e')).collect::<String>()),
+            }
+        }
+
+        fn random_value(rng: &mut StdRng, depth: usize) -> Value {
+            let variants = if depth == 0 { 16 } else { 20 };
+            match rng.random_range(0..variants) {
+                0 => Value::Unit,
+                1 => Value::Option(None),
+                2 => Value::Bool(rng.random()),
+                3 => Value::Char(rng.random_range('a'..='z')),
+                4 => s(STRINGS[rng.random_range(0..STRINGS.len())]),
+                5 => Value::U8(rng.random()),
+                6 => Value::I32(rng.random()),
+                7 => Value::U64(rng.random::<u64>() >> rng.random_range(0..64)),
+                8 => Value::I64(rng.random()),
+                9 => Value::F32(rng.random::<f32>() * 1e6),
+                10 => Value::F64(rng.random::<f64>() * 10f64.powi(rng.random_range(-300..300))),
+                11 => Value::F64([f64::NAN, f64::INFINITY, -0.0][rng.random_range(0..3)]),
+                12 => Value::Bytes((0..rng.random_range(0..4)).map(|_| rng.random()).collect()),
+                13 => Value::Option(Some(Box::new(Value::U8(rng.random())))),
+                14 => Value::Newtype(Box::new(s("inner"))),
+                15 => Value::I8(rng.random()),
+                16 | 17 => Value::Seq((0..rng.random_range(0..4)).map(|_| random_value(rng, depth - 1)).collect()),
+                _ => Value::Map(
+                    (0..rng.random_range(0..4))
+                        .map(|_| (random_key(rng), random_value(rng, depth - 1)))
+                        .collect(),
+                ),
+            }
+        }
+
+        fn integer(format: Format, value: i128) -> Value {
+            match (format, u64::try_from(value), i64::try_from(value)) {
+                (Format::Toml, _, Ok(value)) => Value::I64(value),
+                (_, Ok(value), _) => Value::U64(value),
+                (_, _, Ok(value)) => Value::I64(value),
+                _ => unreachable!(),
+            }
+        }
+
+        /// Returns the value read back from a dump, as documented by `Context::check_serializable`.
+        ///
+        /// Finite `F32` are kept as is for JSON and YAML, see `same`.
+        fn normalize(format: Format, value: Value) -> Value {
+            match value {
+                Value::Unit | Value::Option(None) => Value::Unit,
+                Value::Option(Some(inner)) | Value::Newtype(inner) => normalize(format, *inner),
+                Value::Char(c) => Value::String(c.to_string()),
+                Value::U8(v) => integer(format, v.into()),
+                Value::U16(v) => integer(format, v.into()),
+                Value::U32(v) => integer(format, v.into()),
+                Value::U64(v) => integer(format, v.into()),
+                Value::I8(v) => integer(format, v.into()),
+                Value::I16(v) => integer(format, v.into()),
+                Value::I32(v) => integer(format, v.into()),
+                Value::I64(v) => integer(format, v.into()),
+                Value::F32(v) if format == Format::Toml => Value::F64(v.into()),
+                Value::F32(v) if v.is_finite() => Value::F32(v),
+                Value::F32(v) => Value::F64(v.into()),
+                Value::Bytes(v) => Value::Seq(v.into_iter().map(|b| integer(format, b.into())).collect()),
+                Value::Seq(items) => Value::Seq(items.into_iter().map(|item| normalize(format, item)).collect()),
+                Value::Map(map) => Value::Map(
+                    map.into_iter()
+                        .map(|(k, v)| {
+                            let k = match k {
+                                Value::String(k) => k,
+                                Value::U16(k) => k.to_string(),
+                                Value::Bool(k) => k.to_string(),
+                                other => panic!("unexpected key {:?}", other),
+                            };
+                            (Value::String(k), normalize(format, v))
+                        })
+                        .collect(),
+                ),
+                value => value,
+            }
+        }
+
+        /// Compares a normalized value to the value read back, a `F32` matching the `F64` written
+        /// as its shortest decimal.
+        fn same(expected: &Value, actual: &Value) -> bool {
+            match (expected, actual) {
+                (Value::F32(expected), Value::F64(actual)) => *actual as f32 == *expected,
+                (Value::Seq(expected), Value::Seq(actual)) => expected.len() == actual.len() && expected.iter().zip(actual).all(|(e, a)| same(e, a)),
+                (Value::Map(expected), Value::Map(actual)) => {
+                    expected.len() == actual.len() && expected.iter().zip(actual).all(|((ek, ev), (ak, av))| ek == ak && same(ev, av))
+                }
+                (expected, actual) => expected == actual,
+            }
+        }
+
+        fn dump(ctx: &Context, format: Format) -> cdumay_core::Result<String> {
+            match format {
+                #[cfg(feature = "json")]
+                Format::Json => ctx.to_json(false),
+                #[cfg(feature = "toml")]
+                Format::Toml => ctx.to_toml(false),
+                #[cfg(feature = "yaml")]
+                Format::Yaml => ctx.to_yaml(),
+                #[allow(unreachable_patterns)]
+                _ => unreachable!(),
+            }
+        }
+
+        fn load(data: &str, format: Format) -> cdumay_core::Result<Context> {
+            match format {
+                #[cfg(feature = "json")]
+                Format::Json => Context::from_json(data),
+                #[cfg(feature = "toml")]
+                Format::Toml => Context::from_toml(data),
+                #[cfg(feature = "yaml")]
+                Format::Yaml => Context::from_yaml(data),
+                #[allow(unreachable_patterns)]
+                _ => unreachable!(),
+            }
+        }
+
+        fn formats() -> Vec<Format> {
+            [Format::Json, Format::Toml, Format::Yaml]
+                .into_iter()
+                .filter(|format| match format {
+                    Format::Json => cfg!(feature = "json"),
+                    Format::Toml => cfg!(feature = "toml"),
+                    Format::Yaml => cfg!(feature = "yaml"),
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let mut rng = StdRng::seed_from_u64(0x5eed);
+            let (mut passed, mut rejected) = (0, 0);
+            for _ in 0..2000 {
+                let mut ctx = Context::new();
+                for index in 0..rng.random_range(1..4) {
+                    ctx.insert(format!("k{}", index), random_value(&mut rng, 3));
+                }
+                for format in formats() {
+                    let checked = ctx.check_serializable(format);
+                    match (dump(&ctx, format), checked) {
+                        (Ok(data), Ok(())) => {
+                            let expected = ctx.inner().into_iter().map(|(k, v)| (s(&k), normalize(format, v))).collect();
+                            let actual = load(&data, format).unwrap().inner().into_iter().map(|(k, v)| (s(&k), v)).collect();
+                            let (expected, actual) = (Value::Map(expected), Value::Map(actual));
+                            assert!(
+                                same(&expected, &actual),
+                                "{} dump:\n{}\nexpected {:?}\nactual {:?}",
+                                format,
+                                data,
+                                expected,
+                                actual
+                            );
+                            passed += 1;
+                        }
+                        (Err(err), Err(checked)) => {
+                            assert_eq!(
+                                (err.code(), err.class(), err.message()),
+                                (checked.code(), checked.class(), checked.message())
+                            );
+                            rejected += 1;
+                        }
+                        (dumped, checked) => panic!("{} dump {:?} but check {:?} for {:?}", format, dumped.map(|_| ()), checked, ctx.inner()),
+                    }
+                }
+            }
+            assert!(passed > 100 && rejected > 100, "{} passed, {} rejected", passed, rejected);
+        }
+    }
+
+    #[test]
+    fn test_conformance_vectors() {
+        let vectors = conformance_vectors();
+        assert_eq!(vectors.len(), 16);
+        assert!(vectors.iter().all(|vector| vector.version == CONFORMANCE_VERSION));
+        for vector in vectors.iter().filter(|vector| capabilities().contains(&Capability::from(vector.format))) {
+            let mut ctx = Context::new();
+            ctx.extend(vector.input.clone());
+            assert_eq!(
+                ctx.dump_to(vector.format, vector.pretty).unwrap(),
+                vector.expected,
+                "{}",
+                vector.file_name()
+            );
+
+            let loaded = Context::load_from(vector.format, vector.expected).unwrap();
+            assert_eq!(
+                loaded.dump_to(vector.format, vector.pretty).unwrap(),
+                vector.expected,
+                "{}",
+                vector.file_name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_conformance_golden_files() {
+        for vector in conformance_vectors() {
+            let path = format!("{}/conformance/{}", env!("CARGO_MANIFEST_DIR"), vector.file_name());
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), vector.expected, "{}", path);
+        }
+        let names: Vec<String> = conformance_vectors()
+            .iter()
+            .filter(|vector| vector.format == Format::Json)
+            .map(|vector| vector.file_name())
+            .collect();
+        assert_eq!(&names[..2], &["v1/empty.json", "v1/empty.pretty.json"]);
+    }
+}
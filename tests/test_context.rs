@@ -1,8 +1,10 @@
+#![allow(clippy::approx_constant, clippy::bool_assert_comparison)]
+
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeMap;
+    use cdumay_context::{Context, Contextualize, KeyPolicy};
     use serde_value::Value;
-    use cdumay_context::{Context, Contextualize};
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_new() {
@@ -63,6 +65,28 @@ mod tests {
         assert_eq!(ctx.get("key3").unwrap(), &Value::Bool(true));
     }
 
+    #[test]
+    fn test_case_insensitive_keys() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        assert_eq!(ctx.key_policy(), KeyPolicy::CaseInsensitive);
+
+        ctx.insert("User-Agent".to_string(), Value::String("curl".to_string()));
+        ctx.insert("USER-AGENT".to_string(), Value::String("wget".to_string()));
+        let mut data = BTreeMap::new();
+        data.insert("X-Request-Id".to_string(), Value::U64(1));
+        ctx.extend(data);
+
+        assert_eq!(ctx.inner().len(), 2);
+        assert_eq!(ctx.get("user-agent").unwrap(), &Value::String("wget".to_string()));
+        assert_eq!(ctx["x-request-ID"], Value::U64(1));
+        ctx["X-REQUEST-ID"] = Value::U64(2);
+        assert_eq!(ctx.get("x-request-id").unwrap(), &Value::U64(2));
+
+        let mut sensitive = Context::new();
+        sensitive.insert("Key".to_string(), Value::Bool(true));
+        assert!(sensitive.get("key").is_none());
+    }
+
     #[test]
     fn test_inner() {
         let mut ctx = Context::new();
@@ -152,4 +176,19 @@ mod tests {
         // Test invalid YAML
         assert!(Context::from_yaml("invalid: - yaml: ]").is_err());
     }
+
+    #[test]
+    fn test_inner_ref() {
+        use std::borrow::Cow;
+
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        assert_eq!(matches!(ctx.inner_ref(), Cow::Borrowed(_)), cfg!(not(feature = "ordered")));
+        assert_eq!(ctx.inner_ref().as_ref(), &ctx.inner());
+
+        ctx.register_default("env", || Value::String("prod".to_string()));
+        let data = ctx.inner_ref();
+        assert!(matches!(data, Cow::Owned(_)));
+        assert_eq!(data.get("env"), Some(&Value::String("prod".to_string())));
+    }
 }
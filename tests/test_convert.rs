@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_from_btreemap() {
+        let mut map = BTreeMap::new();
+        map.insert("user".to_string(), Value::U64(42));
+        let ctx = Context::from(map.clone());
+        assert_eq!(ctx.inner(), map);
+
+        let back: BTreeMap<String, Value> = ctx.into();
+        assert_eq!(back, map);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let ctx = Context::from(vec![
+            ("b".to_string(), Value::Bool(true)),
+            ("a".to_string(), Value::String("x".to_string())),
+        ]);
+        assert_eq!(ctx.get("b"), Some(&Value::Bool(true)));
+
+        let pairs: Vec<(String, Value)> = ctx.into();
+        assert_eq!(pairs[0].0, "a");
+        assert_eq!(pairs[1].0, "b");
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_value() {
+        let json = serde_json::json!({"user": "alice", "count": 3});
+        let ctx = Context::try_from(json.clone()).unwrap();
+        assert_eq!(ctx.get("user"), Some(&Value::String("alice".to_string())));
+
+        let back = serde_json::Value::try_from(ctx).unwrap();
+        assert_eq!(back, json);
+
+        assert!(Context::try_from(serde_json::json!([1, 2])).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_map() {
+        let json = serde_json::json!({"zone": "eu", "user": "alice", "attempt": 3});
+        let serde_json::Value::Object(map) = json else { unreachable!() };
+        let ctx = Context::try_from(map.clone()).unwrap();
+        assert_eq!(ctx.key_order(), &cdumay_context::KeyOrder::Insertion);
+
+        let back = serde_json::Map::try_from(ctx).unwrap();
+        assert_eq!(back, map);
+        let keys: Vec<&String> = back.keys().collect();
+        match cfg!(feature = "preserve-order") {
+            true => assert_eq!(keys, vec!["zone", "user", "attempt"]),
+            false => assert_eq!(keys, vec!["attempt", "user", "zone"]),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_map_applies_config() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("alice".to_string()));
+        ctx.insert("password".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_config(cdumay_context::ContextConfig::new().redact("password"));
+        let map = serde_json::Map::try_from(ctx).unwrap();
+        assert_eq!(map["password"], serde_json::json!(cdumay_context::REDACTED));
+
+        let mut ctx = Context::new();
+        ctx.insert("ratio".to_string(), Value::F64(f64::NAN));
+        assert_eq!(serde_json::Map::try_from(ctx).unwrap_err().code(), 400);
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+
+    #[test]
+    fn test_increment() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.increment("bytes_read", 4096).unwrap(), 4096);
+        assert_eq!(ctx.increment("bytes_read", -96).unwrap(), 4000);
+        ctx.insert("retries".to_string(), Value::U8(2));
+        assert_eq!(ctx.increment("retries", 1).unwrap(), 3);
+        assert_eq!(ctx.get("retries"), Some(&Value::I64(3)));
+    }
+
+    #[test]
+    fn test_increment_errors() {
+        let mut ctx = Context::new();
+        ctx.insert("name".to_string(), Value::String("job".to_string()));
+        let err = ctx.increment("name", 1).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert_eq!(ctx.get("name"), Some(&Value::String("job".to_string())));
+
+        ctx.insert("big".to_string(), Value::I64(i64::MAX));
+        assert!(ctx.increment("big", 1).is_err());
+
+        ctx.insert("total".to_string(), Value::I64(1));
+        ctx.protect_key("total");
+        assert_eq!(ctx.increment("total", 1).unwrap_err().code(), 409);
+        assert_eq!(ctx.get("total"), Some(&Value::I64(1)));
+    }
+
+    #[test]
+    fn test_max() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.max("peak_rss", 200).unwrap(), 200);
+        assert_eq!(ctx.max("peak_rss", 300).unwrap(), 300);
+        assert_eq!(ctx.max("peak_rss", 100).unwrap(), 300);
+        assert_eq!(ctx.get("peak_rss"), Some(&Value::I64(300)));
+        ctx.insert("name".to_string(), Value::Bool(true));
+        assert!(ctx.max("name", 1).is_err());
+    }
+
+    #[test]
+    fn test_shared_increment() {
+        let shared = SharedContext::default();
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        shared.increment("requests", 1).unwrap();
+                    }
+                    shared.max("peak", i).unwrap();
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+        assert_eq!(shared.get("requests"), Some(Value::I64(800)));
+        assert_eq!(shared.get("peak"), Some(Value::I64(7)));
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::CrashRing;
+
+    #[test]
+    fn test_ring_keeps_last_entries() {
+        let mut ring = CrashRing::new(3, 16).unwrap();
+        assert!(CrashRing::recover(ring.as_bytes()).is_empty());
+        for index in 0..5 {
+            assert!(ring.push(format!("entry-{}", index).as_bytes()));
+        }
+        let entries = CrashRing::recover(ring.as_bytes());
+        assert_eq!(entries, vec![b"entry-2".to_vec(), b"entry-3".to_vec(), b"entry-4".to_vec()]);
+    }
+
+    #[test]
+    fn test_entries_are_truncated() {
+        let mut ring = CrashRing::new(1, 4).unwrap();
+        assert!(!ring.push(b"overflow"));
+        assert_eq!(CrashRing::recover(ring.as_bytes()), vec![b"over".to_vec()]);
+    }
+
+    #[test]
+    fn test_interrupted_slot_is_ignored() {
+        let mut ring = CrashRing::new(2, 8).unwrap();
+        ring.push(b"first");
+        ring.push(b"second");
+        let mut buffer = ring.as_bytes().to_vec();
+        buffer[24..32].copy_from_slice(&0u64.to_le_bytes());
+        assert_eq!(CrashRing::recover(&buffer), vec![b"second".to_vec()]);
+        assert!(CrashRing::recover(b"garbage").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_push_context() {
+        use cdumay_context::{Context, Contextualize};
+        let mut ctx = Context::new();
+        ctx.insert("step".to_string(), serde_value::Value::String("install".to_string()));
+        let mut ring = CrashRing::new(1, 64).unwrap();
+        assert!(ring.push_context(&ctx).unwrap());
+        assert_eq!(CrashRing::recover(ring.as_bytes()), vec![br#"{"step":"install"}"#.to_vec()]);
+    }
+
+    #[test]
+    fn test_oversized_ring() {
+        let err = CrashRing::new(1, usize::MAX).unwrap_err();
+        assert_eq!(err.code(), 413);
+        assert!(CrashRing::new(usize::MAX / 2, 16).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn test_mapped_ring_survives_in_file() {
+        let path = std::env::temp_dir().join(format!("cdumay-crash-ring-{}", std::process::id()));
+        {
+            let mut ring = CrashRing::create(&path, 2, 16).unwrap();
+            ring.push(b"before crash");
+            ring.flush().unwrap();
+        }
+        let entries = CrashRing::recover(&std::fs::read(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(entries, vec![b"before crash".to_vec()]);
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Contextualize, CrdtContext};
+    use serde_value::Value;
+
+    fn merged(a: &CrdtContext, b: &CrdtContext) -> CrdtContext {
+        let mut result = a.clone();
+        result.merge(b);
+        result
+    }
+
+    #[test]
+    fn test_merge_is_commutative_and_idempotent() {
+        let mut a = CrdtContext::with_node("a");
+        let mut b = CrdtContext::with_node("b");
+        let mut c = CrdtContext::with_node("c");
+        a.insert("k".to_string(), Value::U64(1));
+        b.insert("k".to_string(), Value::U64(2));
+        b.insert("x".to_string(), Value::Bool(true));
+        c.insert("y".to_string(), Value::Bool(false));
+
+        assert_eq!(merged(&a, &b).inner(), merged(&b, &a).inner());
+        assert_eq!(merged(&merged(&a, &b), &c).inner(), merged(&a, &merged(&b, &c)).inner());
+        let ab = merged(&a, &b);
+        assert_eq!(merged(&ab, &ab).inner(), ab.inner());
+        // same timestamp: the highest node id wins
+        assert_eq!(ab.get("k"), Some(&Value::U64(2)));
+    }
+
+    #[test]
+    fn test_later_write_wins() {
+        let mut a = CrdtContext::with_node("z");
+        let mut b = CrdtContext::with_node("a");
+        a.insert("k".to_string(), Value::U64(1));
+        b.merge(&a);
+        b.insert("k".to_string(), Value::U64(2));
+        assert_eq!(b.entry("k").unwrap().timestamp, 2);
+        a.merge(&b);
+        assert_eq!(a.get("k"), Some(&Value::U64(2)));
+        assert_eq!(a.clock(), 2);
+    }
+
+    #[test]
+    fn test_tombstones() {
+        let mut a = CrdtContext::with_node("a");
+        a.insert("k".to_string(), Value::U64(1));
+        let mut b = a.clone();
+        assert_eq!(b.remove("k"), Some(Value::U64(1)));
+        a.merge(&b);
+        assert!(a.get("k").is_none());
+        assert!(a.entry("k").unwrap().value.is_none());
+        assert!(a.inner().is_empty());
+    }
+
+    #[test]
+    fn test_purge_tombstones() {
+        let mut a = CrdtContext::with_node("a");
+        a.insert("k".to_string(), Value::U64(1));
+        a.insert("j".to_string(), Value::U64(1));
+        a.remove("k");
+        a.remove("j");
+        assert_eq!(a.purge_tombstones(3), 1);
+        assert!(a.entry("k").is_none());
+        assert!(a.entry("j").is_some());
+        a.insert("k".to_string(), Value::U64(2));
+        assert_eq!(a.purge_tombstones(a.clock()), 1);
+        assert_eq!(a.get("k"), Some(&Value::U64(2)));
+    }
+
+    #[test]
+    fn test_new_has_unique_node() {
+        let a = CrdtContext::new();
+        let b = CrdtContext::new();
+        assert_ne!(a.node(), b.node());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_serialization() {
+        let mut a = CrdtContext::with_node("a");
+        a.insert("k".to_string(), Value::U64(1));
+        assert_eq!(a.to_json(false).unwrap(), r#"{"k":1}"#);
+        let state = serde_json::to_string(&a).unwrap();
+        let restored: CrdtContext = serde_json::from_str(&state).unwrap();
+        assert_eq!(restored.entry("k"), a.entry("k"));
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, ValueExt, DEADLINE_KEY};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_no_deadline() {
+        let ctx = Context::new();
+        assert!(ctx.deadline().is_none());
+        assert!(ctx.remaining().is_none());
+        assert!(!ctx.is_expired());
+    }
+
+    #[test]
+    fn test_future_deadline() {
+        let mut ctx = Context::new();
+        ctx.set_deadline(Instant::now() + Duration::from_secs(60));
+        assert!(ctx.get(DEADLINE_KEY).and_then(|v| v.as_u64()).is_some());
+        let remaining = ctx.remaining().unwrap();
+        assert!(remaining > Duration::from_secs(55) && remaining <= Duration::from_secs(60));
+        assert!(!ctx.is_expired());
+    }
+
+    #[test]
+    fn test_expired_deadline() {
+        let mut ctx = Context::new();
+        ctx.set_deadline(Instant::now() - Duration::from_millis(10));
+        assert_eq!(ctx.remaining(), Some(Duration::ZERO));
+        assert!(ctx.is_expired());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_deadline_propagates() {
+        let mut ctx = Context::new();
+        ctx.set_deadline(Instant::now() + Duration::from_secs(60));
+        let received = Context::from_json(&ctx.to_json(false).unwrap()).unwrap();
+        assert!(received.remaining().unwrap() > Duration::from_secs(55));
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyPolicy, DEFAULTED_KEY};
+    use serde_value::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_get_falls_back_to_default() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.get("retries"), None);
+        ctx.register_default("retries", || Value::U8(3));
+        assert_eq!(ctx.get("retries"), Some(&Value::U8(3)));
+        assert!(ctx.is_defaulted("retries"));
+
+        ctx.insert("retries".to_string(), Value::U8(5));
+        assert_eq!(ctx.get("retries"), Some(&Value::U8(5)));
+        assert!(!ctx.is_defaulted("retries"));
+
+        ctx.remove("retries");
+        assert_eq!(ctx.get("retries"), Some(&Value::U8(3)));
+    }
+
+    #[test]
+    fn test_default_is_computed_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let mut ctx = Context::new();
+        ctx.register_default("region", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Value::String("eu-west-1".to_string())
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let cloned = ctx.clone();
+        assert_eq!(ctx.get("region"), Some(&Value::String("eu-west-1".to_string())));
+        assert_eq!(cloned.get("region"), Some(&Value::String("eu-west-1".to_string())));
+        ctx.inner();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_dump_marks_defaulted_keys() {
+        let mut ctx = Context::new();
+        ctx.register_default("level", || Value::String("info".to_string()));
+        ctx.register_default("format", || Value::String("json".to_string()));
+        ctx.insert("format".to_string(), Value::String("text".to_string()));
+
+        let dump = ctx.inner();
+        assert_eq!(dump.get("level"), Some(&Value::String("info".to_string())));
+        assert_eq!(dump.get("format"), Some(&Value::String("text".to_string())));
+        assert_eq!(dump.get(DEFAULTED_KEY), Some(&Value::Seq(vec![Value::String("level".to_string())])));
+    }
+
+    #[test]
+    fn test_deferred_value_takes_precedence() {
+        let mut ctx = Context::new();
+        ctx.register_default("build", || Value::String("default".to_string()));
+        ctx.insert_lazy("build", || Value::String("lazy".to_string()));
+        assert!(!ctx.is_defaulted("build"));
+
+        let dump = ctx.inner();
+        assert_eq!(dump.get("build"), Some(&Value::String("lazy".to_string())));
+        assert!(!dump.contains_key(DEFAULTED_KEY));
+    }
+
+    #[test]
+    fn test_default_key_is_normalized() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.register_default("Timeout", || Value::U64(30));
+        assert_eq!(ctx.get("TIMEOUT"), Some(&Value::U64(30)));
+        assert!(ctx.inner().contains_key("timeout"));
+    }
+
+    #[test]
+    fn test_global_default() {
+        Context::register_global_default("test_global_default.pool_size", || Value::U16(8));
+
+        let mut ctx = Context::new();
+        assert_eq!(ctx.get("test_global_default.pool_size"), Some(&Value::U16(8)));
+        assert!(ctx.inner().contains_key("test_global_default.pool_size"));
+
+        ctx.register_default("test_global_default.pool_size", || Value::U16(2));
+        assert_eq!(ctx.get("test_global_default.pool_size"), Some(&Value::U16(2)));
+        assert_eq!(Context::new().get("test_global_default.pool_size"), Some(&Value::U16(8)));
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Checkpoint, Context, ContextDump, Contextualize, DumpLimits};
+    use serde_value::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn counted(calls: &Arc<AtomicUsize>) -> impl Fn() -> Value + Send + 'static {
+        let calls = calls.clone();
+        move || Value::U64(calls.fetch_add(1, Ordering::SeqCst) as u64)
+    }
+
+    #[test]
+    fn test_lazy_not_evaluated_until_dump() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut ctx = Context::new();
+        ctx.insert_lazy("depth", counted(&calls));
+        assert!(ctx.is_deferred("depth"));
+        assert!(ctx.get("depth").is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(ctx.dump().get("depth"), Some(&Value::U64(0)));
+        assert_eq!(ctx.inner().get("depth"), Some(&Value::U64(0)));
+        assert_eq!(ctx.clone().dump().get("depth"), Some(&Value::U64(0)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_lazy_replaced_and_removed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut ctx = Context::new();
+        ctx.insert_lazy("depth", counted(&calls));
+        ctx.insert("depth".to_string(), Value::Bool(true));
+        assert!(!ctx.is_deferred("depth"));
+        assert_eq!(ctx.get("depth"), Some(&Value::Bool(true)));
+
+        ctx.insert_lazy("digest", counted(&calls));
+        assert_eq!(ctx.remove("digest"), Some(Value::U64(0)));
+        assert!(!ctx.inner().contains_key("digest"));
+    }
+
+    #[test]
+    fn test_lazy_consumes_its_closure() {
+        let report = String::from("4f2a");
+        let mut ctx = Context::new();
+        ctx.insert_lazy("digest", move || Value::String(report));
+        assert_eq!(ctx.inner().get("digest"), Some(&Value::String("4f2a".to_string())));
+    }
+
+    #[test]
+    fn test_lazy_after_remove() {
+        let mut ctx = Context::new();
+        let mut mirror = Context::new();
+        ctx.insert("digest".to_string(), Value::U8(1));
+        mirror.apply_delta(&ctx.export_delta(Checkpoint::default())).unwrap();
+
+        let checkpoint = ctx.checkpoint();
+        ctx.remove("digest");
+        mirror.apply_delta(&ctx.export_delta(checkpoint)).unwrap();
+        assert_eq!(ctx.tombstones(), vec!["digest".to_string()]);
+
+        let checkpoint = ctx.checkpoint();
+        ctx.insert_lazy("digest", || Value::U8(2));
+        assert!(ctx.tombstones().is_empty());
+        let delta = ctx.export_delta(checkpoint);
+        assert!(delta.removes.is_empty());
+        mirror.apply_delta(&delta).unwrap();
+        assert_eq!(mirror.get("digest"), Some(&Value::U8(2)));
+    }
+
+    #[test]
+    fn test_lazy_respects_protection() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::U8(1));
+        ctx.protect_key("user");
+        ctx.insert_lazy("user", || Value::U8(2));
+        assert_eq!(ctx.get("user"), Some(&Value::U8(1)));
+        assert!(!ctx.is_deferred("user"));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_lazy_serialized() {
+        let mut ctx = Context::new();
+        ctx.insert_lazy("digest", || Value::String("4f2a".to_string()));
+        assert_eq!(ctx.to_json(false).unwrap(), r#"{"digest":"4f2a"}"#);
+        assert_eq!(serde_json::to_string(&ctx).unwrap(), r#"{"data":{"digest":"4f2a"}}"#);
+    }
+
+    #[test]
+    fn test_live_evaluated_at_every_dump() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut ctx = Context::new();
+        ctx.insert_live("in_flight", counted(&calls));
+        assert!(ctx.is_deferred("in_flight"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(ctx.dump().get("in_flight"), Some(&Value::U64(0)));
+        assert_eq!(ctx.dump().get("in_flight"), Some(&Value::U64(1)));
+        assert_eq!(ctx.clone().inner().get("in_flight"), Some(&Value::U64(2)));
+    }
+
+    #[test]
+    fn test_deferred_values_are_dirty() {
+        let mut ctx = Context::new();
+        let checkpoint = ctx.checkpoint();
+        ctx.insert_lazy("digest", || Value::U8(1));
+        ctx.insert_live("in_flight", || Value::U8(2));
+        assert_eq!(ctx.dirty_since(checkpoint).len(), 2);
+
+        let delta = ctx.export_delta(checkpoint);
+        assert!(delta.removes.is_empty());
+        assert_eq!(delta.adds.get("in_flight"), Some(&Value::U8(2)));
+    }
+
+    #[test]
+    fn test_lazy_evaluated_by_views() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut ctx = Context::new();
+        ctx.insert_lazy("pool_stats", counted(&calls));
+        assert_eq!(ctx.render("idle: ${pool_stats}").unwrap(), "idle: 0");
+        assert_eq!(ctx.dump_truncated(&DumpLimits::new()).get("pool_stats"), Some(&Value::U64(0)));
+        assert_eq!(ctx.diff(&Context::new()).removed.get("pool_stats"), Some(&Value::U64(0)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
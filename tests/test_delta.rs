@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Checkpoint, Context, Contextualize};
+    use serde_value::Value;
+
+    fn synced() -> (Context, Context, Checkpoint) {
+        let mut worker = Context::new();
+        worker.insert("a".to_string(), Value::U64(1));
+        worker.insert("b".to_string(), Value::U64(1));
+        let mut coordinator = Context::new();
+        coordinator.apply_delta(&worker.export_delta(Checkpoint::default())).unwrap();
+        let checkpoint = worker.checkpoint();
+        (worker, coordinator, checkpoint)
+    }
+
+    #[test]
+    fn test_export_delta() {
+        let (mut worker, _, checkpoint) = synced();
+        worker.insert("a".to_string(), Value::U64(2));
+        worker.insert("c".to_string(), Value::U64(3));
+        worker.remove("b");
+        worker.insert("tmp".to_string(), Value::Unit);
+        worker.remove("tmp");
+
+        let delta = worker.export_delta(checkpoint);
+        assert_eq!(delta.base, checkpoint.0);
+        assert_eq!(delta.version, worker.checkpoint().0);
+        assert_eq!(delta.adds.keys().collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!(delta.updates.keys().collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(delta.removes.iter().collect::<Vec<_>>(), vec!["b", "tmp"]);
+        assert!(worker.export_delta(worker.checkpoint()).is_empty());
+    }
+
+    #[test]
+    fn test_export_stored_entries_only() {
+        let (mut worker, _, checkpoint) = synced();
+        worker.register_default("b", || Value::U64(0));
+        worker.alias_key("alpha", "a");
+        worker.set_emit_aliases(true);
+        worker.insert("a".to_string(), Value::U64(2));
+        worker.remove("b");
+        worker.insert_lazy("c", || Value::U64(3));
+
+        let delta = worker.export_delta(checkpoint);
+        assert_eq!(delta.adds, [("c".to_string(), Value::U64(3))].into());
+        assert_eq!(delta.updates, [("a".to_string(), Value::U64(2))].into());
+        assert_eq!(delta.removes.iter().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let (mut worker, mut coordinator, checkpoint) = synced();
+        worker.insert("a".to_string(), Value::U64(2));
+        worker.remove("b");
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        assert_eq!(coordinator.inner(), worker.inner());
+    }
+
+    #[test]
+    fn test_missed_delta_is_a_conflict() {
+        let (mut worker, mut coordinator, checkpoint) = synced();
+        worker.insert("a".to_string(), Value::U64(2));
+        let missed = worker.checkpoint();
+        worker.insert("a".to_string(), Value::U64(3));
+        let err = coordinator.apply_delta(&worker.export_delta(missed)).unwrap_err();
+        assert_eq!(err.code(), 409);
+        assert!(err.class().contains("DeltaConflict"));
+
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        assert_eq!(coordinator.get("a"), Some(&Value::U64(3)));
+    }
+
+    #[test]
+    fn test_local_change_is_a_conflict() {
+        let (mut worker, mut coordinator, checkpoint) = synced();
+        coordinator.insert("a".to_string(), Value::U64(10));
+        worker.insert("a".to_string(), Value::U64(2));
+        worker.insert("c".to_string(), Value::U64(3));
+        let err = coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap_err();
+        assert!(err.message().contains("a"));
+        assert!(coordinator.get("c").is_none());
+    }
+
+    #[test]
+    fn test_tombstones_prevent_resurrection() {
+        let (mut worker, mut coordinator, mut checkpoint) = synced();
+        let since = coordinator.checkpoint();
+        assert_eq!(coordinator.remove("a"), Some(Value::U64(1)));
+        assert_eq!(coordinator.tombstones(), vec!["a".to_string()]);
+
+        worker.insert("b".to_string(), Value::U64(2));
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        checkpoint = worker.checkpoint();
+        worker.insert("a".to_string(), Value::U64(2));
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        checkpoint = worker.checkpoint();
+        assert!(coordinator.get("a").is_none());
+        assert_eq!(coordinator.get("b"), Some(&Value::U64(2)));
+
+        let delta = coordinator.export_delta(since);
+        assert!(delta.removes.contains("a"));
+        assert_eq!(coordinator.acknowledge_delta(&delta), 1);
+        assert!(coordinator.tombstones().is_empty());
+        worker.insert("a".to_string(), Value::U64(3));
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        assert_eq!(coordinator.get("a"), Some(&Value::U64(3)));
+    }
+
+    #[test]
+    fn test_applied_removes_are_not_tombstones() {
+        let (mut worker, mut coordinator, checkpoint) = synced();
+        worker.remove("b");
+        assert_eq!(worker.tombstones(), vec!["b".to_string()]);
+        coordinator.apply_delta(&worker.export_delta(checkpoint)).unwrap();
+        assert!(coordinator.get("b").is_none());
+        assert!(coordinator.tombstones().is_empty());
+        worker.insert("b".to_string(), Value::U64(2));
+        assert!(worker.tombstones().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_compact_format() {
+        let (mut worker, _, checkpoint) = synced();
+        worker.remove("a");
+        let json = serde_json::to_string(&worker.export_delta(checkpoint)).unwrap();
+        assert_eq!(json, format!(r#"{{"b":{},"v":{},"r":["a"]}}"#, checkpoint.0, worker.checkpoint().0));
+        let delta: cdumay_context::Delta = serde_json::from_str(&json).unwrap();
+        assert_eq!(delta.removes.len(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{clear_context_metrics, set_context_metrics, Context, ContextMetrics, Contextualize, DeprecationWarning, KeyAccess};
+    use serde_value::Value;
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    /// Metrics are global: tests installing them run one at a time.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[derive(Default)]
+    struct Warnings(Mutex<Vec<DeprecationWarning>>);
+
+    impl ContextMetrics for Warnings {
+        fn on_deprecated_key(&self, warning: &DeprecationWarning) {
+            self.0.lock().unwrap().push(warning.clone());
+        }
+    }
+
+    fn install() -> (MutexGuard<'static, ()>, Arc<Warnings>) {
+        let guard = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let warnings = Arc::new(Warnings::default());
+        set_context_metrics(warnings.clone());
+        (guard, warnings)
+    }
+
+    fn warnings(warnings: &Warnings) -> Vec<DeprecationWarning> {
+        clear_context_metrics();
+        warnings.0.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_deprecate_key() {
+        let (_guard, recorder) = install();
+        let mut ctx = Context::new();
+        ctx.deprecate_key("legacy", "drop it by 2.0");
+        ctx.insert("legacy".to_string(), Value::Bool(true));
+        ctx.insert("other".to_string(), Value::Bool(true));
+        assert_eq!(ctx.get("legacy"), Some(&Value::Bool(true)));
+        ctx.get("other");
+
+        let warnings = warnings(&recorder);
+        assert_eq!(
+            warnings.iter().map(|warning| warning.access).collect::<Vec<_>>(),
+            vec![KeyAccess::Write, KeyAccess::Read]
+        );
+        assert_eq!(warnings[0].key, "legacy");
+        assert_eq!(warnings[0].replacement, None);
+        assert_eq!(warnings[1].to_string(), "read of deprecated key 'legacy': drop it by 2.0");
+        assert!(ctx.is_deprecated("legacy"));
+        assert!(!ctx.is_deprecated("other"));
+        assert_eq!(ctx.deprecated_keys(), vec![("legacy", "drop it by 2.0")]);
+    }
+
+    #[test]
+    fn test_migrate_key() {
+        let (_guard, recorder) = install();
+        let mut ctx = Context::new();
+        ctx.insert("userId".to_string(), Value::String("jane".to_string()));
+        ctx.migrate_key("userId", "user_id", "renamed");
+        assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["user_id"]);
+
+        ctx.insert("userId".to_string(), Value::String("john".to_string()));
+        assert_eq!(ctx.get("userId"), Some(&Value::String("john".to_string())));
+        assert_eq!(ctx.get("user_id"), Some(&Value::String("john".to_string())));
+
+        let warnings = warnings(&recorder);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].replacement.as_deref(), Some("user_id"));
+        assert_eq!(warnings[0].to_string(), "write of deprecated key 'userId', use 'user_id': renamed");
+    }
+
+    #[test]
+    fn test_guarded_inserts_and_fork() {
+        let (_guard, recorder) = install();
+        let mut ctx = Context::new();
+        ctx.deprecate_key("legacy", "obsolete");
+        ctx.try_insert("legacy".to_string(), Value::Unit).unwrap();
+        ctx.force_insert("legacy".to_string(), Value::Unit);
+        let mut child = ctx.fork();
+        assert!(child.insert_once("legacy".to_string(), Value::Unit).is_err());
+
+        assert_eq!(warnings(&recorder).len(), 3);
+        assert!(child.is_deprecated("legacy"));
+    }
+
+    #[test]
+    fn test_self_migration_is_ignored() {
+        let mut ctx = Context::new();
+        ctx.migrate_key("user_id", "user_id", "noop");
+        assert!(!ctx.is_deprecated("user_id"));
+    }
+}
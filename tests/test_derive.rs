@@ -0,0 +1,59 @@
+#[cfg(test)]
+#[cfg(feature = "derive")]
+mod tests {
+    use cdumay_context::{Context, ContextDump, Contextualize};
+    use serde::Serialize;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Request {
+        method: String,
+        path: String,
+    }
+
+    #[derive(ContextDump)]
+    struct Failure<T: Serialize> {
+        #[context(rename = "user.id")]
+        user_id: u32,
+        #[context(skip)]
+        #[allow(dead_code)]
+        password: String,
+        #[context(flatten)]
+        request: Request,
+        #[context(flatten)]
+        extra: BTreeMap<String, T>,
+        r#type: Option<String>,
+    }
+
+    fn failure() -> Failure<u8> {
+        Failure {
+            user_id: 42,
+            password: "secret".to_string(),
+            request: Request {
+                method: "GET".to_string(),
+                path: "/".to_string(),
+            },
+            extra: BTreeMap::from([("attempt".to_string(), 2)]),
+            r#type: None,
+        }
+    }
+
+    #[test]
+    fn test_derived_dump() {
+        let dump = failure().dump();
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec!["attempt", "method", "path", "type", "user.id"]);
+        assert_eq!(dump["user.id"], Value::U32(42));
+        assert_eq!(dump["method"], Value::String("GET".to_string()));
+        assert_eq!(dump["attempt"], Value::U8(2));
+        assert_eq!(dump["type"], Value::Option(None));
+    }
+
+    #[test]
+    fn test_derived_dump_into_context() {
+        let mut ctx = Context::new();
+        ctx.extend(failure().dump());
+        assert_eq!(ctx.get_str("path").unwrap(), "/");
+        assert!(!ctx.contains_key("password"));
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextDiff, Contextualize, ValueChange};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn before() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("state".to_string(), Value::String("running".to_string()));
+        ctx.insert("worker".to_string(), Value::U8(1));
+        ctx.insert("tags".to_string(), Value::Seq(vec![Value::String("slow".to_string())]));
+        ctx
+    }
+
+    fn after() -> Context {
+        let mut ctx = before();
+        ctx.insert("state".to_string(), Value::String("done".to_string()));
+        ctx.insert("exit_code".to_string(), Value::U8(0));
+        ctx.remove("worker");
+        ctx
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = before().diff(&after());
+        assert_eq!(diff.added, BTreeMap::from([("exit_code".to_string(), Value::U8(0))]));
+        assert_eq!(diff.removed, BTreeMap::from([("worker".to_string(), Value::U8(1))]));
+        assert_eq!(
+            diff.changed,
+            BTreeMap::from([(
+                "state".to_string(),
+                ValueChange {
+                    old: Value::String("running".to_string()),
+                    new: Value::String("done".to_string())
+                }
+            )])
+        );
+        assert_eq!(diff.len(), 3);
+        assert!(before().diff(&before()).is_empty());
+        assert_eq!(before().diff(&before()).to_string(), "");
+    }
+
+    #[test]
+    fn test_apply() {
+        let diff = before().diff(&after());
+        let mut ctx = before();
+        ctx.apply(&diff);
+        assert_eq!(ctx, after());
+
+        let mut ctx = before();
+        ctx.protect_key("worker");
+        ctx.apply(&diff);
+        assert_eq!(ctx.get("worker"), Some(&Value::U8(1)));
+        assert_eq!(ctx.get("exit_code"), Some(&Value::U8(0)));
+
+        let mut ctx = after();
+        ctx.apply(&ContextDiff::default());
+        assert_eq!(ctx, after());
+    }
+
+    #[test]
+    fn test_display() {
+        let diff = after().diff(&before());
+        assert_eq!(diff.to_string(), "+ worker: 1\n- exit_code: 0\n~ state: \"done\" -> \"running\"");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serde() {
+        let diff = before().diff(&after());
+        let json = serde_json::to_string(&diff).unwrap();
+        assert!(json.contains("\"changed\":{\"state\":{\"old\":\"running\",\"new\":\"done\"}}"));
+        let parsed: ContextDiff = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.changed, diff.changed);
+        assert_eq!(parsed.removed.keys().collect::<Vec<_>>(), vec!["worker"]);
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Checkpoint, Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_dirty_tracking() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.checkpoint(), Checkpoint(0));
+        ctx.insert("a".to_string(), Value::U64(1));
+        ctx.insert("b".to_string(), Value::U64(1));
+        let first = ctx.checkpoint();
+        assert!(ctx.dirty_keys(first).is_empty());
+
+        ctx.insert("b".to_string(), Value::U64(2));
+        let mut data = BTreeMap::new();
+        data.insert("c".to_string(), Value::U64(3));
+        ctx.extend(data);
+        let second = ctx.checkpoint();
+        ctx["a"] = Value::U64(4);
+
+        assert_eq!(ctx.dirty_keys(first), vec!["a", "b", "c"]);
+        assert_eq!(ctx.dirty_keys(second), vec!["a"]);
+        assert_eq!(ctx.dirty_since(second).get("a"), Some(&Value::U64(4)));
+        assert_eq!(ctx.dirty_keys(Checkpoint::default()).len(), 3);
+    }
+
+    #[test]
+    fn test_refused_writes_are_not_dirty() {
+        let mut ctx = Context::new();
+        ctx.protect_key("id");
+        ctx.insert("id".to_string(), Value::U64(1));
+        let checkpoint = ctx.checkpoint();
+        ctx.insert("id".to_string(), Value::U64(2));
+        assert!(ctx.dirty_keys(checkpoint).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_to_json_dirty() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), Value::U64(1));
+        let checkpoint = ctx.checkpoint();
+        ctx.insert("b".to_string(), Value::Bool(true));
+        assert_eq!(ctx.to_json_dirty(checkpoint, false).unwrap(), r#"{"b":true}"#);
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+#[cfg(feature = "dotenv")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_from_dotenv() {
+        let content = r#"
+# comment
+export  A=1
+B = spaced value   # trailing comment
+C='literal $HOME \n' # comment
+D="line one
+line two \"quoted\" \$HOME \\"
+E=
+F=url#fragment
+A=2
+"#;
+        let ctx = Context::from_dotenv(content).unwrap();
+        assert_eq!(ctx.get_str("A").unwrap(), "2");
+        assert_eq!(ctx.get_str("B").unwrap(), "spaced value");
+        assert_eq!(ctx.get_str("C").unwrap(), "literal $HOME \\n");
+        assert_eq!(ctx.get_str("D").unwrap(), "line one\nline two \"quoted\" $HOME \\");
+        assert_eq!(ctx.get_str("E").unwrap(), "");
+        assert_eq!(ctx.get_str("F").unwrap(), "url#fragment");
+        assert_eq!(ctx.inner().len(), 6);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (content, line) in [
+            ("A=1\nINVALID\n", 2),
+            ("=1", 1),
+            ("A='open", 1),
+            ("A=\"open\nstill open", 1),
+            ("A=\"x\" y", 1),
+        ] {
+            let err = Context::from_dotenv(content).unwrap_err();
+            assert_eq!(err.code(), 400);
+            assert!(err.message().contains(&format!("line {}", line)), "{}", err.message());
+        }
+    }
+
+    #[test]
+    fn test_to_dotenv() {
+        let mut ctx = Context::new();
+        ctx.insert("PORT".to_string(), Value::U16(8080));
+        ctx.insert("URL".to_string(), Value::String("https://example.com/a,b".to_string()));
+        ctx.insert("MOTD".to_string(), Value::String("say \"hi\"\tto $USER".to_string()));
+        ctx.insert("EMPTY".to_string(), Value::Option(None));
+        assert_eq!(
+            ctx.to_dotenv().unwrap(),
+            "EMPTY=\nMOTD=\"say \\\"hi\\\"\\tto \\$USER\"\nPORT=8080\nURL=https://example.com/a,b\n"
+        );
+        assert_eq!(
+            Context::from_dotenv(&ctx.to_dotenv().unwrap()).unwrap().get_str("MOTD").unwrap(),
+            "say \"hi\"\tto $USER"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        let mut ctx = Context::new();
+        ctx.insert("NESTED".to_string(), Value::Map(BTreeMap::new()));
+        let err = ctx.to_dotenv().unwrap_err();
+        assert_eq!(err.code(), 422);
+        assert!(err.message().contains("NESTED"));
+
+        let mut ctx = Context::new();
+        ctx.insert("BAD KEY".to_string(), Value::Bool(true));
+        assert!(ctx.to_dotenv().is_err());
+    }
+}
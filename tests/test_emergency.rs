@@ -0,0 +1,128 @@
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use cdumay_context::{Context, ContextConfig, Contextualize, Sensitivity};
+    use serde_value::Value;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+    use std::io::{Read, Seek};
+    use std::os::fd::AsFd;
+
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn dump(ctx: &Context) -> (String, usize) {
+        let path = std::env::temp_dir().join(format!("cdumay-emergency-{}-{:?}", std::process::id(), std::thread::current().id()));
+        let mut file = std::fs::File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        let before = ALLOCATIONS.with(Cell::get);
+        ctx.emergency_dump(file.as_fd()).unwrap();
+        let allocations = ALLOCATIONS.with(Cell::get) - before;
+        let mut out = String::new();
+        file.rewind().unwrap();
+        file.read_to_string(&mut out).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        (out, allocations)
+    }
+
+    #[test]
+    fn test_emergency_dump() {
+        let mut ctx = Context::new();
+        ctx.insert("attempt".to_string(), Value::U8(3));
+        ctx.insert("ratio".to_string(), Value::F64(0.5));
+        ctx.insert("step".to_string(), Value::String("say \"hi\"\n".to_string()));
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.insert(
+            "user".to_string(),
+            Value::Map(BTreeMap::from([(
+                Value::String("tags".to_string()),
+                Value::Seq(vec![Value::Bool(true), Value::Unit]),
+            )])),
+        );
+        ctx.insert_lazy("expensive", || Value::U64(42));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+
+        let (out, allocations) = dump(&ctx);
+        assert_eq!(allocations, 0);
+        assert_eq!(
+            out,
+            "{\"attempt\":3,\"ratio\":0.5,\"step\":\"say \\\"hi\\\"\\n\",\"token\":\"[redacted]\",\"user\":{\"tags\":[true,null]},\"expensive\":\"[deferred]\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_large_emergency_dump() {
+        let mut ctx = Context::new();
+        ctx.insert("payload".to_string(), Value::String("x".repeat(5000)));
+        let (out, allocations) = dump(&ctx);
+        assert_eq!(allocations, 0);
+        assert_eq!(out.len(), 5000 + "{\"payload\":\"\"}\n".len());
+    }
+
+    #[test]
+    fn test_emergency_dump_map_keys() {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "limits".to_string(),
+            Value::Map(BTreeMap::from([
+                (Value::U16(1), Value::Bool(true)),
+                (Value::Option(Some(Box::new(Value::String("x".to_string())))), Value::U8(2)),
+                (Value::Seq(vec![Value::U8(1), Value::String("a\"b".to_string())]), Value::U8(3)),
+            ])),
+        );
+        let (out, allocations) = dump(&ctx);
+        assert_eq!(allocations, 0);
+        assert_eq!(out, "{\"limits\":{\"1\":true,\"x\":2,\"[1,\\\"a\\\\\\\"b\\\"]\":3}}\n");
+        #[cfg(feature = "json")]
+        assert!(Context::from_json(&out).is_ok());
+    }
+
+    #[test]
+    fn test_emergency_dump_redacted_keys() {
+        let mut ctx = Context::with_config(ContextConfig::new().redact("*.password").redact("api_key"));
+        ctx.insert("api_key".to_string(), Value::String("k3y".to_string()));
+        ctx.insert(
+            "db".to_string(),
+            Value::Map(BTreeMap::from([
+                (Value::String("host".to_string()), Value::String("localhost".to_string())),
+                (Value::String("password".to_string()), Value::String("s3cr3t".to_string())),
+            ])),
+        );
+        let (out, allocations) = dump(&ctx);
+        assert_eq!(allocations, 0);
+        assert_eq!(
+            out,
+            "{\"api_key\":\"[redacted]\",\"db\":{\"host\":\"localhost\",\"password\":\"[redacted]\"}}\n"
+        );
+
+        ctx.insert(
+            "deep".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("k".repeat(300)), Value::U8(1))])),
+        );
+        assert!(dump(&ctx).0.contains("\"deep\":{\"kkk"));
+        assert!(dump(&ctx).0.ends_with(":\"[redacted]\"}}\n"));
+    }
+}
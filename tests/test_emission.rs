@@ -0,0 +1,58 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{before_emit, clear_emit_hooks, Context, Contextualize, Decision, Lifecycle};
+    use serde_value::Value;
+
+    fn sealed(tenant: &str) -> Context {
+        let mut ctx = Context::new();
+        ctx.start_lifecycle().unwrap();
+        ctx.insert("tenant".to_string(), s(tenant));
+        ctx.insert("client_ip".to_string(), s("10.0.0.1"));
+        ctx.activate().unwrap();
+        ctx.seal().unwrap();
+        ctx
+    }
+
+    // Hooks are process-wide, a single test avoids interferences between tests.
+    #[test]
+    fn test_before_emit() {
+        assert_eq!(sealed("eu-west").prepare_emit().unwrap().len(), 2);
+
+        before_emit(|dump| match dump.get("tenant") {
+            Some(Value::String(tenant)) if tenant == "blocked" => Decision::Deny,
+            Some(Value::String(tenant)) if tenant.starts_with("eu-") => {
+                let mut dump = dump.clone();
+                dump.remove("client_ip");
+                Decision::Modify(dump)
+            }
+            _ => Decision::Allow,
+        });
+        before_emit(|dump| match dump.contains_key("client_ip") {
+            true => Decision::Allow,
+            false => {
+                let mut dump = dump.clone();
+                dump.insert("redacted".to_string(), Value::Bool(true));
+                Decision::Modify(dump)
+            }
+        });
+
+        let mut ctx = sealed("eu-west");
+        let dump = ctx.emit().unwrap();
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec!["redacted", "tenant"]);
+        assert!(ctx.inner().contains_key("client_ip"));
+        assert_eq!(sealed("us-east").prepare_emit().unwrap().len(), 2);
+
+        let mut ctx = sealed("blocked");
+        assert!(ctx.prepare_emit().is_none());
+        let err = ctx.emit().unwrap_err();
+        assert_eq!(err.code(), 401);
+        assert_eq!(ctx.lifecycle(), Some(Lifecycle::Sealed));
+
+        clear_emit_hooks();
+        assert_eq!(ctx.emit().unwrap().len(), 2);
+        assert_eq!(ctx.lifecycle(), Some(Lifecycle::Emitted));
+    }
+}
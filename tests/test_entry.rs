@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyPolicy};
+    use serde_value::Value;
+
+    fn increment(value: &mut Value) {
+        if let Value::U64(n) = value {
+            *n += 1;
+        }
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.insert("Retries".to_string(), Value::U64(1));
+        let checkpoint = ctx.checkpoint();
+        increment(ctx.get_mut("RETRIES").unwrap());
+        assert_eq!(ctx.get("retries"), Some(&Value::U64(2)));
+        assert_eq!(ctx.dirty_keys(checkpoint), vec!["retries"]);
+        assert!(ctx.get_mut("missing").is_none());
+
+        ctx.insert_lazy("region", || Value::String("eu".to_string()));
+        *ctx.get_mut("region").unwrap() = Value::String("us".to_string());
+        assert!(!ctx.is_deferred("region"));
+        assert_eq!(ctx.get("region"), Some(&Value::String("us".to_string())));
+
+        ctx.protect_key("retries");
+        assert!(ctx.get_mut("retries").is_none());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut ctx = Context::new();
+        assert!(!ctx.entry("retries").is_occupied());
+        for _ in 0..3 {
+            ctx.entry("retries").and_modify(increment).or_insert(Value::U64(1));
+        }
+        assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+        assert_eq!(ctx.entry("retries").key(), "retries");
+
+        if let Value::Seq(items) = ctx.entry("tags").or_insert_with(|| Value::Seq(vec![])) {
+            items.push(Value::String("slow".to_string()));
+        }
+        assert_eq!(ctx.get("tags"), Some(&Value::Seq(vec![Value::String("slow".to_string())])));
+        assert_eq!(ctx.entry("parent").or_default(), &Value::Unit);
+
+        ctx.protect_key("retries");
+        ctx.entry("retries").and_modify(increment);
+        assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "key 'retries' is protected")]
+    fn test_entry_protected() {
+        let mut ctx = Context::new();
+        ctx.insert("retries".to_string(), Value::U64(1));
+        ctx.protect_key("retries");
+        ctx.entry("retries").or_insert(Value::U64(0));
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, EnvOptions, KeyCase};
+    use serde_value::Value;
+
+    #[test]
+    fn test_from_env_prefix() {
+        std::env::set_var("TEST_ENV_PREFIX_Region", "eu");
+        std::env::set_var("TEST_ENV_PREFIX_API_TOKEN", "s3cr3t");
+        std::env::set_var("TEST_ENV_PREFIX", "empty key");
+        std::env::set_var("TEST_ENV_PREFIXED", "other prefix");
+        let ctx = Context::from_env(Some("TEST_ENV_PREFIX"));
+        assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["region"]);
+
+        let options = EnvOptions::new().with_key_case(KeyCase::Preserve).with_sensitive(&[]);
+        let ctx = Context::from_env_with(Some("TEST_ENV_PREFIX_"), &options);
+        assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["API_TOKEN", "Region"]);
+
+        let ctx = Context::from_env_with(Some("TEST_ENV_PREFIX_"), &EnvOptions::new().with_key_case(KeyCase::Upper));
+        assert_eq!(ctx.get_str("REGION").unwrap(), "eu");
+
+        let ctx = Context::from_env(None);
+        assert_eq!(ctx.get_str("test_env_prefix_region").unwrap(), "eu");
+        assert!(!ctx.contains_key("test_env_prefix_api_token"));
+    }
+
+    #[test]
+    fn test_coercion() {
+        for (name, value) in [
+            ("BOOL", "true"),
+            ("INT", "-42"),
+            ("BIG", "18446744073709551615"),
+            ("FLOAT", "0.5"),
+            ("NAN", "NaN"),
+            ("TEXT", "True"),
+        ] {
+            std::env::set_var(format!("TEST_ENV_COERCE_{}", name), value);
+        }
+        let ctx = Context::from_env(Some("TEST_ENV_COERCE"));
+        assert_eq!(ctx.get_str("int").unwrap(), "-42");
+
+        let ctx = Context::from_env_with(Some("TEST_ENV_COERCE"), &EnvOptions::new().with_coercion(true));
+        assert_eq!(ctx.get("bool"), Some(&Value::Bool(true)));
+        assert_eq!(ctx.get("int"), Some(&Value::I64(-42)));
+        assert_eq!(ctx.get("big"), Some(&Value::U64(u64::MAX)));
+        assert_eq!(ctx.get("float"), Some(&Value::F64(0.5)));
+        assert_eq!(ctx.get_str("nan").unwrap(), "NaN");
+        assert_eq!(ctx.get_str("text").unwrap(), "True");
+    }
+
+    #[test]
+    fn test_extend_from_env() {
+        std::env::set_var("TEST_ENV_EXTEND_USER", "jane");
+        std::env::set_var("TEST_ENV_EXTEND_TENANT", "other");
+        let mut ctx = Context::new();
+        ctx.insert("tenant".to_string(), Value::String("acme".to_string()));
+        ctx.protect_key("tenant");
+        ctx.extend_from_env(Some("TEST_ENV_EXTEND"));
+        assert_eq!(ctx.get_str("user").unwrap(), "jane");
+        assert_eq!(ctx.get_str("tenant").unwrap(), "acme");
+    }
+}
@@ -1,7 +1,11 @@
+#![allow(clippy::useless_conversion)]
+
 #[cfg(test)]
 mod tests {
-    use cdumay_context::UnExpectedError;
+    use cdumay_context::{Conflict, Context, Contextualize, KeyNotFound, Timeout, UnExpectedError, Unauthorized};
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
     use cdumay_core::{Error, ErrorConverter};
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
     use std::collections::BTreeMap;
 
     #[test]
@@ -13,6 +17,30 @@ mod tests {
         assert!(format!("{:?}", error).contains(error_msg));
     }
 
+    #[test]
+    fn test_http_errors() {
+        let errors: Vec<cdumay_core::Error> = vec![
+            KeyNotFound::new().into(),
+            Conflict::new().into(),
+            Timeout::new().into(),
+            Unauthorized::new().into(),
+        ];
+        let codes: Vec<u16> = errors.iter().map(|err| err.code()).collect();
+        assert_eq!(codes, vec![404, 409, 504, 401]);
+    }
+
+    #[test]
+    fn test_with_context() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), serde_value::Value::U8(1));
+        ctx.insert("reason".to_string(), serde_value::Value::Bool(false));
+        let details = [("reason".to_string(), serde_value::Value::Bool(true))].into();
+        let error = KeyNotFound::new().with_details(details).with_context(&ctx);
+        assert_eq!(error.details().get("user"), Some(&serde_value::Value::U8(1)));
+        assert_eq!(error.details().get("reason"), Some(&serde_value::Value::Bool(true)));
+        assert_eq!(UnExpectedError::new().with_context(&ctx).details().len(), 2);
+    }
+
     #[test]
     #[cfg(feature = "json")]
     fn test_json_error_conversion() {
@@ -63,4 +91,37 @@ mod tests {
         let generic = UnExpectedError::new().with_message("generic error".to_string());
         assert!(format!("{:?}", generic).contains("generic error"));
     }
+
+    #[test]
+    fn test_with_source() {
+        use std::error::Error;
+
+        let mut ctx = Context::new();
+        ctx.insert("path".to_string(), serde_value::Value::String("/etc/app.toml".to_string()));
+        let err = UnExpectedError::new()
+            .with_context(&ctx)
+            .with_source(std::io::Error::other("permission denied"));
+
+        assert_eq!(err.source().map(|s| s.to_string()), Some("permission denied".to_string()));
+        assert_eq!(err.to_string(), err.error().to_string());
+        assert_eq!(err.code(), 500);
+
+        let err: cdumay_core::Error = err.into();
+        let details = err.details();
+        assert_eq!(details.get("path"), Some(&serde_value::Value::String("/etc/app.toml".to_string())));
+        let Some(serde_value::Value::Map(entry)) = details.get("error") else {
+            panic!("missing error entry")
+        };
+        let Some(serde_value::Value::Seq(causes)) = entry.get(&serde_value::Value::String("causes".to_string())) else {
+            panic!("missing causes")
+        };
+        assert_eq!(causes.len(), 1);
+    }
+
+    #[test]
+    fn test_with_source_is_walkable() {
+        let err = KeyNotFound::new().with_source(std::io::Error::other("no such file"));
+        let mut ctx = Context::new();
+        assert_eq!(ctx.record_causes(&err), 2);
+    }
 }
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::errors::{
+        Conflict, ContextError, ContextErrorKind, FeatureDisabled, FrozenKey, InvalidMapKey, KeyNotFound, PrecisionLoss, QuotaExceeded,
+        SerializationError, SizeLimitExceeded, TypeMismatch, UnExpectedError, ValidationError,
+    };
+    use cdumay_context::{Context, Contextualize};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_error_types() {
+        assert_eq!(KeyNotFound::new().kind(), ContextErrorKind::KeyNotFound);
+        assert_eq!(TypeMismatch::new().kind(), ContextErrorKind::TypeMismatch);
+        assert_eq!(PrecisionLoss::new().kind(), ContextErrorKind::TypeMismatch);
+        assert_eq!(ValidationError::new().kind(), ContextErrorKind::Validation);
+        assert_eq!(InvalidMapKey::new().kind(), ContextErrorKind::Validation);
+        assert_eq!(FrozenKey::new().kind(), ContextErrorKind::Access);
+        assert_eq!(Conflict::new().kind(), ContextErrorKind::Conflict);
+        assert_eq!(QuotaExceeded::new().kind(), ContextErrorKind::Limits);
+        assert_eq!(FeatureDisabled::new().kind(), ContextErrorKind::Unsupported);
+        assert_eq!(UnExpectedError::new().kind(), ContextErrorKind::Internal);
+        assert_eq!(ContextError::code(&QuotaExceeded::new().with_code(503)), 503);
+        assert_eq!(SerializationError::new().kind(), ContextErrorKind::Serialization);
+        assert_eq!(SizeLimitExceeded::new().kind(), ContextErrorKind::Limits);
+        assert_eq!(ContextError::code(&SizeLimitExceeded::new()), 413);
+    }
+
+    #[test]
+    fn test_for_key() {
+        let mut ctx = Context::new();
+        ctx.insert("tenant".to_string(), serde_value::Value::String("acme".to_string()));
+
+        let err = KeyNotFound::for_key("job_id", &ctx);
+        assert_eq!(err.key(), Some("job_id".to_string()));
+        assert_eq!(err.message(), "Not found (key 'job_id')");
+        assert!(err.details().contains_key("tenant"));
+        assert_eq!(
+            TypeMismatch::for_key("attempt", &ctx).with_message("Not an integer".to_string()).key(),
+            Some("attempt".to_string())
+        );
+        assert_eq!(KeyNotFound::new().key(), None);
+
+        let err: cdumay_core::Error = SizeLimitExceeded::for_key("payload", &ctx).into();
+        assert_eq!(err.kind(), ContextErrorKind::Limits);
+        assert_eq!(ContextError::key(&err), Some("payload".to_string()));
+        assert_eq!(ContextError::key(&cdumay_core::Error::from(KeyNotFound::new())), None);
+    }
+
+    #[test]
+    fn test_core_errors() {
+        let err: cdumay_core::Error = ValidationError::new().into();
+        assert_eq!(err.kind(), ContextErrorKind::Validation);
+        assert_eq!(ContextError::code(&err), 422);
+
+        let ctx = Context::new();
+        let err = ctx.get_str("missing").unwrap_err();
+        assert_eq!(err.kind(), ContextErrorKind::KeyNotFound);
+
+        let foreign = cdumay_core::Error::new(418, "Client::Teapot::Brewing".to_string(), "I'm a teapot".to_string(), BTreeMap::new());
+        assert_eq!(foreign.kind(), ContextErrorKind::Other);
+        assert_eq!(ContextError::code(&foreign), 418);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serialization_errors() {
+        let err = Context::from_json("{not json").unwrap_err();
+        assert_eq!(err.kind(), ContextErrorKind::Serialization);
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, Priority, EXEMPLAR_LABELS_LIMIT};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_label_names_and_values() {
+        let mut ctx = Context::new();
+        ctx.insert("1st-try".to_string(), Value::Bool(true));
+        ctx.insert("http.status".to_string(), Value::Option(Some(Box::new(Value::U16(503)))));
+        ctx.insert("nested".to_string(), Value::Map(BTreeMap::new()));
+        ctx.insert("missing".to_string(), Value::Option(None));
+        assert_eq!(
+            ctx.to_exemplar_labels(EXEMPLAR_LABELS_LIMIT),
+            vec![
+                ("_1st_try".to_string(), "true".to_string()),
+                ("http_status".to_string(), "503".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_budget_follows_priorities() {
+        let mut ctx = Context::new();
+        ctx.insert("a_payload".to_string(), s(&"x".repeat(100)));
+        ctx.insert("request_id".to_string(), s("abc"));
+        ctx.insert("trace_id".to_string(), s(&"f".repeat(32)));
+        assert_eq!(ctx.to_exemplar_labels(128).len(), 2);
+
+        ctx.set_priority("trace_id", Priority::High);
+        ctx.set_priority("request_id", Priority::High);
+        let labels = ctx.to_exemplar_labels(1000);
+        assert_eq!(labels.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), vec!["request_id", "trace_id"]);
+        assert!(labels.iter().map(|(k, v)| k.chars().count() + v.chars().count()).sum::<usize>() <= EXEMPLAR_LABELS_LIMIT);
+        assert_eq!(ctx.to_exemplar_labels(13).len(), 1);
+        assert!(ctx.to_exemplar_labels(0).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_names_and_utf8_budget() {
+        let mut ctx = Context::new();
+        ctx.insert("user.id".to_string(), s("é"));
+        ctx.insert("user_id".to_string(), s("2"));
+        assert_eq!(ctx.to_exemplar_labels(8), vec![("user_id".to_string(), "é".to_string())]);
+    }
+}
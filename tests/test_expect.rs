@@ -0,0 +1,61 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, Sensitivity, PANIC_DUMP_LIMIT};
+    use serde_value::Value;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = panic::catch_unwind(AssertUnwindSafe(f)).expect_err("expected a panic");
+        match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()).unwrap_or_default(),
+        }
+    }
+
+    fn context() -> Context {
+        let mut ctx = context_of([("user_id", Value::U64(42)), ("password", s("hunter2"))]);
+        ctx.set_sensitivity("password", Sensitivity::Secret);
+        ctx
+    }
+
+    #[test]
+    fn test_expect_key() {
+        let ctx = context();
+        assert_eq!(ctx.expect_key("user_id"), &Value::U64(42));
+
+        let message = panic_message(|| {
+            ctx.expect_key("tenant");
+        });
+        assert_eq!(
+            message,
+            "missing key `tenant` in context: { \"password\": \"[redacted]\", \"user_id\": 42 }"
+        );
+    }
+
+    #[test]
+    fn test_unwrap_get() {
+        let ctx = context();
+        assert_eq!(ctx.unwrap_get::<u64>("user_id"), 42);
+
+        let message = panic_message(|| {
+            ctx.unwrap_get::<bool>("user_id");
+        });
+        assert!(message.starts_with("invalid value for key `user_id` in context (bool: "));
+        assert!(!message.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_panic_dump_truncated() {
+        let mut ctx = Context::new();
+        ctx.insert("blob".to_string(), Value::String("x".repeat(PANIC_DUMP_LIMIT * 2)));
+
+        let message = panic_message(|| {
+            ctx.expect_key("missing");
+        });
+        assert!(message.ends_with("... (truncated)"));
+        assert!(message.len() < PANIC_DUMP_LIMIT + 100);
+    }
+}
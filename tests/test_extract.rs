@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde::{Deserialize, Serialize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Request {
+        request_id: String,
+        attempt: u8,
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Db {
+        host: String,
+        port: u16,
+    }
+
+    fn request() -> Request {
+        Request {
+            request_id: "abc".to_string(),
+            attempt: 2,
+            tags: vec!["retry".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_inject_and_extract() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.insert("attempt".to_string(), Value::U8(1));
+        ctx.inject(&request()).unwrap();
+        assert_eq!(ctx.len(), 4);
+        assert_eq!(ctx.get("attempt"), Some(&Value::U8(2)));
+        assert_eq!(ctx.get("user"), Some(&Value::String("jane".to_string())));
+        assert_eq!(ctx.extract::<Request>().unwrap(), request());
+
+        let err = ctx.extract::<Db>().unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err.message().contains("host"), "{}", err.message());
+    }
+
+    #[test]
+    fn test_extract_at() {
+        let mut ctx = Context::new();
+        ctx.insert_path("config.db.host", Value::String("db-1".to_string())).unwrap();
+        ctx.insert_path("config.db.port", Value::U16(5432)).unwrap();
+        assert_eq!(
+            ctx.extract_at::<Db>("config.db").unwrap(),
+            Db {
+                host: "db-1".to_string(),
+                port: 5432
+            }
+        );
+        assert_eq!(ctx.extract_at::<u16>("config.db.port").unwrap(), 5432);
+        assert_eq!(ctx.extract_at::<Db>("config.cache").unwrap_err().code(), 404);
+        assert_eq!(ctx.extract_at::<Db>("config").unwrap_err().code(), 400);
+    }
+
+    #[test]
+    fn test_inject_maps() {
+        let mut ctx = Context::new();
+        ctx.inject(&BTreeMap::from([(1u16, "one")])).unwrap();
+        assert_eq!(ctx.get("1"), Some(&Value::String("one".to_string())));
+        ctx.inject(&Some(BTreeMap::from([("two", 2u8)]))).unwrap();
+        assert_eq!(ctx.get("two"), Some(&Value::U8(2)));
+
+        let err = ctx.inject(&vec![1, 2]).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert_eq!(ctx.len(), 2);
+    }
+}
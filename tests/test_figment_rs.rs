@@ -0,0 +1,29 @@
+#[cfg(test)]
+#[cfg(feature = "figment")]
+mod tests {
+    use cdumay_context::{Context, Contextualize, Sensitivity};
+    use figment::providers::Serialized;
+    use figment::Figment;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_provider() {
+        let mut ctx = Context::new();
+        ctx.insert("name".to_string(), Value::String("api".to_string()));
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+
+        let figment = Figment::from(Serialized::defaults(BTreeMap::from([("name", "default"), ("region", "eu")]))).merge(&ctx);
+        assert_eq!(figment.extract_inner::<String>("name").unwrap(), "api");
+        assert_eq!(figment.extract_inner::<String>("region").unwrap(), "eu");
+        assert!(figment.extract_inner::<String>("token").is_err());
+    }
+
+    #[test]
+    fn test_from_figment() {
+        let figment = Figment::from(Serialized::defaults(BTreeMap::from([("database", BTreeMap::from([("port", 5432)]))])));
+        let ctx = Context::from_figment(&figment).unwrap();
+        assert_eq!(ctx.at("database").at("port").as_i64(), Some(5432));
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, FlagProvider, FLAGS_KEY};
+    use std::collections::BTreeMap;
+
+    struct StaticFlags;
+
+    impl FlagProvider for StaticFlags {
+        fn flags(&self) -> BTreeMap<String, bool> {
+            BTreeMap::from([("dark_mode".to_string(), false), ("new_checkout".to_string(), true)])
+        }
+    }
+
+    #[test]
+    fn test_capture_flags() {
+        let ctx = Context::new().capture_flags(&StaticFlags);
+        let flags = ctx.at(FLAGS_KEY);
+        assert_eq!(flags.at("dark_mode").as_bool(), Some(false));
+        assert_eq!(flags.at("new_checkout").as_bool(), Some(true));
+    }
+
+    #[test]
+    fn test_capture_no_flags() {
+        let ctx = Context::new().capture_flags(&BTreeMap::new());
+        assert!(ctx.get(FLAGS_KEY).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "flatbuffers")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, FlatContextView};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([
+            ("job", s("export")),
+            ("retries", Value::U8(3)),
+            ("offset", Value::I32(-2)),
+            ("ratio", Value::F32(0.5)),
+            ("dry_run", Value::Bool(true)),
+            ("payload", Value::Bytes(vec![0, 159, 146])),
+            ("parent", Value::Option(None)),
+            ("tags", Value::Seq(vec![Value::Char('a'), Value::Newtype(Box::new(Value::U64(7)))])),
+            (
+                "limits",
+                Value::Map(BTreeMap::from([(Value::U16(1), s("one")), (s("max"), Value::U8(9))])),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_view() {
+        let bytes = context().to_flatbuffer();
+        let view = FlatContextView::from_flatbuffer(&bytes).unwrap();
+        assert_eq!(view.len(), 9);
+        assert_eq!(view.get("job").unwrap().as_str(), Some("export"));
+        assert_eq!(view.get("retries").unwrap().as_u64(), Some(3));
+        assert_eq!(view.get("retries").unwrap().as_i64(), Some(3));
+        assert_eq!(view.get("offset").unwrap().as_i64(), Some(-2));
+        assert_eq!(view.get("offset").unwrap().as_u64(), None);
+        assert_eq!(view.get("ratio").unwrap().as_f64(), Some(0.5));
+        assert_eq!(view.get("dry_run").unwrap().as_bool(), Some(true));
+        assert_eq!(view.get("payload").unwrap().as_bytes(), Some(&[0u8, 159, 146][..]));
+        assert!(view.get("parent").unwrap().is_null());
+        assert!(view.get("missing").is_none());
+        assert_eq!(view.get("job").unwrap().as_u64(), None);
+
+        let tags: Vec<_> = view.get("tags").unwrap().items().unwrap().map(|item| item.to_value()).collect();
+        assert_eq!(tags, vec![Value::String("a".to_string()), Value::U64(7)]);
+        let limits = view.get("limits").unwrap();
+        assert_eq!(limits.get("1").unwrap().as_str(), Some("one"));
+        assert_eq!(limits.get("max").unwrap().as_u64(), Some(9));
+        assert_eq!(limits.entries().unwrap().map(|(k, _)| k).collect::<Vec<_>>(), vec!["1", "max"]);
+
+        let keys: Vec<_> = view.iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec!["dry_run", "job", "limits", "offset", "parent", "payload", "ratio", "retries", "tags"]
+        );
+    }
+
+    #[test]
+    fn test_to_context() {
+        let bytes = context().to_flatbuffer();
+        let ctx = FlatContextView::from_flatbuffer(&bytes).unwrap().to_context();
+        assert_eq!(ctx.get("job"), Some(&Value::String("export".to_string())));
+        assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+        assert_eq!(ctx.get("offset"), Some(&Value::I64(-2)));
+        assert_eq!(ctx.get("parent"), Some(&Value::Unit));
+
+        let empty = Context::new().to_flatbuffer();
+        assert!(FlatContextView::from_flatbuffer(&empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = FlatContextView::from_flatbuffer(b"not a flatbuffer").unwrap_err();
+        assert_eq!(err.code(), 400);
+
+        let mut bytes = context().to_flatbuffer();
+        let len = bytes.len();
+        bytes.truncate(len / 2);
+        bytes[4..8].copy_from_slice(b"CDCX");
+        assert!(FlatContextView::from_flatbuffer(&bytes).is_err());
+    }
+}
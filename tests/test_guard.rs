@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyPolicy, Sensitivity};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn protected() -> Context {
+        let mut ctx = Context::new();
+        ctx.protect_key("request_id");
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_protected_key_is_set_once() {
+        let mut ctx = protected();
+        assert!(ctx.is_protected("request_id"));
+        ctx.insert("request_id".to_string(), Value::String("evil".to_string()));
+
+        let mut data = BTreeMap::new();
+        data.insert("request_id".to_string(), Value::String("evil".to_string()));
+        data.insert("user".to_string(), Value::U64(1));
+        ctx.extend(data);
+
+        assert_eq!(ctx.get("request_id"), Some(&Value::String("abc".to_string())));
+        assert_eq!(ctx.get("user"), Some(&Value::U64(1)));
+    }
+
+    #[test]
+    fn test_try_insert_and_extend() {
+        let mut ctx = protected();
+        let err = ctx.try_insert("request_id".to_string(), Value::Unit).unwrap_err();
+        assert_eq!(err.code(), 409);
+        assert!(err.class().contains("ProtectedKey"));
+        assert!(err.message().contains("request_id"));
+
+        let mut data = BTreeMap::new();
+        data.insert("request_id".to_string(), Value::Unit);
+        data.insert("user".to_string(), Value::U64(1));
+        assert!(ctx.try_extend(data).is_err());
+        assert!(ctx.get("user").is_none());
+
+        assert!(ctx.try_insert("user".to_string(), Value::U64(2)).is_ok());
+    }
+
+    #[test]
+    fn test_force_insert() {
+        let mut ctx = protected();
+        ctx.force_insert("request_id".to_string(), Value::String("def".to_string()));
+        assert_eq!(ctx.get("request_id"), Some(&Value::String("def".to_string())));
+        assert!(ctx.is_protected("request_id"));
+    }
+
+    #[test]
+    fn test_protection_follows_key_policy() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.protect_key("Tenant");
+        ctx.insert("tenant".to_string(), Value::String("acme".to_string()));
+        assert!(ctx.try_insert("TENANT".to_string(), Value::Unit).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "is protected")]
+    fn test_index_mut_on_protected_key() {
+        let mut ctx = protected();
+        ctx["request_id"] = Value::Unit;
+    }
+
+    #[test]
+    fn test_insert_once() {
+        let mut ctx = Context::new();
+        ctx.insert_once("correlation_id".to_string(), Value::String("c-1".to_string())).unwrap();
+        assert!(ctx.is_frozen("correlation_id"));
+
+        let err = ctx
+            .insert_once("correlation_id".to_string(), Value::String("c-2".to_string()))
+            .unwrap_err();
+        assert!(err.class().contains("FrozenKey"));
+        ctx.insert("correlation_id".to_string(), Value::String("c-3".to_string()));
+        ctx.force_insert("correlation_id".to_string(), Value::String("c-4".to_string()));
+        assert_eq!(ctx.get("correlation_id"), Some(&Value::String("c-1".to_string())));
+    }
+
+    #[test]
+    fn test_freeze_key() {
+        let mut ctx = protected();
+        assert!(!ctx.freeze_key("missing"));
+        assert!(ctx.freeze_key("request_id"));
+
+        let err = ctx.try_insert("request_id".to_string(), Value::Unit).unwrap_err();
+        assert!(err.class().contains("FrozenKey"));
+        ctx.force_insert("request_id".to_string(), Value::Unit);
+        assert_eq!(ctx.get("request_id"), Some(&Value::String("abc".to_string())));
+
+        ctx.insert("user".to_string(), Value::U64(1));
+        ctx.freeze_key("user");
+        assert!(ctx
+            .try_insert("user".to_string(), Value::U64(2))
+            .unwrap_err()
+            .class()
+            .contains("FrozenKey"));
+    }
+
+    #[test]
+    fn test_error_details_leave_out_secrets() {
+        let mut ctx = protected();
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx.insert("email".to_string(), Value::String("jane@example.com".to_string()));
+        ctx.set_sensitivity("email", Sensitivity::Confidential);
+
+        let err = ctx.try_insert("request_id".to_string(), Value::Unit).unwrap_err();
+        assert_eq!(err.details().keys().collect::<Vec<_>>(), vec!["request_id"]);
+        let err = ctx.get_i64("token").unwrap_err();
+        assert!(!err.details().contains_key("token"));
+        assert_eq!(ctx.error_details().len(), 1);
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, HTTP_HEADERS_LIMIT, HTTP_KEY, HTTP_VALUE_LIMIT};
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_http_request() {
+        let mut ctx = Context::new();
+        ctx.record_http_request(
+            "post",
+            "/orders",
+            [
+                ("Authorization", "Bearer s3cr3t"),
+                ("Cookie", "session=abc"),
+                ("Accept", "text/html"),
+                ("Accept", "application/json"),
+            ],
+            Some(512),
+        );
+        let request = ctx.at(HTTP_KEY).at("request");
+        assert_eq!(request.at("method").as_str(), Some("POST"));
+        assert_eq!(request.at("uri").as_str(), Some("/orders"));
+        assert_eq!(request.at("body_len").as_u64(), Some(512));
+        assert_eq!(request.at("headers").at("authorization").as_str(), Some("[redacted]"));
+        assert_eq!(request.at("headers").at("cookie").as_str(), Some("[redacted]"));
+        assert_eq!(request.at("headers").at("accept").as_str(), Some("text/html, application/json"));
+    }
+
+    #[test]
+    fn test_record_http_response_keeps_request() {
+        let mut ctx = Context::new();
+        ctx.record_http_request("GET", "/", Vec::<(String, String)>::new(), None);
+        ctx.record_http_response(200, [("Set-Cookie", "session=abc")], Duration::from_millis(42));
+        let http = ctx.at(HTTP_KEY);
+        assert_eq!(http.at("request").at("method").as_str(), Some("GET"));
+        assert!(http.at("request").at("body_len").is_missing());
+        assert_eq!(http.at("response").at("status").as_u64(), Some(200));
+        assert_eq!(http.at("response").at("latency_ms").as_u64(), Some(42));
+        assert_eq!(http.at("response").at("headers").at("set-cookie").as_str(), Some("[redacted]"));
+    }
+
+    #[test]
+    fn test_record_http_limits() {
+        let mut ctx = Context::new();
+        let headers: Vec<(String, String)> = (0..HTTP_HEADERS_LIMIT + 10).map(|i| (format!("x-h{:03}", i), "v".to_string())).collect();
+        let uri = "a".repeat(HTTP_VALUE_LIMIT * 2);
+        ctx.record_http_request("GET", &uri, headers, None);
+        let request = ctx.at(HTTP_KEY).at("request");
+        assert_eq!(request.at("headers").as_map().map(|h| h.len()), Some(HTTP_HEADERS_LIMIT));
+        assert_eq!(request.at("uri").as_str().map(|u| u.chars().count()), Some(HTTP_VALUE_LIMIT + 3));
+    }
+}
@@ -0,0 +1,111 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, ContextConfig, Contextualize, KeyOrder, Sensitivity, HEADERS_TRANSPORT, PROPAGATION_KEY};
+    use http::header::{HeaderMap, HeaderValue};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        let mut ctx = context_of([
+            ("tenant", s("acme corp")),
+            ("requestId", s("abc")),
+            ("retries", Value::U8(3)),
+            ("offset", Value::I32(-2)),
+            ("payload", Value::Bytes(vec![0, 159, 146, 150])),
+            ("note", s("json:3")),
+            ("limits", Value::Map(BTreeMap::from([(Value::U16(1), s("é"))]))),
+            ("token", s("s3cr3t")),
+        ]);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx
+    }
+
+    #[test]
+    fn test_to_headers() {
+        let headers = context().to_headers("X-Ctx-");
+        assert_eq!(headers.len(), 7);
+        assert_eq!(headers["x-ctx-tenant"], "acme%20corp");
+        assert_eq!(headers["x-ctx-request%49d"], "abc");
+        assert_eq!(headers["x-ctx-retries"], "json:3");
+        assert_eq!(headers["x-ctx-offset"], "json:-2");
+        assert_eq!(headers["x-ctx-payload"], "base64:AJ+Slg==");
+        assert_eq!(headers["x-ctx-note"], "%6Ason:3");
+        assert_eq!(headers["x-ctx-limits"], "json:{\"1\":\"%C3%A9\"}");
+        assert!(!headers.contains_key("x-ctx-token"));
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = context().to_headers("x-ctx-");
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        let ctx = Context::from_headers(&headers, "X-Ctx-", "billing");
+        assert_eq!(ctx.len(), 8);
+        assert_eq!(ctx.get("tenant"), Some(&Value::String("acme corp".to_string())));
+        assert_eq!(ctx.get("requestId"), Some(&Value::String("abc".to_string())));
+        assert_eq!(ctx.get("retries"), Some(&Value::U64(3)));
+        assert_eq!(ctx.get("offset"), Some(&Value::I64(-2)));
+        assert_eq!(ctx.get("payload"), Some(&Value::Bytes(vec![0, 159, 146, 150])));
+        assert_eq!(ctx.get("note"), Some(&Value::String("json:3".to_string())));
+        assert_eq!(
+            ctx.get("limits"),
+            Some(&Value::Map(BTreeMap::from([(
+                Value::String("1".to_string()),
+                Value::String("é".to_string())
+            )])))
+        );
+        assert!(ctx.get("content-type").is_none());
+        let hops = ctx.hops();
+        assert_eq!(hops.len(), 1);
+        assert_eq!((hops[0].service.as_str(), hops[0].transport.as_str()), ("billing", HEADERS_TRANSPORT));
+    }
+
+    #[test]
+    fn test_hops_across_services() {
+        let gateway = Context::from_headers(&context().to_headers("x-ctx-"), "x-ctx-", "gateway");
+        let billing = Context::from_headers(&gateway.to_headers("x-ctx-"), "x-ctx-", "billing");
+        let services: Vec<String> = billing.hops().into_iter().map(|hop| hop.service).collect();
+        assert_eq!(services, vec!["gateway", "billing"]);
+    }
+
+    #[test]
+    fn test_to_headers_within() {
+        let mut ctx = context();
+        ctx.insert("payload".to_string(), Value::String("x".repeat(500)));
+        ctx.set_key_order(KeyOrder::Priority(vec!["requestId".to_string(), "tenant".to_string()]));
+        let size = |headers: &HeaderMap| headers.iter().map(|(name, value)| name.as_str().len() + value.len() + 4).sum::<usize>();
+        assert_eq!(ctx.to_headers_within("x-ctx-", usize::MAX), ctx.to_headers("x-ctx-"));
+
+        let headers = ctx.to_headers_within("x-ctx-", 300);
+        assert!(size(&headers) <= 300, "{:?}", headers);
+        assert!(headers.contains_key("x-ctx-request%49d"));
+        assert!(headers.contains_key("x-ctx-tenant"));
+        assert!(!headers.contains_key("x-ctx-payload"));
+        assert!(!headers.contains_key("x-ctx-token"));
+
+        let received = Context::from_headers(&headers, "x-ctx-", "billing");
+        let dropped = received.at(PROPAGATION_KEY).at("dropped");
+        let dropped: Vec<&str> = (0..5).filter_map(|index| dropped.nth(index).as_str()).collect();
+        assert!(dropped.contains(&"payload"), "{:?}", dropped);
+        assert!(!dropped.contains(&"token"));
+        assert_eq!(received.hops().len(), 1);
+
+        ctx.set_config(ContextConfig::new().with_max_size(300));
+        assert_eq!(ctx.to_headers("x-ctx-"), headers);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ctx-broken", HeaderValue::from_static("json:{"));
+        headers.insert("x-ctx-bytes", HeaderValue::from_static("base64:A"));
+        headers.insert("x-ctx-percent", HeaderValue::from_static("100%"));
+        let ctx = Context::from_headers(&headers, "x-ctx-", "billing");
+        assert_eq!(ctx.get("broken"), Some(&Value::String("json:{".to_string())));
+        assert_eq!(ctx.get("bytes"), Some(&Value::String("base64:A".to_string())));
+        assert_eq!(ctx.get("percent"), Some(&Value::String("100%".to_string())));
+    }
+}
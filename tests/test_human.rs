@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, HumanFormat};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_numbers() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), Value::I64(-1234567));
+        ctx.insert("b".to_string(), Value::U16(999));
+        ctx.insert("c".to_string(), Value::F64(12345.5));
+        ctx.insert("d".to_string(), Value::U32(1000));
+        assert_eq!(ctx.to_human(&HumanFormat::new()), "a: -1,234,567\nb: 999\nc: 12,345.5\nd: 1,000\n");
+        let plain = HumanFormat::new().with_thousands_separator(None);
+        assert_eq!(ctx.to_human(&plain), "a: -1234567\nb: 999\nc: 12345.5\nd: 1000\n");
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let mut ctx = Context::new();
+        ctx.insert("created".to_string(), Value::I64(951_782_400_123));
+        ctx.insert("deadline".to_string(), Value::U64(0));
+        let format = HumanFormat::new().timestamp_key("created").with_utc_offset(-90);
+        assert_eq!(
+            ctx.to_human(&format),
+            "created: 2000-02-28 22:30:00.123 -01:30\ndeadline: 1969-12-31 22:30:00.000 -01:30\n"
+        );
+        assert_eq!(
+            ctx.to_human(&HumanFormat::new()),
+            "created: 951,782,400,123\ndeadline: 1970-01-01 00:00:00.000 +00:00\n"
+        );
+    }
+
+    #[test]
+    fn test_nested() {
+        let mut ctx = Context::new();
+        let http = BTreeMap::from([(Value::String("status".to_string()), Value::U16(503))]);
+        ctx.insert("http".to_string(), Value::Map(http));
+        ctx.enter_phase("download");
+        ctx.insert("tags".to_string(), Value::Seq(vec![]));
+        let text = ctx.to_human(&HumanFormat::new());
+        assert!(text.starts_with("http:\n  status: 503\nphases:\n  -:\n    name: download\n    start: 20"));
+        assert!(text.ends_with("tags: []\n"));
+    }
+}
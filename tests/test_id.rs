@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextConfig, Contextualize, IdGenerator, ProcessCounter, Ulid, UuidV4, UuidV7};
+    use serde_value::Value;
+    use std::collections::BTreeSet;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    fn is_uuid(id: &str, version: char) -> bool {
+        let groups: Vec<&str> = id.split('-').collect();
+        groups.iter().map(|group| group.len()).collect::<Vec<_>>() == vec![8, 4, 4, 4, 12]
+            && id.chars().all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c))
+            && groups[2].starts_with(version)
+            && groups[3].starts_with(['8', '9', 'a', 'b'])
+    }
+
+    #[test]
+    fn test_default_generator() {
+        let ctx = Context::new();
+        assert!(ctx.id().starts_with(&format!("{}-", std::process::id())));
+        assert!(ProcessCounter.generate().starts_with(&format!("{}-", std::process::id())));
+    }
+
+    #[test]
+    fn test_uuids() {
+        assert!(is_uuid(&UuidV4.generate(), '4'));
+        assert!(is_uuid(&UuidV7.generate(), '7'));
+        let ids: BTreeSet<String> = (0..1000).map(|_| UuidV4.generate()).collect();
+        assert_eq!(ids.len(), 1000);
+    }
+
+    #[test]
+    fn test_uuid_v7_and_ulid_are_time_ordered() {
+        let first = UuidV7.generate();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(UuidV7.generate() > first);
+
+        let first = Ulid.generate();
+        assert_eq!(first.len(), 26);
+        assert!(first.chars().all(|c| "0123456789ABCDEFGHJKMNPQRSTVWXYZ".contains(c)));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(Ulid.generate() > first);
+    }
+
+    #[test]
+    fn test_custom_generator_and_fork() {
+        let counter = Arc::new(AtomicU64::new(0));
+        let next = counter.clone();
+        let config = ContextConfig::new().with_id_generator(move || format!("req-{}", next.fetch_add(1, Ordering::Relaxed)));
+        let ctx = Context::with_config(config.clone());
+        assert_eq!(ctx.id(), "req-0");
+        let child = ctx.fork();
+        assert_eq!(child.parent_id(), Some("req-0"));
+        assert_eq!(child.id(), "req-1");
+        assert_eq!(ctx.generate_id(), "req-2");
+        assert_eq!(config, config.clone());
+        assert_ne!(config, ContextConfig::new().with_id_generator(Ulid));
+    }
+
+    #[test]
+    fn test_config_merge() {
+        let base = ContextConfig::new().with_id_generator(UuidV4);
+        assert_eq!(base.merge(&ContextConfig::new()).id_generator().generate().len(), 36);
+        assert_eq!(
+            base.merge(&ContextConfig::new().with_id_generator(Ulid)).id_generator().generate().len(),
+            26
+        );
+    }
+
+    #[test]
+    fn test_ulid_values() {
+        let mut ctx = Context::new();
+        let generated = Ulid.generate();
+        let bits = Ulid::decode(&generated).unwrap();
+        assert_eq!(Ulid::encode(bits), generated);
+        assert_eq!(Ulid::decode(&generated.to_lowercase()), Some(bits));
+        assert_eq!(Ulid::decode("8ZZZZZZZZZZZZZZZZZZZZZZZZZ"), None);
+        assert_eq!(Ulid::decode("01J4QKRYG0000000000000001U"), None);
+
+        ctx.insert_ulid("request_id".to_string(), u128::MAX);
+        assert_eq!(ctx.get_str("request_id").unwrap(), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+        assert_eq!(ctx.get_ulid("request_id").unwrap(), u128::MAX);
+
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        let err = ctx.get_ulid("user").unwrap_err();
+        assert_eq!(err.message(), "Invalid value for context key 'user': expected ulid, found string");
+        assert_eq!(ctx.get_ulid("missing").unwrap_err().code(), 404);
+    }
+
+    #[test]
+    fn test_snowflake_values() {
+        let mut ctx = Context::new();
+        ctx.insert_snowflake("tweet_id".to_string(), u64::MAX);
+        assert_eq!(ctx.get_str("tweet_id").unwrap(), "18446744073709551615");
+        assert_eq!(ctx.get_snowflake("tweet_id").unwrap(), u64::MAX);
+
+        ctx.insert("legacy_id".to_string(), Value::U64(42));
+        assert_eq!(ctx.get_snowflake("legacy_id").unwrap(), 42);
+        ctx.insert("negative".to_string(), Value::I64(-1));
+        ctx.insert("signed".to_string(), Value::String("+42".to_string()));
+        ctx.insert("overflow".to_string(), Value::String("18446744073709551616".to_string()));
+        for k in ["negative", "signed", "overflow"] {
+            assert_eq!(ctx.get_snowflake(k).unwrap_err().code(), 400);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{BuildInfo, Context, ContextConfig, Contextualize, Sensitivity, INCIDENT_SUMMARY_TEMPLATE};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_placeholders() {
+        let mut ctx = Context::new();
+        ctx.insert("service".to_string(), s("billing"));
+        ctx.insert("http".to_string(), Value::Map(BTreeMap::from([(s("status"), Value::U16(503))])));
+        ctx.insert("note".to_string(), s("line one\nline two"));
+        assert_eq!(
+            ctx.render_incident_summary("{{service}} returned {{ http.status }} ({{note}}), owner: {{owner}}"),
+            "billing returned 503 (line one line two), owner: -"
+        );
+        assert_eq!(ctx.render_incident_summary("{{#unknown}}{{service"), "{{service");
+    }
+
+    #[test]
+    fn test_redaction() {
+        let mut ctx = Context::with_config(ContextConfig::new().redact("db.password"));
+        ctx.insert("token".to_string(), s("abc"));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx.insert(
+            "db".to_string(),
+            Value::Map(BTreeMap::from([(s("password"), s("s3cr3t")), (s("host"), s("pg"))])),
+        );
+        let summary = ctx.render_incident_summary("{{token}} {{db.password}} {{db.host}} {{db}}");
+        assert_eq!(summary, "[redacted] [redacted] pg {host: pg, password: [redacted]}");
+        assert!(!summary.contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let mut ctx = Context::new().with_build_info(&BuildInfo {
+            git_sha: Some("4f1c2e9".to_string()),
+            ..Default::default()
+        });
+        ctx.insert(
+            "error".to_string(),
+            Value::Map(BTreeMap::from([(s("message"), s("upstream timeout")), (s("code"), Value::U16(504))])),
+        );
+        ctx.enter_phase("fetch");
+        ctx.enter_phase("parse");
+        ctx.set_max_attempts(3);
+        ctx.record_attempt("connection reset");
+        ctx.record_attempt("timeout");
+
+        let summary = ctx.render_incident_summary(INCIDENT_SUMMARY_TEMPLATE);
+        assert!(summary.starts_with("### Incident summary\n\n#### Error\n\n- **code:** 504\n- **message:** upstream timeout\n\n#### Phases\n"));
+        assert!(summary.contains("| Phase | Duration |\n|---|---|\n| fetch | "));
+        assert!(summary.contains(" ms |\n| parse | running |\n"));
+        assert!(summary.contains("#### Attempts\n\n2 of 3 attempts\n\n1. connection reset\n2. timeout\n"));
+        assert!(summary.ends_with("#### System\n\n- **build.git_sha:** 4f1c2e9\n\n"));
+    }
+
+    #[test]
+    fn test_error_causes() {
+        let mut ctx = Context::new();
+        let err = std::io::Error::other("disk full");
+        ctx.record_causes(&err);
+        let summary = ctx.render_incident_summary("{{#error}}");
+        assert!(summary.starts_with("#### Error\n\n"));
+        assert!(summary.contains("\nCauses:\n\n1. `"));
+        assert!(summary.contains("`: disk full\n"));
+    }
+
+    #[test]
+    fn test_empty_sections() {
+        let ctx = Context::new();
+        assert_eq!(ctx.render_incident_summary(INCIDENT_SUMMARY_TEMPLATE), "### Incident summary\n\n");
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextIndex, Contextualize, KeyOrder};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context(env: &str, kind: &str, attempt: u8) -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("env".to_string(), Value::String(env.to_string()));
+        ctx.insert("attempt".to_string(), Value::U8(attempt));
+        ctx.insert(
+            "error".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("kind".to_string()), Value::String(kind.to_string()))])),
+        );
+        ctx.insert(
+            "tags".to_string(),
+            Value::Seq(vec![Value::String("billing".to_string()), Value::String("eu".to_string())]),
+        );
+        ctx
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let ctx = context("prod", "Timeout", 1);
+        let mut reordered = ctx.clone();
+        reordered.set_key_order(KeyOrder::Priority(vec!["tags".to_string()]));
+        assert_eq!(ctx.fingerprint(), reordered.fingerprint());
+        assert_eq!(ctx.fingerprint().len(), 16);
+        assert_ne!(ctx.fingerprint(), context("prod", "Timeout", 2).fingerprint());
+    }
+
+    #[test]
+    fn test_find() {
+        let mut index = ContextIndex::new();
+        let timeout = index.insert(&context("prod", "Timeout", 1));
+        let refused = index.insert(&context("prod", "Refused", 2));
+        let staging = index.insert(&context("staging", "Timeout", 1));
+        assert_eq!(index.insert(&context("prod", "Timeout", 1)), timeout);
+        assert_eq!(index.len(), 3);
+
+        assert_eq!(index.find(&[("env", "prod"), ("error.kind", "Timeout")]), vec![timeout.as_str()]);
+        let mut prod = vec![timeout.as_str(), refused.as_str()];
+        prod.sort();
+        assert_eq!(index.find(&[("env", "prod")]), prod);
+        assert_eq!(index.find(&[("attempt", "2")]), vec![refused.as_str()]);
+        assert_eq!(index.find(&[("tags", "eu")]).len(), 3);
+        assert!(index.find(&[("env", "dev")]).is_empty());
+        assert!(index.find(&[("missing", "prod")]).is_empty());
+        assert_eq!(index.find(&[]).len(), 3);
+        assert_eq!(
+            index.get(&staging).and_then(|dump| dump.get("env")),
+            Some(&Value::String("staging".to_string()))
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_ingest_ndjson() {
+        let ndjson = format!(
+            "{}\n\n{}\n",
+            context("prod", "Timeout", 1).to_json(false).unwrap(),
+            context("staging", "Timeout", 3).to_json(false).unwrap()
+        );
+        let mut index = ContextIndex::new();
+        assert_eq!(index.ingest_ndjson(ndjson.as_bytes()).unwrap(), 2);
+        assert_eq!(index.find(&[("error.kind", "Timeout")]).len(), 2);
+        assert_eq!(index.find(&[("env", "staging"), ("attempt", "3")]).len(), 1);
+
+        assert!(index.ingest_ndjson("not json\n".as_bytes()).is_err());
+    }
+}
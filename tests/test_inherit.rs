@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Checkpoint, Context, Contextualize, KeyNotFound, KeyOrder, KeyPolicy, PARENT_ID_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn request() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_id() {
+        let ctx = request();
+        assert_eq!(ctx.id(), ctx.clone().id());
+        assert_ne!(ctx.id(), request().id());
+        assert!(ctx.parent_id().is_none());
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut parent = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        parent.insert("Request_Id".to_string(), Value::String("abc".to_string()));
+        parent.protect_key("request_id");
+        let child = parent.fork();
+        assert_ne!(child.id(), parent.id());
+        assert_eq!(child.parent_id(), Some(parent.id()));
+        assert_eq!(child.get(PARENT_ID_KEY), Some(&Value::String(parent.id().to_string())));
+        assert_eq!(child.get("REQUEST_ID"), Some(&Value::String("abc".to_string())));
+        assert!(child.is_protected("request_id"));
+        assert!(parent.get(PARENT_ID_KEY).is_none());
+
+        let grandchild = child.fork();
+        assert_eq!(grandchild.parent_id(), Some(child.id()));
+    }
+
+    #[test]
+    fn test_fork_keeps_insertion_order() {
+        let mut parent = Context::new();
+        parent.set_key_order(KeyOrder::Insertion);
+        parent.insert("zeta".to_string(), Value::U8(1));
+        parent.insert("alpha".to_string(), Value::U8(2));
+        let child = parent.fork();
+        let keys: Vec<String> = child.ordered_entries().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["zeta", "alpha", PARENT_ID_KEY]);
+    }
+
+    #[test]
+    fn test_fork_keeps_defaults_and_seals() {
+        let mut parent = request();
+        parent.register_default("timeout_ms", || Value::U64(500));
+        parent.seal_values();
+        let child = parent.fork();
+        assert_eq!(child.get_i64("timeout_ms").unwrap(), 500);
+        assert!(child.has_seals());
+        assert!(child.verify_seals().is_empty());
+        assert_eq!(child.dirty_keys(Checkpoint::default()), vec![PARENT_ID_KEY]);
+    }
+
+    #[test]
+    fn test_scope() {
+        assert!(Context::current().is_none());
+        let ctx = request();
+        let id = ctx.id().to_string();
+        ctx.scope(|| {
+            assert_eq!(Context::current().unwrap().id(), id);
+            Context::new().scope(|| assert_ne!(Context::current().unwrap().id(), id));
+            assert_eq!(Context::current().unwrap().id(), id);
+        });
+        assert!(Context::current().is_none());
+    }
+
+    #[test]
+    fn test_enter_and_with_current() {
+        assert_eq!(Context::with_current(|ctx| ctx.len()), None);
+        let err: cdumay_core::Error = KeyNotFound::new().with_current_context().into();
+        assert!(err.details().is_empty());
+
+        let guard = request().enter();
+        assert_eq!(
+            Context::with_current(|ctx| ctx.insert("user".to_string(), Value::String("jane".to_string()))),
+            Some(())
+        );
+        {
+            let _inner = Context::new().enter();
+            assert!(Context::current().unwrap().is_empty());
+        }
+        let details = BTreeMap::from([("user".to_string(), Value::String("john".to_string()))]);
+        let err: cdumay_core::Error = KeyNotFound::new().with_details(details).with_current_context().into();
+        assert_eq!(err.details()["request_id"], Value::String("abc".to_string()));
+        assert_eq!(err.details()["user"], Value::String("john".to_string()));
+
+        let ctx = guard.exit();
+        assert_eq!(ctx.get("user"), Some(&Value::String("jane".to_string())));
+        assert!(Context::current().is_none());
+    }
+
+    #[test]
+    fn test_with_current_reentrant() {
+        let guard = request().enter();
+        let nested = Context::with_current(|ctx| {
+            ctx.insert("user".to_string(), Value::String("jane".to_string()));
+            (Context::current().is_none(), Context::with_current(|inner| inner.len()))
+        });
+        assert_eq!(nested, Some((true, None)));
+
+        let panicked = std::panic::catch_unwind(|| Context::with_current(|_| panic!("boom")));
+        assert!(panicked.is_err());
+        let ctx = guard.exit();
+        assert_eq!(ctx.get("user"), Some(&Value::String("jane".to_string())));
+    }
+
+    #[test]
+    fn test_spawn_thread() {
+        assert!(Context::spawn_thread(Context::current).join().unwrap().is_none());
+
+        let ctx = request();
+        let id = ctx.id().to_string();
+        let child = ctx.scope(|| Context::spawn_thread(|| Context::current().unwrap()).join().unwrap());
+        assert_eq!(child.parent_id(), Some(id.as_str()));
+        assert_eq!(child.get("request_id"), Some(&Value::String("abc".to_string())));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_spawn_task() {
+        let ctx = request();
+        let id = ctx.id().to_string();
+        let child = ctx
+            .scope_task(async { Context::spawn_task(async { Context::current().unwrap() }).await.unwrap() })
+            .await;
+        assert_eq!(child.parent_id(), Some(id.as_str()));
+        assert!(Context::spawn_task(async { Context::current() }).await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_with_current_task() {
+        let user = request()
+            .scope_task(async {
+                Context::with_current(|ctx| ctx.insert("user".to_string(), Value::String("jane".to_string())));
+                Context::current().unwrap().get("user").cloned()
+            })
+            .await;
+        assert_eq!(user, Some(Value::String("jane".to_string())));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_with_current_task_reentrant() {
+        let (nested, user) = request()
+            .scope_task(async {
+                let nested = Context::with_current(|ctx| {
+                    ctx.insert("user".to_string(), Value::String("jane".to_string()));
+                    (Context::current().is_none(), Context::with_current(|inner| inner.len()))
+                });
+                (nested, Context::current().unwrap().get("user").cloned())
+            })
+            .await;
+        assert_eq!(nested, Some((true, None)));
+        assert_eq!(user, Some(Value::String("jane".to_string())));
+    }
+}
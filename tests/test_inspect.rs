@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, CrdtContext};
+    use serde::Serialize;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[derive(Default, Serialize)]
+    struct MapContext {
+        data: BTreeMap<String, Value>,
+    }
+
+    impl Contextualize for MapContext {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn insert(&mut self, k: String, v: Value) {
+            self.data.insert(k, v);
+        }
+
+        fn get(&self, k: &str) -> Option<&Value> {
+            self.data.get(k)
+        }
+
+        fn extend(&mut self, data: BTreeMap<String, Value>) {
+            self.data.extend(data);
+        }
+
+        fn inner(&self) -> BTreeMap<String, Value> {
+            self.data.clone()
+        }
+    }
+
+    #[test]
+    fn test_default_implementations() {
+        let mut ctx = MapContext::new();
+        assert!(ctx.is_empty());
+        ctx.insert("a".to_string(), Value::U8(1));
+        ctx.insert("b".to_string(), Value::U8(2));
+        assert_eq!(ctx.len(), 2);
+        assert!(ctx.contains_key("a"));
+        assert_eq!(ctx.remove("a"), Some(Value::U8(1)));
+        assert_eq!(ctx.remove("a"), None);
+        assert!(!ctx.contains_key("a"));
+        ctx.clear();
+        assert!(ctx.is_empty());
+    }
+
+    #[test]
+    fn test_context() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), Value::U8(1));
+        ctx.insert("b".to_string(), Value::U8(2));
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx.protect_key("request_id");
+        assert_eq!(ctx.len(), 3);
+        assert!(ctx.contains_key("b"));
+        assert_eq!(ctx.remove("b"), Some(Value::U8(2)));
+        assert!(!ctx.contains_key("b"));
+
+        ctx.clear();
+        assert_eq!(ctx.inner().into_keys().collect::<Vec<_>>(), vec!["request_id"]);
+        assert_eq!(ctx.len(), 1);
+        assert!(!ctx.is_empty());
+    }
+
+    #[test]
+    fn test_crdt_context() {
+        let mut ctx = CrdtContext::with_node("a");
+        ctx.insert("a".to_string(), Value::U8(1));
+        ctx.insert("b".to_string(), Value::U8(2));
+        assert_eq!(ctx.len(), 2);
+        ctx.clear();
+        assert!(ctx.is_empty());
+        assert!(!ctx.contains_key("a"));
+        assert!(ctx.entry("a").is_some());
+        assert_eq!(ctx.node(), "a");
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::interop::ContextValue;
+    use serde_value::Value;
+
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(entries.into_iter().map(|(k, v)| (Value::String(k.to_string()), v)).collect())
+    }
+
+    #[test]
+    fn test_wrapper() {
+        let value = ContextValue::from(Value::U8(1));
+        assert_eq!(Value::from(value.clone()), Value::U8(1));
+        assert_eq!(value.into_inner(), Value::U8(1));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        use cdumay_context::interop::{from_json_value, to_json_value};
+        use std::collections::BTreeMap;
+
+        let value = from_json_value(serde_json::json!({"items": [1, -2, 1.5], "ok": true, "none": null})).unwrap();
+        let expected = map(vec![
+            ("items", Value::Seq(vec![Value::U64(1), Value::I64(-2), Value::F64(1.5)])),
+            ("none", Value::Unit),
+            ("ok", Value::Bool(true)),
+        ]);
+        assert_eq!(value, expected);
+        assert_eq!(
+            to_json_value(&value).unwrap(),
+            serde_json::json!({"items": [1, -2, 1.5], "ok": true, "none": null})
+        );
+
+        let err = serde_json::Value::try_from(ContextValue(Value::Map(BTreeMap::from([(Value::Seq(vec![]), Value::Unit)])))).unwrap_err();
+        assert_eq!(err.message(), "Failed to convert value to JSON");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let document: toml::Value = toml::from_str("name = \"job\"\nat = 1979-05-27T07:32:00Z\n").unwrap();
+        let value = ContextValue::try_from(document.clone()).unwrap().into_inner();
+        assert_eq!(
+            value,
+            map(vec![
+                ("at", Value::String("1979-05-27T07:32:00Z".to_string())),
+                ("name", Value::String("job".to_string()))
+            ])
+        );
+        let document: toml::Value = ContextValue(map(vec![("name", Value::String("job".to_string()))])).try_into().unwrap();
+        assert_eq!(document["name"].as_str(), Some("job"));
+        assert!(TryInto::<toml::Value>::try_into(ContextValue(Value::Unit)).is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let document: serde_yaml::Value = serde_yaml::from_str("id: !uuid abc\n1: one\n").unwrap();
+        let value = ContextValue::try_from(document).unwrap().into_inner();
+        assert_eq!(
+            value,
+            map(vec![("1", Value::String("one".to_string())), ("id", Value::String("abc".to_string()))])
+        );
+        let yaml = serde_yaml::Value::try_from(ContextValue(value)).unwrap();
+        assert_eq!(yaml["id"].as_str(), Some("abc"));
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyPolicy, ScopedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    fn pairs() -> Vec<(String, Value)> {
+        vec![
+            ("user".to_string(), Value::String("jane".to_string())),
+            ("attempt".to_string(), Value::U8(2)),
+        ]
+    }
+
+    #[test]
+    fn test_iterate() {
+        let mut ctx: Context = pairs().into_iter().collect();
+        ctx.insert_lazy("region", || Value::String("eu".to_string()));
+        assert_eq!(ctx.keys().collect::<Vec<_>>(), vec!["attempt", "region", "user"]);
+        assert_eq!(ctx.values().nth(1), Some(Value::String("eu".to_string())));
+        assert_eq!(ctx.iter().count(), 3);
+
+        let mut seen = Vec::new();
+        for (k, v) in &ctx {
+            seen.push((k.as_str(), v));
+        }
+        seen.sort();
+        assert_eq!(seen, vec![("attempt", &Value::U8(2)), ("user", &Value::String("jane".to_string()))]);
+        assert_eq!(ctx.into_iter().collect::<BTreeMap<_, _>>().len(), 3);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        Extend::extend(&mut ctx, pairs());
+        Extend::extend(&mut ctx, [("USER".to_string(), Value::String("john".to_string()))]);
+        assert_eq!(ctx.len(), 2);
+        assert_eq!(ctx["user"], Value::String("john".to_string()));
+
+        ctx.extend(BTreeMap::from([("tenant".to_string(), Value::String("acme".to_string()))]));
+        assert_eq!(ctx.len(), 3);
+    }
+
+    #[test]
+    fn test_eq() {
+        let ctx: Context = pairs().into_iter().collect();
+        let other = Context::from(pairs());
+        assert_ne!(ctx.id(), other.id());
+        assert_eq!(ctx, other);
+        assert_eq!(ctx.clone(), ctx);
+        assert_ne!(ctx, Context::new());
+
+        let mut live = ctx.clone();
+        live.insert_live("in_flight", || Value::U8(1));
+        assert_eq!(live.clone(), live);
+        assert_ne!(live, ctx);
+        let mut other = ctx.clone();
+        other.insert_live("in_flight", || Value::U8(1));
+        assert_ne!(live, other);
+
+        let mut defaulted = ctx.clone();
+        defaulted.register_default("region", || Value::String("eu".to_string()));
+        assert_eq!(defaulted, ctx);
+    }
+
+    #[test]
+    fn test_default_methods() {
+        let mut scoped = ScopedContext::new(Arc::new(pairs().into_iter().collect()));
+        scoped.insert("step".to_string(), Value::String("fetch".to_string()));
+        assert_eq!(scoped.keys().collect::<Vec<_>>(), vec!["attempt", "step", "user"]);
+    }
+}
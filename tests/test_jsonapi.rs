@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Conflict, Context, Contextualize, JsonApiError, JsonApiErrors, Sensitivity};
+    use serde_value::Value;
+
+    #[test]
+    fn test_from_error() {
+        let err: cdumay_core::Error = Conflict::new().with_message("Order 42 already exists".to_string()).into();
+        let error = JsonApiError::from(&err).with_id("abc").with_title("Conflict");
+        assert_eq!(error.id.as_deref(), Some("abc"));
+        assert_eq!(error.status, "409");
+        assert_eq!(error.code.as_deref(), Some(err.class()));
+        assert_eq!(error.title.as_deref(), Some("Conflict"));
+        assert_eq!(error.detail.as_deref(), Some("Order 42 already exists"));
+        assert!(error.meta.is_empty());
+    }
+
+    #[test]
+    fn test_with_context() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.set_sensitivity("request_id", Sensitivity::Public);
+        ctx.set_sensitivity("user", Sensitivity::Confidential);
+
+        let err: cdumay_core::Error = Conflict::new().into();
+        let document: JsonApiErrors = [JsonApiError::from(&err).with_context(&ctx), JsonApiError::from(err)]
+            .into_iter()
+            .collect();
+        assert_eq!(document.errors.len(), 2);
+        assert_eq!(document.errors[0].meta.keys().collect::<Vec<_>>(), vec!["request_id"]);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_to_json() {
+        let err: cdumay_core::Error = Conflict::new().into();
+        let json: serde_json::Value = serde_json::from_str(&JsonApiErrors::from(JsonApiError::from(&err)).to_json(false).unwrap()).unwrap();
+        assert_eq!(json["errors"][0]["status"], "409");
+        assert!(json["errors"][0].get("meta").is_none());
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextConfig, Contextualize, Sensitivity};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_properties() {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "db".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("host".to_string()), Value::String("pg".to_string()))])),
+        );
+        ctx.insert("tags".to_string(), Value::Seq(vec![Value::Bool(true)]));
+        ctx.insert("quote".to_string(), Value::String("say \"a\" & 'b'\u{1}".to_string()));
+        ctx.insert("empty".to_string(), Value::Option(None));
+        assert_eq!(
+            ctx.to_junit_properties(),
+            concat!(
+                "<properties>\n",
+                "  <property name=\"db.host\" value=\"pg\"/>\n",
+                "  <property name=\"empty\" value=\"null\"/>\n",
+                "  <property name=\"quote\" value=\"say &quot;a&quot; &amp; 'b'\"/>\n",
+                "  <property name=\"tags.0\" value=\"true\"/>\n",
+                "</properties>\n"
+            )
+        );
+        assert_eq!(Context::new().to_junit_properties(), "<properties>\n</properties>\n");
+    }
+
+    #[test]
+    fn test_redaction() {
+        let mut ctx = Context::with_config(ContextConfig::new().redact("password"));
+        ctx.insert("password".to_string(), Value::String("hunter2".to_string()));
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        let xml = ctx.to_junit_properties();
+        assert!(xml.contains("<property name=\"password\" value=\"[redacted]\"/>"));
+        assert!(xml.contains("<property name=\"token\" value=\"[redacted]\"/>"));
+        assert!(!xml.contains("hunter2") && !xml.contains("s3cr3t"));
+    }
+}
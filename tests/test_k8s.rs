@@ -0,0 +1,24 @@
+#[cfg(test)]
+#[cfg(feature = "k8s")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+
+    #[test]
+    fn test_with_k8s_metadata_from() {
+        let dir = std::env::temp_dir().join(format!("cdumay_context_podinfo_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("labels"), "app=\"web\"\ntier=\"front \\\"end\\\"\"\n").unwrap();
+        std::env::set_var("POD_NAME", "web-7d9f");
+        std::env::set_var("POD_NAMESPACE", "prod");
+        std::env::set_var("NODE_NAME", "node-1");
+
+        let ctx = Context::new().with_k8s_metadata_from(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(ctx.at("k8s").at("pod_name").as_str(), Some("web-7d9f"));
+        assert_eq!(ctx.at("k8s").at("namespace").as_str(), Some("prod"));
+        assert_eq!(ctx.at("k8s").at("node_name").as_str(), Some("node-1"));
+        assert_eq!(ctx.at("k8s").at("labels").at("app").as_str(), Some("web"));
+        assert_eq!(ctx.at("k8s").at("labels").at("tier").as_str(), Some("front \"end\""));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{keys, BuildInfo, Context, Contextualize, BUILD_KEY, DEADLINE_KEY, HTTP_KEY, PARENT_ID_KEY, PROPAGATION_KEY};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_helpers_use_keys() {
+        let mut ctx = Context::new().with_build_info(&BuildInfo {
+            git_sha: Some("4f1c2e9".to_string()),
+            ..Default::default()
+        });
+        ctx.enter_phase("fetch");
+        ctx.set_max_attempts(3);
+        ctx.record_attempt("timeout");
+        ctx.set_deadline(Instant::now() + Duration::from_secs(5));
+        ctx.record_http_response(503, Vec::<(&str, &str)>::new(), Duration::from_millis(12));
+        ctx.record_causes(&std::io::Error::other("disk full"));
+        for key in [
+            keys::BUILD,
+            keys::PHASES,
+            keys::ATTEMPT,
+            keys::MAX_ATTEMPTS,
+            keys::ATTEMPT_HISTORY,
+            keys::DEADLINE,
+            keys::HTTP,
+            keys::ERROR,
+        ] {
+            assert!(ctx.contains_key(key), "{}", key);
+        }
+        assert_eq!(ctx.get_path(keys::HTTP_STATUS), Some(&serde_value::Value::U16(503)));
+        assert!(ctx.get_path(keys::ERROR_CAUSES).is_some());
+        assert!(ctx.fork().contains_key(keys::PARENT_ID));
+    }
+
+    #[test]
+    fn test_legacy_constants() {
+        assert_eq!(BUILD_KEY, keys::BUILD);
+        assert_eq!(DEADLINE_KEY, keys::DEADLINE);
+        assert_eq!(HTTP_KEY, keys::HTTP);
+        assert_eq!(PARENT_ID_KEY, keys::PARENT_ID);
+        assert_eq!(PROPAGATION_KEY, keys::PROPAGATION);
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+#[cfg(feature = "lambda")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_from_lambda_env() {
+        std::env::set_var("AWS_LAMBDA_FUNCTION_NAME", "checkout");
+        std::env::set_var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "512");
+
+        let ctx = Context::from_lambda_env();
+        assert_eq!(ctx.at("lambda").at("function_name").as_str(), Some("checkout"));
+        assert_eq!(ctx.at("lambda").at("memory_limit_mb").as_u64(), Some(512));
+    }
+
+    #[test]
+    fn test_record_lambda_invocation() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let mut invocation = lambda_runtime::Context::default();
+        invocation.request_id = "8f5e1c2a".to_string();
+        invocation.invoked_function_arn = "arn:aws:lambda:eu-west-1:123456789012:function:checkout".to_string();
+        invocation.deadline = now + 30_000;
+        invocation.xray_trace_id = Some("Root=1-5759e988-bd862e3fe1be46a994272793".to_string());
+        invocation.env_config = Arc::new(lambda_runtime::Config {
+            memory: 256,
+            ..Default::default()
+        });
+
+        let mut ctx = Context::new();
+        ctx.record_lambda_invocation(&invocation);
+
+        let lambda = ctx.at("lambda");
+        assert_eq!(lambda.at("request_id").as_str(), Some("8f5e1c2a"));
+        assert_eq!(
+            lambda.at("function_arn").as_str(),
+            Some("arn:aws:lambda:eu-west-1:123456789012:function:checkout")
+        );
+        assert_eq!(lambda.at("memory_limit_mb").as_u64(), Some(256));
+        assert!(lambda.at("remaining_time_ms").as_u64().unwrap() > 25_000);
+        assert!(lambda.at("xray_trace_id").as_str().is_some());
+        assert!(ctx.remaining().unwrap() > Duration::from_secs(25));
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, ContextDump, Contextualize, ScopedContext};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    fn base() -> Arc<Context> {
+        let mut ctx = Context::new();
+        ctx.insert("service".to_string(), s("billing"));
+        ctx.insert("env".to_string(), s("prod"));
+        Arc::new(ctx)
+    }
+
+    #[test]
+    fn test_reads_fall_back_to_parent() {
+        let base = base();
+        let mut child = base.child();
+        child.insert("env".to_string(), s("canary"));
+        child.insert("request_id".to_string(), s("abc"));
+        assert_eq!(child.get_str("service").unwrap(), "billing");
+        assert_eq!(child.get_str("env").unwrap(), "canary");
+        assert!(child.contains_key("service"));
+        assert!(!child.contains_key("missing"));
+        assert_eq!(child.len(), 3);
+        assert_eq!(child.layer().len(), 2);
+        assert_eq!(base.get_str("env").unwrap(), "prod");
+        assert!(!base.contains_key("request_id"));
+    }
+
+    #[test]
+    fn test_merged_view() {
+        let mut child = ScopedContext::new(base());
+        child.extend(BTreeMap::from([("env".to_string(), s("dev")), ("user".to_string(), s("jane"))]));
+        let expected = BTreeMap::from([
+            ("env".to_string(), s("dev")),
+            ("service".to_string(), s("billing")),
+            ("user".to_string(), s("jane")),
+        ]);
+        assert_eq!(child.inner(), expected);
+        assert_eq!(child.dump(), expected);
+        assert_eq!(serde_value::to_value(&child).unwrap(), serde_value::to_value(&expected).unwrap());
+    }
+
+    #[test]
+    fn test_remove_and_pop() {
+        let base = base();
+        let mut child = base.child();
+        child.insert("env".to_string(), s("canary"));
+        assert_eq!(child.remove("env"), Some(s("canary")));
+        assert_eq!(child.get_str("env").unwrap(), "prod");
+        assert_eq!(child.remove("service"), None);
+        assert_eq!(child.get_str("service").unwrap(), "billing");
+        child.insert("user".to_string(), s("jane"));
+        child.clear();
+        assert_eq!(child.len(), 2);
+        child.insert("user".to_string(), s("jane"));
+        let parent = child.pop();
+        assert!(Arc::ptr_eq(&parent, &base));
+        assert!(!parent.contains_key("user"));
+        assert!(<ScopedContext as Contextualize>::new().is_empty());
+    }
+}
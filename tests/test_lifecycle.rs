@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, Lifecycle};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn sealed() -> Context {
+        let mut ctx = Context::new();
+        ctx.start_lifecycle().unwrap();
+        ctx.insert("user".to_string(), Value::U64(42));
+        ctx.activate().unwrap();
+        ctx.seal().unwrap();
+        ctx
+    }
+
+    #[test]
+    fn test_untracked_lifecycle() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.lifecycle(), None);
+        assert!(!ctx.is_sealed());
+        assert!(ctx.seal().is_err());
+        assert!(ctx.emit().is_err());
+    }
+
+    #[test]
+    fn test_transitions_are_enforced() {
+        let mut ctx = Context::new();
+        ctx.start_lifecycle().unwrap();
+        assert_eq!(ctx.lifecycle(), Some(Lifecycle::Building));
+        assert!(ctx.start_lifecycle().is_err());
+        let err = ctx.seal().unwrap_err();
+        assert_eq!(err.code(), 409);
+        assert!(err.class().contains("InvalidState"));
+        ctx.activate().unwrap();
+        assert!(ctx.activate().is_err());
+        assert!(ctx.emit().is_err());
+        ctx.seal().unwrap();
+        assert_eq!(ctx.lifecycle(), Some(Lifecycle::Sealed));
+    }
+
+    #[test]
+    fn test_sealed_context_cannot_change() {
+        let mut ctx = sealed();
+        ctx.insert("user".to_string(), Value::U64(0));
+        ctx.insert("extra".to_string(), Value::Bool(true));
+        ctx.force_insert("user".to_string(), Value::U64(0));
+        assert!(ctx.remove("user").is_none());
+        assert!(ctx.try_insert("extra".to_string(), Value::Bool(true)).is_err());
+        assert!(ctx.try_extend(BTreeMap::from([("extra".to_string(), Value::Bool(true))])).is_err());
+        assert!(ctx.insert_once("extra".to_string(), Value::Bool(true)).is_err());
+        assert_eq!(ctx.inner(), BTreeMap::from([("user".to_string(), Value::U64(42))]));
+    }
+
+    #[test]
+    fn test_emit_exactly_once() {
+        let mut ctx = sealed();
+        let dump = ctx.emit().unwrap();
+        assert_eq!(dump.get("user"), Some(&Value::U64(42)));
+        assert!(ctx.is_sealed());
+        assert!(ctx.emit().is_err());
+        ctx.insert("user".to_string(), Value::U64(0));
+        assert_eq!(ctx.inner(), dump);
+    }
+}
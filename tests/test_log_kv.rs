@@ -0,0 +1,75 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "log-kv")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, Sensitivity, REDACTED};
+    use log::kv::{Key, Source, ToValue, VisitSource};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Collect {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    fn collect(source: &dyn Source) -> Vec<(String, String)> {
+        let mut visitor = Collect(Vec::new());
+        source.visit(&mut visitor).unwrap();
+        visitor.0
+    }
+
+    fn context() -> Context {
+        let mut ctx = context_of([("status", Value::U16(503)), ("user", s("jane")), ("token", s("s3cr3t"))]);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx.insert("empty".to_string(), Value::Option(None));
+        ctx
+    }
+
+    #[test]
+    fn test_as_kv() {
+        let ctx = context();
+        let kv = ctx.as_kv();
+        assert_eq!(kv.count(), 4);
+        assert_eq!(kv.get(Key::from_str("status")).unwrap().to_u64(), Some(503));
+        assert_eq!(kv.get(Key::from_str("token")).unwrap().to_string(), REDACTED);
+        assert!(kv.get(Key::from_str("missing")).is_none());
+        assert_eq!(
+            collect(&kv),
+            vec![
+                ("empty".to_string(), "None".to_string()),
+                ("status".to_string(), "503".to_string()),
+                ("token".to_string(), REDACTED.to_string()),
+                ("user".to_string(), "jane".to_string()),
+            ]
+        );
+        assert!(kv.to_value().to_string().contains("jane"));
+    }
+
+    #[test]
+    fn test_context_source() {
+        let mut ctx = context();
+        ctx.insert("tags".to_string(), Value::Seq(vec![Value::String("a".to_string())]));
+        ctx.insert(
+            "http".to_string(),
+            Value::Map(BTreeMap::from([(Value::String("method".to_string()), Value::String("GET".to_string()))])),
+        );
+        let pairs: BTreeMap<String, String> = collect(&ctx).into_iter().collect();
+        assert_eq!(pairs["token"], REDACTED);
+        assert_eq!(pairs["user"], "jane");
+        assert!(pairs["tags"].contains('a'));
+        assert!(pairs["http"].contains("GET"));
+        assert_eq!(Source::count(&ctx), 6);
+    }
+
+    #[test]
+    fn test_log_macro() {
+        let ctx = context();
+        log::info!(ctx = ctx.as_kv(); "request received");
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, MapKeyPolicy};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        let mut ctx = context_of([
+            ("job", s("export")),
+            ("mean", Value::F64(f64::NAN)),
+            ("limits", Value::Map(BTreeMap::from([(Value::U16(1), Value::U8(2))]))),
+        ]);
+        ctx.set_map_key_policy(MapKeyPolicy::Reject);
+        ctx
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_lossy() {
+        use cdumay_context::UNSERIALIZABLE_KEY;
+
+        let ctx = context();
+        assert!(ctx.to_json(false).is_err());
+        let loaded = Context::from_json(&ctx.to_json_lossy(false)).unwrap();
+        assert_eq!(loaded.get_str("job").unwrap(), "export");
+        assert_eq!(
+            loaded.get_str("limits").unwrap(),
+            "<unserializable: Map at 'limits' has a non-string key 1>"
+        );
+        assert!(loaded.get_str("mean").unwrap().starts_with("<unserializable: Number NaN at 'mean'"));
+        let substitutions = loaded.at(UNSERIALIZABLE_KEY);
+        assert_eq!(substitutions.at("limits").as_str(), loaded.get_str("limits").ok());
+        assert!(substitutions.at("job").as_str().is_none());
+
+        let mut ctx = Context::new();
+        ctx.insert("job".to_string(), Value::String("export".to_string()));
+        assert_eq!(ctx.to_json_lossy(false), ctx.to_json(false).unwrap());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_lossy() {
+        let mut ctx = context();
+        ctx.insert("parent".to_string(), Value::Option(None));
+        ctx.insert("unserializable".to_string(), Value::Bool(true));
+        let dump = ctx.to_toml_lossy(false);
+        let loaded = Context::from_toml(&dump).unwrap();
+        assert_eq!(loaded.get_str("job").unwrap(), "export");
+        assert!(loaded.get_str("parent").unwrap().starts_with("<unserializable: Null value"));
+        assert!(loaded.at("mean").as_f64().unwrap().is_nan());
+        assert_eq!(loaded.at("unserializable").as_map().map(|map| map.len()), Some(2));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_lossy() {
+        let mut ctx = context();
+        ctx.insert("payload".to_string(), Value::Bytes(vec![0, 159]));
+        let loaded = Context::from_yaml(&ctx.to_yaml_lossy()).unwrap();
+        assert_eq!(loaded.get_str("job").unwrap(), "export");
+        assert!(loaded.get_str("payload").unwrap().starts_with("<unserializable: Byte string"));
+        assert!(loaded.at("unserializable").at("payload").as_str().is_some());
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{BoundedLruContext, ContextDump, Contextualize, LruStats, DEFAULT_LRU_CAPACITY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&evicted);
+        let mut ctx = BoundedLruContext::with_capacity(2).on_evict(move |k, v| sink.lock().unwrap().push((k.to_string(), v.clone())));
+        ctx.insert("a".to_string(), s("1"));
+        ctx.insert("b".to_string(), s("2"));
+        assert_eq!(ctx.get_str("a").unwrap(), "1");
+        ctx.insert("c".to_string(), s("3"));
+        assert_eq!(ctx.keys_by_recency(), vec!["a", "c"]);
+        assert!(ctx.peek("b").is_none());
+
+        assert!(ctx.contains_key("a"));
+        assert_eq!(ctx.peek("a"), Some(&s("1")));
+        ctx.insert("d".to_string(), s("4"));
+        assert_eq!(ctx.dump(), BTreeMap::from([("c".to_string(), s("3")), ("d".to_string(), s("4"))]));
+        assert_eq!(*evicted.lock().unwrap(), vec![("b".to_string(), s("2")), ("a".to_string(), s("1"))]);
+        assert_eq!(
+            ctx.stats(),
+            LruStats {
+                hits: 1,
+                misses: 0,
+                evictions: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut ctx = BoundedLruContext::new();
+        assert_eq!(ctx.capacity(), DEFAULT_LRU_CAPACITY);
+        ctx.insert("a".to_string(), s("1"));
+        assert!(ctx.get("a").is_some());
+        assert!(ctx.get("b").is_none());
+        assert!(ctx.get("c").is_none());
+        assert_eq!(
+            ctx.stats(),
+            LruStats {
+                hits: 1,
+                misses: 2,
+                evictions: 0
+            }
+        );
+        let clone = ctx.clone();
+        ctx.reset_stats();
+        assert_eq!(ctx.stats(), LruStats::default());
+        assert_eq!(clone.stats().misses, 2);
+    }
+
+    #[test]
+    fn test_capacity_changes() {
+        let mut ctx = BoundedLruContext::with_capacity(3);
+        ctx.extend(BTreeMap::from([
+            ("a".to_string(), s("1")),
+            ("b".to_string(), s("2")),
+            ("c".to_string(), s("3")),
+        ]));
+        ctx.insert("a".to_string(), s("4"));
+        ctx.set_capacity(1);
+        assert_eq!(ctx.inner(), BTreeMap::from([("a".to_string(), s("4"))]));
+        assert_eq!(ctx.stats().evictions, 2);
+
+        ctx.set_capacity(0);
+        assert!(ctx.is_empty());
+        ctx.insert("b".to_string(), s("5"));
+        assert!(ctx.is_empty());
+        assert_eq!(ctx.stats().evictions, 4);
+    }
+
+    #[test]
+    fn test_remove_and_clear() {
+        let mut ctx = BoundedLruContext::with_capacity(2);
+        ctx.insert("a".to_string(), s("1"));
+        ctx.insert("b".to_string(), s("2"));
+        assert_eq!(ctx.remove("a"), Some(s("1")));
+        ctx.insert("c".to_string(), s("3"));
+        assert_eq!(ctx.len(), 2);
+        assert_eq!(ctx.stats().evictions, 0);
+        ctx.clear();
+        assert!(ctx.keys_by_recency().is_empty());
+        assert_eq!(serde_value::to_value(&ctx).unwrap(), Value::Map(BTreeMap::new()));
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, MapKeyPolicy};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([(
+            "http",
+            Value::Map(BTreeMap::from([
+                (Value::U16(404), s("not found")),
+                (
+                    Value::Bool(true),
+                    Value::Seq(vec![Value::Map(BTreeMap::from([(Value::I8(-1), Value::U8(0))]))]),
+                ),
+                (s("404"), s("kept")),
+                (Value::Seq(vec![Value::U8(1), Value::U8(2)]), Value::Bool(false)),
+            ])),
+        )])
+    }
+
+    #[test]
+    fn test_default_policy() {
+        assert_eq!(Context::new().map_key_policy(), MapKeyPolicy::Stringify);
+        let mut ctx = context();
+        ctx.set_map_key_policy(MapKeyPolicy::Drop);
+        assert_eq!(ctx.fork().map_key_policy(), MapKeyPolicy::Drop);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        let mut ctx = context();
+        assert_eq!(
+            ctx.to_json(false).unwrap(),
+            r#"{"http":{"404":"kept","[ 1, 2 ]":false,"true":[{"-1":0}]}}"#
+        );
+
+        ctx.set_map_key_policy(MapKeyPolicy::Drop);
+        assert_eq!(ctx.to_json(false).unwrap(), r#"{"http":{"404":"kept"}}"#);
+
+        ctx.set_map_key_policy(MapKeyPolicy::Reject);
+        let err = ctx.to_json(false).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err.class().contains("InvalidMapKey"));
+        assert!(err.message().contains("'http'"), "{}", err.message());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_reject_nested_path() {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "items".to_string(),
+            Value::Seq(vec![Value::Map(BTreeMap::from([(
+                s("codes"),
+                Value::Map(BTreeMap::from([(Value::U8(1), Value::Unit)])),
+            )]))]),
+        );
+        ctx.set_map_key_policy(MapKeyPolicy::Reject);
+        let err = ctx.to_json(false).unwrap_err();
+        assert!(err.message().contains("'items.0.codes'"), "{}", err.message());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let mut ctx = context();
+        ctx.set_map_key_policy(MapKeyPolicy::Drop);
+        assert_eq!(ctx.to_toml(false).unwrap().trim(), "[http]\n404 = \"kept\"");
+
+        ctx.set_map_key_policy(MapKeyPolicy::Stringify);
+        assert!(ctx.to_toml(false).unwrap().contains("[[http.true]]"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let mut ctx = context();
+        assert!(ctx.to_yaml().unwrap().contains("'true':"));
+
+        ctx.set_map_key_policy(MapKeyPolicy::Reject);
+        assert!(ctx.to_yaml().is_err());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn template() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("batch".to_string(), Value::String("nightly".to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let ctx = template();
+        let matrix = BTreeMap::from([
+            (
+                "os".to_string(),
+                vec![Value::String("linux".to_string()), Value::String("macos".to_string())],
+            ),
+            ("version".to_string(), vec![Value::U8(1), Value::U8(2)]),
+        ]);
+        let children = ctx.expand(matrix);
+        let combinations: Vec<_> = children
+            .iter()
+            .map(|child| (child.at("os").as_str(), child.at("version").as_u64()))
+            .collect();
+        assert_eq!(
+            combinations,
+            vec![
+                (Some("linux"), Some(1)),
+                (Some("linux"), Some(2)),
+                (Some("macos"), Some(1)),
+                (Some("macos"), Some(2))
+            ]
+        );
+        for child in &children {
+            assert_eq!(child.get("batch"), ctx.get("batch"));
+            assert_eq!(child.parent_id(), Some(ctx.id()));
+        }
+        assert_ne!(children[0].id(), children[1].id());
+    }
+
+    #[test]
+    fn test_degenerate_matrices() {
+        let ctx = template();
+        let children = ctx.expand(BTreeMap::new());
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].get("batch"), ctx.get("batch"));
+
+        let matrix = BTreeMap::from([("shard".to_string(), vec![Value::U8(0), Value::U8(1)]), ("region".to_string(), vec![])]);
+        assert!(ctx.expand(matrix).is_empty());
+    }
+
+    #[test]
+    fn test_protected_keys_are_kept() {
+        let mut ctx = template();
+        ctx.protect_key("batch");
+        let matrix = BTreeMap::from([("batch".to_string(), vec![Value::String("hourly".to_string())])]);
+        let children = ctx.expand(matrix);
+        assert_eq!(children[0].get("batch"), Some(&Value::String("nightly".to_string())));
+    }
+}
@@ -0,0 +1,83 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, MergeStrategy, SharedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn map(entries: &[(&str, Value)]) -> Value {
+        Value::Map(entries.iter().map(|(k, v)| (s(k), v.clone())).collect())
+    }
+
+    fn base() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "app".to_string(),
+            map(&[("name", s("api")), ("tags", Value::Seq(vec![s("a")])), ("db", map(&[("host", s("db1"))]))]),
+        );
+        ctx.insert("env".to_string(), s("prod"));
+        ctx
+    }
+
+    fn request() -> BTreeMap<String, Value> {
+        BTreeMap::from([
+            (
+                "app".to_string(),
+                map(&[("tags", Value::Seq(vec![s("b")])), ("db", map(&[("port", Value::U16(5432))]))]),
+            ),
+            ("env".to_string(), s("staging")),
+            ("request_id".to_string(), s("abc")),
+        ])
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let mut ctx = base();
+        ctx.merge(request(), MergeStrategy::default());
+        assert_eq!(ctx.at("app").at("name").as_str(), None);
+        assert_eq!(ctx.at("env").as_str(), Some("staging"));
+        assert_eq!(ctx.at("request_id").as_str(), Some("abc"));
+    }
+
+    #[test]
+    fn test_keep_existing() {
+        let mut ctx = base();
+        let checkpoint = ctx.checkpoint();
+        ctx.merge(request(), MergeStrategy::KeepExisting);
+        assert_eq!(ctx.at("app").at("name").as_str(), Some("api"));
+        assert_eq!(ctx.at("env").as_str(), Some("prod"));
+        assert_eq!(ctx.at("request_id").as_str(), Some("abc"));
+        assert_eq!(ctx.dirty_keys(checkpoint), vec!["request_id"]);
+    }
+
+    #[test]
+    fn test_deep_merge() {
+        let mut ctx = base();
+        ctx.merge(request(), MergeStrategy::DeepMerge);
+        assert_eq!(ctx.at("app").at("name").as_str(), Some("api"));
+        assert_eq!(ctx.at("app").at("db").at("host").as_str(), Some("db1"));
+        assert_eq!(ctx.at("app").at("db").at("port").as_u64(), Some(5432));
+        assert_eq!(ctx.at("app").at("tags").as_seq(), Some(&[s("b")][..]));
+        assert_eq!(ctx.at("env").as_str(), Some("staging"));
+    }
+
+    #[test]
+    fn test_append_sequences() {
+        let mut ctx = base();
+        ctx.merge(request(), MergeStrategy::AppendSequences);
+        assert_eq!(ctx.at("app").at("tags").as_seq(), Some(&[s("a"), s("b")][..]));
+        assert_eq!(ctx.at("app").at("db").at("host").as_str(), Some("db1"));
+    }
+
+    #[test]
+    fn test_protected_keys_and_shared() {
+        let mut ctx = base();
+        ctx.protect_key("env");
+        let shared = SharedContext::new(ctx);
+        shared.merge(request(), MergeStrategy::DeepMerge);
+        assert_eq!(shared.get("env"), Some(s("prod")));
+        assert_eq!(shared.get("request_id"), Some(s("abc")));
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{clear_context_metrics, set_context_metrics, Context, ContextMetrics, Contextualize, Format, NoopMetrics};
+    use serde_value::Value;
+    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::time::Duration;
+
+    /// Metrics are global: tests installing them run one at a time.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ContextMetrics for Recorder {
+        fn on_insert(&self, key: &str) {
+            self.0.lock().unwrap().push(format!("insert {}", key));
+        }
+
+        fn on_dump(&self, format: Format, bytes: usize, _elapsed: Duration) {
+            self.0.lock().unwrap().push(format!("dump {} {}", format, bytes));
+        }
+
+        fn on_load(&self, format: Format, bytes: usize, _elapsed: Duration) {
+            self.0.lock().unwrap().push(format!("load {} {}", format, bytes));
+        }
+
+        fn on_eviction(&self, key: &str) {
+            self.0.lock().unwrap().push(format!("evict {}", key));
+        }
+
+        fn on_error(&self, error: &cdumay_core::Error) {
+            self.0.lock().unwrap().push(format!("error {}", error.code()));
+        }
+    }
+
+    fn install() -> (MutexGuard<'static, ()>, Arc<Recorder>) {
+        let guard = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let recorder = Arc::new(Recorder::default());
+        set_context_metrics(recorder.clone());
+        (guard, recorder)
+    }
+
+    fn events(recorder: &Recorder) -> Vec<String> {
+        clear_context_metrics();
+        recorder.0.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_inserts() {
+        let (_guard, recorder) = install();
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.freeze_key("user");
+        ctx.insert("user".to_string(), Value::String("john".to_string()));
+        assert_eq!(events(&recorder), vec!["insert user"]);
+
+        ctx.insert("role".to_string(), Value::Unit);
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_noop_metrics() {
+        let _guard = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        set_context_metrics(NoopMetrics);
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::Unit);
+        clear_context_metrics();
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_dump_and_load() {
+        let (_guard, recorder) = install();
+        let ctx = Context::from_json(r#"{"a":1}"#).unwrap();
+        let dump = ctx.to_json(false).unwrap();
+        assert!(Context::from_json("{").is_err());
+        assert_eq!(
+            events(&recorder),
+            vec![
+                "load JSON 7".to_string(),
+                "insert a".to_string(),
+                format!("dump JSON {}", dump.len()),
+                "error 500".to_string()
+            ]
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_evictions() {
+        use cdumay_context::ContextConfig;
+
+        let mut ctx = Context::with_config(ContextConfig::new().with_max_size(50));
+        ctx.insert("a".to_string(), Value::String("x".repeat(4)));
+        ctx.insert("b".to_string(), Value::String("y".repeat(40)));
+        let (_guard, recorder) = install();
+        ctx.to_json(false).unwrap();
+        let events = events(&recorder);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], "evict b");
+        assert!(events[1].starts_with("dump JSON "));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_error() {
+        let (_guard, recorder) = install();
+        let mut ctx = Context::new();
+        ctx.insert("big".to_string(), Value::U64(u64::MAX));
+        assert!(ctx.to_toml(false).is_err());
+        assert!(Context::from_toml("a = 1").is_ok());
+        let events = events(&recorder);
+        assert!(events[1].starts_with("error "));
+        assert_eq!(events[2], "load TOML 5");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let (_guard, recorder) = install();
+        let ctx = Context::from_yaml("a: 1\n").unwrap();
+        ctx.to_yaml().unwrap();
+        assert_eq!(events(&recorder), vec!["load YAML 5", "insert a", "dump YAML 5"]);
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+
+    #[test]
+    fn test_insert_append() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.insert_append("flag", s("-v")).unwrap(), 1);
+        assert_eq!(ctx.get("flag"), Some(&Value::Seq(vec![s("-v")])));
+        assert_eq!(ctx.insert_append("flag", s("-v")).unwrap(), 2);
+        assert_eq!(ctx.get_all("flag"), vec![&s("-v"), &s("-v")]);
+        assert!(ctx.is_multi("flag"));
+
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a")]));
+        assert_eq!(ctx.get_all("tags"), vec![&Value::Seq(vec![s("a")])]);
+        assert_eq!(ctx.insert_append("tags", s("b")).unwrap(), 2);
+        assert_eq!(ctx.get("tags"), Some(&Value::Seq(vec![Value::Seq(vec![s("a")]), s("b")])));
+    }
+
+    #[test]
+    fn test_insert_resets() {
+        let mut ctx = Context::new();
+        ctx.insert_append("accept", s("a")).unwrap();
+        ctx.insert_append("accept", s("b")).unwrap();
+        ctx.insert("accept".to_string(), Value::Seq(vec![s("c"), s("d")]));
+        assert!(!ctx.is_multi("accept"));
+        assert_eq!(ctx.get_all("accept").len(), 1);
+
+        ctx.insert_append("accept", s("e")).unwrap();
+        ctx.remove("accept");
+        assert!(!ctx.is_multi("accept"));
+        assert!(ctx.get_all("accept").is_empty());
+    }
+
+    #[test]
+    fn test_protected_and_forked() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), s("abc"));
+        ctx.protect_key("request_id");
+        assert!(ctx.insert_append("request_id", s("def")).is_err());
+        assert!(!ctx.is_multi("request_id"));
+
+        ctx.insert_append("via", s("proxy-1")).unwrap();
+        let child = ctx.fork();
+        assert!(child.is_multi("via"));
+    }
+
+    #[test]
+    fn test_shared() {
+        let shared = SharedContext::new(Context::new());
+        shared.insert_append("via", s("proxy-1")).unwrap();
+        shared.insert_append("via", s("proxy-2")).unwrap();
+        assert_eq!(shared.get_all("via"), vec![s("proxy-1"), s("proxy-2")]);
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyPolicy, NamespacedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        for key in ["http.method", "db.statement", "db.rows", "dbx", "request_id"] {
+            ctx.insert(key.to_string(), Value::String(key.to_string()));
+        }
+        ctx
+    }
+
+    #[test]
+    fn test_filter_prefix() {
+        let ctx = context();
+        let db = ctx.filter_prefix("DB.");
+        assert_eq!(db.keys().collect::<Vec<_>>(), vec!["db.rows", "db.statement"]);
+        assert_eq!(db.key_policy(), KeyPolicy::CaseInsensitive);
+        assert_eq!(ctx.filter_prefix("db").len(), 3);
+        assert!(ctx.filter_prefix("job.").is_empty());
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut ctx = context();
+        ctx.protect_key("http.method");
+        ctx.retain(|k, v| k == "request_id" || matches!(v, Value::String(s) if s.ends_with("rows")));
+        assert_eq!(ctx.keys().collect::<Vec<_>>(), vec!["db.rows", "http.method", "request_id"]);
+    }
+
+    #[test]
+    fn test_namespaced() {
+        let mut ctx = context();
+        let mut db = NamespacedContext::new(&mut ctx, "db.");
+        assert_eq!(db.prefix(), "db.");
+        assert_eq!(db.len(), 2);
+        db.insert("statement", Value::String("SELECT 1".to_string()));
+        db.extend(BTreeMap::from([("duration_ms".to_string(), Value::U64(12))]));
+        assert_eq!(db.get("statement"), Some(&Value::String("SELECT 1".to_string())));
+        assert!(db.contains_key("duration_ms"));
+        assert_eq!(db.remove("rows"), Some(Value::String("db.rows".to_string())));
+        assert_eq!(db.inner().keys().collect::<Vec<_>>(), vec!["duration_ms", "statement"]);
+        assert_eq!(db.full_key("rows"), "db.rows");
+
+        let mut job = NamespacedContext::new(&mut ctx, "job");
+        job.insert("statement", Value::String("daily report".to_string()));
+        assert_eq!(job.context().len(), 6);
+        assert_eq!(ctx.get_str("db.statement").unwrap(), "SELECT 1");
+        assert_eq!(ctx.get_str("job.statement").unwrap(), "daily report");
+
+        let mut db = NamespacedContext::new(&mut ctx, "db");
+        db.clear();
+        assert!(db.is_empty());
+        assert_eq!(ctx.keys().collect::<Vec<_>>(), vec!["dbx", "http.method", "job.statement", "request_id"]);
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn key(k: &str) -> Value {
+        Value::String(k.to_string())
+    }
+
+    #[test]
+    fn test_update_map() {
+        let mut ctx = Context::new();
+        ctx.update_map("http", |http| http.insert(key("method"), key("GET"))).unwrap();
+        let previous = ctx.update_map("http", |http| http.insert(key("status"), Value::U16(200))).unwrap();
+        assert!(previous.is_none());
+        assert_eq!(ctx.at("http").at("method").as_str(), Some("GET"));
+        assert_eq!(ctx.at("http").at("status").as_u64(), Some(200));
+    }
+
+    #[test]
+    fn test_update_map_errors() {
+        let mut ctx = Context::new();
+        ctx.insert("http".to_string(), Value::Bool(true));
+        assert_eq!(ctx.update_map("http", |_| ()).unwrap_err().code(), 400);
+
+        ctx.update_map("user", |user| user.insert(key("id"), Value::U8(1))).unwrap();
+        ctx.protect_key("user");
+        assert_eq!(ctx.update_map("user", |user| user.clear()).unwrap_err().code(), 409);
+        assert_eq!(ctx.at("user").at("id").as_u64(), Some(1));
+    }
+
+    #[test]
+    fn test_shared_update_map() {
+        let shared = SharedContext::default();
+        shared.update_map("http", |http| http.insert(key("status"), Value::U16(404))).unwrap();
+        assert_eq!(shared.snapshot().at("http").at("status").as_u64(), Some(404));
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut ctx = Context::new();
+        ctx.insert_path("request.headers.user_agent", key("curl")).unwrap();
+        ctx.insert_path("request.method", key("GET")).unwrap();
+        ctx.insert_path("env", key("prod")).unwrap();
+        ctx.insert("tags".to_string(), Value::Seq(vec![key("a"), Value::Option(Some(Box::new(key("b"))))]));
+
+        assert_eq!(ctx.get_path("request.headers.user_agent"), Some(&key("curl")));
+        assert_eq!(ctx.get_path("request.method"), Some(&key("GET")));
+        assert_eq!(ctx.get_path("env"), Some(&key("prod")));
+        assert_eq!(ctx.get_path("tags.1"), Some(&Value::Option(Some(Box::new(key("b"))))));
+        assert_eq!(ctx.get_path("tags.2"), None);
+        assert_eq!(ctx.get_path("request.missing.deeper"), None);
+        assert_eq!(ctx.get_path("env.nested"), None);
+    }
+
+    #[test]
+    fn test_insert_path_errors() {
+        let mut ctx = Context::new();
+        ctx.insert_path("request.id", Value::U8(1)).unwrap();
+        let err = ctx.insert_path("request.id.value", Value::U8(2)).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert_eq!(err.message(), "Context path 'request.id' does not hold a map");
+        assert_eq!(ctx.get_path("request.id"), Some(&Value::U8(1)));
+        assert_eq!(ctx.insert_path("request.id.value.deeper", Value::U8(2)).unwrap_err().code(), 400);
+
+        ctx.protect_key("request");
+        assert_eq!(ctx.insert_path("request.other", Value::U8(3)).unwrap_err().code(), 409);
+        assert_eq!(ctx.get_path("request.other"), None);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let mut ctx = Context::new();
+        ctx.insert_path("http.headers.user_agent", key("curl")).unwrap();
+        ctx.insert_path("http.status", Value::U16(200)).unwrap();
+        ctx.insert("tags".to_string(), Value::Seq(vec![key("a"), key("b")]));
+        ctx.insert("empty".to_string(), Value::Map(BTreeMap::new()));
+        ctx.insert("codes".to_string(), Value::Map(BTreeMap::from([(Value::U16(404), key("not found"))])));
+
+        let flat = ctx.flatten();
+        assert_eq!(
+            flat,
+            BTreeMap::from([
+                ("codes.404".to_string(), key("not found")),
+                ("empty".to_string(), Value::Map(BTreeMap::new())),
+                ("http.headers.user_agent".to_string(), key("curl")),
+                ("http.status".to_string(), Value::U16(200)),
+                ("tags.0".to_string(), key("a")),
+                ("tags.1".to_string(), key("b")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_shared_paths() {
+        let shared = SharedContext::default();
+        shared.insert_path("http.status", Value::U16(404)).unwrap();
+        assert_eq!(shared.get_path("http.status"), Some(Value::U16(404)));
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, Format, NumericPolicy};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([
+            ("id", Value::U64(u64::MAX)),
+            ("ratio", Value::F32(0.1)),
+            ("stats", Value::Map(BTreeMap::from([(s("mean"), Value::F64(f64::NAN))]))),
+        ])
+    }
+
+    #[test]
+    fn test_default_policy() {
+        assert_eq!(Context::new().numeric_policy(), NumericPolicy::new());
+        assert_eq!(context().fork().numeric_policy(), NumericPolicy::new());
+    }
+
+    #[test]
+    fn test_check_numbers() {
+        let mut ctx = context();
+        assert!(ctx.check_numbers(Format::Yaml).is_ok());
+        assert!(ctx.check_numbers(Format::Json).unwrap_err().message().contains("stats.mean"));
+        assert!(ctx.check_numbers(Format::Toml).unwrap_err().message().contains("'id'"));
+
+        ctx.set_numeric_policy(NumericPolicy::new().with_max_integer(10));
+        assert!(ctx.check_numbers(Format::Yaml).is_err());
+        assert_eq!(ctx.fork().numeric_policy(), ctx.numeric_policy());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        use cdumay_context::{FloatHandling, OnPrecisionLoss};
+
+        let mut ctx = context();
+        let err = ctx.to_json(false).unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err.class().contains("PrecisionLoss"));
+        assert!(err.message().contains("stats.mean"), "{}", err.message());
+
+        ctx.set_numeric_policy(NumericPolicy::new().on_precision_loss(OnPrecisionLoss::Stringify));
+        let json = ctx.to_json(false).unwrap();
+        assert_eq!(json, r#"{"id":18446744073709551615,"ratio":0.1,"stats":{"mean":"NaN"}}"#);
+        assert_eq!(Context::from_json(&json).unwrap().at("id").as_u64(), Some(u64::MAX));
+
+        ctx.set_numeric_policy(
+            NumericPolicy::new()
+                .with_max_integer(NumericPolicy::JAVASCRIPT_SAFE_INTEGER)
+                .with_float_handling(FloatHandling::Stringify)
+                .on_precision_loss(OnPrecisionLoss::Stringify),
+        );
+        assert_eq!(
+            ctx.to_json(false).unwrap(),
+            r#"{"id":"18446744073709551615","ratio":"0.1","stats":{"mean":"NaN"}}"#
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_negative_integers_and_sequences() {
+        let mut ctx = Context::new();
+        ctx.insert("offsets".to_string(), Value::Seq(vec![Value::I64(-5), Value::I64(i64::MIN)]));
+        ctx.set_numeric_policy(NumericPolicy::new().with_max_integer(1000));
+        let err = ctx.to_json(false).unwrap_err();
+        assert!(err.message().contains("offsets.1"), "{}", err.message());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        use cdumay_context::OnPrecisionLoss;
+
+        let mut ctx = Context::new();
+        ctx.insert("id".to_string(), Value::U64(u64::MAX));
+        ctx.insert("small".to_string(), Value::U64(42));
+        let err = ctx.to_toml(false).unwrap_err();
+        assert!(err.class().contains("PrecisionLoss"));
+
+        ctx.set_numeric_policy(NumericPolicy::new().on_precision_loss(OnPrecisionLoss::Stringify));
+        let toml = ctx.to_toml(false).unwrap();
+        let loaded = Context::from_toml(&toml).unwrap();
+        assert_eq!(loaded.at("id").as_str(), Some("18446744073709551615"));
+        assert_eq!(loaded.at("small").as_u64(), Some(42));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let mut ctx = context();
+        ctx.remove("ratio");
+        let yaml = ctx.to_yaml().unwrap();
+        let loaded = Context::from_yaml(&yaml).unwrap();
+        assert_eq!(loaded.at("id").as_u64(), Some(u64::MAX));
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+
+    fn ctx(pairs: &[(&str, u64)]) -> Context {
+        Context::from(pairs.iter().map(|(k, v)| (k.to_string(), Value::U64(*v))).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_add() {
+        let merged = ctx(&[("a", 1), ("b", 1)]) + ctx(&[("b", 2), ("c", 2)]);
+        assert_eq!(merged.inner(), ctx(&[("a", 1), ("b", 2), ("c", 2)]).inner());
+
+        let mut acc = ctx(&[("a", 1)]);
+        acc += &ctx(&[("a", 3)]);
+        assert_eq!(acc.get("a"), Some(&Value::U64(3)));
+    }
+
+    #[test]
+    fn test_bitor_keeps_existing() {
+        let merged = ctx(&[("a", 1), ("b", 1)]) | ctx(&[("b", 2), ("c", 2)]);
+        assert_eq!(merged.inner(), ctx(&[("a", 1), ("b", 1), ("c", 2)]).inner());
+
+        let mut acc = ctx(&[("a", 1)]);
+        acc |= ctx(&[("a", 3), ("d", 4)]);
+        assert_eq!(acc.inner(), ctx(&[("a", 1), ("d", 4)]).inner());
+    }
+
+    #[test]
+    fn test_lhs_protection_is_kept() {
+        let mut base = ctx(&[("request_id", 1)]);
+        base.protect_key("request_id");
+        let merged = base + ctx(&[("request_id", 2)]);
+        assert_eq!(merged.get("request_id"), Some(&Value::U64(1)));
+        assert!(merged.is_protected("request_id"));
+    }
+
+    #[test]
+    fn test_sum_and_collect() {
+        let contexts = vec![ctx(&[("a", 1)]), ctx(&[("a", 2), ("b", 2)]), ctx(&[("c", 3)])];
+        let summed: Context = contexts.iter().sum();
+        assert_eq!(summed.inner(), ctx(&[("a", 2), ("b", 2), ("c", 3)]).inner());
+
+        let collected: Context = contexts.into_iter().collect();
+        assert_eq!(collected.inner(), summed.inner());
+    }
+}
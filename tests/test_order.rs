@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, KeyOrder, KeyPolicy};
+    use serde_value::Value;
+
+    fn context() -> Context {
+        let mut ctx = Context::new();
+        for key in ["user", "error", "request_id", "attempt"] {
+            ctx.insert(key.to_string(), Value::String(key.to_string()));
+        }
+        ctx
+    }
+
+    fn keys(ctx: &Context) -> Vec<String> {
+        ctx.ordered_entries().into_iter().map(|(k, _)| k).collect()
+    }
+
+    #[cfg(not(feature = "ordered"))]
+    #[test]
+    fn test_alphabetical() {
+        let ctx = context();
+        assert_eq!(ctx.key_order(), &KeyOrder::Alphabetical);
+        assert_eq!(keys(&ctx), vec!["attempt", "error", "request_id", "user"]);
+    }
+
+    #[test]
+    fn test_insertion() {
+        let mut ctx = context();
+        ctx.set_key_order(KeyOrder::Insertion);
+        ctx.insert("user".to_string(), Value::U8(1));
+        assert_eq!(keys(&ctx), vec!["user", "error", "request_id", "attempt"]);
+        ctx.remove("user");
+        ctx.insert("user".to_string(), Value::U8(2));
+        assert_eq!(keys(&ctx), vec!["error", "request_id", "attempt", "user"]);
+    }
+
+    #[test]
+    fn test_priority() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.extend(context().inner());
+        ctx.set_key_order(KeyOrder::Priority(vec![
+            "Request_Id".to_string(),
+            "error".to_string(),
+            "missing".to_string(),
+        ]));
+        assert_eq!(keys(&ctx), vec!["request_id", "error", "attempt", "user"]);
+        assert_eq!(keys(&ctx.fork())[..2], ["request_id", "error"]);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_order() {
+        let mut ctx = context();
+        ctx.set_key_order(KeyOrder::Priority(vec!["request_id".to_string()]));
+        assert_eq!(
+            ctx.to_json(false).unwrap(),
+            r#"{"request_id":"request_id","attempt":"attempt","error":"error","user":"user"}"#
+        );
+        assert!(serde_json::to_string(&ctx).unwrap().starts_with(r#"{"data":{"request_id""#));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_yaml_order() {
+        let mut ctx = context();
+        ctx.set_key_order(KeyOrder::Insertion);
+        assert!(ctx.to_yaml().unwrap().starts_with("user: user\nerror: error\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_toml_order() {
+        let mut ctx = context();
+        ctx.set_key_order(KeyOrder::Priority(vec!["user".to_string()]));
+        assert!(ctx.to_toml(false).unwrap().starts_with("user = \"user\"\n"));
+    }
+}
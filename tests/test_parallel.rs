@@ -0,0 +1,41 @@
+#[cfg(test)]
+#[cfg(feature = "rayon")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+
+    fn data(n: u64) -> Vec<(String, Value)> {
+        (0..n).map(|i| (format!("key_{:04}", i), Value::U64(i))).collect()
+    }
+
+    #[test]
+    fn test_par_extend() {
+        let mut ctx = Context::new();
+        ctx.protect_key("key_0000");
+        ctx.insert("key_0000".to_string(), Value::Bool(true));
+        ctx.par_extend(data(1000));
+        assert_eq!(ctx.inner().len(), 1000);
+        assert_eq!(ctx.get("key_0999"), Some(&Value::U64(999)));
+        assert_eq!(ctx.get("key_0000"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn test_par_retain() {
+        let mut ctx = Context::new();
+        ctx.par_extend(data(100));
+        ctx.freeze_key("key_0001");
+        ctx.par_retain(|k, _| k.ends_with('0'));
+        assert_eq!(ctx.inner().len(), 11);
+        assert!(ctx.get("key_0001").is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_to_json_par() {
+        let mut ctx = Context::new();
+        ctx.par_extend(data(500));
+        ctx.insert("quoted \"key\"".to_string(), Value::String("a\nb".to_string()));
+        assert_eq!(ctx.to_json_par().unwrap(), ctx.to_json(false).unwrap());
+        assert_eq!(Context::new().to_json_par().unwrap(), "{}");
+    }
+}
@@ -0,0 +1,121 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([
+            ("state", s("running")),
+            ("worker", Value::U8(1)),
+            ("tags", Value::Seq(vec![s("slow")])),
+            ("http", Value::Map(BTreeMap::from([(s("method"), s("GET"))]))),
+        ])
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let mut ctx = context();
+        ctx.apply_json_patch(
+            r#"[
+                {"op": "test", "path": "/state", "value": "running"},
+                {"op": "replace", "path": "/state", "value": "done"},
+                {"op": "add", "path": "/tags/-", "value": "retried"},
+                {"op": "add", "path": "/tags/0", "value": "first"},
+                {"op": "add", "path": "/http/status", "value": 200},
+                {"op": "copy", "from": "/http/method", "path": "/a~1b"},
+                {"op": "move", "from": "/a~1b", "path": "/method"},
+                {"op": "remove", "path": "/worker"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(ctx.get("state"), Some(&Value::String("done".to_string())));
+        assert_eq!(ctx.get("worker"), None);
+        assert_eq!(ctx.get("method"), Some(&Value::String("GET".to_string())));
+        assert_eq!(ctx.get("a/b"), None);
+        assert_eq!(ctx.get_path("http.status"), Some(&Value::U64(200)));
+        assert_eq!(
+            ctx.get("tags"),
+            Some(&Value::Seq(vec![
+                Value::String("first".to_string()),
+                Value::String("slow".to_string()),
+                Value::String("retried".to_string())
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_json_patch_errors() {
+        let mut ctx = context();
+        let patches = [
+            (r#"{"op": "remove", "path": "/state"}"#, 400),
+            (r#"[{"op": "rename", "path": "/state"}]"#, 400),
+            (r#"[{"op": "add", "path": "state", "value": 1}]"#, 400),
+            (r#"[{"op": "add", "path": "/tags/01", "value": 1}]"#, 400),
+            (r#"[{"op": "add", "path": "", "value": []}]"#, 400),
+            (r#"[{"op": "remove", "path": "/missing"}]"#, 404),
+            (r#"[{"op": "add", "path": "/tags/5", "value": 1}]"#, 404),
+            (
+                r#"[{"op": "remove", "path": "/state"}, {"op": "test", "path": "/worker", "value": 2}]"#,
+                409,
+            ),
+        ];
+        for (patch, code) in patches {
+            let err = ctx.apply_json_patch(patch).unwrap_err();
+            assert_eq!(err.code(), code, "{}", patch);
+        }
+        assert_eq!(ctx, context());
+
+        ctx.protect_key("worker");
+        let err = ctx
+            .apply_json_patch(r#"[{"op": "remove", "path": "/state"}, {"op": "remove", "path": "/worker"}]"#)
+            .unwrap_err();
+        assert_eq!(err.code(), 409);
+        assert_eq!(ctx, context());
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut ctx = context();
+        ctx.apply_merge_patch(r#"{"state": "done", "worker": null, "http": {"status": 200}, "tags": ["retried"]}"#)
+            .unwrap();
+        assert_eq!(ctx.get("state"), Some(&Value::String("done".to_string())));
+        assert_eq!(ctx.get("worker"), None);
+        assert_eq!(ctx.get_path("http.method"), Some(&Value::String("GET".to_string())));
+        assert_eq!(ctx.get_path("http.status"), Some(&Value::U64(200)));
+        assert_eq!(ctx.get("tags"), Some(&Value::Seq(vec![Value::String("retried".to_string())])));
+
+        assert_eq!(ctx.apply_merge_patch("[]").unwrap_err().code(), 400);
+        ctx.freeze_key("state");
+        assert_eq!(ctx.apply_merge_patch(r#"{"state": null}"#).unwrap_err().code(), 409);
+    }
+
+    #[test]
+    fn test_create_patch() {
+        let before = context();
+        let mut after = context();
+        after
+            .apply_merge_patch(r#"{"state": "done", "worker": null, "http": {"status": 200}, "a/b": true}"#)
+            .unwrap();
+
+        let patch = before.create_patch(&after).unwrap();
+        let operations: serde_json::Value = serde_json::from_str(&patch).unwrap();
+        assert_eq!(
+            operations,
+            serde_json::json!([
+                {"op": "remove", "path": "/worker"},
+                {"op": "add", "path": "/a~1b", "value": true},
+                {"op": "add", "path": "/http/status", "value": 200},
+                {"op": "replace", "path": "/state", "value": "done"},
+            ])
+        );
+        let mut mirror = context();
+        mirror.apply_json_patch(&patch).unwrap();
+        assert_eq!(mirror, after);
+        assert_eq!(after.create_patch(&after).unwrap(), "[]");
+    }
+}
@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+
+    #[test]
+    fn test_phases_timeline() {
+        let mut ctx = Context::new();
+        assert!(ctx.current_phase().is_none());
+        assert!(ctx.exit_phase().is_none());
+
+        ctx.enter_phase("download");
+        assert_eq!(ctx.current_phase(), Some("download"));
+        ctx.enter_phase("extract");
+        assert_eq!(ctx.current_phase(), Some("extract"));
+        assert!(ctx.exit_phase().is_some());
+        assert!(ctx.current_phase().is_none());
+        assert!(ctx.exit_phase().is_none());
+
+        let phases = ctx.at("phases");
+        assert_eq!(phases.as_seq().unwrap().len(), 2);
+        assert_eq!(phases.nth(0).at("name").as_str(), Some("download"));
+        assert_eq!(phases.nth(1).at("name").as_str(), Some("extract"));
+        for i in 0..2 {
+            assert!(phases.nth(i).at("start").as_u64().is_some());
+            assert!(phases.nth(i).at("duration").as_u64().is_some());
+        }
+    }
+
+    #[test]
+    fn test_failed_phase_stays_open() {
+        let mut ctx = Context::new();
+        ctx.enter_phase("upload");
+        assert!(ctx.at("phases").nth(0).at("duration").is_missing());
+        assert_eq!(ctx.current_phase(), Some("upload"));
+    }
+}
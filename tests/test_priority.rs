@@ -0,0 +1,73 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, KeyOrder, KeyPolicy, Priority, PROPAGATION_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn encoded_len(dump: &BTreeMap<String, Value>) -> usize {
+        format!("{:?}", dump).len()
+    }
+
+    fn context() -> Context {
+        context_of([
+            ("attempt", Value::U8(2)),
+            ("request_id", s("abc")),
+            ("trace", Value::String("x".repeat(500))),
+            ("user", s("jane")),
+        ])
+    }
+
+    fn keys(ctx: &Context) -> Vec<String> {
+        ctx.ordered_entries().into_iter().map(|(k, _)| k).collect()
+    }
+
+    #[test]
+    fn test_default_priority() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        assert_eq!(ctx.priority("user"), Priority::Normal);
+        ctx.set_priority("User", Priority::High);
+        assert_eq!(ctx.priority("USER"), Priority::High);
+        assert_eq!(ctx.fork().priority("user"), Priority::High);
+    }
+
+    #[test]
+    fn test_priorities_order_keys() {
+        let mut ctx = context();
+        assert_eq!(keys(&ctx), vec!["attempt", "request_id", "trace", "user"]);
+
+        ctx.set_priority("user", Priority::High);
+        ctx.set_priority("attempt", Priority::Low);
+        assert_eq!(keys(&ctx), vec!["user", "request_id", "trace", "attempt"]);
+
+        ctx.set_key_order(KeyOrder::Priority(vec!["trace".to_string()]));
+        assert_eq!(keys(&ctx), vec!["user", "trace", "request_id", "attempt"]);
+    }
+
+    #[test]
+    fn test_trimming_drops_low_priority_first() {
+        let mut ctx = context();
+        ctx.set_priority("trace", Priority::Low);
+        let dump = ctx.dump_within(200, encoded_len);
+        assert!(!dump.contains_key("trace"));
+        assert!(dump.contains_key("user"));
+        assert!(dump.contains_key("attempt"));
+    }
+
+    #[test]
+    fn test_critical_keys_are_never_dropped() {
+        let mut ctx = context();
+        ctx.set_priority("request_id", Priority::Critical);
+        ctx.set_priority("user", Priority::High);
+        let dump = ctx.dump_within(0, encoded_len);
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec![PROPAGATION_KEY, "request_id"]);
+
+        let ctx = Context::from(dump);
+        let dropped: Vec<&str> = (0..3)
+            .filter_map(|index| ctx.at(PROPAGATION_KEY).at("dropped").nth(index).as_str())
+            .collect();
+        assert_eq!(dropped, vec!["user", "attempt", "trace"]);
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, ProblemDetails, Sensitivity, Timeout};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_from_error() {
+        let err: cdumay_core::Error = Timeout::new().with_message("Upstream did not answer".to_string()).into();
+        let problem = ProblemDetails::from(&err).with_instance("/orders/42");
+        assert_eq!(problem.problem_type, "about:blank");
+        assert_eq!(problem.title, err.class());
+        assert_eq!(problem.status, 504);
+        assert_eq!(problem.detail.as_deref(), Some("Upstream did not answer"));
+        assert_eq!(problem.instance.as_deref(), Some("/orders/42"));
+        assert!(problem.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_status() {
+        let err = cdumay_core::Error::new(1042, "Legacy".to_string(), String::new(), BTreeMap::new());
+        let problem = ProblemDetails::from(err);
+        assert_eq!(problem.status, 500);
+        assert!(problem.detail.is_none());
+    }
+
+    #[test]
+    fn test_with_context() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx.insert("status".to_string(), Value::U16(200));
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("request_id", Sensitivity::Public);
+        ctx.set_sensitivity("status", Sensitivity::Public);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+
+        let err: cdumay_core::Error = Timeout::new().into();
+        let problem = ProblemDetails::from(&err).with_context(&ctx);
+        assert_eq!(problem.status, 504);
+        assert_eq!(problem.extensions.keys().collect::<Vec<_>>(), vec!["request_id"]);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_to_json() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), Value::String("abc".to_string()));
+        ctx.set_sensitivity("request_id", Sensitivity::Public);
+        let err: cdumay_core::Error = Timeout::new().into();
+        let json: serde_json::Value = serde_json::from_str(&ProblemDetails::from(&err).with_context(&ctx).to_json(false).unwrap()).unwrap();
+        assert_eq!(json["type"], "about:blank");
+        assert_eq!(json["status"], 504);
+        assert_eq!(json["request_id"], "abc");
+    }
+}
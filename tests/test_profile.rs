@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextDump, Contextualize, ProfiledContext, PROFILE_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    fn profiled() -> ProfiledContext {
+        let mut base = Context::new();
+        base.insert("service".to_string(), string("billing"));
+        base.insert("db_host".to_string(), string("localhost"));
+        ProfiledContext::new(base)
+            .overlay("staging", BTreeMap::from([("db_host".to_string(), string("db.staging"))]))
+            .overlay(
+                "prod",
+                BTreeMap::from([("db_host".to_string(), string("db.prod")), ("replicas".to_string(), Value::U8(3))]),
+            )
+    }
+
+    #[test]
+    fn test_no_profile_selected() {
+        let profiled = profiled();
+        assert_eq!(profiled.active_profile(), None);
+        assert_eq!(profiled.profiles().collect::<Vec<_>>(), vec!["prod", "staging"]);
+        assert_eq!(profiled.get("db_host"), Some(&string("localhost")));
+        assert_eq!(profiled.dump(), profiled.base().inner());
+    }
+
+    #[test]
+    fn test_switch_profiles() {
+        let mut profiled = profiled().select("prod").unwrap();
+        assert_eq!(profiled.active_profile(), Some("prod"));
+        assert_eq!(profiled.get("db_host"), Some(&string("db.prod")));
+        assert_eq!(profiled.get("replicas"), Some(&Value::U8(3)));
+        assert_eq!(profiled.get("service"), Some(&string("billing")));
+
+        profiled.switch("staging").unwrap();
+        let dump = profiled.dump();
+        assert_eq!(dump.get("db_host"), Some(&string("db.staging")));
+        assert_eq!(dump.get("replicas"), None);
+        assert_eq!(dump.get(PROFILE_KEY), Some(&string("staging")));
+
+        profiled.deselect();
+        assert_eq!(profiled.dump().get(PROFILE_KEY), None);
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let mut profiled = profiled().select("staging").unwrap();
+        let err = profiled.switch("qa").unwrap_err();
+        assert_eq!(err.code(), 404);
+        assert_eq!(profiled.active_profile(), Some("staging"));
+        assert!(profiled.select("qa").is_err());
+    }
+
+    #[test]
+    fn test_protected_keys_are_not_overridden() {
+        let mut profiled = profiled();
+        profiled.base_mut().protect_key("db_host");
+        let profiled = profiled.select("prod").unwrap();
+        assert_eq!(profiled.get("db_host"), Some(&string("localhost")));
+        assert_eq!(profiled.context().get("db_host"), Some(&string("localhost")));
+        assert_eq!(profiled.context().get("replicas"), Some(&Value::U8(3)));
+    }
+}
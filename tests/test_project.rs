@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, KeyPolicy};
+    use serde::Deserialize;
+    use serde_value::Value;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Mode {
+        Fast,
+        Safe { retries: u8 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Http {
+        status: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Handler {
+        user_id: u64,
+        tenant: String,
+        mode: Mode,
+        http: Http,
+        tags: Vec<String>,
+        dry_run: Option<bool>,
+    }
+
+    #[test]
+    fn test_project() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.insert("User_Id".to_string(), Value::U64(42));
+        ctx.insert("tenant".to_string(), s("acme"));
+        ctx.insert("mode".to_string(), s("Fast"));
+        ctx.insert("http".to_string(), Value::Map([(s("status"), Value::U16(200))].into()));
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a")]));
+        ctx.insert("unrelated".to_string(), Value::Bool(true));
+        let handler: Handler = ctx.project().unwrap();
+        assert_eq!(handler.user_id, 42);
+        assert_eq!(handler.mode, Mode::Fast);
+        assert_eq!(handler.http, Http { status: 200 });
+        assert_eq!(handler.dry_run, None);
+    }
+
+    #[test]
+    fn test_project_reports_every_field() {
+        let mut ctx = Context::new();
+        ctx.insert("user_id".to_string(), s("forty-two"));
+        ctx.insert("http".to_string(), Value::Map([(s("status"), s("ok"))].into()));
+        ctx.insert("dry_run".to_string(), Value::U8(1));
+        let err = ctx.project::<Handler>().unwrap_err();
+        assert_eq!(err.code(), 400);
+
+        let details = err.details();
+        assert_eq!(details["missing_fields"], Value::Seq(vec![s("tenant"), s("mode"), s("tags")]));
+        let invalid: Vec<&Value> = match &details["invalid_fields"] {
+            Value::Map(map) => map.keys().collect(),
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(invalid, vec![&s("dry_run"), &s("http"), &s("user_id")]);
+        assert_eq!(details["user_id"], s("forty-two"));
+        assert!(err.message().contains("missing fields: tenant, mode, tags"));
+    }
+
+    #[test]
+    fn test_project_map() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), Value::U8(1));
+        let map: std::collections::BTreeMap<String, u8> = ctx.project().unwrap();
+        assert_eq!(map["a"], 1);
+        ctx.insert("b".to_string(), s("x"));
+        assert!(ctx.project::<std::collections::BTreeMap<String, u8>>().is_err());
+    }
+
+    #[test]
+    fn test_project_into_core_error() {
+        fn handler(ctx: &Context) -> cdumay_core::Result<Http> {
+            Ok(ctx.project::<Http>()?)
+        }
+        assert_eq!(handler(&Context::new()).unwrap_err().code(), 400);
+        let _ = Mode::Safe { retries: 0 };
+    }
+}
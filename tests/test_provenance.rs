@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, PROPAGATION_HOPS_LIMIT, PROPAGATION_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_record_hop() {
+        let mut ctx = Context::new();
+        assert!(ctx.hops().is_empty());
+        ctx.record_hop("gateway", "headers");
+        ctx.record_hop("orders", "baggage");
+        let hops = ctx.hops();
+        assert_eq!(hops.iter().map(|hop| hop.service.as_str()).collect::<Vec<_>>(), vec!["gateway", "orders"]);
+        assert_eq!(hops[1].transport, "baggage");
+        assert!(hops[0].at > 0 && hops[0].at <= hops[1].at);
+    }
+
+    #[test]
+    fn test_hops_are_capped() {
+        let mut ctx = Context::new();
+        for index in 0..PROPAGATION_HOPS_LIMIT + 3 {
+            ctx.record_hop(&format!("service-{}", index), "kafka");
+        }
+        let hops = ctx.hops();
+        assert_eq!(hops.len(), PROPAGATION_HOPS_LIMIT);
+        assert_eq!(hops[0].service, "service-3");
+    }
+
+    #[test]
+    fn test_other_propagation_entries_are_kept() {
+        let mut ctx = Context::new();
+        let propagation = BTreeMap::from([(Value::String("origin".to_string()), Value::String("edge".to_string()))]);
+        ctx.insert(PROPAGATION_KEY.to_string(), Value::Map(propagation));
+        ctx.record_hop("gateway", "headers");
+        assert_eq!(ctx.at(PROPAGATION_KEY).at("origin").as_str(), Some("edge"));
+        assert_eq!(ctx.hops().len(), 1);
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+#[cfg(feature = "system")]
+mod tests {
+    use cdumay_context::{BuildInfo, BuildInfoProvider, Context, ContextProvider, Contextualize, ProcessProvider, SystemProvider};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    struct Static(&'static str, &'static str);
+
+    impl ContextProvider for Static {
+        fn provide(&self) -> BTreeMap<String, Value> {
+            BTreeMap::from([(self.0.to_string(), Value::String(self.1.to_string()))])
+        }
+    }
+
+    #[test]
+    fn test_builtin_providers() {
+        let process = ProcessProvider::new().with_version("1.4.2");
+        let build = BuildInfoProvider::from(BuildInfo {
+            profile: Some("release".to_string()),
+            ..Default::default()
+        });
+        let ctx = Context::new().with_providers(&[&SystemProvider, &process, &build]);
+
+        assert_eq!(ctx.at("system").at("arch").as_str(), Some(std::env::consts::ARCH));
+        assert_eq!(ctx.at("process").at("pid").as_u64(), Some(u64::from(std::process::id())));
+        assert_eq!(ctx.at("process").at("version").as_str(), Some("1.4.2"));
+        assert!(ctx.at("process").at("uptime_secs").as_u64().is_some());
+        assert_eq!(ctx.at("build").at("profile").as_str(), Some("release"));
+        assert!(BuildInfoProvider::default().provide().is_empty());
+    }
+
+    #[test]
+    fn test_providers_order_and_protection() {
+        let mut ctx = Context::new();
+        ctx.insert("region".to_string(), Value::String("eu".to_string()));
+        ctx.protect_key("region");
+
+        let ctx = ctx.with_providers(&[&Static("env", "staging"), &Static("env", "prod"), &Static("region", "us")]);
+        assert_eq!(ctx.get_str("env").unwrap(), "prod");
+        assert_eq!(ctx.get_str("region").unwrap(), "eu");
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+
+    cdumay_context::define_context_errors! {
+        billing {
+            PaymentRequired = PaymentRequiredError(402, "Payment required"),
+            InvoiceNotFound = InvoiceNotFoundError(404, "Invoice not found"),
+        }
+    }
+
+    #[test]
+    fn test_registered_kinds() {
+        assert_eq!(billing::PaymentRequiredError.code(), 402);
+        assert_eq!(billing::InvoiceNotFoundError.code(), 404);
+
+        let err: cdumay_core::Error = billing::PaymentRequired::new().with_message("Card expired".into()).into();
+        assert_eq!(err.code(), 402);
+        assert_eq!(err.message(), "Card expired");
+    }
+
+    #[test]
+    fn test_registered_with_context() {
+        let mut ctx = Context::new();
+        ctx.insert("invoice".to_string(), Value::U64(42));
+        ctx.insert("user".to_string(), Value::String("alice".into()));
+
+        let err = billing::InvoiceNotFound::new()
+            .with_details(std::collections::BTreeMap::from([("user".to_string(), Value::String("bob".into()))]))
+            .with_context(&ctx);
+        let details = err.details();
+        assert_eq!(details.get("invoice"), Some(&Value::U64(42)));
+        assert_eq!(details.get("user"), Some(&Value::String("bob".into())));
+    }
+
+    #[test]
+    fn test_has_duplicate_codes() {
+        assert!(!cdumay_context::has_duplicate_codes(&[400, 404, 409]));
+        assert!(cdumay_context::has_duplicate_codes(&[400, 404, 400]));
+    }
+}
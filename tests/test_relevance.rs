@@ -0,0 +1,57 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, KeyNotFound, NotFoundError, RelevanceRules, Timeout, TimeoutError};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        context_of([
+            ("net", Value::Map(BTreeMap::from([(s("peer"), Value::U16(443))]))),
+            ("network", Value::Bool(true)),
+            ("tls.version", s("1.3")),
+            ("request_id", s("abc")),
+            ("user", s("jane")),
+        ])
+    }
+
+    #[test]
+    fn test_select() {
+        let rules = RelevanceRules::new()
+            .with_rule(TimeoutError, &["net.*"])
+            .with_rule(TimeoutError, &["tls.*"])
+            .with_common(&["request_id"]);
+        assert_eq!(rules.patterns(TimeoutError).unwrap().len(), 2);
+        assert_eq!(rules.patterns(NotFoundError), None);
+        assert!(!rules.is_relevant(TimeoutError, "network"));
+        assert!(rules.is_relevant(NotFoundError, "network"));
+
+        let err = Timeout::new().with_relevant_context_by(&context(), &rules);
+        assert_eq!(err.details().keys().collect::<Vec<_>>(), vec!["net", "request_id", "tls.version"]);
+        let err = KeyNotFound::new().with_relevant_context_by(&context(), &rules);
+        assert_eq!(err.details().len(), 5);
+
+        let rules = rules.with_fallback(&["user"]);
+        let err = KeyNotFound::new().with_relevant_context_by(&context(), &rules);
+        assert_eq!(err.details().keys().collect::<Vec<_>>(), vec!["request_id", "user"]);
+    }
+
+    #[test]
+    fn test_details_take_precedence() {
+        let rules = RelevanceRules::new().with_rule(TimeoutError, &["user"]);
+        let details = BTreeMap::from([("user".to_string(), Value::String("john".to_string()))]);
+        let err = Timeout::new().with_details(details).with_relevant_context_by(&context(), &rules);
+        assert_eq!(err.details()["user"], Value::String("john".to_string()));
+    }
+
+    #[test]
+    fn test_global_rules() {
+        assert_eq!(RelevanceRules::global(), RelevanceRules::new());
+        RelevanceRules::set_global(RelevanceRules::new().with_rule(TimeoutError, &["tls.*"]));
+        let err: cdumay_core::Error = Timeout::new().with_relevant_context(&context()).into();
+        assert_eq!(err.details().keys().collect::<Vec<_>>(), vec!["tls.version"]);
+        RelevanceRules::set_global(RelevanceRules::new());
+    }
+}
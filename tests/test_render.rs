@@ -0,0 +1,81 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{BoundedLruContext, Context, Contextualize, KeyOrder};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn sample() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("attempt".to_string(), Value::U8(2));
+        ctx.insert("error".to_string(), Value::Unit);
+        ctx.insert(
+            "http".to_string(),
+            Value::Map(BTreeMap::from([
+                (s("method"), s("GET")),
+                (s("headers"), Value::Map(BTreeMap::from([(s("accept"), s("*/*"))]))),
+            ])),
+        );
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a"), s("b,c")]));
+        ctx.insert("user".to_string(), s("Jane Doe"));
+        ctx
+    }
+
+    #[test]
+    fn test_logfmt() {
+        let ctx = sample();
+        assert_eq!(
+            ctx.to_string(),
+            r#"attempt=2, error=null, http={headers={accept=*/*}, method=GET}, tags=[a, "b,c"], user="Jane Doe""#
+        );
+        assert_eq!(format!("{}", Context::new()), "");
+
+        let mut ctx = Context::new();
+        ctx.set_key_order(KeyOrder::Insertion);
+        ctx.insert("z".to_string(), s(""));
+        ctx.insert("a".to_string(), s("x=\"1\""));
+        assert_eq!(ctx.to_logfmt(), r#"z="", a="x=\"1\"""#);
+    }
+
+    #[test]
+    fn test_table() {
+        let expected = "\
+attempt     2
+error       null
+http
+  headers
+    accept  */*
+  method    GET
+tags
+  [0]       a
+  [1]       b,c
+user        Jane Doe
+";
+        assert_eq!(sample().to_table(), expected);
+        assert_eq!(Context::new().to_table(), "");
+    }
+
+    #[test]
+    fn test_default_methods() {
+        let mut ctx = BoundedLruContext::new();
+        ctx.insert("empty".to_string(), Value::Map(BTreeMap::new()));
+        ctx.insert("id".to_string(), Value::U64(7));
+        assert_eq!(ctx.to_logfmt(), "empty={}, id=7");
+        assert_eq!(ctx.to_table(), "empty  {}\nid     7\n");
+    }
+
+    #[cfg(feature = "color")]
+    #[test]
+    fn test_table_colored() {
+        let mut ctx = Context::new();
+        ctx.insert("id".to_string(), Value::U64(7));
+        ctx.insert("name".to_string(), s("job"));
+        ctx.insert("none".to_string(), Value::Unit);
+        assert_eq!(
+            ctx.to_table_colored(),
+            "\x1b[1mid\x1b[0m    \x1b[33m7\x1b[0m\n\x1b[1mname\x1b[0m  job\n\x1b[1mnone\x1b[0m  \x1b[2mnull\x1b[0m\n"
+        );
+    }
+}
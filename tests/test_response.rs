@@ -0,0 +1,49 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, ErrorResponse, ResponseFormat, Sensitivity, Unauthorized};
+
+    fn context() -> Context {
+        let mut ctx = context_of([("request_id", s("abc")), ("db_host", s("10.0.0.12")), ("token", s("s3cr3t"))]);
+        ctx.set_sensitivity("request_id", Sensitivity::Public);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx
+    }
+
+    fn error(ctx: &Context) -> cdumay_core::Error {
+        Unauthorized::new().with_context(ctx).into()
+    }
+
+    #[test]
+    fn test_json_api_public() {
+        let ctx = context();
+        let response = ErrorResponse::new(&error(&ctx), &ctx, ResponseFormat::JsonApi, Sensitivity::Public).unwrap();
+        assert_eq!(response.status, 401);
+        assert_eq!(response.content_type, "application/vnd.api+json");
+        let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(json["errors"][0]["meta"], serde_json::json!({"request_id": "abc"}));
+    }
+
+    #[test]
+    fn test_plain_json_internal() {
+        let ctx = context();
+        let response = ErrorResponse::new(&error(&ctx), &ctx, ResponseFormat::PlainJson, Sensitivity::Internal).unwrap();
+        assert_eq!(response.content_type, "application/json");
+        let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(json["code"], 401);
+        assert_eq!(json["details"], serde_json::json!({"request_id": "abc", "db_host": "10.0.0.12"}));
+    }
+
+    #[test]
+    fn test_problem_details_internal() {
+        let ctx = context();
+        let response = ErrorResponse::new(&error(&ctx), &ctx, ResponseFormat::default(), Sensitivity::Internal).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(json["status"], 401);
+        assert_eq!(json["db_host"], "10.0.0.12");
+        assert!(json.get("token").is_none());
+    }
+}
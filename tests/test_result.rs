@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextResultExt, Contextualize, Timeout};
+    use serde_value::Value;
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_ctx() {
+        let mut ctx = Context::new();
+        ctx.insert("host".to_string(), Value::String("db-1".to_string()));
+        ctx.insert("step".to_string(), Value::String("connect".to_string()));
+
+        let result: Result<(), Timeout> = Err(Timeout::new().with_details([("step".to_string(), Value::String("query".to_string()))].into()));
+        let err = result.ctx(&ctx).unwrap_err();
+        assert_eq!(err.code(), 504);
+        assert_eq!(err.details().get("host"), Some(&Value::String("db-1".to_string())));
+        assert_eq!(err.details().get("step"), Some(&Value::String("query".to_string())));
+
+        assert_eq!(Ok::<u8, Timeout>(1).ctx(&ctx).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_with_ctx_is_lazy() {
+        let calls = Cell::new(0);
+        let details = || {
+            calls.set(calls.get() + 1);
+            BTreeMap::from([("attempt".to_string(), Value::U8(3))])
+        };
+        assert!(Ok::<(), cdumay_core::Error>(()).with_ctx(details).is_ok());
+        assert_eq!(calls.get(), 0);
+
+        let err = Err::<(), cdumay_core::Error>(Timeout::new().into()).with_ctx(details).unwrap_err();
+        assert_eq!(calls.get(), 1);
+        assert_eq!(err.details().get("attempt"), Some(&Value::U8(3)));
+        assert!(err.class().contains("Timeout"));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, ATTEMPT_HISTORY_LIMIT};
+
+    #[test]
+    fn test_record_attempt() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.attempt(), 0);
+        assert_eq!(ctx.record_attempt("timeout"), 1);
+        assert_eq!(ctx.record_attempt("connection refused"), 2);
+        assert_eq!(ctx.attempt(), 2);
+
+        let history = ctx.at("attempt_history");
+        assert_eq!(history.nth(0).at("error").as_str(), Some("timeout"));
+        assert_eq!(history.nth(1).at("attempt").as_u64(), Some(2));
+        assert!(history.nth(1).at("at").as_u64().is_some());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut ctx = Context::new();
+        for i in 0..ATTEMPT_HISTORY_LIMIT + 5 {
+            ctx.record_attempt(format!("error {}", i));
+        }
+        let history = ctx.at("attempt_history");
+        assert_eq!(history.as_seq().unwrap().len(), ATTEMPT_HISTORY_LIMIT);
+        assert_eq!(history.nth(0).at("attempt").as_u64(), Some(6));
+        assert_eq!(ctx.attempt(), (ATTEMPT_HISTORY_LIMIT + 5) as u64);
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let mut ctx = Context::new();
+        assert!(ctx.has_attempts_left());
+        assert!(ctx.max_attempts().is_none());
+        ctx.set_max_attempts(2);
+        ctx.record_attempt("first");
+        assert!(ctx.has_attempts_left());
+        ctx.record_attempt("second");
+        assert!(!ctx.has_attempts_left());
+        assert_eq!(ctx.max_attempts(), Some(2));
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "ron")]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::testing::FailingContext;
+    use cdumay_context::{BoundedLruContext, Context, Contextualize, KeyOrder};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut ctx = Context::new();
+        ctx.insert("http".to_string(), Value::Map(BTreeMap::from([(s("method"), s("GET"))])));
+        ctx.insert("retry".to_string(), Value::Option(None));
+        ctx.insert("status".to_string(), Value::U16(404));
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a"), Value::Char('b')]));
+        ctx.insert("user".to_string(), s("Jane"));
+
+        let ron = ctx.to_ron(false).unwrap();
+        assert_eq!(
+            ron,
+            r#"{"http":{"method":"GET"},"retry":None,"status":404,"tags":["a",'b'],"user":"Jane"}"#
+        );
+        assert_eq!(Context::from_ron(&ron).unwrap().inner(), ctx.inner());
+
+        let pretty = ctx.to_ron(true).unwrap();
+        assert!(pretty.contains("\n    \"status\": 404,\n"));
+        assert_eq!(Context::from_ron(&pretty).unwrap().inner(), ctx.inner());
+    }
+
+    #[test]
+    fn test_from_ron_struct() {
+        let ctx = Context::from_ron("Config(level: 3, name: \"api\", limits: (max: Some(10)))").unwrap();
+        assert_eq!(ctx.get("level"), Some(&Value::U8(3)));
+        assert_eq!(ctx.get("name"), Some(&s("api")));
+        assert_eq!(
+            ctx.get("limits"),
+            Some(&Value::Map(BTreeMap::from([(s("max"), Value::Option(Some(Box::new(Value::U8(10)))))])))
+        );
+        assert_eq!(BoundedLruContext::from_ron("{}").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_from_ron_errors() {
+        let err = Context::from_ron("{\n  \"a\": }").unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert_eq!(err.message(), "Failed to load context");
+        assert_eq!(err.details().get("line"), Some(&Value::U64(2)));
+        assert!(err.details().contains_key("origin"));
+
+        assert_eq!(Context::from_ron("[1, 2]").unwrap_err().code(), 400);
+        assert_eq!(Context::from_ron("{1: 2}").unwrap_err().code(), 400);
+    }
+
+    #[test]
+    fn test_to_ron_key_order() {
+        let mut ctx = Context::new();
+        ctx.set_key_order(KeyOrder::Insertion);
+        ctx.insert("z".to_string(), Value::U8(1));
+        ctx.insert("a".to_string(), Value::U8(2));
+        assert_eq!(ctx.to_ron(false).unwrap(), r#"{"z":1,"a":2}"#);
+    }
+
+    #[test]
+    fn test_to_ron_failing() {
+        let ctx = FailingContext::new();
+        assert_eq!(ctx.to_ron(false).unwrap_err().message(), "Injected serialization failure");
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, RouteRules, DEFAULT_ROUTE_TARGET};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_route_target_by() {
+        let rules = RouteRules::new()
+            .with_rule("payments.errors", &[("domain", "payments"), ("error.kind", "*")])
+            .with_rule("slow", &[("http.status", "5*"), ("latency_ms", "*")])
+            .with_rule("payments", &[("domain", "pay*")]);
+        let mut ctx = Context::new();
+        assert_eq!(ctx.route_target_by(&rules), DEFAULT_ROUTE_TARGET);
+        assert_eq!(ctx.route_target_by(&rules.clone().with_default("app")), "app");
+
+        ctx.insert("domain".to_string(), s("payroll"));
+        assert_eq!(ctx.route_target_by(&rules), "payments");
+        ctx.insert("http.status".to_string(), Value::U16(503));
+        ctx.insert("latency_ms".to_string(), Value::U64(1200));
+        assert_eq!(ctx.route_target_by(&rules), "slow");
+
+        ctx.insert("domain".to_string(), s("payments"));
+        ctx.insert("error".to_string(), Value::Map(BTreeMap::from([(s("kind"), s("NotFound"))])));
+        assert_eq!(ctx.route_target_by(&rules), "payments.errors");
+        assert_eq!(ctx.route_target_by(&RouteRules::new().with_rule("all", &[])), "all");
+    }
+
+    #[test]
+    fn test_global_rules() {
+        let mut ctx = Context::new();
+        ctx.insert("domain".to_string(), s("payments"));
+        assert_eq!(RouteRules::global(), RouteRules::new());
+        assert_eq!(ctx.route_target(), DEFAULT_ROUTE_TARGET);
+        RouteRules::set_global(RouteRules::new().with_rule("payments", &[("domain", "payments")]));
+        assert_eq!(ctx.route_target(), "payments");
+        RouteRules::set_global(RouteRules::new());
+    }
+}
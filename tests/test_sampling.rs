@@ -0,0 +1,59 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_sampling_fingerprint() {
+        let mut ctx = Context::new();
+        ctx.insert("tenant".to_string(), s("acme"));
+        assert_eq!(ctx.sampling_fingerprint(&["tenant", "error.kind"]), 0xbb29c88e57b4b174);
+        assert_ne!(ctx.sampling_fingerprint(&["tenant"]), ctx.sampling_fingerprint(&["tenant", "error.kind"]));
+        assert_ne!(
+            ctx.sampling_fingerprint(&["tenant", "route"]),
+            ctx.sampling_fingerprint(&["route", "tenant"])
+        );
+
+        let mut other = Context::new();
+        other.insert("tenant".to_string(), s("acme"));
+        other.insert("error".to_string(), Value::Map(BTreeMap::from([(s("kind"), s("NotFound"))])));
+        assert_eq!(ctx.sampling_fingerprint(&["tenant"]), other.sampling_fingerprint(&["tenant"]));
+        assert_ne!(ctx.sampling_fingerprint(&["error.kind"]), other.sampling_fingerprint(&["error.kind"]));
+        ctx.insert("error.kind".to_string(), s("NotFound"));
+        assert_eq!(
+            ctx.sampling_fingerprint(&["tenant", "error.kind"]),
+            other.sampling_fingerprint(&["tenant", "error.kind"])
+        );
+
+        let mut number = Context::new();
+        number.insert("status".to_string(), Value::U16(503));
+        let mut string = Context::new();
+        string.insert("status".to_string(), s("503"));
+        assert_eq!(number.sampling_fingerprint(&["status"]), string.sampling_fingerprint(&["status"]));
+        string.insert("status".to_string(), Value::Option(None));
+        assert_ne!(number.sampling_fingerprint(&["status"]), string.sampling_fingerprint(&["status"]));
+        assert_ne!(Context::new().sampling_fingerprint(&["status"]), string.sampling_fingerprint(&["status"]));
+    }
+
+    #[test]
+    fn test_is_sampled() {
+        let contexts: Vec<Context> = (0..1000)
+            .map(|i| {
+                let mut ctx = Context::new();
+                ctx.insert("request_id".to_string(), Value::U64(i));
+                ctx
+            })
+            .collect();
+        let sampled = |rate: f64| contexts.iter().filter(|ctx| ctx.is_sampled(&["request_id"], rate)).count();
+        assert_eq!(sampled(0.0), 0);
+        assert_eq!(sampled(1.0), 1000);
+        assert!((50..150).contains(&sampled(0.1)));
+        assert!(contexts
+            .iter()
+            .all(|ctx| !ctx.is_sampled(&["request_id"], 0.1) || ctx.is_sampled(&["request_id"], 0.5)));
+    }
+}
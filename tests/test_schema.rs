@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::errors::{ContextError, ContextErrorKind};
+    use cdumay_context::{Context, ContextSchema, Contextualize, KeySchema, ValueKind, ViolationKind};
+    use serde_value::Value;
+
+    fn schema() -> ContextSchema {
+        ContextSchema::new()
+            .with_title("Job context")
+            .with_description("Context of dispatched jobs.")
+            .required("tenant_id", ValueKind::String, "Tenant running the job")
+            .optional("attempt", ValueKind::Integer, "Attempt number | starting at 0")
+            .optional("payload", ValueKind::Any, "Raw payload")
+    }
+
+    #[test]
+    fn test_declarations() {
+        let schema = schema().required("attempt", ValueKind::Integer, "Attempt number");
+        assert_eq!(
+            schema.keys().iter().map(|declared| declared.key.as_str()).collect::<Vec<_>>(),
+            vec!["tenant_id", "attempt", "payload"]
+        );
+        assert_eq!(
+            schema.key("attempt"),
+            Some(&KeySchema {
+                key: "attempt".to_string(),
+                kind: ValueKind::Integer,
+                required: true,
+                description: "Attempt number".to_string()
+            })
+        );
+        assert!(schema.key("unknown").is_none());
+        assert_eq!(ContextSchema::new().title(), "Context");
+    }
+
+    #[test]
+    fn test_value_kinds() {
+        assert!(ValueKind::String.matches(&Value::Char('a')));
+        assert!(ValueKind::Integer.matches(&Value::Option(Some(Box::new(Value::I8(-1))))));
+        assert!(!ValueKind::Integer.matches(&Value::F64(1.5)));
+        assert!(ValueKind::Number.matches(&Value::U64(1)));
+        assert!(ValueKind::Number.matches(&Value::F32(1.5)));
+        assert!(ValueKind::Seq.matches(&Value::Seq(vec![])));
+        assert!(!ValueKind::Map.matches(&Value::Unit));
+        assert!(ValueKind::Any.matches(&Value::Unit));
+        assert_eq!(ValueKind::Bool.to_string(), "boolean");
+        assert_eq!(ValueKind::Any.to_string(), "any");
+    }
+
+    #[test]
+    fn test_markdown_docs() {
+        assert_eq!(
+            schema().to_markdown_docs(),
+            "# Job context\n\nContext of dispatched jobs.\n\n\
+             | Key | Type | Required | Description |\n\
+             | --- | --- | --- | --- |\n\
+             | `tenant_id` | string | yes | Tenant running the job |\n\
+             | `attempt` | integer | no | Attempt number \\| starting at 0 |\n\
+             | `payload` | any | no | Raw payload |\n"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = schema()
+            .required("queue", ValueKind::String, "Queue the job is dispatched to")
+            .with_rule("attempt", "non-negative", |value| !matches!(value, Value::I64(n) if *n < 0))
+            .with_rule(
+                "queue",
+                "known queue",
+                |value| matches!(value, Value::String(queue) if queue == "default"),
+            );
+
+        let mut ctx = Context::new();
+        ctx.insert("tenant_id".to_string(), Value::String("acme".to_string()));
+        ctx.insert("queue".to_string(), Value::String("default".to_string()));
+        assert_eq!(schema.validate(&ctx), Ok(()));
+        ctx.insert("attempt".to_string(), Value::I64(3));
+        assert_eq!(schema.validate(&ctx), Ok(()));
+
+        ctx.remove("tenant_id");
+        ctx.insert("attempt".to_string(), Value::I64(-1));
+        ctx.insert("queue".to_string(), Value::U8(1));
+        let errors = schema.validate(&ctx).unwrap_err();
+        let kinds: Vec<(&str, &ViolationKind)> = errors
+            .violations()
+            .iter()
+            .map(|violation| (violation.key.as_str(), &violation.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("tenant_id", &ViolationKind::Missing),
+                ("queue", &ViolationKind::WrongKind(ValueKind::String)),
+                ("attempt", &ViolationKind::Rule("non-negative".to_string())),
+            ]
+        );
+        assert_eq!(
+            errors.to_string(),
+            "Required context key 'tenant_id' is missing; Context key 'queue' must hold a value of kind string; \
+             Context key 'attempt' does not satisfy rule 'non-negative'"
+        );
+
+        let keys: Vec<Option<String>> = errors.errors().iter().map(ContextError::key).collect();
+        assert_eq!(
+            keys,
+            vec![Some("tenant_id".to_string()), Some("queue".to_string()), Some("attempt".to_string())]
+        );
+        assert!(errors.errors().iter().all(|err| err.class().ends_with("::ValidationError")));
+        assert!(errors
+            .errors()
+            .iter()
+            .all(|err| err.kind() == ContextErrorKind::Validation && err.details().contains_key("attempt")));
+
+        let err = cdumay_core::Error::from(errors);
+        assert!(err.class().ends_with("::ValidationError"));
+        assert_eq!(ContextError::code(&err), 422);
+        assert!(err
+            .message()
+            .starts_with("Context validation failed: Required context key 'tenant_id' is missing"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_schema() {
+        let schema = schema().to_json_schema();
+        assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+        assert_eq!(schema["title"], "Job context");
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], serde_json::json!(["tenant_id"]));
+        assert_eq!(
+            schema["properties"]["attempt"],
+            serde_json::json!({"type": "integer", "description": "Attempt number | starting at 0"})
+        );
+        assert_eq!(schema["properties"]["payload"], serde_json::json!({"description": "Raw payload"}));
+        assert_eq!(schema["additionalProperties"], true);
+    }
+}
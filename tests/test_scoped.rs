@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{with_context, Context, Contextualize, Sensitivity, Timeout};
+    use serde_value::Value;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn test_entries_restored() {
+        let mut ctx = Context::new();
+        ctx.insert("step".to_string(), s("init"));
+        let result = with_context!(ctx, { "step" => "upload", "attempt" => 2u8 }, {
+            assert_eq!(ctx.get("step"), Some(&s("upload")));
+            let inner = with_context!(ctx, { "step" => "chunk" }, {
+                assert_eq!(ctx.get("attempt"), Some(&Value::U8(2)));
+                Ok::<_, cdumay_core::Error>(ctx.get_str("step").unwrap().to_string())
+            });
+            assert_eq!(ctx.get("step"), Some(&s("upload")));
+            inner
+        });
+        assert_eq!(result.unwrap(), "chunk");
+        assert_eq!(ctx.get("step"), Some(&s("init")));
+        assert!(!ctx.contains_key("attempt"));
+        assert!(ctx.get("upload_elapsed_ms").is_some());
+        assert!(ctx.get("chunk_elapsed_ms").is_some());
+    }
+
+    #[test]
+    fn test_error_enriched() {
+        let mut ctx = Context::new();
+        ctx.insert("request_id".to_string(), s("abc"));
+        let err = ctx
+            .scoped(vec![("step".to_string(), s("fetch"))], |_| {
+                Err::<(), _>(Timeout::new().with_details([("step".to_string(), s("connect"))].into()))
+            })
+            .unwrap_err();
+        assert_eq!(err.code(), 504);
+        assert_eq!(err.details().get("request_id"), Some(&s("abc")));
+        assert_eq!(err.details().get("step"), Some(&s("connect")));
+        assert!(err.details().contains_key("fetch_elapsed_ms"));
+        assert!(!ctx.contains_key("step"));
+    }
+
+    #[test]
+    fn test_panic_dump() {
+        let mut ctx = Context::new();
+        ctx.insert("token".to_string(), s("s3cr3t"));
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        let payload = catch_unwind(AssertUnwindSafe(|| {
+            let _ = with_context!(ctx, { "step" => "parse" }, {
+                if ctx.contains_key("step") {
+                    panic!("boom");
+                }
+                Ok::<_, cdumay_core::Error>(())
+            });
+        }))
+        .unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("boom in `parse` with context: "));
+        assert!(message.contains("parse"));
+        assert!(!message.contains("s3cr3t"));
+        assert!(!ctx.contains_key("step"));
+    }
+}
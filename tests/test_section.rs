@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde::{Deserialize, Serialize};
+    use serde_value::Value;
+    use std::time::Duration;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct HttpInfo {
+        method: String,
+        status: u16,
+        #[serde(default)]
+        path: Option<String>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let info = HttpInfo {
+            method: "POST".to_string(),
+            status: 201,
+            path: Some("/jobs".to_string()),
+        };
+        let mut ctx = Context::new();
+        ctx.set_section("http", &info).unwrap();
+        assert!(matches!(ctx.get("http"), Some(Value::Map(_))));
+        assert_eq!(ctx.section::<HttpInfo>("http").unwrap(), Some(info));
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let ctx = Context::new();
+        assert_eq!(ctx.section::<HttpInfo>("http").unwrap(), None);
+    }
+
+    #[test]
+    fn test_ill_typed_section() {
+        let mut ctx = Context::new();
+        ctx.insert("http".to_string(), Value::String("not a map".to_string()));
+        let err = ctx.section::<HttpInfo>("http").unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err.message().contains("http"));
+        assert!(err.class().contains("TypeMismatch"));
+    }
+    #[test]
+    fn test_section_with_ttl() {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.section_with_ttl("burst_debug", Duration::from_secs(300)).unwrap();
+        ctx.insert_path("burst_debug.sql", Value::String("SELECT 1".to_string())).unwrap();
+        assert!(ctx.inner().contains_key("burst_debug"));
+        assert!(ctx.section_expires_in("burst_debug").unwrap() > Duration::from_secs(299));
+        assert_eq!(ctx.section_expires_in("user"), None);
+        assert!(ctx.prune_expired_sections().is_empty());
+
+        ctx.section_with_ttl("burst_debug", Duration::ZERO).unwrap();
+        assert_eq!(ctx.section_expires_in("burst_debug"), Some(Duration::ZERO));
+        assert_eq!(ctx.inner().keys().collect::<Vec<_>>(), vec!["user"]);
+        assert_eq!(ctx.fork().inner().get("burst_debug"), None);
+        assert!(ctx.get("burst_debug").is_some());
+        assert_eq!(ctx.prune_expired_sections(), vec!["burst_debug".to_string()]);
+        assert!(ctx.get("burst_debug").is_none());
+
+        ctx.section_with_ttl("burst_debug", Duration::ZERO).unwrap();
+        ctx.remove("burst_debug");
+        ctx.insert("burst_debug".to_string(), Value::Map(Default::default()));
+        assert!(ctx.inner().contains_key("burst_debug"));
+        assert_eq!(ctx.section_with_ttl("user", Duration::MAX).unwrap_err().code(), 400);
+    }
+}
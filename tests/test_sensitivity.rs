@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextDump, Contextualize, KeyPolicy, Sensitivity};
+    use serde_value::Value;
+
+    fn context() -> Context {
+        let mut ctx = Context::new();
+        for key in ["request_id", "db_host", "email", "token"] {
+            ctx.insert(key.to_string(), Value::String(key.to_uppercase()));
+        }
+        ctx.set_sensitivity("request_id", Sensitivity::Public);
+        ctx.set_sensitivity("email", Sensitivity::Confidential);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        let ctx = context();
+        assert_eq!(ctx.sensitivity("request_id"), Sensitivity::Public);
+        assert_eq!(ctx.sensitivity("db_host"), Sensitivity::Internal);
+        assert_eq!(ctx.sensitivity("missing"), Sensitivity::Internal);
+        assert!(Sensitivity::Public < Sensitivity::Internal && Sensitivity::Confidential < Sensitivity::Secret);
+    }
+
+    #[test]
+    fn test_views() {
+        let ctx = context();
+        let keys = |level| ctx.view(level).dump().into_keys().collect::<Vec<String>>();
+        assert_eq!(keys(Sensitivity::Public), vec!["request_id"]);
+        assert_eq!(keys(Sensitivity::Internal), vec!["db_host", "request_id"]);
+        assert_eq!(keys(Sensitivity::Confidential), vec!["db_host", "email", "request_id"]);
+        assert_eq!(keys(Sensitivity::Secret).len(), 4);
+
+        let view = ctx.view(Sensitivity::Public);
+        assert_eq!(view.level(), Sensitivity::Public);
+        assert!(view.get("token").is_none());
+        assert!(!view.is_visible("db_host"));
+        let public = view.to_context();
+        assert_eq!(public.inner().len(), 1);
+        assert_eq!(public.sensitivity("request_id"), Sensitivity::Public);
+    }
+
+    #[test]
+    fn test_case_insensitive_sensitivity() {
+        let mut ctx = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        ctx.insert("X-Request-Id".to_string(), Value::U8(1));
+        ctx.set_sensitivity("x-request-id", Sensitivity::Public);
+        assert!(ctx.view(Sensitivity::Public).get("X-REQUEST-ID").is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_view_serialization() {
+        let ctx = context();
+        assert_eq!(
+            serde_json::to_string(&ctx.view(Sensitivity::Public)).unwrap(),
+            r#"{"data":{"request_id":"REQUEST_ID"}}"#
+        );
+        assert_eq!(
+            ctx.view(Sensitivity::Public).to_context().to_json(false).unwrap(),
+            r#"{"request_id":"REQUEST_ID"}"#
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "sentry")]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{sentry_event, Context, Sensitivity, UnExpectedError, SENTRY_CONTEXT_KEY};
+    use sentry_core::protocol::{self, Event, Level};
+    use sentry_core::Scope;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        let mut ctx = context_of([
+            ("job", s("export")),
+            ("retries", Value::U8(3)),
+            ("limits", Value::Map(BTreeMap::from([(Value::U16(1), Value::Option(None))]))),
+            ("token", s("s3cr3t")),
+        ]);
+        ctx.set_sensitivity("token", Sensitivity::Secret);
+        ctx
+    }
+
+    #[test]
+    fn test_attach_to_scope() {
+        let mut scope = Scope::default();
+        context().attach_to_scope(&mut scope);
+        let event = scope.apply_to_event(Event::default()).unwrap();
+        let protocol::Context::Other(map) = &event.contexts[SENTRY_CONTEXT_KEY] else {
+            panic!("unexpected context type")
+        };
+        assert_eq!(map["job"], "export");
+        assert_eq!(map["retries"], 3);
+        assert!(map["limits"].as_object().unwrap()["1"].is_null());
+        assert!(!map.contains_key("token"));
+    }
+
+    #[test]
+    fn test_sentry_event() {
+        let err = UnExpectedError::new().with_message("export failed".to_string()).with_context(&context());
+        let event = sentry_event(err);
+        assert_eq!(event.level, Level::Error);
+        assert_eq!(event.message.as_deref(), Some("export failed"));
+        assert!(event.exception[0].ty.contains("UnExpectedError"));
+        assert_eq!(event.tags["code"], "500");
+        assert_eq!(event.extra["retries"], 3);
+        assert!(event.extra["limits"].as_object().unwrap()["1"].is_null());
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize, SharedContext};
+    use serde_value::Value;
+
+    #[test]
+    fn test_push() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.push("warnings", s("a")).unwrap(), 1);
+        assert_eq!(ctx.push("warnings", s("a")).unwrap(), 2);
+        assert_eq!(ctx.get("warnings"), Some(&Value::Seq(vec![s("a"), s("a")])));
+    }
+
+    #[test]
+    fn test_push_capped() {
+        let mut ctx = Context::new();
+        for v in ["a", "b", "c", "d"] {
+            ctx.push_capped("warnings", s(v), 2).unwrap();
+        }
+        assert_eq!(ctx.get("warnings"), Some(&Value::Seq(vec![s("c"), s("d")])));
+    }
+
+    #[test]
+    fn test_add_to_set() {
+        let mut ctx = Context::new();
+        assert!(ctx.add_to_set("features", s("gzip")).unwrap());
+        assert!(ctx.add_to_set("features", s("tls")).unwrap());
+        assert!(!ctx.add_to_set("features", s("gzip")).unwrap());
+        assert_eq!(ctx.get("features"), Some(&Value::Seq(vec![s("gzip"), s("tls")])));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut ctx = Context::new();
+        ctx.insert("count".to_string(), Value::U8(1));
+        assert_eq!(ctx.push("count", s("a")).unwrap_err().code(), 400);
+        assert_eq!(ctx.add_to_set("count", s("a")).unwrap_err().code(), 400);
+
+        ctx.push("audit", s("created")).unwrap();
+        ctx.protect_key("audit");
+        assert_eq!(ctx.push("audit", s("deleted")).unwrap_err().code(), 409);
+        assert_eq!(ctx.get("audit"), Some(&Value::Seq(vec![s("created")])));
+    }
+
+    #[test]
+    fn test_shared_push() {
+        let shared = SharedContext::default();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50u8 {
+                        shared.push("warnings", Value::U8(i)).unwrap();
+                        shared.add_to_set("seen", Value::U8(i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+        let ctx = shared.snapshot();
+        assert_eq!(ctx.at("warnings").as_seq().unwrap().len(), 200);
+        assert_eq!(ctx.at("seen").as_seq().unwrap().len(), 50);
+    }
+}
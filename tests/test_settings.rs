@@ -0,0 +1,138 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, ContextConfig, Contextualize, KeyOrder, KeyPolicy, MapKeyPolicy, NullPolicy, NumericPolicy, Priority};
+    #[cfg(any(feature = "json", all(feature = "toml", feature = "yaml")))]
+    use serde_value::Value;
+    use std::sync::Mutex;
+
+    /// Serializes the tests, the global configuration being shared by the whole process.
+    static GLOBAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_merge() {
+        let base = ContextConfig::new().with_null_policy(NullPolicy::Omit).redact("a").with_max_size(10);
+        let over = ContextConfig::new().with_max_size(20).redact("b").with_map_key_policy(MapKeyPolicy::Drop);
+        let merged = base.merge(&over);
+        assert_eq!(merged.null_policy(), NullPolicy::Omit);
+        assert_eq!(merged.map_key_policy(), MapKeyPolicy::Drop);
+        assert_eq!(merged.numeric_policy(), NumericPolicy::new());
+        assert_eq!(merged.key_policy(), KeyPolicy::CaseSensitive);
+        assert_eq!(merged.max_size(), Some(20));
+        assert_eq!(merged.redacted_keys(), ["a".to_string(), "b".to_string()]);
+        assert_eq!(ContextConfig::new().merge(&ContextConfig::new()), ContextConfig::new());
+    }
+
+    #[test]
+    fn test_context_config() {
+        let _guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        let config = ContextConfig::new()
+            .with_key_policy(KeyPolicy::CaseInsensitive)
+            .with_map_key_policy(MapKeyPolicy::Reject);
+        let mut ctx = Context::with_config(config.clone());
+        ctx.insert("User".to_string(), s("jane"));
+        assert_eq!(ctx.key_policy(), KeyPolicy::CaseInsensitive);
+        assert_eq!(ctx.config(), &config);
+        assert_eq!(ctx.map_key_policy(), MapKeyPolicy::Reject);
+
+        ctx.set_numeric_policy(NumericPolicy::new().with_max_integer(10));
+        assert_eq!(ctx.config().numeric_policy(), NumericPolicy::new().with_max_integer(10));
+        assert_eq!(ctx.fork().config(), ctx.config());
+
+        ctx.set_config(ContextConfig::new());
+        assert_eq!(ctx.map_key_policy(), MapKeyPolicy::Stringify);
+        assert_eq!(ctx.key_policy(), KeyPolicy::CaseInsensitive);
+    }
+
+    #[test]
+    fn test_global_config() {
+        let _guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        ContextConfig::set_global(
+            ContextConfig::new()
+                .with_key_policy(KeyPolicy::CaseInsensitive)
+                .with_null_policy(NullPolicy::Omit)
+                .redact("token"),
+        );
+        let ctx = Context::new();
+        let effective = ctx.effective_config(Some(&ContextConfig::new().with_null_policy(NullPolicy::Keep).redact("secret")));
+        let scoped = Context::with_config(ContextConfig::new().with_key_policy(KeyPolicy::CaseSensitive));
+        ContextConfig::set_global(ContextConfig::new());
+
+        assert_eq!(ctx.key_policy(), KeyPolicy::CaseInsensitive);
+        assert_eq!(scoped.key_policy(), KeyPolicy::CaseSensitive);
+        assert_eq!(effective.null_policy(), NullPolicy::Keep);
+        assert_eq!(effective.redacted_keys(), ["token".to_string(), "secret".to_string()]);
+        assert_eq!(ContextConfig::global(), ContextConfig::new());
+        assert_eq!(Context::new().key_policy(), KeyPolicy::CaseSensitive);
+    }
+
+    #[test]
+    fn test_key_order() {
+        let _guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        assert_eq!(ContextConfig::new().key_order(), KeyOrder::default());
+        let merged = ContextConfig::new().with_key_order(KeyOrder::Insertion).merge(&ContextConfig::new());
+        assert_eq!(merged.key_order(), KeyOrder::Insertion);
+
+        ContextConfig::set_global(ContextConfig::new().with_key_order(KeyOrder::Insertion));
+        let mut ctx = Context::new();
+        let scoped = Context::with_config(ContextConfig::new().with_key_order(KeyOrder::Alphabetical));
+        ContextConfig::set_global(ContextConfig::new());
+
+        assert_eq!(scoped.key_order(), &KeyOrder::Alphabetical);
+        assert_eq!(ctx.key_order(), &KeyOrder::Insertion);
+        for key in ["user", "error", "request_id"] {
+            ctx.insert(key.to_string(), s(key));
+        }
+        ctx.set_priority("request_id", Priority::High);
+        let keys: Vec<String> = ctx.ordered_entries().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["request_id", "user", "error"]);
+        #[cfg(feature = "json")]
+        assert_eq!(
+            ctx.to_json(false).unwrap(),
+            r#"{"request_id":"request_id","user":"user","error":"error"}"#
+        );
+        assert_eq!(Context::new().key_order(), &KeyOrder::default());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        let _guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        let mut ctx = Context::with_config(ContextConfig::new().redact("auth.*").with_null_policy(NullPolicy::Omit));
+        ctx.insert("auth".to_string(), Value::Map([(s("user"), s("jane")), (s("scheme"), s("basic"))].into()));
+        ctx.insert("parent".to_string(), Value::Option(None));
+        ctx.insert("payload".to_string(), s(&"x".repeat(100)));
+        ctx.insert("tags".to_string(), Value::Seq(vec![Value::Unit, s("a")]));
+        assert_eq!(
+            ctx.to_json(false).unwrap(),
+            format!(
+                r#"{{"auth":{{"scheme":"[redacted]","user":"[redacted]"}},"payload":"{}","tags":[null,"a"]}}"#,
+                "x".repeat(100)
+            )
+        );
+
+        let call = ContextConfig::new().with_null_policy(NullPolicy::Keep).with_max_size(120);
+        let json = ctx.to_json_with(false, &call).unwrap();
+        assert!(json.len() <= 120, "{}", json);
+        assert_eq!(
+            json,
+            r#"{"auth":{"scheme":"[redacted]","user":"[redacted]"},"parent":null,"propagation":{"dropped":["payload","tags"]}}"#
+        );
+    }
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn test_toml_yaml() {
+        let _guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+        let mut ctx = Context::new();
+        ctx.insert("parent".to_string(), Value::Option(None));
+        ctx.insert("user".to_string(), s("jane"));
+        assert!(ctx.to_toml(false).is_err());
+
+        let config = ContextConfig::new().with_null_policy(NullPolicy::Omit).redact("user");
+        assert_eq!(ctx.to_toml_with(false, &config).unwrap(), "user = \"[redacted]\"\n");
+        assert_eq!(ctx.to_yaml_with(&config).unwrap(), "user: '[redacted]'\n");
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextDump, Contextualize, SharedContext};
+    use serde_value::Value;
+    use std::thread;
+
+    fn exercise(shared: SharedContext) {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    shared.insert(format!("worker_{}", i), Value::U64(i));
+                    shared.update(|ctx| {
+                        let count = ctx
+                            .get("count")
+                            .and_then(|v| if let Value::U64(c) = v { Some(*c) } else { None })
+                            .unwrap_or(0);
+                        ctx.insert("count".to_string(), Value::U64(count + 1));
+                    });
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+
+        assert_eq!(shared.get("count"), Some(Value::U64(8)));
+        assert_eq!(shared.dump().len(), 9);
+        assert_eq!(shared.read(|ctx| ctx.inner().len()), 9);
+    }
+
+    #[test]
+    fn test_locked_mode() {
+        let shared = SharedContext::default();
+        assert!(!shared.is_lock_free());
+        exercise(shared);
+    }
+
+    #[test]
+    fn test_snapshot_is_isolated() {
+        let shared = SharedContext::new(Context::new());
+        shared.insert("a".to_string(), Value::Bool(true));
+        let snapshot = shared.snapshot();
+        shared.insert("b".to_string(), Value::Bool(true));
+        assert!(snapshot.get("b").is_none());
+        assert!(shared.get("b").is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "arc-swap")]
+    fn test_lock_free_mode() {
+        let shared = SharedContext::lock_free(Context::new());
+        assert!(shared.is_lock_free());
+        let before = shared.snapshot();
+        exercise(shared.clone());
+        assert!(before.inner().is_empty());
+        assert_eq!(shared.snapshot().inner().len(), 9);
+    }
+}
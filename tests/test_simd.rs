@@ -0,0 +1,51 @@
+#[cfg(test)]
+#[cfg(feature = "simd-json")]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SIMD_JSON_THRESHOLD};
+    use cdumay_core::ErrorConverter;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn large_json() -> String {
+        let mut ctx = Context::new();
+        ctx.insert("padding".to_string(), Value::String("x".repeat(SIMD_JSON_THRESHOLD)));
+        ctx.insert("id".to_string(), Value::U64(u64::MAX));
+        ctx.insert("offset".to_string(), Value::I64(-42));
+        ctx.insert("ratio".to_string(), Value::F64(0.1));
+        ctx.insert("note".to_string(), Value::String("line\nbreak \u{e9}".to_string()));
+        ctx.insert(
+            "nested".to_string(),
+            Value::Map(BTreeMap::from([(
+                Value::String("items".to_string()),
+                Value::Seq(vec![Value::Bool(true), Value::Unit]),
+            )])),
+        );
+        ctx.to_json(false).unwrap()
+    }
+
+    #[test]
+    fn test_large_payload_semantics() {
+        let json = large_json();
+        let ctx = Context::from_json(&json).unwrap();
+        let expected: BTreeMap<String, Value> = serde_json::from_str::<BTreeMap<String, serde_json::Value>>(&json)
+            .unwrap()
+            .into_iter()
+            .map(|(k, v)| (k, serde_value::to_value(v).unwrap()))
+            .collect();
+        assert_eq!(ctx.inner(), expected);
+        assert_eq!(ctx.at("id").as_u64(), Some(u64::MAX));
+        assert_eq!(ctx.at("note").as_str(), Some("line\nbreak \u{e9}"));
+    }
+
+    #[test]
+    fn test_errors_are_unchanged() {
+        let json = large_json();
+        let truncated = &json[..json.len() - 1];
+        let err = Context::from_json(truncated).unwrap_err();
+        let expected = serde_json::from_str::<BTreeMap<String, serde_json::Value>>(truncated).unwrap_err();
+        let expected = cdumay_json::JsonErrorConverter::convert_error(&expected, Some("Failed to load context".to_string()), BTreeMap::new());
+        assert_eq!(err.code(), expected.code());
+        assert_eq!(err.class(), expected.class());
+        assert_eq!(err.message(), expected.message());
+    }
+}
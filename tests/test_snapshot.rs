@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, SnapshotOptions};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn sample() -> Context {
+        let mut http = BTreeMap::new();
+        http.insert(Value::String("status".to_string()), Value::U16(502));
+        http.insert(Value::String("started_at".to_string()), Value::String("2024-05-01T10:00:00Z".to_string()));
+        let mut ctx = Context::new();
+        ctx.insert("http".to_string(), Value::Map(http));
+        ctx.insert(
+            "request_id".to_string(),
+            Value::String("67E55044-10B1-426F-9247-BB680E5FE0C8".to_string()),
+        );
+        ctx.insert(
+            "tags".to_string(),
+            Value::Seq(vec![Value::String("a\"b".to_string()), Value::Bool(false)]),
+        );
+        ctx.insert("token".to_string(), Value::String("s3cr3t".to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_default_snapshot() {
+        let expected = r#"{
+  "http": {
+    "started_at": "[timestamp]",
+    "status": 502
+  },
+  "request_id": "[uuid]",
+  "tags": [
+    "a\"b",
+    false
+  ],
+  "token": "s3cr3t"
+}"#;
+        assert_eq!(sample().to_snapshot(&SnapshotOptions::new()), expected);
+    }
+
+    #[test]
+    fn test_masked_keys() {
+        let options = SnapshotOptions::new().mask_key("token").mask_key("http.*");
+        let snapshot = sample().to_snapshot(&options);
+        assert!(snapshot.contains("\"token\": \"[masked]\""));
+        assert!(snapshot.contains("\"status\": \"[masked]\""));
+        assert!(!snapshot.contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_disable_detection() {
+        let options = SnapshotOptions::new().with_uuid_detection(false).with_timestamp_detection(false);
+        let snapshot = sample().to_snapshot(&options);
+        assert!(snapshot.contains("67E55044-10B1-426F-9247-BB680E5FE0C8"));
+        assert!(snapshot.contains("2024-05-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_empty_context() {
+        assert_eq!(Context::new().to_snapshot(&SnapshotOptions::new()), "{}");
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{normalize_sql, Context, Contextualize, SqlParams, DB_KEY};
+    use serde_value::Value;
+
+    #[test]
+    fn test_normalize_sql() {
+        assert_eq!(
+            normalize_sql("SELECT id FROM t1 -- by name\nWHERE name = 'O''Brien'  AND age > 42 /* adults */ LIMIT 10"),
+            "SELECT id FROM t1 WHERE name = ? AND age > ? LIMIT ?"
+        );
+        assert_eq!(
+            normalize_sql("UPDATE \"t'1\" SET x = 1.5 WHERE id = $2"),
+            "UPDATE \"t'1\" SET x = ? WHERE id = $2"
+        );
+        assert_eq!(normalize_sql("INSERT INTO t VALUES (:1, :name)"), "INSERT INTO t VALUES (:1, :name)");
+    }
+
+    #[test]
+    fn test_normalize_sql_escapes() {
+        assert_eq!(
+            normalize_sql("SELECT * FROM t WHERE name = 'O\\'Brien secret' AND note = E'a\\\\' AND id = 1"),
+            "SELECT * FROM t WHERE name = ? AND note = E? AND id = ?"
+        );
+        assert_eq!(
+            normalize_sql("SELECT $$it's secret$$, $body$x $$ y$body$ FROM t$1 WHERE id = $1"),
+            "SELECT ?, ? FROM t$1 WHERE id = $1"
+        );
+    }
+
+    #[test]
+    fn test_record_sql_placeholders() {
+        let mut ctx = Context::new();
+        ctx.record_sql("SELECT * FROM users WHERE id = $1 AND active = $2", &[Value::U64(42), Value::Bool(true)]);
+        let params = ctx.at(DB_KEY).at("params");
+        assert_eq!(
+            params.as_seq(),
+            Some(&[Value::String("<unsigned>".to_string()), Value::String("<bool>".to_string())][..])
+        );
+        assert_eq!(SqlParams::default(), SqlParams::Placeholders);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn test_record_sql_hashed() {
+        let mut ctx = Context::new();
+        let email = Value::String("jane@example.com".to_string());
+        let salt = b"s3cr3t".to_vec();
+        ctx.record_sql_with(
+            "SELECT 1 WHERE a = $1 AND b = $2",
+            &[email.clone(), email.clone()],
+            SqlParams::Hashed(salt.clone()),
+        );
+        let params = ctx.at(DB_KEY).at("params").as_seq().unwrap().to_vec();
+        assert_eq!(params[0], params[1]);
+        let hashed = match &params[0] {
+            Value::String(hashed) => hashed.clone(),
+            other => panic!("unexpected param {:?}", other),
+        };
+        assert!(hashed.starts_with("hmac-sha256:"));
+        assert_eq!(hashed.len(), "hmac-sha256:".len() + 32);
+        assert!(!hashed.contains("jane"));
+
+        let mut other = Context::new();
+        other.record_sql_with("SELECT 1 WHERE a = $1", &[email], SqlParams::Hashed(b"other".to_vec()));
+        assert_ne!(other.at(DB_KEY).at("params").as_seq().unwrap()[0], params[0]);
+    }
+}
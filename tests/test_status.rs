@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{
+        register_grpc_code, ConflictError, ContextSerializationError, ContextValidationError, ContextValueError, DeltaConflictError,
+        FeatureDisabledError, FrozenKeyError, GenericContextError, GrpcCode, InvalidMapKeyError, InvalidStateError, NotFoundError,
+        PrecisionLossError, ProtectedKeyError, QuotaExceededError, SizeLimitExceededError, StatusMapping, TimeoutError, UnauthorizedError,
+        YamlExpansionLimitError,
+    };
+    use cdumay_core::ErrorKind;
+
+    #[test]
+    fn test_crate_kinds() {
+        assert_eq!(NotFoundError.http_status(), 404);
+        assert_eq!(NotFoundError.grpc_code(), GrpcCode::NotFound);
+        assert_eq!(UnauthorizedError.grpc_code(), GrpcCode::Unauthenticated);
+        assert_eq!(FrozenKeyError.http_status(), 409);
+        assert_eq!(FrozenKeyError.grpc_code(), GrpcCode::FailedPrecondition);
+        assert_eq!(DeltaConflictError.grpc_code(), GrpcCode::Aborted);
+        assert_eq!(ConflictError.grpc_code(), GrpcCode::Aborted);
+    }
+
+    #[test]
+    fn test_every_crate_kind() {
+        let kinds = [
+            GenericContextError,
+            ContextValueError,
+            ProtectedKeyError,
+            FrozenKeyError,
+            DeltaConflictError,
+            NotFoundError,
+            ConflictError,
+            TimeoutError,
+            UnauthorizedError,
+            InvalidStateError,
+            PrecisionLossError,
+            InvalidMapKeyError,
+            FeatureDisabledError,
+            YamlExpansionLimitError,
+            QuotaExceededError,
+            ContextValidationError,
+            ContextSerializationError,
+            SizeLimitExceededError,
+        ];
+        for kind in kinds {
+            assert_ne!(kind.grpc_code(), GrpcCode::Unknown, "{:?}", kind);
+        }
+        assert_eq!(FeatureDisabledError.grpc_code(), GrpcCode::Unimplemented);
+        assert_eq!(YamlExpansionLimitError.grpc_code(), GrpcCode::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_payload_too_large() {
+        assert_eq!(ErrorKind("TooLarge", 413, "Too large").grpc_code(), GrpcCode::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_registered_kinds() {
+        let kind = ErrorKind("Teapot", 418, "I'm a teapot");
+        assert_eq!(kind.grpc_code(), GrpcCode::Unknown);
+        register_grpc_code(kind.clone(), GrpcCode::Unavailable);
+        assert_eq!(kind.grpc_code(), GrpcCode::Unavailable);
+        register_grpc_code(kind.clone(), GrpcCode::Internal);
+        assert_eq!(kind.grpc_code(), GrpcCode::Internal);
+        assert_eq!(ErrorKind("Teapot", 418, "Another teapot").grpc_code(), GrpcCode::Unknown);
+    }
+
+    #[test]
+    fn test_downstream_kinds() {
+        cdumay_context::define_context_errors! {
+            billing {
+                PaymentRequired = PaymentRequiredError(402, "Payment required"),
+                Forbidden = ForbiddenError(403, "Forbidden"),
+                Unavailable = UnavailableError(503, "Unavailable"),
+            }
+        }
+        assert_eq!(billing::PaymentRequiredError.http_status(), 402);
+        assert_eq!(billing::PaymentRequiredError.grpc_code(), GrpcCode::Unknown);
+        assert_eq!(billing::ForbiddenError.grpc_code(), GrpcCode::PermissionDenied);
+        assert_eq!(billing::UnavailableError.grpc_code(), GrpcCode::Unavailable);
+    }
+
+    #[test]
+    fn test_invalid_code() {
+        let kind = ErrorKind("Legacy", 1042, "Legacy error");
+        assert_eq!(kind.http_status(), 500);
+        assert_eq!(kind.grpc_code(), GrpcCode::Internal);
+        assert_eq!(GrpcCode::Internal as i32, 13);
+    }
+}
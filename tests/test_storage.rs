@@ -0,0 +1,185 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextStorage, Contextualize, Format, FsStorage, MemoryStorage};
+    use std::path::{Path, PathBuf};
+
+    fn storage_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("cdumay-storage-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path("app.json"), Some(Format::Json));
+        assert_eq!(Format::from_path("conf/app.TOML"), Some(Format::Toml));
+        assert_eq!(Format::from_path("app.yml"), Some(Format::Yaml));
+        assert_eq!(Format::from_path("app.yaml"), Some(Format::Yaml));
+        assert_eq!(Format::from_path("app.ini"), None);
+        assert_eq!(Format::from_path("app"), None);
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        let storage = MemoryStorage::new();
+        let path = Path::new("a/b.json");
+        assert_eq!(storage.version(path).unwrap(), None);
+        assert_eq!(storage.read(path).unwrap_err().code(), 404);
+
+        storage.write(path, "{}").unwrap();
+        assert_eq!(storage.version(path).unwrap(), Some(0));
+        storage.write(path, "{\"a\": 1}").unwrap();
+        assert_eq!(storage.version(path).unwrap(), Some(1));
+        assert_eq!(storage.read(path).unwrap(), "{\"a\": 1}");
+        assert_eq!(storage.paths(), vec![PathBuf::from("a/b.json")]);
+
+        assert_eq!(storage.remove(path), Some("{\"a\": 1}".to_string()));
+        assert_eq!(storage.version(path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        let storage = MemoryStorage::new();
+        let err = Context::new().save_file(&storage, "context.ini").unwrap_err();
+        assert_eq!(err.code(), 422);
+        assert!(storage.paths().is_empty());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let err = Context::load_file(&MemoryStorage::new(), "missing.json").unwrap_err();
+        assert_eq!(err.code(), 404);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_memory_roundtrip() {
+        use serde_value::Value;
+
+        let storage = MemoryStorage::new();
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.save_file(&storage, "context.json").unwrap();
+
+        assert!(storage.read(Path::new("context.json")).unwrap().contains("\"user\": \"jane\""));
+        assert_eq!(Context::load_file(&storage, "context.json").unwrap().inner(), ctx.inner());
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn test_disabled_format() {
+        let err = Context::new().save_file(&MemoryStorage::new(), "context.toml").unwrap_err();
+        assert_eq!(err.code(), 501);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_fs_roundtrip() {
+        use serde_value::Value;
+
+        let dir = storage_dir("roundtrip");
+        let path = dir.join("nested/context.yaml");
+        let mut ctx = Context::new();
+        ctx.insert("retries".to_string(), Value::U64(3));
+        ctx.save_file(&FsStorage, &path).unwrap();
+        let loaded = Context::load_file(&FsStorage, &path);
+        let version = FsStorage.version(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.unwrap().inner(), ctx.inner());
+        assert!(version.is_some());
+        assert_eq!(FsStorage.version(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fs_missing_file() {
+        let path = storage_dir("missing").join("context.json");
+        assert_eq!(FsStorage.read(&path).unwrap_err().code(), 404);
+        assert_eq!(FsStorage.version(&path).unwrap(), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_watch_file() {
+        use serde_value::Value;
+
+        let storage = MemoryStorage::new();
+        let mut watcher = Context::watch_file(&storage, "context.json");
+        assert!(watcher.poll().unwrap().is_none());
+
+        let mut ctx = Context::new();
+        ctx.insert("level".to_string(), Value::String("info".to_string()));
+        ctx.save_file(&storage, "context.json").unwrap();
+        assert_eq!(watcher.poll().unwrap().unwrap().inner(), ctx.inner());
+        assert!(watcher.poll().unwrap().is_none());
+
+        storage.write(Path::new("context.json"), "{not json").unwrap();
+        assert!(watcher.poll().is_err());
+        assert!(watcher.poll().is_err());
+
+        ctx.insert("level".to_string(), Value::String("debug".to_string()));
+        ctx.save_file(&storage, "context.json").unwrap();
+        let reloaded = watcher.poll().unwrap().unwrap();
+        assert_eq!(reloaded.get("level"), Some(&Value::String("debug".to_string())));
+
+        storage.remove("context.json");
+        assert!(watcher.poll().unwrap().is_none());
+        assert_eq!(watcher.path(), Path::new("context.json"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_bundle_in_memory() {
+        use serde_value::Value;
+
+        let storage = MemoryStorage::new();
+        let mut ctx = Context::new();
+        ctx.insert("job".to_string(), Value::String("nightly".to_string()));
+        ctx.freeze_key("job");
+        ctx.export_bundle_to(&storage, "bundle").unwrap();
+
+        assert_eq!(
+            storage.paths(),
+            vec![PathBuf::from("bundle/context.json"), PathBuf::from("bundle/metadata.json")]
+        );
+        let replayed = Context::import_bundle_from(&storage, "bundle").unwrap();
+        assert_eq!(replayed.inner(), ctx.inner());
+        assert!(replayed.is_frozen("job"));
+    }
+
+    #[test]
+    fn test_from_file_errors() {
+        let path = storage_dir("from-file").join("context.json");
+        let err = Context::from_file(&path).unwrap_err();
+        assert_eq!(err.code(), if cfg!(feature = "json") { 404 } else { 501 });
+        assert_eq!(err.details().get("path"), Some(&serde_value::Value::String(path.display().to_string())));
+
+        let err = Context::new().save_to_file("context.ini", None).unwrap_err();
+        assert_eq!(err.code(), 422);
+        assert_eq!(err.details().get("path"), Some(&serde_value::Value::String("context.ini".to_string())));
+    }
+
+    #[cfg(all(feature = "json", feature = "toml"))]
+    #[test]
+    fn test_file_roundtrip() {
+        use serde_value::Value;
+
+        let dir = storage_dir("file-roundtrip");
+        let mut ctx = Context::new();
+        ctx.insert("retries".to_string(), Value::U64(3));
+        ctx.save_to_file(dir.join("context.json"), None).unwrap();
+        ctx.save_to_file(dir.join("context.conf"), Some(Format::Toml)).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let loaded = Context::from_file(dir.join("context.json"));
+        let toml = std::fs::read_to_string(dir.join("context.conf"));
+        let broken = Context::from_file(dir.join("broken.json"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.unwrap().inner(), ctx.inner());
+        assert_eq!(toml.unwrap(), "retries = 3\n");
+        let err = broken.unwrap_err();
+        assert_eq!(
+            err.details().get("path"),
+            Some(&Value::String(dir.join("broken.json").display().to_string()))
+        );
+    }
+}
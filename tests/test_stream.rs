@@ -0,0 +1,73 @@
+#[cfg(test)]
+#[cfg(feature = "json")]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+    use std::io::Read;
+    use std::ops::ControlFlow;
+
+    /// Fails once more than `limit` bytes were read.
+    struct Limited<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl Read for Limited<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.limit == 0 {
+                return Err(std::io::Error::other("read past the limit"));
+            }
+            let len = buf.len().min(self.data.len()).min(self.limit);
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            self.limit -= len;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_stream_all_entries() {
+        let json = r#"{"b":{"x":[1,-2,0.5]},"a":"text","c":null}"#;
+        let mut entries = BTreeMap::new();
+        let mut keys = Vec::new();
+        Context::from_json_stream(json.as_bytes(), |key, value| {
+            keys.push(key.clone());
+            entries.insert(key, value);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(keys, vec!["b", "a", "c"]);
+        assert_eq!(entries, Context::from_json(json).unwrap().inner());
+    }
+
+    #[test]
+    fn test_early_exit() {
+        let mut json = String::from(r#"{"request_id":"abc","#);
+        json.push_str(&format!(r#""payload":"{}"}}"#, "x".repeat(1 << 20)));
+        let mut found = None;
+        let reader = Limited {
+            data: json.as_bytes(),
+            limit: 64 * 1024,
+        };
+        Context::from_json_stream(reader, |key, value| {
+            found = Some((key, value));
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(found, Some(("request_id".to_string(), Value::String("abc".to_string()))));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut calls = 0;
+        let mut count = |_, _| {
+            calls += 1;
+            ControlFlow::Continue(())
+        };
+        assert!(Context::from_json_stream(r#"{"a":1,"b":"#.as_bytes(), &mut count).is_err());
+        assert!(Context::from_json_stream("[1, 2]".as_bytes(), &mut count).is_err());
+        assert!(Context::from_json_stream(r#"{"a":1} trailing"#.as_bytes(), &mut count).is_err());
+        assert_eq!(calls, 2);
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_datetimes() {
+        use cdumay_context::{Context, ContextConfig, Contextualize, TAG_KEY, TAG_VALUE_KEY, TOML_DATETIME_TAG};
+        use serde_value::Value;
+
+        let toml = "at = 1979-05-27T07:32:00Z\n\n[window]\nfrom = 07:32:00\n";
+        let ctx = Context::from_toml(toml).unwrap();
+        assert_eq!(ctx.get("at"), Some(&Value::String("1979-05-27T07:32:00Z".to_string())));
+        assert_eq!(ctx.at("window").at("from").as_str(), Some("07:32:00"));
+
+        let ctx = Context::from_toml_with(toml, &ContextConfig::new().with_tagged_values(true)).unwrap();
+        assert_eq!(ctx.at("at").at(TAG_KEY).as_str(), Some(TOML_DATETIME_TAG));
+        assert_eq!(ctx.at("at").at(TAG_VALUE_KEY).as_str(), Some("1979-05-27T07:32:00Z"));
+        assert_eq!(ctx.to_toml(false).unwrap(), toml);
+
+        let plain = ctx.to_toml_with(false, &ContextConfig::new().with_tagged_values(false)).unwrap();
+        assert!(plain.contains("[at]"), "{}", plain);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_tags() {
+        use cdumay_context::{Context, ContextConfig, Contextualize, TAG_KEY, TAG_VALUE_KEY};
+
+        let yaml = "hosts: !hostlist\n- a\n- !local b\ntoken: !vault secret/db\n";
+        let ctx = Context::from_yaml(yaml).unwrap();
+        assert_eq!(ctx.at("hosts").nth(1).as_str(), Some("b"));
+
+        let ctx = Context::from_yaml_with(yaml, &ContextConfig::new().with_tagged_values(true)).unwrap();
+        assert_eq!(ctx.at("hosts").at(TAG_KEY).as_str(), Some("!hostlist"));
+        assert_eq!(ctx.at("hosts").at(TAG_VALUE_KEY).nth(1).at(TAG_KEY).as_str(), Some("!local"));
+        assert_eq!(ctx.to_yaml().unwrap(), yaml);
+    }
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn test_cross_format() {
+        use cdumay_context::{Context, ContextConfig, Contextualize};
+
+        let config = ContextConfig::new().with_tagged_values(true);
+        let ctx = Context::from_toml_with("at = 1979-05-27\n", &config).unwrap();
+        let yaml = ctx.to_yaml().unwrap();
+        assert_eq!(yaml, "at: !toml:datetime 1979-05-27\n");
+        assert_eq!(
+            Context::from_yaml_with(&yaml, &config).unwrap().to_toml(false).unwrap(),
+            "at = 1979-05-27\n"
+        );
+    }
+}
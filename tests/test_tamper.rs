@@ -0,0 +1,57 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, SealViolation, SealViolationKind};
+    use serde_value::Value;
+
+    fn context() -> Context {
+        context_of([("user", s("jane")), ("role", s("reader")), ("quota", Value::U32(10))])
+    }
+
+    #[test]
+    fn test_verify_seals() {
+        let mut ctx = context();
+        assert!(!ctx.has_seals());
+        ctx.seal_values();
+        assert!(ctx.has_seals());
+        assert!(ctx.verify_seals().is_empty());
+
+        ctx.insert("role".to_string(), Value::String("admin".to_string()));
+        ctx.remove("quota");
+        ctx.insert("debug".to_string(), Value::Bool(true));
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        let violations = ctx.verify_seals();
+        let found: Vec<(SealViolationKind, &str)> = violations.iter().map(|v| (v.kind, v.key.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (SealViolationKind::Added, "debug"),
+                (SealViolationKind::Removed, "quota"),
+                (SealViolationKind::Modified, "role")
+            ]
+        );
+        assert_eq!(violations[2].to_string(), "role: modified since sealing");
+    }
+
+    #[test]
+    fn test_reseal_and_unseal() {
+        let mut ctx = context();
+        ctx.seal_values();
+        ctx.insert("quota".to_string(), Value::U64(10));
+        assert_eq!(
+            ctx.verify_seals(),
+            vec![SealViolation {
+                kind: SealViolationKind::Modified,
+                key: "quota".to_string()
+            }]
+        );
+
+        ctx.seal_values();
+        assert!(ctx.verify_seals().is_empty());
+        ctx.unseal_values();
+        ctx.insert("quota".to_string(), Value::U64(20));
+        assert!(ctx.verify_seals().is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::errors::{ContextError, ContextErrorKind};
+    use cdumay_context::{Context, Contextualize, MissingKeyPolicy, Sensitivity};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn context() -> Context {
+        let mut ctx = context_of([("app", s("billing")), ("attempt", Value::U8(3))]);
+        ctx.insert_path("job.queue", s("default")).unwrap();
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("slow"), s("retried")]));
+        ctx
+    }
+
+    #[test]
+    fn test_render() {
+        let ctx = context();
+        assert_eq!(
+            ctx.render("${app} attempt ${ attempt } on ${job.queue}: ${tags}").unwrap(),
+            "billing attempt 3 on default: [slow, retried]"
+        );
+        assert_eq!(ctx.render("cost: $${app} and ${app").unwrap(), "cost: ${app} and ${app");
+        assert_eq!(ctx.render("no placeholder").unwrap(), "no placeholder");
+
+        let err = ctx.render("${app} in ${region}").unwrap_err();
+        assert_eq!(err.kind(), ContextErrorKind::KeyNotFound);
+        assert_eq!(ContextError::key(&err), Some("region".to_string()));
+        assert_eq!(ctx.render_with("${app} in ${region}", MissingKeyPolicy::Empty).unwrap(), "billing in ");
+        assert_eq!(
+            ctx.render_with("${app} in ${region}", MissingKeyPolicy::Keep).unwrap(),
+            "billing in ${region}"
+        );
+    }
+
+    #[test]
+    fn test_redaction() {
+        let mut ctx = context();
+        ctx.insert("api_key".to_string(), Value::String("s3cr3t".to_string()));
+        ctx.set_sensitivity("api_key", Sensitivity::Secret);
+        assert!(!ctx.render("key: ${api_key}").unwrap().contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_render_value() {
+        let mut ctx = context();
+        ctx.insert(
+            "message".to_string(),
+            Value::Map(BTreeMap::from([
+                (Value::String("title".to_string()), Value::String("${app} failed".to_string())),
+                (
+                    Value::String("lines".to_string()),
+                    Value::Seq(vec![Value::String("attempt ${attempt}".to_string()), Value::U8(1)]),
+                ),
+            ])),
+        );
+        ctx.insert("nested".to_string(), Value::String("${message}".to_string()));
+
+        let rendered = ctx.render_value("message").unwrap();
+        assert_eq!(ctx.at("message").at("title").as_str(), Some("${app} failed"));
+        assert_eq!(
+            rendered,
+            Value::Map(BTreeMap::from([
+                (Value::String("title".to_string()), Value::String("billing failed".to_string())),
+                (
+                    Value::String("lines".to_string()),
+                    Value::Seq(vec![Value::String("attempt 3".to_string()), Value::U8(1)])
+                ),
+            ]))
+        );
+        assert_eq!(ctx.render_value("job.queue").unwrap(), Value::String("default".to_string()));
+        assert_eq!(
+            ctx.render_value("nested").unwrap(),
+            Value::String("{lines: [attempt ${attempt}, 1], title: ${app} failed}".to_string())
+        );
+
+        ctx.insert("broken".to_string(), Value::String("${missing}".to_string()));
+        assert!(ctx.render_value("broken").is_err());
+        assert_eq!(
+            ctx.render_value_with("broken", MissingKeyPolicy::Empty).unwrap(),
+            Value::String(String::new())
+        );
+        assert_eq!(ctx.render_value_with("unknown", MissingKeyPolicy::Keep).unwrap_err().code(), 404);
+    }
+}
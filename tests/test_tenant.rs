@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, QuotaEvent, TenantQuota, TenantRegistry, TenantUsage};
+    use serde_value::Value;
+    use std::sync::{Arc, Mutex};
+
+    fn context(job: &str) -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("job".to_string(), Value::String(job.to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_max_contexts() {
+        let registry = TenantRegistry::new();
+        registry.set_quota("acme", TenantQuota::new().with_max_contexts(2));
+        let first = registry.register("acme", context("a")).unwrap();
+        registry.register("acme", context("b")).unwrap();
+
+        let err = registry.register("acme", context("c")).unwrap_err();
+        assert_eq!(err.code(), 429);
+        assert_eq!(err.details().get("tenant"), Some(&Value::String("acme".to_string())));
+        assert_eq!(err.details().get("quota.limit"), Some(&Value::String("contexts".to_string())));
+        assert_eq!(err.details().get("quota.max"), Some(&Value::U64(2)));
+        assert_eq!(registry.usage("acme").contexts, 2);
+
+        assert!(registry.release("acme", &first).is_some());
+        assert!(registry.release("acme", &first).is_none());
+        assert!(registry.register("acme", context("c")).is_ok());
+        assert_eq!(registry.quota("other"), TenantQuota::new());
+    }
+
+    #[test]
+    fn test_max_bytes() {
+        let registry = TenantRegistry::new().with_default_quota(TenantQuota::new().with_max_bytes(20));
+        // "job" and "export": 9 bytes.
+        registry.register("acme", context("export")).unwrap();
+        registry.register("acme", context("import")).unwrap();
+        assert_eq!(registry.usage("acme"), TenantUsage { contexts: 2, bytes: 18 });
+
+        let err = registry.register("acme", context("export")).unwrap_err();
+        assert_eq!(err.details().get("quota.limit"), Some(&Value::String("bytes".to_string())));
+        assert_eq!(err.details().get("quota.requested"), Some(&Value::U64(27)));
+        assert_eq!(registry.usage("globex"), TenantUsage::default());
+        assert!(registry.register("globex", context("export")).is_ok());
+    }
+
+    #[test]
+    fn test_replace_same_context() {
+        let registry = TenantRegistry::new().with_default_quota(TenantQuota::new().with_max_contexts(1).with_max_bytes(12));
+        let mut ctx = context("a");
+        let id = ctx.id().to_string();
+        assert_eq!(registry.register("acme", ctx.clone()).unwrap(), id);
+        ctx.insert("job".to_string(), Value::String("abcdefgh".to_string()));
+        assert_eq!(registry.register("acme", ctx.clone()).unwrap(), id);
+        assert_eq!(registry.usage("acme"), TenantUsage { contexts: 1, bytes: 11 });
+        assert_eq!(registry.get("acme", &id).unwrap().inner(), ctx.inner());
+        assert!(registry.get("globex", &id).is_none());
+    }
+
+    #[test]
+    fn test_hooks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let registry = TenantRegistry::new()
+            .with_default_quota(TenantQuota::new().with_max_contexts(1))
+            .on_event(move |tenant, event, usage| recorded.lock().unwrap().push((tenant.to_string(), event, usage.contexts)));
+
+        let id = registry.register("acme", context("a")).unwrap();
+        assert!(registry.register("acme", context("b")).is_err());
+        registry.release("acme", &id);
+        assert!(registry.tenants().is_empty());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ("acme".to_string(), QuotaEvent::Admitted, 1),
+                ("acme".to_string(), QuotaEvent::Rejected, 1),
+                ("acme".to_string(), QuotaEvent::Released, 0),
+            ]
+        );
+    }
+}
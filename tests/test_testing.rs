@@ -0,0 +1,178 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::testing::{Call, FailingContext, RecordingContext};
+    use cdumay_context::{ContextDump, Contextualize, Timeout};
+    #[cfg(feature = "test-utils")]
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_records_calls() {
+        let mut ctx = RecordingContext::new();
+        ctx.insert("user".to_string(), s("jane"));
+        assert_eq!(ctx.get_str("user").unwrap(), "jane");
+        ctx.extend(BTreeMap::from([("env".to_string(), s("prod"))]));
+        assert_eq!(ctx.remove("env"), Some(s("prod")));
+        assert!(!ctx.contains_key("env"));
+        assert_eq!(
+            ctx.calls(),
+            vec![
+                Call::New,
+                Call::Insert("user".to_string(), s("jane")),
+                Call::Get("user".to_string()),
+                Call::Extend(BTreeMap::from([("env".to_string(), s("prod"))])),
+                Call::Remove("env".to_string()),
+                Call::ContainsKey("env".to_string()),
+            ]
+        );
+        assert_eq!(ctx.reads("user"), 1);
+        assert_eq!(ctx.context().get_str("user").unwrap(), "jane");
+
+        ctx.reset_calls();
+        assert!(ctx.calls().is_empty());
+        assert_eq!(ctx.dump(), BTreeMap::from([("user".to_string(), s("jane"))]));
+        assert!(ctx.calls().is_empty());
+    }
+
+    #[test]
+    fn test_insert_counts() {
+        let mut ctx = RecordingContext::new();
+        ctx.insert("a".to_string(), s("1"));
+        ctx.extend(BTreeMap::from([("a".to_string(), s("2")), ("b".to_string(), s("3"))]));
+        assert_eq!(ctx.inserts("a"), 2);
+        ctx.assert_inserted_once("b");
+        ctx.assert_not_inserted("c");
+        let clone = ctx.clone();
+        assert_eq!(clone.calls(), ctx.calls());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected key 'a' to be inserted once, it was inserted 2 times")]
+    fn test_assert_inserted_once_panics() {
+        let mut ctx = RecordingContext::new();
+        ctx.insert("a".to_string(), s("1"));
+        ctx.insert("a".to_string(), s("2"));
+        ctx.assert_inserted_once("a");
+    }
+
+    #[test]
+    fn test_failing_serializers() {
+        let mut ctx = FailingContext::new();
+        ctx.insert("user".to_string(), s("jane"));
+        assert_eq!(ctx.get_str("user").unwrap(), "jane");
+        assert_eq!(ctx.len(), 1);
+        let err = serde_value::to_value(&ctx).unwrap_err();
+        assert!(err.to_string().contains("Injected serialization failure"));
+        assert_eq!(ctx.error().code(), 500);
+
+        ctx.set_error(Timeout::new().with_message("disk is slow".to_string()).into());
+        #[cfg(feature = "json")]
+        {
+            let err = ctx.to_json(true).unwrap_err();
+            assert_eq!(err.code(), 504);
+            assert_eq!(err.message(), "disk is slow");
+            let dir = std::env::temp_dir().join(format!("cdumay_context_failing_{}", std::process::id()));
+            assert!(ctx.save_to_file(dir.join("ctx.json"), None).is_err());
+            assert!(!dir.join("ctx.json").exists());
+        }
+        #[cfg(feature = "toml")]
+        assert_eq!(ctx.to_toml(false).unwrap_err().code(), 504);
+        #[cfg(feature = "yaml")]
+        assert_eq!(ctx.to_yaml().unwrap_err().code(), 504);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_assert_context() {
+        use cdumay_context::{assert_context, Context};
+
+        let ctx = Context::builder()
+            .with("status", 404u16)
+            .with("ratio", 0.5f32)
+            .with("initial", 'J')
+            .with("team", Option::<String>::None)
+            .with(
+                "user",
+                BTreeMap::from([("id", Value::I32(42)), ("roles", Value::Seq(vec![s("admin"), s("ops")]))]),
+            )
+            .fixture();
+        assert_context!(ctx, {
+            "status" => 404i64,
+            "ratio" => 0.5f64,
+            "initial" => "J",
+            "team" => (),
+            "user" => BTreeMap::from([("roles", vec!["admin", "ops"])]),
+        });
+        assert_context!(ctx, {});
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    #[should_panic(expected = "context does not match expected context: 1 mismatch(es):\n  - user.id: expected I32(7), got I32(42)")]
+    fn test_assert_context_panics() {
+        let ctx = cdumay_context::Context::builder().with("user", BTreeMap::from([("id", 42)])).fixture();
+        cdumay_context::assert_context!(ctx, { "user" => BTreeMap::from([("id", 7)]) });
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_fixture_as() {
+        let ctx: RecordingContext = cdumay_context::Context::builder().with("a", 1).fixture_as();
+        assert_eq!(
+            ctx.calls(),
+            vec![Call::New, Call::Extend(BTreeMap::from([("a".to_string(), Value::I32(1))]))]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    #[should_panic(expected = "failed to build context fixture")]
+    fn test_fixture_panics() {
+        cdumay_context::Context::builder().with_overrides(["invalid"]).fixture();
+    }
+
+    #[cfg(feature = "test-utils")]
+    cdumay_context::testing::proptest::proptest! {
+        #[test]
+        fn test_arb_context_bounds(ctx in cdumay_context::testing::arb_context::<cdumay_context::Context>(cdumay_context::testing::ArbitraryParams {
+            max_entries: 3,
+            max_depth: 2,
+            max_items: 2,
+            nulls: false,
+        })) {
+            fn depth(value: &Value) -> usize {
+                match value {
+                    Value::Seq(items) => 1 + items.iter().map(depth).max().unwrap_or(0),
+                    Value::Map(map) => 1 + map.values().map(depth).max().unwrap_or(0),
+                    Value::Unit => panic!("null generated"),
+                    _ => 0,
+                }
+            }
+            assert!(ctx.len() <= 3);
+            for (k, v) in ctx.inner() {
+                assert!(k.chars().next().unwrap().is_ascii_lowercase());
+                assert!(depth(&v) <= 2);
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "json")]
+        fn test_arb_context_json_roundtrip(ctx in cdumay_context::testing::proptest::prelude::any::<cdumay_context::Context>()) {
+            let loaded = cdumay_context::Context::from_json(&ctx.to_json(false).unwrap()).unwrap();
+            loaded.assert_matches(&ctx, &cdumay_context::MatchOptions::new());
+        }
+
+        #[test]
+        #[cfg(feature = "toml")]
+        fn test_arb_context_toml_roundtrip(ctx in cdumay_context::testing::proptest::prelude::any_with::<cdumay_context::Context>(cdumay_context::testing::ArbitraryParams {
+            nulls: false,
+            ..Default::default()
+        })) {
+            let loaded = cdumay_context::Context::from_toml(&ctx.to_toml(false).unwrap()).unwrap();
+            loaded.assert_matches(&ctx, &cdumay_context::MatchOptions::new());
+        }
+    }
+}
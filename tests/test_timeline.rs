@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextTimeline, Contextualize, SharedContext};
+    use serde_value::Value;
+
+    fn context(depth: u64) -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("queue_depth".to_string(), Value::U64(depth));
+        ctx
+    }
+
+    #[test]
+    fn test_downsampling_keeps_whole_history() {
+        let mut timeline = ContextTimeline::new(4);
+        for at in 0..16 {
+            timeline.record_at(at, &context(at));
+        }
+        let times: Vec<u64> = timeline.samples().map(|sample| sample.at).collect();
+        assert_eq!(timeline.stride(), 4);
+        assert_eq!(times.first(), Some(&0));
+        assert!(times.len() <= 4);
+        assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(*times.last().unwrap() >= 8);
+    }
+
+    #[test]
+    fn test_series() {
+        let mut timeline = ContextTimeline::new(8);
+        timeline.record_at(10, &context(1));
+        timeline.record_at(20, &Context::new());
+        assert_eq!(timeline.series("queue_depth"), vec![(10, Some(&Value::U64(1))), (20, None)]);
+    }
+
+    #[test]
+    fn test_render_changes() {
+        let mut timeline = ContextTimeline::new(8);
+        let mut ctx = context(1);
+        ctx.insert("state".to_string(), Value::String("running".to_string()));
+        timeline.record_at(1, &ctx);
+        ctx.insert("queue_depth".to_string(), Value::U64(2));
+        timeline.record_at(2, &ctx);
+        ctx.insert("other".to_string(), Value::Bool(true));
+        timeline.record_at(3, &ctx);
+        ctx.remove("state");
+        timeline.record_at(4, &ctx);
+        assert_eq!(
+            timeline.render(&["queue_depth", "state"]),
+            "1 queue_depth=1 state=\"running\"\n2 queue_depth=2\n4 state=-\n"
+        );
+    }
+
+    #[test]
+    fn test_record_shared() {
+        let shared = SharedContext::new(context(5));
+        let mut timeline = ContextTimeline::new(2);
+        timeline.record_shared(&shared);
+        let sample = timeline.samples().next().unwrap();
+        assert_eq!(sample.data.get("queue_depth"), Some(&Value::U64(5)));
+        assert!(sample.at > 0);
+    }
+}
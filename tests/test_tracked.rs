@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{ChangeOp, Context, Contextualize, KeyPolicy, TrackedContext};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_history() {
+        let mut base = Context::with_key_policy(KeyPolicy::CaseInsensitive);
+        base.insert("service".to_string(), Value::String("billing".to_string()));
+        let mut ctx = base.into_tracked();
+        assert!(ctx.history().is_empty());
+
+        ctx.insert("State".to_string(), Value::String("running".to_string()));
+        ctx.set_actor("scheduler");
+        ctx.extend(BTreeMap::from([
+            ("state".to_string(), Value::String("done".to_string())),
+            ("exit_code".to_string(), Value::U8(0)),
+        ]));
+        ctx.clear_actor();
+        assert_eq!(ctx.remove("STATE"), Some(Value::String("done".to_string())));
+        assert_eq!(ctx.remove("missing"), None);
+
+        let ops: Vec<(ChangeOp, &str)> = ctx.history().iter().map(|change| (change.op, change.key.as_str())).collect();
+        assert_eq!(
+            ops,
+            vec![
+                (ChangeOp::Insert, "state"),
+                (ChangeOp::Extend, "exit_code"),
+                (ChangeOp::Extend, "state"),
+                (ChangeOp::Remove, "state")
+            ]
+        );
+        assert!(ctx.history().windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+        let state = ctx.history_for("STATE");
+        assert_eq!(state.len(), 3);
+        assert_eq!(state[0].actor, None);
+        assert_eq!(state[1].actor.as_deref(), Some("scheduler"));
+        assert_eq!(state[1].old, Some(Value::String("running".to_string())));
+        assert_eq!(state[1].new, Some(Value::String("done".to_string())));
+        assert_eq!(state[2].new, None);
+        assert_eq!(ctx.get("service"), Some(&Value::String("billing".to_string())));
+    }
+
+    #[test]
+    fn test_locked_keys() {
+        let mut base = Context::new();
+        base.insert("tenant".to_string(), Value::String("acme".to_string()));
+        base.protect_key("tenant");
+        let mut ctx = TrackedContext::from(base).with_actor("plugin");
+        ctx.insert("tenant".to_string(), Value::String("evil".to_string()));
+        assert_eq!(ctx.remove("tenant"), None);
+        assert!(ctx.history().is_empty());
+
+        ctx.insert("step".to_string(), Value::String("fetch".to_string()));
+        ctx.clear();
+        assert_eq!(ctx.len(), 1);
+        assert_eq!(ctx.history_for("step").len(), 2);
+        assert_eq!(ctx.into_context().get("tenant"), Some(&Value::String("acme".to_string())));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serde() {
+        let mut ctx = TrackedContext::new().with_actor("billing");
+        ctx.insert("state".to_string(), Value::String("running".to_string()));
+        ctx.remove("state");
+        ctx.insert("attempt".to_string(), Value::U64(2));
+
+        let json = serde_json::to_value(&ctx).unwrap();
+        assert_eq!(json["state"], serde_json::json!({"attempt": 2}));
+        assert_eq!(json["history"][1]["op"], "remove");
+        assert_eq!(json["history"][1]["old"], "running");
+        assert_eq!(json["history"][1]["actor"], "billing");
+        assert!(json["history"][1].get("new").is_none());
+        assert_eq!(ctx.to_json(false).unwrap(), r#"{"attempt":2}"#);
+
+        let restored: TrackedContext = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.history(), ctx.history());
+        assert_eq!(restored.context(), ctx.context());
+        assert_eq!(restored.actor(), None);
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Base64Bytes, Context, ContextDump, Contextualize, RoundFloats};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_base64_bytes() {
+        let mut ctx = Context::new();
+        for (key, bytes) in [("a", "f"), ("b", "fo"), ("c", "foo"), ("d", "foob")] {
+            ctx.insert(key.to_string(), Value::Bytes(bytes.as_bytes().to_vec()));
+        }
+        ctx.add_transformer(Base64Bytes);
+        let dump = ctx.dump();
+        assert_eq!(dump.get("a"), Some(&Value::String("Zg==".to_string())));
+        assert_eq!(dump.get("b"), Some(&Value::String("Zm8=".to_string())));
+        assert_eq!(dump.get("c"), Some(&Value::String("Zm9v".to_string())));
+        assert_eq!(dump.get("d"), Some(&Value::String("Zm9vYg==".to_string())));
+        assert_eq!(ctx.get("a"), Some(&Value::Bytes(b"f".to_vec())));
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let mut ctx = Context::new();
+        let http = BTreeMap::from([(Value::String("latency".to_string()), Value::F64(0.12345))]);
+        ctx.insert("http".to_string(), Value::Map(http));
+        ctx.insert("samples".to_string(), Value::Seq(vec![Value::F64(1.0), Value::F64(2.5)]));
+        ctx.add_transformer(RoundFloats(2));
+        ctx.add_transformer(|path: &str, value: Value| match path {
+            "samples.1" => Value::Unit,
+            _ => value,
+        });
+        assert_eq!(ctx.at("http").at("latency").as_f64(), Some(0.12345));
+        let dump = ctx.dump();
+        let dumped = Context::from(dump);
+        assert_eq!(dumped.at("http").at("latency").as_f64(), Some(0.12));
+        assert_eq!(dumped.at("samples").nth(1).value(), Some(&Value::Unit));
+
+        ctx.clear_transformers();
+        assert_eq!(
+            ctx.dump_with(&RoundFloats(1)).get("samples"),
+            Some(&Value::Seq(vec![Value::F64(1.0), Value::F64(2.5)]))
+        );
+    }
+
+    #[test]
+    fn test_fork_keeps_transformers() {
+        let mut ctx = Context::new();
+        ctx.insert("payload".to_string(), Value::Bytes(vec![0]));
+        ctx.add_transformer(Base64Bytes);
+        assert_eq!(ctx.fork().dump().get("payload"), Some(&Value::String("AA==".to_string())));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_serialization_uses_transformers() {
+        let mut ctx = Context::new();
+        ctx.insert("ratio".to_string(), Value::F64(0.33333));
+        ctx.add_transformer(RoundFloats(2));
+        assert_eq!(ctx.to_json(false).unwrap(), r#"{"ratio":0.33}"#);
+        assert_eq!(serde_json::to_string(&ctx).unwrap(), r#"{"data":{"ratio":0.33}}"#);
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, ContextConfig, Contextualize, DotOptions, PARENT_ID_KEY};
+    use serde_value::Value;
+
+    fn string(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn test_export_tree() {
+        let mut request = Context::new();
+        request.insert("request_id".to_string(), string("abc"));
+        let first = request.fork();
+        let second = request.fork();
+        let grandchild = first.fork();
+
+        let tree = Context::export_tree([&grandchild, &second, &request, &first]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.roots.len(), 1);
+        let root = &tree.roots[0];
+        assert_eq!(root.id, request.id());
+        assert_eq!(root.parent_id, None);
+        assert_eq!(
+            root.children.iter().map(|node| node.id.as_str()).collect::<Vec<_>>(),
+            vec![second.id(), first.id()]
+        );
+        assert_eq!(root.children[1].children[0].id, grandchild.id());
+        assert_eq!(root.children[1].children[0].parent_id.as_deref(), Some(first.id()));
+
+        let ids: Vec<&str> = tree.nodes().iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, vec![request.id(), second.id(), first.id(), grandchild.id()]);
+        assert_eq!(tree.find(grandchild.id()).unwrap().entries.get("request_id"), Some(&string("abc")));
+        assert!(tree.find("unknown").is_none());
+    }
+
+    #[test]
+    fn test_missing_parent_is_root() {
+        let request = Context::new();
+        let child = request.fork();
+        let tree = Context::export_tree([&child]);
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].parent_id.as_deref(), Some(request.id()));
+    }
+
+    #[test]
+    fn test_duplicates_and_cycles() {
+        let mut a = Context::new();
+        let mut b = Context::new();
+        a.insert(PARENT_ID_KEY.to_string(), string(b.id()));
+        b.insert(PARENT_ID_KEY.to_string(), string(a.id()));
+        let clone = a.clone();
+
+        let tree = Context::export_tree([&a, &b, &clone]);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].children[0].id, b.id());
+        assert!(Context::export_tree([]).is_empty());
+    }
+
+    #[test]
+    fn test_tree_redaction() {
+        let mut ctx = Context::with_config(ContextConfig::new().redact("token"));
+        ctx.insert("token".to_string(), string("s3cr3t"));
+        let tree = Context::export_tree([&ctx]);
+        assert_eq!(tree.roots[0].entries.get("token"), Some(&string("[redacted]")));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut request = Context::new();
+        request.insert("request_id".to_string(), string("abc"));
+        request.insert("note".to_string(), string("say \"hi\""));
+        let mut child = request.fork();
+        child.insert("task".to_string(), string(&"x".repeat(50)));
+        child.insert("tags".to_string(), Value::Seq(vec![Value::U8(1), Value::U8(2)]));
+
+        let dot = Context::export_tree([&request, &child]).to_dot();
+        assert!(dot.starts_with("digraph contexts {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains(&format!("    \"{}\" -> \"{}\" [label=\"fork\"];\n", request.id(), child.id())));
+        assert!(dot.contains("note = \\\"say \\\\\\\"hi\\\\\\\"\\\"\\l"));
+        let child_line = dot.lines().find(|line| line.starts_with(&format!("    \"{}\" [", child.id()))).unwrap();
+        assert!(!child_line.contains("request_id"));
+        assert!(!child_line.contains(PARENT_ID_KEY));
+        assert!(child_line.contains("tags = [2 items]\\l"));
+        assert!(child_line.contains(&format!("task = \\\"{}...\\l", "x".repeat(39))));
+    }
+
+    #[test]
+    fn test_dot_hops() {
+        let mut request = Context::new();
+        request.record_hop("gateway", "headers");
+        let mut child = request.fork();
+        child.record_hop("billing", "kafka");
+        child.record_hop("ledger", "grpc");
+        let tree = Context::export_tree([&request, &child]);
+
+        let dot = tree.to_dot();
+        let (root, child) = (request.id(), child.id());
+        assert!(dot.contains(&format!("    \"{}/hop/0\" [shape=ellipse, label=\"gateway\"];\n", root)));
+        assert!(dot.contains(&format!("    \"{}/hop/0\" -> \"{}\" [style=dashed];\n", root, root)));
+        assert!(dot.contains(&format!("    \"{}\" -> \"{}/hop/0\" [label=\"kafka\"];\n", root, child)));
+        assert!(dot.contains(&format!("    \"{}/hop/0\" -> \"{}/hop/1\" [label=\"grpc\"];\n", child, child)));
+        assert!(dot.contains(&format!("    \"{}/hop/1\" -> \"{}\" [style=dashed];\n", child, child)));
+        assert!(!dot.contains("fork"));
+        assert!(!dot.contains("propagation"));
+        assert_eq!(tree.roots[0].children[0].hops().len(), 3);
+
+        let dot = tree.to_dot_with(&DotOptions::new().with_hops(false));
+        assert!(!dot.contains("hop/"));
+        assert!(dot.contains(&format!("    \"{}\" -> \"{}\" [label=\"fork\"];\n", root, child)));
+    }
+
+    #[test]
+    fn test_dot_labels() {
+        let mut request = Context::new();
+        request.insert("request_id".to_string(), string("abc"));
+        request.insert("http.method".to_string(), string("GET"));
+        let mut child = request.fork();
+        child.insert("task".to_string(), string("upload"));
+
+        let dot = Context::export_tree([&request, &child]).to_dot_with(&DotOptions::new().with_labels(&["request_id", "http.*"]));
+        let child_line = dot.lines().find(|line| line.starts_with(&format!("    \"{}\" [", child.id()))).unwrap();
+        assert!(child_line.contains("request_id = \\\"abc\\\"\\l"));
+        assert!(child_line.contains("http.method = "));
+        assert!(!child_line.contains("task"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_to_json() {
+        let request = Context::new();
+        let child = request.fork();
+        let json = Context::export_tree([&request, &child]).to_json(false).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"roots":[{{"id":"{0}","parent_id":null,"entries":{{}},"children":[{{"id":"{1}","parent_id":"{0}","entries":{{"parent_id":"{0}"}},"children":[]}}]}}]}}"#,
+                request.id(),
+                child.id()
+            )
+        );
+    }
+}
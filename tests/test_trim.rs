@@ -0,0 +1,52 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{context_of, s};
+    use cdumay_context::{Context, Contextualize, KeyOrder, PROPAGATION_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn encoded_len(dump: &BTreeMap<String, Value>) -> usize {
+        format!("{:?}", dump).len()
+    }
+
+    fn context() -> Context {
+        let mut ctx = context_of([("request_id", s("abc")), ("user", s("jane")), ("payload", Value::String("x".repeat(500)))]);
+        ctx.set_key_order(KeyOrder::Priority(vec!["request_id".to_string(), "user".to_string()]));
+        ctx
+    }
+
+    #[test]
+    fn test_dump_fits_untouched() {
+        let ctx = context();
+        assert_eq!(ctx.dump_within(usize::MAX, encoded_len), ctx.inner());
+    }
+
+    #[test]
+    fn test_lowest_priority_keys_are_dropped() {
+        let mut ctx = context();
+        ctx.record_hop("gateway", "headers");
+        let dump = ctx.dump_within(300, encoded_len);
+        assert!(encoded_len(&dump) <= 300);
+        assert!(dump.contains_key("request_id"));
+        assert!(dump.contains_key("user"));
+        assert!(!dump.contains_key("payload"));
+        assert_eq!(ctx.at("payload").as_str().map(str::len), Some(500));
+
+        let ctx = Context::from(dump);
+        assert_eq!(ctx.at(PROPAGATION_KEY).at("dropped").nth(0).as_str(), Some("payload"));
+        assert_eq!(ctx.hops().len(), 1);
+    }
+
+    #[test]
+    fn test_dump_too_large() {
+        let dump = context().dump_within(0, encoded_len);
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec![PROPAGATION_KEY]);
+        let ctx = Context::from(dump);
+        let dropped: Vec<&str> = (0..3)
+            .filter_map(|index| ctx.at(PROPAGATION_KEY).at("dropped").nth(index).as_str())
+            .collect();
+        assert_eq!(dropped, vec!["request_id", "user", "payload"]);
+    }
+}
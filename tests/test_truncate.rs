@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, DumpLimits, KeyOrder, TRUNCATED_KEY};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn nested() -> Value {
+        let inner = Value::Map(BTreeMap::from([(
+            Value::String("ids".to_string()),
+            Value::Seq(vec![Value::U8(1), Value::U8(2)]),
+        )]));
+        Value::Map(BTreeMap::from([(Value::String("inner".to_string()), inner)]))
+    }
+
+    #[test]
+    fn test_value_len() {
+        let mut ctx = Context::new();
+        ctx.insert("body".to_string(), Value::String("x".repeat(2048)));
+        ctx.insert("blob".to_string(), Value::Option(Some(Box::new(Value::Bytes(vec![0; 100])))));
+        ctx.insert(
+            "tags".to_string(),
+            Value::Seq(vec![Value::String("short".to_string()), Value::String("y".repeat(20))]),
+        );
+        ctx.insert("name".to_string(), Value::String("0123456789".to_string()));
+
+        let dump = ctx.dump_truncated(&DumpLimits::new().with_max_value_len(10));
+        assert_eq!(dump["body"], Value::String("<truncated 2048 bytes>".to_string()));
+        assert_eq!(
+            dump["blob"],
+            Value::Option(Some(Box::new(Value::String("<truncated 100 bytes>".to_string()))))
+        );
+        assert_eq!(
+            dump["tags"],
+            Value::Seq(vec![
+                Value::String("short".to_string()),
+                Value::String("<truncated 20 bytes>".to_string())
+            ])
+        );
+        assert_eq!(dump["name"], Value::String("0123456789".to_string()));
+        assert_eq!(ctx.dump_truncated(&DumpLimits::default()), ctx.inner());
+    }
+
+    #[test]
+    fn test_depth() {
+        let mut ctx = Context::new();
+        ctx.insert("request".to_string(), nested());
+        ctx.insert("attempt".to_string(), Value::U8(1));
+
+        let dump = ctx.dump_truncated(&DumpLimits::new().with_max_depth(0));
+        assert_eq!(dump["request"], Value::String("<truncated 1 items>".to_string()));
+        assert_eq!(dump["attempt"], Value::U8(1));
+
+        let dump = ctx.dump_truncated(&DumpLimits::new().with_max_depth(2));
+        let request = Value::Map(BTreeMap::from([(
+            Value::String("inner".to_string()),
+            Value::Map(BTreeMap::from([(
+                Value::String("ids".to_string()),
+                Value::String("<truncated 2 items>".to_string()),
+            )])),
+        )]));
+        assert_eq!(dump["request"], request);
+        assert_eq!(ctx.dump_truncated(&DumpLimits::new().with_max_depth(3))["request"], nested());
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut ctx = Context::new();
+        for key in ["a", "b", "c", "request_id"] {
+            ctx.insert(key.to_string(), Value::String(key.to_string()));
+        }
+        ctx.set_key_order(KeyOrder::Priority(vec!["request_id".to_string()]));
+
+        let dump = ctx.dump_truncated(&DumpLimits::new().with_max_entries(2));
+        assert_eq!(dump.keys().collect::<Vec<_>>(), vec!["a", "request_id", TRUNCATED_KEY]);
+        assert_eq!(dump[TRUNCATED_KEY], Value::String("<truncated 2 entries>".to_string()));
+        assert!(!ctx.dump_truncated(&DumpLimits::new().with_max_entries(4)).contains_key(TRUNCATED_KEY));
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, ContextDump, Contextualize};
+    use std::time::Duration;
+
+    #[test]
+    fn test_expired_entries_are_hidden() {
+        let mut ctx = Context::new();
+        ctx.insert_with_ttl("last_error".to_string(), s("timeout"), Duration::ZERO);
+        ctx.insert_with_ttl("last_job_id".to_string(), s("42"), Duration::from_secs(3600));
+        ctx.insert("worker".to_string(), s("w-1"));
+        assert!(ctx.get("last_error").is_none());
+        assert!(!ctx.contains_key("last_error"));
+        assert_eq!(ctx.get_str("last_job_id").unwrap(), "42");
+        assert_eq!(ctx.len(), 2);
+        assert_eq!(ctx.dump().keys().collect::<Vec<_>>(), vec!["last_job_id", "worker"]);
+        assert_eq!(ctx.expires_in("last_error"), Some(Duration::ZERO));
+        assert!(ctx.expires_in("last_job_id").unwrap() > Duration::from_secs(3500));
+        assert_eq!(ctx.expires_in("worker"), None);
+        #[cfg(feature = "json")]
+        assert_eq!(ctx.to_json(false).unwrap(), r#"{"last_job_id":"42","worker":"w-1"}"#);
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let mut ctx = Context::new();
+        ctx.insert_with_ttl("a".to_string(), s("1"), Duration::ZERO);
+        ctx.insert_with_ttl("b".to_string(), s("2"), Duration::from_secs(3600));
+        assert_eq!(ctx.purge_expired(), vec!["a".to_string()]);
+        assert!(ctx.purge_expired().is_empty());
+        assert_eq!(ctx.expires_in("a"), None);
+        assert_eq!(ctx.len(), 1);
+    }
+
+    #[test]
+    fn test_insert_makes_entry_permanent() {
+        let mut ctx = Context::new();
+        ctx.insert_with_ttl("a".to_string(), s("1"), Duration::ZERO);
+        ctx.insert("a".to_string(), s("2"));
+        assert_eq!(ctx.get_str("a").unwrap(), "2");
+        assert_eq!(ctx.expires_in("a"), None);
+
+        ctx.insert_with_ttl("a".to_string(), s("3"), Duration::ZERO);
+        assert_eq!(ctx.remove("a"), Some(s("3")));
+        ctx.insert("a".to_string(), s("4"));
+        assert_eq!(ctx.get_str("a").unwrap(), "4");
+    }
+
+    #[test]
+    fn test_protected_and_forked() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), s("1"));
+        ctx.protect_key("a");
+        ctx.insert_with_ttl("a".to_string(), s("2"), Duration::ZERO);
+        assert_eq!(ctx.get_str("a").unwrap(), "1");
+        assert_eq!(ctx.expires_in("a"), None);
+
+        ctx.insert_with_ttl("b".to_string(), s("2"), Duration::ZERO);
+        let child = ctx.fork();
+        assert!(child.get("b").is_none());
+        assert_eq!(child.expires_in("b"), Some(Duration::ZERO));
+    }
+}
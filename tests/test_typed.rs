@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize};
+    use serde::Deserialize;
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn ctx() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("user".to_string(), Value::String("jane".to_string()));
+        ctx.insert("attempt".to_string(), Value::U8(3));
+        ctx.insert("ratio".to_string(), Value::F64(0.5));
+        ctx.insert("retry".to_string(), Value::Option(Some(Box::new(Value::Bool(true)))));
+        ctx.insert("big".to_string(), Value::U64(u64::MAX));
+        ctx
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let ctx = ctx();
+        assert_eq!(ctx.get_str("user").unwrap(), "jane");
+        assert_eq!(ctx.get_i64("attempt").unwrap(), 3);
+        assert_eq!(ctx.get_f64("ratio").unwrap(), 0.5);
+        assert!(ctx.get_bool("retry").unwrap());
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let err = ctx().get_i64("missing").unwrap_err();
+        assert_eq!(err.code(), 404);
+        assert_eq!(err.message(), "Context key 'missing' not found");
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        let ctx = ctx();
+        let err = ctx.get_str("attempt").unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert_eq!(err.message(), "Invalid value for context key 'attempt': expected string, found u8");
+        assert_eq!(
+            ctx.get_i64("big").unwrap_err().message(),
+            "Invalid value for context key 'big': expected i64, found u64"
+        );
+        assert_eq!(ctx.get_bool("retry").ok(), Some(true));
+    }
+
+    #[test]
+    fn test_get_as() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Owner {
+            name: String,
+        }
+
+        let mut ctx = ctx();
+        let mut owner = BTreeMap::new();
+        owner.insert(Value::String("name".to_string()), Value::String("jane".to_string()));
+        ctx.insert("owner".to_string(), Value::Map(owner));
+
+        assert_eq!(ctx.get_as::<Owner>("owner").unwrap(), Owner { name: "jane".to_string() });
+        assert_eq!(ctx.get_as::<u8>("attempt").unwrap(), 3);
+        assert_eq!(ctx.get_as::<String>("missing").unwrap_err().code(), 404);
+
+        let err = ctx.get_as::<u8>("user").unwrap_err();
+        assert_eq!(err.code(), 400);
+        assert!(err
+            .message()
+            .starts_with("Invalid value for context key 'user': expected u8, found string"));
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "urlencoded")]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::{Context, Contextualize};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_to_query_string() {
+        let mut ctx = Context::new();
+        ctx.insert(
+            "http".to_string(),
+            Value::Map(BTreeMap::from([(s("status"), Value::U16(503)), (s("method"), s("GET"))])),
+        );
+        ctx.insert("empty".to_string(), Value::Option(None));
+        ctx.insert("payload".to_string(), Value::Bytes(vec![1, 2]));
+        ctx.insert("ok".to_string(), Value::Bool(false));
+        ctx.insert("path".to_string(), s("/a?b=c#d~"));
+        ctx.insert(
+            "items".to_string(),
+            Value::Seq(vec![Value::U8(1), Value::Map(BTreeMap::from([(s("id"), Value::U8(2))]))]),
+        );
+        assert_eq!(
+            ctx.to_query_string(),
+            "http.method=GET&http.status=503&items=1&items.1.id=2&ok=false&path=%2Fa%3Fb%3Dc%23d%7E"
+        );
+        assert_eq!(Context::new().to_query_string(), "");
+    }
+
+    #[test]
+    fn test_from_query_string() {
+        let ctx = Context::from_query_string("a=1&&b&a=2+3&c=%zz%4&d=%E2%82%AC&e=%FF&http.status=503&a=");
+        assert_eq!(ctx.get("a"), Some(&Value::Seq(vec![s("1"), s("2 3"), s("")])));
+        assert_eq!(ctx.get_str("b").unwrap(), "");
+        assert_eq!(ctx.get_str("c").unwrap(), "%zz%4");
+        assert_eq!(ctx.get_str("d").unwrap(), "€");
+        assert_eq!(ctx.get_str("e").unwrap(), "\u{FFFD}");
+        assert_eq!(ctx.get_str("http.status").unwrap(), "503");
+        assert!(Context::from_query_string("").inner().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut ctx = Context::new();
+        ctx.insert("name".to_string(), s("Jane Doe & co +1 100%"));
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("é"), s("a=b")]));
+        assert_eq!(Context::from_query_string(&ctx.to_query_string()).inner(), ctx.inner());
+    }
+}
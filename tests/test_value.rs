@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use cdumay_context::{Context, Contextualize, ValueExt, ValueRef};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    fn sample() -> Context {
+        let mut http = BTreeMap::new();
+        http.insert(Value::String("status".to_string()), Value::Option(Some(Box::new(Value::U16(503)))));
+        http.insert(Value::String("retries".to_string()), Value::Seq(vec![Value::I64(1), Value::I64(-2)]));
+        let mut ctx = Context::new();
+        ctx.insert("http".to_string(), Value::Map(http));
+        ctx.insert("user".to_string(), Value::String("alice".to_string()));
+        ctx
+    }
+
+    #[test]
+    fn test_index() {
+        let mut ctx = sample();
+        assert_eq!(ctx["user"], Value::String("alice".to_string()));
+
+        ctx["user"] = Value::String("bob".to_string());
+        assert_eq!(ctx.get("user"), Some(&Value::String("bob".to_string())));
+    }
+
+    #[test]
+    #[should_panic(expected = "key 'missing' not found in context")]
+    fn test_index_missing_key() {
+        let _ = &sample()["missing"];
+    }
+
+    #[test]
+    fn test_at_chaining() {
+        let ctx = sample();
+        assert_eq!(ctx.at("http").at("status").as_u64(), Some(503));
+        assert_eq!(ctx.at("http").at("retries").nth(0).as_u64(), Some(1));
+        assert_eq!(ctx.at("http").at("retries").nth(1).as_u64(), None);
+        assert!(ctx.at("http").at("retries").nth(5).is_missing());
+        assert!(ctx.at("user").at("name").is_missing());
+        assert_eq!(ctx.at("missing"), ValueRef::new(None));
+        assert_eq!(ctx.at("user").value(), Some(&Value::String("alice".to_string())));
+    }
+
+    #[test]
+    fn test_value_ext_scalars() {
+        assert_eq!(Value::String("x".to_string()).as_str(), Some("x"));
+        assert_eq!(Value::U64(u64::MAX).as_i64(), None);
+        assert_eq!(Value::I16(-5).as_i64(), Some(-5));
+        assert_eq!(Value::U32(5).as_u64(), Some(5));
+        assert_eq!(Value::I32(5).as_f64(), Some(5.0));
+        assert_eq!(Value::F32(0.5).as_f64(), Some(0.5));
+        assert_eq!(Value::Bool(false).as_bool(), Some(false));
+        assert_eq!(Value::Newtype(Box::new(Value::Bool(true))).as_bool(), Some(true));
+        assert_eq!(Value::String("1".to_string()).as_i64(), None);
+    }
+
+    #[test]
+    fn test_value_ext_collections() {
+        let ctx = sample();
+        assert_eq!(ctx["http"].as_map().map(|m| m.len()), Some(2));
+        assert_eq!(ctx.at("http").at("retries").as_seq().map(|s| s.len()), Some(2));
+        assert_eq!(ctx.at("http").at("retries").nth(1).as_i64(), Some(-2));
+        assert_eq!(ctx.at("user").as_str(), Some("alice"));
+        assert!(ctx["user"].as_map().is_none());
+        assert!(ctx["user"].as_seq().is_none());
+    }
+}
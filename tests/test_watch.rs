@@ -0,0 +1,49 @@
+#[cfg(test)]
+#[cfg(all(feature = "watch", feature = "json"))]
+mod tests {
+    use cdumay_context::{Context, Contextualize, WatchEvent};
+    use serde_value::Value;
+    use std::time::Duration;
+
+    #[test]
+    fn test_watch_reloads_on_change() {
+        let dir = std::env::temp_dir().join(format!("cdumay_context_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("flags.json");
+        std::fs::write(&path, r#"{"beta":false}"#).unwrap();
+
+        let watch = Context::watch(&path).unwrap();
+        let events = watch.subscribe();
+        assert_eq!(watch.context().get("beta"), Some(Value::Bool(false)));
+
+        std::fs::write(&path, r#"{"beta":true,"region":"eu"}"#).unwrap();
+        let diff = loop {
+            match events.recv_timeout(Duration::from_secs(10)).unwrap() {
+                WatchEvent::Reloaded(diff) => break diff,
+                WatchEvent::Failed(_) => continue,
+            }
+        };
+        assert!(diff.added.contains_key("region"));
+        assert!(diff.changed.contains_key("beta"));
+        assert_eq!(watch.snapshot().get("beta"), Some(&Value::Bool(true)));
+
+        drop(watch);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_manual_reload() {
+        let dir = std::env::temp_dir().join(format!("cdumay_context_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("context.json");
+        std::fs::write(&path, r#"{"step":1}"#).unwrap();
+
+        let watch = Context::watch(&path).unwrap();
+        assert_eq!(watch.path(), path.as_path());
+        assert!(watch.reload().is_none());
+        assert!(matches!(Context::watch(dir.join("missing.json")), Err(err) if err.code() == 404));
+
+        drop(watch);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
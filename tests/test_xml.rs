@@ -0,0 +1,118 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "xml")]
+mod tests {
+    use crate::common::s;
+    use cdumay_context::testing::FailingContext;
+    use cdumay_context::{BoundedLruContext, Context, Contextualize, KeyOrder};
+    use serde_value::Value;
+    use std::collections::BTreeMap;
+
+    const DECL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+    #[test]
+    fn test_to_xml() {
+        let mut ctx = Context::new();
+        ctx.insert("empty".to_string(), s(""));
+        ctx.insert("retry".to_string(), Value::Unit);
+        ctx.insert("status".to_string(), Value::U16(404));
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a"), Value::Bool(true)]));
+        ctx.insert(
+            "user".to_string(),
+            Value::Map(BTreeMap::from([(s("name"), s("Jane & <Joe>")), (s("id"), Value::I64(-1))])),
+        );
+        assert_eq!(
+            ctx.to_xml("context").unwrap(),
+            format!(
+                "{}<context><empty></empty><retry/><status>404</status><tags>a</tags><tags>true</tags><user><id>-1</id><name>Jane &amp; &lt;Joe&gt;</name></user></context>",
+                DECL
+            )
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut ctx = Context::new();
+        ctx.insert("status".to_string(), s("404"));
+        ctx.insert("retry".to_string(), Value::Unit);
+        ctx.insert("empty".to_string(), s(""));
+        ctx.insert("tags".to_string(), Value::Seq(vec![s("a"), s("b")]));
+        ctx.insert("user".to_string(), Value::Map(BTreeMap::from([(s("name"), s("Jane & <Joe>"))])));
+        let xml = ctx.to_xml("root").unwrap();
+        assert_eq!(Context::from_xml(&xml).unwrap().inner(), ctx.inner());
+        assert_eq!(BoundedLruContext::from_xml(&xml).unwrap().inner(), ctx.inner());
+    }
+
+    #[test]
+    fn test_from_xml() {
+        let xml = r#"<?xml version="1.0"?>
+<!-- exported by the legacy system -->
+<request id="ignored">
+    <status>404</status>
+    <message>x &lt; &#65;<![CDATA[<raw>]]></message>
+    <tags>a</tags>
+    <tags>b</tags>
+    <user>
+        <name>Jane</name>
+    </user>
+</request>"#;
+        let ctx = Context::from_xml(xml).unwrap();
+        assert_eq!(ctx.get("status"), Some(&s("404")));
+        assert_eq!(ctx.get("message"), Some(&s("x < A<raw>")));
+        assert_eq!(ctx.get("tags"), Some(&Value::Seq(vec![s("a"), s("b")])));
+        assert_eq!(ctx.get("user"), Some(&Value::Map(BTreeMap::from([(s("name"), s("Jane"))]))));
+        assert_eq!(ctx.len(), 4);
+        assert_eq!(Context::from_xml("<context/>").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_from_xml_errors() {
+        for xml in [
+            "",
+            "<a><b>1</b>",
+            "<a><b>1</c></a>",
+            "<a><b>1<c/></b></a>",
+            "<a>text</a>",
+            "<a><b>&unknown;</b></a>",
+        ] {
+            let err = Context::from_xml(xml).unwrap_err();
+            assert_eq!(err.code(), 400, "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_to_xml_errors() {
+        let mut ctx = Context::new();
+        ctx.insert("a".to_string(), Value::U8(1));
+        assert_eq!(ctx.to_xml("not a name").unwrap_err().code(), 400);
+
+        let mut bad_key = ctx.clone();
+        bad_key.insert("1st".to_string(), Value::U8(1));
+        let err = bad_key.to_xml("context").unwrap_err();
+        assert_eq!(err.details().get("key"), Some(&s("1st")));
+        assert!(err.details().contains_key("a"));
+
+        let mut bytes = ctx.clone();
+        bytes.insert("raw".to_string(), Value::Bytes(vec![1]));
+        assert_eq!(bytes.to_xml("context").unwrap_err().details().get("key"), Some(&s("raw")));
+
+        let mut nested = ctx.clone();
+        nested.insert("matrix".to_string(), Value::Seq(vec![Value::Seq(vec![Value::U8(1)])]));
+        assert!(nested.to_xml("context").is_err());
+
+        assert_eq!(
+            FailingContext::new().to_xml("context").unwrap_err().message(),
+            "Injected serialization failure"
+        );
+    }
+
+    #[test]
+    fn test_to_xml_key_order() {
+        let mut ctx = Context::new();
+        ctx.set_key_order(KeyOrder::Insertion);
+        ctx.insert("z".to_string(), Value::U8(1));
+        ctx.insert("a".to_string(), Value::U8(2));
+        assert_eq!(ctx.to_xml("c").unwrap(), format!("{}<c><z>1</z><a>2</a></c>", DECL));
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+#[cfg(feature = "yaml")]
+mod tests {
+    use cdumay_context::{Context, Contextualize, YAML_EXPANSION_LIMIT};
+    use serde_value::Value;
+
+    #[test]
+    fn test_aliases_and_merge_keys() {
+        let yaml = "
+base: &base
+  region: eu
+  retries: 3
+extra: &extra
+  retries: 5
+  debug: true
+hosts: &hosts [a, b]
+upload:
+  <<: [*base, *extra]
+  timeout: 60
+  hosts: *hosts
+";
+        let ctx = Context::from_yaml(yaml).unwrap();
+        assert_eq!(ctx.at("upload").at("region").as_str(), Some("eu"));
+        assert_eq!(ctx.at("upload").at("retries").as_u64(), Some(3));
+        assert_eq!(ctx.at("upload").at("debug").as_bool(), Some(true));
+        assert_eq!(ctx.at("upload").at("hosts").nth(1).as_str(), Some("b"));
+        assert!(ctx.at("upload").at("<<").is_missing());
+    }
+
+    #[test]
+    fn test_plain_values() {
+        let ctx = Context::from_yaml("codes: {404: missing, true: yes}\ncount: !custom 3\nnothing: ~").unwrap();
+        assert_eq!(ctx.at("codes").at("404").as_str(), Some("missing"));
+        assert_eq!(ctx.at("codes").at("true").as_str(), Some("yes"));
+        assert_eq!(ctx.get("count"), Some(&Value::U64(3)));
+        assert_eq!(ctx.get("nothing"), Some(&Value::Unit));
+        assert!(Context::from_yaml("").unwrap().inner().is_empty());
+        assert_eq!(Context::from_yaml("- 1").unwrap_err().code(), 400);
+        assert_eq!(Context::from_yaml("a: {<<: 1}").unwrap_err().code(), 400);
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        let mut yaml = "a: &a [x, x, x, x, x, x, x, x, x, x]\n".to_string();
+        for (level, previous) in ["b", "c", "d", "e", "f", "g", "h", "i"]
+            .iter()
+            .zip(["a", "b", "c", "d", "e", "f", "g", "h"])
+        {
+            yaml.push_str(&format!("{}: &{} [{}]\n", level, level, vec![format!("*{}", previous); 10].join(", ")));
+        }
+        let err = Context::from_yaml(&yaml).unwrap_err();
+        assert_eq!(err.code(), 413);
+        assert!(err.class().contains("YamlExpansionLimit"), "{}", err.class());
+
+        let yaml = format!("a: &a [{}]\nb: [{}]\n", vec!["x"; 2000].join(", "), vec!["*a"; 60].join(", "));
+        let err = Context::from_yaml(&yaml).unwrap_err();
+        assert!(err.message().contains(&YAML_EXPANSION_LIMIT.to_string()), "{}", err.message());
+
+        let yaml = format!("a: {}1{}", "[".repeat(200), "]".repeat(200));
+        assert_eq!(Context::from_yaml(&yaml).unwrap_err().code(), 413);
+    }
+
+    #[test]
+    fn test_large_plain_document() {
+        let yaml = format!("items: [{}]\n", vec!["1"; YAML_EXPANSION_LIMIT + 10].join(", "));
+        let ctx = Context::from_yaml(&yaml).unwrap();
+        assert_eq!(ctx.at("items").nth(YAML_EXPANSION_LIMIT).as_u64(), Some(1));
+    }
+}